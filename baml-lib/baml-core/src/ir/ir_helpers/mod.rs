@@ -9,8 +9,8 @@ use crate::{
     error_not_found,
     ir::{
         repr::{IntermediateRepr, Walker},
-        Class, Client, Enum, EnumValue, Field, FunctionNode, RetryPolicy, TemplateString, TestCase,
-        TypeAlias,
+        Class, Client, Enum, EnumValue, Field, FunctionNode, PromptSuite, RetryPolicy,
+        TemplateString, TestCase, TypeAlias,
     },
 };
 use anyhow::Result;
@@ -30,6 +30,7 @@ pub type TypeAliasWalker<'a> = Walker<'a, &'a TypeAlias>;
 pub type TemplateStringWalker<'a> = Walker<'a, &'a TemplateString>;
 pub type ClientWalker<'a> = Walker<'a, &'a Client>;
 pub type RetryPolicyWalker<'a> = Walker<'a, &'a RetryPolicy>;
+pub type PromptSuiteWalker<'a> = Walker<'a, &'a PromptSuite>;
 pub type TestCaseWalker<'a> = Walker<'a, (&'a FunctionNode, &'a TestCase)>;
 pub type ClassFieldWalker<'a> = Walker<'a, &'a Field>;
 
@@ -44,6 +45,8 @@ pub trait IRHelper {
         &'a self,
         template_string_name: &str,
     ) -> Result<TemplateStringWalker<'a>>;
+    /// The template of the `variant` of `function_name` in one of the prompt suites.
+    fn find_prompt_variant<'a>(&'a self, function_name: &str, variant: &str) -> Result<&'a str>;
     fn find_test<'a>(
         &'a self,
         function: &'a FunctionWalker<'a>,
@@ -168,6 +171,27 @@ impl IRHelper for IntermediateRepr {
         }
     }
 
+    fn find_prompt_variant<'a>(&'a self, function_name: &str, variant: &str) -> Result<&'a str> {
+        let suites = self
+            .walk_prompt_suites()
+            .filter(|suite| suite.function_name() == function_name)
+            .collect::<Vec<_>>();
+        match suites.iter().find_map(|suite| suite.variant(variant)) {
+            Some(template) => Ok(template),
+            None => {
+                let variants = suites
+                    .iter()
+                    .flat_map(|suite| suite.variants().keys().map(String::as_str))
+                    .collect::<Vec<_>>();
+                error_not_found!(
+                    format!("`{function_name}` prompt variant"),
+                    variant,
+                    &variants
+                )
+            }
+        }
+    }
+
     // find_template_string
     fn find_template_string(&self, template_string_name: &str) -> Result<TemplateStringWalker<'_>> {
         match self
@@ -378,6 +402,18 @@ impl IRHelper for IntermediateRepr {
                 anyhow::bail!("Could not unify Float with {:?}", field_type)
             }
 
+            BamlValue::Decimal(d) => {
+                let primitive = if decimal_is_integral(&d) {
+                    TypeValue::Int
+                } else {
+                    TypeValue::Float
+                };
+                if self.is_subtype(&FieldType::Primitive(primitive), &field_type) {
+                    return Ok(BamlValueWithMeta::Decimal(d, field_type));
+                }
+                anyhow::bail!("Could not unify Decimal with {:?}", field_type)
+            }
+
             BamlValue::Bool(b) => {
                 let literal_type = FieldType::Literal(LiteralValue::Bool(b));
                 let primitive_type = FieldType::Primitive(TypeValue::Bool);
@@ -391,6 +427,13 @@ impl IRHelper for IntermediateRepr {
                 }
             }
 
+            BamlValue::Bytes(b) => {
+                if self.is_subtype(&FieldType::Primitive(TypeValue::Bytes), &field_type) {
+                    return Ok(BamlValueWithMeta::Bytes(b, field_type));
+                }
+                anyhow::bail!("Could not unify Bytes with {:?}", field_type)
+            }
+
             BamlValue::Null
                 if self.is_subtype(&FieldType::Primitive(TypeValue::Null), &field_type) =>
             {
@@ -583,13 +626,23 @@ impl IRHelper for IntermediateRepr {
 
 const UNIT_TYPE: FieldType = FieldType::Tuple(vec![]);
 
+/// Whether the text of a `BamlValue::Decimal` is a whole number (`123`, not `1.5` or `1e3`).
+pub fn decimal_is_integral(decimal: &str) -> bool {
+    !decimal.contains(['.', 'e', 'E'])
+}
+
 /// Derive the simplest type that can categorize a given value. This is meant to be used
 /// by `distribute_type`, for dynamic fields of classes, whose types are not known statically.
 pub fn infer_type(value: &BamlValue) -> Option<FieldType> {
     let ret = match value {
         BamlValue::Int(_) => Some(FieldType::Primitive(TypeValue::Int)),
         BamlValue::Bool(_) => Some(FieldType::Primitive(TypeValue::Bool)),
+        BamlValue::Bytes(_) => Some(FieldType::Primitive(TypeValue::Bytes)),
         BamlValue::Float(_) => Some(FieldType::Primitive(TypeValue::Float)),
+        BamlValue::Decimal(d) if decimal_is_integral(d) => {
+            Some(FieldType::Primitive(TypeValue::Int))
+        }
+        BamlValue::Decimal(_) => Some(FieldType::Primitive(TypeValue::Float)),
         BamlValue::String(_) => Some(FieldType::Primitive(TypeValue::String)),
         BamlValue::Null => Some(FieldType::Primitive(TypeValue::Null)),
         BamlValue::Map(pairs) => {
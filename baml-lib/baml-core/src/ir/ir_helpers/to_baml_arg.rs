@@ -7,7 +7,7 @@ use std::path::PathBuf;
 
 use crate::ir::IntermediateRepr;
 
-use super::{scope_diagnostics::ScopeStack, IRHelper};
+use super::{decimal_is_integral, scope_diagnostics::ScopeStack, IRHelper};
 use crate::ir::jinja_helpers::evaluate_predicate;
 
 #[derive(Default)]
@@ -49,6 +49,7 @@ impl ArgCoercer {
                 TypeValue::String if self.allow_implicit_cast_to_string => match value {
                     BamlValue::Int(i) => Ok(BamlValue::String(i.to_string())),
                     BamlValue::Float(f) => Ok(BamlValue::String(f.to_string())),
+                    BamlValue::Decimal(d) => Ok(BamlValue::String(d.clone())),
                     BamlValue::Bool(true) => Ok(BamlValue::String("true".to_string())),
                     BamlValue::Bool(false) => Ok(BamlValue::String("false".to_string())),
                     BamlValue::Null => Ok(BamlValue::String("null".to_string())),
@@ -58,15 +59,19 @@ impl ArgCoercer {
                     }
                 },
                 TypeValue::Int if matches!(value, BamlValue::Int(_)) => Ok(value.clone()),
+                TypeValue::Int if matches!(value, BamlValue::Decimal(d) if decimal_is_integral(d)) => {
+                    Ok(value.clone())
+                }
                 TypeValue::Float => match value {
                     BamlValue::Int(val) => Ok(BamlValue::Float(*val as f64)),
-                    BamlValue::Float(_) => Ok(value.clone()),
+                    BamlValue::Float(_) | BamlValue::Decimal(_) => Ok(value.clone()),
                     _ => {
                         scope.push_error(format!("Expected type {:?}, got `{}`", t, value));
                         Err(())
                     }
                 },
                 TypeValue::Bool if matches!(value, BamlValue::Bool(_)) => Ok(value.clone()),
+                TypeValue::Bytes if matches!(value, BamlValue::Bytes(_)) => Ok(value.clone()),
                 TypeValue::Null if matches!(value, BamlValue::Null) => Ok(value.clone()),
                 TypeValue::Media(media_type) => match value {
                     BamlValue::Media(v) => Ok(BamlValue::Media(v.clone())),
@@ -175,6 +175,10 @@ impl WithJsonSchema for FieldType {
                 TypeValue::Bool => json!({
                     "type": "boolean",
                 }),
+                TypeValue::Bytes => json!({
+                    "type": "string",
+                    "contentEncoding": "base64",
+                }),
                 TypeValue::Null => json!({
                     "type": "null",
                 }),
@@ -0,0 +1,139 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use anyhow::Result;
+use internal_baml_parser_database::{ParserDatabase, TypeWalker};
+
+use super::repr::{Class, Enum, Function, Node, TypeAlias, WithRepr};
+
+type Memo<T> = RwLock<HashMap<String, Arc<Node<T>>>>;
+
+/// A view of the IR that builds each node from the parser database the first time it is asked
+/// for, and remembers it.
+///
+/// [`IntermediateRepr::from_parser_database`](super::repr::IntermediateRepr::from_parser_database)
+/// builds every class, enum, function and client up front, which dominates for large schemas
+/// when only one target is needed. Nodes built here are the same as the eager ones.
+pub struct LazyIr<'db> {
+    db: &'db ParserDatabase,
+    classes: Memo<Class>,
+    enums: Memo<Enum>,
+    type_aliases: Memo<TypeAlias>,
+    functions: Memo<Function>,
+}
+
+impl<'db> LazyIr<'db> {
+    pub fn new(db: &'db ParserDatabase) -> Self {
+        Self {
+            db,
+            classes: Default::default(),
+            enums: Default::default(),
+            type_aliases: Default::default(),
+            functions: Default::default(),
+        }
+    }
+
+    pub fn db(&self) -> &'db ParserDatabase {
+        self.db
+    }
+
+    pub fn find_class(&self, name: &str) -> Result<Arc<Node<Class>>> {
+        memoized(&self.classes, name, || match self.db.find_type_by_str(name) {
+            Some(TypeWalker::Class(class)) => class.node(self.db),
+            _ => anyhow::bail!("Class `{name}` not found"),
+        })
+    }
+
+    pub fn find_enum(&self, name: &str) -> Result<Arc<Node<Enum>>> {
+        memoized(&self.enums, name, || match self.db.find_type_by_str(name) {
+            Some(TypeWalker::Enum(enm)) => enm.node(self.db),
+            _ => anyhow::bail!("Enum `{name}` not found"),
+        })
+    }
+
+    pub fn find_type_alias(&self, name: &str) -> Result<Arc<Node<TypeAlias>>> {
+        memoized(&self.type_aliases, name, || {
+            match self.db.find_type_by_str(name) {
+                Some(TypeWalker::TypeAlias(alias)) => alias.node(self.db),
+                _ => anyhow::bail!("Type alias `{name}` not found"),
+            }
+        })
+    }
+
+    pub fn find_function(&self, name: &str) -> Result<Arc<Node<Function>>> {
+        memoized(&self.functions, name, || {
+            match self.db.find_function_by_name(name) {
+                Some(function) => function.node(self.db),
+                None => anyhow::bail!("Function `{name}` not found"),
+            }
+        })
+    }
+
+    /// Number of nodes built so far.
+    pub fn built(&self) -> usize {
+        self.classes.read().unwrap().len()
+            + self.enums.read().unwrap().len()
+            + self.type_aliases.read().unwrap().len()
+            + self.functions.read().unwrap().len()
+    }
+}
+
+/// The node named `name` in `memo`, built and stored on first use. Failures are not stored.
+fn memoized<T>(
+    memo: &Memo<T>,
+    name: &str,
+    build: impl FnOnce() -> Result<Node<T>>,
+) -> Result<Arc<Node<T>>> {
+    if let Some(node) = memo.read().unwrap().get(name) {
+        return Ok(node.clone());
+    }
+    let node = Arc::new(build()?);
+    // Another thread may have built it meanwhile; keep the first so callers share one node.
+    Ok(memo
+        .write()
+        .unwrap()
+        .entry(name.to_string())
+        .or_insert(node)
+        .clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{ir_helpers::IRHelper, repr::make_test_ir};
+    use crate::validate;
+    use std::path::PathBuf;
+
+    #[test]
+    fn builds_only_requested_nodes_once() {
+        let source = r#"
+          class Foo {
+            bar Bar
+          }
+          class Bar {
+            baz int @alias("qux")
+          }
+          enum Color {
+            RED
+          }
+        "#;
+        let path: PathBuf = "fake_file.baml".into();
+        let validated = validate(&path, vec![(path.clone(), source).into()]);
+        let lazy = LazyIr::new(&validated.db);
+
+        let foo = lazy.find_class("Foo").unwrap();
+        assert_eq!(lazy.built(), 1);
+        assert!(Arc::ptr_eq(&foo, &lazy.find_class("Foo").unwrap()));
+        assert!(lazy.find_class("Color").is_err());
+        assert_eq!(lazy.built(), 1);
+
+        let eager = make_test_ir(source).unwrap();
+        let bar = lazy.find_class("Bar").unwrap();
+        assert_eq!(
+            format!("{:?}", bar),
+            format!("{:?}", eager.find_class("Bar").unwrap().item)
+        );
+    }
+}
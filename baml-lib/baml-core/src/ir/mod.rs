@@ -1,15 +1,19 @@
 mod ir_helpers;
 pub mod jinja_helpers;
 mod json_schema;
+mod lazy;
 pub mod repr;
 mod walker;
 
 pub use ir_helpers::{
     scope_diagnostics, ArgCoercer, ClassFieldWalker, ClassWalker, ClientWalker, EnumValueWalker,
-    EnumWalker, FunctionWalker, IRHelper, RetryPolicyWalker, TemplateStringWalker, TestCaseWalker,
+    EnumWalker, FunctionWalker, IRHelper, PromptSuiteWalker, RetryPolicyWalker,
+    TemplateStringWalker, TestCaseWalker,
 };
 
-pub(super) use repr::IntermediateRepr;
+pub use json_schema::WithJsonSchema;
+pub use lazy::LazyIr;
+pub use repr::IntermediateRepr;
 
 // Add aliases for the IR types
 pub type Enum = repr::Node<repr::Enum>;
@@ -26,6 +30,7 @@ pub(super) type FunctionArgs = repr::FunctionArgs;
 pub(super) type Impl = repr::Node<repr::Implementation>;
 pub type Client = repr::Node<repr::Client>;
 pub type RetryPolicy = repr::Node<repr::RetryPolicy>;
+pub type PromptSuite = repr::Node<repr::PromptSuite>;
 pub type TemplateString = repr::Node<repr::TemplateString>;
 pub type TestCase = repr::Node<repr::TestCase>;
 pub(super) type Walker<'db, I> = repr::Walker<'db, I>;
@@ -32,6 +32,7 @@ pub struct IntermediateRepr {
     clients: Vec<Node<Client>>,
     retry_policies: Vec<Node<RetryPolicy>>,
     template_strings: Vec<Node<TemplateString>>,
+    prompt_suites: Vec<Node<PromptSuite>>,
 
     /// Strongly connected components of the dependency graph (finite cycles).
     finite_recursive_cycles: Vec<IndexSet<String>>,
@@ -66,6 +67,7 @@ impl IntermediateRepr {
             clients: vec![],
             retry_policies: vec![],
             template_strings: vec![],
+            prompt_suites: vec![],
             configuration: Configuration::new(),
         }
     }
@@ -173,6 +175,14 @@ impl IntermediateRepr {
             .map(|e| Walker { db: self, item: e })
     }
 
+    pub fn walk_prompt_suites(
+        &self,
+    ) -> impl ExactSizeIterator<Item = Walker<'_, &Node<PromptSuite>>> {
+        self.prompt_suites
+            .iter()
+            .map(|e| Walker { db: self, item: e })
+    }
+
     pub fn from_parser_database(
         db: &ParserDatabase,
         configuration: Configuration,
@@ -227,17 +237,26 @@ impl IntermediateRepr {
                 .walk_templates()
                 .map(|e| e.node(db))
                 .collect::<Result<Vec<_>>>()?,
+            prompt_suites: db
+                .walk_prompt_suites()
+                .map(|e| WithRepr::<PromptSuite>::node(&e, db))
+                .collect::<Result<Vec<_>>>()?,
             configuration,
         };
 
         // Sort each item by name.
         repr.enums.sort_by(|a, b| a.elem.name.cmp(&b.elem.name));
         repr.classes.sort_by(|a, b| a.elem.name.cmp(&b.elem.name));
+        repr.type_aliases.sort_by(|a, b| a.elem.name.cmp(&b.elem.name));
         repr.functions
             .sort_by(|a, b| a.elem.name().cmp(b.elem.name()));
         repr.clients.sort_by(|a, b| a.elem.name.cmp(&b.elem.name));
         repr.retry_policies
             .sort_by(|a, b| a.elem.name.0.cmp(&b.elem.name.0));
+        repr.template_strings
+            .sort_by(|a, b| a.elem.name.cmp(&b.elem.name));
+        repr.prompt_suites
+            .sort_by(|a, b| a.elem.name.cmp(&b.elem.name));
 
         Ok(repr)
     }
@@ -303,6 +322,18 @@ fn to_ir_attributes(
             dynamic_type,
             skip,
             constraints,
+            deprecated,
+            since,
+            until,
+            open,
+            coerce,
+            group,
+            required_if,
+            one_of,
+            normalize_to_list,
+            int_overflow,
+            null_values,
+            render,
         } = attributes;
 
         let description = description
@@ -328,7 +359,126 @@ fn to_ir_attributes(
             }
         });
 
-        let meta = vec![description, alias, dynamic_type, skip]
+        let deprecated = deprecated
+            .as_ref()
+            .map(|v| ("deprecated".to_string(), v.without_meta()));
+
+        let open = open.as_ref().and_then(|v| {
+            if *v {
+                Some(("open".to_string(), UnresolvedValue::Bool(true, ())))
+            } else {
+                None
+            }
+        });
+        let since = since
+            .as_ref()
+            .map(|v| ("since".to_string(), v.without_meta()));
+        let until = until
+            .as_ref()
+            .map(|v| ("until".to_string(), v.without_meta()));
+        let coerce = coerce
+            .as_ref()
+            .map(|v| ("coerce".to_string(), v.without_meta()));
+        let group = group
+            .as_ref()
+            .map(|v| ("group".to_string(), v.without_meta()));
+        let required_if = required_if.as_ref().map(|condition| {
+            (
+                "required_if".to_string(),
+                UnresolvedValue::String(StringOr::JinjaExpression(condition.clone()), ()),
+            )
+        });
+
+        let one_of = (!one_of.is_empty()).then(|| {
+            let groups = one_of
+                .iter()
+                .map(|fields| {
+                    UnresolvedValue::Array(
+                        fields
+                            .iter()
+                            .map(|f| UnresolvedValue::String(StringOr::Value(f.clone()), ()))
+                            .collect(),
+                        (),
+                    )
+                })
+                .collect();
+            ("one_of".to_string(), UnresolvedValue::Array(groups, ()))
+        });
+
+        let normalize = normalize_to_list.as_ref().and_then(|v| {
+            if *v {
+                Some((
+                    "normalize".to_string(),
+                    UnresolvedValue::String(StringOr::Value("list".to_string()), ()),
+                ))
+            } else {
+                None
+            }
+        });
+
+        let int_overflow = int_overflow.as_ref().map(|policy| {
+            (
+                "int_overflow".to_string(),
+                UnresolvedValue::String(StringOr::Value(policy.clone()), ()),
+            )
+        });
+
+        let null_values = null_values.as_ref().map(|values| {
+            let values = values
+                .iter()
+                .map(|v| UnresolvedValue::String(StringOr::Value(v.clone()), ()))
+                .collect();
+            ("null_values".to_string(), UnresolvedValue::Array(values, ()))
+        });
+
+        let render = render.as_ref().map(|render| {
+            let mut options = IndexMap::new();
+            if let Some(hoisted) = render.hoisted {
+                let layout = if hoisted { "hoisted" } else { "inline" };
+                options.insert(
+                    "layout".to_string(),
+                    (
+                        (),
+                        UnresolvedValue::String(StringOr::Value(layout.to_string()), ()),
+                    ),
+                );
+            }
+            if let Some(separator) = &render.separator {
+                options.insert(
+                    "separator".to_string(),
+                    (
+                        (),
+                        UnresolvedValue::String(StringOr::Value(separator.clone()), ()),
+                    ),
+                );
+            }
+            if let Some(descriptions) = render.descriptions {
+                options.insert(
+                    "descriptions".to_string(),
+                    ((), UnresolvedValue::Bool(descriptions, ())),
+                );
+            }
+            ("render".to_string(), UnresolvedValue::Map(options, ()))
+        });
+
+        let meta = vec![
+            description,
+            alias,
+            dynamic_type,
+            skip,
+            deprecated,
+            since,
+            until,
+            open,
+            coerce,
+            group,
+            required_if,
+            one_of,
+            normalize,
+            int_overflow,
+            null_values,
+            render,
+        ]
             .into_iter()
             .flatten()
             .collect();
@@ -374,7 +524,7 @@ fn type_with_arity(t: FieldType, arity: &FieldArity) -> FieldType {
 impl WithRepr<FieldType> for ast::FieldType {
     // TODO: (Greg) This code only extracts constraints, and ignores any
     // other types of attributes attached to the type directly.
-    fn attributes(&self, _db: &ParserDatabase) -> NodeAttributes {
+    fn attributes(&self, db: &ParserDatabase) -> NodeAttributes {
         let constraints = self
             .attributes()
             .iter()
@@ -402,7 +552,7 @@ impl WithRepr<FieldType> for ast::FieldType {
                 }?;
                 Some(Constraint {
                     level,
-                    expression,
+                    expression: db.resolve_constants(&expression),
                     label,
                 })
             })
@@ -557,6 +707,8 @@ type TemplateStringId = String;
 pub struct TemplateString {
     pub name: TemplateStringId,
     pub params: Vec<Field>,
+    /// The default values of the params that have one, resolved when the template is rendered.
+    pub defaults: IndexMap<String, UnresolvedValue<()>>,
     pub content: String,
 }
 
@@ -587,6 +739,11 @@ impl WithRepr<TemplateString> for TemplateStringWalker<'_> {
                     })
                     .collect::<Vec<_>>()
             }),
+            defaults: self
+                .defaults()
+                .iter()
+                .map(|(name, value)| (name.clone(), value.without_meta()))
+                .collect(),
             content: self.template_string().to_string(),
         })
     }
@@ -1038,6 +1195,38 @@ impl WithRepr<RetryPolicy> for ConfigurationWalker<'_> {
     }
 }
 
+/// Named prompt templates for a function, to render instead of its own prompt.
+#[derive(Debug)]
+pub struct PromptSuite {
+    pub name: String,
+    pub function: String,
+    /// The template of each variant, in declaration order.
+    pub variants: IndexMap<String, String>,
+}
+
+impl WithRepr<PromptSuite> for ConfigurationWalker<'_> {
+    fn attributes(&self, _db: &ParserDatabase) -> NodeAttributes {
+        NodeAttributes {
+            meta: IndexMap::new(),
+            constraints: Vec::new(),
+            span: Some(self.span().clone()),
+        }
+    }
+
+    fn repr(&self, _db: &ParserDatabase) -> Result<PromptSuite> {
+        let suite = self.prompt_suite();
+        Ok(PromptSuite {
+            name: self.name().to_string(),
+            function: suite.function.0.clone(),
+            variants: suite
+                .variants
+                .iter()
+                .map(|(name, (template, _))| (name.clone(), template.clone()))
+                .collect(),
+        })
+    }
+}
+
 #[derive(serde::Serialize, Debug)]
 pub struct TestCaseFunction(String);
 
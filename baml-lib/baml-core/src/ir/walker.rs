@@ -10,8 +10,8 @@ use std::collections::{HashMap, HashSet};
 
 use super::{
     repr::{self, FunctionConfig, WithRepr},
-    Class, Client, Enum, EnumValue, Field, FunctionNode, IRHelper, Impl, RetryPolicy,
-    TemplateString, TestCase, TypeAlias, Walker,
+    Class, Client, Enum, EnumValue, Field, FunctionNode, IRHelper, Impl, PromptSuite,
+    RetryPolicy, TemplateString, TestCase, TypeAlias, Walker,
 };
 use crate::ir::jinja_helpers::render_expression;
 
@@ -339,6 +339,33 @@ impl<'a> Walker<'a, &'a RetryPolicy> {
     }
 }
 
+impl<'a> Walker<'a, &'a PromptSuite> {
+    pub fn name(&self) -> &'a str {
+        &self.elem().name
+    }
+
+    pub fn elem(&self) -> &'a repr::PromptSuite {
+        &self.item.elem
+    }
+
+    pub fn function_name(&self) -> &'a str {
+        &self.elem().function
+    }
+
+    pub fn variants(&self) -> &'a IndexMap<String, String> {
+        &self.elem().variants
+    }
+
+    /// The template of `variant`, if the suite has it.
+    pub fn variant(&self, name: &str) -> Option<&'a str> {
+        self.variants().get(name).map(String::as_str)
+    }
+
+    pub fn span(&self) -> Option<&crate::Span> {
+        self.item.attributes.span.as_ref()
+    }
+}
+
 impl<'a> Walker<'a, &'a TemplateString> {
     pub fn elem(&self) -> &'a repr::TemplateString {
         &self.item.elem
@@ -352,6 +379,10 @@ impl<'a> Walker<'a, &'a TemplateString> {
         &self.item.elem.params
     }
 
+    pub fn defaults(&self) -> &'a IndexMap<String, UnresolvedValue<()>> {
+        &self.item.elem.defaults
+    }
+
     pub fn template(&self) -> &str {
         &self.elem().content
     }
@@ -8,17 +8,17 @@ pub use internal_baml_parser_database::{self};
 pub use internal_baml_schema_ast::{self, ast};
 
 use rayon::prelude::*;
-use std::{
-    path::{Path, PathBuf},
-    sync::Mutex,
-};
+use std::path::{Path, PathBuf};
 
 use internal_baml_diagnostics::{DatamodelError, Diagnostics, SourceFile, Span};
+use internal_baml_parser_database::{ReservedNamePolicy, DEFAULT_MAX_TYPE_DEPTH};
 
 mod common;
 pub mod configuration;
 pub mod ir;
 // mod lockfile;
+mod session;
+mod symbols;
 mod validate;
 
 use self::validate::generator_loader;
@@ -26,6 +26,8 @@ use self::validate::generator_loader;
 pub use crate::{
     common::{PreviewFeature, PreviewFeatures, ALL_PREVIEW_FEATURES},
     configuration::Configuration,
+    session::SchemaSession,
+    symbols::{Symbol, SymbolKind},
 };
 
 pub struct ValidatedSchema {
@@ -43,26 +45,52 @@ impl std::fmt::Debug for ValidatedSchema {
 /// The most general API for dealing with BAML source code. It accumulates what analysis and
 /// validation information it can, and returns it along with any error and warning diagnostics.
 pub fn validate(root_path: &Path, files: Vec<SourceFile>) -> ValidatedSchema {
+    validate_with_reserved_names(root_path, files, ReservedNamePolicy::default())
+}
+
+/// Like [`validate`], with `reserved_names` deciding which names declarations may not use.
+pub fn validate_with_reserved_names(
+    root_path: &Path,
+    files: Vec<SourceFile>,
+    reserved_names: ReservedNamePolicy,
+) -> ValidatedSchema {
+    validate_with_limits(root_path, files, reserved_names, DEFAULT_MAX_TYPE_DEPTH)
+}
+
+/// Like [`validate_with_reserved_names`], rejecting types nested deeper than `max_type_depth`.
+pub fn validate_with_limits(
+    root_path: &Path,
+    files: Vec<SourceFile>,
+    reserved_names: ReservedNamePolicy,
+    max_type_depth: usize,
+) -> ValidatedSchema {
+    let parsed = files
+        .par_iter()
+        .map(|file| internal_baml_schema_ast::parse_schema(root_path, file))
+        .collect::<Vec<_>>();
+    validate_parsed(root_path, parsed, reserved_names, max_type_depth)
+}
+
+/// Validates files parsed with [`internal_baml_schema_ast::parse_schema`], in order.
+fn validate_parsed(
+    root_path: &Path,
+    parsed: impl IntoIterator<Item = Result<(ast::SchemaAst, Diagnostics), Diagnostics>>,
+    reserved_names: ReservedNamePolicy,
+    max_type_depth: usize,
+) -> ValidatedSchema {
     let mut diagnostics = Diagnostics::new(root_path.to_path_buf());
     let mut db = internal_baml_parser_database::ParserDatabase::new();
-
-    {
-        let diagnostics = Mutex::new(&mut diagnostics);
-        let db = Mutex::new(&mut db);
-        files.par_iter().for_each(|file| {
-            match internal_baml_schema_ast::parse_schema(root_path, file) {
-                Ok((ast, err)) => {
-                    let mut diagnostics = diagnostics.lock().unwrap();
-                    let mut db = db.lock().unwrap();
-                    diagnostics.push(err);
-                    db.add_ast(ast);
-                }
-                Err(err) => {
-                    let mut diagnostics = diagnostics.lock().unwrap();
-                    diagnostics.push(err);
-                }
+    db.set_reserved_names(reserved_names);
+    db.set_max_type_depth(max_type_depth);
+
+    for file in parsed {
+        match file {
+            Ok((ast, err)) => {
+                diagnostics.push(err);
+                db.add_ast(ast);
             }
-        });
+            Err(err) => diagnostics.push(err),
+        }
     }
 
     if let Err(d) = db.validate(&mut diagnostics) {
@@ -0,0 +1,260 @@
+use std::{
+    mem,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use indexmap::IndexMap;
+use internal_baml_diagnostics::{Diagnostics, SourceFile};
+use internal_baml_parser_database::{ReservedNamePolicy, DEFAULT_MAX_TYPE_DEPTH};
+use internal_baml_schema_ast::{ast::SchemaAst, parse_schema};
+
+use crate::{validate_parsed, ValidatedSchema};
+
+/// A set of `.baml` files that change one at a time, as in an editor.
+///
+/// Each file keeps its parsed AST until its contents change, so [`validate`](Self::validate)
+/// after an edit only parses the edited file, and the validated schema is kept until the next
+/// edit. The ASTs move into the validated schema's database and back out of it on the next
+/// validation, rather than being copied, unless the previous schema is still in use: then the
+/// unchanged files are parsed again.
+///
+/// Name resolution and validation are not incremental: after an edit they run again over all
+/// the files, since an edit to one may break references in the others.
+#[derive(Debug)]
+pub struct SchemaSession {
+    root_path: PathBuf,
+    reserved_names: ReservedNamePolicy,
+    max_type_depth: usize,
+    files: IndexMap<PathBuf, ParsedFile>,
+    /// The result of the last [`validate`](Self::validate).
+    validated: Option<Arc<ValidatedSchema>>,
+    /// Whether a file or setting changed since `validated`.
+    changed: bool,
+}
+
+#[derive(Debug)]
+struct ParsedFile {
+    source: SourceFile,
+    /// The diagnostics of parsing the file: its errors, if it did not parse.
+    diagnostics: Diagnostics,
+    ast: FileAst,
+}
+
+#[derive(Debug)]
+enum FileAst {
+    /// The file did not parse.
+    Invalid,
+    Parsed(SchemaAst),
+    /// The AST is in the database of the validated schema, at these positions.
+    Validated {
+        tops: Range<usize>,
+        trivia: Range<usize>,
+    },
+}
+
+impl ParsedFile {
+    fn parse(root_path: &Path, source: SourceFile) -> Self {
+        let (ast, diagnostics) = match parse_schema(root_path, &source) {
+            Ok((ast, diagnostics)) => (FileAst::Parsed(ast), diagnostics),
+            Err(diagnostics) => (FileAst::Invalid, diagnostics),
+        };
+        Self {
+            source,
+            diagnostics,
+            ast,
+        }
+    }
+}
+
+impl SchemaSession {
+    pub fn new(root_path: &Path) -> Self {
+        Self {
+            root_path: root_path.to_path_buf(),
+            reserved_names: ReservedNamePolicy::default(),
+            max_type_depth: DEFAULT_MAX_TYPE_DEPTH,
+            files: IndexMap::new(),
+            validated: None,
+            changed: false,
+        }
+    }
+
+    /// Use `policy` instead of the default reserved names when validating.
+    pub fn set_reserved_names(&mut self, policy: ReservedNamePolicy) {
+        self.reserved_names = policy;
+        self.changed = true;
+    }
+
+    /// Reject types nested deeper than `max_depth` instead of [`DEFAULT_MAX_TYPE_DEPTH`].
+    pub fn set_max_type_depth(&mut self, max_depth: usize) {
+        self.max_type_depth = max_depth;
+        self.changed = true;
+    }
+
+    /// Adds `file`, or replaces the file with the same path. Returns whether it was parsed:
+    /// a file whose contents did not change keeps its AST.
+    pub fn update_file(&mut self, file: SourceFile) -> bool {
+        let path = file.path_buf().clone();
+        if let Some(existing) = self.files.get(&path) {
+            if existing.source.as_str() == file.as_str() {
+                return false;
+            }
+        }
+        let parsed = ParsedFile::parse(&self.root_path, file);
+        self.files.insert(path, parsed);
+        self.changed = true;
+        true
+    }
+
+    /// Removes the file at `path`. Returns whether there was one.
+    pub fn remove_file(&mut self, path: &Path) -> bool {
+        let removed = self.files.shift_remove(path).is_some();
+        self.changed |= removed;
+        removed
+    }
+
+    /// The paths of the files, in the order they were added.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.keys().map(PathBuf::as_path)
+    }
+
+    /// Validates the files together, like [`validate_with_limits`](crate::validate_with_limits)
+    /// with the session's reserved names and depth limit. Without changes since the last call,
+    /// returns the same schema without validating again.
+    pub fn validate(&mut self) -> Arc<ValidatedSchema> {
+        if let Some(validated) = self.validated.as_ref().filter(|_| !self.changed) {
+            return validated.clone();
+        }
+        self.take_back_asts();
+
+        let mut tops = 0;
+        let mut trivia = 0;
+        let mut parsed = vec![];
+        for file in self.files.values_mut() {
+            match mem::replace(&mut file.ast, FileAst::Invalid) {
+                FileAst::Parsed(ast) => {
+                    file.ast = FileAst::Validated {
+                        tops: tops..tops + ast.tops.len(),
+                        trivia: trivia..trivia + ast.trivia.len(),
+                    };
+                    tops += ast.tops.len();
+                    trivia += ast.trivia.len();
+                    parsed.push(Ok((ast, file.diagnostics.clone())));
+                }
+                _ => parsed.push(Err(file.diagnostics.clone())),
+            }
+        }
+        let validated = Arc::new(validate_parsed(
+            &self.root_path,
+            parsed,
+            self.reserved_names.clone(),
+            self.max_type_depth,
+        ));
+        self.validated = Some(validated.clone());
+        self.changed = false;
+        validated
+    }
+
+    /// Moves the ASTs of the files back out of the last validated schema, or parses the files
+    /// again if that schema is still in use.
+    fn take_back_asts(&mut self) {
+        let ast = self
+            .validated
+            .take()
+            .and_then(|validated| Arc::try_unwrap(validated).ok())
+            .map(|validated| validated.db.into_ast());
+        let Some(ast) = ast else {
+            for file in self.files.values_mut() {
+                if matches!(file.ast, FileAst::Validated { .. }) {
+                    *file = ParsedFile::parse(&self.root_path, file.source.clone());
+                }
+            }
+            return;
+        };
+        let mut tops = ast.tops.into_iter().map(Some).collect::<Vec<_>>();
+        let mut trivia = ast.trivia.into_iter().map(Some).collect::<Vec<_>>();
+        for file in self.files.values_mut() {
+            if let FileAst::Validated {
+                tops: file_tops,
+                trivia: file_trivia,
+            } = &file.ast
+            {
+                file.ast = FileAst::Parsed(SchemaAst {
+                    tops: take(&mut tops[file_tops.clone()]),
+                    trivia: take(&mut trivia[file_trivia.clone()]),
+                });
+            }
+        }
+    }
+}
+
+/// The items of `slots`, leaving them empty.
+fn take<T>(slots: &mut [Option<T>]) -> Vec<T> {
+    slots.iter_mut().flat_map(Option::take).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, contents: &str) -> SourceFile {
+        (PathBuf::from(path), contents).into()
+    }
+
+    #[test]
+    fn reparses_only_changed_files() {
+        let mut session = SchemaSession::new(Path::new("."));
+        assert!(session.update_file(file("paint.baml", "class Paint {\n  color Color\n}\n")));
+        assert!(session.update_file(file("color.baml", "enum Color {\n  Red\n}\n")));
+        assert!(!session.validate().diagnostics.has_errors());
+
+        let validated = session.validate();
+        assert!(!session.update_file(file("color.baml", "enum Color {\n  Red\n}\n")));
+        assert!(Arc::ptr_eq(&validated, &session.validate()));
+        assert!(session.update_file(file("color.baml", "enum Colour {\n  Red\n}\n")));
+        let validated = session.validate();
+        let errors = validated.diagnostics.errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].span().file.path(), "paint.baml");
+
+        assert!(session.remove_file(Path::new("color.baml")));
+        assert!(!session.remove_file(Path::new("color.baml")));
+        assert_eq!(
+            session.paths().collect::<Vec<_>>(),
+            [Path::new("paint.baml")]
+        );
+        assert!(session.validate().diagnostics.has_errors());
+    }
+
+    #[test]
+    fn unchanged_asts_survive_revalidation() {
+        let mut session = SchemaSession::new(Path::new("."));
+        session.update_file(file("paint.baml", "class Paint {\n  color Color\n}\n"));
+        session.update_file(file("color.baml", "enum Color {\n  Red\n}\n"));
+        session.update_file(file("bad.baml", "class {"));
+        let errors = |session: &mut SchemaSession| {
+            let validated = session.validate();
+            let errors = validated.diagnostics.errors();
+            errors
+                .iter()
+                .map(|e| e.span().file.path())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(errors(&mut session), ["bad.baml"]);
+
+        // The ASTs move back out of the schema, which nothing else holds.
+        session.update_file(file("paint.baml", "class Paint {\n  color Color?\n}\n"));
+        assert_eq!(errors(&mut session), ["bad.baml"]);
+        session.remove_file(Path::new("bad.baml"));
+        assert!(errors(&mut session).is_empty());
+
+        // The unchanged files are parsed again, as the schema is still in use.
+        let held = session.validate();
+        session.update_file(file("paint.baml", "class Paint {\n  colour Colour\n}\n"));
+        assert_eq!(errors(&mut session), ["paint.baml"]);
+        session.update_file(file("paint.baml", "class Paint {\n  color Color\n}\n"));
+        assert!(errors(&mut session).is_empty());
+        assert!(!held.diagnostics.has_errors());
+    }
+}
@@ -0,0 +1,201 @@
+//! What is at a position in the schema, for hover and go-to-definition in editors.
+
+use std::path::Path;
+
+use baml_types::FieldType;
+use internal_baml_diagnostics::Span;
+use internal_baml_schema_ast::ast::{self, Top, WithIdentifier, WithName, WithSpan};
+
+use crate::{ir::repr::WithRepr, ValidatedSchema};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Class,
+    Enum,
+    TypeAlias,
+    Field,
+    EnumValue,
+}
+
+/// A class, enum, type alias, field or enum value found by [`ValidatedSchema::symbol_at`].
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub kind: SymbolKind,
+    /// The name, qualified for fields and enum values: `Person.name`, `Color.Red`.
+    pub name: String,
+    /// The identifier at the position: the declaration or a reference to it.
+    pub span: Span,
+    /// The identifier in the declaration.
+    pub definition: Span,
+    /// The type of a field, the target of a type alias, or the class or enum itself (that of
+    /// an enum value), with non-recursive aliases resolved. `None` when the schema has errors.
+    pub r#type: Option<FieldType>,
+}
+
+impl ValidatedSchema {
+    /// The symbol whose name is at byte `offset` of `file`: a class, enum, type alias, field or
+    /// enum value where it is declared, or the class, enum or type alias a type refers to, in
+    /// fields, type aliases, and parameters and return types of functions and template strings.
+    /// The end of a name counts as on it, as for a cursor right after it.
+    pub fn symbol_at(&self, file: &Path, offset: usize) -> Option<Symbol> {
+        let at = |span: &Span| {
+            span.file.path_buf() == file && span.start <= offset && offset <= span.end
+        };
+        let top = self
+            .db
+            .ast()
+            .iter_tops()
+            .map(|(_, top)| top)
+            .find(|top| at(top.span()))?;
+        if at(top.identifier().span()) {
+            return self.type_symbol(top.name(), top.identifier().span());
+        }
+
+        let mut types = Vec::new();
+        match top {
+            Top::Class(block) | Top::Enum(block) => {
+                for (_, field) in block.iter_fields() {
+                    let span = field.identifier().span();
+                    if at(span) {
+                        let (kind, r#type) = match top {
+                            Top::Class(_) => (
+                                SymbolKind::Field,
+                                field.expr.as_ref().and_then(|t| self.resolve_type(t)),
+                            ),
+                            _ => (
+                                SymbolKind::EnumValue,
+                                Some(FieldType::Enum(block.name().to_string())),
+                            ),
+                        };
+                        return Some(Symbol {
+                            kind,
+                            name: format!("{}.{}", block.name(), field.name()),
+                            span: span.clone(),
+                            definition: span.clone(),
+                            r#type: r#type.filter(|_| !self.diagnostics.has_errors()),
+                        });
+                    }
+                    types.extend(field.expr.as_ref());
+                }
+            }
+            Top::TypeAlias(assignment) => types.push(&assignment.value),
+            Top::Function(block) => {
+                let input = block.input().into_iter().flat_map(|args| &args.args);
+                types.extend(input.map(|(_, arg)| &arg.field_type));
+                types.extend(block.output().map(|arg| &arg.field_type));
+            }
+            Top::TemplateString(template) => {
+                let input = template.input().into_iter().flat_map(|args| &args.args);
+                types.extend(input.map(|(_, arg)| &arg.field_type));
+            }
+            _ => {}
+        }
+        let reference = types
+            .into_iter()
+            .flat_map(|field_type| field_type.flat_idns())
+            .find(|idn| at(idn.span()))?;
+        self.type_symbol(reference.name(), reference.span())
+    }
+
+    /// The class, enum or type alias `name`, referred to at `span`.
+    fn type_symbol(&self, name: &str, span: &Span) -> Option<Symbol> {
+        let top = self.db.ast().iter_tops().map(|(_, top)| top).find(|top| {
+            top.name() == name && matches!(top, Top::Class(_) | Top::Enum(_) | Top::TypeAlias(_))
+        })?;
+        let (kind, r#type) = match top {
+            Top::Class(_) => (SymbolKind::Class, Some(FieldType::Class(name.to_string()))),
+            Top::Enum(_) => (SymbolKind::Enum, Some(FieldType::Enum(name.to_string()))),
+            _ => (
+                SymbolKind::TypeAlias,
+                top.as_type_alias_assignment()
+                    .and_then(|a| self.resolve_type(&a.value)),
+            ),
+        };
+        Some(Symbol {
+            kind,
+            name: name.to_string(),
+            span: span.clone(),
+            definition: top.identifier().span().clone(),
+            r#type: r#type.filter(|_| !self.diagnostics.has_errors()),
+        })
+    }
+
+    /// `field_type` with non-recursive aliases resolved, if the schema resolved.
+    fn resolve_type(&self, field_type: &ast::FieldType) -> Option<FieldType> {
+        if self.diagnostics.has_errors() {
+            return None;
+        }
+        field_type.repr(&self.db).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::validate;
+
+    const SCHEMA: &str = r##"class Person {
+  name string
+  home Home?
+}
+
+type Home = Address | string
+
+class Address {
+  city string
+}
+
+enum Color {
+  Red
+}
+
+function Locate(person: Person) -> Address {
+  client "openai/gpt-4o"
+  prompt #"{{ person }}"#
+}
+"##;
+
+    #[test]
+    fn finds_declarations_and_references() {
+        let path = PathBuf::from("schema.baml");
+        let schema = validate(Path::new("."), vec![(path.clone(), SCHEMA).into()]);
+        assert!(!schema.diagnostics.has_errors());
+        let offset = |text: &str| SCHEMA.find(text).unwrap();
+        let symbol = |offset: usize| schema.symbol_at(&path, offset).unwrap();
+
+        let field = symbol(offset("name string"));
+        assert_eq!(
+            (field.kind, field.name.as_str()),
+            (SymbolKind::Field, "Person.name")
+        );
+        assert_eq!(field.r#type.unwrap().to_string(), "string");
+
+        let alias = symbol(offset("Home?") + 2);
+        assert_eq!(
+            (alias.kind, alias.name.as_str()),
+            (SymbolKind::TypeAlias, "Home")
+        );
+        assert_eq!(alias.definition.start, offset("Home ="));
+        assert_eq!(alias.span.start, offset("Home?"));
+        assert_eq!(alias.r#type.unwrap().to_string(), "(Address | string)");
+
+        let class = symbol(offset("-> Address") + 3);
+        assert_eq!(
+            (class.kind, class.name.as_str()),
+            (SymbolKind::Class, "Address")
+        );
+        assert_eq!(class.definition.start, offset("Address {"));
+
+        let value = symbol(offset("Red"));
+        assert_eq!(
+            (value.kind, value.name.as_str()),
+            (SymbolKind::EnumValue, "Color.Red")
+        );
+        assert_eq!(value.r#type.unwrap().to_string(), "Color");
+
+        assert!(schema.symbol_at(&path, offset("client")).is_none());
+        assert!(schema.symbol_at(Path::new("other.baml"), 0).is_none());
+    }
+}
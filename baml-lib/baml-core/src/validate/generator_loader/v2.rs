@@ -1,11 +1,8 @@
-use std::{
-    collections::HashMap,
-    path::{Path, PathBuf},
-    str::FromStr,
-};
+use std::{path::Path, str::FromStr};
 
 use bstd::ProjectFqn;
 use internal_baml_diagnostics::DatamodelError;
+use internal_baml_parser_database::config_block::{ConfigProperties, Spanned};
 use internal_baml_schema_ast::ast::{self, WithName, WithSpan};
 use semver::Version;
 use strum::VariantNames;
@@ -15,55 +12,23 @@ use crate::configuration::{
     GeneratorDefaultClientMode, GeneratorOutputType,
 };
 
-fn parse_required_key<'a>(
-    map: &'a HashMap<&str, &ast::Expression>,
-    key: &str,
-    generator_span: &ast::Span,
-) -> Result<(&'a str, &'a ast::Span), DatamodelError> {
-    let expr = match map.get(key) {
-        Some(expr) => expr,
-        None => {
-            return Err(DatamodelError::new_validation_error(
-                &format!("The `{}` argument is required for a generator.", key),
-                generator_span.clone(),
-            ))
-        }
-    };
-
-    match expr.as_string_value() {
-        Some((name, _)) => Ok((name, expr.span())),
-        None => Err(DatamodelError::new_validation_error(
-            &format!("`{}` must be a string.", key),
-            expr.span().clone(),
-        )),
-    }
-}
-
-fn parse_optional_key<'a>(
-    map: &'a HashMap<&str, &ast::Expression>,
-    key: &str,
-) -> Result<Option<&'a str>, DatamodelError> {
-    let expr = match map.get(key) {
-        Some(expr) => expr,
-        None => {
-            return Ok(None);
-        }
-    };
+const GENERATOR_PROPERTIES: &[&str] = &[
+    "output_type",
+    "output_dir",
+    "version",
+    "default_client_mode",
+    "on_generate",
+    "project",
+];
 
-    match expr.as_string_value() {
-        Some((name, _)) => Ok(Some(name)),
-        None => Err(DatamodelError::new_validation_error(
-            &format!("`{}` must be a string.", key),
-            expr.span().clone(),
-        )),
-    }
-}
+const BOUNDARY_CLOUD_OUTPUT_TYPE: &str = "boundary-cloud";
 
 pub(crate) fn parse_generator(
     ast_generator: &ast::ValueExprBlock,
     baml_src: &Path,
 ) -> Result<Generator, Vec<DatamodelError>> {
     let generator_name = ast_generator.name();
+    let mut properties = ConfigProperties::new(ast_generator, GENERATOR_PROPERTIES);
 
     let mut builder = CodegenGeneratorBuilder::default();
 
@@ -72,125 +37,60 @@ pub(crate) fn parse_generator(
         .baml_src(baml_src.to_path_buf())
         .span(ast_generator.span().clone());
 
-    let args = check_property_allowlist(generator_name, ast_generator)?;
-    let mut errors = vec![];
-
-    match parse_required_key(&args, "output_type", ast_generator.span()) {
-        Ok((name, name_span)) => match GeneratorOutputType::from_str(name) {
+    if let Some(output_type) = properties.require::<Spanned<'_, &str>>("output_type") {
+        match GeneratorOutputType::from_str(output_type.value) {
             Ok(lang) => {
                 builder.output_type(lang);
             }
-            Err(_) => {
-                const BOUNDARY_CLOUD_OUTPUT_TYPE: &str = "boundary-cloud";
-
-                if name == BOUNDARY_CLOUD_OUTPUT_TYPE {
-                    let mut cloud_builder = CloudProjectBuilder::default();
-                    cloud_builder
-                        .name(generator_name.to_string())
-                        .baml_src(baml_src.to_path_buf())
-                        .span(ast_generator.span().clone());
-                    parse_cloud_project(ast_generator, &args, &mut cloud_builder)?;
-                    return match cloud_builder.build() {
-                        Ok(generator) => Ok(Generator::BoundaryCloud(generator)),
-                        Err(e) => Err(vec![DatamodelError::new_anyhow_error(
-                            anyhow::Error::from(e).context("Error parsing generator"),
-                            ast_generator.span().clone(),
-                        )]),
-                    };
-                } else {
-                    log::error!("Unknown output type: {}", name);
-                    errors.push(DatamodelError::not_found_error(
-                        "output_type",
-                        name,
-                        name_span.clone(),
-                        GeneratorOutputType::VARIANTS
-                            .iter()
-                            .chain([BOUNDARY_CLOUD_OUTPUT_TYPE].iter())
-                            .map(|s| s.to_string())
-                            .collect(),
-                        false,
-                    ));
-                }
-            }
-        },
-        Err(err) => {
-            errors.push(err);
-        }
-    };
-
-    match parse_optional_key(&args, "output_dir") {
-        Ok(Some(name)) => {
-            builder.output_dir(name.into());
-        }
-        Ok(None) => {
-            builder.output_dir("../".into());
-        }
-        Err(err) => {
-            errors.push(err);
-        }
-    };
-
-    match parse_optional_key(&args, "version") {
-        Ok(Some(version_str)) => match Version::parse(version_str) {
-            Ok(version) => {
-                builder.version(version.to_string());
+            Err(_) if output_type.value == BOUNDARY_CLOUD_OUTPUT_TYPE => {
+                return parse_cloud_project(ast_generator, baml_src, properties);
             }
             Err(_) => {
-                errors.push(DatamodelError::new_validation_error(
-                    &format!("Invalid semver version string: '{}'", version_str),
-                    args.get("version")
-                        .map(|arg| arg.span().clone())
-                        .unwrap_or_else(|| ast_generator.span().clone()),
+                log::error!("Unknown output type: {}", output_type.value);
+                properties.push_error(DatamodelError::not_found_error(
+                    "output_type",
+                    output_type.value,
+                    output_type.span.clone(),
+                    GeneratorOutputType::VARIANTS
+                        .iter()
+                        .chain([BOUNDARY_CLOUD_OUTPUT_TYPE].iter())
+                        .map(|s| s.to_string())
+                        .collect(),
+                    false,
                 ));
             }
-        },
-        Ok(None) => {
-            builder.version("0.0.0".to_string());
-        }
-        Err(err) => {
-            errors.push(err);
         }
     }
 
-    match parse_optional_key(&args, "default_client_mode") {
-        Ok(Some("sync")) => {
+    builder.output_dir(properties.get::<&str>("output_dir").unwrap_or("../").into());
+    builder.version(parse_version(&mut properties));
+
+    match properties.get::<Spanned<'_, &str>>("default_client_mode") {
+        Some(Spanned { value: "sync", .. }) => {
             builder.default_client_mode(Some(GeneratorDefaultClientMode::Sync));
         }
-        Ok(Some("async")) => {
+        Some(Spanned { value: "async", .. }) => {
             builder.default_client_mode(Some(GeneratorDefaultClientMode::Async));
         }
-        Ok(Some(name)) => {
-            errors.push(DatamodelError::new_validation_error(
-                &format!("'{}' is not supported. Use one of: 'async' or 'sync'", name),
-                args.get("default_client_mode")
-                    .map(|arg| arg.span())
-                    .unwrap_or_else(|| ast_generator.span())
-                    .clone(),
+        Some(Spanned { value, span }) => {
+            properties.push_error(DatamodelError::new_validation_error(
+                &format!("'{}' is not supported. Use one of: 'async' or 'sync'", value),
+                span.clone(),
             ));
         }
-        Ok(None) => {
+        None => {
             builder.default_client_mode(None);
         }
-        Err(err) => {
-            errors.push(err);
-        }
     }
 
-    match parse_optional_key(&args, "on_generate") {
-        Ok(Some(cmd)) => {
-            builder.on_generate(vec![cmd.to_string()]);
-        }
-        Ok(None) => {
-            builder.on_generate(vec![]);
-        }
-        Err(err) => {
-            errors.push(err);
-        }
-    }
+    builder.on_generate(
+        properties
+            .get::<String>("on_generate")
+            .into_iter()
+            .collect(),
+    );
 
-    if !errors.is_empty() {
-        return Err(errors);
-    }
+    properties.finish()?;
 
     match builder.build() {
         Ok(generator) => Ok(Generator::Codegen(generator)),
@@ -203,114 +103,62 @@ pub(crate) fn parse_generator(
 
 fn parse_cloud_project(
     ast_generator: &ast::ValueExprBlock,
-    args: &HashMap<&str, &ast::Expression>,
-    builder: &mut CloudProjectBuilder,
-) -> Result<(), Vec<DatamodelError>> {
-    let mut errors = vec![];
-    match parse_optional_key(args, "version") {
-        Ok(Some(version_str)) => match Version::parse(version_str) {
-            Ok(version) => {
-                builder.version(version.to_string());
-            }
-            Err(_) => {
-                errors.push(DatamodelError::new_validation_error(
-                    &format!("Invalid semver version string: '{}'", version_str),
-                    args.get("version")
-                        .map(|arg| arg.span().clone())
-                        .unwrap_or_else(|| ast_generator.span().clone()),
-                ));
-            }
-        },
-        Ok(None) => {
-            builder.version("0.0.0".to_string());
-        }
-        Err(err) => {
-            errors.push(err);
-        }
-    }
+    baml_src: &Path,
+    mut properties: ConfigProperties<'_>,
+) -> Result<Generator, Vec<DatamodelError>> {
+    let mut builder = CloudProjectBuilder::default();
+    builder
+        .name(ast_generator.name().to_string())
+        .baml_src(baml_src.to_path_buf())
+        .span(ast_generator.span().clone());
 
-    match parse_optional_key(args, "project") {
-        Ok(Some(project_fqn_str)) => match ProjectFqn::parse(project_fqn_str) {
+    builder.version(parse_version(&mut properties));
+
+    match properties.get::<Spanned<'_, &str>>("project") {
+        Some(project) => match ProjectFqn::parse(project.value) {
             Ok(project_fqn) => {
                 builder.project_fqn(project_fqn);
             }
-            Err(e) => {
-                errors.push(DatamodelError::new_validation_error(
+            Err(_) => {
+                properties.push_error(DatamodelError::new_validation_error(
                     "`project` must be a fully-qualified project ID, i.e. @boundaryml/baml",
-                    args.get("project")
-                        .map(|arg| arg.span().clone())
-                        .unwrap_or_else(|| ast_generator.span().clone()),
+                    project.span.clone(),
                 ));
             }
         },
-        Ok(None) => {
-            errors.push(DatamodelError::new_validation_error(
+        None if !properties.contains("project") => {
+            properties.push_error(DatamodelError::new_validation_error(
                 "`project` is required for a boundary-cloud generator.",
-                args.get("project")
-                    .map(|arg| arg.span().clone())
-                    .unwrap_or_else(|| ast_generator.span().clone()),
+                properties.span_of("project").clone(),
             ));
         }
-        Err(err) => {
-            errors.push(err);
-        }
+        None => {}
     }
 
-    if !errors.is_empty() {
-        return Err(errors);
-    }
+    properties.finish()?;
 
-    Ok(())
+    match builder.build() {
+        Ok(generator) => Ok(Generator::BoundaryCloud(generator)),
+        Err(e) => Err(vec![DatamodelError::new_anyhow_error(
+            anyhow::Error::from(e).context("Error parsing generator"),
+            ast_generator.span().clone(),
+        )]),
+    }
 }
 
-fn check_property_allowlist<'ir>(
-    generator_name: &str,
-    ast_generator: &'ir ast::ValueExprBlock,
-) -> Result<HashMap<&'ir str, &'ir ast::Expression>, Vec<DatamodelError>> {
-    const FIRST_CLASS_PROPERTIES: &[&str] = &[
-        "output_type",
-        "output_dir",
-        "version",
-        "default_client_mode",
-        "on_generate",
-        "project",
-    ];
-
-    let mut errors = vec![];
-    let args = ast_generator
-        .fields()
-        .iter()
-        .map(|arg| match &arg.expr {
-            Some(expr) => {
-                if FIRST_CLASS_PROPERTIES.iter().any(|k| *k == arg.name()) {
-                    Ok((arg.name(), expr))
-                } else {
-                    Err(DatamodelError::new_property_not_known_error(
-                        arg.name(),
-                        arg.span().clone(),
-                        FIRST_CLASS_PROPERTIES.to_vec(),
-                    ))
-                }
-            }
-            None => Err(DatamodelError::new_config_property_missing_value_error(
-                arg.name(),
-                generator_name,
-                "generator",
-                arg.span().clone(),
-            )),
-        })
-        .filter_map(|res| match res {
-            Ok(val) => Some(val),
-            Err(err) => {
-                errors.push(err);
-                None
+/// The semver `version` of the generator, `0.0.0` if not set.
+fn parse_version(properties: &mut ConfigProperties<'_>) -> String {
+    match properties.get::<Spanned<'_, &str>>("version") {
+        Some(version) => match Version::parse(version.value) {
+            Ok(version) => version.to_string(),
+            Err(_) => {
+                properties.push_error(DatamodelError::new_validation_error(
+                    &format!("Invalid semver version string: '{}'", version.value),
+                    version.span.clone(),
+                ));
+                "0.0.0".to_string()
             }
-        })
-        .collect::<HashMap<_, _>>();
-
-    if !errors.is_empty() {
-        return Err(errors);
+        },
+        None => "0.0.0".to_string(),
     }
-
-    Ok(args)
 }
@@ -1,6 +1,7 @@
 use crate::PreviewFeature;
 use enumflags2::BitFlags;
 use internal_baml_diagnostics::{DatamodelError, DatamodelWarning, Diagnostics};
+use rayon::prelude::*;
 
 /// The validation context. The lifetime parameter is _not_ the AST lifetime, but the subtype of
 /// all relevant lifetimes. No data escapes for validations, so the context only need to be valid
@@ -22,4 +23,32 @@ impl Context<'_> {
     pub(super) fn push_warning(&mut self, warning: DatamodelWarning) {
         self.diagnostics.push_warning(warning);
     }
+
+    /// Runs `validate` on every item on the rayon thread pool, each with diagnostics of its
+    /// own, then appends them in item order. The diagnostics are the same as those of a
+    /// sequential loop, so items must not depend on each other.
+    pub(super) fn par_for_each<T: Send>(
+        &mut self,
+        items: Vec<T>,
+        validate: impl Fn(&mut Context<'_>, T) + Sync,
+    ) {
+        let (db, preview_features) = (self.db, self.preview_features);
+        let root_path = self.diagnostics.root_path.clone();
+        let results = items
+            .into_par_iter()
+            .map(|item| {
+                let mut diagnostics = Diagnostics::new(root_path.clone());
+                let mut ctx = Context {
+                    db,
+                    preview_features,
+                    diagnostics: &mut diagnostics,
+                };
+                validate(&mut ctx, item);
+                diagnostics
+            })
+            .collect::<Vec<_>>();
+        for diagnostics in results {
+            self.diagnostics.push(diagnostics);
+        }
+    }
 }
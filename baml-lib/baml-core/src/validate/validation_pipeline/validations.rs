@@ -17,13 +17,17 @@ use super::context::Context;
 use std::collections::HashSet;
 
 pub(super) fn validate(ctx: &mut Context<'_>) {
-    enums::validate(ctx);
-    classes::validate(ctx);
-    functions::validate(ctx);
-    clients::validate(ctx);
-    template_strings::validate(ctx);
-    configurations::validate(ctx);
-    tests::validate(ctx);
+    // These passes only read the database, so they run in parallel.
+    let passes: Vec<fn(&mut Context<'_>)> = vec![
+        enums::validate,
+        classes::validate,
+        functions::validate,
+        clients::validate,
+        template_strings::validate,
+        configurations::validate,
+        tests::validate,
+    ];
+    ctx.par_for_each(passes, |ctx, pass| pass(ctx));
 
     let generators = load_generators_from_ast(ctx.db.ast(), ctx.diagnostics);
     let codegen_targets: HashSet<GeneratorOutputType> = generators
@@ -9,11 +9,13 @@ use itertools::join;
 use std::collections::{HashMap, HashSet};
 
 pub(super) fn validate(ctx: &mut Context<'_>) {
-    let mut defined_types = internal_baml_jinja_types::PredefinedTypes::default(
-        internal_baml_jinja_types::JinjaContext::Prompt,
-    );
+    let db = ctx.db;
+    let classes = db.walk_classes().collect::<Vec<_>>();
+    ctx.par_for_each(classes, |ctx, cls| {
+        let mut defined_types = internal_baml_jinja_types::PredefinedTypes::default(
+            internal_baml_jinja_types::JinjaContext::Prompt,
+        );
 
-    for cls in ctx.db.walk_classes() {
         for c in cls.static_fields() {
             let field = c.ast_field();
             if let Some(ft) = &field.expr {
@@ -47,7 +49,7 @@ pub(super) fn validate(ctx: &mut Context<'_>) {
 
         defined_types.end_scope();
         defined_types.errors_mut().clear();
-    }
+    });
 }
 
 /// Enforce that keywords in the user's requested target languages
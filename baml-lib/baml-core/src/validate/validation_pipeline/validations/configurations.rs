@@ -1,4 +1,5 @@
 use internal_baml_diagnostics::{DatamodelError, DatamodelWarning};
+use internal_baml_schema_ast::ast::WithName;
 
 use crate::validate::validation_pipeline::context::Context;
 
@@ -7,6 +8,30 @@ pub(super) fn validate(ctx: &mut Context<'_>) {
         // Nothing to validate.
     }
 
+    // Variant names must be unique per function, across suites.
+    let mut variants = std::collections::HashMap::new();
+    for config in ctx.db.walk_prompt_suites() {
+        let suite = config.prompt_suite();
+        let (function, span) = &suite.function;
+        if ctx.db.find_function_by_name(function).is_none() {
+            ctx.push_error(DatamodelError::new_type_not_found_error(
+                function,
+                ctx.db.valid_function_names(),
+                span.clone(),
+            ));
+        }
+        for (variant, (_, span)) in &suite.variants {
+            if let Some(other) = variants.insert((function, variant), config.ast_node().name()) {
+                ctx.push_error(DatamodelError::new_validation_error(
+                    &format!(
+                        "Variant `{variant}` of function `{function}` is already defined in prompt_suite {other}"
+                    ),
+                    span.clone(),
+                ));
+            }
+        }
+    }
+
     for config in ctx.db.walk_test_cases() {
         // Ensure that the test case name is valid.
         let case = config.test_case();
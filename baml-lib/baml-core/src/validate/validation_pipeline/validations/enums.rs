@@ -4,10 +4,12 @@ use internal_baml_diagnostics::DatamodelError;
 use internal_baml_schema_ast::ast::{WithName, WithSpan};
 
 pub(super) fn validate(ctx: &mut Context<'_>) {
-    let mut defined_types = internal_baml_jinja_types::PredefinedTypes::default(
-        internal_baml_jinja_types::JinjaContext::Prompt,
-    );
-    for enm in ctx.db.walk_enums() {
+    let db = ctx.db;
+    let enums = db.walk_enums().collect::<Vec<_>>();
+    ctx.par_for_each(enums, |ctx, enm| {
+        let mut defined_types = internal_baml_jinja_types::PredefinedTypes::default(
+            internal_baml_jinja_types::JinjaContext::Prompt,
+        );
         for args in enm.walk_input_args() {
             let arg = args.ast_arg();
             validate_type(ctx, &arg.1.field_type)
@@ -34,5 +36,5 @@ pub(super) fn validate(ctx: &mut Context<'_>) {
 
         defined_types.end_scope();
         defined_types.errors_mut().clear();
-    }
+    });
 }
@@ -112,6 +112,12 @@ pub(super) fn validate(ctx: &mut Context<'_>) {
                     span,
                 ));
             }
+            if let Some(default) = &arg.1.default {
+                ctx.push_error(DatamodelError::new_validation_error(
+                    "Only template_string parameters can have default values.",
+                    default.span().clone(),
+                ));
+            }
         }
 
         // Ensure the client is correct.
@@ -2,9 +2,13 @@ use std::collections::HashSet;
 
 use crate::validate::validation_pipeline::context::Context;
 
+use baml_types::{LiteralValue, TypeValue};
 use internal_baml_diagnostics::{DatamodelError, DatamodelWarning, Span};
 
-use internal_baml_schema_ast::ast::{FieldType, TypeExpId, WithIdentifier, WithName, WithSpan};
+use internal_baml_parser_database::walkers::TemplateStringWalker;
+use internal_baml_schema_ast::ast::{
+    Expression, FieldType, Identifier, TypeExpId, WithIdentifier, WithName, WithSpan,
+};
 
 use super::types::validate_type;
 
@@ -40,6 +44,7 @@ pub(super) fn validate(ctx: &mut Context<'_>) {
                 ));
             }
         }
+        validate_defaults(ctx, template);
 
         let prompt = match template.template_raw() {
             Some(p) => p,
@@ -120,3 +125,85 @@ pub(super) fn validate(ctx: &mut Context<'_>) {
         defined_types.errors_mut().clear();
     }
 }
+
+/// Defaults must be literals of the type of their parameter, and parameters with one must come
+/// last, since calls can pass arguments by position.
+fn validate_defaults(ctx: &mut Context<'_>, template: TemplateStringWalker<'_>) {
+    let mut defaulted = None;
+    for arg in template.walk_input_args() {
+        let (Some(name), arg) = arg.ast_arg() else {
+            continue;
+        };
+        match &arg.default {
+            Some(default) => {
+                if !default_fits(default, &arg.field_type) {
+                    ctx.push_error(DatamodelError::new_validation_error(
+                        &format!(
+                            "The default value of `{}` must be a literal of type `{}`.",
+                            name.name(),
+                            arg.field_type
+                        ),
+                        default.span().clone(),
+                    ));
+                }
+                defaulted = Some(name.name());
+            }
+            None => {
+                if let Some(defaulted) = defaulted {
+                    ctx.push_error(DatamodelError::new_validation_error(
+                        &format!(
+                            "Parameter `{}` needs a default value, since it follows `{defaulted}` which has one.",
+                            name.name()
+                        ),
+                        name.span().clone(),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Whether `default` is a literal value of `field_type`. `env.NAME` stands for a string.
+fn default_fits(default: &Expression, field_type: &FieldType) -> bool {
+    let is_null =
+        matches!(default, Expression::Identifier(Identifier::Local(name, _)) if name == "null");
+    if is_null {
+        return field_type.is_optional();
+    }
+    match (field_type, default) {
+        (FieldType::Union(_, items, ..), _) => items.iter().any(|item| default_fits(default, item)),
+        (
+            FieldType::Primitive(_, TypeValue::String, ..),
+            Expression::StringValue(..)
+            | Expression::RawStringValue(_)
+            | Expression::Identifier(Identifier::ENV(..)),
+        ) => true,
+        (FieldType::Primitive(_, TypeValue::Int, ..), Expression::NumericValue(value, _)) => {
+            !value.contains('.')
+        }
+        (FieldType::Primitive(_, TypeValue::Float, ..), Expression::NumericValue(..)) => true,
+        (FieldType::Primitive(_, TypeValue::Bool, ..), Expression::BoolValue(..)) => true,
+        (
+            FieldType::Literal(_, LiteralValue::String(literal), ..),
+            Expression::StringValue(value, _),
+        ) => literal == value,
+        (
+            FieldType::Literal(_, LiteralValue::Int(literal), ..),
+            Expression::NumericValue(value, _),
+        ) => value.parse() == Ok(*literal),
+        (
+            FieldType::Literal(_, LiteralValue::Bool(literal), ..),
+            Expression::BoolValue(value, _),
+        ) => literal == value,
+        (FieldType::List(_, item, dims, ..), Expression::Array(values, _)) => {
+            values.iter().all(|value| match dims {
+                1 => default_fits(value, item),
+                _ => matches!(value, Expression::Array(..)),
+            })
+        }
+        (FieldType::Map(_, key_value, ..), Expression::Map(entries, _)) => entries
+            .iter()
+            .all(|(_, value)| default_fits(value, &key_value.1)),
+        _ => false,
+    }
+}
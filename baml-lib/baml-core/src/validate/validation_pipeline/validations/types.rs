@@ -146,6 +146,9 @@ fn validate_type_constraints(ctx: &mut Context<'_>, field_type: &FieldType) {
                     internal_baml_jinja_types::JinjaContext::Parsing,
                 );
                 defined_types.add_variable("this", internal_baml_jinja_types::Type::Unknown);
+                for constant in ctx.db.walk_constants() {
+                    defined_types.add_variable(constant.name(), constant.jinja_type());
+                }
                 match internal_baml_jinja_types::validate_expression(&expr.0, &mut defined_types) {
                     Ok(_) => {}
                     Err(e) => {
@@ -196,6 +199,9 @@ fn validate_type_constraints(ctx: &mut Context<'_>, field_type: &FieldType) {
                     internal_baml_jinja_types::JinjaContext::Parsing,
                 );
                 defined_types.add_variable("this", internal_baml_jinja_types::Type::Unknown);
+                for constant in ctx.db.walk_constants() {
+                    defined_types.add_variable(constant.name(), constant.jinja_type());
+                }
                 match internal_baml_jinja_types::validate_expression(&expr.0, &mut defined_types) {
                     Ok(_) => {}
                     Err(e) => {
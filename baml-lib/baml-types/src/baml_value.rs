@@ -2,8 +2,10 @@ use std::collections::HashMap;
 use std::{
     collections::{HashSet, VecDeque},
     fmt,
+    str::FromStr,
 };
 
+use base64::{engine::general_purpose::STANDARD, Engine as _};
 use serde::ser::SerializeMap;
 use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
 
@@ -15,7 +17,12 @@ pub enum BamlValue {
     String(String),
     Int(i64),
     Float(f64),
+    /// A number kept as its exact decimal text (e.g. `12345678901234567890` or
+    /// `0.12345678901234567890`) because it does not fit an `i64` or `f64` without loss.
+    Decimal(String),
     Bool(bool),
+    /// Binary data, serialized as a base64 string.
+    Bytes(Vec<u8>),
     Map(BamlMap<String, BamlValue>),
     List(Vec<BamlValue>),
     Media(BamlMedia),
@@ -30,7 +37,9 @@ impl serde::Serialize for BamlValue {
             BamlValue::String(s) => serializer.serialize_str(s),
             BamlValue::Int(i) => serializer.serialize_i64(*i),
             BamlValue::Float(f) => serializer.serialize_f64(*f),
+            BamlValue::Decimal(d) => serialize_decimal(d, serializer),
             BamlValue::Bool(b) => serializer.serialize_bool(*b),
+            BamlValue::Bytes(b) => serializer.serialize_str(&STANDARD.encode(b)),
             BamlValue::Map(m) => m.serialize(serializer),
             BamlValue::List(l) => l.serialize(serializer),
             BamlValue::Media(m) => {
@@ -69,7 +78,9 @@ impl BamlValue {
             BamlValue::String(_) => "string".into(),
             BamlValue::Int(_) => "int".into(),
             BamlValue::Float(_) => "float".into(),
+            BamlValue::Decimal(_) => "decimal".into(),
             BamlValue::Bool(_) => "bool".into(),
+            BamlValue::Bytes(_) => "bytes".into(),
             BamlValue::Map(kv) => {
                 let value_types = kv
                     .values()
@@ -123,6 +134,16 @@ impl BamlValue {
         }
     }
 
+    /// The exact decimal text of an `Int`, `Float` or `Decimal`.
+    pub fn as_decimal_str(&self) -> Option<std::borrow::Cow<'_, str>> {
+        match self {
+            BamlValue::Int(i) => Some(i.to_string().into()),
+            BamlValue::Float(f) => Some(f.to_string().into()),
+            BamlValue::Decimal(d) => Some(d.as_str().into()),
+            _ => None,
+        }
+    }
+
     pub fn as_str(&self) -> Option<&str> {
         match self {
             BamlValue::String(s) => Some(s),
@@ -359,7 +380,9 @@ pub enum BamlValueWithMeta<T> {
     String(String, T),
     Int(i64, T),
     Float(f64, T),
+    Decimal(String, T),
     Bool(bool, T),
+    Bytes(Vec<u8>, T),
     Map(BamlMap<String, BamlValueWithMeta<T>>, T),
     List(Vec<BamlValueWithMeta<T>>, T),
     Media(BamlMedia, T),
@@ -385,7 +408,9 @@ impl<T> BamlValueWithMeta<T> {
             BamlValueWithMeta::String(v, _) => BamlValue::String(v),
             BamlValueWithMeta::Int(v, _) => BamlValue::Int(v),
             BamlValueWithMeta::Float(v, _) => BamlValue::Float(v),
+            BamlValueWithMeta::Decimal(v, _) => BamlValue::Decimal(v),
             BamlValueWithMeta::Bool(v, _) => BamlValue::Bool(v),
+            BamlValueWithMeta::Bytes(v, _) => BamlValue::Bytes(v),
             BamlValueWithMeta::Map(v, _) => {
                 BamlValue::Map(v.into_iter().map(|(k, v)| (k, v.value())).collect())
             }
@@ -406,7 +431,9 @@ impl<T> BamlValueWithMeta<T> {
             BamlValueWithMeta::String(_, m) => m,
             BamlValueWithMeta::Int(_, m) => m,
             BamlValueWithMeta::Float(_, m) => m,
+            BamlValueWithMeta::Decimal(_, m) => m,
             BamlValueWithMeta::Bool(_, m) => m,
+            BamlValueWithMeta::Bytes(_, m) => m,
             BamlValueWithMeta::Map(_, m) => m,
             BamlValueWithMeta::List(_, m) => m,
             BamlValueWithMeta::Media(_, m) => m,
@@ -421,7 +448,9 @@ impl<T> BamlValueWithMeta<T> {
             BamlValueWithMeta::String(_, m) => m,
             BamlValueWithMeta::Int(_, m) => m,
             BamlValueWithMeta::Float(_, m) => m,
+            BamlValueWithMeta::Decimal(_, m) => m,
             BamlValueWithMeta::Bool(_, m) => m,
+            BamlValueWithMeta::Bytes(_, m) => m,
             BamlValueWithMeta::Map(_, m) => m,
             BamlValueWithMeta::List(_, m) => m,
             BamlValueWithMeta::Media(_, m) => m,
@@ -440,7 +469,9 @@ impl<T> BamlValueWithMeta<T> {
             BamlValue::String(s) => String(s.clone(), T::default()),
             BamlValue::Int(i) => Int(*i, T::default()),
             BamlValue::Float(f) => Float(*f, T::default()),
+            BamlValue::Decimal(d) => Decimal(d.clone(), T::default()),
             BamlValue::Bool(b) => Bool(*b, T::default()),
+            BamlValue::Bytes(b) => Bytes(b.clone(), T::default()),
             BamlValue::Map(entries) => BamlValueWithMeta::Map(
                 entries
                     .iter()
@@ -473,7 +504,9 @@ impl<T> BamlValueWithMeta<T> {
             BamlValueWithMeta::String(v, m) => BamlValueWithMeta::String(v.clone(), f(m)),
             BamlValueWithMeta::Int(v, m) => BamlValueWithMeta::Int(*v, f(m)),
             BamlValueWithMeta::Float(v, m) => BamlValueWithMeta::Float(*v, f(m)),
+            BamlValueWithMeta::Decimal(v, m) => BamlValueWithMeta::Decimal(v.clone(), f(m)),
             BamlValueWithMeta::Bool(v, m) => BamlValueWithMeta::Bool(*v, f(m)),
+            BamlValueWithMeta::Bytes(v, m) => BamlValueWithMeta::Bytes(v.clone(), f(m)),
             BamlValueWithMeta::Map(v, m) => BamlValueWithMeta::Map(
                 v.iter().map(|(k, v)| (k.clone(), v.map_meta(f))).collect(),
                 f(m),
@@ -535,7 +568,9 @@ impl<'a, T: 'a> Iterator for BamlValueWithMetaIterator<'a, T> {
                 BamlValueWithMeta::String(..)
                 | BamlValueWithMeta::Int(..)
                 | BamlValueWithMeta::Float(..)
+                | BamlValueWithMeta::Decimal(..)
                 | BamlValueWithMeta::Bool(..)
+                | BamlValueWithMeta::Bytes(..)
                 | BamlValueWithMeta::Media(..)
                 | BamlValueWithMeta::Enum(..)
                 | BamlValueWithMeta::Null(..) => {}
@@ -563,7 +598,9 @@ impl<T> From<&BamlValueWithMeta<T>> for BamlValue {
             String(v, _) => BamlValue::String(v.clone()),
             Int(v, _) => BamlValue::Int(*v),
             Float(v, _) => BamlValue::Float(*v),
+            Decimal(v, _) => BamlValue::Decimal(v.clone()),
             Bool(v, _) => BamlValue::Bool(*v),
+            Bytes(v, _) => BamlValue::Bytes(v.clone()),
             Map(v, _) => {
                 BamlValue::Map(v.into_iter().map(|(k, v)| (k.clone(), v.into())).collect())
             }
@@ -586,7 +623,9 @@ impl<T> From<BamlValueWithMeta<T>> for BamlValue {
             String(v, _) => BamlValue::String(v),
             Int(v, _) => BamlValue::Int(v),
             Float(v, _) => BamlValue::Float(v),
+            Decimal(v, _) => BamlValue::Decimal(v),
             Bool(v, _) => BamlValue::Bool(v),
+            Bytes(v, _) => BamlValue::Bytes(v),
             Map(v, _) => BamlValue::Map(v.into_iter().map(|(k, v)| (k, v.into())).collect()),
             List(v, _) => BamlValue::List(v.into_iter().map(|v| v.into()).collect()),
             Media(v, _) => BamlValue::Media(v),
@@ -612,7 +651,14 @@ impl Serialize for BamlValueWithMeta<Vec<ResponseCheck>> {
             BamlValueWithMeta::String(v, cr) => serialize_with_checks(v, cr, serializer),
             BamlValueWithMeta::Int(v, cr) => serialize_with_checks(v, cr, serializer),
             BamlValueWithMeta::Float(v, cr) => serialize_with_checks(v, cr, serializer),
+            BamlValueWithMeta::Decimal(v, cr) => match serde_json::Number::from_str(v) {
+                Ok(n) => serialize_with_checks(&n, cr, serializer),
+                Err(_) => serialize_with_checks(v, cr, serializer),
+            },
             BamlValueWithMeta::Bool(v, cr) => serialize_with_checks(v, cr, serializer),
+            BamlValueWithMeta::Bytes(v, cr) => {
+                serialize_with_checks(&STANDARD.encode(v), cr, serializer)
+            }
             BamlValueWithMeta::Map(v, cr) => {
                 let mut map = serializer.serialize_map(None)?;
                 for (key, value) in v {
@@ -644,6 +690,15 @@ impl Serialize for BamlValueWithMeta<Vec<ResponseCheck>> {
     }
 }
 
+/// Serializes `decimal` as a JSON number with all of its digits (serde_json's
+/// `arbitrary_precision`), or as a string if it is not a valid number.
+fn serialize_decimal<S: Serializer>(decimal: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    match serde_json::Number::from_str(decimal) {
+        Ok(n) => n.serialize(serializer),
+        Err(_) => serializer.serialize_str(decimal),
+    }
+}
+
 fn serialize_with_checks<S, T: Serialize>(
     value: &T,
     checks: &[ResponseCheck],
@@ -29,6 +29,10 @@ impl FieldType {
         FieldType::Primitive(TypeValue::Bool)
     }
 
+    pub fn bytes() -> Self {
+        FieldType::Primitive(TypeValue::Bytes)
+    }
+
     pub fn null() -> Self {
         FieldType::Primitive(TypeValue::Null)
     }
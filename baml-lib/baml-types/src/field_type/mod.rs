@@ -9,6 +9,8 @@ pub enum TypeValue {
     Int,
     Float,
     Bool,
+    /// Binary data, written by the model as base64.
+    Bytes,
     // Char,
     Null,
     Media(BamlMediaType),
@@ -23,6 +25,7 @@ impl std::str::FromStr for TypeValue {
             "int" => TypeValue::Int,
             "float" => TypeValue::Float,
             "bool" => TypeValue::Bool,
+            "bytes" => TypeValue::Bytes,
             "null" => TypeValue::Null,
             "image" => TypeValue::Media(BamlMediaType::Image),
             "audio" => TypeValue::Media(BamlMediaType::Audio),
@@ -38,6 +41,7 @@ impl std::fmt::Display for TypeValue {
             TypeValue::Int => write!(f, "int"),
             TypeValue::Float => write!(f, "float"),
             TypeValue::Bool => write!(f, "bool"),
+            TypeValue::Bytes => write!(f, "bytes"),
             TypeValue::Null => write!(f, "null"),
             TypeValue::Media(BamlMediaType::Image) => write!(f, "image"),
             TypeValue::Media(BamlMediaType::Audio) => write!(f, "audio"),
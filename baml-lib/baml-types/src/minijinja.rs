@@ -19,7 +19,16 @@ impl From<BamlValue> for minijinja::Value {
             BamlValue::String(s) => minijinja::Value::from(s),
             BamlValue::Int(n) => minijinja::Value::from(n),
             BamlValue::Float(n) => minijinja::Value::from(n),
+            // Templates only need an approximation beyond `i128`.
+            BamlValue::Decimal(d) => match d.parse::<i128>() {
+                Ok(n) => minijinja::Value::from(n),
+                Err(_) => d
+                    .parse::<f64>()
+                    .map(minijinja::Value::from)
+                    .unwrap_or_else(|_| minijinja::Value::from(d)),
+            },
             BamlValue::Bool(b) => minijinja::Value::from(b),
+            BamlValue::Bytes(b) => minijinja::Value::from(b.as_slice()),
             BamlValue::Map(m) => {
                 let map = m.into_iter().map(|(k, v)| (k, minijinja::Value::from(v)));
                 minijinja::Value::from_iter(map)
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
 use std::{
     collections::{HashMap, HashSet},
     str::FromStr,
@@ -7,7 +8,7 @@ use std::{
 use crate::JinjaExpression;
 use indexmap::{IndexMap, IndexSet};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Resolvable<Id, Meta> {
     // Enums go into here.
     String(Id, Meta),
@@ -154,7 +155,10 @@ impl StringOr {
         match self {
             Self::EnvVar(name) => HashSet::from([name.clone()]),
             Self::Value(_) => HashSet::new(),
-            Self::JinjaExpression(_) => HashSet::new(),
+            Self::JinjaExpression(expression) => referenced_env_vars(&expression.0)
+                .into_iter()
+                .map(String::from)
+                .collect(),
         }
     }
 
@@ -262,11 +266,40 @@ impl StringOr {
         match self {
             Self::EnvVar(name) => ctx.get_env_var(name),
             Self::Value(value) => Ok(value.to_string()),
-            Self::JinjaExpression(_) => todo!("Jinja expressions cannot yet be resolved"),
+            Self::JinjaExpression(expression) => resolve_jinja_expression(expression, ctx),
         }
     }
 }
 
+/// Evaluate a Jinja expression such as `{{ env.PREFIX ~ "name" }}`, reading `env.NAME` through
+/// `ctx` so that missing environment variables behave as they do for `env.NAME` values.
+fn resolve_jinja_expression(expression: &JinjaExpression, ctx: &impl GetEnvVar) -> Result<String> {
+    let env_vars = referenced_env_vars(&expression.0)
+        .into_iter()
+        .map(|name| Ok((name.to_string(), ctx.get_env_var(name)?)))
+        .collect::<Result<HashMap<_, _>>>()?;
+    let mut env = minijinja::Environment::new();
+    env.set_undefined_behavior(minijinja::UndefinedBehavior::Strict);
+    let template = format!("{{{{ {} }}}}", expression.0);
+    let args = minijinja::Value::from_serialize(HashMap::from([("env", env_vars)]));
+    env.render_str(&template, args)
+        .map_err(|e| anyhow::anyhow!("Failed to evaluate `{{{{ {} }}}}`: {e}", expression.0))
+}
+
+/// The environment variables a Jinja expression reads as `env.NAME`.
+fn referenced_env_vars(expression: &str) -> HashSet<&str> {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    expression
+        .match_indices("env.")
+        .filter(|(i, _)| !expression[..*i].ends_with(|c: char| is_ident(c) || c == '.'))
+        .map(|(i, m)| {
+            let name = &expression[i + m.len()..];
+            &name[..name.find(|c: char| !is_ident(c)).unwrap_or(name.len())]
+        })
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
 impl<Meta> UnresolvedValue<Meta> {
     pub fn as_static_str(&self) -> Result<&str> {
         match self {
@@ -286,43 +319,43 @@ impl<Meta> UnresolvedValue<Meta> {
     }
 
     pub fn resolve_string(&self, ctx: &impl GetEnvVar) -> Result<String> {
-        match self.resolve(ctx) {
-            Ok(ResolvedValue::String(s, ..)) => Ok(s),
+        match self.resolve(ctx)? {
+            ResolvedValue::String(s, ..) => Ok(s),
             _ => Err(anyhow::anyhow!("Expected a string")),
         }
     }
 
     pub fn resolve_bool(&self, ctx: &impl GetEnvVar) -> Result<bool> {
-        match self.resolve(ctx) {
-            Ok(ResolvedValue::Bool(b, ..)) => Ok(b),
+        match self.resolve(ctx)? {
+            ResolvedValue::Bool(b, ..) => Ok(b),
             _ => Err(anyhow::anyhow!("Expected a boolean")),
         }
     }
 
     pub fn resolve_array(&self, ctx: &impl GetEnvVar) -> Result<Vec<ResolvedValue>> {
-        match self.resolve(ctx) {
-            Ok(ResolvedValue::Array(a, ..)) => Ok(a),
+        match self.resolve(ctx)? {
+            ResolvedValue::Array(a, ..) => Ok(a),
             _ => Err(anyhow::anyhow!("Expected an array")),
         }
     }
 
     pub fn resolve_map(&self, ctx: &impl GetEnvVar) -> Result<IndexMap<String, ResolvedValue>> {
-        match self.resolve(ctx) {
-            Ok(ResolvedValue::Map(m, ..)) => Ok(m.into_iter().map(|(k, (_, v))| (k, v)).collect()),
+        match self.resolve(ctx)? {
+            ResolvedValue::Map(m, ..) => Ok(m.into_iter().map(|(k, (_, v))| (k, v)).collect()),
             _ => Err(anyhow::anyhow!("Expected a map")),
         }
     }
 
     pub fn resolve_numeric(&self, ctx: &impl GetEnvVar) -> Result<String> {
-        match self.resolve(ctx) {
-            Ok(ResolvedValue::Numeric(n, ..)) => Ok(n),
+        match self.resolve(ctx)? {
+            ResolvedValue::Numeric(n, ..) => Ok(n),
             _ => Err(anyhow::anyhow!("Expected a numeric value")),
         }
     }
 
     pub fn resolve_null(&self, ctx: &impl GetEnvVar) -> Result<()> {
-        match self.resolve(ctx) {
-            Ok(ResolvedValue::Null(..)) => Ok(()),
+        match self.resolve(ctx)? {
+            ResolvedValue::Null(..) => Ok(()),
             _ => Err(anyhow::anyhow!("Expected a null value")),
         }
     }
@@ -419,7 +452,11 @@ impl crate::BamlValue {
             }
             crate::BamlValue::Int(i) => Resolvable::Numeric(i.to_string(), ()),
             crate::BamlValue::Float(f) => Resolvable::Numeric(f.to_string(), ()),
+            crate::BamlValue::Decimal(d) => Resolvable::Numeric(d.clone(), ()),
             crate::BamlValue::Bool(b) => Resolvable::Bool(*b, ()),
+            crate::BamlValue::Bytes(b) => {
+                Resolvable::String(StringOr::Value(STANDARD.encode(b)), ())
+            }
             crate::BamlValue::Class(_, index_map) | crate::BamlValue::Map(index_map) => {
                 let values = index_map
                     .iter()
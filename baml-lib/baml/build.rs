@@ -1,4 +1,11 @@
 fn main() {
-    // Uncomment this line if you need to build for Python
+    // Only needed when building the Python extension module.
+    #[cfg(feature = "python")]
     pyo3_build_config::add_extension_module_link_args();
+
+    #[cfg(feature = "grpc")]
+    {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path().unwrap());
+        tonic_build::compile_protos("proto/baml_lib.proto").unwrap();
+    }
 }
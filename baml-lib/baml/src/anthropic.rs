@@ -0,0 +1,63 @@
+//! Renderers for Anthropic models: tool definitions and XML-tagged prompts.
+
+use baml_types::FieldType;
+use internal_baml_jinja::types::RenderOptions;
+use serde_json::{json, Value};
+
+use crate::BamlContext;
+
+/// The tags of [`BamlContext::render_xml_prompt`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlTags {
+    /// Wraps the rendered schema.
+    pub schema: String,
+    /// The model is asked to answer inside this tag.
+    pub answer: String,
+}
+
+impl Default for XmlTags {
+    fn default() -> Self {
+        Self {
+            schema: "output_format".to_string(),
+            answer: "answer".to_string(),
+        }
+    }
+}
+
+impl BamlContext {
+    /// An Anthropic tool definition whose `input_schema` is the target class, see
+    /// [`json_schema`](Self::json_schema). Validate the `input` of the `tool_use` block the
+    /// model returns, serialized to JSON, with [`validate_result`](Self::validate_result).
+    pub fn anthropic_tool(&self, name: &str, description: Option<&str>) -> anyhow::Result<Value> {
+        if !matches!(self.format.target, FieldType::Class(_)) {
+            anyhow::bail!(
+                "Tool inputs must be objects, so the target must be a class, not `{}`",
+                self.format.target
+            );
+        }
+        let mut tool = json!({
+            "name": name,
+            "input_schema": self.json_schema(false)?,
+        });
+        if let Some(description) = description {
+            tool["description"] = json!(description);
+        }
+        Ok(tool)
+    }
+
+    /// The schema in `tags.schema` tags, followed by the instruction to answer in `tags.answer`
+    /// tags, as Anthropic recommends for structuring prompts. Outputs that wrap the answer in
+    /// the tags validate as usual.
+    pub fn render_xml_prompt(&self, tags: &XmlTags) -> anyhow::Result<String> {
+        let schema = self.render_with(
+            RenderOptions::new(Some(None), None, None, None, None, None)
+                .with_field_order(self.field_order)
+                .with_style(self.render_style)
+                .with_format(self.render_format),
+        )?;
+        let XmlTags { schema: schema_tag, answer } = tags;
+        Ok(format!(
+            "<{schema_tag}>\n{schema}\n</{schema_tag}>\n\nAnswer inside <{answer}></{answer}> tags, following <{schema_tag}>."
+        ))
+    }
+}
@@ -0,0 +1,295 @@
+//! Re-validating archives of stored model outputs, e.g. whenever the schema changes.
+//!
+//! Outputs are read from JSONL one line at a time and results are returned as they are
+//! produced, so archives of any size can be processed in constant memory. Results can be
+//! collected into a [`ValidationReport`] for evaluation dashboards.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    io::BufRead,
+    time::{Duration, Instant},
+};
+
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::BamlContext;
+
+/// Lines read ahead and validated together in parallel mode.
+const PARALLEL_CHUNK_LINES: usize = 1024;
+
+/// Options for [`revalidate_jsonl`].
+#[derive(Debug, Clone, Default)]
+pub struct BatchOptions {
+    /// Validate the outputs as partial (streamed) results.
+    pub allow_partials: bool,
+    /// Read the output from this field of each line, which must then be a JSON object.
+    /// Otherwise a line holding a JSON string is that string, and any other line is the output
+    /// itself.
+    pub output_field: Option<String>,
+    /// Validate chunks of lines on the rayon thread pool. Results keep the order of the lines.
+    pub parallel: bool,
+}
+
+/// The result of validating one line of the archive.
+#[derive(Debug)]
+pub struct LineResult {
+    /// 1-based line number in the archive.
+    pub line: usize,
+    /// The validated output as JSON, or why the line could not be read or validated.
+    pub result: anyhow::Result<String>,
+    /// Number of coercion flags raised, by flag kind. Empty when validation failed.
+    pub flag_counts: BTreeMap<String, usize>,
+    /// Time spent reading and validating the line.
+    pub duration: Duration,
+}
+
+impl LineResult {
+    /// The paths of the fields (`<root>.fruit.0.price`) that could not be coerced, `<root>`
+    /// when the line could not be read or the output was not parsed at all, and nothing on
+    /// success.
+    pub fn error_fields(&self) -> Vec<String> {
+        let Err(error) = &self.result else {
+            return Vec::new();
+        };
+        let Some(error) = error.downcast_ref::<jsonish::CoerceError>() else {
+            return vec!["<root>".to_string()];
+        };
+        error
+            .0
+            .leaf_paths()
+            .into_iter()
+            .map(|path| {
+                std::iter::once("<root>")
+                    .chain(path.iter().map(String::as_str))
+                    .collect::<Vec<_>>()
+                    .join(".")
+            })
+            .collect()
+    }
+}
+
+/// Validates every stored output of the JSONL `reader` against `context`, yielding one result
+/// per non-empty line, in order.
+pub fn revalidate_jsonl<'a, R: BufRead + 'a>(
+    reader: R,
+    context: &'a BamlContext,
+    options: &'a BatchOptions,
+) -> impl Iterator<Item = LineResult> + 'a {
+    Revalidation {
+        lines: reader.lines().enumerate(),
+        context,
+        options,
+        pending: VecDeque::new(),
+    }
+}
+
+struct Revalidation<'a, L> {
+    lines: L,
+    context: &'a BamlContext,
+    options: &'a BatchOptions,
+    pending: VecDeque<LineResult>,
+}
+
+impl<L: Iterator<Item = (usize, std::io::Result<String>)>> Iterator for Revalidation<'_, L> {
+    type Item = LineResult;
+
+    fn next(&mut self) -> Option<LineResult> {
+        if let Some(result) = self.pending.pop_front() {
+            return Some(result);
+        }
+        let (context, options) = (self.context, self.options);
+        if !options.parallel {
+            let (index, line) = self.lines.find(|(_, line)| !is_blank(line))?;
+            return Some(validate_line(context, options, index + 1, line));
+        }
+        let chunk = self
+            .lines
+            .by_ref()
+            .filter(|(_, line)| !is_blank(line))
+            .take(PARALLEL_CHUNK_LINES)
+            .collect::<Vec<_>>();
+        self.pending = chunk
+            .into_par_iter()
+            .map(|(index, line)| validate_line(context, options, index + 1, line))
+            .collect::<Vec<_>>()
+            .into();
+        self.pending.pop_front()
+    }
+}
+
+fn validate_line(
+    context: &BamlContext,
+    options: &BatchOptions,
+    line: usize,
+    text: std::io::Result<String>,
+) -> LineResult {
+    let start = Instant::now();
+    let result = text.map_err(anyhow::Error::from).and_then(|text| {
+        let output = stored_output(&text, options.output_field.as_deref())?;
+        context.validate_result_counting_flags(&output, options.allow_partials)
+    });
+    let (result, flag_counts) = match result {
+        Ok((json, flag_counts)) => (Ok(json), flag_counts),
+        Err(e) => (Err(e), BTreeMap::new()),
+    };
+    LineResult {
+        line,
+        result,
+        flag_counts,
+        duration: start.elapsed(),
+    }
+}
+
+fn is_blank(line: &std::io::Result<String>) -> bool {
+    line.as_ref().is_ok_and(|line| line.trim().is_empty())
+}
+
+/// The model output stored on a line, see [`BatchOptions::output_field`].
+fn stored_output(line: &str, output_field: Option<&str>) -> anyhow::Result<String> {
+    let Some(field) = output_field else {
+        return Ok(match serde_json::from_str::<serde_json::Value>(line) {
+            Ok(serde_json::Value::String(output)) => output,
+            _ => line.to_string(),
+        });
+    };
+    let record = serde_json::from_str::<serde_json::Value>(line)
+        .map_err(|e| anyhow::anyhow!("Line is not a JSON object: {e}"))?;
+    match record.get(field) {
+        Some(serde_json::Value::String(output)) => Ok(output.clone()),
+        Some(value) => Ok(value.to_string()),
+        None => Err(anyhow::anyhow!("Line has no `{field}` field")),
+    }
+}
+
+/// A machine-readable summary of a batch validation, built by collecting [`LineResult`]s:
+/// `revalidate_jsonl(..).collect::<ValidationReport>()`.
+///
+/// The JSON and CSV layouts are stable: fields and columns may be added, but not renamed or
+/// removed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ValidationReport {
+    pub items: Vec<ItemReport>,
+    pub successes: usize,
+    pub failures: usize,
+    /// Number of coercion flags raised over all items, by flag kind.
+    pub flag_counts: BTreeMap<String, usize>,
+    /// Number of failed items, by field path that could not be coerced. An item failing on
+    /// several fields counts once for each.
+    pub errors_by_field: BTreeMap<String, usize>,
+    /// Sum of the items' validation latencies.
+    pub total_duration_us: u64,
+}
+
+/// One line of a [`ValidationReport`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemReport {
+    /// 1-based line number in the archive.
+    pub line: usize,
+    pub status: ItemStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// See [`LineResult::error_fields`].
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub error_fields: Vec<String>,
+    pub flag_counts: BTreeMap<String, usize>,
+    pub duration_us: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemStatus {
+    Success,
+    Failure,
+}
+
+impl ItemStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            ItemStatus::Success => "success",
+            ItemStatus::Failure => "failure",
+        }
+    }
+}
+
+impl ValidationReport {
+    /// Adds the result of one line.
+    pub fn add(&mut self, result: &LineResult) {
+        let error_fields = result.error_fields();
+        let status = match &result.result {
+            Ok(_) => {
+                self.successes += 1;
+                ItemStatus::Success
+            }
+            Err(_) => {
+                self.failures += 1;
+                ItemStatus::Failure
+            }
+        };
+        for (kind, count) in &result.flag_counts {
+            *self.flag_counts.entry(kind.clone()).or_default() += count;
+        }
+        for field in &error_fields {
+            *self.errors_by_field.entry(field.clone()).or_default() += 1;
+        }
+        let duration_us = result.duration.as_micros() as u64;
+        self.total_duration_us += duration_us;
+        self.items.push(ItemReport {
+            line: result.line,
+            status,
+            error: result.result.as_ref().err().map(|e| e.to_string()),
+            error_fields,
+            flag_counts: result.flag_counts.clone(),
+            duration_us,
+        });
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("reports serialize to JSON")
+    }
+
+    /// One row per item with the columns `line,status,duration_us,flags,error_fields,error`.
+    /// `flags` holds `Kind=count` pairs and `error_fields` the paths, both separated by `;`.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("line,status,duration_us,flags,error_fields,error\n");
+        for item in &self.items {
+            let flags = item
+                .flag_counts
+                .iter()
+                .map(|(kind, count)| format!("{kind}={count}"))
+                .collect::<Vec<_>>()
+                .join(";");
+            let row = [
+                item.line.to_string(),
+                item.status.as_str().to_string(),
+                item.duration_us.to_string(),
+                flags,
+                item.error_fields.join(";"),
+                item.error.clone().unwrap_or_default(),
+            ];
+            let row = row.iter().map(|cell| csv_cell(cell)).collect::<Vec<_>>();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+impl FromIterator<LineResult> for ValidationReport {
+    fn from_iter<I: IntoIterator<Item = LineResult>>(results: I) -> Self {
+        let mut report = ValidationReport::default();
+        for result in results {
+            report.add(&result);
+        }
+        report
+    }
+}
+
+/// Quotes `cell` if it holds a comma, quote or line break (RFC 4180).
+fn csv_cell(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
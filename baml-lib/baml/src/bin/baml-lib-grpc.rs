@@ -0,0 +1,16 @@
+//! gRPC service for rendering prompts and validating LLM output.
+//!
+//! Built with `cargo build -p baml-lib --features grpc`. Listens on
+//! `BAML_LIB_ADDR` (default `0.0.0.0:50051`).
+
+use std::net::SocketAddr;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let addr: SocketAddr = std::env::var("BAML_LIB_ADDR")
+        .unwrap_or_else(|_| "0.0.0.0:50051".to_string())
+        .parse()?;
+    eprintln!("baml-lib-grpc listening on {addr}");
+    baml_lib::grpc::serve(addr).await?;
+    Ok(())
+}
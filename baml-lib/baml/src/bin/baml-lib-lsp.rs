@@ -0,0 +1,8 @@
+//! Language server for BAML schemas, speaking LSP over stdin/stdout.
+//!
+//! Built with `cargo build -p baml-lib --features lsp`.
+
+#[tokio::main]
+async fn main() {
+    baml_lib::lsp::serve_stdio().await;
+}
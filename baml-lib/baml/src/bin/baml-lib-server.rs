@@ -0,0 +1,16 @@
+//! HTTP service for rendering prompts and validating LLM output.
+//!
+//! Built with `cargo build -p baml-lib --features server`. Listens on
+//! `BAML_LIB_ADDR` (default `0.0.0.0:8080`).
+
+use std::net::SocketAddr;
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let addr: SocketAddr = std::env::var("BAML_LIB_ADDR")
+        .unwrap_or_else(|_| "0.0.0.0:8080".to_string())
+        .parse()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    eprintln!("baml-lib-server listening on {addr}");
+    baml_lib::server::serve(addr).await
+}
@@ -0,0 +1,383 @@
+//! Command-line access to schema validation, prompt rendering and output parsing.
+//!
+//! Built with `cargo build -p baml-lib --features cli`.
+
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use anyhow::Context;
+use baml_lib::{
+    internal_baml_core::{
+        self,
+        internal_baml_schema_ast::{format_schema, FormatOptions},
+        ir::{repr::IntermediateRepr, WithJsonSchema},
+    },
+    capture::Fixture,
+    suggestions::{analyze, Thresholds},
+    from_json_schema, sse_deltas, BamlContext, DecodingBackend, FieldOrder, RenderStyle,
+    ReservedNamePolicy, SchemaOptions, SourceFile, ValidatedSchema,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+#[derive(Parser)]
+#[command(
+    name = "baml-lib",
+    version,
+    about = "BAML schema validation and output parsing"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Validate schema files and print diagnostics.
+    Validate {
+        /// BAML schema files.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// Also warn about patterns that bloat the rendered prompt.
+        #[arg(long)]
+        suggestions: bool,
+        #[command(flatten)]
+        reserved_names: ReservedNameArgs,
+    },
+    /// Render the output format prompt for a target type.
+    RenderPrompt {
+        #[command(flatten)]
+        schema: SchemaArgs,
+        /// Use this prefix instead of the default one.
+        #[arg(long)]
+        prefix: Option<String>,
+        /// Always render enums separately instead of inline.
+        #[arg(long)]
+        always_hoist_enums: bool,
+        /// Order of class fields: declaration, alphabetical or required_first.
+        #[arg(long, default_value = "declaration")]
+        field_order: FieldOrder,
+        /// Layout of the schema: standard or compact.
+        #[arg(long, default_value = "standard")]
+        style: RenderStyle,
+    },
+    /// Parse an LLM output against a target type and print the result as JSON.
+    CheckOutput {
+        #[command(flatten)]
+        schema: SchemaArgs,
+        /// File containing the LLM output. Reads stdin when omitted.
+        output: Option<PathBuf>,
+        /// Accept outputs that do not fill every required field.
+        #[arg(long)]
+        allow_partials: bool,
+        /// Print class fields in declaration order instead of the order the model wrote them.
+        #[arg(long)]
+        canonical_field_order: bool,
+        /// Return values of unions such as `string | string[]` as lists, wrapping single values.
+        #[arg(long)]
+        normalize_lists: bool,
+        /// The output is a captured server-sent events stream: validate the text of its deltas.
+        #[arg(long)]
+        sse: bool,
+        /// Validate each JSON document (e.g. JSONL line) separately and print one result per line.
+        #[arg(long)]
+        documents: bool,
+        /// Print how the output was coerced (candidates, scores, flags, checks) instead of the
+        /// result.
+        #[arg(long, value_name = "FORMAT")]
+        trace: Option<TraceFormat>,
+        /// Also write a fixture reproducing the validation to this file, to attach to bug
+        /// reports. It embeds the schema and output.
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["documents", "trace"])]
+        capture: Option<PathBuf>,
+    },
+    /// Print the target type as a response format for constrained decoding.
+    ResponseFormat {
+        #[command(flatten)]
+        schema: SchemaArgs,
+        /// Where the response format is sent: openai, vllm or tgi.
+        #[arg(long, default_value = "openai")]
+        backend: DecodingBackend,
+    },
+    /// Validate the output of a captured fixture again and check the result has not changed.
+    Replay {
+        /// Fixture written by `check-output --capture`.
+        fixture: PathBuf,
+    },
+    /// Print BAML definitions equivalent to a JSON Schema.
+    ImportJsonSchema {
+        /// JSON Schema file.
+        file: PathBuf,
+    },
+    /// Format schema files in place.
+    Fmt {
+        /// BAML schema files.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// Do not write, exit with an error if any file is not formatted.
+        #[arg(long)]
+        check: bool,
+    },
+    /// Print the intermediate representation of a schema.
+    Ir {
+        /// BAML schema files.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// Print the IR as JSON schema definitions instead of the debug dump.
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TraceFormat {
+    Text,
+    Html,
+    Json,
+}
+
+#[derive(Args)]
+struct SchemaArgs {
+    /// BAML schema file.
+    schema: PathBuf,
+    /// Target class or enum. Defaults to the first class in the schema.
+    #[arg(long, short)]
+    target: Option<String>,
+    /// Use `///` doc comments as descriptions where `@description` is absent.
+    #[arg(long)]
+    doc_comment_descriptions: bool,
+    #[command(flatten)]
+    reserved_names: ReservedNameArgs,
+}
+
+#[derive(Args)]
+struct ReservedNameArgs {
+    /// Also reserve this name (repeatable).
+    #[arg(long = "reserve-name", value_name = "NAME")]
+    reserve: Vec<String>,
+    /// Allow this name although it is reserved by default (repeatable).
+    #[arg(long = "allow-reserved-name", value_name = "NAME")]
+    allow: Vec<String>,
+}
+
+impl ReservedNameArgs {
+    fn policy(&self) -> ReservedNamePolicy {
+        ReservedNamePolicy {
+            reserve: self.reserve.clone(),
+            allow: self.allow.clone(),
+        }
+    }
+}
+
+impl SchemaArgs {
+    fn load(&self) -> anyhow::Result<BamlContext> {
+        let schema_string = read_file(&self.schema)?;
+        let options = SchemaOptions {
+            doc_comment_descriptions: self.doc_comment_descriptions,
+            reserved_names: self.reserved_names.policy(),
+            source_name: Some(self.schema.display().to_string()),
+            ..Default::default()
+        };
+        BamlContext::try_from_schema_with_options(&schema_string, self.target.clone(), &options)
+    }
+}
+
+fn read_file(path: &Path) -> anyhow::Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+fn validate_files(
+    files: &[PathBuf],
+    reserved_names: ReservedNamePolicy,
+) -> anyhow::Result<ValidatedSchema> {
+    let sources = files
+        .iter()
+        .map(|path| Ok(SourceFile::from((path.clone(), read_file(path)?))))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let root_path = files
+        .first()
+        .and_then(|f| f.parent())
+        .unwrap_or(Path::new("."));
+    Ok(internal_baml_core::validate_with_reserved_names(
+        root_path,
+        sources,
+        reserved_names,
+    ))
+}
+
+fn run(command: Command) -> anyhow::Result<ExitCode> {
+    match command {
+        Command::Validate {
+            files,
+            suggestions,
+            reserved_names,
+        } => {
+            let mut validated = validate_files(&files, reserved_names.policy())?;
+            if suggestions && !validated.diagnostics.has_errors() {
+                for suggestion in analyze(&validated.db, &Thresholds::default()) {
+                    validated.diagnostics.push_warning(suggestion.to_warning());
+                }
+            }
+            eprint!("{}", validated.diagnostics);
+            if validated.diagnostics.has_errors() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::RenderPrompt {
+            schema,
+            prefix,
+            always_hoist_enums,
+            field_order,
+            style,
+        } => {
+            let context = schema
+                .load()?
+                .with_field_order(field_order)
+                .with_render_style(style);
+            println!(
+                "{}",
+                context.render_prompt(prefix, Some(always_hoist_enums))?
+            );
+        }
+        Command::CheckOutput {
+            schema,
+            output,
+            allow_partials,
+            canonical_field_order,
+            normalize_lists,
+            sse,
+            documents,
+            trace,
+            capture,
+        } => {
+            let context = schema
+                .load()?
+                .with_canonical_field_order(canonical_field_order)
+                .with_list_normalization(normalize_lists);
+            let output = match output {
+                Some(path) => read_file(&path)?,
+                None => {
+                    let mut buffer = String::new();
+                    std::io::stdin().read_to_string(&mut buffer)?;
+                    buffer
+                }
+            };
+            let output = if sse { sse_deltas(&output)?.concat() } else { output };
+            if let Some(format) = trace {
+                let trace = context.trace_result(&output, allow_partials);
+                match format {
+                    TraceFormat::Text => print!("{}", trace.to_text()),
+                    TraceFormat::Html => print!("{}", trace.to_html()),
+                    TraceFormat::Json => println!("{}", trace.to_json()),
+                }
+                return Ok(if trace.error.is_some() {
+                    ExitCode::FAILURE
+                } else {
+                    ExitCode::SUCCESS
+                });
+            }
+            if let Some(path) = capture {
+                let fixture = context.capture(&output, allow_partials);
+                std::fs::write(&path, fixture.to_json())
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+            }
+            if !documents {
+                println!("{}", context.validate_result(&output, allow_partials)?);
+                return Ok(ExitCode::SUCCESS);
+            }
+            let mut failed = false;
+            for (index, result) in context
+                .validate_documents(&output, allow_partials)
+                .into_iter()
+                .enumerate()
+            {
+                match result {
+                    Ok(json) => println!("{json}"),
+                    Err(err) => {
+                        eprintln!("document {index}: {err:#}");
+                        failed = true;
+                    }
+                }
+            }
+            if failed {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Replay { fixture } => {
+            let fixture = Fixture::from_json(&read_file(&fixture)?)?;
+            let replay = BamlContext::replay(&fixture)?;
+            if !replay.matches() {
+                eprintln!("expected: {:?}", replay.expected);
+                eprintln!("actual:   {:?}", replay.actual);
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::ImportJsonSchema { file } => {
+            let schema: serde_json::Value = serde_json::from_str(&read_file(&file)?)
+                .with_context(|| format!("Invalid JSON in {}", file.display()))?;
+            print!("{}", from_json_schema(&schema)?);
+        }
+        Command::Fmt { files, check } => {
+            let mut unformatted = false;
+            for path in files {
+                let source = read_file(&path)?;
+                let formatted = format_schema(
+                    &source,
+                    FormatOptions {
+                        indent_width: 2,
+                        fail_on_unhandled_rule: false,
+                    },
+                )
+                .with_context(|| format!("Failed to format {}", path.display()))?;
+                if formatted == source {
+                    continue;
+                }
+                if check {
+                    eprintln!("{} is not formatted", path.display());
+                    unformatted = true;
+                } else {
+                    std::fs::write(&path, formatted)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                }
+            }
+            if unformatted {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::ResponseFormat { schema, backend } => {
+            let context = schema.load()?;
+            let (format, downgrades) = context.response_format_with_downgrades(backend)?;
+            for downgrade in downgrades {
+                eprintln!("warning: {downgrade}");
+            }
+            println!("{}", serde_json::to_string_pretty(&format)?);
+        }
+        Command::Ir { files, json } => {
+            let validated = validate_files(&files, ReservedNamePolicy::default())?;
+            if validated.diagnostics.has_errors() {
+                eprint!("{}", validated.diagnostics);
+                return Ok(ExitCode::FAILURE);
+            }
+            let ir =
+                IntermediateRepr::from_parser_database(&validated.db, validated.configuration)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&ir.json_schema())?);
+            } else {
+                println!("{ir:#?}");
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse().command) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("{err:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! Validated schemas shared between requests of the network services.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use crate::BamlContext;
+
+/// Maximum number of schemas kept in the cache before it is flushed.
+const DEFAULT_CACHE_CAPACITY: usize = 128;
+
+type CacheKey = (String, Option<String>);
+
+/// Validated schemas shared between requests.
+pub struct SchemaCache {
+    capacity: usize,
+    contexts: RwLock<HashMap<CacheKey, Arc<BamlContext>>>,
+}
+
+impl Default for SchemaCache {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CACHE_CAPACITY)
+    }
+}
+
+impl SchemaCache {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            contexts: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the cached context for `schema` and `target_name`, building it on first use.
+    pub fn get_or_build(
+        &self,
+        schema: &str,
+        target_name: Option<&str>,
+    ) -> anyhow::Result<Arc<BamlContext>> {
+        let key = (schema.to_string(), target_name.map(str::to_string));
+        if let Some(context) = self.contexts.read().unwrap().get(&key) {
+            return Ok(context.clone());
+        }
+
+        let context = Arc::new(BamlContext::try_from_schema(&key.0, key.1.clone())?);
+        let mut contexts = self.contexts.write().unwrap();
+        if contexts.len() >= self.capacity {
+            contexts.clear();
+        }
+        contexts.insert(key, context.clone());
+        Ok(context)
+    }
+
+    pub fn len(&self) -> usize {
+        self.contexts.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
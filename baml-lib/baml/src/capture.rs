@@ -0,0 +1,273 @@
+//! Self-contained fixtures reproducing one validation, for bug reports and regression tests.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BamlContext, BoolVocabulary, FieldOrder, FloatFormat, IntOverflow, NumberOverflow,
+    refusal::RefusalDetector, ReservedNamePolicy, SchemaOptions, TrailingZeros,
+};
+
+/// Bump when the fixture layout changes incompatibly.
+const FIXTURE_VERSION: u32 = 2;
+
+/// Everything needed to repeat one validation: the schema and how the context was built, the
+/// raw output and what validating it returned. See [`BamlContext::capture`].
+///
+/// The schema options include the environment variables given to the context, so check a
+/// fixture for secrets before sharing it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fixture {
+    pub fixture_version: u32,
+    /// Version of this library that captured the fixture.
+    pub library_version: String,
+    /// Hex-encoded SHA-256 of the schema source, see [`BamlContext::schema_hash`].
+    pub schema_hash: String,
+    pub schema: String,
+    pub target: String,
+    pub schema_options: FixtureSchemaOptions,
+    pub options: FixtureOptions,
+    pub allow_partials: bool,
+    /// The raw LLM output.
+    pub output: String,
+    pub outcome: Outcome,
+}
+
+/// The [`SchemaOptions`] of a [`Fixture`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FixtureSchemaOptions {
+    pub version: Option<String>,
+    pub doc_comment_descriptions: bool,
+    pub reserved_names: Vec<String>,
+    pub allowed_reserved_names: Vec<String>,
+    pub source_name: Option<String>,
+    pub env_vars: BTreeMap<String, String>,
+    #[serde(default)]
+    pub max_type_depth: Option<usize>,
+}
+
+/// The parsing and serialization options of a [`Fixture`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FixtureOptions {
+    pub number_overflow: String,
+    pub int_overflow: Option<String>,
+    pub field_order: String,
+    pub canonical_field_order: bool,
+    pub normalize_lists: bool,
+    pub bool_truthy: Vec<String>,
+    pub bool_falsy: Vec<String>,
+    pub null_values: Vec<String>,
+    pub float_decimal_places: Option<usize>,
+    pub float_fixed: bool,
+    pub float_trailing_zeros: String,
+    /// The refusal patterns, when refusal detection is on.
+    #[serde(default)]
+    pub refusal_patterns: Option<Vec<String>>,
+    /// Names of the registered custom coercers, which cannot be captured. Replaying needs a
+    /// context with the same coercers, see [`Fixture::replay_on`].
+    pub coercers: Vec<String>,
+}
+
+/// What `validate_result` returned.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Result(String),
+    Error(String),
+}
+
+impl From<anyhow::Result<String>> for Outcome {
+    fn from(result: anyhow::Result<String>) -> Self {
+        match result {
+            Ok(json) => Outcome::Result(json),
+            Err(e) => Outcome::Error(e.to_string()),
+        }
+    }
+}
+
+/// The outcome of replaying a [`Fixture`], next to the captured one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replay {
+    pub expected: Outcome,
+    pub actual: Outcome,
+}
+
+impl Replay {
+    /// Whether validating the output again returned exactly what was captured.
+    pub fn matches(&self) -> bool {
+        self.expected == self.actual
+    }
+}
+
+impl BamlContext {
+    /// Validates `result` and bundles the schema, the options of this context, the output and
+    /// the outcome into a [`Fixture`] that [`replay`](Self::replay) repeats.
+    pub fn capture(&self, result: &str, allow_partials: bool) -> Fixture {
+        let outcome = self.validate_result(result, allow_partials).into();
+        let schema_options = &self.schema_options;
+        let options = &self.coerce_options;
+        let mut coercers = options.coercers.keys().cloned().collect::<Vec<_>>();
+        coercers.sort();
+        Fixture {
+            fixture_version: FIXTURE_VERSION,
+            library_version: env!("CARGO_PKG_VERSION").to_string(),
+            schema_hash: self.schema_hash.clone(),
+            schema: self.schema_source.clone(),
+            target: self.target.to_string(),
+            schema_options: FixtureSchemaOptions {
+                version: schema_options.version.clone(),
+                doc_comment_descriptions: schema_options.doc_comment_descriptions,
+                reserved_names: schema_options.reserved_names.reserve.clone(),
+                allowed_reserved_names: schema_options.reserved_names.allow.clone(),
+                source_name: schema_options.source_name.clone(),
+                env_vars: schema_options.env_vars.clone().into_iter().collect(),
+                max_type_depth: schema_options.max_type_depth,
+            },
+            options: FixtureOptions {
+                number_overflow: match options.number_overflow {
+                    NumberOverflow::Lossy => "lossy",
+                    NumberOverflow::Preserve => "preserve",
+                    NumberOverflow::Error => "error",
+                }
+                .to_string(),
+                int_overflow: options.int_overflow.map(|policy| {
+                    match policy {
+                        IntOverflow::Error => "error",
+                        IntOverflow::Saturate => "saturate",
+                        IntOverflow::CoerceToFloat => "float",
+                    }
+                    .to_string()
+                }),
+                field_order: match self.field_order {
+                    FieldOrder::Declaration => "declaration",
+                    FieldOrder::Alphabetical => "alphabetical",
+                    FieldOrder::RequiredFirst => "required_first",
+                }
+                .to_string(),
+                canonical_field_order: options.field_order.is_some(),
+                normalize_lists: options.normalize_lists,
+                bool_truthy: to_strings(options.bool_vocabulary.truthy()),
+                bool_falsy: to_strings(options.bool_vocabulary.falsy()),
+                null_values: options.null_values.clone(),
+                float_decimal_places: self.float_format.max_decimal_places,
+                float_fixed: self.float_format.fixed,
+                float_trailing_zeros: match self.float_format.trailing_zeros {
+                    TrailingZeros::Minimal => "minimal",
+                    TrailingZeros::Trim => "trim",
+                    TrailingZeros::Pad => "pad",
+                }
+                .to_string(),
+                refusal_patterns: self
+                    .refusal_detector
+                    .as_ref()
+                    .map(|detector| detector.patterns().to_vec()),
+                coercers,
+            },
+            allow_partials,
+            output: result.to_string(),
+            outcome,
+        }
+    }
+
+    /// Rebuilds the context captured in `fixture` and validates its output again.
+    ///
+    /// Fails if the context cannot be rebuilt, e.g. because the fixture needs custom coercers;
+    /// register them on [`Fixture::context`] and use [`Fixture::replay_on`] instead.
+    pub fn replay(fixture: &Fixture) -> anyhow::Result<Replay> {
+        if !fixture.options.coercers.is_empty() {
+            anyhow::bail!(
+                "The fixture needs the custom coercers {}; register them on `Fixture::context()` and use `Fixture::replay_on`",
+                fixture.options.coercers.join(", ")
+            );
+        }
+        Ok(fixture.replay_on(&fixture.context()?))
+    }
+}
+
+impl Fixture {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("fixtures serialize to JSON")
+    }
+
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let fixture: Fixture = serde_json::from_str(json)?;
+        if fixture.fixture_version > FIXTURE_VERSION {
+            anyhow::bail!(
+                "Fixture version {} is newer than the supported version {FIXTURE_VERSION}",
+                fixture.fixture_version
+            );
+        }
+        Ok(fixture)
+    }
+
+    /// The context the fixture was captured with, without its custom coercers and message
+    /// catalog.
+    pub fn context(&self) -> anyhow::Result<BamlContext> {
+        let schema_options = &self.schema_options;
+        let options = SchemaOptions {
+            version: schema_options.version.clone(),
+            doc_comment_descriptions: schema_options.doc_comment_descriptions,
+            reserved_names: ReservedNamePolicy {
+                reserve: schema_options.reserved_names.clone(),
+                allow: schema_options.allowed_reserved_names.clone(),
+            },
+            source_name: schema_options.source_name.clone(),
+            env_vars: schema_options.env_vars.clone().into_iter().collect::<HashMap<_, _>>(),
+            max_type_depth: schema_options.max_type_depth,
+            message_catalog: None,
+        };
+        let options_of = &self.options;
+        let mut context = BamlContext::try_from_schema_with_options(
+            &self.schema,
+            Some(self.target.clone()),
+            &options,
+        )?
+        .with_number_overflow(options_of.number_overflow.parse()?)
+        .with_field_order(
+            options_of
+                .field_order
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Unknown field order `{}`", options_of.field_order))?,
+        )
+        .with_canonical_field_order(options_of.canonical_field_order)
+        .with_list_normalization(options_of.normalize_lists)
+        .with_bool_vocabulary(
+            BoolVocabulary::default()
+                .with_truthy(&options_of.bool_truthy)
+                .with_falsy(&options_of.bool_falsy),
+        )
+        .with_null_values(options_of.null_values.clone())
+        .with_float_format(FloatFormat {
+            max_decimal_places: options_of.float_decimal_places,
+            fixed: options_of.float_fixed,
+            trailing_zeros: options_of.float_trailing_zeros.parse()?,
+        });
+        if let Some(patterns) = &options_of.refusal_patterns {
+            context = context.with_refusal_detection(RefusalDetector::new(patterns));
+        }
+        if let Some(policy) = &options_of.int_overflow {
+            context = context.with_int_overflow(
+                policy
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Unknown int overflow `{policy}`"))?,
+            );
+        }
+        Ok(context)
+    }
+
+    /// Validates the captured output with `context`, e.g. [`context`](Self::context) with the
+    /// custom coercers registered again.
+    pub fn replay_on(&self, context: &BamlContext) -> Replay {
+        Replay {
+            expected: self.outcome.clone(),
+            actual: context
+                .validate_result(&self.output, self.allow_partials)
+                .into(),
+        }
+    }
+}
+
+fn to_strings(words: Vec<&str>) -> Vec<String> {
+    words.into_iter().map(String::from).collect()
+}
@@ -0,0 +1,69 @@
+//! Picking which of several targets an output is, for prompts that let the model choose
+//! between response types.
+
+use crate::{flagged, version::SchemaVersion, BamlContext};
+
+/// The outcome of [`BamlContext::classify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Classification {
+    /// The candidate the output coerced into best.
+    pub target: String,
+    /// The output coerced into `target`, as JSON.
+    pub value: String,
+    /// The coercion score of each candidate, in the order given: lower is better, `None` when
+    /// the output does not coerce into it.
+    pub scores: Vec<(String, Option<i32>)>,
+}
+
+impl BamlContext {
+    /// Coerce `result` into each of `candidates` (classes, enums, type aliases or functions of
+    /// the schema) and return the one it fits best, i.e. with the fewest fixes and
+    /// conversions. Ties go to the earlier candidate. Fails when it fits none.
+    pub fn classify(&self, result: &str, candidates: &[&str]) -> anyhow::Result<Classification> {
+        let version = self
+            .schema_options
+            .version
+            .as_deref()
+            .map(str::parse::<SchemaVersion>)
+            .transpose()?;
+        let mut best: Option<(usize, i32, String)> = None;
+        let mut scores = Vec::with_capacity(candidates.len());
+        let mut errors = vec![];
+        for (index, candidate) in candidates.iter().enumerate() {
+            let target = Self::build_target_type(
+                &self.validated_schema,
+                Some(candidate.to_string()),
+                version.as_ref(),
+            )?;
+            let parsed = jsonish::from_str_with_options(
+                &self.format,
+                &target,
+                result,
+                false,
+                &self.coerce_options,
+            );
+            let score = match parsed {
+                Ok(value) => {
+                    let score = flagged::fix_score(&value);
+                    if best.as_ref().is_none_or(|(_, best, _)| score < *best) {
+                        best = Some((index, score, self.to_json(value.into())));
+                    }
+                    Some(score)
+                }
+                Err(e) => {
+                    errors.push(format!("{candidate}: {e}"));
+                    None
+                }
+            };
+            scores.push((candidate.to_string(), score));
+        }
+        let Some((index, _, value)) = best else {
+            anyhow::bail!("The output matches none of the candidates:\n{}", errors.join("\n"));
+        };
+        Ok(Classification {
+            target: candidates[index].to_string(),
+            value,
+            scores,
+        })
+    }
+}
@@ -0,0 +1,118 @@
+//! Which `@assert`s and `@check`s a validated result passed.
+
+use baml_types::ConstraintLevel;
+use jsonish::{
+    deserializer::{coercer::ParsingError, deserialize_flags::Flag},
+    BamlValueWithFlags,
+};
+use serde::Serialize;
+
+use crate::BamlContext;
+
+/// The result of [`BamlContext::validate_with_constraints`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ConstraintReport {
+    /// The validated value, as JSON.
+    pub value: String,
+    /// Every constraint evaluated on the value, outermost first.
+    pub constraints: Vec<ConstraintResult>,
+}
+
+impl ConstraintReport {
+    /// The constraints that did not pass.
+    pub fn failures(&self) -> impl Iterator<Item = &ConstraintResult> {
+        self.constraints.iter().filter(|c| !c.passed)
+    }
+}
+
+/// The outcome of one `@assert` or `@check` on a part of the value.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConstraintResult {
+    /// Where the constrained value is, such as `$.items[0].name`.
+    pub path: String,
+    pub level: ConstraintLevel,
+    /// Checks are always labelled, asserts may not be.
+    pub label: Option<String>,
+    pub expression: String,
+    pub passed: bool,
+}
+
+impl BamlContext {
+    /// Like [`validate_result`](Self::validate_result), also reporting every `@assert` and
+    /// `@check` on the value and whether it passed, for evaluation pipelines.
+    ///
+    /// Failing checks do not fail the validation and show up in the report. An output failing
+    /// an assert is rejected, as with `validate_result`, including when the failing part was
+    /// dropped from a list or map or replaced by a default.
+    pub fn validate_with_constraints(
+        &self,
+        result: &str,
+        allow_partials: bool,
+    ) -> anyhow::Result<ConstraintReport> {
+        self.validate(result, allow_partials, |value| {
+            let mut constraints = vec![];
+            collect(&value, "$", &mut constraints)?;
+            Ok(ConstraintReport {
+                value: self.to_json((&value).into()),
+                constraints,
+            })
+        })?
+    }
+}
+
+/// Gathers the outcomes the coercer recorded for `value` and the values in it.
+fn collect(
+    value: &BamlValueWithFlags,
+    path: &str,
+    results: &mut Vec<ConstraintResult>,
+) -> anyhow::Result<()> {
+    for flag in value.conditions().flags() {
+        match flag {
+            Flag::ConstraintResults(outcomes) => results.extend(outcomes.iter().map(
+                |(constraint, passed)| ConstraintResult {
+                    path: path.to_string(),
+                    level: constraint.level.clone(),
+                    label: constraint.label.clone(),
+                    expression: constraint.expression.to_string(),
+                    passed: *passed,
+                },
+            )),
+            Flag::ArrayItemParseError(_, error)
+            | Flag::MapValueParseError(_, error)
+            | Flag::DefaultButHadUnparseableValue(error)
+                if is_failed_assert(error) =>
+            {
+                anyhow::bail!("{error}");
+            }
+            _ => {}
+        }
+    }
+    match value {
+        BamlValueWithFlags::List(_, items) => {
+            for (index, item) in items.iter().enumerate() {
+                collect(item, &format!("{path}[{index}]"), results)?;
+            }
+        }
+        BamlValueWithFlags::Map(_, entries) => {
+            for (key, (_, entry)) in entries {
+                collect(entry, &format!("{path}.{key}"), results)?;
+            }
+        }
+        BamlValueWithFlags::Class(_, _, fields) => {
+            for (key, field) in fields {
+                collect(field, &format!("{path}.{key}"), results)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Whether `error`, or one of the errors that caused it, is an `@assert` that did not pass.
+fn is_failed_assert(error: &ParsingError) -> bool {
+    error
+        .localizable
+        .as_ref()
+        .is_some_and(|message| message.id == "assertions_failed")
+        || error.causes.iter().any(is_failed_assert)
+}
@@ -0,0 +1,106 @@
+//! Schema errors and warnings as data, for editors and CI tools that place them in the source.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::{
+    version::SchemaVersion, BamlContext, Diagnostics, MessageCatalog, SchemaOptions, SourceFile,
+    Span, DEFAULT_SOURCE_NAME,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// An error or a warning of a schema and where it is.
+///
+/// Lines and columns are zero-based, columns counting characters; `start` and `end` are byte
+/// offsets into the file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiagnosticItem {
+    pub severity: Severity,
+    /// The message, in the locale of the message catalog if any.
+    pub message: String,
+    /// The file, or the source name of a schema string.
+    pub path: String,
+    pub start: usize,
+    pub end: usize,
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl DiagnosticItem {
+    fn new(severity: Severity, message: String, span: &Span) -> Self {
+        let ((start_line, start_column), (end_line, end_column)) = span.line_and_column();
+        Self {
+            severity,
+            message,
+            path: span.file.path(),
+            start: span.start,
+            end: span.end,
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        }
+    }
+}
+
+impl BamlContext {
+    /// Like [`try_from_schema_with_options`](Self::try_from_schema_with_options), also returning
+    /// every error and warning of the schema, errors first. The context is an error when there
+    /// are errors or the target is not found.
+    pub fn try_from_schema_with_diagnostics(
+        schema_string: &str,
+        target_name: Option<String>,
+        options: &SchemaOptions,
+    ) -> (anyhow::Result<Self>, Vec<DiagnosticItem>) {
+        let version = options.version.as_deref().map(str::parse::<SchemaVersion>);
+        let version = match version.transpose() {
+            Ok(version) => version,
+            Err(e) => return (Err(e), vec![]),
+        };
+        let name = options
+            .source_name
+            .as_deref()
+            .unwrap_or(DEFAULT_SOURCE_NAME);
+        let file = SourceFile::anonymous(name, schema_string);
+        let validated_schema = Self::validate_sources(Path::new("."), vec![file], options);
+        let items = diagnostic_items(
+            &validated_schema.diagnostics,
+            options.message_catalog.as_deref(),
+        );
+        let context = Self::try_from_validated(
+            validated_schema,
+            schema_string.to_string(),
+            target_name,
+            version.as_ref(),
+            options,
+        );
+        (context, items)
+    }
+}
+
+fn diagnostic_items(
+    diagnostics: &Diagnostics,
+    catalog: Option<&dyn MessageCatalog>,
+) -> Vec<DiagnosticItem> {
+    let errors = diagnostics.errors().iter().map(|e| {
+        let message = match catalog {
+            Some(catalog) => e.localized_message(catalog).into_owned(),
+            None => e.message().to_string(),
+        };
+        DiagnosticItem::new(Severity::Error, message, e.span())
+    });
+    let warnings = diagnostics
+        .warnings()
+        .iter()
+        .map(|w| DiagnosticItem::new(Severity::Warning, w.message().to_string(), w.span()));
+    errors.chain(warnings).collect()
+}
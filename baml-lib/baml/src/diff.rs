@@ -0,0 +1,285 @@
+//! What changed between two schemas, and a Markdown changelog of it for release notes.
+
+use std::collections::BTreeMap;
+
+use baml_types::{BamlMap, FieldType};
+use internal_baml_jinja::types::{Class, Enum};
+
+use crate::{type_convert::to_raw_field_type, BamlContext};
+
+/// What a [`SchemaChange`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ItemKind {
+    Class,
+    Enum,
+    TypeAlias,
+}
+
+impl ItemKind {
+    /// The changelog section of the kind.
+    fn heading(self) -> &'static str {
+        match self {
+            ItemKind::Class => "Classes",
+            ItemKind::Enum => "Enums",
+            ItemKind::TypeAlias => "Type aliases",
+        }
+    }
+}
+
+/// A change to a class, an enum or a type alias.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaChange {
+    pub kind: ItemKind,
+    /// The schema name of the class, enum or type alias.
+    pub name: String,
+    /// What changed, in Markdown, such as ``Field `age` removed.``
+    pub summary: String,
+    /// Whether code using the results validated against the old schema may break: a class,
+    /// field or enum value removed, a type changed or made optional, a JSON key renamed.
+    pub breaking: bool,
+}
+
+/// The changes from `old` to `new`, by class, enum and type alias.
+///
+/// Fields and enum values are matched by schema name, so changing an `@alias` is a rename
+/// in the JSON. Types are compared as rendered, with non-recursive aliases expanded.
+pub fn diff(old: &BamlContext, new: &BamlContext) -> Vec<SchemaChange> {
+    let mut changes = Changes::default();
+    for (name, (old_class, new_class)) in pair(&old.format.classes, &new.format.classes) {
+        match (old_class, new_class) {
+            (Some(old_class), Some(new_class)) => {
+                diff_class(&mut changes, name, (old, old_class), (new, new_class))
+            }
+            (None, _) => changes.push(ItemKind::Class, name, "Class added.".to_string(), false),
+            (_, None) => changes.push(ItemKind::Class, name, "Class removed.".to_string(), true),
+        }
+    }
+    for (name, (old_enum, new_enum)) in pair(&old.format.enums, &new.format.enums) {
+        match (old_enum, new_enum) {
+            (Some(old_enum), Some(new_enum)) => {
+                diff_enum(&mut changes, name, (old, old_enum), (new, new_enum))
+            }
+            (None, _) => changes.push(ItemKind::Enum, name, "Enum added.".to_string(), false),
+            (_, None) => changes.push(ItemKind::Enum, name, "Enum removed.".to_string(), true),
+        }
+    }
+    let (old_aliases, new_aliases) = (type_aliases(old), type_aliases(new));
+    for (name, (old_target, new_target)) in pair(&old_aliases, &new_aliases) {
+        let (summary, breaking) = match (old_target, new_target) {
+            (Some(old_target), Some(new_target)) if old_target == new_target => continue,
+            (Some(old_target), Some(new_target)) => {
+                (format!("Changed from `{old_target}` to `{new_target}`."), true)
+            }
+            (None, Some(target)) => (format!("Type alias added: `{target}`."), false),
+            (_, None) => ("Type alias removed.".to_string(), true),
+        };
+        changes.push(ItemKind::TypeAlias, name, summary, breaking);
+    }
+    changes.0
+}
+
+/// A Markdown changelog of the [`diff`] from `old` to `new`: a section per kind of item and
+/// a subsection per class, enum or type alias, with breaking changes marked and listed first.
+pub fn render_changelog(old: &BamlContext, new: &BamlContext) -> String {
+    let changes = diff(old, new);
+    if changes.is_empty() {
+        return "No changes.\n".to_string();
+    }
+
+    let mut sections = BTreeMap::<ItemKind, BamlMap<&str, Vec<&SchemaChange>>>::new();
+    for change in &changes {
+        sections
+            .entry(change.kind)
+            .or_default()
+            .entry(change.name.as_str())
+            .or_default()
+            .push(change);
+    }
+
+    let breaking = changes.iter().filter(|c| c.breaking).count();
+    let mut changelog = match breaking {
+        0 => "No breaking changes.\n".to_string(),
+        1 => "**1 breaking change.**\n".to_string(),
+        n => format!("**{n} breaking changes.**\n"),
+    };
+    for (kind, items) in sections {
+        changelog.push_str(&format!("\n## {}\n", kind.heading()));
+        for (name, mut item_changes) in items {
+            item_changes.sort_by_key(|c| !c.breaking);
+            changelog.push_str(&format!("\n### `{name}`\n\n"));
+            for change in item_changes {
+                let marker = if change.breaking { "**Breaking:** " } else { "" };
+                changelog.push_str(&format!("- {marker}{}\n", change.summary));
+            }
+        }
+    }
+    changelog
+}
+
+#[derive(Default)]
+struct Changes(Vec<SchemaChange>);
+
+impl Changes {
+    fn push(&mut self, kind: ItemKind, name: &str, summary: String, breaking: bool) {
+        self.0.push(SchemaChange {
+            kind,
+            name: name.to_string(),
+            summary,
+            breaking,
+        });
+    }
+}
+
+/// A name with its item in the old and in the new context.
+type Paired<'a, T> = (&'a str, (Option<&'a T>, Option<&'a T>));
+
+/// The items of `old` and `new` by name: those of `new` in order, then those only in `old`.
+fn pair<'a, T>(old: &'a BamlMap<String, T>, new: &'a BamlMap<String, T>) -> Vec<Paired<'a, T>> {
+    let mut pairs = new
+        .iter()
+        .map(|(name, item)| (name.as_str(), (old.get(name), Some(item))))
+        .collect::<Vec<_>>();
+    pairs.extend(
+        old.iter()
+            .filter(|(name, _)| !new.contains_key(*name))
+            .map(|(name, item)| (name.as_str(), (Some(item), None))),
+    );
+    pairs
+}
+
+fn diff_class(
+    changes: &mut Changes,
+    name: &str,
+    (old, old_class): (&BamlContext, &Class),
+    (new, new_class): (&BamlContext, &Class),
+) {
+    // Fields by schema name, with their rendered name, type and description.
+    let fields = |context: &BamlContext, class: &Class| {
+        class
+            .fields
+            .iter()
+            .map(|(field, field_type, description, _)| {
+                let rendered = field.rendered_name();
+                (
+                    context.schema_name(name, rendered),
+                    (rendered.to_string(), field_type.clone(), description.clone()),
+                )
+            })
+            .collect::<BamlMap<_, _>>()
+    };
+    let (old_fields, new_fields) = (fields(old, old_class), fields(new, new_class));
+    for (field, (old_field, new_field)) in pair(&old_fields, &new_fields) {
+        match (old_field, new_field) {
+            (None, Some((_, field_type, _))) => changes.push(
+                ItemKind::Class,
+                name,
+                format!("Field `{field}` added: `{field_type}`."),
+                false,
+            ),
+            (_, None) => {
+                changes.push(ItemKind::Class, name, format!("Field `{field}` removed."), true)
+            }
+            (Some(old_field), Some(new_field)) => {
+                let (old_rendered, old_type, old_description) = old_field;
+                let (new_rendered, new_type, new_description) = new_field;
+                if old_rendered != new_rendered {
+                    changes.push(
+                        ItemKind::Class,
+                        name,
+                        format!(
+                            "Field `{field}` renamed in JSON from `{old_rendered}` to `{new_rendered}`."
+                        ),
+                        true,
+                    );
+                }
+                if let Some((summary, breaking)) = type_change(field, old_type, new_type) {
+                    changes.push(ItemKind::Class, name, summary, breaking);
+                }
+                if old_description != new_description {
+                    changes.push(
+                        ItemKind::Class,
+                        name,
+                        format!("Description of field `{field}` changed."),
+                        false,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// How the type of `field` changed, and whether that is breaking: a field made optional may
+/// now be null, while a field made required is a safe narrowing.
+fn type_change(field: &str, old: &FieldType, new: &FieldType) -> Option<(String, bool)> {
+    let (old_type, new_type) = (old.to_string(), new.to_string());
+    if old_type == new_type {
+        return None;
+    }
+    Some(match (old, new) {
+        (_, FieldType::Optional(inner)) if inner.to_string() == old_type => {
+            (format!("Field `{field}` is now optional."), true)
+        }
+        (FieldType::Optional(inner), _) if inner.to_string() == new_type => {
+            (format!("Field `{field}` is now required."), false)
+        }
+        _ => (
+            format!("Field `{field}` changed from `{old_type}` to `{new_type}`."),
+            true,
+        ),
+    })
+}
+
+fn diff_enum(
+    changes: &mut Changes,
+    name: &str,
+    (old, old_enum): (&BamlContext, &Enum),
+    (new, new_enum): (&BamlContext, &Enum),
+) {
+    // Values by schema name, with their rendered name.
+    let values = |context: &BamlContext, enm: &Enum| {
+        enm.values
+            .iter()
+            .map(|(value, ..)| {
+                let rendered = value.rendered_name();
+                (context.schema_name(name, rendered), rendered.to_string())
+            })
+            .collect::<BamlMap<_, _>>()
+    };
+    let (old_values, new_values) = (values(old, old_enum), values(new, new_enum));
+    for (value, (old_rendered, new_rendered)) in pair(&old_values, &new_values) {
+        match (old_rendered, new_rendered) {
+            (None, _) => {
+                changes.push(ItemKind::Enum, name, format!("Value `{value}` added."), false)
+            }
+            (_, None) => {
+                changes.push(ItemKind::Enum, name, format!("Value `{value}` removed."), true)
+            }
+            (Some(old_rendered), Some(new_rendered)) if old_rendered != new_rendered => {
+                let summary = format!(
+                    "Value `{value}` renamed in JSON from `{old_rendered}` to `{new_rendered}`."
+                );
+                changes.push(ItemKind::Enum, name, summary, true)
+            }
+            _ => {}
+        }
+    }
+    if !old_enum.open && new_enum.open {
+        changes.push(
+            ItemKind::Enum,
+            name,
+            "Now open: values outside the enum are accepted.".to_string(),
+            true,
+        );
+    }
+}
+
+/// The type aliases of the schema with their targets.
+fn type_aliases(context: &BamlContext) -> BamlMap<String, String> {
+    let db = &context.validated_schema.db;
+    db.walk_type_aliases()
+        .map(|alias| {
+            let target = to_raw_field_type(alias.target(), db);
+            (alias.name().to_string(), target.to_string())
+        })
+        .collect()
+}
@@ -0,0 +1,47 @@
+//! Splitting an LLM output that holds several independent JSON documents, such as JSONL or
+//! one code block per record.
+
+use std::ops::Range;
+
+/// The byte ranges of the top-level JSON objects and arrays in `output`, in order.
+///
+/// Brackets inside double-quoted strings are ignored. A document still open at the end of the
+/// output (a truncated last record) runs to the end.
+pub(crate) fn split(output: &str) -> Vec<Range<usize>> {
+    let mut documents = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, c) in output.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' if depth > 0 => in_string = true,
+            '{' | '[' => {
+                if depth == 0 {
+                    start = index;
+                }
+                depth += 1;
+            }
+            '}' | ']' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    documents.push(start..index + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    if depth > 0 {
+        documents.push(start..output.len());
+    }
+    documents
+}
@@ -0,0 +1,111 @@
+//! Structured events emitted by [`BamlContext::validate_result`](crate::BamlContext::validate_result).
+
+use std::{collections::BTreeMap, time::Duration};
+
+use jsonish::BamlValueWithFlags;
+
+/// Receives one [`ValidationEvent`] per `validate_result` call.
+///
+/// Implementations are called synchronously on the validating thread, so they should hand the
+/// event off (to a channel, a metrics registry, ...) rather than block.
+pub trait ValidationSink: Send + Sync {
+    fn record(&self, event: &ValidationEvent);
+}
+
+impl<F> ValidationSink for F
+where
+    F: Fn(&ValidationEvent) + Send + Sync,
+{
+    fn record(&self, event: &ValidationEvent) {
+        self(event)
+    }
+}
+
+/// Whether the LLM output could be coerced into the target type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationOutcome {
+    Success,
+    Failure { error: String },
+    /// The model refused to answer, see
+    /// [`BamlContext::with_refusal_detection`](crate::BamlContext::with_refusal_detection).
+    Refusal { text: String },
+}
+
+impl ValidationOutcome {
+    pub fn is_success(&self) -> bool {
+        matches!(self, ValidationOutcome::Success)
+    }
+}
+
+/// A single `validate_result` call.
+#[derive(Debug, Clone)]
+pub struct ValidationEvent {
+    /// Hex-encoded SHA-256 of the schema source, see
+    /// [`BamlContext::schema_hash`](crate::BamlContext::schema_hash).
+    pub schema_hash: String,
+    /// The target type, e.g. `FruitOrders`.
+    pub target: String,
+    pub allow_partials: bool,
+    pub outcome: ValidationOutcome,
+    /// Number of coercion flags raised, by flag kind (`ImpliedKey`, `SingleToArray`, ...).
+    /// Empty when the output could not be coerced.
+    pub flag_counts: BTreeMap<String, usize>,
+    /// Number of coercion flags raised, by field path (`<root>.fruit.0.price`).
+    pub field_flag_counts: BTreeMap<String, usize>,
+    /// Time spent parsing and coercing the raw output.
+    pub parse_duration: Duration,
+    /// Total time spent in `validate_result`, including serialization of the result.
+    pub total_duration: Duration,
+}
+
+impl ValidationEvent {
+    /// Total number of coercion flags, i.e. how much the output had to be repaired.
+    pub fn flag_total(&self) -> usize {
+        self.flag_counts.values().sum()
+    }
+}
+
+/// Counts the flags of `value` and its children into `by_kind` and `by_field`.
+pub(crate) fn count_flags(
+    value: &BamlValueWithFlags,
+    path: &str,
+    by_kind: &mut BTreeMap<String, usize>,
+    by_field: &mut BTreeMap<String, usize>,
+) {
+    count_conditions(value.conditions().flags(), path, by_kind, by_field);
+    match value {
+        BamlValueWithFlags::List(_, items) => {
+            for (idx, item) in items.iter().enumerate() {
+                count_flags(item, &format!("{path}.{idx}"), by_kind, by_field);
+            }
+        }
+        BamlValueWithFlags::Map(_, entries) => {
+            for (key, (conditions, item)) in entries.iter() {
+                let path = format!("{path}.{key}");
+                count_conditions(conditions.flags(), &path, by_kind, by_field);
+                count_flags(item, &path, by_kind, by_field);
+            }
+        }
+        BamlValueWithFlags::Class(_, _, fields) => {
+            for (name, field) in fields.iter() {
+                count_flags(field, &format!("{path}.{name}"), by_kind, by_field);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn count_conditions(
+    flags: &[jsonish::deserializer::deserialize_flags::Flag],
+    path: &str,
+    by_kind: &mut BTreeMap<String, usize>,
+    by_field: &mut BTreeMap<String, usize>,
+) {
+    if flags.is_empty() {
+        return;
+    }
+    for flag in flags {
+        *by_kind.entry(flag.kind().to_string()).or_default() += 1;
+    }
+    *by_field.entry(path.to_string()).or_default() += flags.len();
+}
@@ -0,0 +1,196 @@
+//! Stable fingerprints of the rendered output format.
+
+use std::collections::BTreeSet;
+
+use baml_types::FieldType;
+use internal_baml_jinja::types::{
+    FieldOrder, OutputFormatContent, RecursionRendering, RenderFormat, RenderStyle,
+};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::PromptOptions;
+
+/// Bump when the canonical encoding below changes.
+const FINGERPRINT_VERSION: u32 = 2;
+
+/// Hex-encoded SHA-256 of the schema behind the prompt rendered for `format.target`: the
+/// target, the classes, enums and recursive aliases it reaches (with aliases, descriptions,
+/// constraints and the field attributes that change coercion, such as `@coerce` or
+/// `@int_overflow`) and the render options: `options` and the context's render settings.
+///
+/// Types the target does not reach, comments and formatting do not affect the fingerprint, and
+/// neither do the context's coercion settings or float format.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn fingerprint(
+    format: &OutputFormatContent,
+    options: &PromptOptions,
+    field_order: FieldOrder,
+    render_style: RenderStyle,
+    render_format: RenderFormat,
+    recursion: RecursionRendering,
+    shared_definitions: bool,
+) -> String {
+    let mut classes = BTreeSet::new();
+    let mut enums = BTreeSet::new();
+    let mut aliases = BTreeSet::new();
+    let mut pending = vec![format.target.clone()];
+    while let Some(field_type) = pending.pop() {
+        match field_type {
+            FieldType::Class(name) => {
+                if classes.insert(name.clone()) {
+                    if let Ok(class) = format.find_class(&name) {
+                        pending.extend(class.fields.iter().map(|(_, t, ..)| t.clone()));
+                    }
+                }
+            }
+            FieldType::Enum(name) => {
+                enums.insert(name);
+            }
+            FieldType::RecursiveTypeAlias(name) => {
+                if aliases.insert(name.clone()) {
+                    if let Ok(target) = format.find_recursive_alias_target(&name) {
+                        pending.push(target.clone());
+                    }
+                }
+            }
+            FieldType::List(inner) | FieldType::Optional(inner) => pending.push(*inner),
+            FieldType::Constrained { base, .. } => pending.push(*base),
+            FieldType::Map(key, value) => pending.extend([*key, *value]),
+            FieldType::Union(items) | FieldType::Tuple(items) => pending.extend(items),
+            FieldType::Primitive(_) | FieldType::Literal(_) => {}
+        }
+    }
+
+    let classes = classes
+        .iter()
+        .filter_map(|name| format.find_class(name).ok())
+        .map(|class| {
+            json!({
+                "name": class.name.real_name(),
+                "alias": class.name.rendered_name(),
+                "fields": class.fields.iter().map(|(name, field_type, description, metadata)| {
+                    json!({
+                        "name": name.real_name(),
+                        "alias": name.rendered_name(),
+                        "type": field_type,
+                        "description": description,
+                        "deprecated": metadata.deprecated,
+                        "coerce": metadata.coercer,
+                        "required_if": metadata.required_if,
+                        "normalize_to_list": metadata.normalize_to_list,
+                        "int_overflow": metadata.int_overflow.map(|o| format!("{o:?}")),
+                        "null_values": metadata.null_values,
+                    })
+                }).collect::<Vec<_>>(),
+                "constraints": class.constraints,
+                "one_of": class.one_of,
+                "render": format.class_rendering(class.name.real_name()).map(|rendering| json!({
+                    "hoisted": rendering.hoisted,
+                    "field_separator": rendering.field_separator,
+                    "descriptions": rendering.descriptions,
+                })),
+            })
+        })
+        .collect::<Vec<_>>();
+    let enums = enums
+        .iter()
+        .filter_map(|name| format.find_enum(name).ok())
+        .map(|enm| {
+            json!({
+                "name": enm.name.real_name(),
+                "alias": enm.name.rendered_name(),
+                "values": enm.values.iter().map(|(name, description, metadata)| json!({
+                    "name": name.real_name(),
+                    "alias": name.rendered_name(),
+                    "description": description,
+                    "deprecated": metadata.deprecated,
+                    "group": metadata.group,
+                })).collect::<Vec<_>>(),
+                "constraints": enm.constraints,
+                "open": enm.open,
+            })
+        })
+        .collect::<Vec<_>>();
+    let aliases = aliases
+        .iter()
+        .filter_map(|name| {
+            let target = format.find_recursive_alias_target(name).ok()?;
+            Some(json!({ "name": name, "type": target }))
+        })
+        .collect::<Vec<_>>();
+
+    let render_options = json!({
+        "prefix": options.prefix,
+        "always_hoist_enums": options.always_hoist_enums,
+        "or_splitter": options.or_splitter,
+        "enum_value_prefix": options.enum_value_prefix,
+        "hoisted_class_prefix": options.hoisted_class_prefix,
+        "map_style": options.map_style.map(|style| format!("{style:?}")),
+        "union_style": format!("{:?}", options.union_style),
+        "field_order": format!("{field_order:?}"),
+        "style": format!("{render_style:?}"),
+        "format": format!("{render_format:?}"),
+        "recursion_inline_depth": recursion.inline_depth,
+        "references": format!("{:?}", recursion.references),
+        "shared_definitions": shared_definitions,
+    });
+    let canonical = json!({
+        "version": FINGERPRINT_VERSION,
+        "target": format.target,
+        "classes": classes,
+        "enums": enums,
+        "aliases": aliases,
+        "render_options": render_options,
+    });
+
+    sha256_hex(canonical.to_string().as_bytes())
+}
+
+/// Hex-encoded SHA-256 of a prompt fingerprint, the text around the rendered schema and the
+/// arguments of the prompt. Arguments are compared as JSON values: key order and whitespace
+/// do not matter.
+pub(crate) fn cache_key(
+    fingerprint: &str,
+    header: Option<&str>,
+    footer: Option<&str>,
+    args: &serde_json::Value,
+) -> String {
+    let canonical = json!({
+        "version": FINGERPRINT_VERSION,
+        "fingerprint": fingerprint,
+        "header": header,
+        "footer": footer,
+        "args": sorted(args),
+    });
+    sha256_hex(canonical.to_string().as_bytes())
+}
+
+/// Hex-encoded SHA-256 of a schema source, stable across builds and platforms.
+pub(crate) fn schema_hash(source: &str) -> String {
+    sha256_hex(source.as_bytes())
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// `value` with the keys of its objects sorted, since objects keep their insertion order.
+fn sorted(value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(object) => {
+            let mut entries = object.iter().collect::<Vec<_>>();
+            entries.sort_by_key(|(key, _)| *key);
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.clone(), sorted(value)))
+                .collect::<serde_json::Map<_, _>>()
+                .into()
+        }
+        serde_json::Value::Array(items) => items.iter().map(sorted).collect(),
+        _ => value.clone(),
+    }
+}
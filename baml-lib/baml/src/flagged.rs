@@ -0,0 +1,215 @@
+//! Parsed values together with the flags recording how each part was coerced.
+
+use std::ops::Range;
+
+use baml_types::{BamlMap, BamlValue};
+use jsonish::{deserializer::deserialize_flags::Flag, BamlValueWithFlags};
+use serde::Serialize;
+
+/// A parsed value and the flags (fixes, conversions, defaults, ...) raised while coercing it.
+///
+/// Serializes as `{"flags": [...], "span": {"start": 0, "end": 9}, "type": "...", ...}` with
+/// children serialized the same way.
+#[derive(Debug, Clone, Serialize)]
+pub struct FlaggedValue {
+    pub(crate) flags: Vec<FlagInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) span: Option<Range<usize>>,
+    #[serde(flatten)]
+    pub(crate) node: FlaggedNode,
+}
+
+/// The shape of a [`FlaggedValue`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FlaggedNode {
+    /// A string, number, bool, bytes, enum value, media or null.
+    Value { value: BamlValue },
+    List { items: Vec<FlaggedValue> },
+    Map { entries: BamlMap<String, FlaggedValue> },
+    Class {
+        name: String,
+        fields: BamlMap<String, FlaggedValue>,
+    },
+}
+
+/// A flag raised while coercing a value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FlagInfo {
+    /// Payload-free name of the flag, such as `SingleToArray`.
+    pub kind: String,
+    /// Human-readable description.
+    pub message: String,
+}
+
+impl FlagInfo {
+    pub(crate) fn new(flag: &Flag) -> Self {
+        Self {
+            kind: flag.kind().to_string(),
+            message: flag.to_string().trim_end().to_string(),
+        }
+    }
+}
+
+impl FlaggedValue {
+    /// The flags of this node, not including those of its children.
+    pub fn flags(&self) -> &[FlagInfo] {
+        &self.flags
+    }
+
+    pub fn node(&self) -> &FlaggedNode {
+        &self.node
+    }
+
+    /// The byte range of the LLM output this node was parsed from, if it could be found. See
+    /// [`provenance`](Self::provenance).
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.span.clone()
+    }
+
+    /// The byte range of the LLM output the node at `path` was parsed from, with paths like
+    /// `$.items[0].name`.
+    ///
+    /// Ranges are found by searching the output for the parsed keys and values, so nodes that
+    /// were not written as parsed (defaults, numbers such as `2.50` parsed as `2.5`) have none.
+    pub fn provenance(&self, path: &str) -> Option<Range<usize>> {
+        let mut node = self;
+        let mut rest = path.strip_prefix('$').unwrap_or(path);
+        while !rest.is_empty() {
+            if let Some(index) = rest.strip_prefix('[') {
+                let (index, after) = index.split_once(']')?;
+                node = node.item(index.trim().parse().ok()?)?;
+                rest = after;
+            } else {
+                let key = rest.strip_prefix('.')?;
+                let end = key.find(['.', '[']).unwrap_or(key.len());
+                node = node.get(&key[..end])?;
+                rest = &key[end..];
+            }
+        }
+        node.span()
+    }
+
+    /// The flags of this node and all its children, with the paths (`$.items[0].name`) of the
+    /// nodes that raised them, parents first.
+    pub fn all_flags(&self) -> Vec<(String, &FlagInfo)> {
+        let mut all = vec![];
+        self.collect_flags("$".to_string(), &mut all);
+        all
+    }
+
+    fn collect_flags<'a>(&'a self, path: String, all: &mut Vec<(String, &'a FlagInfo)>) {
+        all.extend(self.flags.iter().map(|flag| (path.clone(), flag)));
+        match &self.node {
+            FlaggedNode::Value { .. } => {}
+            FlaggedNode::List { items } => {
+                for (index, item) in items.iter().enumerate() {
+                    item.collect_flags(format!("{path}[{index}]"), all);
+                }
+            }
+            FlaggedNode::Map { entries: children } | FlaggedNode::Class { fields: children, .. } => {
+                for (key, child) in children {
+                    child.collect_flags(format!("{path}.{key}"), all);
+                }
+            }
+        }
+    }
+
+    /// The field of a class or the entry of a map named `key`.
+    pub fn get(&self, key: &str) -> Option<&FlaggedValue> {
+        match &self.node {
+            FlaggedNode::Map { entries: children } | FlaggedNode::Class { fields: children, .. } => {
+                children.get(key)
+            }
+            FlaggedNode::Value { .. } | FlaggedNode::List { .. } => None,
+        }
+    }
+
+    /// The item of a list at `index`.
+    pub fn item(&self, index: usize) -> Option<&FlaggedValue> {
+        match &self.node {
+            FlaggedNode::List { items } => items.get(index),
+            _ => None,
+        }
+    }
+
+    /// The value without flags, as returned by `parse_result`.
+    pub fn value(&self) -> BamlValue {
+        let children = |children: &BamlMap<String, FlaggedValue>| {
+            children
+                .iter()
+                .map(|(key, child)| (key.clone(), child.value()))
+                .collect()
+        };
+        match &self.node {
+            FlaggedNode::Value { value } => value.clone(),
+            FlaggedNode::List { items } => {
+                BamlValue::List(items.iter().map(FlaggedValue::value).collect())
+            }
+            FlaggedNode::Map { entries } => BamlValue::Map(children(entries)),
+            FlaggedNode::Class { name, fields } => BamlValue::Class(name.clone(), children(fields)),
+        }
+    }
+}
+
+/// The coercion score of `value` (lower is better), without the pick among a single candidate
+/// that the parser flags on any output it did not have to guess about, so that an output that
+/// needed no fixing scores 0.
+pub(crate) fn fix_score(value: &BamlValueWithFlags) -> i32 {
+    let single_picks = value
+        .conditions()
+        .flags()
+        .iter()
+        .filter(|flag| is_single_pick(flag))
+        .count();
+    value.score() - single_picks as i32
+}
+
+fn is_single_pick(flag: &Flag) -> bool {
+    matches!(flag, Flag::FirstMatch(_, candidates) if candidates.len() == 1)
+}
+
+impl From<BamlValueWithFlags> for FlaggedValue {
+    fn from(value: BamlValueWithFlags) -> Self {
+        let flags = value
+            .conditions()
+            .flags()
+            .iter()
+            .filter(|flag| !is_single_pick(flag))
+            .map(FlagInfo::new)
+            .collect();
+        let node = match value {
+            BamlValueWithFlags::List(_, items) => FlaggedNode::List {
+                items: items.into_iter().map(FlaggedValue::from).collect(),
+            },
+            BamlValueWithFlags::Map(_, entries) => FlaggedNode::Map {
+                entries: entries
+                    .into_iter()
+                    .map(|(key, (conditions, entry))| {
+                        // Flags about the entry (such as its key) go with its value.
+                        let mut entry = FlaggedValue::from(entry);
+                        let value_flags = std::mem::take(&mut entry.flags);
+                        entry.flags = conditions.flags().iter().map(FlagInfo::new).collect();
+                        entry.flags.extend(value_flags);
+                        (key, entry)
+                    })
+                    .collect(),
+            },
+            BamlValueWithFlags::Class(name, _, fields) => FlaggedNode::Class {
+                name,
+                fields: fields
+                    .into_iter()
+                    .map(|(key, field)| (key, field.into()))
+                    .collect(),
+            },
+            value => FlaggedNode::Value {
+                value: value.into(),
+            },
+        };
+        Self {
+            flags,
+            span: None,
+            node,
+        }
+    }
+}
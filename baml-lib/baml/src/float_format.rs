@@ -0,0 +1,95 @@
+//! How floats are written in JSON results.
+
+use baml_types::BamlValue;
+
+/// Serialization options for floats in `validate_result` output. The default writes floats as
+/// `serde_json` does: shortest round-trip digits, scientific notation for very large or small
+/// magnitudes (`1e-7`), and `.0` on whole numbers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FloatFormat {
+    /// Round to at most this many decimal places. Implies fixed notation.
+    pub max_decimal_places: Option<usize>,
+    /// Never use scientific notation (`0.0000001` rather than `1e-7`).
+    pub fixed: bool,
+    pub trailing_zeros: TrailingZeros,
+}
+
+/// What to do with zeros at the end of the fractional part.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrailingZeros {
+    /// Drop them, but keep one decimal on whole numbers: `2.5`, `2.0`.
+    #[default]
+    Minimal,
+    /// Drop them along with the decimal point of whole numbers: `2.5`, `2`.
+    Trim,
+    /// Pad to [`FloatFormat::max_decimal_places`]: `2.50`, `2.00`. Like `Minimal` without a
+    /// maximum.
+    Pad,
+}
+
+impl std::str::FromStr for TrailingZeros {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "minimal" => Ok(TrailingZeros::Minimal),
+            "trim" => Ok(TrailingZeros::Trim),
+            "pad" => Ok(TrailingZeros::Pad),
+            _ => anyhow::bail!("Unknown trailing zeros policy `{s}`, expected minimal, trim or pad"),
+        }
+    }
+}
+
+impl FloatFormat {
+    /// Replaces the floats in `value` with their formatted decimal text, which serializes as a
+    /// JSON number as written.
+    pub(crate) fn apply(&self, value: BamlValue) -> BamlValue {
+        if *self == FloatFormat::default() {
+            return value;
+        }
+        match value {
+            BamlValue::Float(f) if f.is_finite() => BamlValue::Decimal(self.format(f)),
+            BamlValue::List(items) => {
+                BamlValue::List(items.into_iter().map(|item| self.apply(item)).collect())
+            }
+            BamlValue::Map(entries) => BamlValue::Map(
+                entries
+                    .into_iter()
+                    .map(|(key, entry)| (key, self.apply(entry)))
+                    .collect(),
+            ),
+            BamlValue::Class(name, fields) => BamlValue::Class(
+                name,
+                fields
+                    .into_iter()
+                    .map(|(key, field)| (key, self.apply(field)))
+                    .collect(),
+            ),
+            value => value,
+        }
+    }
+
+    fn format(&self, f: f64) -> String {
+        let text = match self.max_decimal_places {
+            Some(places) => format!("{f:.places$}"),
+            // `Display` never uses scientific notation.
+            None if self.fixed => f.to_string(),
+            None => serde_json::to_string(&f).unwrap_or_else(|_| f.to_string()),
+        };
+        let padded = self.trailing_zeros == TrailingZeros::Pad && self.max_decimal_places.is_some();
+        if padded || text.contains(['e', 'E']) {
+            return text;
+        }
+        let text = match text.split_once('.') {
+            Some((whole, fraction)) => match fraction.trim_end_matches('0') {
+                "" => whole.to_string(),
+                fraction => return format!("{whole}.{fraction}"),
+            },
+            None => text,
+        };
+        match self.trailing_zeros {
+            TrailingZeros::Trim => text,
+            TrailingZeros::Minimal | TrailingZeros::Pad => format!("{text}.0"),
+        }
+    }
+}
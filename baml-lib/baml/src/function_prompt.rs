@@ -0,0 +1,205 @@
+//! Rendering the whole prompt of a `function`, not just its output format, and the schema of
+//! its arguments.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use anyhow::Context;
+use baml_types::{BamlMap, BamlValue, EvaluationContext, FieldType};
+use internal_baml_core::ir::{ArgCoercer, IRHelper};
+use internal_baml_jinja::{
+    formatter::STANDARD_FORMATTER,
+    referenced_names, render_prompt,
+    types::{Class, FieldMetadata, Name, RenderOptions},
+    RenderContext, RenderContext_Client, TemplateStringMacro,
+};
+pub use internal_baml_jinja::{ChatMessagePart, RenderedChatMessage, RenderedPrompt};
+
+use crate::{version::SchemaVersion, BamlContext};
+
+impl BamlContext {
+    /// Render the prompt template of the function `function_name` with `args`, as the BAML
+    /// runtime would before calling the model: `ctx.output_format` is the function's return
+    /// type, template strings are available as macros, and `_.role(...)` splits the prompt into
+    /// chat messages.
+    ///
+    /// Arguments are checked against the function's parameters. `ctx.client` is the function's
+    /// client, with `system`, `user` and `assistant` as allowed roles.
+    pub fn render_function_prompt(
+        &self,
+        function_name: &str,
+        args: BamlMap<String, BamlValue>,
+    ) -> anyhow::Result<RenderedPrompt> {
+        self.render_function_prompt_with(function_name, None, args)
+    }
+
+    /// Like [`render_function_prompt`](Self::render_function_prompt), with the template of
+    /// `variant` from a `prompt_suite` of the function instead of its own prompt.
+    pub fn render_function_prompt_variant(
+        &self,
+        function_name: &str,
+        variant: &str,
+        args: BamlMap<String, BamlValue>,
+    ) -> anyhow::Result<RenderedPrompt> {
+        self.render_function_prompt_with(function_name, Some(variant), args)
+    }
+
+    /// Describe the parameters of the function `function_name` the way
+    /// [`render_prompt`](Self::render_prompt) describes the output, for prompts where the model
+    /// writes the arguments of a call: `Call ExtractItem with arguments in JSON using this
+    /// schema:` and an object with a field per parameter.
+    pub fn render_input_schema(&self, function_name: &str) -> anyhow::Result<String> {
+        let ir = self.ir()?;
+        let function = ir.find_function(function_name)?;
+        let version = self
+            .schema_options
+            .version
+            .as_deref()
+            .map(str::parse::<SchemaVersion>)
+            .transpose()?;
+        // The arguments render as a class named after the function, which no class can share.
+        let mut format = Self::build_output_format(
+            &self.validated_schema,
+            FieldType::Class(function_name.to_string()),
+            version.as_ref(),
+            &self.schema_options,
+        )?;
+        let arguments = Class {
+            name: Name::new(function_name.to_string()),
+            fields: function
+                .inputs()
+                .iter()
+                .map(|(name, field_type)| {
+                    let name = Name::new(name.clone());
+                    (name, field_type.clone(), None, FieldMetadata::default())
+                })
+                .collect(),
+            constraints: vec![],
+            one_of: vec![],
+        };
+        Arc::get_mut(&mut format.classes)
+            .expect("the output format was just built")
+            .insert(function_name.to_string(), arguments);
+        let options = RenderOptions::new(
+            Some(Some(format!(
+                "Call {function_name} with arguments in JSON using this schema:\n"
+            ))),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .with_field_order(self.field_order)
+        .with_style(self.render_style)
+        .with_format(self.render_format)
+        .with_recursion(self.recursion)
+        .with_shared_definitions(self.shared_definitions);
+        Ok(self
+            .output_formatters
+            .render(STANDARD_FORMATTER, &format, options)?
+            .unwrap_or_default())
+    }
+
+    fn render_function_prompt_with(
+        &self,
+        function_name: &str,
+        variant: Option<&str>,
+        args: BamlMap<String, BamlValue>,
+    ) -> anyhow::Result<RenderedPrompt> {
+        let ir = self.ir()?;
+        let function = ir.find_function(function_name)?;
+        let Some(config) = function.elem().configs.first() else {
+            anyhow::bail!("Function `{function_name}` has no prompt");
+        };
+        let template = match variant {
+            Some(variant) => ir.find_prompt_variant(function_name, variant)?,
+            None => config.prompt_template.as_str(),
+        };
+        let args = ir.check_function_params(
+            &function,
+            &args,
+            ArgCoercer {
+                span_path: None,
+                allow_implicit_cast_to_string: false,
+            },
+        )?;
+        let client = config.client.as_str();
+        let provider = match client.split_once('/') {
+            Some((provider, _)) => provider.to_string(),
+            None => ir.find_client(&client)?.elem().provider.to_string(),
+        };
+        let version = self
+            .schema_options
+            .version
+            .as_deref()
+            .map(str::parse::<SchemaVersion>)
+            .transpose()?;
+        let output_format = Self::build_output_format(
+            &self.validated_schema,
+            function.output().clone(),
+            version.as_ref(),
+            &self.schema_options,
+        )?;
+        // Only the template strings the prompt reaches are passed on, so that a default reading
+        // an unset `env.NAME` breaks just the prompts calling its template string. Defaults may
+        // read `env.NAME`, so they are resolved on every render.
+        let eval_ctx = EvaluationContext::new(&self.schema_options.env_vars, false);
+        let mut macros = vec![];
+        let mut used = HashSet::new();
+        let mut pending = referenced_names(template).into_iter().collect::<Vec<_>>();
+        while let Some(name) = pending.pop() {
+            if used.contains(&name) {
+                continue;
+            }
+            let Some(template) = ir.walk_template_strings().find(|t| t.name() == name) else {
+                continue;
+            };
+            used.insert(name);
+            let defaults = template
+                .defaults()
+                .iter()
+                .map(|(name, value)| {
+                    let value = value.resolve_serde(&eval_ctx).with_context(|| {
+                        format!(
+                            "Failed to resolve the default of `{}.{name}`",
+                            template.name()
+                        )
+                    })?;
+                    Ok((name.clone(), value))
+                })
+                .collect::<anyhow::Result<_>>()?;
+            pending.extend(referenced_names(template.template()));
+            macros.push(TemplateStringMacro {
+                name: template.name().to_string(),
+                args: template
+                    .inputs()
+                    .iter()
+                    .map(|field| (field.name.clone(), field.r#type.elem.to_string()))
+                    .collect(),
+                defaults,
+                template: template.template().to_string(),
+            });
+        }
+        let roles = ["system", "user", "assistant"].map(str::to_string).to_vec();
+        render_prompt(
+            template,
+            &args,
+            RenderContext {
+                client: RenderContext_Client {
+                    name: client,
+                    provider,
+                    default_role: "system".to_string(),
+                    allowed_roles: roles,
+                },
+                output_format,
+                tags: HashMap::new(),
+            },
+            &macros,
+            &ir,
+            &self.schema_options.env_vars,
+        )
+    }
+}
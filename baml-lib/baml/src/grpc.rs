@@ -0,0 +1,294 @@
+//! gRPC service exposing rendering, validation and diagnostics.
+//!
+//! Enabled with the `grpc` feature. The service definition lives in
+//! `proto/baml_lib.proto`.
+
+// `tonic::Status` is the error type of every generated handler.
+#![allow(clippy::result_large_err)]
+
+use std::{net::SocketAddr, path::PathBuf, pin::Pin, sync::Arc};
+
+use futures::{Stream, StreamExt};
+use internal_baml_core::internal_baml_diagnostics::Span;
+use tonic::{Request, Response, Status, Streaming};
+
+pub use crate::cache::SchemaCache;
+use crate::{BamlContext, SourceFile};
+
+/// Generated protobuf types and service traits.
+pub mod proto {
+    tonic::include_proto!("baml_lib.v1");
+}
+
+use proto::{
+    baml_lib_server::{BamlLib, BamlLibServer},
+    diagnostic::Severity,
+    Diagnostic, DiagnosticsRequest, DiagnosticsResponse, RenderPromptRequest, RenderPromptResponse,
+    ValidateResultRequest, ValidateResultResponse, ValidateStreamRequest,
+};
+
+/// Implementation of the `BamlLib` gRPC service.
+#[derive(Default)]
+pub struct BamlLibService {
+    cache: Arc<SchemaCache>,
+}
+
+impl BamlLibService {
+    pub fn new(cache: Arc<SchemaCache>) -> Self {
+        Self { cache }
+    }
+
+    fn context(&self, schema: Option<proto::Schema>) -> Result<Arc<BamlContext>, Status> {
+        let schema = schema.ok_or_else(|| Status::invalid_argument("`schema` is required"))?;
+        self.cache
+            .get_or_build(&schema.source, schema.target_name.as_deref())
+            .map_err(invalid_argument)
+    }
+}
+
+fn invalid_argument(err: anyhow::Error) -> Status {
+    Status::invalid_argument(format!("{err:#}"))
+}
+
+fn validate(
+    context: &BamlContext,
+    result: &str,
+    allow_partials: bool,
+) -> Result<ValidateResultResponse, Status> {
+    let value = context
+        .parse_result(result, allow_partials)
+        .map_err(invalid_argument)?;
+    let value_json = serde_json::to_string(&context.float_format.apply(value))
+        .map_err(|e| invalid_argument(e.into()))?;
+    Ok(ValidateResultResponse { value_json })
+}
+
+fn to_diagnostic(severity: Severity, message: &str, span: &Span) -> Diagnostic {
+    Diagnostic {
+        severity: severity.into(),
+        message: message.to_string(),
+        path: span.file.path(),
+        start: span.start as u64,
+        end: span.end as u64,
+    }
+}
+
+type ValidateStreamResponse =
+    Pin<Box<dyn Stream<Item = Result<ValidateResultResponse, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl BamlLib for BamlLibService {
+    async fn render_prompt(
+        &self,
+        request: Request<RenderPromptRequest>,
+    ) -> Result<Response<RenderPromptResponse>, Status> {
+        let request = request.into_inner();
+        let context = self.context(request.schema)?;
+        let prompt = context
+            .render_prompt(request.prefix, request.always_hoist_enums)
+            .map_err(invalid_argument)?;
+        Ok(Response::new(RenderPromptResponse { prompt }))
+    }
+
+    async fn validate_result(
+        &self,
+        request: Request<ValidateResultRequest>,
+    ) -> Result<Response<ValidateResultResponse>, Status> {
+        let request = request.into_inner();
+        let context = self.context(request.schema)?;
+        validate(&context, &request.result, request.allow_partials).map(Response::new)
+    }
+
+    async fn diagnostics(
+        &self,
+        request: Request<DiagnosticsRequest>,
+    ) -> Result<Response<DiagnosticsResponse>, Status> {
+        let files = request
+            .into_inner()
+            .files
+            .into_iter()
+            .map(|f| SourceFile::from((PathBuf::from(f.path), f.contents)))
+            .collect();
+        let validated = internal_baml_core::validate(&PathBuf::from("."), files);
+        let errors = validated
+            .diagnostics
+            .errors()
+            .iter()
+            .map(|e| to_diagnostic(Severity::Error, e.message(), e.span()));
+        let warnings = validated
+            .diagnostics
+            .warnings()
+            .iter()
+            .map(|w| to_diagnostic(Severity::Warning, w.message(), w.span()));
+        Ok(Response::new(DiagnosticsResponse {
+            diagnostics: errors.chain(warnings).collect(),
+        }))
+    }
+
+    type ValidateStreamStream = ValidateStreamResponse;
+
+    async fn validate_stream(
+        &self,
+        request: Request<Streaming<ValidateStreamRequest>>,
+    ) -> Result<Response<Self::ValidateStreamStream>, Status> {
+        let cache = self.cache.clone();
+        let mut inbound = request.into_inner();
+
+        let outbound = async_stream::try_stream! {
+            let mut context: Option<Arc<BamlContext>> = None;
+            let mut output = String::new();
+            while let Some(chunk) = inbound.next().await {
+                let chunk = chunk?;
+                if context.is_none() {
+                    context = Some(BamlLibService::new(cache.clone()).context(chunk.schema)?);
+                }
+                output.push_str(&chunk.delta);
+                let context = context.as_ref().unwrap();
+                yield validate(context, &output, !chunk.done)?;
+                if chunk.done {
+                    break;
+                }
+            }
+        };
+
+        Ok(Response::new(Box::pin(outbound)))
+    }
+}
+
+/// Serve [`BamlLibService`] on `addr` until the process is stopped.
+pub async fn serve(addr: SocketAddr) -> Result<(), tonic::transport::Error> {
+    tonic::transport::Server::builder()
+        .add_service(BamlLibServer::new(BamlLibService::default()))
+        .serve(addr)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use proto::{baml_lib_client::BamlLibClient, Schema};
+    use tokio_stream::wrappers::TcpListenerStream;
+
+    use super::*;
+
+    const SCHEMA: &str = r#"
+class Paint {
+  color string
+  liters float
+}
+"#;
+
+    fn schema() -> Option<Schema> {
+        Some(Schema {
+            source: SCHEMA.to_string(),
+            target_name: Some("Paint".to_string()),
+        })
+    }
+
+    #[tokio::test]
+    async fn unary_calls() {
+        let service = BamlLibService::default();
+
+        let prompt = service
+            .render_prompt(Request::new(RenderPromptRequest {
+                schema: schema(),
+                prefix: None,
+                always_hoist_enums: None,
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .prompt;
+        assert_eq!(
+            prompt,
+            "Answer in JSON using this schema:\n{\n  color: string,\n  liters: float,\n}"
+        );
+
+        let value = service
+            .validate_result(Request::new(ValidateResultRequest {
+                schema: schema(),
+                result: r#"{"color": "red", "liters": 1}"#.to_string(),
+                allow_partials: false,
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .value_json;
+        assert_eq!(value, r#"{"color":"red","liters":1.0}"#);
+
+        let diagnostics = service
+            .diagnostics(Request::new(DiagnosticsRequest {
+                files: vec![proto::SourceFile {
+                    path: "bad.baml".to_string(),
+                    contents: "class A { b B }".to_string(),
+                }],
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .diagnostics;
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity(), Severity::Error);
+        assert_eq!(diagnostics[0].path, "bad.baml");
+        assert_eq!((diagnostics[0].start, diagnostics[0].end), (12, 13));
+    }
+
+    #[tokio::test]
+    async fn enum_target_is_a_json_string() {
+        let value = BamlLibService::default()
+            .validate_result(Request::new(ValidateResultRequest {
+                schema: Some(Schema {
+                    source: "enum Color {\n  Red\n  Green\n}".to_string(),
+                    target_name: Some("Color".to_string()),
+                }),
+                result: "green".to_string(),
+                allow_partials: false,
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .value_json;
+        assert_eq!(value, r#""Green""#);
+    }
+
+    #[tokio::test]
+    async fn streaming_validation() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(BamlLibServer::new(BamlLibService::default()))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        let mut client = BamlLibClient::connect(format!("http://{addr}"))
+            .await
+            .unwrap();
+        let chunks = vec![
+            ValidateStreamRequest {
+                schema: schema(),
+                delta: r#"{"color": "re"#.to_string(),
+                done: false,
+            },
+            ValidateStreamRequest {
+                schema: None,
+                delta: r#"d", "liters": 2}"#.to_string(),
+                done: true,
+            },
+        ];
+        let responses = client
+            .validate_stream(futures::stream::iter(chunks))
+            .await
+            .unwrap()
+            .into_inner()
+            .map(|r| r.unwrap().value_json)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            responses,
+            vec![
+                r#"{"color":"re","liters":null}"#.to_string(),
+                r#"{"color":"red","liters":2.0}"#.to_string(),
+            ]
+        );
+    }
+}
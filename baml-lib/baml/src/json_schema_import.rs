@@ -0,0 +1,388 @@
+//! BAML definitions from a JSON Schema, for migrating existing schemas.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Context;
+use serde_json::Value;
+
+/// BAML classes, enums and type aliases equivalent to `schema`.
+///
+/// Each entry of `$defs` (or `definitions`) becomes a declaration named after its key: objects
+/// are classes, string `enum`s are enums and anything else is a type alias. A root object is a
+/// class named after its `title`, or `Root`. Objects nested in properties become classes named
+/// after the class and the property.
+///
+/// Properties outside `required`, `nullable: true` and `null` in a `type`, `oneOf` or `anyOf`
+/// make a type optional. Names that are not identifiers are renamed with an `@alias` to the
+/// original, and `description`s become `@description`s and doc comments. Names that would
+/// collide once renamed get a number appended, such as `foo_bar_2` for a `foo-bar` property
+/// next to `foo_bar`. Fails on `$ref`s outside the definitions and on schemas that accept any
+/// value, which BAML cannot express.
+pub fn from_json_schema(schema: &Value) -> anyhow::Result<String> {
+    let mut importer = Importer::default();
+    let mut definitions = vec![];
+    for key in ["$defs", "definitions"] {
+        if let Some(defs) = schema.get(key) {
+            let defs = defs
+                .as_object()
+                .with_context(|| format!("`{key}` must be an object"))?;
+            definitions.extend(defs);
+        }
+    }
+    // Name every definition first, so that the classes named after properties avoid them.
+    for (key, _) in &definitions {
+        let name = importer.type_name(type_name(key));
+        importer.definitions.insert(key.to_string(), name);
+    }
+    for (key, def) in definitions {
+        let name = importer.definitions[key].clone();
+        importer
+            .definition(&name, def)
+            .with_context(|| format!("In definition `{key}`"))?;
+    }
+    if describes_a_type(schema) {
+        let name = schema
+            .get("title")
+            .and_then(Value::as_str)
+            .map(type_name)
+            .unwrap_or_else(|| "Root".to_string());
+        let name = importer.type_name(name);
+        importer.definition(&name, schema)?;
+    }
+    Ok(importer.declarations.join("\n"))
+}
+
+#[derive(Default)]
+struct Importer {
+    declarations: Vec<String>,
+    /// The names of the declared types.
+    type_names: HashSet<String>,
+    /// The type name of each definition, by its key in `$defs`.
+    definitions: HashMap<String, String>,
+}
+
+impl Importer {
+    /// `name`, or `name` with a number appended when another type already has it.
+    fn type_name(&mut self, name: String) -> String {
+        unique(&mut self.type_names, name, "")
+    }
+
+    fn definition(&mut self, name: &str, schema: &Value) -> anyhow::Result<()> {
+        if schema.get("properties").is_some() {
+            return self.class(name, schema);
+        }
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            if let Some(values) = values.iter().map(Value::as_str).collect::<Option<Vec<_>>>() {
+                let mut declaration = doc_comment(schema);
+                declaration.push_str(&format!("enum {name} {{\n"));
+                let mut identifiers = HashSet::new();
+                for value in values {
+                    let identifier = unique(&mut identifiers, enum_value_name(value), "_");
+                    if identifier == value {
+                        declaration.push_str(&format!("  {identifier}\n"));
+                    } else {
+                        declaration.push_str(&format!(
+                            "  {identifier} @alias({})\n",
+                            string_literal(value)
+                        ));
+                    }
+                }
+                declaration.push_str("}\n");
+                self.declarations.push(declaration);
+                return Ok(());
+            }
+        }
+        let field_type = self.field_type(schema, name)?;
+        let mut declaration = doc_comment(schema);
+        declaration.push_str(&format!("type {name} = {field_type}\n"));
+        self.declarations.push(declaration);
+        Ok(())
+    }
+
+    fn class(&mut self, name: &str, schema: &Value) -> anyhow::Result<()> {
+        // Reserve the position so the class comes before the classes nested in it.
+        let index = self.declarations.len();
+        self.declarations.push(String::new());
+
+        let required = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|required| required.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let properties = schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .with_context(|| format!("`{name}.properties` must be an object"))?;
+
+        let mut declaration = doc_comment(schema);
+        declaration.push_str(&format!("class {name} {{\n"));
+        let mut fields = HashSet::new();
+        for (property, property_schema) in properties {
+            let hint = format!("{name}{}", type_name(property));
+            let mut field_type = self
+                .field_type(property_schema, &hint)
+                .with_context(|| format!("In property `{name}.{property}`"))?;
+            if !required.contains(&property.as_str()) {
+                field_type = optional(field_type);
+            }
+            let field = unique(&mut fields, field_name(property), "_");
+            declaration.push_str(&format!("  {field} {field_type}"));
+            if field != *property {
+                declaration.push_str(&format!(" @alias({})", string_literal(property)));
+            }
+            if let Some(description) = property_schema.get("description").and_then(Value::as_str)
+            {
+                declaration.push_str(&format!(" @description({})", string_literal(description)));
+            }
+            declaration.push('\n');
+        }
+        declaration.push_str("}\n");
+        self.declarations[index] = declaration;
+        Ok(())
+    }
+
+    /// The BAML type of `schema`. Nested objects become classes named `hint`.
+    fn field_type(&mut self, schema: &Value, hint: &str) -> anyhow::Result<String> {
+        let field_type = self.non_null_type(schema, hint)?;
+        Ok(if schema.get("nullable") == Some(&Value::Bool(true)) {
+            optional(field_type)
+        } else {
+            field_type
+        })
+    }
+
+    fn non_null_type(&mut self, schema: &Value, hint: &str) -> anyhow::Result<String> {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let name = reference
+                .strip_prefix("#/$defs/")
+                .or_else(|| reference.strip_prefix("#/definitions/"))
+                .with_context(|| format!("Unsupported `$ref` `{reference}`"))?;
+            return Ok(match self.definitions.get(name) {
+                Some(name) => name.clone(),
+                None => type_name(name),
+            });
+        }
+        if let Some(value) = schema.get("const") {
+            return literal(value);
+        }
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            return union(values.iter().map(literal).collect::<anyhow::Result<Vec<_>>>()?);
+        }
+        for key in ["oneOf", "anyOf"] {
+            if let Some(items) = schema.get(key).and_then(Value::as_array) {
+                let items = items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| {
+                        let hint = if items.len() > 1 {
+                            format!("{hint}{}", index + 1)
+                        } else {
+                            hint.to_string()
+                        };
+                        self.field_type(item, &hint)
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                return union(items);
+            }
+        }
+        match schema.get("type") {
+            Some(Value::String(r#type)) => self.typed(r#type, schema, hint),
+            Some(Value::Array(types)) => {
+                let items = types
+                    .iter()
+                    .map(|r#type| {
+                        let r#type = r#type.as_str().context("`type` must list strings")?;
+                        self.typed(r#type, schema, hint)
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                union(items)
+            }
+            Some(other) => anyhow::bail!("`type` must be a string or a list, not {other}"),
+            None if schema.get("properties").is_some() => self.typed("object", schema, hint),
+            None if schema.get("items").is_some() => self.typed("array", schema, hint),
+            None => anyhow::bail!("The schema accepts any value, which BAML cannot express"),
+        }
+    }
+
+    fn typed(&mut self, r#type: &str, schema: &Value, hint: &str) -> anyhow::Result<String> {
+        Ok(match r#type {
+            "string" => "string".to_string(),
+            "integer" => "int".to_string(),
+            "number" => "float".to_string(),
+            "boolean" => "bool".to_string(),
+            "null" => "null".to_string(),
+            "array" => {
+                let items = schema
+                    .get("items")
+                    .context("Arrays need `items`, BAML lists cannot hold any value")?;
+                let item = self.field_type(items, &format!("{hint}Item"))?;
+                if item.contains(' ') || item.ends_with('?') {
+                    format!("({item})[]")
+                } else {
+                    format!("{item}[]")
+                }
+            }
+            "object" if schema.get("properties").is_some() => {
+                let name = self.type_name(hint.to_string());
+                self.class(&name, schema)?;
+                name
+            }
+            "object" => match schema.get("additionalProperties") {
+                Some(values @ Value::Object(_)) => {
+                    format!("map<string, {}>", self.field_type(values, &format!("{hint}Value"))?)
+                }
+                _ => anyhow::bail!(
+                    "Objects need `properties` or typed `additionalProperties`, BAML maps cannot hold any value"
+                ),
+            },
+            other => anyhow::bail!("Unknown type `{other}`"),
+        })
+    }
+}
+
+/// `items` joined as a union, with a `null` among them making it optional.
+fn union(items: Vec<String>) -> anyhow::Result<String> {
+    let nullable = items.iter().any(|item| item == "null");
+    let mut items = items
+        .into_iter()
+        .filter(|item| item != "null")
+        .collect::<Vec<_>>();
+    items.dedup();
+    let field_type = match items.len() {
+        0 => return Ok("null".to_string()),
+        1 => items.remove(0),
+        _ => items.join(" | "),
+    };
+    Ok(if nullable {
+        optional(field_type)
+    } else {
+        field_type
+    })
+}
+
+fn optional(field_type: String) -> String {
+    if field_type.ends_with('?') || field_type.ends_with("| null") || field_type == "null" {
+        field_type
+    } else if field_type.contains(" | ") {
+        format!("{field_type} | null")
+    } else {
+        format!("{field_type}?")
+    }
+}
+
+fn literal(value: &Value) -> anyhow::Result<String> {
+    Ok(match value {
+        Value::String(s) => string_literal(s),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) if n.is_i64() => n.to_string(),
+        Value::Null => "null".to_string(),
+        other => anyhow::bail!("BAML has no literal type for {other}"),
+    })
+}
+
+fn string_literal(s: &str) -> String {
+    if s.contains('"') || s.contains('\n') {
+        format!("#\"{s}\"#")
+    } else {
+        format!("\"{s}\"")
+    }
+}
+
+fn doc_comment(schema: &Value) -> String {
+    match schema.get("description").and_then(Value::as_str) {
+        Some(description) => description
+            .lines()
+            .map(|line| format!("/// {line}\n"))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// Whether `schema` has a type of its own, rather than only holding definitions.
+fn describes_a_type(schema: &Value) -> bool {
+    [
+        "type",
+        "$ref",
+        "oneOf",
+        "anyOf",
+        "properties",
+        "enum",
+        "const",
+        "items",
+    ]
+    .iter()
+    .any(|key| schema.get(key).is_some())
+}
+
+/// `name`, or `name` with the first number from 2 not in `taken` appended after `separator`.
+/// The result is added to `taken`.
+fn unique(taken: &mut HashSet<String>, name: String, separator: &str) -> String {
+    let mut candidate = name.clone();
+    let mut number = 2;
+    while !taken.insert(candidate.clone()) {
+        candidate = format!("{name}{separator}{number}");
+        number += 1;
+    }
+    candidate
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// The words of `name`, split on anything that is not a letter or digit.
+fn words(name: &str) -> impl Iterator<Item = &str> {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+}
+
+/// `name` in PascalCase, as class, enum and alias names are.
+fn type_name(name: &str) -> String {
+    if is_identifier(name) && name.starts_with(|c: char| c.is_ascii_uppercase()) {
+        return name.to_string();
+    }
+    let name = words(name)
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect::<String>();
+    prefix_digit(name, "T")
+}
+
+fn field_name(name: &str) -> String {
+    if is_identifier(name) {
+        return name.to_string();
+    }
+    prefix_digit(words(name).collect::<Vec<_>>().join("_"), "field_")
+}
+
+/// Enum values must start with an uppercase letter.
+fn enum_value_name(value: &str) -> String {
+    if is_identifier(value) && value.starts_with(|c: char| c.is_ascii_uppercase()) {
+        return value.to_string();
+    }
+    prefix_digit(
+        words(value)
+            .map(str::to_ascii_uppercase)
+            .collect::<Vec<_>>()
+            .join("_"),
+        "V",
+    )
+}
+
+/// Identifiers cannot start with a digit or be empty.
+fn prefix_digit(name: String, prefix: &str) -> String {
+    if name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        name
+    } else {
+        format!("{prefix}{name}")
+    }
+}
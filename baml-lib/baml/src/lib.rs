@@ -1,115 +1,934 @@
 #![doc = include_str!("../README.md")]
 #![deny(rust_2018_idioms, unsafe_code)]
 
-use std::path::PathBuf;
+#[cfg(test)]
+mod tests;
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use anyhow::Context;
 use baml_types::{BamlValue, FieldType, EvaluationContext, UnresolvedValue};
 use serde_json;
 use internal_baml_core::ast::{WithName, SubType};
 pub use internal_baml_core::{
     self,
-    internal_baml_diagnostics::{self, Diagnostics, SourceFile, Span},
-    internal_baml_parser_database::{self, TypeWalker},
+    internal_baml_diagnostics::{
+        self, Diagnostics, Message, MessageCatalog, SourceFile, Span, TemplateCatalog,
+    },
+    ir::{self, IntermediateRepr},
+    internal_baml_parser_database::{
+        self, ReservedNamePolicy, TypeWalker, DEFAULT_MAX_TYPE_DEPTH,
+    },
     Configuration, ValidatedSchema,
 };
+pub use jsonish::{
+    BamlValueWithFlags, BoolVocabulary, CoercerMiddleware, CustomCoercer, NumberOverflow,
+    ParsingError, TypeKind,
+};
 use internal_baml_jinja::types::{OutputFormatContent, RenderOptions, Name};
+pub use internal_baml_jinja::types::{
+    FieldOrder, IntOverflow, MapStyle, RecursionRendering, ReferenceStyle, RenderFormat,
+    RenderStyle, UnionStyle,
+};
+pub use internal_baml_jinja::formatter::{
+    FormatterRegistry, OutputFormatter, StandardFormatter, STANDARD_FORMATTER,
+};
 mod type_convert;
-use type_convert::to_raw_field_type;
+mod literal_enums;
+mod partial;
+pub mod batch;
+pub mod events;
+mod fingerprint;
+pub mod refactor;
+pub mod diff;
+pub mod suggestions;
+pub mod version;
+mod presence;
+mod documents;
+mod flagged;
+mod float_format;
+mod provenance;
+pub mod trace;
+pub mod capture;
+pub use flagged::{FlagInfo, FlaggedNode, FlaggedValue};
+pub use float_format::{FloatFormat, TrailingZeros};
+mod profiles;
+pub use profiles::{RenderProfile, RenderedPrompt};
+mod response_format;
+pub use response_format::{Capabilities, DecodingBackend, Downgrade, DowngradeKind};
+mod json_schema_import;
+pub use json_schema_import::from_json_schema;
+mod pydantic;
+mod typescript;
+mod anthropic;
+pub use anthropic::XmlTags;
+mod stream;
+pub mod refusal;
+mod reasoning;
+mod classify;
+mod constraints;
+mod diagnostic_items;
+pub mod function_prompt;
+pub use classify::Classification;
+pub use constraints::{ConstraintReport, ConstraintResult};
+pub use diagnostic_items::{DiagnosticItem, Severity};
+pub use reasoning::{Reasoned, REASONING_TAGS};
+pub use stream::{sse_deltas, StreamState};
+#[cfg(any(feature = "server", feature = "grpc"))]
+mod cache;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "lsp")]
+pub mod lsp;
+#[cfg(feature = "otel")]
+pub mod otel;
+#[cfg(feature = "server")]
+pub mod server;
+use type_convert::{alias_field_type, recursive_aliases, to_raw_field_type};
+use events::{ValidationEvent, ValidationOutcome, ValidationSink};
+use refusal::{Refusal, RefusalDetector};
+use version::SchemaVersion;
 
 /// The most general API for dealing with Prisma schemas. It accumulates what analysis and
 /// validation information it can, and returns it along with any error and warning diagnostics.
-pub fn validate(schema_string: &String) -> ValidatedSchema {
-    let pathbuf = PathBuf::from("schema.baml");
-    let file = SourceFile::from((&pathbuf, schema_string));
-    internal_baml_core::validate(pathbuf.as_path(), vec![file])
+pub fn validate(schema_string: &str) -> ValidatedSchema {
+    validate_named(DEFAULT_SOURCE_NAME, schema_string)
 }
 
-use pyo3::prelude::PyModuleMethods;
-use python_interface::PyBamlContext;
+/// Like [`validate`], with diagnostics pointing at `name` instead of a file path.
+pub fn validate_named(name: &str, schema_string: &str) -> ValidatedSchema {
+    let file = SourceFile::anonymous(name, schema_string);
+    internal_baml_core::validate(&PathBuf::from("."), vec![file])
+}
+
+/// The label diagnostics show for a schema given as a string without a name.
+const DEFAULT_SOURCE_NAME: &str = "<schema>";
+
+#[cfg(feature = "python")]
 mod python_interface;
 
+#[cfg(feature = "wasm")]
+mod wasm_interface;
+
+#[cfg(feature = "python")]
 #[pyo3::prelude::pymodule]
 fn baml_lib(m: &pyo3::Bound<'_, pyo3::prelude::PyModule>) -> pyo3::PyResult<()> {
-    m.add_class::<PyBamlContext>()?;
+    use pyo3::prelude::PyModuleMethods;
+
+    m.add_class::<python_interface::PyBamlContext>()?;
+    m.add_class::<python_interface::PyFlaggedValue>()?;
     Ok(())
 }
 
 // -------------------------------------------------------------------------------------------------
 // Exported structs and functions
 
+/// Options for [`BamlContext::try_from_schema_with_options`].
+#[derive(Debug, Clone, Default)]
+pub struct SchemaOptions {
+    /// Build the schema as of this version, see
+    /// [`try_from_schema_versioned`](BamlContext::try_from_schema_versioned).
+    pub version: Option<String>,
+    /// Use `///` doc comments as the description of fields and enum values without
+    /// `@description`.
+    pub doc_comment_descriptions: bool,
+    /// Names the schema may not use, instead of the default reserved names.
+    pub reserved_names: ReservedNamePolicy,
+    /// The label diagnostics show for the schema, `<schema>` if unset.
+    pub source_name: Option<String>,
+    /// Environment variables that attributes such as `@alias(env.NAME)` or
+    /// `@description({{ env.NAME }})` read. Reading one that is not set is an error.
+    pub env_vars: HashMap<String, String>,
+    /// How deeply types may nest, [`DEFAULT_MAX_TYPE_DEPTH`] if unset. Deeper types are
+    /// rejected with a diagnostic rather than risking a stack overflow.
+    pub max_type_depth: Option<usize>,
+    /// Translations of the schema diagnostics and of the errors of
+    /// [`validate_result`](BamlContext::validate_result) and its variants. English if unset.
+    pub message_catalog: Option<Arc<dyn MessageCatalog>>,
+}
+
+/// Options for [`BamlContext::render_prompt_with_options`], each keeping its default when
+/// unset.
+#[derive(Debug, Clone, Default)]
+pub struct PromptOptions {
+    /// The text before the schema, instead of the one for the output type such as
+    /// `Answer in JSON using this schema:`.
+    pub prefix: Option<String>,
+    /// Hoist every enum into its own definition, even small ones written inline as their values.
+    pub always_hoist_enums: Option<bool>,
+    /// The separator between alternatives, ` or ` by default.
+    pub or_splitter: Option<String>,
+    /// The text before each value of a hoisted enum, `- ` by default.
+    pub enum_value_prefix: Option<String>,
+    /// The word before hoisted definitions and after "Answer in JSON using this", `schema` by
+    /// default.
+    pub hoisted_class_prefix: Option<String>,
+    /// How maps are written, `map<string, int>` by default.
+    pub map_style: Option<MapStyle>,
+    /// How a union output type is laid out, such as [`UnionStyle::Variants`] for a block per
+    /// alternative under a numbered header.
+    pub union_style: UnionStyle,
+}
+
 /// The context around a BAML schema.
-#[derive(Debug)]
 pub struct BamlContext {
     /// The prompt prefix for the language model.
     pub format: OutputFormatContent,
-    /// Target output: one of `FieldType::Enum` and `FieldType::Class`.
+    /// Target output: a class, an enum, or the type of an alias or a function output.
     pub target: FieldType,
     /// The validated schema.
     pub validated_schema: ValidatedSchema,
+    schema_hash: String,
+    schema_source: String,
+    schema_options: SchemaOptions,
+    validation_sink: Option<Arc<dyn ValidationSink>>,
+    coerce_options: jsonish::CoerceOptions,
+    field_order: FieldOrder,
+    render_style: RenderStyle,
+    render_format: RenderFormat,
+    recursion: RecursionRendering,
+    shared_definitions: bool,
+    render_profiles: BTreeMap<String, RenderProfile>,
+    output_formatters: FormatterRegistry,
+    float_format: FloatFormat,
+    refusal_detector: Option<RefusalDetector>,
+}
+
+impl std::fmt::Debug for BamlContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BamlContext")
+            .field("format", &self.format)
+            .field("target", &self.target)
+            .field("validated_schema", &self.validated_schema)
+            .field("schema_hash", &self.schema_hash)
+            .field("validation_sink", &self.validation_sink.is_some())
+            .field("coerce_options", &self.coerce_options)
+            .field("field_order", &self.field_order)
+            .field("render_style", &self.render_style)
+            .field("render_format", &self.render_format)
+            .field("recursion", &self.recursion)
+            .field("shared_definitions", &self.shared_definitions)
+            .field("render_profiles", &self.render_profiles)
+            .field("output_formatters", &self.output_formatters)
+            .field("float_format", &self.float_format)
+            .finish()
+    }
 }
 
 impl BamlContext {
     /// try to build a `BamlContext` from a schema string and an optional target name.
     pub fn try_from_schema(
-        schema_string: &String,
+        schema_string: &str,
+        target_name: Option<String>,
+    ) -> anyhow::Result<Self> {
+        Self::try_from_schema_at(schema_string, target_name, None, &SchemaOptions::default())
+    }
+
+    /// Like [`try_from_schema`](Self::try_from_schema), validating against any of `targets`,
+    /// such as an `Invoice` or a `Receipt`. Same as the target name `"Invoice, Receipt"`.
+    pub fn try_from_schema_with_union(
+        schema_string: &str,
+        targets: &[&str],
+    ) -> anyhow::Result<Self> {
+        if targets.is_empty() {
+            anyhow::bail!("A union target needs at least one type");
+        }
+        Self::try_from_schema(schema_string, Some(targets.join(", ")))
+    }
+
+    /// Like [`try_from_schema`](Self::try_from_schema), with the schema as of `version`: classes,
+    /// enums, fields and enum values outside their `@since` / `@until` range are left out.
+    pub fn try_from_schema_versioned(
+        schema_string: &str,
+        target_name: Option<String>,
+        version: &str,
+    ) -> anyhow::Result<Self> {
+        let options = SchemaOptions {
+            version: Some(version.to_string()),
+            ..Default::default()
+        };
+        Self::try_from_schema_with_options(schema_string, target_name, &options)
+    }
+
+    /// Like [`try_from_schema`](Self::try_from_schema), with the given [`SchemaOptions`].
+    pub fn try_from_schema_with_options(
+        schema_string: &str,
+        target_name: Option<String>,
+        options: &SchemaOptions,
+    ) -> anyhow::Result<Self> {
+        let version = options
+            .version
+            .as_deref()
+            .map(str::parse::<SchemaVersion>)
+            .transpose()?;
+        Self::try_from_schema_at(schema_string, target_name, version.as_ref(), options)
+    }
+
+    fn try_from_schema_at(
+        schema_string: &str,
+        target_name: Option<String>,
+        version: Option<&SchemaVersion>,
+        options: &SchemaOptions,
+    ) -> anyhow::Result<Self> {
+        let name = options.source_name.as_deref().unwrap_or(DEFAULT_SOURCE_NAME);
+        let file = SourceFile::anonymous(name, schema_string);
+        Self::try_from_sources(
+            Path::new("."),
+            vec![file],
+            schema_string.to_string(),
+            target_name,
+            version,
+            options,
+        )
+    }
+
+    /// Like [`try_from_schema`](Self::try_from_schema), for a schema split across `.baml` files
+    /// that may use each other's types. Diagnostics name the file, relative to `root`.
+    pub fn try_from_files(root: &Path, files: Vec<(PathBuf, String)>) -> anyhow::Result<Self> {
+        Self::try_from_files_with_options(root, files, None, &SchemaOptions::default())
+    }
+
+    /// Like [`try_from_files`](Self::try_from_files), with a target and [`SchemaOptions`].
+    /// `options.source_name` is not used: diagnostics name the files.
+    pub fn try_from_files_with_options(
+        root: &Path,
+        files: Vec<(PathBuf, String)>,
+        target_name: Option<String>,
+        options: &SchemaOptions,
+    ) -> anyhow::Result<Self> {
+        let version = options
+            .version
+            .as_deref()
+            .map(str::parse::<SchemaVersion>)
+            .transpose()?;
+        // What captures and the schema hash see: the files in order, as if they were one.
+        let schema_source = files
+            .iter()
+            .map(|(_, contents)| contents.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let files = files.into_iter().map(SourceFile::from).collect();
+        Self::try_from_sources(
+            root,
+            files,
+            schema_source,
+            target_name,
+            version.as_ref(),
+            options,
+        )
+    }
+
+    fn try_from_sources(
+        root: &Path,
+        files: Vec<SourceFile>,
+        schema_string: String,
+        target_name: Option<String>,
+        version: Option<&SchemaVersion>,
+        options: &SchemaOptions,
+    ) -> anyhow::Result<Self> {
+        let validated_schema = Self::validate_sources(root, files, options);
+        Self::try_from_validated(
+            validated_schema,
+            schema_string,
+            target_name,
+            version,
+            options,
+        )
+    }
+
+    fn validate_sources(
+        root: &Path,
+        files: Vec<SourceFile>,
+        options: &SchemaOptions,
+    ) -> ValidatedSchema {
+        internal_baml_core::validate_with_limits(
+            root,
+            files,
+            options.reserved_names.clone(),
+            options.max_type_depth.unwrap_or(DEFAULT_MAX_TYPE_DEPTH),
+        )
+    }
+
+    fn try_from_validated(
+        validated_schema: ValidatedSchema,
+        schema_string: String,
         target_name: Option<String>,
+        version: Option<&SchemaVersion>,
+        options: &SchemaOptions,
     ) -> anyhow::Result<Self> {
-        let validated_schema = validate(schema_string);
         let diagnostics = &validated_schema.diagnostics;
         if diagnostics.has_errors() {
-            let formatted_error = diagnostics.to_pretty_string();
+            let formatted_error = match &options.message_catalog {
+                Some(catalog) => diagnostics.to_pretty_string_localized(catalog.as_ref()),
+                None => diagnostics.to_pretty_string(),
+            };
             return Err(anyhow::anyhow!(formatted_error));
         }
-        let target = Self::build_target_type(&validated_schema, target_name)?;
-        let format = Self::build_output_format(&validated_schema, target.clone());
+        let target = Self::build_target_type(&validated_schema, target_name, version)?;
+        let format = Self::build_output_format(&validated_schema, target.clone(), version, options)?;
         Ok(Self {
             format,
             target,
             validated_schema,
+            schema_hash: fingerprint::schema_hash(&schema_string),
+            schema_source: schema_string,
+            schema_options: options.clone(),
+            validation_sink: None,
+            coerce_options: Default::default(),
+            field_order: FieldOrder::Declaration,
+            render_style: RenderStyle::Standard,
+            render_format: RenderFormat::Block,
+            recursion: RecursionRendering::default(),
+            shared_definitions: false,
+            render_profiles: BTreeMap::new(),
+            output_formatters: FormatterRegistry::default(),
+            float_format: FloatFormat::default(),
+            refusal_detector: None,
+        })
+    }
+
+    /// Emit a [`ValidationEvent`] to `sink` on every `validate_result` call.
+    pub fn with_validation_sink(mut self, sink: Arc<dyn ValidationSink>) -> Self {
+        self.validation_sink = Some(sink);
+        self
+    }
+
+    /// Choose what `validate_result` does with numbers an `int` or `float` cannot hold exactly;
+    /// [`NumberOverflow::Preserve`] outputs them with every digit.
+    pub fn with_number_overflow(mut self, number_overflow: NumberOverflow) -> Self {
+        self.coerce_options.number_overflow = number_overflow;
+        self
+    }
+
+    /// Choose what `validate_result` does with numbers outside the range of an `int`, for fields
+    /// without `@int_overflow`. Takes precedence over [`with_number_overflow`](Self::with_number_overflow)
+    /// for ints.
+    pub fn with_int_overflow(mut self, int_overflow: IntOverflow) -> Self {
+        self.coerce_options.int_overflow = Some(int_overflow);
+        self
+    }
+
+    /// Also accept the words of `vocabulary` (e.g. `oui` / `non`) as `bool` values, flagged
+    /// `StringToBool` like `"true"`.
+    pub fn with_bool_vocabulary(mut self, vocabulary: BoolVocabulary) -> Self {
+        self.coerce_options.bool_vocabulary = vocabulary;
+        self
+    }
+
+    /// Parse these strings (`N/A`, `unknown`, `-`, ...) as null for optional values, ignoring case
+    /// and surrounding whitespace, flagged `NullSynonym`. Fields with `@null_values` use their
+    /// own list instead.
+    pub fn with_null_values(mut self, values: Vec<String>) -> Self {
+        self.coerce_options.null_values = values;
+        self
+    }
+
+    /// Cut objects and arrays nested more than `max_depth` fields, list items and map values
+    /// deep, as adversarially deep outputs of recursive types can be: they become null or an
+    /// empty list or map, flagged `DepthLimitExceeded`, where the type allows it, and fail
+    /// validation otherwise.
+    pub fn with_max_value_depth(mut self, max_depth: usize) -> Self {
+        self.coerce_options.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Check outputs for refusals (`I'm sorry, but I cannot ...`) before coercing them, and
+    /// fail with a [`refusal::Refusal`] error instead of a type mismatch when `detector`
+    /// finds one.
+    pub fn with_refusal_detection(mut self, detector: RefusalDetector) -> Self {
+        self.refusal_detector = Some(detector);
+        self
+    }
+
+    /// Order class fields in the rendered prompt, e.g. [`FieldOrder::RequiredFirst`] to put the
+    /// fields the model must fill first. Defaults to declaration order.
+    pub fn with_field_order(mut self, field_order: FieldOrder) -> Self {
+        self.field_order = field_order;
+        if self.coerce_options.field_order.is_some() {
+            self.coerce_options.field_order = Some(field_order);
+        }
+        self
+    }
+
+    /// Lay out the rendered prompt in `style`, e.g. [`RenderStyle::Compact`] to spend fewer
+    /// tokens on large schemas. Validation is not affected.
+    pub fn with_render_style(mut self, style: RenderStyle) -> Self {
+        self.render_style = style;
+        self
+    }
+
+    /// Write classes in the rendered prompt in `format`, e.g. [`RenderFormat::MarkdownTable`]
+    /// for a table of fields per class, which some models follow more reliably. Validation is
+    /// not affected.
+    pub fn with_render_format(mut self, format: RenderFormat) -> Self {
+        self.render_format = format;
+        self
+    }
+
+    /// Write out recursive classes to `recursion.inline_depth` levels where they are used, and
+    /// refer to them (and to recursive type aliases) in the style of `recursion.references`,
+    /// such as `#/Node`. By default they are referred to by name right away.
+    pub fn with_recursion_rendering(mut self, recursion: RecursionRendering) -> Self {
+        self.recursion = recursion;
+        self
+    }
+
+    /// Define classes whose schema the prompt would write out more than once, such as an
+    /// `Address` used by several fields, once above the answer and refer to them by name. Classes
+    /// of the same shape share one definition. Validation is not affected.
+    pub fn with_shared_definitions(mut self, shared_definitions: bool) -> Self {
+        self.shared_definitions = shared_definitions;
+        self
+    }
+
+    /// Emit parsed class fields in the rendered [field order](Self::with_field_order) instead
+    /// of the order the model wrote them in. Map entries always keep the model's order.
+    pub fn with_canonical_field_order(mut self, canonical: bool) -> Self {
+        self.coerce_options.field_order = canonical.then_some(self.field_order);
+        self
+    }
+
+    /// Write floats in `validate_result` output according to `float_format`, e.g. rounded to
+    /// two decimal places without scientific notation. `parse_result` is not affected.
+    pub fn with_float_format(mut self, float_format: FloatFormat) -> Self {
+        self.float_format = float_format;
+        self
+    }
+
+    /// Return every value coerced into a union such as `string | string[]` as a list, wrapping
+    /// single values, as if each such field were marked `@normalize("list")`.
+    pub fn with_list_normalization(mut self, normalize: bool) -> Self {
+        self.coerce_options.normalize_lists = normalize;
+        self
+    }
+
+    /// Register the coercer that parses fields marked `@coerce("name")`.
+    ///
+    /// Fields naming a coercer that was never registered fail to parse.
+    pub fn with_coercer(mut self, name: impl Into<String>, coercer: Arc<dyn CustomCoercer>) -> Self {
+        self.coerce_options.coercers.insert(name.into(), coercer);
+        self
+    }
+
+    /// Add `middleware` to the hooks run around the coercion of every value, after the ones
+    /// added before it.
+    pub fn with_coercer_middleware(mut self, middleware: Arc<dyn CoercerMiddleware>) -> Self {
+        self.coerce_options.middleware.push(middleware);
+        self
+    }
+
+    /// Register `formatter` as `name`, for
+    /// [`render_prompt_with_formatter`](Self::render_prompt_with_formatter). Registering it as
+    /// [`STANDARD_FORMATTER`] replaces the built-in renderer wherever the context renders the
+    /// schema, except in [function prompts](Self::render_function_prompt).
+    pub fn with_output_formatter(
+        mut self,
+        name: impl Into<String>,
+        formatter: Arc<dyn OutputFormatter>,
+    ) -> Self {
+        self.output_formatters.register(name, formatter);
+        self
+    }
+
+    /// The names of the registered output formatters, the standard one first.
+    pub fn output_formatters(&self) -> Vec<&str> {
+        self.output_formatters.names()
+    }
+
+    /// Advisory suggestions to shrink the rendered prompt, with the default thresholds.
+    ///
+    /// Flags deeply nested unions, enums with many values and long descriptions anywhere in the
+    /// schema. They never affect rendering or validation.
+    pub fn suggestions(&self) -> Vec<suggestions::Suggestion> {
+        self.suggestions_with(&Default::default())
+    }
+
+    /// Like [`suggestions`](Self::suggestions), with custom thresholds.
+    pub fn suggestions_with(
+        &self,
+        thresholds: &suggestions::Thresholds,
+    ) -> Vec<suggestions::Suggestion> {
+        suggestions::analyze(&self.validated_schema.db, thresholds)
+    }
+
+    /// Stable fingerprint of the prompt semantics for the default render options.
+    ///
+    /// Covers the target type, the classes and enums it reaches (including aliases and
+    /// descriptions) and the render options, but not unrelated types, comments or formatting.
+    pub fn fingerprint(&self) -> String {
+        self.fingerprint_with_options(&PromptOptions::default())
+    }
+
+    /// Like [`fingerprint`](Self::fingerprint), for the prompt rendered by
+    /// [`render_prompt_with_options`](Self::render_prompt_with_options) with `options`.
+    pub fn fingerprint_with_options(&self, options: &PromptOptions) -> String {
+        fingerprint::fingerprint(
+            &self.format,
+            options,
+            self.field_order,
+            self.render_style,
+            self.render_format,
+            self.recursion,
+            self.shared_definitions,
+        )
+    }
+
+    /// A key for caches of LLM responses to prompts rendered from this context: a stable hash of
+    /// the [fingerprint](Self::fingerprint) for `render_profile` (or the default render
+    /// options), the profile's header and footer, and `args_json`, the arguments of the prompt.
+    /// The fingerprint covers the field attributes that change how responses are coerced, so
+    /// cached responses are not reused across such schema changes either. It does not cover
+    /// the context's own coercion settings, such as
+    /// [`with_bool_vocabulary`](Self::with_bool_vocabulary), custom coercers or
+    /// [`with_float_format`](Self::with_float_format): keep separate caches for contexts that
+    /// differ in those.
+    ///
+    /// Arguments are normalized, so the same values in another key order or layout give the
+    /// same key. Fails when `args_json` is not JSON or the profile is not registered.
+    pub fn cache_key(&self, args_json: &str, render_profile: Option<&str>) -> anyhow::Result<String> {
+        let args = serde_json::from_str::<serde_json::Value>(args_json)
+            .context("Cache key arguments must be JSON")?;
+        let Some(name) = render_profile else {
+            return Ok(fingerprint::cache_key(&self.fingerprint(), None, None, &args));
+        };
+        let Some(profile) = self.render_profiles.get(name) else {
+            anyhow::bail!(
+                "Unknown render profile `{name}`, expected one of: {}",
+                self.render_profiles().join(", ")
+            );
+        };
+        let options = PromptOptions {
+            prefix: profile.prefix.clone(),
+            always_hoist_enums: profile.always_hoist_enums,
+            ..Default::default()
+        };
+        let fingerprint = fingerprint::fingerprint(
+            &self.format,
+            &options,
+            profile.field_order,
+            profile.style,
+            profile.format,
+            self.recursion,
+            self.shared_definitions,
+        );
+        Ok(fingerprint::cache_key(
+            &fingerprint,
+            profile.header.as_deref(),
+            profile.footer.as_deref(),
+            &args,
+        ))
+    }
+
+    /// Hex-encoded SHA-256 of the schema source this context was built from.
+    pub fn schema_hash(&self) -> &str {
+        &self.schema_hash
+    }
+
+    /// The intermediate representation of the schema: its enums, classes, type aliases,
+    /// functions and template strings, to build code generators on. Generators declared in the
+    /// schema are not part of it.
+    pub fn ir(&self) -> anyhow::Result<IntermediateRepr> {
+        IntermediateRepr::from_parser_database(&self.validated_schema.db, Configuration::new())
+    }
+
+    /// Like [`ir`](Self::ir), but builds each class, enum, type alias or function only when it
+    /// is looked up, for large schemas of which only a few types are needed.
+    pub fn lazy_ir(&self) -> ir::LazyIr<'_> {
+        ir::LazyIr::new(&self.validated_schema.db)
+    }
+
+    /// The type of `allow_partials` results, for generating matching partial types.
+    ///
+    /// Classes keep their name: see [`partial_class_fields`](Self::partial_class_fields) for
+    /// their fields, every one of which may be `null` except lists and maps (empty instead).
+    pub fn partial_target_type(&self) -> FieldType {
+        partial::partial_value_type(&self.target)
+    }
+
+    /// The fields of `class` in `allow_partials` results, keyed as in the output.
+    pub fn partial_class_fields(&self, class: &str) -> Option<Vec<(String, FieldType)>> {
+        self.format.classes.get(class).map(|class| {
+            class
+                .fields
+                .iter()
+                .map(|(name, field_type, ..)| {
+                    (name.real_name().to_string(), partial::partial_field_type(field_type))
+                })
+                .collect()
         })
     }
 
     /// Render the prompt prefix for the output.
     pub fn render_prompt(&self, prefix: Option<String>, always_hoist_enums: Option<bool>) -> anyhow::Result<String> {
-        let output = self.format.render(RenderOptions::new(
-            prefix.map(Some),
-            None,
-            None,
+        self.render_prompt_with_options(&PromptOptions {
+            prefix,
             always_hoist_enums,
-            None,
-            None,
-        ))?;
-
-        Ok(output.unwrap_or_default())
-    }
-
-    /// Check the LLM output for validity.
-    pub fn validate_result(&self, result: &String, allow_partials: bool) -> anyhow::Result<String> {
-        let result = jsonish::from_str(&self.format, &self.target, &result, allow_partials);
-        result.map(|r| {
-            let baml_value: BamlValue = r.into();
-            // BAML serializes values using `serde_json::json!` which adds quotes around strings.
-            // Enum result is a JSON string, so remove quotes around it.
-            serde_json::json!(&baml_value)
-                .to_string()
-                .trim_matches('"')
-                .to_string()
+            ..Default::default()
         })
     }
 
+    /// Like [`render_prompt`](Self::render_prompt), with every rendering option of `options`.
+    pub fn render_prompt_with_options(&self, options: &PromptOptions) -> anyhow::Result<String> {
+        self.render_with(
+            RenderOptions::new(
+                options.prefix.clone().map(Some),
+                options.or_splitter.clone(),
+                options.enum_value_prefix.clone().map(Some),
+                options.always_hoist_enums,
+                options.map_style,
+                options.hoisted_class_prefix.clone().map(Some),
+            )
+            .with_field_order(self.field_order)
+            .with_style(self.render_style)
+            .with_format(self.render_format)
+            .with_union_style(options.union_style),
+        )
+    }
+
+    /// Like [`render_prompt`](Self::render_prompt), with the output formatter registered as
+    /// `name`.
+    pub fn render_prompt_with_formatter(&self, name: &str) -> anyhow::Result<String> {
+        let options = RenderOptions::default()
+            .with_field_order(self.field_order)
+            .with_style(self.render_style)
+            .with_format(self.render_format)
+            .with_recursion(self.recursion)
+            .with_shared_definitions(self.shared_definitions);
+        Ok(self.output_formatters.render(name, &self.format, options)?.unwrap_or_default())
+    }
+
+    fn render_with(&self, options: RenderOptions) -> anyhow::Result<String> {
+        let options = options
+            .with_recursion(self.recursion)
+            .with_shared_definitions(self.shared_definitions);
+        Ok(self
+            .output_formatters
+            .render(STANDARD_FORMATTER, &self.format, options)?
+            .unwrap_or_default())
+    }
+
+    /// Check the LLM output for validity, returning the value as JSON. Use
+    /// [`parse_result`](Self::parse_result) or [`parse_result_with_flags`](Self::parse_result_with_flags)
+    /// to work with the typed value instead.
+    pub fn validate_result(&self, result: &str, allow_partials: bool) -> anyhow::Result<String> {
+        self.validate(result, allow_partials, |value| self.to_json(value.into()))
+    }
+
+    /// Like [`validate_result`](Self::validate_result), but returns the value instead of its JSON
+    /// encoding, e.g. with `bytes` fields as raw bytes rather than base64.
+    pub fn parse_result(&self, result: &str, allow_partials: bool) -> anyhow::Result<BamlValue> {
+        self.validate(result, allow_partials, BamlValue::from)
+    }
+
+    /// Like [`parse_result`](Self::parse_result), but keeps the flags recording how each part
+    /// of the value was coerced (fixed JSON, converted types, defaults, ...) and where in
+    /// `result` it came from.
+    pub fn parse_result_with_flags(
+        &self,
+        result: &str,
+        allow_partials: bool,
+    ) -> anyhow::Result<FlaggedValue> {
+        self.validate(result, allow_partials, |value| {
+            let mut value = FlaggedValue::from(value);
+            provenance::locate(&mut value, result, 0);
+            value
+        })
+    }
+
+    /// Like [`validate_result`](Self::validate_result), also counting the coercion flags raised,
+    /// by kind.
+    pub(crate) fn validate_result_counting_flags(
+        &self,
+        result: &str,
+        allow_partials: bool,
+    ) -> anyhow::Result<(String, BTreeMap<String, usize>)> {
+        self.validate(result, allow_partials, |value| {
+            let mut flag_counts = BTreeMap::new();
+            events::count_flags(&value, "<root>", &mut flag_counts, &mut BTreeMap::new());
+            (self.to_json(value.into()), flag_counts)
+        })
+    }
+
+    /// Like [`validate_result`](Self::validate_result), also returning the coercion score (lower
+    /// is better, 0 for an output that needed no fixing) and the flagged value.
+    #[cfg(any(test, feature = "python"))]
+    pub(crate) fn validate_result_with_flags(
+        &self,
+        result: &str,
+        allow_partials: bool,
+    ) -> anyhow::Result<(String, i32, FlaggedValue)> {
+        self.validate(result, allow_partials, |value| {
+            let score = flagged::fix_score(&value);
+            let json = self.to_json((&value).into());
+            let mut flagged = FlaggedValue::from(value);
+            provenance::locate(&mut flagged, result, 0);
+            (json, score, flagged)
+        })
+    }
+
+    /// Like [`validate_result`](Self::validate_result), but records every decision taken while
+    /// coercing (candidates and their scores, flags, check outcomes) for debugging. Slower and
+    /// much larger than the result itself; not meant for production paths.
+    pub fn trace_result(&self, result: &str, allow_partials: bool) -> trace::CoercionTrace {
+        let root = self.validate(result, allow_partials, |value| {
+            trace::TraceNode::new(&value, "<root>")
+        });
+        let (root, error) = match root {
+            Ok(root) => (Some(root), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        trace::CoercionTrace {
+            target: self.target.to_string(),
+            allow_partials,
+            root,
+            error,
+        }
+    }
+
+    /// Validates each JSON document in `result` (JSONL lines, one code block per record, ...)
+    /// separately, for prompts that return many records in one completion.
+    ///
+    /// Returns one result per document, in order; text around the documents is ignored. An
+    /// output without any JSON object or array is validated as a single document.
+    pub fn validate_documents(
+        &self,
+        result: &str,
+        allow_partials: bool,
+    ) -> Vec<anyhow::Result<String>> {
+        let documents = documents::split(result);
+        if documents.is_empty() {
+            return vec![self.validate_result(result, allow_partials)];
+        }
+        documents
+            .into_iter()
+            .map(|range| self.validate_result(&result[range], allow_partials))
+            .collect()
+    }
+
+    /// Like [`validate_result`](Self::validate_result), but fields absent from the output are
+    /// left out of the result instead of being `null` (or defaulted), so that callers can tell
+    /// "absent" from "present with null".
+    pub fn validate_result_omitting_missing(
+        &self,
+        result: &str,
+        allow_partials: bool,
+    ) -> anyhow::Result<String> {
+        self.validate(result, allow_partials, |value| {
+            self.to_json(presence::without_missing_fields(value))
+        })
+    }
+
+    fn to_json(&self, value: BamlValue) -> String {
+        to_json(self.float_format.apply(value))
+    }
+
+    fn validate<T>(
+        &self,
+        result: &str,
+        allow_partials: bool,
+        finish: impl FnOnce(BamlValueWithFlags) -> T,
+    ) -> anyhow::Result<T> {
+        #[cfg(feature = "otel")]
+        let span = tracing::info_span!(
+            "baml.validate_result",
+            target = %self.target,
+            allow_partials,
+            schema_hash = %self.schema_hash,
+            outcome = tracing::field::Empty,
+        )
+        .entered();
+        let start = Instant::now();
+        let refusal = self.refusal_detector.as_ref().and_then(|detector| detector.detect(result));
+        let result = match refusal {
+            Some(refusal) => Err(anyhow::Error::new(refusal)),
+            None => jsonish::from_str_with_options(
+                &self.format,
+                &self.target,
+                result,
+                allow_partials,
+                &self.coerce_options,
+            ),
+        };
+        let parse_duration = start.elapsed();
+        #[cfg(feature = "otel")]
+        span.record(
+            "outcome",
+            match &result {
+                Ok(_) => "success",
+                Err(e) if e.is::<Refusal>() => "refusal",
+                Err(_) => "failure",
+            },
+        );
+
+        let mut flag_counts = BTreeMap::new();
+        let mut field_flag_counts = BTreeMap::new();
+        let outcome = self.validation_sink.as_ref().map(|_| match &result {
+            Ok(value) => {
+                events::count_flags(value, "<root>", &mut flag_counts, &mut field_flag_counts);
+                ValidationOutcome::Success
+            }
+            Err(e) => match e.downcast_ref::<Refusal>() {
+                Some(refusal) => ValidationOutcome::Refusal { text: refusal.text.clone() },
+                None => ValidationOutcome::Failure { error: e.to_string() },
+            },
+        });
+        let result = result.map(finish);
+
+        if let (Some(sink), Some(outcome)) = (&self.validation_sink, outcome) {
+            sink.record(&ValidationEvent {
+                schema_hash: self.schema_hash.clone(),
+                target: self.target.to_string(),
+                allow_partials,
+                outcome,
+                flag_counts,
+                field_flag_counts,
+                parse_duration,
+                total_duration: start.elapsed(),
+            });
+        }
+        result.map_err(|e| self.localize_error(e))
+    }
+
+    /// `error` in the locale of the message catalog, if any. Coercion errors can still be
+    /// downcast to [`jsonish::CoerceError`].
+    fn localize_error(&self, error: anyhow::Error) -> anyhow::Error {
+        let Some(catalog) = &self.schema_options.message_catalog else {
+            return error;
+        };
+        let Some(coerce_error) = error.downcast_ref::<jsonish::CoerceError>() else {
+            return error;
+        };
+        let localized = coerce_error.localized(catalog.as_ref());
+        error.context(localized)
+    }
+
     fn build_target_type(
         validated_schema: &ValidatedSchema,
         target_name: Option<String>,
+        version: Option<&SchemaVersion>,
     ) -> anyhow::Result<FieldType> {
         let target = if let Some(target_name) = &target_name {
-            let target = validated_schema.db.find_type_by_str(target_name).unwrap();
-            match target {
-                TypeWalker::Class(cl) => FieldType::Class(cl.ast_type_block().name.name().to_string()),
-                TypeWalker::Enum(enm) => FieldType::Enum(enm.ast_type_block().name.name().to_string()),
-                TypeWalker::TypeAlias(alias) => FieldType::RecursiveTypeAlias(alias.name().to_string()),
+            // `Invoice, Receipt` stands for the union of the types.
+            let names = target_name.split(',').map(str::trim).collect::<Vec<_>>();
+            if names.iter().any(|name| name.is_empty()) {
+                anyhow::bail!("Invalid target `{target_name}`: expected comma-separated type names");
+            }
+            match names.as_slice() {
+                [name] => Self::named_target_type(validated_schema, name, version)?,
+                _ => FieldType::Union(
+                    names
+                        .iter()
+                        .map(|name| Self::named_target_type(validated_schema, name, version))
+                        .collect::<anyhow::Result<_>>()?,
+                ),
             }
         } else {
-            let first_class = validated_schema.db.walk_classes().next();
-            let first_enum = validated_schema.db.walk_enums().next();
+            let first_class = validated_schema.db.walk_classes()
+                .find(|c| version::is_available(version, c.get_default_attributes(SubType::Class)));
+            let first_enum = validated_schema.db.walk_enums()
+                .find(|e| version::is_available(version, e.get_default_attributes(SubType::Enum)));
             if first_class.is_none() && first_enum.is_none() {
                 return Err(anyhow::anyhow!(
                     "No BAML `class` or `enum` found in the schema"
@@ -125,72 +944,342 @@ impl BamlContext {
         Ok(target)
     }
 
-    // The helper function
-    fn resolve_value(val: Option<&Option<UnresolvedValue<Span>>>) -> Option<String> {
-        let ctx = EvaluationContext::default();
+    /// The type of the class, enum, type alias or function named `target_name`.
+    fn named_target_type(
+        validated_schema: &ValidatedSchema,
+        target_name: &str,
+        version: Option<&SchemaVersion>,
+    ) -> anyhow::Result<FieldType> {
+        // A function stands for its declared return type.
+        if let Some(function) = validated_schema.db.find_function_by_name(target_name) {
+            let Some(output) = function.ast_function().output() else {
+                anyhow::bail!("Function `{target_name}` has no return type");
+            };
+            let output = to_raw_field_type(&output.field_type, &validated_schema.db);
+            for name in version::referenced_types(&output) {
+                let available = match validated_schema.db.find_type_by_str(name) {
+                    Some(TypeWalker::Class(cl)) => version::is_available(version, cl.get_default_attributes(SubType::Class)),
+                    Some(TypeWalker::Enum(enm)) => version::is_available(version, enm.get_default_attributes(SubType::Enum)),
+                    _ => true,
+                };
+                if !available {
+                    return Err(anyhow::anyhow!(
+                        "Function `{}` returns `{}`, which is not part of schema version {}",
+                        target_name,
+                        name,
+                        version.map(|v| v.to_string()).unwrap_or_default()
+                    ));
+                }
+            }
+            return Ok(output);
+        }
+        let Some(target) = validated_schema.db.find_type_by_str(target_name) else {
+            anyhow::bail!("No class, enum, type alias or function named `{target_name}`");
+        };
+        let available = match &target {
+            TypeWalker::Class(cl) => version::is_available(version, cl.get_default_attributes(SubType::Class)),
+            TypeWalker::Enum(enm) => version::is_available(version, enm.get_default_attributes(SubType::Enum)),
+            TypeWalker::TypeAlias(_) => true,
+        };
+        if !available {
+            return Err(anyhow::anyhow!(
+                "`{}` is not part of schema version {}",
+                target_name,
+                version.map(|v| v.to_string()).unwrap_or_default()
+            ));
+        }
+        Ok(match target {
+            TypeWalker::Class(cl) => FieldType::Class(cl.ast_type_block().name.name().to_string()),
+            TypeWalker::Enum(enm) => FieldType::Enum(enm.ast_type_block().name.name().to_string()),
+            TypeWalker::TypeAlias(alias) => alias_field_type(alias.name(), alias, &validated_schema.db),
+        })
+    }
+
+    /// Resolves an attribute value such as `@alias`, which may read `env.NAME` directly or in
+    /// a Jinja expression.
+    fn resolve_value(
+        val: Option<&Option<UnresolvedValue<Span>>>,
+        ctx: &EvaluationContext<'_>,
+    ) -> anyhow::Result<Option<String>> {
         val.and_then(|d| d.as_ref())
-           .and_then(|d_ref| d_ref.as_str())
-           .and_then(|r_str| r_str.resolve(&ctx).ok())
+           .map(|d| d.resolve_string(ctx))
+           .transpose()
+    }
+
+    /// The schema name of the field or enum value of `parent` rendered as `rendered`, since
+    /// [`format`](Self::format) only keeps rendered (`@alias`) names.
+    pub(crate) fn schema_name(&self, parent: &str, rendered: &str) -> String {
+        let ctx = EvaluationContext::new(&self.schema_options.env_vars, false);
+        let matches = |name: &str, attributes: Option<&internal_baml_parser_database::Attributes>| {
+            Self::resolve_value(attributes.map(|a| a.alias()), &ctx)
+                .ok()
+                .flatten()
+                .as_deref()
+                .unwrap_or(name)
+                == rendered
+        };
+        let db = &self.validated_schema.db;
+        let name = match db.find_type_by_str(parent) {
+            Some(TypeWalker::Class(c)) => c
+                .static_fields()
+                .find(|f| matches(f.name(), f.get_default_attributes()))
+                .map(|f| f.name().to_string()),
+            Some(TypeWalker::Enum(e)) => e
+                .values()
+                .find(|v| matches(v.name(), v.get_default_attributes()))
+                .map(|v| v.name().to_string()),
+            _ => None,
+        };
+        name.unwrap_or_else(|| rendered.to_string())
+    }
+
+    /// `text` with each `{{ Type.member }}` reference replaced by the member's rendered name.
+    fn render_references(
+        db: &internal_baml_parser_database::ParserDatabase,
+        text: String,
+        ctx: &EvaluationContext<'_>,
+    ) -> anyhow::Result<String> {
+        let references = internal_baml_parser_database::description_references(&text);
+        if references.is_empty() {
+            return Ok(text);
+        }
+        let mut rendered = String::with_capacity(text.len());
+        let mut offset = 0;
+        for (range, ty, member) in references {
+            let attributes = match db.find_type_by_str(ty) {
+                Some(TypeWalker::Class(c)) => c
+                    .static_fields()
+                    .find(|f| f.name() == member)
+                    .map(|f| f.get_default_attributes()),
+                Some(TypeWalker::Enum(e)) => e.find_value(member).map(|v| v.get_default_attributes()),
+                _ => None,
+            };
+            // Unvalidated text such as doc comments may hold references to nothing.
+            let Some(attributes) = attributes else {
+                continue;
+            };
+            let alias = Self::resolve_value(attributes.map(|a| a.alias()), ctx)
+                .with_context(|| format!("Failed to resolve the alias of `{ty}.{member}`"))?;
+            rendered.push_str(&text[offset..range.start]);
+            rendered.push_str(alias.as_deref().unwrap_or(member));
+            offset = range.end;
+        }
+        rendered.push_str(&text[offset..]);
+        Ok(rendered)
     }
 
     fn build_output_format(
         validated_schema: &ValidatedSchema,
         target: FieldType,
-    ) -> OutputFormatContent {
-        let enums = validated_schema
+        version: Option<&SchemaVersion>,
+        options: &SchemaOptions,
+    ) -> anyhow::Result<OutputFormatContent> {
+        let ctx = EvaluationContext::new(&options.env_vars, false);
+        // Doc comments stand in for a missing `@description` when enabled.
+        let describe = |description: Option<String>, documentation: Option<&str>| {
+            description.or_else(|| {
+                documentation
+                    .filter(|_| options.doc_comment_descriptions)
+                    .map(|d| d.trim().to_string())
+                    .filter(|d| !d.is_empty())
+            })
+        };
+        let open_enums = validated_schema
+            .db
+            .types_with_attribute("open")
+            .filter_map(|t| match t {
+                TypeWalker::Enum(e) => Some(e.id),
+                _ => None,
+            })
+            .collect::<std::collections::HashSet<_>>();
+        let mut enums = validated_schema
             .db
             .walk_enums()
+            .filter(|e| version::is_available(version, e.get_default_attributes(SubType::Enum)))
             .map(|e| {
                 let values = e.values()
+                    .filter(|v| version::is_available(version, v.get_default_attributes()))
                     .map(|v| {
                         let name = v.name().to_string();
-                        let alias = Self::resolve_value(
+                        let resolve = |value| {
+                            Self::resolve_value(value, &ctx)
+                                .with_context(|| format!("Failed to resolve an attribute of `{}.{}`", e.name(), name))
+                        };
+                        let alias = resolve(
                                 v.get_default_attributes()
                                 .map(|a| a.alias())
-                        );
-                        let description = Self::resolve_value(
+                        )?;
+                        let description = describe(
+                            resolve(v.get_default_attributes().map(|a| a.description()))?,
+                            v.documentation(),
+                        )
+                        .map(|d| Self::render_references(&validated_schema.db, d, &ctx))
+                        .transpose()?;
+                        let deprecated = resolve(
+                            v.get_default_attributes()
+                            .map(|a| a.deprecated())
+                        )?;
+                        let group = resolve(
                             v.get_default_attributes()
-                            .map(|a| a.description())
-                        );
-                        // let doc = v.documentation().map(|d| d.to_string());
-                        (internal_baml_jinja::types::Name::new(alias.unwrap_or(name)), description)
+                            .map(|a| a.group())
+                        )?;
+                        let metadata = internal_baml_jinja::types::EnumValueMetadata { deprecated, group };
+                        Ok((internal_baml_jinja::types::Name::new(alias.unwrap_or(name)), description, metadata))
                     })
-                    .collect::<Vec<_>>();
-                internal_baml_jinja::types::Enum {
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                Ok(internal_baml_jinja::types::Enum {
                     name: Name::new(e.name().to_string()),
                     values,
                     constraints: e.get_constraints(SubType::Enum).unwrap_or(vec![]),
-                }
+                    open: open_enums.contains(&e.id),
+                })
             })
-            .collect::<Vec<_>>();
+            .collect::<anyhow::Result<Vec<_>>>()?;
 
-        let classes = validated_schema
+        let mut classes = validated_schema
             .db
             .walk_classes()
+            .filter(|c| version::is_available(version, c.get_default_attributes(SubType::Class)))
             .map(|c| {
                 let fields = c.static_fields()
+                    .filter(|f| version::is_available(version, f.get_default_attributes()))
                     .map(|f| {
                         let name = f.name().to_string();
                         let t = f.r#type().clone().expect(&format!("Cannot retrieve type from field {}", f.name()));
                         let field_type = to_raw_field_type(&t, &validated_schema.db);
-                        let alias = Self::resolve_value(
+                        let resolve = |value| {
+                            Self::resolve_value(value, &ctx)
+                                .with_context(|| format!("Failed to resolve an attribute of `{}.{}`", c.name(), name))
+                        };
+                        let alias = resolve(
                                 f.get_default_attributes()
                                 .map(|a| a.alias())
-                        );
-                        let description = Self::resolve_value(
+                        )?;
+                        let description = describe(
+                            resolve(f.get_default_attributes().map(|a| a.description()))?,
+                            f.get_documentation().as_deref(),
+                        )
+                        .map(|d| Self::render_references(&validated_schema.db, d, &ctx))
+                        .transpose()?;
+                        let deprecated = resolve(
+                            f.get_default_attributes()
+                            .map(|a| a.deprecated())
+                        )?;
+                        let coercer = resolve(
                             f.get_default_attributes()
-                            .map(|a| a.description())
-                        );
-                        (internal_baml_jinja::types::Name::new(alias.unwrap_or(name)), field_type, description)
+                            .map(|a| a.coerce())
+                        )?;
+                        let required_if = f.get_default_attributes()
+                            .and_then(|a| a.required_if().clone());
+                        let normalize_to_list = f.get_default_attributes()
+                            .is_some_and(|a| a.normalize_to_list().unwrap_or(false));
+                        let int_overflow = f.get_default_attributes()
+                            .and_then(|a| a.int_overflow().as_deref())
+                            .map(|policy| policy.parse::<IntOverflow>())
+                            .transpose()
+                            .with_context(|| format!("Invalid @int_overflow on `{}.{}`", c.name(), name))?;
+                        let null_values = f.get_default_attributes()
+                            .and_then(|a| a.null_values().clone());
+                        // Deprecated fields are not rendered, so the LLM may leave them out.
+                        let field_type = if deprecated.is_some() && !field_type.is_optional() {
+                            FieldType::Optional(Box::new(field_type))
+                        } else {
+                            field_type
+                        };
+                        let metadata = internal_baml_jinja::types::FieldMetadata {
+                            deprecated,
+                            coercer,
+                            required_if,
+                            normalize_to_list,
+                            int_overflow,
+                            null_values,
+                        };
+                        Ok((internal_baml_jinja::types::Name::new(alias.unwrap_or(name)), field_type, description, metadata))
                     })
-                    .collect::<Vec<_>>();
-                internal_baml_jinja::types::Class {
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                // `@@one_of` lists schema field names, but parsed fields are keyed by alias.
+                let keys = c.static_fields()
+                    .filter(|f| version::is_available(version, f.get_default_attributes()))
+                    .map(|f| {
+                        let alias = Self::resolve_value(
+                            f.get_default_attributes()
+                            .map(|a| a.alias()),
+                            &ctx,
+                        )?;
+                        Ok((f.name().to_string(), alias.unwrap_or_else(|| f.name().to_string())))
+                    })
+                    .collect::<anyhow::Result<HashMap<_, _>>>()?;
+                let one_of = c.get_default_attributes(SubType::Class)
+                    .map(|a| a.one_of().iter()
+                        .map(|group| group.iter().filter_map(|f| keys.get(f).cloned()).collect::<Vec<_>>())
+                        .filter(|group| !group.is_empty())
+                        .collect())
+                    .unwrap_or_default();
+                Ok(internal_baml_jinja::types::Class {
                     name: Name::new(c.name().to_string()),
                     fields,
                     constraints: c.get_constraints(SubType::Class).unwrap_or(vec![]),
+                    one_of,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        enums.extend(literal_enums::lower(&mut classes));
+        if let Some(version) = version {
+            // A field must not refer to a type that is not part of the version.
+            let available = classes.iter().map(|c| c.name.real_name())
+                .chain(enums.iter().map(|e| e.name.real_name()))
+                .collect::<std::collections::HashSet<_>>();
+            for class in &classes {
+                for (name, field_type, ..) in &class.fields {
+                    if let Some(missing) = version::referenced_types(field_type)
+                        .into_iter()
+                        .find(|t| !available.contains(t))
+                    {
+                        return Err(anyhow::anyhow!(
+                            "Field `{}.{}` refers to `{}`, which is not part of schema version {}",
+                            class.name.real_name(), name.real_name(), missing, version
+                        ));
+                    }
                 }
+            }
+        }
+        let class_rendering = validated_schema
+            .db
+            .walk_classes()
+            .filter_map(|c| {
+                let overrides = c.get_default_attributes(SubType::Class)?.render().as_ref()?;
+                let rendering = internal_baml_jinja::types::ClassRendering {
+                    hoisted: overrides.hoisted.unwrap_or(false),
+                    field_separator: overrides.separator.clone(),
+                    descriptions: overrides.descriptions.unwrap_or(true),
+                };
+                Some((c.name().to_string(), rendering))
             })
-            .collect::<Vec<_>>();
-        OutputFormatContent::target(target.clone()).enums(enums).classes(classes).build()
+            .collect();
+        // Classes in a cycle are rendered by reference where they recur, not inlined forever.
+        let recursive_classes = validated_schema
+            .db
+            .finite_recursive_cycles()
+            .iter()
+            .flatten()
+            .map(|id| validated_schema.db.ast()[*id].name().to_string())
+            .collect();
+        Ok(OutputFormatContent::target(target.clone())
+            .enums(enums)
+            .classes(classes)
+            .recursive_classes(recursive_classes)
+            .structural_recursive_aliases(recursive_aliases(&validated_schema.db))
+            .class_rendering(class_rendering)
+            .build())
     }
 }
+
+/// The JSON returned by `validate_result`.
+fn to_json(value: BamlValue) -> String {
+    // BAML serializes values using `serde_json::json!` which adds quotes around strings.
+    // Enum result is a JSON string, so remove quotes around it.
+    serde_json::json!(&value)
+        .to_string()
+        .trim_matches('"')
+        .to_string()
+}
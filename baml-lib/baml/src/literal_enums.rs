@@ -0,0 +1,99 @@
+//! Unions of string literals, like `"draft" | "published"`, as anonymous enums.
+//!
+//! As enums they render as a list of values and parse with the enum matcher, which tolerates
+//! case and punctuation differences that literal comparison does not.
+
+use baml_types::{FieldType, LiteralValue};
+use internal_baml_jinja::types::{Class, Enum, EnumValueMetadata, Name};
+
+/// Replaces the string literal unions in class fields with enums and returns those enums.
+///
+/// An enum is named after the first field using it (`Class.field`), which no schema type can be
+/// named; unions with the same values share one enum.
+pub(crate) fn lower(classes: &mut [Class]) -> Vec<Enum> {
+    let mut enums = Vec::new();
+    for class in classes.iter_mut() {
+        let class_name = class.name.real_name().to_string();
+        for (name, field_type, ..) in class.fields.iter_mut() {
+            let enum_name = format!("{class_name}.{}", name.real_name());
+            lower_field_type(field_type, &enum_name, &mut enums);
+        }
+    }
+    enums
+}
+
+fn lower_field_type(field_type: &mut FieldType, enum_name: &str, enums: &mut Vec<Enum>) {
+    match field_type {
+        FieldType::Union(items) => match string_literals(items) {
+            Some(values) => *field_type = FieldType::Enum(enum_for(values, enum_name, enums)),
+            None => items
+                .iter_mut()
+                .for_each(|item| lower_field_type(item, enum_name, enums)),
+        },
+        FieldType::List(inner) | FieldType::Optional(inner) => {
+            lower_field_type(inner, enum_name, enums)
+        }
+        // Map keys stay as they are: literal keys are matched exactly.
+        FieldType::Map(_, value) => lower_field_type(value, enum_name, enums),
+        FieldType::Tuple(items) => items
+            .iter_mut()
+            .for_each(|item| lower_field_type(item, enum_name, enums)),
+        FieldType::Constrained { base, .. } => lower_field_type(base, enum_name, enums),
+        FieldType::Primitive(_)
+        | FieldType::Enum(_)
+        | FieldType::Literal(_)
+        | FieldType::Class(_)
+        | FieldType::RecursiveTypeAlias(_) => {}
+    }
+}
+
+/// Whether `name` is one of the enums made by [`lower`].
+pub(crate) fn is_lowered(name: &str) -> bool {
+    name.contains('.')
+}
+
+/// The values of a union of at least two string literals.
+fn string_literals(items: &[FieldType]) -> Option<Vec<String>> {
+    if items.len() < 2 {
+        return None;
+    }
+    items
+        .iter()
+        .map(|item| match item {
+            FieldType::Literal(LiteralValue::String(value)) => Some(value.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The name of the enum with `values`, added under `name` (or `name.2`, ...) if new.
+fn enum_for(values: Vec<String>, name: &str, enums: &mut Vec<Enum>) -> String {
+    let same_values = |e: &&Enum| {
+        e.values.len() == values.len()
+            && e.values
+                .iter()
+                .zip(&values)
+                .all(|((n, ..), v)| n.real_name() == v)
+    };
+    if let Some(existing) = enums.iter().find(same_values) {
+        return existing.name.real_name().to_string();
+    }
+    let taken = |candidate: &str| enums.iter().any(|e| e.name.real_name() == candidate);
+    let mut unique = name.to_string();
+    for n in 2.. {
+        if !taken(&unique) {
+            break;
+        }
+        unique = format!("{name}.{n}");
+    }
+    enums.push(Enum {
+        name: Name::new(unique.clone()),
+        values: values
+            .into_iter()
+            .map(|v| (Name::new(v), None, EnumValueMetadata::default()))
+            .collect(),
+        constraints: Vec::new(),
+        open: false,
+    });
+    unique
+}
@@ -0,0 +1,548 @@
+//! Language server for BAML schemas.
+//!
+//! Enabled with the `lsp` feature. All open documents are validated together so
+//! that types can be shared across files. [`Analysis`] holds the editor-agnostic
+//! queries; [`Backend`] wires them to the LSP protocol with `tower-lsp`.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use internal_baml_core::{
+    ast::{Top, WithDocumentation, WithIdentifier, WithName, WithSpan},
+    internal_baml_parser_database::{BLOCK_ATTRIBUTES, FIELD_ATTRIBUTES},
+    internal_baml_schema_ast::{format_schema, FormatOptions},
+    SchemaSession,
+};
+use tower_lsp::{
+    jsonrpc,
+    lsp_types::{
+        CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams,
+        CompletionResponse, Diagnostic, DiagnosticSeverity, DidChangeTextDocumentParams,
+        DidCloseTextDocumentParams, DidOpenTextDocumentParams, DocumentFormattingParams,
+        GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents, HoverParams,
+        HoverProviderCapability, InitializeParams, InitializeResult, Location, MarkupContent,
+        MarkupKind, OneOf, Position, Range, ServerCapabilities, TextDocumentSyncCapability,
+        TextDocumentSyncKind, TextEdit, Url,
+    },
+    Client, LanguageServer, LspService, Server,
+};
+
+use crate::{SourceFile, Span, ValidatedSchema};
+
+const KEYWORDS: &[&str] = &[
+    "class",
+    "enum",
+    "function",
+    "type",
+    "template_string",
+    "client<llm>",
+    "retry_policy",
+    "generator",
+    "test",
+    "prompt_suite",
+    "const",
+];
+
+const PRIMITIVES: &[&str] = &[
+    "string", "int", "float", "bool", "bytes", "null", "image", "audio", "map",
+];
+
+/// A symbol definition found by [`Analysis::definition`].
+#[derive(Debug, PartialEq)]
+pub struct Definition {
+    pub path: PathBuf,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Validation results for a set of documents and the queries editors need.
+pub struct Analysis {
+    schema: Arc<ValidatedSchema>,
+}
+
+impl Analysis {
+    pub fn new(documents: impl IntoIterator<Item = (PathBuf, String)>) -> Self {
+        let files = documents
+            .into_iter()
+            .map(SourceFile::from)
+            .collect::<Vec<_>>();
+        Self {
+            schema: Arc::new(internal_baml_core::validate(&PathBuf::from("."), files)),
+        }
+    }
+
+    pub fn schema(&self) -> &ValidatedSchema {
+        &self.schema
+    }
+
+    fn top_by_name(&self, name: &str) -> Option<&Top> {
+        self.schema
+            .db
+            .ast()
+            .iter_tops()
+            .map(|(_, top)| top)
+            .find(|top| top.name() == name)
+    }
+
+    /// The top-level declaration named by the identifier at `offset` in `source`.
+    pub fn definition(&self, source: &str, offset: usize) -> Option<Definition> {
+        let word = word_at(source, offset)?;
+        let span = self.top_by_name(word)?.identifier().span();
+        Some(Definition {
+            path: span.file.path_buf().clone(),
+            start: span.start,
+            end: span.end,
+        })
+    }
+
+    /// Markdown describing the identifier at `offset` in `source`.
+    pub fn hover(&self, source: &str, offset: usize) -> Option<String> {
+        let word = word_at(source, offset)?;
+        if PRIMITIVES.contains(&word) {
+            return Some(format!("```baml\n{word}\n```\nBuilt-in type."));
+        }
+
+        let top = self.top_by_name(word)?;
+        let span = top.span();
+        let definition = &span.file.as_str()[span.start..span.end];
+        let mut hover = format!("```baml\n{}\n```", definition.trim());
+        let documentation = match top {
+            Top::Class(block) | Top::Enum(block) => block.documentation(),
+            _ => None,
+        };
+        if let Some(documentation) = documentation {
+            hover.push('\n');
+            hover.push_str(documentation);
+        }
+        Some(hover)
+    }
+
+    /// Names that can be completed anywhere in a schema.
+    pub fn completions(&self) -> Vec<(String, CompletionItemKind)> {
+        let db = &self.schema.db;
+        let types = db
+            .walk_classes()
+            .map(|c| (c.name().to_string(), CompletionItemKind::CLASS))
+            .chain(
+                db.walk_enums()
+                    .map(|e| (e.name().to_string(), CompletionItemKind::ENUM)),
+            )
+            .chain(
+                db.walk_type_aliases()
+                    .map(|a| (a.name().to_string(), CompletionItemKind::STRUCT)),
+            )
+            .chain(
+                db.walk_functions()
+                    .map(|f| (f.name().to_string(), CompletionItemKind::FUNCTION)),
+            );
+        let builtins = PRIMITIVES
+            .iter()
+            .map(|p| (p.to_string(), CompletionItemKind::TYPE_PARAMETER))
+            .chain(
+                KEYWORDS
+                    .iter()
+                    .map(|k| (k.to_string(), CompletionItemKind::KEYWORD)),
+            )
+            .chain(
+                FIELD_ATTRIBUTES
+                    .iter()
+                    .map(|a| format!("@{a}"))
+                    .chain(BLOCK_ATTRIBUTES.iter().map(|a| format!("@@{a}")))
+                    .map(|a| (a, CompletionItemKind::PROPERTY)),
+            );
+        types.chain(builtins).collect()
+    }
+
+    /// Errors and warnings, grouped by file path in path order.
+    pub fn diagnostics(&self) -> BTreeMap<PathBuf, Vec<(DiagnosticSeverity, String, Span)>> {
+        let diagnostics = &self.schema.diagnostics;
+        let errors = diagnostics
+            .errors()
+            .iter()
+            .map(|e| (DiagnosticSeverity::ERROR, e.message(), e.span()));
+        let warnings = diagnostics
+            .warnings()
+            .iter()
+            .map(|w| (DiagnosticSeverity::WARNING, w.message(), w.span()));
+
+        let mut by_file: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for (severity, message, span) in errors.chain(warnings) {
+            by_file
+                .entry(span.file.path_buf().clone())
+                .or_default()
+                .push((severity, message.to_string(), span.clone()));
+        }
+        by_file
+    }
+}
+
+/// The identifier surrounding byte `offset`, if any.
+pub fn word_at(source: &str, offset: usize) -> Option<&str> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    if offset > source.len() || !source.is_char_boundary(offset) {
+        return None;
+    }
+    let start = source[..offset]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_ident(*c))
+        .last()
+        .map_or(offset, |(idx, _)| idx);
+    let end = source[offset..]
+        .char_indices()
+        .find(|(_, c)| !is_ident(*c))
+        .map_or(source.len(), |(idx, _)| offset + idx);
+    (start < end).then(|| &source[start..end])
+}
+
+/// Converts a byte offset to an LSP position (UTF-16 columns).
+pub fn offset_to_position(source: &str, offset: usize) -> Position {
+    let offset = offset.min(source.len());
+    let line_start = source[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+    let line = source[..line_start].matches('\n').count();
+    let character = source[line_start..offset]
+        .chars()
+        .map(char::len_utf16)
+        .sum::<usize>();
+    Position::new(line as u32, character as u32)
+}
+
+/// Converts an LSP position (UTF-16 columns) to a byte offset.
+pub fn position_to_offset(source: &str, position: Position) -> usize {
+    let line_start = if position.line == 0 {
+        0
+    } else {
+        source
+            .match_indices('\n')
+            .nth(position.line as usize - 1)
+            .map_or(source.len(), |(idx, _)| idx + 1)
+    };
+    let mut utf16 = 0;
+    for (idx, c) in source[line_start..].char_indices() {
+        if utf16 >= position.character as usize || c == '\n' {
+            return line_start + idx;
+        }
+        utf16 += c.len_utf16();
+    }
+    source.len()
+}
+
+fn span_to_range(span: &Span) -> Range {
+    let source = span.file.as_str();
+    Range::new(
+        offset_to_position(source, span.start),
+        offset_to_position(source, span.end),
+    )
+}
+
+fn url_to_path(url: &Url) -> PathBuf {
+    url.to_file_path()
+        .unwrap_or_else(|_| PathBuf::from(url.as_str()))
+}
+
+fn path_to_url(path: &PathBuf) -> Option<Url> {
+    Url::from_file_path(path)
+        .ok()
+        .or_else(|| Url::parse(&path.to_string_lossy()).ok())
+}
+
+/// The `tower-lsp` language server.
+pub struct Backend {
+    client: Client,
+    documents: RwLock<HashMap<Url, String>>,
+    /// The parsed documents, so an edit only reparses the edited one, and their validated
+    /// schema, shared by the requests made until the next edit.
+    session: RwLock<SchemaSession>,
+}
+
+impl Backend {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            documents: RwLock::new(HashMap::new()),
+            session: RwLock::new(SchemaSession::new(Path::new("."))),
+        }
+    }
+
+    fn analysis(&self) -> Analysis {
+        Analysis {
+            schema: self.session.write().unwrap().validate(),
+        }
+    }
+
+    fn update_document(&self, url: Url, text: String) {
+        let file = SourceFile::from((url_to_path(&url), text.as_str()));
+        self.session.write().unwrap().update_file(file);
+        self.documents.write().unwrap().insert(url, text);
+    }
+
+    fn document(&self, url: &Url) -> Option<String> {
+        self.documents.read().unwrap().get(url).cloned()
+    }
+
+    async fn publish_diagnostics(&self) {
+        let urls = self
+            .documents
+            .read()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut diagnostics = self.analysis().diagnostics();
+        for url in urls {
+            let items = diagnostics
+                .remove(&url_to_path(&url))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(severity, message, span)| Diagnostic {
+                    range: span_to_range(&span),
+                    severity: Some(severity),
+                    source: Some("baml".to_string()),
+                    message,
+                    ..Default::default()
+                })
+                .collect();
+            self.client.publish_diagnostics(url, items, None).await;
+        }
+    }
+}
+
+#[tower_lsp::async_trait]
+impl LanguageServer for Backend {
+    async fn initialize(&self, _: InitializeParams) -> jsonrpc::Result<InitializeResult> {
+        Ok(InitializeResult {
+            capabilities: ServerCapabilities {
+                text_document_sync: Some(TextDocumentSyncCapability::Kind(
+                    TextDocumentSyncKind::FULL,
+                )),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                definition_provider: Some(OneOf::Left(true)),
+                completion_provider: Some(CompletionOptions {
+                    trigger_characters: Some(vec!["@".to_string()]),
+                    ..Default::default()
+                }),
+                document_formatting_provider: Some(OneOf::Left(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+    }
+
+    async fn shutdown(&self) -> jsonrpc::Result<()> {
+        Ok(())
+    }
+
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        self.update_document(params.text_document.uri, params.text_document.text);
+        self.publish_diagnostics().await;
+    }
+
+    async fn did_change(&self, mut params: DidChangeTextDocumentParams) {
+        // Full sync: the last change holds the whole document.
+        if let Some(change) = params.content_changes.pop() {
+            self.update_document(params.text_document.uri, change.text);
+        }
+        self.publish_diagnostics().await;
+    }
+
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        let uri = &params.text_document.uri;
+        self.session.write().unwrap().remove_file(&url_to_path(uri));
+        self.documents.write().unwrap().remove(uri);
+        self.client
+            .publish_diagnostics(params.text_document.uri, vec![], None)
+            .await;
+        self.publish_diagnostics().await;
+    }
+
+    async fn hover(&self, params: HoverParams) -> jsonrpc::Result<Option<Hover>> {
+        let position = params.text_document_position_params;
+        let Some(source) = self.document(&position.text_document.uri) else {
+            return Ok(None);
+        };
+        let offset = position_to_offset(&source, position.position);
+        Ok(self.analysis().hover(&source, offset).map(|value| Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: None,
+        }))
+    }
+
+    async fn goto_definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> jsonrpc::Result<Option<GotoDefinitionResponse>> {
+        let position = params.text_document_position_params;
+        let Some(source) = self.document(&position.text_document.uri) else {
+            return Ok(None);
+        };
+        let offset = position_to_offset(&source, position.position);
+        let Some(definition) = self.analysis().definition(&source, offset) else {
+            return Ok(None);
+        };
+        let Some(uri) = path_to_url(&definition.path) else {
+            return Ok(None);
+        };
+        let target = self.document(&uri).unwrap_or_default();
+        Ok(Some(GotoDefinitionResponse::Scalar(Location::new(
+            uri,
+            Range::new(
+                offset_to_position(&target, definition.start),
+                offset_to_position(&target, definition.end),
+            ),
+        ))))
+    }
+
+    async fn completion(&self, _: CompletionParams) -> jsonrpc::Result<Option<CompletionResponse>> {
+        let items = self
+            .analysis()
+            .completions()
+            .into_iter()
+            .map(|(label, kind)| CompletionItem {
+                label,
+                kind: Some(kind),
+                ..Default::default()
+            })
+            .collect();
+        Ok(Some(CompletionResponse::Array(items)))
+    }
+
+    async fn formatting(
+        &self,
+        params: DocumentFormattingParams,
+    ) -> jsonrpc::Result<Option<Vec<TextEdit>>> {
+        let Some(source) = self.document(&params.text_document.uri) else {
+            return Ok(None);
+        };
+        let options = FormatOptions {
+            indent_width: params.options.tab_size as isize,
+            fail_on_unhandled_rule: false,
+        };
+        let Ok(formatted) = format_schema(&source, options) else {
+            return Ok(None);
+        };
+        Ok(Some(vec![TextEdit::new(
+            Range::new(
+                Position::new(0, 0),
+                offset_to_position(&source, source.len()),
+            ),
+            formatted,
+        )]))
+    }
+}
+
+/// Run the language server over stdin/stdout.
+pub async fn serve_stdio() {
+    let (service, socket) = LspService::new(Backend::new);
+    Server::new(tokio::io::stdin(), tokio::io::stdout(), socket)
+        .serve(service)
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TYPES: &str = r#"
+/// A paint can.
+class Paint {
+  color Color
+  liters float
+}
+"#;
+
+    const COLORS: &str = r#"enum Color {
+  Red
+  Green
+}
+"#;
+
+    fn analysis() -> Analysis {
+        Analysis::new([
+            (PathBuf::from("types.baml"), TYPES.to_string()),
+            (PathBuf::from("colors.baml"), COLORS.to_string()),
+        ])
+    }
+
+    #[test]
+    fn words() {
+        assert_eq!(word_at("a Color[]", 4), Some("Color"));
+        assert_eq!(word_at("a Color[]", 2), Some("Color"));
+        assert_eq!(word_at("a Color[]", 7), Some("Color"));
+        assert_eq!(word_at("a  b", 2), None);
+    }
+
+    #[test]
+    fn positions_use_utf16_columns() {
+        let source = "héllo\n🦀 x";
+        let x = source.find('x').unwrap();
+        assert_eq!(offset_to_position(source, x), Position::new(1, 3));
+        assert_eq!(position_to_offset(source, Position::new(1, 3)), x);
+        assert_eq!(position_to_offset(source, Position::new(0, 2)), 3);
+    }
+
+    #[test]
+    fn definition_across_files() {
+        let offset = TYPES.find("Color").unwrap() + 1;
+        assert_eq!(
+            analysis().definition(TYPES, offset),
+            Some(Definition {
+                path: PathBuf::from("colors.baml"),
+                start: 5,
+                end: 10,
+            })
+        );
+    }
+
+    #[test]
+    fn hover_shows_declaration_and_docs() {
+        let offset = TYPES.find("Paint").unwrap();
+        let hover = analysis().hover(TYPES, offset).unwrap();
+        assert!(hover.starts_with("```baml\nclass Paint {"), "{hover}");
+        assert!(hover.ends_with("A paint can."), "{hover}");
+    }
+
+    #[test]
+    fn completions_include_schema_types() {
+        let completions = analysis().completions();
+        assert!(completions.contains(&("Paint".to_string(), CompletionItemKind::CLASS)));
+        assert!(completions.contains(&("Color".to_string(), CompletionItemKind::ENUM)));
+        assert!(completions.contains(&("@alias".to_string(), CompletionItemKind::PROPERTY)));
+    }
+
+    #[test]
+    fn completions_include_every_parsed_attribute() {
+        let completions = analysis().completions();
+        for attribute in [
+            "@group",
+            "@required_if",
+            "@deprecated",
+            "@@open",
+            "@@render",
+            "@@one_of",
+        ] {
+            let item = (attribute.to_string(), CompletionItemKind::PROPERTY);
+            assert!(completions.contains(&item), "{attribute}");
+        }
+        let item = ("@@group".to_string(), CompletionItemKind::PROPERTY);
+        assert!(!completions.contains(&item));
+    }
+
+    #[test]
+    fn diagnostics_are_grouped_by_file() {
+        let analysis = Analysis::new([(PathBuf::from("bad.baml"), "class A { b B }".to_string())]);
+        let diagnostics = analysis.diagnostics();
+        let items = &diagnostics[&PathBuf::from("bad.baml")];
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].0, DiagnosticSeverity::ERROR);
+        assert_eq!(
+            span_to_range(&items[0].2),
+            Range::new(Position::new(0, 12), Position::new(0, 13))
+        );
+    }
+}
@@ -0,0 +1,73 @@
+//! OpenTelemetry export of validation spans.
+//!
+//! With the `otel` feature, every [`BamlContext::validate_result`](crate::BamlContext::validate_result)
+//! call opens a `baml.validate_result` span (`target`, `allow_partials`, `schema_hash`, `outcome`)
+//! with one child per parsing stage:
+//!
+//! - `jsonish.parse`: `candidates`, the number of ways the raw output could be read;
+//! - `jsonish.extract`: JSON extraction from markdown or surrounding text, and JSON fixing;
+//! - `jsonish.coerce`: `target`, coercion of the candidates into the target type;
+//! - `jsonish.constraints`: `constraints`, evaluation of checks and asserts.
+//!
+//! The spans are ordinary `tracing` spans; install [`layer`] in the application's subscriber to
+//! export them alongside the rest of its traces.
+
+pub use opentelemetry;
+pub use tracing_opentelemetry::{layer, OpenTelemetryLayer};
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_sdk::{testing::trace::InMemorySpanExporter, trace::TracerProvider};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use crate::BamlContext;
+
+    #[test]
+    fn validate_result_exports_stage_spans() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(super::layer().with_tracer(provider.tracer("test")));
+
+        let schema =
+            "class Item {\n  qty int\n  @@check(positive, {{ this.qty > 0 }})\n}".to_string();
+        let ctx = BamlContext::try_from_schema(&schema, None).unwrap();
+        tracing::subscriber::with_default(subscriber, || {
+            ctx.validate_result("Sure:\n```json\n{\"qty\": 2}\n```", false)
+                .unwrap();
+        });
+        provider.force_flush();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let names = spans.iter().map(|s| s.name.as_ref()).collect::<Vec<_>>();
+        for stage in [
+            "baml.validate_result",
+            "jsonish.parse",
+            "jsonish.extract",
+            "jsonish.coerce",
+            "jsonish.constraints",
+        ] {
+            assert!(names.contains(&stage), "missing {stage} in {names:?}");
+        }
+
+        let root = spans
+            .iter()
+            .find(|s| s.name == "baml.validate_result")
+            .unwrap();
+        let attribute = |key: &str| {
+            root.attributes
+                .iter()
+                .find(|kv| kv.key.as_str() == key)
+                .map(|kv| kv.value.to_string())
+        };
+        assert_eq!(attribute("target").as_deref(), Some("Item"));
+        assert_eq!(attribute("outcome").as_deref(), Some("success"));
+        assert!(spans
+            .iter()
+            .filter(|s| s.name != "baml.validate_result")
+            .all(|s| s.span_context.trace_id() == root.span_context.trace_id()));
+    }
+}
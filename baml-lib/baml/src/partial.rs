@@ -0,0 +1,38 @@
+//! The types of partial results (`allow_partials`), in which anything the model has not
+//! finished writing yet may be `null`.
+
+use baml_types::{FieldType, TypeValue};
+
+/// The type of a class field in partial results: nullable, except for lists and maps, which
+/// default to empty.
+pub(crate) fn partial_field_type(field_type: &FieldType) -> FieldType {
+    match field_type {
+        FieldType::List(_) | FieldType::Map(..) | FieldType::Primitive(TypeValue::Null) => {
+            partial_value_type(field_type)
+        }
+        _ => FieldType::Optional(Box::new(partial_value_type(field_type))),
+    }
+}
+
+/// The type of a value present in partial results.
+///
+/// List items and map values that cannot be parsed yet are left out rather than set to `null`,
+/// so they keep their type. Classes keep their name and get partial fields. Constraints are
+/// dropped since unfinished values need not meet them.
+pub(crate) fn partial_value_type(field_type: &FieldType) -> FieldType {
+    match field_type {
+        FieldType::Optional(inner) => partial_value_type(inner),
+        FieldType::Constrained { base, .. } => partial_value_type(base),
+        FieldType::List(item) => FieldType::List(Box::new(partial_value_type(item))),
+        FieldType::Map(key, value) => {
+            FieldType::Map(key.clone(), Box::new(partial_value_type(value)))
+        }
+        FieldType::Tuple(items) => FieldType::Tuple(items.iter().map(partial_field_type).collect()),
+        FieldType::Union(items) => FieldType::Union(items.iter().map(partial_value_type).collect()),
+        FieldType::Primitive(_)
+        | FieldType::Enum(_)
+        | FieldType::Literal(_)
+        | FieldType::Class(_)
+        | FieldType::RecursiveTypeAlias(_) => field_type.clone(),
+    }
+}
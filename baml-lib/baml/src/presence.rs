@@ -0,0 +1,39 @@
+//! Telling fields absent from the LLM output apart from fields set to `null`.
+
+use baml_types::BamlValue;
+use jsonish::{deserializer::deserialize_flags::Flag, BamlValueWithFlags};
+
+/// Whether `value` was filled in because its field was absent from the output.
+fn is_missing(value: &BamlValueWithFlags) -> bool {
+    value.conditions().flags().iter().any(|flag| {
+        matches!(
+            flag,
+            Flag::OptionalDefaultFromNoValue | Flag::DefaultFromNoValue
+        )
+    })
+}
+
+/// Converts `value` like `BamlValue::from`, but leaves out class fields that were absent from
+/// the output instead of setting them to `null` (or their default).
+pub(crate) fn without_missing_fields(value: BamlValueWithFlags) -> BamlValue {
+    match value {
+        BamlValueWithFlags::Class(name, _, fields) => BamlValue::Class(
+            name,
+            fields
+                .into_iter()
+                .filter(|(_, field)| !is_missing(field))
+                .map(|(key, field)| (key, without_missing_fields(field)))
+                .collect(),
+        ),
+        BamlValueWithFlags::List(_, items) => {
+            BamlValue::List(items.into_iter().map(without_missing_fields).collect())
+        }
+        BamlValueWithFlags::Map(_, entries) => BamlValue::Map(
+            entries
+                .into_iter()
+                .map(|(key, (_, value))| (key, without_missing_fields(value)))
+                .collect(),
+        ),
+        value => value.into(),
+    }
+}
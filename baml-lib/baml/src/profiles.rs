@@ -0,0 +1,77 @@
+//! Named render profiles, to compare prompt formats on one context.
+
+use internal_baml_jinja::types::{FieldOrder, RenderFormat, RenderOptions, RenderStyle};
+
+use crate::BamlContext;
+
+/// One way of rendering the prompt, registered under a name with
+/// [`BamlContext::with_render_profile`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RenderProfile {
+    pub style: RenderStyle,
+    pub format: RenderFormat,
+    pub field_order: FieldOrder,
+    /// Replaces the default prefix (`Answer in JSON using this schema:`, ...) when set.
+    pub prefix: Option<String>,
+    pub always_hoist_enums: Option<bool>,
+    /// Text before the rendered schema, separated by a blank line.
+    pub header: Option<String>,
+    /// Text after the rendered schema, separated by a blank line.
+    pub footer: Option<String>,
+}
+
+/// A prompt rendered with a [`RenderProfile`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedPrompt {
+    pub prompt: String,
+    /// The name of the profile, to attribute outcomes to it.
+    pub profile: String,
+}
+
+impl BamlContext {
+    /// Register `profile` as `name`, replacing any profile of that name. The context's own
+    /// [field order](Self::with_field_order) and [style](Self::with_render_style) keep
+    /// applying to [`render_prompt`](Self::render_prompt).
+    pub fn with_render_profile(mut self, name: &str, profile: RenderProfile) -> Self {
+        self.render_profiles.insert(name.to_string(), profile);
+        self
+    }
+
+    /// The names of the registered render profiles, sorted.
+    pub fn render_profiles(&self) -> Vec<&str> {
+        self.render_profiles.keys().map(String::as_str).collect()
+    }
+
+    /// Render the prompt with the profile registered as `name`.
+    pub fn render_prompt_with_profile(&self, name: &str) -> anyhow::Result<RenderedPrompt> {
+        let Some(profile) = self.render_profiles.get(name) else {
+            anyhow::bail!(
+                "Unknown render profile `{name}`, expected one of: {}",
+                self.render_profiles().join(", ")
+            );
+        };
+        let schema = self.render_with(
+            RenderOptions::new(
+                profile.prefix.clone().map(Some),
+                None,
+                None,
+                profile.always_hoist_enums,
+                None,
+                None,
+            )
+            .with_field_order(profile.field_order)
+            .with_style(profile.style)
+            .with_format(profile.format),
+        )?;
+        let prompt = [profile.header.as_deref(), Some(schema.as_str()), profile.footer.as_deref()]
+            .into_iter()
+            .flatten()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        Ok(RenderedPrompt {
+            prompt,
+            profile: name.to_string(),
+        })
+    }
+}
@@ -0,0 +1,129 @@
+//! Byte ranges in the LLM output that parsed values came from.
+//!
+//! The parser does not keep positions, so values are looked up in the output after parsing:
+//! keys first, then their values after them. It is best effort: values that do not appear in
+//! the output as written, such as defaults for missing fields or numbers written differently
+//! (`2.50` parsed as `2.5`), get no range.
+
+use std::ops::Range;
+
+use baml_types::BamlValue;
+
+use crate::flagged::{FlaggedNode, FlaggedValue};
+
+/// Sets the span of `value` and its children, searching `output` from byte `from`, and returns
+/// where to continue searching after `value`.
+pub(crate) fn locate(value: &mut FlaggedValue, output: &str, from: usize) -> usize {
+    let defaulted = value.flags.iter().any(|flag| {
+        matches!(
+            flag.kind.as_str(),
+            "DefaultFromNoValue" | "OptionalDefaultFromNoValue"
+        )
+    });
+    if defaulted {
+        return from;
+    }
+    let mut end = from;
+    value.span = match &mut value.node {
+        FlaggedNode::Value { value } => needles(value)
+            .iter()
+            .find_map(|needle| find(output, from, needle)),
+        FlaggedNode::List { items } => {
+            for item in items.iter_mut() {
+                end = locate(item, output, end);
+            }
+            let span = cover(items.iter().filter_map(|item| item.span.clone()));
+            span.map(|span| widen(output, span, '[', ']'))
+        }
+        FlaggedNode::Map { entries: children } | FlaggedNode::Class { fields: children, .. } => {
+            let mut spans = Vec::new();
+            // Fields may be in a different order than the model wrote them, so each key is
+            // searched from the start of the object.
+            for (key, child) in children.iter_mut() {
+                let Some((key_start, value_start)) = find_key(output, from, key) else {
+                    continue;
+                };
+                end = end.max(locate(child, output, value_start));
+                spans.push(key_start..value_start);
+                spans.extend(child.span.clone());
+            }
+            cover(spans.into_iter()).map(|span| widen(output, span, '{', '}'))
+        }
+    };
+    value.span.as_ref().map_or(end, |span| span.end.max(end))
+}
+
+/// The ways a scalar may be written in the output.
+fn needles(value: &BamlValue) -> Vec<String> {
+    match value {
+        BamlValue::String(s) | BamlValue::Enum(_, s) if !s.is_empty() => {
+            let escaped = serde_json::to_string(s).unwrap_or_default();
+            let escaped = escaped.trim_matches('"').to_string();
+            if escaped == *s {
+                vec![s.clone()]
+            } else {
+                vec![s.clone(), escaped]
+            }
+        }
+        BamlValue::Int(i) => vec![i.to_string()],
+        BamlValue::Float(f) => vec![f.to_string()],
+        BamlValue::Decimal(d) => vec![d.clone()],
+        BamlValue::Bool(b) => vec![b.to_string()],
+        _ => Vec::new(),
+    }
+}
+
+/// The first occurrence of `needle` in `output` at or after `from`, ignoring ASCII case if
+/// there is no exact match.
+fn find(output: &str, from: usize, needle: &str) -> Option<Range<usize>> {
+    let haystack = output.get(from..)?;
+    let start = haystack.find(needle).or_else(|| {
+        haystack
+            .to_ascii_lowercase()
+            .find(&needle.to_ascii_lowercase())
+    })?;
+    Some(from + start..from + start + needle.len())
+}
+
+/// The first `key:` (or `"key":`) at or after `from`: where the key starts and where its value
+/// can start.
+fn find_key(output: &str, from: usize, key: &str) -> Option<(usize, usize)> {
+    if key.is_empty() {
+        return None;
+    }
+    let mut search = from;
+    while let Some(found) = find(output, search, key) {
+        search = found.end;
+        let rest = &output[found.end..];
+        let after_quote = rest.strip_prefix(['"', '\'']).unwrap_or(rest);
+        let Some(value) = after_quote.trim_start().strip_prefix(':') else {
+            continue;
+        };
+        let quoted = output[..found.start].ends_with(['"', '\'']);
+        let key_start = if quoted { found.start - 1 } else { found.start };
+        return Some((key_start, output.len() - value.len()));
+    }
+    None
+}
+
+/// The smallest range containing all `spans`.
+fn cover(spans: impl Iterator<Item = Range<usize>>) -> Option<Range<usize>> {
+    spans.reduce(|a, b| a.start.min(b.start)..a.end.max(b.end))
+}
+
+/// Extends `span` to the brackets around it, if only whitespace (or the quotes of a string)
+/// separates them.
+fn widen(output: &str, span: Range<usize>, open: char, close: char) -> Range<usize> {
+    let quotes = ['"', '\''];
+    let before = output[..span.start].trim_end_matches(quotes).trim_end();
+    let after = output[span.end..].trim_start_matches(quotes).trim_start();
+    let start = match before.strip_suffix(open) {
+        Some(rest) => rest.len(),
+        None => span.start,
+    };
+    let end = match after.strip_prefix(close) {
+        Some(rest) => output.len() - rest.len(),
+        None => span.end,
+    };
+    start..end
+}
@@ -0,0 +1,274 @@
+//! Python `pydantic` models for the classes and enums of a schema.
+
+use std::collections::{BTreeSet, VecDeque};
+
+use baml_types::{FieldType, LiteralValue, TypeValue};
+use internal_baml_jinja::types::OutputFormatContent;
+
+use crate::BamlContext;
+
+impl BamlContext {
+    /// Python source of `pydantic` (v2) models for every class and enum of the schema, to use
+    /// the output of [`validate_result`](Self::validate_result) as typed objects.
+    ///
+    /// Enums are `str` enums whose values are the rendered (`@alias`) names, and fields with an
+    /// `@alias` are keyed by it in JSON while keeping their schema name in Python. Optional
+    /// fields default to `None`, `@description`s become field descriptions and recursive type
+    /// aliases become `TypeAliasType`s. Names that are Python keywords get a trailing `_`.
+    pub fn to_pydantic(&self) -> anyhow::Result<String> {
+        emit(self)
+    }
+}
+
+fn emit(context: &BamlContext) -> anyhow::Result<String> {
+    let format = &context.format;
+    let mut emitter = Emitter {
+        format,
+        typing: BTreeSet::new(),
+        pydantic: BTreeSet::from(["BaseModel"]),
+        aliases: VecDeque::new(),
+        seen_aliases: BTreeSet::new(),
+    };
+
+    let mut body = String::new();
+    for enm in format.enums.values() {
+        body.push_str(&format!("\n\nclass {}(str, Enum):\n", python_name(enm.name.real_name())));
+        if enm.values.is_empty() {
+            body.push_str("    pass\n");
+        }
+        for (value, description, _) in &enm.values {
+            for line in description.iter().flat_map(|d| d.lines()) {
+                body.push_str(format!("    # {line}").trim_end());
+                body.push('\n');
+            }
+            body.push_str(&format!(
+                "    {} = {}\n",
+                python_name(&context.schema_name(enm.name.real_name(), value.rendered_name())),
+                string_literal(value.rendered_name())
+            ));
+        }
+    }
+
+    for class in format.classes.values() {
+        body.push_str(&format!("\n\nclass {}(BaseModel):\n", python_name(class.name.real_name())));
+        let attributes = class
+            .fields
+            .iter()
+            .map(|(name, ..)| {
+                python_name(&context.schema_name(class.name.real_name(), name.rendered_name()))
+            })
+            .collect::<Vec<_>>();
+        let aliased = class
+            .fields
+            .iter()
+            .zip(&attributes)
+            .any(|((name, ..), attribute)| attribute != name.rendered_name());
+        if aliased {
+            emitter.pydantic.insert("ConfigDict");
+            body.push_str("    model_config = ConfigDict(populate_by_name=True)\n\n");
+        }
+        if class.fields.is_empty() {
+            body.push_str("    pass\n");
+        }
+        for ((name, field_type, description, _), attribute) in class.fields.iter().zip(attributes) {
+            let annotation = emitter.annotation(field_type, false).map_err(|e| {
+                e.context(format!("In field `{}.{}`", class.name.real_name(), attribute))
+            })?;
+            let mut arguments = vec![];
+            if field_type.is_optional() {
+                arguments.push("default=None".to_string());
+            }
+            if attribute != name.rendered_name() {
+                arguments.push(format!("alias={}", string_literal(name.rendered_name())));
+            }
+            if let Some(description) = description {
+                arguments.push(format!("description={}", string_literal(description)));
+            }
+            let default = match arguments.as_slice() {
+                [] => String::new(),
+                [only] if only == "default=None" => " = None".to_string(),
+                _ => {
+                    emitter.pydantic.insert("Field");
+                    format!(" = Field({})", arguments.join(", "))
+                }
+            };
+            body.push_str(&format!("    {attribute}: {annotation}{default}\n"));
+        }
+    }
+
+    // Aliases are evaluated when the module is loaded, so they refer to models by string.
+    let mut aliases = String::new();
+    while let Some(name) = emitter.aliases.pop_front() {
+        let target = format.find_recursive_alias_target(&name)?;
+        let annotation = emitter.annotation(target, true)?;
+        aliases.push_str(&format!(
+            "\n{name} = TypeAliasType({}, {annotation})\n",
+            string_literal(&name)
+        ));
+    }
+
+    let mut source = String::from("from __future__ import annotations\n\n");
+    let mut standard = vec![];
+    if !format.enums.is_empty() {
+        standard.push("from enum import Enum".to_string());
+    }
+    if !emitter.typing.is_empty() {
+        standard.push(format!(
+            "from typing import {}",
+            emitter.typing.iter().copied().collect::<Vec<_>>().join(", ")
+        ));
+    }
+    if !standard.is_empty() {
+        source.push_str(&standard.join("\n"));
+        source.push_str("\n\n");
+    }
+    source.push_str(&format!(
+        "from pydantic import {}\n",
+        emitter.pydantic.iter().copied().collect::<Vec<_>>().join(", ")
+    ));
+    if !aliases.is_empty() {
+        source.push_str("from typing_extensions import TypeAliasType\n");
+        source.push_str(&aliases);
+    }
+    source.push_str(&body);
+    Ok(source)
+}
+
+struct Emitter<'f> {
+    format: &'f OutputFormatContent,
+    /// Names imported from `typing`.
+    typing: BTreeSet<&'static str>,
+    /// Names imported from `pydantic`.
+    pydantic: BTreeSet<&'static str>,
+    /// Recursive aliases still to define.
+    aliases: VecDeque<String>,
+    seen_aliases: BTreeSet<String>,
+}
+
+impl Emitter<'_> {
+    /// The Python annotation for `field_type`. With `quoted`, models and aliases are referred
+    /// to by string.
+    fn annotation(&mut self, field_type: &FieldType, quoted: bool) -> anyhow::Result<String> {
+        let reference = |name: &str| {
+            let name = python_name(name);
+            if quoted {
+                format!("\"{name}\"")
+            } else {
+                name
+            }
+        };
+        Ok(match field_type {
+            FieldType::Primitive(primitive) => match primitive {
+                TypeValue::String => "str".to_string(),
+                TypeValue::Int => "int".to_string(),
+                TypeValue::Float => "float".to_string(),
+                TypeValue::Bool => "bool".to_string(),
+                TypeValue::Null => "None".to_string(),
+                TypeValue::Bytes => {
+                    self.pydantic.insert("Base64Bytes");
+                    "Base64Bytes".to_string()
+                }
+                TypeValue::Media(media) => {
+                    anyhow::bail!("Type `{media}` is not supported in outputs")
+                }
+            },
+            FieldType::Literal(literal) => {
+                self.typing.insert("Literal");
+                match literal {
+                    LiteralValue::String(s) => format!("Literal[{}]", string_literal(s)),
+                    LiteralValue::Int(i) => format!("Literal[{i}]"),
+                    LiteralValue::Bool(b) => {
+                        format!("Literal[{}]", if *b { "True" } else { "False" })
+                    }
+                }
+            }
+            // Open enums also accept values other than their variants.
+            FieldType::Enum(name) if self.format.find_enum(name)?.open => {
+                self.typing.insert("Union");
+                format!("Union[{}, str]", reference(name))
+            }
+            FieldType::Enum(name) | FieldType::Class(name) => reference(name),
+            FieldType::RecursiveTypeAlias(name) => {
+                if self.seen_aliases.insert(name.clone()) {
+                    self.aliases.push_back(name.clone());
+                }
+                reference(name)
+            }
+            FieldType::List(item) => {
+                self.typing.insert("List");
+                format!("List[{}]", self.annotation(item, quoted)?)
+            }
+            FieldType::Map(key, value) => {
+                self.typing.insert("Dict");
+                format!(
+                    "Dict[{}, {}]",
+                    self.annotation(key, quoted)?,
+                    self.annotation(value, quoted)?
+                )
+            }
+            FieldType::Tuple(items) => {
+                self.typing.insert("Tuple");
+                let items = items
+                    .iter()
+                    .map(|item| self.annotation(item, quoted))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                format!("Tuple[{}]", items.join(", "))
+            }
+            FieldType::Union(items) => {
+                let nullable = items.iter().any(FieldType::is_null);
+                let mut annotations = vec![];
+                for item in items.iter().filter(|item| !item.is_null()) {
+                    let annotation = self.annotation(item, quoted)?;
+                    if !annotations.contains(&annotation) {
+                        annotations.push(annotation);
+                    }
+                }
+                let union = match annotations.as_slice() {
+                    [] => "None".to_string(),
+                    [only] => only.clone(),
+                    _ => {
+                        self.typing.insert("Union");
+                        format!("Union[{}]", annotations.join(", "))
+                    }
+                };
+                if nullable && union != "None" {
+                    self.typing.insert("Optional");
+                    format!("Optional[{union}]")
+                } else {
+                    union
+                }
+            }
+            FieldType::Optional(inner) => {
+                let inner = self.annotation(inner, quoted)?;
+                if inner.starts_with("Optional[") || inner == "None" {
+                    inner
+                } else {
+                    self.typing.insert("Optional");
+                    format!("Optional[{inner}]")
+                }
+            }
+            FieldType::Constrained { base, .. } => self.annotation(base, quoted)?,
+        })
+    }
+}
+
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class",
+    "continue", "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if",
+    "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try",
+    "while", "with", "yield",
+];
+
+/// `name`, with a trailing `_` if it is a Python keyword.
+fn python_name(name: &str) -> String {
+    if PYTHON_KEYWORDS.contains(&name) {
+        format!("{name}_")
+    } else {
+        name.to_string()
+    }
+}
+
+/// A Python string literal. JSON string escapes are valid in Python.
+fn string_literal(s: &str) -> String {
+    serde_json::Value::String(s.to_string()).to_string()
+}
@@ -1,6 +1,19 @@
-use pyo3::{create_exception, PyErr};
+use std::collections::HashMap;
 
-use crate::BamlContext;
+use baml_types::{BamlMap, BamlValue, ConstraintLevel};
+use pyo3::{
+    create_exception,
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyBool, PyBytes, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple},
+    PyErr,
+};
+
+use crate::{
+    function_prompt::RenderedPrompt, refusal::RefusalDetector, BamlContext, BoolVocabulary,
+    FlaggedNode, FlaggedValue, FloatFormat, PromptOptions, RecursionRendering, ReferenceStyle,
+    ReservedNamePolicy, SchemaOptions, Severity, TrailingZeros, UnionStyle, XmlTags,
+};
 
 create_exception!(baml_lib, BamlLibError, pyo3::exceptions::PyException);
 
@@ -19,33 +32,851 @@ pub struct PyBamlContext {
 #[pyo3::prelude::pymethods]
 impl PyBamlContext {
     #[new]
-    #[pyo3(signature= (schema_string, target_name=None))]
-    fn new(schema_string: String, target_name: Option<String>) -> pyo3::prelude::PyResult<Self> {
-        let context = BamlContext::try_from_schema(&schema_string, target_name)
-            .map_err(BamlLibError::from_anyhow)?;
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature= (schema_string, target_name=None, version=None, number_overflow=None, field_order=None, doc_comment_descriptions=None, canonical_field_order=None, reserved_names=None, allowed_reserved_names=None, source_name=None, env_vars=None, normalize_lists=None, int_overflow=None, bool_truthy=None, bool_falsy=None, bool_locales=None, null_values=None, float_decimal_places=None, float_fixed=None, float_trailing_zeros=None, max_type_depth=None, render_style=None, detect_refusals=None, refusal_patterns=None, recursion_depth=None, references=None, max_value_depth=None, shared_definitions=None, render_format=None))]
+    fn new(
+        schema_string: String,
+        target_name: Option<String>,
+        version: Option<String>,
+        number_overflow: Option<String>,
+        field_order: Option<String>,
+        doc_comment_descriptions: Option<bool>,
+        canonical_field_order: Option<bool>,
+        reserved_names: Option<Vec<String>>,
+        allowed_reserved_names: Option<Vec<String>>,
+        source_name: Option<String>,
+        env_vars: Option<HashMap<String, String>>,
+        normalize_lists: Option<bool>,
+        int_overflow: Option<String>,
+        bool_truthy: Option<Vec<String>>,
+        bool_falsy: Option<Vec<String>>,
+        bool_locales: Option<Vec<String>>,
+        null_values: Option<Vec<String>>,
+        float_decimal_places: Option<usize>,
+        float_fixed: Option<bool>,
+        float_trailing_zeros: Option<String>,
+        max_type_depth: Option<usize>,
+        render_style: Option<String>,
+        detect_refusals: Option<bool>,
+        refusal_patterns: Option<Vec<String>>,
+        recursion_depth: Option<usize>,
+        references: Option<String>,
+        max_value_depth: Option<usize>,
+        shared_definitions: Option<bool>,
+        render_format: Option<String>,
+    ) -> pyo3::prelude::PyResult<Self> {
+        let options = SchemaOptions {
+            version,
+            doc_comment_descriptions: doc_comment_descriptions.unwrap_or(false),
+            reserved_names: ReservedNamePolicy {
+                reserve: reserved_names.unwrap_or_default(),
+                allow: allowed_reserved_names.unwrap_or_default(),
+            },
+            source_name,
+            env_vars: env_vars.unwrap_or_default(),
+            max_type_depth,
+            message_catalog: None,
+        };
+        let mut context =
+            BamlContext::try_from_schema_with_options(&schema_string, target_name, &options)
+                .map_err(BamlLibError::from_anyhow)?;
+        if let Some(number_overflow) = number_overflow {
+            context = context
+                .with_number_overflow(number_overflow.parse().map_err(BamlLibError::from_anyhow)?);
+        }
+        if let Some(field_order) = field_order {
+            let field_order = field_order.parse().map_err(|_| {
+                BamlLibError::new_err(format!(
+                    "Unknown field order `{field_order}`, expected declaration, alphabetical or required_first"
+                ))
+            })?;
+            context = context.with_field_order(field_order);
+        }
+        if let Some(render_style) = render_style {
+            let render_style = render_style.parse().map_err(|_| {
+                BamlLibError::new_err(format!(
+                    "Unknown render style `{render_style}`, expected standard or compact"
+                ))
+            })?;
+            context = context.with_render_style(render_style);
+        }
+        if let Some(render_format) = render_format {
+            let render_format = render_format.parse().map_err(|_| {
+                BamlLibError::new_err(format!(
+                    "Unknown render format `{render_format}`, expected block or markdown"
+                ))
+            })?;
+            context = context.with_render_format(render_format);
+        }
+        if recursion_depth.is_some() || references.is_some() {
+            let references = match references {
+                Some(references) => references.parse().map_err(|_| {
+                    BamlLibError::new_err(format!(
+                        "Unknown reference style `{references}`, expected name or marker"
+                    ))
+                })?,
+                None => ReferenceStyle::default(),
+            };
+            context = context.with_recursion_rendering(RecursionRendering {
+                inline_depth: recursion_depth.unwrap_or(0),
+                references,
+            });
+        }
+        if let Some(shared_definitions) = shared_definitions {
+            context = context.with_shared_definitions(shared_definitions);
+        }
+        if let Some(canonical_field_order) = canonical_field_order {
+            context = context.with_canonical_field_order(canonical_field_order);
+        }
+        if let Some(normalize_lists) = normalize_lists {
+            context = context.with_list_normalization(normalize_lists);
+        }
+        if let Some(int_overflow) = int_overflow {
+            let int_overflow = int_overflow.parse().map_err(|_| {
+                BamlLibError::new_err(format!(
+                    "Unknown int overflow `{int_overflow}`, expected error, saturate or float"
+                ))
+            })?;
+            context = context.with_int_overflow(int_overflow);
+        }
+        let mut bool_vocabulary = BoolVocabulary::default();
+        for locale in bool_locales.unwrap_or_default() {
+            bool_vocabulary = bool_vocabulary
+                .with_locale(&locale)
+                .map_err(BamlLibError::from_anyhow)?;
+        }
+        let bool_vocabulary = bool_vocabulary
+            .with_truthy(bool_truthy.unwrap_or_default())
+            .with_falsy(bool_falsy.unwrap_or_default());
+        context = context.with_bool_vocabulary(bool_vocabulary);
+        if let Some(null_values) = null_values {
+            context = context.with_null_values(null_values);
+        }
+        if let Some(max_value_depth) = max_value_depth {
+            context = context.with_max_value_depth(max_value_depth);
+        }
+        let float_format = FloatFormat {
+            max_decimal_places: float_decimal_places,
+            fixed: float_fixed.unwrap_or(false),
+            trailing_zeros: match float_trailing_zeros {
+                Some(policy) => policy.parse().map_err(BamlLibError::from_anyhow)?,
+                None => TrailingZeros::default(),
+            },
+        };
+        context = context.with_float_format(float_format);
+        if let Some(patterns) = refusal_patterns {
+            context = context.with_refusal_detection(RefusalDetector::new(patterns));
+        } else if detect_refusals.unwrap_or(false) {
+            context = context.with_refusal_detection(RefusalDetector::default());
+        }
         Ok(PyBamlContext { context })
     }
 
-    #[pyo3(signature = (prefix=None, always_hoist_enums=None))]
+    /// The errors and warnings of the schema, as dicts with the `severity` (`"error"` or
+    /// `"warning"`), `message`, `path`, byte offsets `start` and `end`, and zero-based
+    /// `start_line`, `start_column`, `end_line` and `end_column`.
+    #[staticmethod]
+    #[pyo3(signature = (schema_string, target_name=None, source_name=None))]
+    pub fn diagnostics(
+        py: Python<'_>,
+        schema_string: String,
+        target_name: Option<String>,
+        source_name: Option<String>,
+    ) -> PyResult<Vec<PyObject>> {
+        let options = SchemaOptions {
+            source_name,
+            ..Default::default()
+        };
+        let (_, items) =
+            BamlContext::try_from_schema_with_diagnostics(&schema_string, target_name, &options);
+        items
+            .into_iter()
+            .map(|item| {
+                let dict = PyDict::new_bound(py);
+                let severity = match item.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                };
+                dict.set_item("severity", severity)?;
+                dict.set_item("message", item.message)?;
+                dict.set_item("path", item.path)?;
+                dict.set_item("start", item.start)?;
+                dict.set_item("end", item.end)?;
+                dict.set_item("start_line", item.start_line)?;
+                dict.set_item("start_column", item.start_column)?;
+                dict.set_item("end_line", item.end_line)?;
+                dict.set_item("end_column", item.end_column)?;
+                Ok(dict.into_py(py))
+            })
+            .collect()
+    }
+
+    #[pyo3(signature = (prefix=None, always_hoist_enums=None, or_splitter=None, enum_value_prefix=None, hoisted_class_prefix=None, map_style=None, union_style=None))]
+    #[allow(clippy::too_many_arguments)]
     pub fn render_prompt(
         &self,
         prefix: Option<String>,
-        always_hoist_enums: Option<bool>
+        always_hoist_enums: Option<bool>,
+        or_splitter: Option<String>,
+        enum_value_prefix: Option<String>,
+        hoisted_class_prefix: Option<String>,
+        map_style: Option<String>,
+        union_style: Option<String>,
     ) -> pyo3::prelude::PyResult<String> {
+        let map_style = match map_style {
+            Some(map_style) => Some(map_style.parse().map_err(|_| {
+                BamlLibError::new_err(format!(
+                    "Unknown map style `{map_style}`, expected angle or object"
+                ))
+            })?),
+            None => None,
+        };
+        let union_style = match union_style {
+            Some(union_style) => union_style.parse().map_err(|_| {
+                BamlLibError::new_err(format!(
+                    "Unknown union style `{union_style}`, expected inline or variants"
+                ))
+            })?,
+            None => UnionStyle::default(),
+        };
+        let options = PromptOptions {
+            prefix,
+            always_hoist_enums,
+            or_splitter,
+            enum_value_prefix,
+            hoisted_class_prefix,
+            map_style,
+            union_style,
+        };
         self.context
-            .render_prompt(prefix, always_hoist_enums)
+            .render_prompt_with_options(&options)
             .map_err(BamlLibError::from_anyhow)
     }
 
-    #[pyo3(signature = (result, allow_partials=None))]
+    /// A stable key for caching LLM responses to the prompt rendered with `args_json`.
+    pub fn cache_key(&self, args_json: String) -> PyResult<String> {
+        self.context
+            .cache_key(&args_json, None)
+            .map_err(BamlLibError::from_anyhow)
+    }
+
+    /// The prompt of the function `name` rendered with `args`, a dict of Python values: a
+    /// string for completion prompts, a list of `{"role": str, "content": str}` for chat ones.
+    #[pyo3(signature = (name, args, variant=None))]
+    pub fn render_function_prompt(
+        &self,
+        py: Python<'_>,
+        name: String,
+        args: &Bound<'_, PyDict>,
+        variant: Option<String>,
+    ) -> PyResult<PyObject> {
+        let args = args
+            .iter()
+            .map(|(key, value)| Ok((key.extract::<String>()?, from_python(&value)?)))
+            .collect::<PyResult<BamlMap<_, _>>>()?;
+        let prompt = match variant {
+            Some(variant) => self.context.render_function_prompt_variant(&name, &variant, args),
+            None => self.context.render_function_prompt(&name, args),
+        }
+        .map_err(BamlLibError::from_anyhow)?;
+        Ok(match prompt {
+            RenderedPrompt::Completion(text) => text.into_py(py),
+            RenderedPrompt::Chat(messages) => {
+                let messages = messages
+                    .into_iter()
+                    .map(|message| {
+                        let content =
+                            message.parts.iter().map(ToString::to_string).collect::<String>();
+                        let dict = PyDict::new_bound(py);
+                        dict.set_item("role", message.role)?;
+                        dict.set_item("content", content)?;
+                        Ok(dict.into_py(py))
+                    })
+                    .collect::<PyResult<Vec<_>>>()?;
+                PyList::new_bound(py, messages).into_py(py)
+            }
+        })
+    }
+
+    /// The parameters of the function `name`, described like `render_prompt` describes the output.
+    pub fn render_input_schema(&self, name: String) -> PyResult<String> {
+        self.context
+            .render_input_schema(&name)
+            .map_err(BamlLibError::from_anyhow)
+    }
+
+    /// The JSON Schema, as JSON, of `target` or of the target of this context.
+    #[pyo3(signature = (target=None))]
+    pub fn to_json_schema(&self, target: Option<String>) -> PyResult<String> {
+        let schema = self
+            .context
+            .to_json_schema(target.as_deref())
+            .map_err(BamlLibError::from_anyhow)?;
+        Ok(schema.to_string())
+    }
+
+    /// Python source of `pydantic` models for the classes and enums of the schema.
+    pub fn to_pydantic(&self) -> PyResult<String> {
+        self.context.to_pydantic().map_err(BamlLibError::from_anyhow)
+    }
+
+    /// TypeScript declarations for the classes and enums of the schema.
+    pub fn to_typescript(&self) -> PyResult<String> {
+        self.context.to_typescript().map_err(BamlLibError::from_anyhow)
+    }
+
+    /// The target type as a JSON response format for `backend`: "openai", "vllm" or "tgi".
+    pub fn response_format(&self, backend: String) -> PyResult<String> {
+        let backend = backend.parse().map_err(BamlLibError::from_anyhow)?;
+        let format = self
+            .context
+            .response_format(backend)
+            .map_err(BamlLibError::from_anyhow)?;
+        Ok(format.to_string())
+    }
+
+    /// Where the response format for `backend` accepts more than `validate_result` does.
+    pub fn response_format_downgrades(&self, backend: String) -> PyResult<Vec<String>> {
+        let backend = backend.parse().map_err(BamlLibError::from_anyhow)?;
+        let (_, downgrades) = self
+            .context
+            .response_format_with_downgrades(backend)
+            .map_err(BamlLibError::from_anyhow)?;
+        Ok(downgrades.iter().map(ToString::to_string).collect())
+    }
+
+    /// An Anthropic tool definition, as JSON, whose input schema is the target class.
+    #[pyo3(signature = (name, description=None))]
+    pub fn anthropic_tool(&self, name: String, description: Option<String>) -> PyResult<String> {
+        let tool = self
+            .context
+            .anthropic_tool(&name, description.as_deref())
+            .map_err(BamlLibError::from_anyhow)?;
+        Ok(tool.to_string())
+    }
+
+    #[pyo3(signature = (schema_tag=None, answer_tag=None))]
+    pub fn render_xml_prompt(
+        &self,
+        schema_tag: Option<String>,
+        answer_tag: Option<String>,
+    ) -> PyResult<String> {
+        let defaults = XmlTags::default();
+        let tags = XmlTags {
+            schema: schema_tag.unwrap_or(defaults.schema),
+            answer: answer_tag.unwrap_or(defaults.answer),
+        };
+        self.context
+            .render_xml_prompt(&tags)
+            .map_err(BamlLibError::from_anyhow)
+    }
+
+    #[pyo3(signature = (result, allow_partials=None, omit_missing=None))]
     pub fn validate_result(
         &self,
         result: String,
-        allow_partials: Option<bool>
+        allow_partials: Option<bool>,
+        omit_missing: Option<bool>,
     ) -> pyo3::prelude::PyResult<String> {
+        let allow_partials = allow_partials.unwrap_or(false);
+        if omit_missing.unwrap_or(false) {
+            self.context
+                .validate_result_omitting_missing(&result, allow_partials)
+        } else {
+            self.context.validate_result(&result, allow_partials)
+        }
+        .map_err(BamlLibError::from_anyhow)
+    }
+
+    /// One `{"result": json}` or `{"error": message}` dict per JSON document in `result`.
+    #[pyo3(signature = (result, allow_partials=None))]
+    pub fn validate_documents(
+        &self,
+        py: Python<'_>,
+        result: String,
+        allow_partials: Option<bool>,
+    ) -> PyResult<Vec<PyObject>> {
         self.context
-            .validate_result(&result, allow_partials.unwrap_or(false))
+            .validate_documents(&result, allow_partials.unwrap_or(false))
+            .into_iter()
+            .map(|document| {
+                let dict = PyDict::new_bound(py);
+                match document {
+                    Ok(json) => dict.set_item("result", json)?,
+                    Err(err) => dict.set_item("error", format!("{err:#}"))?,
+                }
+                Ok(dict.into_py(py))
+            })
+            .collect()
+    }
+
+    #[pyo3(signature = (result, allow_partials=None))]
+    pub fn parse_result(
+        &self,
+        py: Python<'_>,
+        result: String,
+        allow_partials: Option<bool>,
+    ) -> pyo3::prelude::PyResult<PyObject> {
+        let value = self
+            .context
+            .parse_result(&result, allow_partials.unwrap_or(false))
+            .map_err(BamlLibError::from_anyhow)?;
+        to_python(py, value)
+    }
+
+    /// Whether `result` parses to `expected`, given as native Python values like those
+    /// `parse_result` returns. Ints and floats compare by value.
+    #[pyo3(signature = (result, expected, allow_partials=None))]
+    pub fn result_matches(
+        &self,
+        result: String,
+        expected: &Bound<'_, PyAny>,
+        allow_partials: Option<bool>,
+    ) -> PyResult<bool> {
+        let expected = from_python(expected)?;
+        let value = self
+            .context
+            .parse_result(&result, allow_partials.unwrap_or(false))
+            .map_err(BamlLibError::from_anyhow)?;
+        Ok(value_matches(&value, &expected))
+    }
+
+    #[pyo3(signature = (result, allow_partials=None))]
+    pub fn parse_result_with_flags(
+        &self,
+        result: String,
+        allow_partials: Option<bool>,
+    ) -> pyo3::prelude::PyResult<PyFlaggedValue> {
+        self.context
+            .parse_result_with_flags(&result, allow_partials.unwrap_or(false))
+            .map(|value| PyFlaggedValue { value })
             .map_err(BamlLibError::from_anyhow)
     }
+
+    /// `{"value": json, "score": int, "flags": [...]}`: the result of `validate_result`, how much
+    /// coercing it cost (0 when nothing was fixed, higher is less confident) and every flag
+    /// raised, as dicts with `path` (`$.items[0].name`), `kind` and `message`.
+    #[pyo3(signature = (result, allow_partials=None))]
+    pub fn validate_result_with_flags(
+        &self,
+        py: Python<'_>,
+        result: String,
+        allow_partials: Option<bool>,
+    ) -> PyResult<PyObject> {
+        let (json, score, flagged) = self
+            .context
+            .validate_result_with_flags(&result, allow_partials.unwrap_or(false))
+            .map_err(BamlLibError::from_anyhow)?;
+        let flags = flagged
+            .all_flags()
+            .into_iter()
+            .map(|(path, flag)| {
+                let dict = PyDict::new_bound(py);
+                dict.set_item("path", path)?;
+                dict.set_item("kind", &flag.kind)?;
+                dict.set_item("message", &flag.message)?;
+                Ok(dict.into_py(py))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let dict = PyDict::new_bound(py);
+        dict.set_item("value", json)?;
+        dict.set_item("score", score)?;
+        dict.set_item("flags", flags)?;
+        Ok(dict.into_py(py))
+    }
+
+    /// `{"value": json, "reasoning": str | None}`: the result of `validate_result` and the
+    /// reasoning written before it or in `<think>` tags.
+    #[pyo3(signature = (result, allow_partials=None))]
+    pub fn validate_result_with_reasoning(
+        &self,
+        py: Python<'_>,
+        result: String,
+        allow_partials: Option<bool>,
+    ) -> PyResult<PyObject> {
+        let reasoned = self
+            .context
+            .validate_result_with_reasoning(&result, allow_partials.unwrap_or(false))
+            .map_err(BamlLibError::from_anyhow)?;
+        let dict = PyDict::new_bound(py);
+        dict.set_item("value", reasoned.value)?;
+        dict.set_item("reasoning", reasoned.reasoning)?;
+        Ok(dict.into_py(py))
+    }
+
+    /// `{"value": json, "constraints": [...]}`: the result of `validate_result` and every
+    /// `@assert` and `@check` on it, as dicts with `path`, `level` (`check` or `assert`),
+    /// `label`, `expression` and `passed`.
+    #[pyo3(signature = (result, allow_partials=None))]
+    pub fn validate_with_constraints(
+        &self,
+        py: Python<'_>,
+        result: String,
+        allow_partials: Option<bool>,
+    ) -> PyResult<PyObject> {
+        let report = self
+            .context
+            .validate_with_constraints(&result, allow_partials.unwrap_or(false))
+            .map_err(BamlLibError::from_anyhow)?;
+        let constraints = report
+            .constraints
+            .into_iter()
+            .map(|constraint| {
+                let dict = PyDict::new_bound(py);
+                dict.set_item("path", constraint.path)?;
+                dict.set_item(
+                    "level",
+                    match constraint.level {
+                        ConstraintLevel::Check => "check",
+                        ConstraintLevel::Assert => "assert",
+                    },
+                )?;
+                dict.set_item("label", constraint.label)?;
+                dict.set_item("expression", constraint.expression)?;
+                dict.set_item("passed", constraint.passed)?;
+                Ok(dict.into_py(py))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let dict = PyDict::new_bound(py);
+        dict.set_item("value", report.value)?;
+        dict.set_item("constraints", constraints)?;
+        Ok(dict.into_py(py))
+    }
+
+    /// `{"target": name, "value": json, "scores": {name: int | None}}`: which of `candidates`
+    /// the output fits best, see `BamlContext::classify`.
+    pub fn classify(
+        &self,
+        py: Python<'_>,
+        result: String,
+        candidates: Vec<String>,
+    ) -> PyResult<PyObject> {
+        let candidates = candidates.iter().map(String::as_str).collect::<Vec<_>>();
+        let classification = self
+            .context
+            .classify(&result, &candidates)
+            .map_err(BamlLibError::from_anyhow)?;
+        let scores = PyDict::new_bound(py);
+        for (candidate, score) in classification.scores {
+            scores.set_item(candidate, score)?;
+        }
+        let dict = PyDict::new_bound(py);
+        dict.set_item("target", classification.target)?;
+        dict.set_item("value", classification.value)?;
+        dict.set_item("scores", scores)?;
+        Ok(dict.into_py(py))
+    }
+
+    /// Advisory suggestions to shrink the rendered prompt, as dicts with `kind`, `path` and
+    /// `message`.
+    pub fn suggestions(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        self.context
+            .suggestions()
+            .into_iter()
+            .map(|suggestion| {
+                let dict = PyDict::new_bound(py);
+                dict.set_item("kind", suggestion.kind.as_str())?;
+                dict.set_item("path", suggestion.path)?;
+                dict.set_item("message", suggestion.message)?;
+                Ok(dict.into_py(py))
+            })
+            .collect()
+    }
 }
 
+/// A parsed value with the flags raised while coercing each part of it.
+#[pyo3::prelude::pyclass(name = "FlaggedValue")]
+pub struct PyFlaggedValue {
+    value: FlaggedValue,
+}
+
+#[pyo3::prelude::pymethods]
+impl PyFlaggedValue {
+    /// The value without flags, as `parse_result` returns it.
+    #[getter]
+    fn value(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, self.value.value())
+    }
+
+    /// The flags of this node (not its children), as dicts with `kind` and `message`.
+    #[getter]
+    fn flags(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        self.value
+            .flags()
+            .iter()
+            .map(|flag| {
+                let dict = PyDict::new_bound(py);
+                dict.set_item("kind", &flag.kind)?;
+                dict.set_item("message", &flag.message)?;
+                Ok(dict.into_py(py))
+            })
+            .collect()
+    }
+
+    /// `"value"`, `"list"`, `"map"` or `"class"`.
+    #[getter]
+    fn r#type(&self) -> &'static str {
+        match self.value.node() {
+            FlaggedNode::Value { .. } => "value",
+            FlaggedNode::List { .. } => "list",
+            FlaggedNode::Map { .. } => "map",
+            FlaggedNode::Class { .. } => "class",
+        }
+    }
+
+    /// The `(start, end)` byte range of the output this node was parsed from, or `None`.
+    #[getter]
+    fn span(&self) -> Option<(usize, usize)> {
+        self.value.span().map(|span| (span.start, span.end))
+    }
+
+    /// The `(start, end)` byte range of the node at `path`, such as `$.items[0].name`.
+    fn provenance(&self, path: &str) -> Option<(usize, usize)> {
+        self.value.provenance(path).map(|span| (span.start, span.end))
+    }
+
+    /// The child nodes: a list for lists, a dict for classes and maps, `None` otherwise.
+    #[getter]
+    fn children(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let child = |value: &FlaggedValue| {
+            Py::new(py, PyFlaggedValue { value: value.clone() }).map(|v| v.into_py(py))
+        };
+        Ok(match self.value.node() {
+            FlaggedNode::Value { .. } => py.None(),
+            FlaggedNode::List { items } => {
+                let items = items.iter().map(child).collect::<PyResult<Vec<_>>>()?;
+                PyList::new_bound(py, items).into_py(py)
+            }
+            FlaggedNode::Map { entries: children } | FlaggedNode::Class { fields: children, .. } => {
+                let dict = PyDict::new_bound(py);
+                for (key, value) in children {
+                    dict.set_item(key, child(value)?)?;
+                }
+                dict.into_py(py)
+            }
+        })
+    }
+}
+
+/// Converts a parsed value to Python: classes and maps become dicts, enums their value, `bytes`
+/// fields `bytes`, and preserved big numbers `int` or `decimal.Decimal`.
+fn to_python(py: Python<'_>, value: BamlValue) -> PyResult<PyObject> {
+    Ok(match value {
+        BamlValue::String(s) | BamlValue::Enum(_, s) => s.into_py(py),
+        BamlValue::Int(i) => i.into_py(py),
+        BamlValue::Float(f) => f.into_py(py),
+        BamlValue::Decimal(d) => {
+            let (module, constructor) = if d.contains(['.', 'e', 'E']) {
+                ("decimal", "Decimal")
+            } else {
+                ("builtins", "int")
+            };
+            py.import_bound(module)?
+                .getattr(constructor)?
+                .call1((d,))?
+                .unbind()
+        }
+        BamlValue::Bool(b) => b.into_py(py),
+        BamlValue::Bytes(b) => PyBytes::new_bound(py, &b).into_py(py),
+        BamlValue::Map(fields) | BamlValue::Class(_, fields) => {
+            let dict = PyDict::new_bound(py);
+            for (key, value) in fields {
+                dict.set_item(key, to_python(py, value)?)?;
+            }
+            dict.into_py(py)
+        }
+        BamlValue::List(items) => {
+            let items = items
+                .into_iter()
+                .map(|item| to_python(py, item))
+                .collect::<PyResult<Vec<_>>>()?;
+            PyList::new_bound(py, items).into_py(py)
+        }
+        BamlValue::Media(_) => {
+            return Err(BamlLibError::new_err(
+                "media values are not supported in outputs",
+            ))
+        }
+        BamlValue::Null => py.None(),
+    })
+}
+
+/// Whether a parsed value equals one converted by [`from_python`], which has maps where the
+/// parsed value has classes and strings where it has enums.
+fn value_matches(parsed: &BamlValue, expected: &BamlValue) -> bool {
+    match (parsed, expected) {
+        (BamlValue::Class(_, fields) | BamlValue::Map(fields), BamlValue::Map(expected)) => {
+            fields.len() == expected.len()
+                && fields.iter().all(|(key, value)| {
+                    expected
+                        .get(key)
+                        .is_some_and(|expected| value_matches(value, expected))
+                })
+        }
+        (BamlValue::List(items), BamlValue::List(expected)) => {
+            items.len() == expected.len()
+                && items
+                    .iter()
+                    .zip(expected)
+                    .all(|(item, expected)| value_matches(item, expected))
+        }
+        (BamlValue::Enum(_, value), BamlValue::String(expected)) => value == expected,
+        (BamlValue::Int(i), BamlValue::Float(f)) | (BamlValue::Float(f), BamlValue::Int(i)) => {
+            *i as f64 == *f
+        }
+        _ => parsed == expected,
+    }
+}
+
+/// Converts a Python value to a `BamlValue`, the reverse of [`to_python`]: `None`, `bool`, `int`,
+/// `float`, `str`, `bytes`, `decimal.Decimal`, lists and tuples, and dicts with `str` keys.
+/// Ints an `i64` cannot hold become decimals, keeping every digit; NaN and infinite decimals are
+/// rejected.
+fn from_python(value: &Bound<'_, PyAny>) -> PyResult<BamlValue> {
+    // `bool` is a subclass of `int`, so it is checked before it.
+    if value.is_none() {
+        Ok(BamlValue::Null)
+    } else if let Ok(b) = value.downcast::<PyBool>() {
+        Ok(BamlValue::Bool(b.is_true()))
+    } else if value.is_instance_of::<PyInt>() {
+        Ok(match value.extract::<i64>() {
+            Ok(i) => BamlValue::Int(i),
+            Err(_) => BamlValue::Decimal(value.str()?.to_string()),
+        })
+    } else if let Ok(f) = value.downcast::<PyFloat>() {
+        Ok(BamlValue::Float(f.value()))
+    } else if let Ok(s) = value.downcast::<PyString>() {
+        Ok(BamlValue::String(s.to_string()))
+    } else if let Ok(b) = value.downcast::<PyBytes>() {
+        Ok(BamlValue::Bytes(b.as_bytes().to_vec()))
+    } else if let Ok(dict) = value.downcast::<PyDict>() {
+        let fields = dict
+            .iter()
+            .map(|(key, value)| {
+                let key = key.extract::<String>().map_err(|_| {
+                    PyTypeError::new_err(format!("dict keys must be str, not {}", key.get_type()))
+                })?;
+                Ok((key, from_python(&value)?))
+            })
+            .collect::<PyResult<_>>()?;
+        Ok(BamlValue::Map(fields))
+    } else if let Ok(list) = value.downcast::<PyList>() {
+        let items = list.iter().map(|item| from_python(&item)).collect::<PyResult<_>>()?;
+        Ok(BamlValue::List(items))
+    } else if let Ok(tuple) = value.downcast::<PyTuple>() {
+        let items = tuple.iter().map(|item| from_python(&item)).collect::<PyResult<_>>()?;
+        Ok(BamlValue::List(items))
+    } else if value.is_instance(&value.py().import_bound("decimal")?.getattr("Decimal")?)? {
+        if !value.call_method0("is_finite")?.is_truthy()? {
+            return Err(PyValueError::new_err(format!(
+                "cannot convert Decimal('{}') to a BAML value, it is not finite",
+                value.str()?
+            )));
+        }
+        Ok(BamlValue::Decimal(value.str()?.to_string()))
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "cannot convert {} to a BAML value",
+            value.get_type()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"
+enum Unit {
+  Celsius
+  Fahrenheit
+}
+
+class Reading {
+  value float
+  unit Unit
+  tags string[]
+}
+"#;
+
+    fn round_trip(py: Python<'_>, code: &str) -> PyResult<BamlValue> {
+        let value = py.eval_bound(code, None, None)?;
+        let converted = from_python(&value)?;
+        let back = to_python(py, converted.clone())?;
+        assert!(back.bind(py).eq(&value)?, "{code} came back as {back}");
+        Ok(converted)
+    }
+
+    #[test]
+    fn converts_python_values_both_ways() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let value = round_trip(
+                py,
+                "{'name': 'ada', 'age': 36, 'score': 1.5, 'ok': True, 'none': None, 'tags': ['a', 'b']}",
+            )
+            .unwrap();
+            let BamlValue::Map(fields) = value else {
+                panic!("expected a map, got {value:?}");
+            };
+            assert_eq!(fields["age"], BamlValue::Int(36));
+            assert_eq!(fields["ok"], BamlValue::Bool(true));
+            assert_eq!(fields["none"], BamlValue::Null);
+
+            assert_eq!(
+                round_trip(py, "2 ** 70").unwrap(),
+                BamlValue::Decimal("1180591620717411303424".into())
+            );
+            assert_eq!(
+                round_trip(py, "__import__('decimal').Decimal('12.50')").unwrap(),
+                BamlValue::Decimal("12.50".into())
+            );
+            assert_eq!(
+                round_trip(py, "b'\\x00\\xff'").unwrap(),
+                BamlValue::Bytes(vec![0, 255])
+            );
+            assert_eq!(
+                from_python(&py.eval_bound("(1, 'x')", None, None).unwrap()).unwrap(),
+                BamlValue::List(vec![BamlValue::Int(1), BamlValue::String("x".into())])
+            );
+        });
+    }
+
+    #[test]
+    fn rejects_values_without_a_baml_form() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            for code in [
+                "__import__('decimal').Decimal('NaN')",
+                "__import__('decimal').Decimal('-Infinity')",
+            ] {
+                let err = from_python(&py.eval_bound(code, None, None).unwrap()).unwrap_err();
+                assert!(err.is_instance_of::<PyValueError>(py), "{code}: {err}");
+            }
+            for code in ["{1: 'one'}", "object()"] {
+                let err = from_python(&py.eval_bound(code, None, None).unwrap()).unwrap_err();
+                assert!(err.is_instance_of::<PyTypeError>(py), "{code}: {err}");
+            }
+        });
+    }
+
+    #[test]
+    fn compares_results_with_python_values() {
+        pyo3::prepare_freethreaded_python();
+        let context = PyBamlContext {
+            context: BamlContext::try_from_schema(SCHEMA, Some("Reading".into())).unwrap(),
+        };
+        Python::with_gil(|py| {
+            let result = r#"{"value": 21, "unit": "Celsius", "tags": ["indoor"]}"#;
+            let matches = |code: &str| {
+                let expected = py.eval_bound(code, None, None).unwrap();
+                context.result_matches(result.into(), &expected, None).unwrap()
+            };
+            assert!(matches("{'unit': 'Celsius', 'value': 21, 'tags': ['indoor']}"));
+            assert!(matches("{'value': 21.0, 'unit': 'Celsius', 'tags': ['indoor']}"));
+            assert!(!matches("{'value': 21, 'unit': 'Fahrenheit', 'tags': ['indoor']}"));
+            assert!(!matches("{'value': 21, 'unit': 'Celsius', 'tags': []}"));
+            assert!(!matches("{'value': 21, 'unit': 'Celsius'}"));
+        });
+    }
+}
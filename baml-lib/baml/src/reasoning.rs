@@ -0,0 +1,97 @@
+//! Keeping the reasoning models write before their answer.
+
+use crate::{documents, BamlContext};
+
+/// The tags whose content is reasoning, as in `<think>...</think>`.
+pub const REASONING_TAGS: &[&str] = &["think", "thinking", "reasoning"];
+
+/// A result together with the reasoning the model wrote around it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reasoned<T> {
+    pub value: T,
+    /// The content of the reasoning tags or, without tags, the text before the JSON answer.
+    pub reasoning: Option<String>,
+}
+
+impl BamlContext {
+    /// Like [`validate_result`](Self::validate_result), but keeps the reasoning instead of
+    /// discarding it.
+    ///
+    /// The content of [`REASONING_TAGS`] blocks is the reasoning and is left out of parsing, so
+    /// drafts of the answer inside them are not mistaken for it. A closing tag without an
+    /// opening one (the opening tag was in the prompt) ends the reasoning, and an unclosed one
+    /// runs to the end of the output. Without tags, the text before the first JSON object or
+    /// array, and before the code fence around it, is the reasoning.
+    pub fn validate_result_with_reasoning(
+        &self,
+        result: &str,
+        allow_partials: bool,
+    ) -> anyhow::Result<Reasoned<String>> {
+        Ok(match split_tagged(result) {
+            Some((reasoning, answer)) => Reasoned {
+                value: self.validate_result(&answer, allow_partials)?,
+                reasoning: Some(reasoning),
+            },
+            None => Reasoned {
+                value: self.validate_result(result, allow_partials)?,
+                reasoning: preamble(result),
+            },
+        })
+    }
+}
+
+/// The reasoning in the first of [`REASONING_TAGS`] the output uses, and the output without it.
+fn split_tagged(output: &str) -> Option<(String, String)> {
+    for tag in REASONING_TAGS {
+        let open = format!("<{tag}>");
+        let close = format!("</{tag}>");
+        let mut reasoning = vec![];
+        let mut answer = String::new();
+        let mut rest = output;
+        if let Some(end) = rest.find(&close) {
+            if rest.find(&open).is_none_or(|start| start > end) {
+                reasoning.push(&rest[..end]);
+                rest = &rest[end + close.len()..];
+            }
+        }
+        while let Some(start) = rest.find(&open) {
+            answer.push_str(&rest[..start]);
+            let inner = &rest[start + open.len()..];
+            match inner.find(&close) {
+                Some(end) => {
+                    reasoning.push(&inner[..end]);
+                    rest = &inner[end + close.len()..];
+                }
+                None => {
+                    reasoning.push(inner);
+                    rest = "";
+                }
+            }
+        }
+        if reasoning.is_empty() {
+            continue;
+        }
+        answer.push_str(rest);
+        let reasoning = reasoning
+            .into_iter()
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        return Some((reasoning, answer));
+    }
+    None
+}
+
+/// The text before the first JSON document of `output`, if any.
+fn preamble(output: &str) -> Option<String> {
+    let first = documents::split(output).into_iter().next()?;
+    let mut preamble = output[..first.start].trim_end();
+    if let Some(fence) = preamble.rfind("```") {
+        if !preamble[fence + 3..].contains(char::is_whitespace) {
+            preamble = &preamble[..fence];
+        }
+    }
+    let preamble = preamble.trim();
+    (!preamble.is_empty()).then(|| preamble.to_string())
+}
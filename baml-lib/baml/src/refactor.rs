@@ -0,0 +1,264 @@
+//! Schema-wide refactors for tooling, such as renaming a type or extracting fields into a class.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use internal_baml_core::ast::{Top, WithName, WithSpan};
+
+use crate::{SourceFile, TypeWalker, ValidatedSchema};
+
+/// Replace `start..end` (byte offsets) of the file at `path` with `new_text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub path: PathBuf,
+    pub start: usize,
+    pub end: usize,
+    pub new_text: String,
+}
+
+/// Edits renaming `old` to `new` across `schema_files`, ordered by file and offset.
+///
+/// `old` is a class, enum or type alias (`Order`) or a class field (`Order.status`); `new` is
+/// the bare new name. Renaming a type updates every field type, type alias and function or
+/// template string signature using it. Renaming a field updates the class's `@@one_of`
+/// arguments and `this.field` in its `@@assert` / `@@check` expressions and `@required_if`
+/// conditions; `@alias` names are left alone, so the rendered prompt does not change when
+/// there is one.
+///
+/// Fails if the schema is invalid, `old` does not exist, or the renamed schema would be
+/// invalid, e.g. because `new` is already taken.
+pub fn rename_symbol(
+    schema_files: &[(PathBuf, String)],
+    old: &str,
+    new: &str,
+) -> anyhow::Result<Vec<TextEdit>> {
+    if !is_identifier(new) {
+        bail!("`{new}` is not a valid name");
+    }
+    let schema = validate(schema_files);
+    if schema.diagnostics.has_errors() {
+        bail!("{}", schema.diagnostics.to_pretty_string());
+    }
+
+    let spans = match old.split_once('.') {
+        Some((class, field)) => {
+            let class = schema
+                .db
+                .find_type_by_str(class)
+                .and_then(|t| match t {
+                    TypeWalker::Class(class) => Some(class),
+                    _ => None,
+                })
+                .with_context(|| format!("No class named `{class}`"))?;
+            if !class.static_fields().any(|f| f.name() == field) {
+                bail!("`{}` has no field `{field}`", class.name());
+            }
+            schema.db.field_references(class.name(), field)
+        }
+        None => {
+            if schema.db.find_type_by_str(old).is_none() {
+                bail!("No class, enum or type alias named `{old}`");
+            }
+            schema.db.type_references(old)
+        }
+    };
+    let mut edits = spans
+        .into_iter()
+        .map(|span| TextEdit {
+            path: span.file.path_buf().clone(),
+            start: span.start,
+            end: span.end,
+            new_text: new.to_string(),
+        })
+        .collect::<Vec<_>>();
+    edits.sort_by(|a, b| (&a.path, a.start).cmp(&(&b.path, b.start)));
+    edits.dedup();
+
+    ensure_valid_after(
+        schema_files,
+        &edits,
+        &format!("Renaming `{old}` to `{new}`"),
+    )?;
+    Ok(edits)
+}
+
+/// Edits moving `fields` of `class` into a new class `new_class`, replaced in `class` by a
+/// single field `new_field` of type `new_class` where the first of them was.
+///
+/// The fields keep their order, attributes and the comments on the lines above them. Fields
+/// referred to from outside the extracted ones (e.g. by the class's `@@assert` or `@@one_of`)
+/// cannot be extracted, since the reference would no longer resolve.
+///
+/// Fails if the schema is invalid, a field does not exist or does not sit on its own lines,
+/// or the resulting schema would be invalid, e.g. because `new_class` is already taken.
+pub fn extract_class(
+    schema_files: &[(PathBuf, String)],
+    class: &str,
+    fields: &[&str],
+    new_class: &str,
+    new_field: &str,
+) -> anyhow::Result<Vec<TextEdit>> {
+    for name in [new_class, new_field] {
+        if !is_identifier(name) {
+            bail!("`{name}` is not a valid name");
+        }
+    }
+    if fields.is_empty() {
+        bail!("No fields to extract");
+    }
+    let schema = validate(schema_files);
+    if schema.diagnostics.has_errors() {
+        bail!("{}", schema.diagnostics.to_pretty_string());
+    }
+    let block = schema
+        .db
+        .ast()
+        .iter_tops()
+        .find_map(|(_, top)| match top {
+            Top::Class(block) if block.name() == class => Some(block),
+            _ => None,
+        })
+        .with_context(|| format!("No class named `{class}`"))?;
+    let source = block.span.file.as_str();
+    // Fields must be between the line with `{` and the one with `}`.
+    let body = line_end(source, block.name.span().end)..line_start(source, block.span.end - 1);
+
+    // The lines of each field, with the comments above it, in declaration order.
+    let mut ranges = Vec::new();
+    for field in &block.fields {
+        if !fields.contains(&field.name()) {
+            continue;
+        }
+        let comments = schema.db.ast().trivia_of(&field.span);
+        let first = comments
+            .attached_comments()
+            .first()
+            .map_or(field.span.start, |comment| comment.span.start);
+        let start = line_start(source, first);
+        // The field's span runs on to the indentation of the next line.
+        let end = field.span.start + source[field.span.start..field.span.end].trim_end().len();
+        let range = start..line_end(source, end);
+        if range.start < body.start || range.end > body.end {
+            bail!("`{class}.{}` must be on its own lines", field.name());
+        }
+        ranges.push(range);
+    }
+    if let Some(missing) = fields
+        .iter()
+        .find(|f| !block.fields.iter().any(|field| field.name() == **f))
+    {
+        bail!("`{class}` has no field `{missing}`");
+    }
+    for field in fields {
+        let outside = schema
+            .db
+            .field_references(class, field)
+            .into_iter()
+            .any(|span| !ranges.iter().any(|r| r.contains(&span.start)));
+        if outside {
+            bail!("`{class}.{field}` is referred to by attributes that stay in `{class}`");
+        }
+    }
+
+    let path = block.span.file.path_buf();
+    let newline = block.span.file.newline();
+    let first = &ranges[0];
+    let first_line = &source[first.clone()];
+    let field_line = first_line
+        .lines()
+        .find(|line| !line.trim_start().starts_with("//"))
+        .unwrap_or_default();
+    let indent = &field_line[..field_line.len() - field_line.trim_start().len()];
+    let mut edits = ranges
+        .iter()
+        .enumerate()
+        .map(|(i, range)| TextEdit {
+            path: path.clone(),
+            start: range.start,
+            end: range.end,
+            new_text: if i == 0 {
+                format!("{indent}{new_field} {new_class}{newline}")
+            } else {
+                String::new()
+            },
+        })
+        .collect::<Vec<_>>();
+    let extracted = ranges
+        .iter()
+        .map(|range| &source[range.clone()])
+        .collect::<String>();
+    edits.push(TextEdit {
+        path: path.clone(),
+        start: block.span.end,
+        end: block.span.end,
+        new_text: format!("{newline}{newline}class {new_class} {{{newline}{extracted}}}"),
+    });
+
+    ensure_valid_after(
+        schema_files,
+        &edits,
+        &format!("Extracting `{new_class}` from `{class}`"),
+    )?;
+    Ok(edits)
+}
+
+/// `source` with the `edits` for `path` applied.
+pub fn apply_edits(path: &Path, source: &str, edits: &[TextEdit]) -> String {
+    let mut result = source.to_string();
+    let mut edits = edits.iter().filter(|e| e.path == path).collect::<Vec<_>>();
+    // Apply from the end so earlier offsets stay valid.
+    edits.sort_by_key(|e| std::cmp::Reverse(e.start));
+    for edit in edits {
+        result.replace_range(edit.start..edit.end, &edit.new_text);
+    }
+    result
+}
+
+/// Fails, explaining that `action` would make the schema invalid, if it would.
+fn ensure_valid_after(
+    schema_files: &[(PathBuf, String)],
+    edits: &[TextEdit],
+    action: &str,
+) -> anyhow::Result<()> {
+    let edited = schema_files
+        .iter()
+        .map(|(path, source)| (path.clone(), apply_edits(path, source, edits)))
+        .collect::<Vec<_>>();
+    let edited = validate(&edited);
+    if edited.diagnostics.has_errors() {
+        bail!(
+            "{action} would make the schema invalid:\n{}",
+            edited.diagnostics.to_pretty_string()
+        );
+    }
+    Ok(())
+}
+
+/// Offset of the start of the line containing `offset`.
+fn line_start(source: &str, offset: usize) -> usize {
+    source[..offset].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// Offset just past the newline ending the line containing `offset`.
+fn line_end(source: &str, offset: usize) -> usize {
+    source[offset..]
+        .find('\n')
+        .map_or(source.len(), |i| offset + i + 1)
+}
+
+fn validate(schema_files: &[(PathBuf, String)]) -> ValidatedSchema {
+    let files = schema_files
+        .iter()
+        .cloned()
+        .map(SourceFile::from)
+        .collect::<Vec<_>>();
+    internal_baml_core::validate(&PathBuf::from("."), files)
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
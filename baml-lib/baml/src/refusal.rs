@@ -0,0 +1,82 @@
+//! Recognizing refusals before coercion, so that they are not reported as type mismatches.
+
+use crate::documents;
+
+/// The phrases [`RefusalDetector::default`] looks for.
+pub const DEFAULT_REFUSAL_PATTERNS: &[&str] = &[
+    "i cannot",
+    "i can't",
+    "i can not",
+    "i'm sorry",
+    "i am sorry",
+    "i apologize",
+    "i'm unable",
+    "i am unable",
+    "i'm not able",
+    "i am not able",
+    "i won't be able",
+];
+
+/// Flags outputs that contain one of its phrases, ignoring case, and no JSON object or array.
+/// Enable it with [`BamlContext::with_refusal_detection`](crate::BamlContext::with_refusal_detection).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefusalDetector {
+    patterns: Vec<String>,
+}
+
+impl Default for RefusalDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_REFUSAL_PATTERNS.iter().copied())
+    }
+}
+
+impl RefusalDetector {
+    /// Look for `patterns` instead of [`DEFAULT_REFUSAL_PATTERNS`].
+    pub fn new(patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            patterns: patterns
+                .into_iter()
+                .map(|pattern| normalize(&pattern.into()))
+                .collect(),
+        }
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    pub(crate) fn detect(&self, output: &str) -> Option<Refusal> {
+        let normalized = normalize(output);
+        if !self.patterns.iter().any(|pattern| normalized.contains(pattern.as_str())) {
+            return None;
+        }
+        // An answer that apologizes for something but still holds the data is not a refusal.
+        if !documents::split(output).is_empty() {
+            return None;
+        }
+        Some(Refusal {
+            text: output.trim().to_string(),
+        })
+    }
+}
+
+/// Lowercase, with typographic apostrophes as in `I’m sorry` made plain.
+fn normalize(text: &str) -> String {
+    text.to_lowercase().replace('\u{2019}', "'")
+}
+
+/// The error of validating an output the model refused to answer with. Tell it from other
+/// failures with `error.downcast_ref::<Refusal>()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Refusal {
+    /// The output, trimmed.
+    pub text: String,
+}
+
+impl std::fmt::Display for Refusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The model refused to answer: {}", self.text)
+    }
+}
+
+impl std::error::Error for Refusal {}
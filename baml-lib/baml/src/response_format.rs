@@ -0,0 +1,386 @@
+//! JSON Schema exports for guided decoding and structured outputs.
+
+use std::collections::BTreeMap;
+
+use baml_types::{ConstraintLevel, FieldType, LiteralValue, TypeValue};
+use internal_baml_jinja::types::OutputFormatContent;
+use serde_json::{json, Value};
+
+use crate::{version::SchemaVersion, BamlContext};
+
+/// Where a [`BamlContext::response_format`] is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodingBackend {
+    /// OpenAI structured outputs: the `response_format` of a chat completion, in strict mode.
+    OpenAi,
+    /// vLLM guided decoding: the `guided_json` extra parameter.
+    Vllm,
+    /// Text Generation Inference: the `grammar` parameter.
+    Tgi,
+}
+
+impl std::str::FromStr for DecodingBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "openai" => Ok(DecodingBackend::OpenAi),
+            "vllm" => Ok(DecodingBackend::Vllm),
+            "tgi" => Ok(DecodingBackend::Tgi),
+            _ => anyhow::bail!("Unknown decoding backend `{s}`, expected openai, vllm or tgi"),
+        }
+    }
+}
+
+impl DecodingBackend {
+    /// What the decoder can enforce. The TGI entry is conservative, for versions whose
+    /// grammars resolve neither recursive references nor typed maps and literals.
+    pub fn capabilities(self) -> Capabilities {
+        match self {
+            DecodingBackend::OpenAi => Capabilities {
+                typed_maps: false,
+                ..Capabilities::ALL
+            },
+            DecodingBackend::Vllm => Capabilities::ALL,
+            DecodingBackend::Tgi => Capabilities {
+                recursion: false,
+                typed_maps: false,
+                literals: false,
+            },
+        }
+    }
+}
+
+/// The schema features a decoder supports. Missing ones are downgraded to a looser schema,
+/// see [`Downgrade`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// `$ref`s to a definition that contains them.
+    pub recursion: bool,
+    /// `additionalProperties` with a schema for the values.
+    pub typed_maps: bool,
+    /// `enum`s of one value, for literal types.
+    pub literals: bool,
+}
+
+impl Capabilities {
+    pub const ALL: Capabilities = Capabilities {
+        recursion: true,
+        typed_maps: true,
+        literals: true,
+    };
+}
+
+/// A part of the target type the exported schema accepts more loosely than
+/// [`validate_result`](BamlContext::validate_result) does, so outputs the decoder allows may
+/// still fail validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Downgrade {
+    /// The class field (`Class.field`), type alias or target type it happened in.
+    pub location: String,
+    pub kind: DowngradeKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DowngradeKind {
+    /// A recursive reference to this class or alias accepts any value.
+    RecursionFlattened(String),
+    /// A map accepts any object, whatever its values.
+    MapAsObject,
+    /// A literal accepts any value of its type.
+    LiteralWidened(String),
+    /// An `@assert` is not enforced while decoding; its label, if any.
+    AssertDropped(Option<String>),
+}
+
+impl std::fmt::Display for Downgrade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.location)?;
+        match &self.kind {
+            DowngradeKind::RecursionFlattened(name) => {
+                write!(f, "recursive reference to `{name}` accepts any value")
+            }
+            DowngradeKind::MapAsObject => write!(f, "map accepts any object"),
+            DowngradeKind::LiteralWidened(literal) => {
+                write!(f, "literal {literal} accepts any value of its type")
+            }
+            DowngradeKind::AssertDropped(Some(label)) => {
+                write!(f, "@assert `{label}` is only checked when validating")
+            }
+            DowngradeKind::AssertDropped(None) => write!(f, "@assert is only checked when validating"),
+        }
+    }
+}
+
+impl BamlContext {
+    /// The JSON Schema of the target type, keyed by rendered names (`@alias`) as in the output
+    /// the model writes. Classes, enums and recursive aliases are under `$defs`; deprecated
+    /// fields and enum values are left out.
+    ///
+    /// With `strict`, the schema follows the rules of OpenAI's strict mode: every field is
+    /// required (optional ones accept null instead), objects have `additionalProperties: false`
+    /// and maps, which strict mode cannot express, are an error.
+    pub fn json_schema(&self, strict: bool) -> anyhow::Result<Value> {
+        Ok(export(&self.format, strict, Capabilities::ALL)?.0)
+    }
+
+    /// The JSON Schema of `target`, a class, enum, type alias or function (for its return
+    /// type) of the schema, or of the target of this context if `None`. Like
+    /// [`json_schema(false)`](Self::json_schema), for providers that take a schema instead of
+    /// the output format in the prompt.
+    pub fn to_json_schema(&self, target: Option<&str>) -> anyhow::Result<Value> {
+        let Some(target) = target else {
+            return self.json_schema(false);
+        };
+        let version = self
+            .schema_options
+            .version
+            .as_deref()
+            .map(str::parse::<SchemaVersion>)
+            .transpose()?;
+        let target_type = Self::build_target_type(
+            &self.validated_schema,
+            Some(target.to_string()),
+            version.as_ref(),
+        )?;
+        let format = Self::build_output_format(
+            &self.validated_schema,
+            target_type,
+            version.as_ref(),
+            &self.schema_options,
+        )?;
+        Ok(export(&format, false, Capabilities::ALL)?.0)
+    }
+
+    /// The target type in the request shape `backend` expects, so one context can drive both
+    /// the prompt and constrained decoding. See [`json_schema`](Self::json_schema).
+    pub fn response_format(&self, backend: DecodingBackend) -> anyhow::Result<Value> {
+        Ok(self.response_format_with_downgrades(backend)?.0)
+    }
+
+    /// Like [`response_format`](Self::response_format), also listing where the schema had to
+    /// be loosened for what `backend` [can enforce](DecodingBackend::capabilities).
+    pub fn response_format_with_downgrades(
+        &self,
+        backend: DecodingBackend,
+    ) -> anyhow::Result<(Value, Vec<Downgrade>)> {
+        let strict = backend == DecodingBackend::OpenAi;
+        let (schema, downgrades) = export(&self.format, strict, backend.capabilities())?;
+        let format = match backend {
+            DecodingBackend::OpenAi => {
+                let FieldType::Class(name) = &self.format.target else {
+                    anyhow::bail!(
+                        "OpenAI structured outputs need a class as the target, not `{}`",
+                        self.format.target
+                    );
+                };
+                json!({
+                    "type": "json_schema",
+                    "json_schema": {
+                        "name": name,
+                        "strict": true,
+                        "schema": schema,
+                    },
+                })
+            }
+            DecodingBackend::Vllm => json!({ "guided_json": schema }),
+            DecodingBackend::Tgi => json!({
+                "grammar": { "type": "json", "value": schema },
+            }),
+        };
+        Ok((format, downgrades))
+    }
+}
+
+fn export(
+    format: &OutputFormatContent,
+    strict: bool,
+    capabilities: Capabilities,
+) -> anyhow::Result<(Value, Vec<Downgrade>)> {
+    let mut schema = Schema {
+        format,
+        strict,
+        capabilities,
+        defs: BTreeMap::new(),
+        location: format.target.to_string(),
+        downgrades: vec![],
+    };
+    let mut root = schema.field_type(&format.target)?;
+    // Inline the target class so the root is an object, which OpenAI requires.
+    if let FieldType::Class(name) = &format.target {
+        root = schema.defs[name].clone();
+    }
+    if !schema.defs.is_empty() {
+        root["$defs"] = json!(schema.defs);
+    }
+    Ok((root, schema.downgrades))
+}
+
+struct Schema<'f> {
+    format: &'f OutputFormatContent,
+    strict: bool,
+    capabilities: Capabilities,
+    defs: BTreeMap<String, Value>,
+    /// Where the type being exported is, for downgrades.
+    location: String,
+    downgrades: Vec<Downgrade>,
+}
+
+impl Schema<'_> {
+    fn field_type(&mut self, field_type: &FieldType) -> anyhow::Result<Value> {
+        Ok(match field_type {
+            FieldType::Primitive(primitive) => match primitive {
+                TypeValue::String => json!({ "type": "string" }),
+                TypeValue::Int => json!({ "type": "integer" }),
+                TypeValue::Float => json!({ "type": "number" }),
+                TypeValue::Bool => json!({ "type": "boolean" }),
+                TypeValue::Null => json!({ "type": "null" }),
+                TypeValue::Bytes if self.strict => json!({ "type": "string" }),
+                TypeValue::Bytes => json!({ "type": "string", "contentEncoding": "base64" }),
+                TypeValue::Media(media) => {
+                    anyhow::bail!("Type `{media}` is not supported in outputs")
+                }
+            },
+            FieldType::Literal(literal) if !self.capabilities.literals => {
+                self.downgrade(DowngradeKind::LiteralWidened(literal.to_string()));
+                self.field_type(&literal.literal_base_type())?
+            }
+            FieldType::Literal(literal) => match literal {
+                LiteralValue::String(s) => json!({ "type": "string", "enum": [s] }),
+                LiteralValue::Int(i) => json!({ "type": "integer", "enum": [i] }),
+                LiteralValue::Bool(b) => json!({ "type": "boolean", "enum": [b] }),
+            },
+            // A lowered union of string literals is still a union of literals to the decoder.
+            FieldType::Enum(name)
+                if !self.capabilities.literals && crate::literal_enums::is_lowered(name) =>
+            {
+                for (value, ..) in &self.format.find_enum(name)?.values {
+                    let literal = LiteralValue::String(value.rendered_name().to_string());
+                    self.downgrade(DowngradeKind::LiteralWidened(literal.to_string()));
+                }
+                json!({ "type": "string" })
+            }
+            FieldType::Enum(name) => {
+                if !self.defs.contains_key(name) {
+                    let enm = self.format.find_enum(name)?;
+                    let def = if enm.open {
+                        json!({ "type": "string" })
+                    } else {
+                        json!({
+                            "type": "string",
+                            "enum": enm
+                                .values
+                                .iter()
+                                .filter(|(.., metadata)| metadata.deprecated.is_none())
+                                .map(|(value, ..)| value.rendered_name())
+                                .collect::<Vec<_>>(),
+                        })
+                    };
+                    self.defs.insert(name.clone(), def);
+                }
+                reference(name)
+            }
+            FieldType::Class(name) | FieldType::RecursiveTypeAlias(name)
+                if !self.capabilities.recursion && self.defs.get(name) == Some(&Value::Null) =>
+            {
+                self.downgrade(DowngradeKind::RecursionFlattened(name.clone()));
+                json!({})
+            }
+            FieldType::Class(name) => {
+                if !self.defs.contains_key(name) {
+                    // Placeholder so that recursive classes refer to themselves.
+                    self.defs.insert(name.clone(), Value::Null);
+                    let def = self.class(name)?;
+                    self.defs.insert(name.clone(), def);
+                }
+                reference(name)
+            }
+            FieldType::RecursiveTypeAlias(name) => {
+                if !self.defs.contains_key(name) {
+                    self.defs.insert(name.clone(), Value::Null);
+                    let target = self.format.find_recursive_alias_target(name)?;
+                    let location = std::mem::replace(&mut self.location, name.clone());
+                    let def = self.field_type(target);
+                    self.location = location;
+                    let def = def?;
+                    self.defs.insert(name.clone(), def);
+                }
+                reference(name)
+            }
+            FieldType::List(item) => json!({ "type": "array", "items": self.field_type(item)? }),
+            FieldType::Map(_, value) => {
+                if self.strict {
+                    anyhow::bail!("Maps are not supported by strict structured outputs");
+                }
+                if !self.capabilities.typed_maps {
+                    self.downgrade(DowngradeKind::MapAsObject);
+                    return Ok(json!({ "type": "object" }));
+                }
+                json!({ "type": "object", "additionalProperties": self.field_type(value)? })
+            }
+            FieldType::Union(items) => json!({
+                "anyOf": items
+                    .iter()
+                    .map(|item| self.field_type(item))
+                    .collect::<anyhow::Result<Vec<_>>>()?,
+            }),
+            FieldType::Optional(inner) => {
+                json!({ "anyOf": [self.field_type(inner)?, { "type": "null" }] })
+            }
+            FieldType::Tuple(_) => anyhow::bail!("Tuple type is not supported in outputs"),
+            FieldType::Constrained { base, constraints } => {
+                for constraint in constraints {
+                    if constraint.level == ConstraintLevel::Assert {
+                        self.downgrade(DowngradeKind::AssertDropped(constraint.label.clone()));
+                    }
+                }
+                self.field_type(base)?
+            }
+        })
+    }
+
+    fn class(&mut self, name: &str) -> anyhow::Result<Value> {
+        let class = self.format.find_class(name)?;
+        let mut properties = serde_json::Map::new();
+        let mut required = vec![];
+        for (field, field_type, description, metadata) in &class.fields {
+            if metadata.deprecated.is_some() {
+                continue;
+            }
+            let location = std::mem::replace(
+                &mut self.location,
+                format!("{name}.{}", field.rendered_name()),
+            );
+            let property = self.field_type(field_type);
+            self.location = location;
+            let mut property = property?;
+            // Keywords next to `$ref` are ignored by some validators, so references go without.
+            if let (Some(description), None) = (description, property.get("$ref")) {
+                property["description"] = json!(description);
+            }
+            properties.insert(field.rendered_name().to_string(), property);
+            if self.strict || !field_type.is_optional() {
+                required.push(field.rendered_name().to_string());
+            }
+        }
+        Ok(json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false,
+        }))
+    }
+}
+
+impl Schema<'_> {
+    fn downgrade(&mut self, kind: DowngradeKind) {
+        self.downgrades.push(Downgrade {
+            location: self.location.clone(),
+            kind,
+        });
+    }
+}
+
+fn reference(name: &str) -> Value {
+    json!({ "$ref": format!("#/$defs/{name}") })
+}
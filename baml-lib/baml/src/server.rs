@@ -0,0 +1,197 @@
+//! HTTP service exposing [`crate::BamlContext`] rendering and validation.
+//!
+//! Enabled with the `server` feature. Schemas are validated once and cached by
+//! `(schema, target_name)`, so clients can send the same schema with every request.
+
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+use serde::{Deserialize, Serialize};
+
+pub use crate::cache::SchemaCache;
+
+#[derive(Deserialize)]
+pub struct RenderPromptRequest {
+    pub schema: String,
+    pub target_name: Option<String>,
+    pub prefix: Option<String>,
+    pub always_hoist_enums: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct RenderPromptResponse {
+    pub prompt: String,
+}
+
+#[derive(Deserialize)]
+pub struct ValidateResultRequest {
+    pub schema: String,
+    pub target_name: Option<String>,
+    pub result: String,
+    #[serde(default)]
+    pub allow_partials: bool,
+}
+
+#[derive(Serialize)]
+pub struct ValidateResultResponse {
+    /// The value as [`BamlContext::validate_result`](crate::BamlContext::validate_result)
+    /// encodes it.
+    pub value: serde_json::Value,
+}
+
+#[derive(Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+type HandlerResult<T> = Result<Json<T>, (StatusCode, Json<ErrorResponse>)>;
+
+fn bad_request(err: anyhow::Error) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse {
+            error: format!("{err:#}"),
+        }),
+    )
+}
+
+async fn render_prompt(
+    State(cache): State<Arc<SchemaCache>>,
+    Json(request): Json<RenderPromptRequest>,
+) -> HandlerResult<RenderPromptResponse> {
+    let context = cache
+        .get_or_build(&request.schema, request.target_name.as_deref())
+        .map_err(bad_request)?;
+    let prompt = context
+        .render_prompt(request.prefix, request.always_hoist_enums)
+        .map_err(bad_request)?;
+    Ok(Json(RenderPromptResponse { prompt }))
+}
+
+async fn validate_result(
+    State(cache): State<Arc<SchemaCache>>,
+    Json(request): Json<ValidateResultRequest>,
+) -> HandlerResult<ValidateResultResponse> {
+    let context = cache
+        .get_or_build(&request.schema, request.target_name.as_deref())
+        .map_err(bad_request)?;
+    let value = context
+        .parse_result(&request.result, request.allow_partials)
+        .map_err(bad_request)?;
+    let value = serde_json::to_value(context.float_format.apply(value))
+        .map_err(|e| bad_request(e.into()))?;
+    Ok(Json(ValidateResultResponse { value }))
+}
+
+/// The `/render_prompt` and `/validate_result` routes, sharing `cache`.
+pub fn router(cache: Arc<SchemaCache>) -> Router {
+    Router::new()
+        .route("/render_prompt", post(render_prompt))
+        .route("/validate_result", post(validate_result))
+        .with_state(cache)
+}
+
+/// Serve [`router`] on `addr` until the process is stopped.
+pub async fn serve(addr: SocketAddr) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router(Arc::new(SchemaCache::default()))).await
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::Request,
+    };
+    use tower::ServiceExt;
+
+    use super::*;
+
+    const SCHEMA: &str = r#"
+class Paint {
+  color string
+  liters float
+}
+"#;
+
+    async fn post_json(
+        router: Router,
+        uri: &str,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        let response = router
+            .oneshot(
+                Request::post(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn render_and_validate_share_cache() {
+        let cache = Arc::new(SchemaCache::default());
+
+        let (status, body) = post_json(
+            router(cache.clone()),
+            "/render_prompt",
+            serde_json::json!({ "schema": SCHEMA, "target_name": "Paint" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body["prompt"],
+            "Answer in JSON using this schema:\n{\n  color: string,\n  liters: float,\n}"
+        );
+
+        let (status, body) = post_json(
+            router(cache.clone()),
+            "/validate_result",
+            serde_json::json!({
+                "schema": SCHEMA,
+                "target_name": "Paint",
+                "result": "{\"color\": \"red\", \"liters\": \"2\"}",
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body["value"],
+            serde_json::json!({ "color": "red", "liters": 2.0 })
+        );
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn enum_target_is_a_json_string() {
+        let (status, body) = post_json(
+            router(Arc::new(SchemaCache::default())),
+            "/validate_result",
+            serde_json::json!({
+                "schema": "enum Color {\n  Red\n  Green\n}",
+                "target_name": "Color",
+                "result": "green",
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["value"], "Green");
+    }
+
+    #[tokio::test]
+    async fn invalid_schema_is_a_bad_request() {
+        let (status, body) = post_json(
+            router(Arc::new(SchemaCache::default())),
+            "/render_prompt",
+            serde_json::json!({ "schema": "class Paint {" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("error"));
+    }
+}
@@ -0,0 +1,101 @@
+//! Validating an LLM output as it streams in.
+
+use anyhow::Context;
+use baml_types::BamlValue;
+use serde_json::Value;
+
+use crate::BamlContext;
+
+/// The output received so far, and the last value parsed from it. Start each stream with
+/// `StreamState::default()`.
+#[derive(Debug, Clone, Default)]
+pub struct StreamState {
+    buffer: String,
+    last: Option<BamlValue>,
+}
+
+impl StreamState {
+    /// The output received so far.
+    pub fn output(&self) -> &str {
+        &self.buffer
+    }
+
+    /// The last value parsed from the output, if any chunk parsed.
+    pub fn last(&self) -> Option<&BamlValue> {
+        self.last.as_ref()
+    }
+}
+
+impl BamlContext {
+    /// Append `chunk` to the output in `state` and return the best-effort partial value so far,
+    /// as [`parse_result`](Self::parse_result) with partials allowed. When the output does not
+    /// parse yet, e.g. mid-way through a key, the last value that did is returned.
+    pub fn validate_stream_chunk(
+        &self,
+        state: &mut StreamState,
+        chunk: &str,
+    ) -> Option<BamlValue> {
+        state.buffer.push_str(chunk);
+        // Whitespace changes nothing the parser would see.
+        if chunk.trim().is_empty() && state.last.is_some() {
+            return state.last.clone();
+        }
+        if let Ok(value) = self.parse_result(&state.buffer, true) {
+            state.last = Some(value);
+        }
+        state.last.clone()
+    }
+
+    /// Parse the complete output of a stream, which must fill every required field.
+    pub fn finish_stream(&self, state: StreamState) -> anyhow::Result<BamlValue> {
+        self.parse_result(&state.buffer, false)
+    }
+}
+
+/// The text deltas of a captured server-sent events transcript, in order: the
+/// `choices[0].delta.content` of OpenAI-style chunks and the `delta.text` of Anthropic
+/// `content_block_delta` events. Other events and the final `[DONE]` are skipped.
+pub fn sse_deltas(transcript: &str) -> anyhow::Result<Vec<String>> {
+    let mut deltas = vec![];
+    let mut data = vec![];
+    // A blank line ends an event; a transcript may lack the last one.
+    for (index, line) in transcript.lines().chain([""]).enumerate() {
+        let line = line.trim_end_matches('\r');
+        if let Some(field) = line.strip_prefix("data:") {
+            data.push(field.strip_prefix(' ').unwrap_or(field));
+            continue;
+        }
+        if !line.is_empty() || data.is_empty() {
+            continue;
+        }
+        let event = data.join("\n");
+        data.clear();
+        if event == "[DONE]" {
+            continue;
+        }
+        let event: Value = serde_json::from_str(&event)
+            .with_context(|| format!("Invalid JSON in the event ending on line {}", index + 1))?;
+        let delta = if event["type"] == "content_block_delta" {
+            &event["delta"]["text"]
+        } else {
+            &event["choices"][0]["delta"]["content"]
+        };
+        if let Some(delta) = delta.as_str() {
+            deltas.push(delta.to_string());
+        }
+    }
+    Ok(deltas)
+}
+
+impl BamlContext {
+    /// Feed the deltas of a captured SSE transcript (see [`sse_deltas`]) through
+    /// [`validate_stream_chunk`](Self::validate_stream_chunk) and return the
+    /// [finished](Self::finish_stream) value, to check logged streams again.
+    pub fn validate_sse_transcript(&self, transcript: &str) -> anyhow::Result<BamlValue> {
+        let mut state = StreamState::default();
+        for delta in sse_deltas(transcript)? {
+            self.validate_stream_chunk(&mut state, &delta);
+        }
+        self.finish_stream(state)
+    }
+}
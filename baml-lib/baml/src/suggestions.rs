@@ -0,0 +1,193 @@
+//! Advisory analysis of schema patterns that bloat the rendered prompt.
+//!
+//! Suggestions never make a schema invalid: [`BamlContext::suggestions`](crate::BamlContext::suggestions)
+//! returns them, and [`Suggestion::to_warning`] turns them into diagnostics warnings for tools
+//! that want to surface them (e.g. `baml-lib validate --suggestions`).
+
+use baml_types::EvaluationContext;
+use internal_baml_core::{
+    ast::{self, SubType, WithName, WithSpan},
+    internal_baml_diagnostics::{DatamodelWarning, Span},
+    internal_baml_parser_database::{Attributes, ParserDatabase},
+};
+
+/// Limits above which a pattern is reported.
+#[derive(Debug, Clone)]
+pub struct Thresholds {
+    /// Unions (including optionals) nested deeper than this.
+    pub max_union_depth: usize,
+    /// Enums with more values than this.
+    pub max_enum_values: usize,
+    /// Descriptions longer than this many characters.
+    pub max_description_len: usize,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            max_union_depth: 2,
+            max_enum_values: 20,
+            max_description_len: 200,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestionKind {
+    /// A field type with deeply nested (optional) unions.
+    NestedUnion,
+    /// An enum with many values.
+    LargeEnum,
+    /// A description over the length threshold.
+    VerboseDescription,
+}
+
+impl SuggestionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SuggestionKind::NestedUnion => "nested_union",
+            SuggestionKind::LargeEnum => "large_enum",
+            SuggestionKind::VerboseDescription => "verbose_description",
+        }
+    }
+}
+
+/// One prompt-bloating pattern and how to avoid it.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub kind: SuggestionKind,
+    /// The type or member, e.g. `Order`, `Order.status` or `Status.Shipped`.
+    pub path: String,
+    pub message: String,
+    pub span: Span,
+}
+
+impl Suggestion {
+    /// The suggestion as a diagnostics warning at its span.
+    pub fn to_warning(&self) -> DatamodelWarning {
+        DatamodelWarning::new(self.message.clone(), self.span.clone())
+    }
+}
+
+/// Suggestions for every class, then every enum, in `db`.
+pub fn analyze(db: &ParserDatabase, thresholds: &Thresholds) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+    for class in db.walk_classes() {
+        let attributes = class.get_default_attributes(SubType::Class);
+        check_description(
+            &mut suggestions,
+            thresholds,
+            class.name(),
+            attributes,
+            class.ast_type_block().name.span(),
+        );
+        for field in class.static_fields() {
+            let path = format!("{}.{}", class.name(), field.name());
+            check_description(
+                &mut suggestions,
+                thresholds,
+                &path,
+                field.get_default_attributes(),
+                field.span(),
+            );
+            let depth = field.r#type().as_ref().map_or(0, |t| union_depth(t, false));
+            if depth > thresholds.max_union_depth {
+                suggestions.push(Suggestion {
+                    kind: SuggestionKind::NestedUnion,
+                    message: format!(
+                        "`{path}` nests unions {depth} levels deep; flatten them into a single \
+                         union or move the alternatives into a class"
+                    ),
+                    path,
+                    span: field.span().clone(),
+                });
+            }
+        }
+    }
+    for enm in db.walk_enums() {
+        let attributes = enm.get_default_attributes(SubType::Enum);
+        check_description(
+            &mut suggestions,
+            thresholds,
+            enm.name(),
+            attributes,
+            enm.ast_type_block().name.span(),
+        );
+        let values = enm.values();
+        if values.len() > thresholds.max_enum_values {
+            suggestions.push(Suggestion {
+                kind: SuggestionKind::LargeEnum,
+                path: enm.name().to_string(),
+                message: format!(
+                    "`{}` has {} values; split it or use a string field with a description",
+                    enm.name(),
+                    values.len()
+                ),
+                span: enm.ast_type_block().name.span().clone(),
+            });
+        }
+        for value in values {
+            let path = format!("{}.{}", enm.name(), value.name());
+            check_description(
+                &mut suggestions,
+                thresholds,
+                &path,
+                value.get_default_attributes(),
+                value.span(),
+            );
+        }
+    }
+    suggestions
+}
+
+fn check_description(
+    suggestions: &mut Vec<Suggestion>,
+    thresholds: &Thresholds,
+    path: &str,
+    attributes: Option<&Attributes>,
+    span: &Span,
+) {
+    let ctx = EvaluationContext::default();
+    let Some(description) = attributes
+        .and_then(|a| a.description().as_ref())
+        .and_then(|d| d.as_str())
+        .and_then(|d| d.resolve(&ctx).ok())
+    else {
+        return;
+    };
+    let len = description.chars().count();
+    if len > thresholds.max_description_len {
+        suggestions.push(Suggestion {
+            kind: SuggestionKind::VerboseDescription,
+            path: path.to_string(),
+            message: format!(
+                "`{path}` has a {len}-character description (over {}); shorten it",
+                thresholds.max_description_len
+            ),
+            span: span.clone(),
+        });
+    }
+}
+
+/// How many unions are nested in `field_type`, counting a top-level optional as a union with null.
+fn union_depth(field_type: &ast::FieldType, in_union: bool) -> usize {
+    match field_type {
+        ast::FieldType::Union(_, items, ..) => {
+            1 + items
+                .iter()
+                .map(|t| union_depth(t, true))
+                .max()
+                .unwrap_or(0)
+        }
+        ast::FieldType::List(_, inner, ..) => union_depth(inner, false),
+        ast::FieldType::Map(_, kv, ..) => union_depth(&kv.1, false),
+        ast::FieldType::Tuple(_, items, ..) => items
+            .iter()
+            .map(|t| union_depth(t, false))
+            .max()
+            .unwrap_or(0),
+        ast::FieldType::Symbol(arity, ..)
+        | ast::FieldType::Primitive(arity, ..)
+        | ast::FieldType::Literal(arity, ..) => usize::from(!in_union && arity.is_optional()),
+    }
+}
@@ -0,0 +1,3112 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use baml_types::{BamlMap, BamlValue, FieldType};
+
+use crate::{
+    from_json_schema,
+    refactor::{apply_edits, extract_class, rename_symbol},
+    sse_deltas,
+    suggestions::SuggestionKind, BamlContext, BamlValueWithFlags, BoolVocabulary, CoercerMiddleware,
+    CustomCoercer, DecodingBackend, Downgrade, DowngradeKind, FieldOrder, IntOverflow, MapStyle,
+    NumberOverflow, ParsingError, PromptOptions, Reasoned, RecursionRendering, ReferenceStyle, RenderFormat, RenderProfile,
+    RenderStyle, ReservedNamePolicy, SchemaOptions, Severity, StreamState, TemplateCatalog, TypeKind, UnionStyle, XmlTags,
+};
+
+const FRUIT_SCHEMA: &str = r#"
+enum FruitName {
+  Apple
+  Banana
+  Orange
+  Others @description("Default")
+}
+
+class Fruit {
+  fruit       FruitName
+  price       int @description("Price per unit") @alias("fruit_price")
+  dateSold    string
+  received    bool
+}
+
+class FruitOrders {
+  id    string
+  fruit Fruit[]
+}
+"#;
+
+fn load_context(schema: &str, target: Option<&str>) -> BamlContext {
+    BamlContext::try_from_schema(schema, target.map(str::to_string))
+        .expect("schema should be valid")
+}
+
+#[test]
+fn render_prompt_with_hoisted_enums() {
+    let ctx = load_context(FRUIT_SCHEMA, Some("FruitOrders"));
+    let rendered = ctx.render_prompt(None, Some(true)).unwrap();
+    assert_eq!(
+        rendered,
+        r#"FruitName
+----
+- Apple
+- Banana
+- Orange
+- Others: Default
+
+Answer in JSON using this schema:
+{
+  id: string,
+  fruit: [
+    {
+      fruit: FruitName,
+      // Price per unit
+      fruit_price: int,
+      dateSold: string,
+      received: bool,
+    }
+  ],
+}"#
+    );
+}
+
+#[test]
+fn validate_partial_result() {
+    let ctx = load_context(FRUIT_SCHEMA, Some("FruitOrders"));
+    let result = r#"
+{
+  "id": 1234,
+  "fruit": [{
+    "fruit": "apple",
+    "dateSold": "123456",
+    "received": false,
+    "fruit_price": 123
+"#
+    .trim()
+    .to_string();
+    let parsed = ctx.validate_result(&result, true).unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&parsed).unwrap(),
+        serde_json::json!({
+            "id": "1234",
+            "fruit": [{
+                "fruit": "Apple",
+                "fruit_price": null,
+                "dateSold": "123456",
+                "received": false
+            }]
+        })
+    );
+}
+
+#[test]
+fn default_target_is_first_class() {
+    let ctx = load_context(FRUIT_SCHEMA, None);
+    assert_eq!(ctx.target, baml_types::FieldType::class("Fruit"));
+}
+
+#[test]
+fn invalid_schema_is_an_error() {
+    let err = BamlContext::try_from_schema("class Foo {", None).unwrap_err();
+    assert!(err.to_string().contains("error"), "{err}");
+}
+
+#[test]
+fn validation_sink_receives_one_event_per_call() {
+    use crate::events::ValidationEvent;
+    use std::sync::{Arc, Mutex};
+
+    let events: Arc<Mutex<Vec<ValidationEvent>>> = Arc::default();
+    let recorded = events.clone();
+    let ctx = load_context(FRUIT_SCHEMA, Some("FruitOrders")).with_validation_sink(Arc::new(
+        move |event: &ValidationEvent| recorded.lock().unwrap().push(event.clone()),
+    ));
+
+    ctx.validate_result(
+        r#"{"id": "1", "fruit": {"fruit": "Apple", "fruit_price": 3, "dateSold": "today", "received": true}}"#,
+        false,
+    )
+    .unwrap();
+    assert!(ctx.validate_result("no json here", false).is_err());
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].target, "FruitOrders");
+    assert_eq!(events[0].schema_hash, ctx.schema_hash());
+    assert!(events[0].outcome.is_success());
+    assert_eq!(events[0].flag_counts.get("SingleToArray"), Some(&1));
+    assert_eq!(events[0].field_flag_counts.get("<root>.fruit"), Some(&1));
+    assert!(events[0].total_duration >= events[0].parse_duration);
+    assert!(!events[1].outcome.is_success());
+    assert!(events[1].flag_counts.is_empty());
+}
+
+#[test]
+fn schema_hash_is_the_sha256_of_the_source() {
+    let ctx = load_context("class T {\n  a int\n}\n", Some("T"));
+    assert_eq!(
+        ctx.schema_hash(),
+        "fa3810f120b18470e7e7333cd8320a04fc019cbe8d65a4ff4c985ee6c910df55"
+    );
+}
+
+#[test]
+fn fingerprint_tracks_rendered_semantics() {
+    let base = load_context(FRUIT_SCHEMA, Some("Fruit")).fingerprint();
+    assert_eq!(base.len(), 64);
+    assert_eq!(
+        base,
+        load_context(FRUIT_SCHEMA, Some("Fruit")).fingerprint()
+    );
+
+    // Unrelated types and comments do not matter.
+    let unrelated = format!("// A comment\n{FRUIT_SCHEMA}\nclass Basket {{\n  size int\n}}\n");
+    assert_eq!(base, load_context(&unrelated, Some("Fruit")).fingerprint());
+
+    // Descriptions of reachable types, the target and render options do.
+    let described = FRUIT_SCHEMA.replace("Default", "Anything else");
+    assert_ne!(base, load_context(&described, Some("Fruit")).fingerprint());
+    let aliased = FRUIT_SCHEMA.replace("fruit_price", "unit_price");
+    assert_ne!(base, load_context(&aliased, Some("Fruit")).fingerprint());
+    assert_ne!(
+        base,
+        load_context(FRUIT_SCHEMA, Some("FruitOrders")).fingerprint()
+    );
+    let ctx = load_context(FRUIT_SCHEMA, Some("Fruit"));
+    let with = |options: PromptOptions| ctx.fingerprint_with_options(&options);
+    assert_eq!(base, with(PromptOptions::default()));
+    assert_ne!(
+        base,
+        with(PromptOptions {
+            always_hoist_enums: Some(true),
+            ..Default::default()
+        })
+    );
+    assert_ne!(
+        base,
+        with(PromptOptions {
+            union_style: UnionStyle::Variants,
+            ..Default::default()
+        })
+    );
+    assert_ne!(
+        base,
+        with(PromptOptions {
+            or_splitter: Some(" | ".to_string()),
+            ..Default::default()
+        })
+    );
+    assert_ne!(
+        base,
+        with(PromptOptions {
+            map_style: Some(MapStyle::ObjectLiteral),
+            ..Default::default()
+        })
+    );
+    assert_ne!(
+        base,
+        ctx.with_field_order(FieldOrder::Alphabetical).fingerprint()
+    );
+}
+
+#[test]
+fn deprecated_members_are_parsed_but_not_rendered() {
+    use crate::events::ValidationEvent;
+    use std::sync::{Arc, Mutex};
+
+    let schema = r#"
+enum Size {
+  Small
+  Large
+  Huge @deprecated("use Large instead")
+}
+
+class Shirt {
+  size   Size
+  colour string
+  color  string @deprecated("use colour instead")
+}
+"#;
+    let events: Arc<Mutex<Vec<ValidationEvent>>> = Arc::default();
+    let recorded = events.clone();
+    let ctx = load_context(schema, Some("Shirt")).with_validation_sink(Arc::new(
+        move |event: &ValidationEvent| recorded.lock().unwrap().push(event.clone()),
+    ));
+    let rendered = ctx.render_prompt(None, None).unwrap();
+    assert!(!rendered.contains("Huge"), "{rendered}");
+    assert!(!rendered.contains("color:"), "{rendered}");
+    assert!(rendered.contains("colour"), "{rendered}");
+
+    // Legacy outputs are still accepted.
+    let legacy = ctx
+        .validate_result(
+            r#"{"size": "Huge", "color": "red", "colour": "red"}"#,
+            false,
+        )
+        .unwrap();
+    assert_eq!(legacy, r#"{"size":"Huge","color":"red","colour":"red"}"#);
+    assert_eq!(
+        events.lock().unwrap()[0].flag_counts.get("Deprecated"),
+        Some(&2)
+    );
+
+    // Deprecated fields may be left out, even when not optional.
+    let current = ctx
+        .validate_result(r#"{"size": "Large", "colour": "red"}"#, false)
+        .unwrap();
+    assert_eq!(current, r#"{"size":"Large","colour":"red","color":null}"#);
+}
+
+#[test]
+fn deprecated_requires_a_message_and_a_member() {
+    let schema = "class Shirt {\n  colour string\n  @@deprecated(\"gone\")\n}";
+    assert!(BamlContext::try_from_schema(schema, None).is_err());
+    let schema = "class Shirt {\n  colour string @deprecated\n}";
+    assert!(BamlContext::try_from_schema(schema, None).is_err());
+}
+
+const VERSIONED_SCHEMA: &str = r#"
+class Address {
+  street string
+  city   string @until("2")
+  town   string @since("2")
+}
+
+class Label {
+  text string
+  @@since("1.5")
+}
+
+class Person {
+  name    string
+  address Address
+  nick    string? @since("1.5")
+  label   Label?  @since("1.5")
+}
+"#;
+
+#[test]
+fn versioned_schema_materializes_fields_per_version() {
+    let render = |version: &str| {
+        BamlContext::try_from_schema_versioned(
+            VERSIONED_SCHEMA,
+            Some("Person".to_string()),
+            version,
+        )
+        .unwrap()
+        .render_prompt(None, None)
+        .unwrap()
+    };
+
+    let v1 = render("1");
+    assert!(
+        v1.contains("city") && !v1.contains("town") && !v1.contains("nick"),
+        "{v1}"
+    );
+    let v15 = render("1.5.0");
+    assert!(
+        v15.contains("city") && v15.contains("nick") && v15.contains("text"),
+        "{v15}"
+    );
+    let v2 = render("2");
+    assert!(!v2.contains("city") && v2.contains("town"), "{v2}");
+
+    // Without a version, every member is part of the schema.
+    let all = load_context(VERSIONED_SCHEMA, Some("Person"))
+        .render_prompt(None, None)
+        .unwrap();
+    assert!(all.contains("city") && all.contains("town"), "{all}");
+}
+
+#[test]
+fn versioned_schema_rejects_unavailable_types() {
+    let err =
+        BamlContext::try_from_schema_versioned(VERSIONED_SCHEMA, Some("Label".to_string()), "1")
+            .unwrap_err();
+    assert!(
+        err.to_string().contains("not part of schema version 1"),
+        "{err}"
+    );
+
+    let err = BamlContext::try_from_schema_versioned(VERSIONED_SCHEMA, None, "v2").unwrap_err();
+    assert!(err.to_string().contains("Invalid schema version"), "{err}");
+
+    let invalid = "class Foo {\n  bar string @since(\"next\")\n}".to_string();
+    assert!(BamlContext::try_from_schema(&invalid, None).is_err());
+}
+
+const OPEN_ENUM_SCHEMA: &str = r#"
+enum Channel {
+  Email
+  Phone
+  @@open
+}
+
+class Contact {
+  channel Channel
+}
+"#;
+
+#[test]
+fn open_enum_keeps_unmatched_strings() {
+    let ctx = load_context(OPEN_ENUM_SCHEMA, Some("Contact"));
+    let rendered = ctx.render_prompt(None, None).unwrap();
+    assert!(
+        rendered.contains("'Email' or 'Phone' or any other value"),
+        "{rendered}"
+    );
+
+    let matched = ctx
+        .validate_result(r#"{"channel": "email"}"#, false)
+        .unwrap();
+    assert_eq!(matched, r#"{"channel":"Email"}"#);
+    let other = ctx
+        .validate_result(r#"{"channel": " Carrier pigeon "}"#, false)
+        .unwrap();
+    assert_eq!(other, r#"{"channel":"Carrier pigeon"}"#);
+
+    let closed = load_context(&OPEN_ENUM_SCHEMA.replace("@@open", ""), Some("Contact"));
+    assert!(closed
+        .validate_result(r#"{"channel": "Carrier pigeon"}"#, false)
+        .is_err());
+    assert_ne!(ctx.fingerprint(), closed.fingerprint());
+}
+
+#[test]
+fn only_enums_can_be_open() {
+    let schema = "class Contact {\n  channel string\n  @@open\n}".to_string();
+    let err = BamlContext::try_from_schema(&schema, None).unwrap_err();
+    assert!(
+        err.to_string().contains("only enums can be @@open"),
+        "{err}"
+    );
+}
+
+#[test]
+fn omitting_missing_fields_distinguishes_null_from_absent() {
+    let schema = r#"
+class Item {
+  qty int
+}
+
+class Patch {
+  name  string?
+  note  string?
+  tags  string[]
+  items Item[]
+}
+"#;
+    let ctx = load_context(schema, Some("Patch"));
+    let output = r#"{"name": null, "items": [{"qty": 1}]}"#.to_string();
+
+    assert_eq!(
+        ctx.validate_result(&output, false).unwrap(),
+        r#"{"name":null,"items":[{"qty":1}],"note":null,"tags":[]}"#
+    );
+    assert_eq!(
+        ctx.validate_result_omitting_missing(&output, false).unwrap(),
+        r#"{"name":null,"items":[{"qty":1}]}"#
+    );
+}
+
+#[test]
+fn number_overflow_controls_big_numbers() {
+    let schema = "class Invoice {\n  id int\n  amount float\n}";
+    let output = r#"{"id": 98765432109876543210, "amount": 1234567890123.456789}"#.to_string();
+
+    let ctx = load_context(schema, None).with_number_overflow(NumberOverflow::Preserve);
+    assert_eq!(
+        ctx.validate_result(&output, false).unwrap(),
+        r#"{"id":98765432109876543210,"amount":1234567890123.456789}"#
+    );
+
+    let ctx = load_context(schema, None).with_number_overflow(NumberOverflow::Error);
+    assert!(ctx.validate_result(&output, false).is_err());
+}
+
+#[test]
+fn bool_vocabulary_accepts_configured_words() {
+    let schema = "class Answer {\n  agreed bool\n}";
+    let output = r#"{"agreed": "Oui"}"#.to_string();
+    assert!(load_context(schema, None).validate_result(&output, false).is_err());
+
+    let vocabulary = BoolVocabulary::default().with_locale("fr").unwrap();
+    let ctx = load_context(schema, None).with_bool_vocabulary(vocabulary.clone());
+    assert_eq!(ctx.validate_result(&output, false).unwrap(), r#"{"agreed":true}"#);
+    let flagged = ctx.parse_result_with_flags(&output, false).unwrap();
+    assert_eq!(flagged.get("agreed").unwrap().flags()[0].kind, "StringToBool");
+
+    // Later additions win over the locale pack.
+    let ctx = load_context(schema, None).with_bool_vocabulary(vocabulary.with_falsy(["oui"]));
+    assert_eq!(ctx.validate_result(&output, false).unwrap(), r#"{"agreed":false}"#);
+    assert!(BoolVocabulary::default().with_locale("xx").is_err());
+}
+
+#[test]
+fn null_synonyms_parse_as_null_for_optional_fields() {
+    let schema = r#"
+class Listing {
+  title string
+  price int?
+  color string? @null_values(["-", "n/a"])
+}
+"#;
+    let output = r#"{"title": "N/A", "price": "unknown", "color": "N/A"}"#.to_string();
+    let ctx = load_context(schema, None);
+    assert_eq!(
+        ctx.validate_result(&output, false).unwrap(),
+        r#"{"title":"N/A","price":null,"color":null}"#
+    );
+    let flagged = ctx.parse_result_with_flags(&output, false).unwrap();
+    assert_eq!(flagged.get("color").unwrap().flags()[0].kind, "NullSynonym");
+    assert_ne!(flagged.get("price").unwrap().flags()[0].kind, "NullSynonym");
+
+    // Only optional values become null; the field's list replaces the global one.
+    let ctx = load_context(schema, None).with_null_values(vec!["unknown".to_string()]);
+    let flagged = ctx.parse_result_with_flags(&output, false).unwrap();
+    assert_eq!(flagged.get("title").unwrap().value(), BamlValue::String("N/A".to_string()));
+    assert_eq!(flagged.get("price").unwrap().flags()[0].kind, "NullSynonym");
+    assert_eq!(flagged.get("color").unwrap().flags()[0].kind, "NullSynonym");
+
+    let err = BamlContext::try_from_schema(
+        "class Listing {\n  title string @null_values([\"-\"])\n}",
+        None,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("only applies to optional fields"), "{err}");
+}
+
+#[test]
+fn trace_result_records_union_candidates() {
+    let schema = r#"
+class Dog {
+  barks bool
+}
+class Cat {
+  meows bool
+}
+class Pet {
+  animal Dog | Cat
+  age int @check(adult, {{ this >= 2 }})
+}
+"#;
+    let ctx = load_context(schema, Some("Pet"));
+    let output = r#"{"animal": {"meows": true}, "age": "3"}"#.to_string();
+    let trace = ctx.trace_result(&output, false);
+    assert!(trace.error.is_none());
+    let root = trace.root.as_ref().unwrap();
+    assert_eq!(root.kind, "Pet");
+    let animal = &root.children[0];
+    assert_eq!((animal.path.as_str(), animal.kind.as_str()), ("<root>.animal", "Cat"));
+    assert_eq!(animal.candidates.len(), 2);
+    assert!(animal.candidates[1].chosen);
+    let age = &root.children[1];
+    assert!(age.constraints[0].passed);
+
+    assert!(trace.to_text().contains("<root>.animal Cat"));
+    assert!(trace.to_html().contains("&lt;root&gt;.animal Cat"));
+    let json: serde_json::Value = serde_json::from_str(&trace.to_json()).unwrap();
+    assert_eq!(json["root"]["children"][0]["candidates"][1]["chosen"], true);
+
+    let trace = ctx.trace_result("no pets here", false);
+    assert!(trace.root.is_none() && trace.error.is_some());
+}
+
+#[test]
+fn int_overflow_policy_per_call_and_per_field() {
+    let schema = r#"
+class Counters {
+  views int @int_overflow("saturate")
+  likes int
+}
+"#;
+    let output = r#"{"views": 98765432109876543210, "likes": 98765432109876543210}"#.to_string();
+
+    let ctx = load_context(schema, None).with_int_overflow(IntOverflow::Error);
+    assert!(ctx.validate_result(&output, false).is_err());
+
+    let ctx = load_context(schema, None).with_int_overflow(IntOverflow::CoerceToFloat);
+    let value: serde_json::Value =
+        serde_json::from_str(&ctx.validate_result(&output, false).unwrap()).unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!({"views": 9223372036854775807i64, "likes": 9.876543210987654e19})
+    );
+    let flagged = ctx.parse_result_with_flags(&output, false).unwrap();
+    assert_eq!(flagged.get("views").unwrap().flags()[0].kind, "IntSaturated");
+    assert_eq!(flagged.get("likes").unwrap().flags()[0].kind, "IntToFloat");
+
+    let err = BamlContext::try_from_schema(
+        "class Counters {\n  name string @int_overflow(\"saturate\")\n}",
+        None,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("only applies to fields holding an `int`"), "{err}");
+}
+
+#[test]
+fn bytes_fields_are_base64_in_json_and_raw_when_parsed() {
+    let ctx = load_context("class Thumbnail {\n  mime string\n  data bytes\n}", None);
+    let rendered = ctx.render_prompt(None, None).unwrap();
+    assert!(rendered.contains("data: string (base64)"), "{rendered}");
+
+    let output = r#"{"mime": "image/png", "data": "data:image/png;base64,iVBORw=="}"#.to_string();
+    assert_eq!(
+        ctx.validate_result(&output, false).unwrap(),
+        r#"{"mime":"image/png","data":"iVBORw=="}"#
+    );
+    let BamlValue::Class(_, fields) = ctx.parse_result(&output, false).unwrap() else {
+        panic!("expected a class");
+    };
+    assert_eq!(fields["data"], BamlValue::Bytes(vec![0x89, b'P', b'N', b'G']));
+}
+
+/// Parses formulas such as `H2O` into their elements and counts.
+struct FormulaCoercer;
+
+impl CustomCoercer for FormulaCoercer {
+    fn coerce(&self, raw: &str, _target: &FieldType) -> anyhow::Result<BamlValue> {
+        let mut elements = Vec::new();
+        let mut rest = raw.trim();
+        while let Some(first) = rest.chars().next() {
+            anyhow::ensure!(first.is_ascii_uppercase(), "expected an element in {raw:?}");
+            let symbol_len = 1 + rest[1..].chars().take_while(char::is_ascii_lowercase).count();
+            let (symbol, tail) = rest.split_at(symbol_len);
+            let digits = tail.chars().take_while(char::is_ascii_digit).count();
+            let count = if digits == 0 { 1 } else { tail[..digits].parse()? };
+            elements.push(BamlValue::Map(BamlMap::from([
+                ("symbol".to_string(), BamlValue::String(symbol.to_string())),
+                ("count".to_string(), BamlValue::Int(count)),
+            ])));
+            rest = &tail[digits..];
+        }
+        Ok(BamlValue::Map(BamlMap::from([(
+            "elements".to_string(),
+            BamlValue::List(elements),
+        )])))
+    }
+}
+
+const COMPOUND_SCHEMA: &str = r#"
+class Element {
+  symbol string
+  count  int
+}
+
+class Formula {
+  elements Element[]
+}
+
+class Compound {
+  name    string
+  formula Formula @coerce("formula")
+}
+"#;
+
+#[test]
+fn custom_coercers_parse_marked_fields() {
+    let ctx = load_context(COMPOUND_SCHEMA, Some("Compound"))
+        .with_coercer("formula", Arc::new(FormulaCoercer));
+    let output = r#"{"name": "water", "formula": "H2O"}"#.to_string();
+    assert_eq!(
+        ctx.validate_result(&output, false).unwrap(),
+        r#"{"name":"water","formula":{"elements":[{"symbol":"H","count":2},{"symbol":"O","count":1}]}}"#
+    );
+
+    let err = ctx
+        .validate_result(r#"{"name": "water", "formula": "h2o"}"#, false)
+        .unwrap_err();
+    assert!(err.to_string().contains("expected an element"), "{err}");
+
+    let unregistered = load_context(COMPOUND_SCHEMA, Some("Compound"));
+    let err = unregistered.validate_result(&output, false).unwrap_err();
+    assert!(err.to_string().contains("Coercer `formula` failed"), "{err}");
+}
+
+#[test]
+fn coerce_requires_a_name_and_a_field() {
+    let schema = "class Compound {\n  formula string @coerce\n}".to_string();
+    assert!(BamlContext::try_from_schema(&schema, None).is_err());
+    let schema = "enum Phase {\n  Solid @coerce(\"phase\")\n}".to_string();
+    let err = BamlContext::try_from_schema(&schema, None).unwrap_err();
+    assert!(err.to_string().contains("only class fields can use @coerce"), "{err}");
+}
+
+#[test]
+fn suggestions_flag_prompt_bloat() {
+    let values = (0..25).map(|i| format!("  Code{i}")).collect::<Vec<_>>().join("\n");
+    let schema = format!(
+        r#"
+enum Code {{
+{values}
+}}
+
+class Ticket {{
+  code    Code
+  note    (string | (int | (bool | float)?))?
+  summary string @description("{long}")
+  tags    (string | int)[]
+}}
+"#,
+        long = "word ".repeat(60)
+    );
+    let ctx = load_context(&schema, Some("Ticket"));
+    let found = ctx
+        .suggestions()
+        .into_iter()
+        .map(|s| (s.kind, s.path))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        vec![
+            (SuggestionKind::NestedUnion, "Ticket.note".to_string()),
+            (SuggestionKind::VerboseDescription, "Ticket.summary".to_string()),
+            (SuggestionKind::LargeEnum, "Code".to_string()),
+        ]
+    );
+
+    assert!(load_context(FRUIT_SCHEMA, None).suggestions().is_empty());
+}
+
+#[test]
+fn field_order_only_changes_rendering() {
+    let schema = "class Ticket {\n  notes string?\n  title string\n}";
+    let ctx = load_context(schema, None).with_field_order(FieldOrder::RequiredFirst);
+    let rendered = ctx.render_prompt(None, None).unwrap();
+    assert!(
+        rendered.find("title").unwrap() < rendered.find("notes").unwrap(),
+        "{rendered}"
+    );
+
+    // Outputs keep the model's key order.
+    assert_eq!(
+        ctx.validate_result(r#"{"notes": "on boot", "title": "Crash"}"#, false)
+            .unwrap(),
+        r#"{"notes":"on boot","title":"Crash"}"#
+    );
+}
+
+#[test]
+fn doc_comments_describe_when_enabled() {
+    let schema = r#"
+enum Priority {
+  /// Needs a fix today
+  Urgent
+  Low @description("Whenever")
+}
+
+class Ticket {
+  /// One line summary
+  /// of the problem
+  title    string
+  /// Ignored in favor of the attribute
+  priority Priority @description("How soon to fix it")
+}
+"#
+    .to_string();
+    let options = SchemaOptions {
+        doc_comment_descriptions: true,
+        ..Default::default()
+    };
+    let ctx = BamlContext::try_from_schema_with_options(&schema, None, &options).unwrap();
+    let rendered = ctx.render_prompt(None, Some(true)).unwrap();
+    assert!(
+        rendered.contains("// One line summary\n  // of the problem\n  title: string"),
+        "{rendered}"
+    );
+    assert!(rendered.contains("// How soon to fix it\n"), "{rendered}");
+    assert!(!rendered.contains("Ignored"), "{rendered}");
+    assert!(rendered.contains("- Urgent: Needs a fix today"), "{rendered}");
+
+    let plain = load_context(&schema, None).render_prompt(None, Some(true)).unwrap();
+    assert!(!plain.contains("One line summary"), "{plain}");
+}
+
+#[test]
+fn grouped_enum_values_render_under_headings() {
+    let schema = r#"
+enum Food {
+  Apple  @group("Fruit")
+  Carrot @group("Vegetable")
+  Banana @group("Fruit")
+}
+
+class Meal {
+  food Food
+}
+"#;
+    let ctx = load_context(schema, Some("Meal"));
+    let rendered = ctx.render_prompt(None, None).unwrap();
+    assert!(
+        rendered.contains("Food\n----\nFruit:\n- Apple\n- Banana\nVegetable:\n- Carrot"),
+        "{rendered}"
+    );
+    assert_eq!(
+        ctx.validate_result(r#"{"food": "banana"}"#, false).unwrap(),
+        r#"{"food":"Banana"}"#
+    );
+
+    let schema = "class Meal {\n  food string @group(\"Fruit\")\n}".to_string();
+    let err = BamlContext::try_from_schema(&schema, None).unwrap_err();
+    assert!(err.to_string().contains("only enum values can use @group"), "{err}");
+}
+
+#[test]
+fn required_if_rejects_null_when_condition_holds() {
+    let schema = r#"
+class Customer {
+  kind    string
+  company string? @required_if({{ this.kind == "business" }})
+}
+"#;
+    let ctx = load_context(schema, Some("Customer"));
+    assert_eq!(
+        ctx.validate_result(r#"{"kind": "personal"}"#, false)
+            .unwrap(),
+        r#"{"kind":"personal","company":null}"#
+    );
+    assert_eq!(
+        ctx.validate_result(r#"{"kind": "business", "company": "Acme"}"#, false)
+            .unwrap(),
+        r#"{"kind":"business","company":"Acme"}"#
+    );
+    let err = ctx
+        .validate_result(r#"{"kind": "business"}"#, false)
+        .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("company: Required when `this.kind == \"business\"`, got null"),
+        "{err:#}"
+    );
+
+    let schema = "class Customer {\n  company string @required_if({{ true }})\n}".to_string();
+    let err = BamlContext::try_from_schema(&schema, None).unwrap_err();
+    assert!(err.to_string().contains("@required_if only applies to optional fields"), "{err}");
+}
+
+#[test]
+fn field_constraints_run_during_validation() {
+    let schema = r#"
+class Person {
+  age  int @check(young, {{ this < 10 }}) @assert(nonnegative, {{ this >= 0 }})
+  tags string[] @assert(few, {{ this|length < 3 }})
+}
+"#;
+    let ctx = load_context(schema, Some("Person"));
+    // Unlike an assert, a failing check does not reject the value.
+    let json = ctx
+        .validate_result(r#"{"age": 11, "tags": []}"#, false)
+        .unwrap();
+    assert!(json.contains(r#""age":11"#), "{json}");
+    assert!(ctx
+        .validate_result(r#"{"age": -1, "tags": []}"#, false)
+        .is_err());
+    assert!(ctx
+        .validate_result(r#"{"age": 1, "tags": ["a", "b", "c"]}"#, false)
+        .is_err());
+}
+
+#[test]
+fn one_of_requires_exactly_one_field_of_a_group() {
+    let schema = r#"
+class Contact {
+  name  string
+  email string?
+  phone string? @alias("phone_number")
+
+  @@one_of(email, phone)
+}
+"#;
+    let ctx = load_context(schema, Some("Contact"));
+    let rendered = ctx.render_prompt(None, None).unwrap();
+    assert!(
+        rendered.contains("  // Set exactly one of: email, phone_number\n}"),
+        "{rendered}"
+    );
+    assert_eq!(
+        ctx.validate_result(r#"{"name": "Ada", "phone_number": "555"}"#, false)
+            .unwrap(),
+        r#"{"name":"Ada","phone_number":"555","email":null}"#
+    );
+    let err = ctx
+        .validate_result(r#"{"name": "Ada"}"#, false)
+        .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("Expected exactly one of `email`, `phone_number`, got none"),
+        "{err:#}"
+    );
+    let err = ctx
+        .validate_result(
+            r#"{"name": "Ada", "email": "a@b.c", "phone_number": "555"}"#,
+            false,
+        )
+        .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("got `email`, `phone_number`"),
+        "{err:#}"
+    );
+
+    let schema = "class Contact {\n  email string\n  phone string?\n  @@one_of(email, phone)\n}"
+        .to_string();
+    let err = BamlContext::try_from_schema(&schema, None).unwrap_err();
+    assert!(err.to_string().contains("@@one_of field `email` must be optional"), "{err}");
+    let schema = "class Contact {\n  email string?\n  @@one_of(email, fax)\n}".to_string();
+    let err = BamlContext::try_from_schema(&schema, None).unwrap_err();
+    assert!(err.to_string().contains("`fax` is not a field of the class"), "{err}");
+}
+
+#[test]
+fn outputs_keep_model_key_order_unless_canonical() {
+    let schema = r#"
+class Ticket {
+  id     int
+  title  string
+  notes  string?
+  labels map<string, string>
+}
+"#;
+    let output = r#"{"notes": "n", "labels": {"z": "1", "a": "2"}, "title": "t", "id": 1}"#
+        .to_string();
+    let ctx = load_context(schema, None);
+    assert_eq!(
+        ctx.validate_result(&output, false).unwrap(),
+        r#"{"notes":"n","labels":{"z":"1","a":"2"},"title":"t","id":1}"#
+    );
+
+    let ctx = ctx
+        .with_field_order(FieldOrder::Alphabetical)
+        .with_canonical_field_order(true);
+    assert_eq!(
+        ctx.validate_result(&output, false).unwrap(),
+        r#"{"id":1,"labels":{"z":"1","a":"2"},"notes":"n","title":"t"}"#
+    );
+}
+
+#[test]
+fn rename_symbol_updates_every_reference() {
+    let files = vec![
+        (
+            PathBuf::from("types.baml"),
+            r#"
+enum Status {
+  Open
+  Closed
+}
+
+class Order {
+  status Status
+  items  Status[]?
+  email  string?
+  phone  string? @required_if({{ this.status == "Open" and this.emailAddress == null }})
+
+  @@one_of(email, phone)
+  @@assert(has_contact, {{ this.email != null or this.phone != null }})
+}
+"#
+            .to_string(),
+        ),
+        (
+            PathBuf::from("functions.baml"),
+            r##"
+type Orders = Order[] | map<string, Order>
+
+function Extract(order: Order, status: Status?) -> Orders {
+  client "openai/gpt-4o"
+  prompt #"{{ ctx.output_format }}"#
+}
+"##
+            .to_string(),
+        ),
+    ];
+    let rename = |old: &str, new: &str| {
+        let edits = rename_symbol(&files, old, new).unwrap();
+        files
+            .iter()
+            .map(|(path, source)| apply_edits(path, source, &edits))
+            .collect::<Vec<_>>()
+    };
+
+    let renamed = rename("Order", "PurchaseOrder");
+    assert!(renamed[0].contains("class PurchaseOrder {"), "{}", renamed[0]);
+    assert!(
+        renamed[1].contains("type Orders = PurchaseOrder[] | map<string, PurchaseOrder>"),
+        "{}",
+        renamed[1]
+    );
+    assert!(
+        renamed[1].contains("function Extract(order: PurchaseOrder, status: Status?)"),
+        "{}",
+        renamed[1]
+    );
+
+    let renamed = rename("Status", "State");
+    assert!(renamed[0].contains("enum State {"), "{}", renamed[0]);
+    assert!(renamed[0].contains("status State\n  items  State[]?"), "{}", renamed[0]);
+    assert!(renamed[1].contains("status: State?"), "{}", renamed[1]);
+
+    let renamed = rename("Order.email", "emailAddress");
+    assert!(renamed[0].contains("  emailAddress  string?"), "{}", renamed[0]);
+    assert!(renamed[0].contains("@@one_of(emailAddress, phone)"), "{}", renamed[0]);
+    assert!(
+        renamed[0].contains("{{ this.emailAddress != null or this.phone != null }}"),
+        "{}",
+        renamed[0]
+    );
+    // Only whole field names are renamed.
+    assert!(
+        renamed[0].contains("this.status == \"Open\" and this.emailAddress == null"),
+        "{}",
+        renamed[0]
+    );
+
+    let err = rename_symbol(&files, "Order", "Status").unwrap_err();
+    assert!(err.to_string().contains("would make the schema invalid"), "{err}");
+    assert!(rename_symbol(&files, "Order.fax", "telefax").is_err());
+    assert!(rename_symbol(&files, "Order", "Purchase Order").is_err());
+}
+
+#[test]
+fn extract_class_moves_fields_with_attributes_and_comments() {
+    let source = r#"class Customer {
+  name    string
+  /// Street and number.
+  street  string @description("No PO boxes")
+  country string? // ISO code
+  city    string? @required_if({{ this.country != null }})
+  email   string?
+
+  @@assert(named, {{ this.name|length > 0 }})
+}
+"#;
+    let files = vec![(PathBuf::from("customer.baml"), source.to_string())];
+    let edits = extract_class(&files, "Customer", &["city", "street", "country"], "Address", "address")
+        .unwrap();
+    let extracted = apply_edits(&files[0].0, source, &edits);
+    assert_eq!(
+        extracted,
+        r#"class Customer {
+  name    string
+  address Address
+  email   string?
+
+  @@assert(named, {{ this.name|length > 0 }})
+}
+
+class Address {
+  /// Street and number.
+  street  string @description("No PO boxes")
+  country string? // ISO code
+  city    string? @required_if({{ this.country != null }})
+}
+"#
+    );
+
+    // Files saved on Windows keep their byte order mark and `\r\n` line endings.
+    let windows = format!("\u{feff}{}", source.replace('\n', "\r\n"));
+    let windows_files = vec![(PathBuf::from("customer.baml"), windows.clone())];
+    let edits = extract_class(&windows_files, "Customer", &["city", "street", "country"], "Address", "address")
+        .unwrap();
+    assert_eq!(
+        apply_edits(&windows_files[0].0, &windows, &edits),
+        format!("\u{feff}{}", extracted.replace('\n', "\r\n"))
+    );
+
+    // `city` would lose the `country` its condition refers to.
+    let err = extract_class(&files, "Customer", &["country"], "Address", "address").unwrap_err();
+    assert!(err.to_string().contains("is referred to"), "{err}");
+    let err = extract_class(&files, "Customer", &["email"], "Customer", "contact").unwrap_err();
+    assert!(err.to_string().contains("would make the schema invalid"), "{err}");
+    assert!(extract_class(&files, "Customer", &["fax"], "Contact", "contact").is_err());
+}
+
+#[test]
+fn reserved_name_policy_extends_and_relaxes_the_defaults() {
+    let schema = r#"
+class String {
+  value string
+}
+
+class Image {
+  url String
+}
+"#
+    .to_string();
+    let err = BamlContext::try_from_schema(&schema, Some("Image".into())).unwrap_err();
+    assert!(err.to_string().contains("\"String\" is a reserved scalar type name"), "{err}");
+
+    let relaxed = SchemaOptions {
+        reserved_names: ReservedNamePolicy {
+            allow: vec!["String".into()],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    BamlContext::try_from_schema_with_options(&schema, Some("Image".into()), &relaxed).unwrap();
+
+    let strict = SchemaOptions {
+        reserved_names: ReservedNamePolicy {
+            reserve: vec!["Image".into()],
+            allow: vec!["String".into()],
+        },
+        ..Default::default()
+    };
+    let err = BamlContext::try_from_schema_with_options(&schema, Some("Image".into()), &strict)
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("This name is reserved by the active reserved-name policy."),
+        "{err}"
+    );
+
+    let client = "class BamlClient {\n  value string\n}".to_string();
+    let err = BamlContext::try_from_schema(&client, None).unwrap_err();
+    assert!(err.to_string().contains("The reserved-name policy can allow it."), "{err}");
+}
+
+#[test]
+fn schema_strings_are_labelled_in_diagnostics() {
+    let schema = "class Foo {\n  bar Missing\n}".to_string();
+    let err = BamlContext::try_from_schema(&schema, Some("Foo".into())).unwrap_err();
+    assert!(err.to_string().contains("<schema>:2"), "{err}");
+
+    let named = SchemaOptions {
+        source_name: Some("inline/foo".into()),
+        ..Default::default()
+    };
+    let err = BamlContext::try_from_schema_with_options(&schema, Some("Foo".into()), &named)
+        .unwrap_err();
+    assert!(err.to_string().contains("inline/foo:2"), "{err}");
+
+    let validated = crate::validate_named("prompt.txt", &schema);
+    let pretty = validated.diagnostics.to_pretty_string();
+    assert!(pretty.contains("prompt.txt:2"), "{pretty}");
+    assert!(!pretty.contains("file extension"), "{pretty}");
+}
+
+#[test]
+fn attributes_read_injected_env_vars() {
+    let schema = r#"
+class Person {
+  name string @alias(env.NAME_KEY)
+  age  int @description({{ "Age in " ~ env.AGE_UNIT }})
+}
+"#
+    .to_string();
+    let options = SchemaOptions {
+        env_vars: [("NAME_KEY", "full_name"), ("AGE_UNIT", "years")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        ..Default::default()
+    };
+    let ctx = BamlContext::try_from_schema_with_options(&schema, None, &options).unwrap();
+    let rendered = ctx.render_prompt(None, None).unwrap();
+    assert!(rendered.contains("full_name: string"), "{rendered}");
+    assert!(rendered.contains("// Age in years"), "{rendered}");
+
+    let err = BamlContext::try_from_schema(&schema, None).unwrap_err();
+    assert!(format!("{err:#}").contains("Failed to resolve an attribute of `Person.name`"), "{err:#}");
+    assert!(format!("{err:#}").contains("Environment variable NAME_KEY not set"), "{err:#}");
+}
+
+#[test]
+fn type_aliases_expand_or_render_as_definitions() {
+    let schema = r#"
+type Id = int
+type Payload = int | string | Payload[] | map<string, Payload>
+
+class Record {
+  id   Id
+  data Payload
+}
+"#;
+    let ctx = load_context(schema, Some("Record"));
+    let rendered = ctx.render_prompt(None, None).unwrap();
+    assert!(rendered.contains("id: int"), "{rendered}");
+    assert!(rendered.contains("Payload = "), "{rendered}");
+    assert_eq!(
+        ctx.validate_result(r#"{"id": 1, "data": [1, {"a": "b"}]}"#, false)
+            .unwrap(),
+        r#"{"id":1,"data":[1,{"a":"b"}]}"#
+    );
+
+    let ctx = load_context(schema, Some("Id"));
+    assert_eq!(ctx.target, FieldType::int());
+}
+
+#[test]
+fn recursive_alias_targets_render_and_validate() {
+    let schema = "type Payload = int | string | Payload[] | map<string, Payload>";
+    let ctx = load_context(schema, Some("Payload"));
+    assert_eq!(ctx.target, FieldType::RecursiveTypeAlias("Payload".to_string()));
+    let rendered = ctx.render_prompt(None, None).unwrap();
+    assert!(rendered.starts_with("Payload = "), "{rendered}");
+    assert!(rendered.ends_with("Answer in JSON using this schema: Payload"), "{rendered}");
+    assert_eq!(
+        ctx.validate_result(r#"{"a": [1, "b"], "c": {"d": 2}}"#, false).unwrap(),
+        r#"{"a":[1,"b"],"c":{"d":2}}"#
+    );
+}
+
+#[test]
+fn string_literal_unions_match_like_enums() {
+    let schema = r#"
+class Post {
+  status "draft" | "published"
+  tags ("news" | "opinion")[]
+}
+"#;
+    let ctx = load_context(schema, Some("Post"));
+    let rendered = ctx.render_prompt(None, None).unwrap();
+    assert!(rendered.contains("status: 'draft' or 'published'"), "{rendered}");
+    assert_eq!(
+        ctx.validate_result(
+            r#"{"status": "Published", "tags": ["NEWS", "opinion."]}"#,
+            false
+        )
+        .unwrap(),
+        r#"{"status":"published","tags":["news","opinion"]}"#
+    );
+}
+
+#[test]
+fn normalize_wraps_single_values_in_a_list() {
+    let schema = r#"
+class Recipe {
+  tags string | string[] @normalize("list")
+  notes string | string[]
+}
+"#;
+    let ctx = load_context(schema, Some("Recipe"));
+    let output = r#"{"tags": "vegan", "notes": "spicy"}"#.to_string();
+    assert_eq!(
+        ctx.validate_result(&output, false).unwrap(),
+        r#"{"tags":["vegan"],"notes":"spicy"}"#
+    );
+    let ctx = ctx.with_list_normalization(true);
+    assert_eq!(
+        ctx.validate_result(&output, false).unwrap(),
+        r#"{"tags":["vegan"],"notes":["spicy"]}"#
+    );
+
+    let err = BamlContext::try_from_schema(
+        r#"
+class Recipe {
+  tags string @normalize("list")
+}
+"#,
+        None,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("only applies to unions with a list"), "{err}");
+}
+
+#[test]
+fn partial_results_are_deep_partial() {
+    use baml_types::FieldType;
+
+    let schema = r#"
+class Address {
+  city string
+  zip  int
+}
+
+class Customer {
+  name    string
+  address Address
+  tags    string[]
+}
+"#;
+    let ctx = load_context(schema, Some("Customer"));
+    assert_eq!(ctx.partial_target_type(), FieldType::class("Customer"));
+    assert_eq!(
+        ctx.partial_class_fields("Customer").unwrap(),
+        vec![
+            ("name".to_string(), FieldType::string().as_optional()),
+            ("address".to_string(), FieldType::class("Address").as_optional()),
+            ("tags".to_string(), FieldType::List(Box::new(FieldType::string()))),
+        ]
+    );
+    assert_eq!(
+        ctx.partial_class_fields("Address").unwrap(),
+        vec![
+            ("city".to_string(), FieldType::string().as_optional()),
+            ("zip".to_string(), FieldType::int().as_optional()),
+        ]
+    );
+    assert!(ctx.partial_class_fields("Missing").is_none());
+
+    assert_eq!(
+        ctx.validate_result(r#"{"name": "Ada", "address": {"city": "Lon"#, true)
+            .unwrap(),
+        r#"{"name":"Ada","address":{"city":"Lon","zip":null},"tags":[]}"#
+    );
+}
+
+#[test]
+fn parse_result_with_flags_keeps_per_node_flags() {
+    let ctx = load_context(FRUIT_SCHEMA, Some("FruitOrders"));
+    let output = r#"{"id": 1, "fruit": {"fruit": "apple", "fruit_price": "3", "dateSold": "today", "received": true}}"#
+        .to_string();
+    let flagged = ctx.parse_result_with_flags(&output, false).unwrap();
+    assert_eq!(flagged.value(), ctx.parse_result(&output, false).unwrap());
+
+    let fruits = flagged.get("fruit").unwrap();
+    assert!(fruits.flags().iter().any(|f| f.kind == "SingleToArray"));
+    let fruit = fruits.item(0).unwrap();
+    assert!(fruit.get("received").unwrap().flags().is_empty());
+
+    let json = serde_json::to_value(&flagged).unwrap();
+    assert_eq!(json["type"], "class");
+    assert_eq!(json["fields"]["fruit"]["type"], "list");
+    assert_eq!(json["fields"]["fruit"]["flags"][0]["kind"], "SingleToArray");
+    assert_eq!(json["fields"]["fruit"]["items"][0]["fields"]["received"]["value"], true);
+}
+
+#[test]
+fn validate_result_with_flags_lists_flags_by_path() {
+    let ctx = load_context(FRUIT_SCHEMA, Some("FruitOrders"));
+    let output = r#"{"id": "1", "fruit": {"fruit": "Apple", "fruit_price": 3, "dateSold": "today", "received": true}}"#;
+    let (json, score, flagged) = ctx.validate_result_with_flags(output, false).unwrap();
+    assert_eq!(json, ctx.validate_result(output, false).unwrap());
+    assert!(score > 0);
+    let paths = flagged
+        .all_flags()
+        .into_iter()
+        .map(|(path, flag)| (path, flag.kind.as_str()))
+        .collect::<Vec<_>>();
+    assert!(paths.contains(&("$.fruit".to_string(), "SingleToArray")), "{paths:?}");
+
+    let exact = r#"{"id": "1", "fruit": []}"#;
+    let (_, score, flagged) = ctx.validate_result_with_flags(exact, false).unwrap();
+    assert_eq!(score, 0);
+    assert!(flagged.all_flags().is_empty());
+}
+
+#[test]
+fn provenance_maps_values_back_to_the_output() {
+    let ctx = load_context(FRUIT_SCHEMA, Some("FruitOrders"));
+    let output = r#"Here you go:
+{"id": "12", "fruit": [{"fruit": "apple", "fruit_price": 3, "dateSold": "today", "received": true}]}"#
+        .to_string();
+    let flagged = ctx.parse_result_with_flags(&output, false).unwrap();
+    let text = |path: &str| flagged.provenance(path).map(|span| &output[span]);
+
+    assert_eq!(text("$.id"), Some("12"));
+    assert_eq!(text("$.fruit[0].fruit"), Some("apple"));
+    assert_eq!(text("$.fruit[0].fruit_price"), Some("3"));
+    assert_eq!(text("$.fruit[0].dateSold"), Some("today"));
+    assert_eq!(
+        text("$.fruit[0]"),
+        Some(r#"{"fruit": "apple", "fruit_price": 3, "dateSold": "today", "received": true}"#)
+    );
+    assert_eq!(text("$.fruit[1]"), None);
+    assert_eq!(text("$.missing"), None);
+}
+
+#[test]
+fn validate_documents_checks_each_record() {
+    let ctx = load_context(
+        r#"
+class Person {
+  name string
+  age  int
+}
+"#,
+        Some("Person"),
+    );
+    let output = r#"{"name": "Ada", "age": 36}
+{"name": "Bob {the builder}", "age": "41"}
+{"name": "Cy"}
+{"name": "Di", "age": 2"#;
+    let results = ctx.validate_documents(output, false);
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0].as_ref().unwrap(), r#"{"name":"Ada","age":36}"#);
+    assert_eq!(results[1].as_ref().unwrap(), r#"{"name":"Bob {the builder}","age":41}"#);
+    assert!(results[2].is_err());
+    assert_eq!(results[3].as_ref().unwrap(), r#"{"name":"Di","age":2}"#);
+
+    let results = ctx.validate_documents("no records today", false);
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_err());
+}
+
+#[test]
+fn revalidate_jsonl_streams_line_results() {
+    use crate::batch::{revalidate_jsonl, BatchOptions};
+
+    let ctx = load_context(
+        r#"
+class Person {
+  name string
+  age  int
+}
+"#,
+        Some("Person"),
+    );
+    let archive = r#"{"id": 1, "output": "{\"name\": \"Ada\", \"age\": 36}"}
+
+{"id": 2, "output": "I don't know"}
+not json
+"#;
+    for parallel in [false, true] {
+        let options = BatchOptions {
+            output_field: Some("output".to_string()),
+            parallel,
+            ..Default::default()
+        };
+        let results = revalidate_jsonl(archive.as_bytes(), &ctx, &options).collect::<Vec<_>>();
+        assert_eq!(results.iter().map(|r| r.line).collect::<Vec<_>>(), [1, 3, 4]);
+        assert_eq!(results[0].result.as_ref().unwrap(), r#"{"name":"Ada","age":36}"#);
+        assert!(results[1].result.is_err());
+        assert!(results[2].result.as_ref().unwrap_err().to_string().contains("not a JSON object"));
+    }
+
+    let archive = "\"{\\\"name\\\": \\\"Bo\\\", \\\"age\\\": 4}\"\n{\"name\": \"Cy\", \"age\": 5}\n";
+    let results = revalidate_jsonl(archive.as_bytes(), &ctx, &BatchOptions::default())
+        .map(|r| r.result.unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(results, [r#"{"name":"Bo","age":4}"#, r#"{"name":"Cy","age":5}"#]);
+}
+
+#[test]
+fn validation_report_summarizes_batch_results() {
+    use crate::batch::{revalidate_jsonl, BatchOptions, ItemStatus, ValidationReport};
+
+    let ctx = load_context(
+        r#"
+class Person {
+  name string
+  age  int
+}
+"#,
+        Some("Person"),
+    );
+    let archive = "{name: \"Ada\", age: \"36\"}\n{\"name\": \"Bo\"}\n";
+    let options = BatchOptions::default();
+    let report = revalidate_jsonl(archive.as_bytes(), &ctx, &options).collect::<ValidationReport>();
+    assert_eq!((report.successes, report.failures), (1, 1));
+    assert_eq!(report.items[0].status, ItemStatus::Success);
+    assert!(!report.items[0].flag_counts.is_empty());
+    assert_eq!(report.flag_counts, report.items[0].flag_counts);
+    assert_eq!(report.items[1].error_fields, ["<root>.age"]);
+    assert_eq!(report.errors_by_field.get("<root>.age"), Some(&1));
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(json["items"][1]["status"], "failure");
+    assert_eq!(json["errors_by_field"]["<root>.age"], 1);
+
+    let csv = report.to_csv();
+    let mut rows = csv.lines();
+    assert_eq!(rows.next(), Some("line,status,duration_us,flags,error_fields,error"));
+    assert!(rows.next().unwrap().starts_with("1,success,"));
+    assert!(rows.next().unwrap().contains(",<root>.age,"));
+}
+
+#[test]
+fn float_format_controls_serialized_floats() {
+    use crate::{FloatFormat, TrailingZeros};
+
+    let schema = r#"
+class Reading {
+  value float
+  ratio float
+  tiny  float
+}
+"#;
+    let output = r#"{"value": 2, "ratio": 2.456, "tiny": 0.0000001}"#.to_string();
+    let ctx = load_context(schema, Some("Reading"));
+    assert_eq!(
+        ctx.validate_result(&output, false).unwrap(),
+        r#"{"value":2.0,"ratio":2.456,"tiny":1e-7}"#
+    );
+
+    let ctx = ctx.with_float_format(FloatFormat {
+        fixed: true,
+        trailing_zeros: TrailingZeros::Trim,
+        ..Default::default()
+    });
+    assert_eq!(
+        ctx.validate_result(&output, false).unwrap(),
+        r#"{"value":2,"ratio":2.456,"tiny":0.0000001}"#
+    );
+
+    let ctx = ctx.with_float_format(FloatFormat {
+        max_decimal_places: Some(2),
+        trailing_zeros: TrailingZeros::Pad,
+        ..Default::default()
+    });
+    assert_eq!(
+        ctx.validate_result(&output, false).unwrap(),
+        r#"{"value":2.00,"ratio":2.46,"tiny":0.00}"#
+    );
+    let BamlValue::Class(_, fields) = ctx.parse_result(&output, false).unwrap() else {
+        panic!("expected a class");
+    };
+    assert_eq!(fields["ratio"], BamlValue::Float(2.456));
+}
+
+#[test]
+fn capture_replays_validation_from_fixture() {
+    use crate::capture::{Fixture, Outcome};
+
+    let schema = r#"
+class Reading {
+  value int
+  ok bool
+  note string?
+}
+"#;
+    let ctx = load_context(schema, Some("Reading"))
+        .with_bool_vocabulary(BoolVocabulary::default().with_truthy(["oui"]))
+        .with_null_values(vec!["N/A".to_string()])
+        .with_int_overflow(IntOverflow::Saturate);
+    let output = r#"{"value": 99999999999999999999, "ok": "oui", "note": "N/A"}"#;
+    let fixture = ctx.capture(output, false);
+    assert_eq!(
+        fixture.outcome,
+        Outcome::Result(format!(r#"{{"value":{},"ok":true,"note":null}}"#, i64::MAX))
+    );
+    assert_eq!(fixture.schema_hash, ctx.schema_hash());
+
+    let fixture = Fixture::from_json(&fixture.to_json()).unwrap();
+    let replay = BamlContext::replay(&fixture).unwrap();
+    assert!(replay.matches(), "{replay:?}");
+
+    // Replaying without the options captured with the fixture changes the outcome.
+    let replay = fixture.replay_on(&load_context(schema, Some("Reading")));
+    assert!(!replay.matches());
+    assert!(matches!(replay.actual, Outcome::Error(_)));
+
+    let fixture = ctx.capture("not json", false);
+    assert!(matches!(fixture.outcome, Outcome::Error(_)));
+    assert!(BamlContext::replay(&fixture).unwrap().matches());
+}
+
+#[test]
+fn outputs_are_identical_across_runs() {
+    use internal_baml_core::ir::{repr::IntermediateRepr, WithJsonSchema};
+
+    // Every `aX` parameter is as close to `ae`, so the suggestions depend on tie-breaking.
+    let invalid = r##"
+template_string Greeting(aa: string, ab: string, ac: string, ad: string) #"
+  {{ ae }} {{ ctx.output_format(zz=1, yy=2) }}
+"#
+"##
+    .to_string();
+    let schema = r#"
+class Node {
+  value int
+  kind Kind
+}
+class Leaf {
+  label string
+}
+enum Kind {
+  A
+  B
+}
+type Tree = Node | Leaf
+class Forest {
+  trees Tree[]
+  kind Kind
+}
+"#;
+
+    let run = || {
+        let diagnostics = crate::validate(&invalid)
+            .diagnostics
+            .warnings_to_pretty_string();
+        let validated = crate::validate(schema);
+        let ir = IntermediateRepr::from_parser_database(&validated.db, validated.configuration)
+            .unwrap();
+        let ir = serde_json::to_string(&ir.json_schema()).unwrap();
+        let prompt = load_context(schema, Some("Forest"))
+            .render_prompt(None, Some(true))
+            .unwrap();
+        (diagnostics, ir, prompt)
+    };
+    let first = run();
+    assert!(
+        first.0.contains("Did you mean one of these: `aa`, `ab`, `ac`?"),
+        "{}",
+        first.0
+    );
+    for _ in 0..20 {
+        assert_eq!(run(), first);
+    }
+}
+
+#[test]
+fn parallel_validation_reports_errors_in_declaration_order() {
+    let schema = (0..200)
+        .map(|i| format!("class C{i} {{\n  m{i} map<float, string>\n}}\nenum E{i} {{\n  V\n}}\n"))
+        .collect::<String>();
+    let validated = crate::validate(&schema);
+    let errors = validated.diagnostics.errors();
+    assert_eq!(errors.len(), 200);
+    assert!(errors
+        .windows(2)
+        .all(|pair| pair[0].span().start < pair[1].span().start));
+}
+
+#[test]
+fn types_nested_past_the_depth_limit_are_rejected() {
+    let deep = format!("class Deep {{\n  cells int{}\n}}\n", "[]".repeat(200));
+    let err = BamlContext::try_from_schema(&deep, None).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("This type nests 201 levels deep, over the limit of 128."),
+        "{err:#}"
+    );
+
+    let options = SchemaOptions {
+        max_type_depth: Some(256),
+        ..Default::default()
+    };
+    assert!(BamlContext::try_from_schema_with_options(&deep, None, &options).is_ok());
+
+    // Each alias is shallow, but they nest once resolved.
+    let mut chain = "type A0 = int\n".to_string();
+    for i in 1..10 {
+        chain += &format!("type A{i} = A{}[]\n", i - 1);
+    }
+    chain += "class Uses {\n  a A9\n}\n";
+    let options = SchemaOptions {
+        max_type_depth: Some(5),
+        ..Default::default()
+    };
+    let err = BamlContext::try_from_schema_with_options(&chain, None, &options).unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("Type alias `A5` nests deeper than the limit of 5 levels once resolved."), "{err}");
+    assert!(!err.contains("`A4`"), "{err}");
+}
+
+#[test]
+fn render_profiles_render_by_name() {
+    let schema = r#"
+class Person {
+  name string
+  age int?
+}
+"#;
+    let ctx = load_context(schema, Some("Person"))
+        .with_render_profile(
+            "compact",
+            RenderProfile {
+                style: RenderStyle::Compact,
+                header: Some("Extract the person.".to_string()),
+                ..Default::default()
+            },
+        )
+        .with_render_profile(
+            "required_first",
+            RenderProfile {
+                field_order: FieldOrder::RequiredFirst,
+                footer: Some("Only JSON.".to_string()),
+                ..Default::default()
+            },
+        );
+    assert_eq!(ctx.render_profiles(), ["compact", "required_first"]);
+
+    let compact = ctx.render_prompt_with_profile("compact").unwrap();
+    assert_eq!(compact.profile, "compact");
+    assert_eq!(
+        compact.prompt,
+        "Extract the person.\n\nAnswer in JSON using this schema:\n{name: str, age: int|null}"
+    );
+
+    let standard = ctx.render_prompt_with_profile("required_first").unwrap();
+    assert_eq!(
+        standard.prompt,
+        format!("{}\n\nOnly JSON.", ctx.render_prompt(None, None).unwrap())
+    );
+
+    let err = ctx.render_prompt_with_profile("missing").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unknown render profile `missing`, expected one of: compact, required_first"
+    );
+}
+
+#[test]
+fn response_formats_follow_backend_shapes() {
+    let schema = r#"
+enum Mood {
+  HAPPY
+  SAD @alias("sad")
+}
+
+class Person {
+  name string @alias("full_name") @description("As written")
+  mood Mood
+  tags map<string, int>?
+}
+"#;
+    let ctx = load_context(schema, Some("Person"));
+    let mood = serde_json::json!({ "type": "string", "enum": ["HAPPY", "sad"] });
+
+    let vllm = ctx.response_format(DecodingBackend::Vllm).unwrap();
+    assert_eq!(
+        vllm,
+        serde_json::json!({
+            "guided_json": {
+                "type": "object",
+                "properties": {
+                    "full_name": { "type": "string", "description": "As written" },
+                    "mood": { "$ref": "#/$defs/Mood" },
+                    "tags": {
+                        "anyOf": [
+                            { "type": "object", "additionalProperties": { "type": "integer" } },
+                            { "type": "null" },
+                        ],
+                    },
+                },
+                "required": ["full_name", "mood"],
+                "additionalProperties": false,
+                "$defs": {
+                    "Mood": mood,
+                    "Person": vllm["guided_json"]["$defs"]["Person"],
+                },
+            },
+        })
+    );
+
+    let err = ctx.response_format(DecodingBackend::OpenAi).unwrap_err();
+    assert_eq!(err.to_string(), "Maps are not supported by strict structured outputs");
+
+    let ctx = load_context(&schema.replace("  tags map<string, int>?\n", "  note string?\n"), Some("Person"));
+    let openai = ctx.response_format(DecodingBackend::OpenAi).unwrap();
+    assert_eq!(openai["type"], "json_schema");
+    assert_eq!(openai["json_schema"]["name"], "Person");
+    assert_eq!(openai["json_schema"]["strict"], true);
+    assert_eq!(
+        openai["json_schema"]["schema"]["required"],
+        serde_json::json!(["full_name", "mood", "note"])
+    );
+
+    let tgi = ctx.response_format(DecodingBackend::Tgi).unwrap();
+    assert_eq!(tgi["grammar"]["type"], "json");
+    assert_eq!(tgi["grammar"]["value"]["required"], serde_json::json!(["full_name", "mood"]));
+}
+
+#[test]
+fn response_formats_report_downgrades() {
+    let schema = r#"
+class Node {
+  value int @assert(positive, {{ this > 0 }})
+  kind "leaf" | "branch"
+  attrs map<string, string>
+  children Node[]
+}
+"#;
+    let ctx = load_context(schema, Some("Node"));
+
+    let (_, downgrades) = ctx.response_format_with_downgrades(DecodingBackend::Vllm).unwrap();
+    assert_eq!(
+        downgrades,
+        vec![Downgrade {
+            location: "Node.value".to_string(),
+            kind: DowngradeKind::AssertDropped(Some("positive".to_string())),
+        }]
+    );
+
+    let (tgi, downgrades) = ctx.response_format_with_downgrades(DecodingBackend::Tgi).unwrap();
+    let messages = downgrades.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "Node.value: @assert `positive` is only checked when validating",
+            "Node.kind: literal \"leaf\" accepts any value of its type",
+            "Node.kind: literal \"branch\" accepts any value of its type",
+            "Node.attrs: map accepts any object",
+            "Node.children: recursive reference to `Node` accepts any value",
+        ]
+    );
+    let properties = &tgi["grammar"]["value"]["properties"];
+    assert_eq!(properties["attrs"], serde_json::json!({ "type": "object" }));
+    assert_eq!(properties["children"], serde_json::json!({ "type": "array", "items": {} }));
+}
+
+#[test]
+fn json_schemas_export_any_target() {
+    let schema = r##"
+enum Mood {
+  HAPPY @alias("happy")
+  SAD
+}
+
+class Person {
+  name string @alias("full_name") @description("As written")
+  mood Mood
+  status "active" | "inactive"
+  nickname string?
+}
+
+class Team {
+  members Person[]
+  scores map<string, float>
+}
+
+function Lead(team: string) -> Person {
+  client "openai/gpt-4o"
+  prompt #"{{ team }}"#
+}
+"##;
+    let ctx = load_context(schema, Some("Team"));
+
+    assert_eq!(ctx.to_json_schema(None).unwrap(), ctx.json_schema(false).unwrap());
+
+    let mood = serde_json::json!({ "type": "string", "enum": ["happy", "SAD"] });
+    let person = serde_json::json!({
+        "type": "object",
+        "properties": {
+            "full_name": { "type": "string", "description": "As written" },
+            "mood": { "$ref": "#/$defs/Mood" },
+            // String literal unions are lowered to enums named after the field.
+            "status": { "$ref": "#/$defs/Person.status" },
+            "nickname": { "anyOf": [{ "type": "string" }, { "type": "null" }] },
+        },
+        "required": ["full_name", "mood", "status"],
+        "additionalProperties": false,
+    });
+    let status = serde_json::json!({ "type": "string", "enum": ["active", "inactive"] });
+    let mut expected = person.clone();
+    expected["$defs"] =
+        serde_json::json!({ "Mood": mood, "Person": person, "Person.status": status });
+    assert_eq!(ctx.to_json_schema(Some("Person")).unwrap(), expected);
+    assert_eq!(ctx.to_json_schema(Some("Lead")).unwrap(), expected);
+
+    assert_eq!(
+        ctx.to_json_schema(None).unwrap()["properties"]["scores"],
+        serde_json::json!({ "type": "object", "additionalProperties": { "type": "number" } })
+    );
+    assert_eq!(ctx.to_json_schema(Some("Mood")).unwrap()["$ref"], "#/$defs/Mood");
+    assert!(ctx.to_json_schema(Some("Missing")).is_err());
+}
+
+#[test]
+fn json_schemas_import_as_baml() {
+    let schema = serde_json::json!({
+        "title": "Order",
+        "type": "object",
+        "description": "A customer order",
+        "properties": {
+            "id": { "type": "string" },
+            "status": { "$ref": "#/$defs/status" },
+            "items": { "type": "array", "items": { "$ref": "#/$defs/LineItem" } },
+            "note": { "type": ["string", "null"], "description": "Free text" },
+            "shipping-address": {
+                "type": "object",
+                "properties": { "city": { "type": "string" } },
+                "required": ["city"],
+            },
+            "payment": {
+                "oneOf": [{ "type": "integer" }, { "type": "string", "enum": ["cash"] }],
+            },
+        },
+        "required": ["id", "status", "items", "shipping-address", "payment"],
+        "$defs": {
+            "status": { "type": "string", "enum": ["open", "in progress"] },
+            "LineItem": {
+                "type": "object",
+                "properties": {
+                    "sku": { "type": "string" },
+                    "quantity": { "type": "integer", "nullable": true },
+                    "prices": { "type": "object", "additionalProperties": { "type": "number" } },
+                },
+                "required": ["sku", "quantity"],
+            },
+        },
+    });
+    let baml = from_json_schema(&schema).unwrap();
+    assert_eq!(
+        baml,
+        r#"enum Status {
+  OPEN @alias("open")
+  IN_PROGRESS @alias("in progress")
+}
+
+class LineItem {
+  sku string
+  quantity int?
+  prices map<string, float>?
+}
+
+/// A customer order
+class Order {
+  id string
+  status Status
+  items LineItem[]
+  note string? @description("Free text")
+  shipping_address OrderShippingAddress @alias("shipping-address")
+  payment int | "cash"
+}
+
+class OrderShippingAddress {
+  city string
+}
+"#
+    );
+
+    let ctx = load_context(&baml, Some("Order"));
+    let output = r#"{
+      "id": "A1",
+      "status": "in progress",
+      "items": [{"sku": "X", "quantity": null}],
+      "shipping-address": {"city": "Oslo"},
+      "payment": "cash"
+    }"#;
+    let result: serde_json::Value =
+        serde_json::from_str(&ctx.validate_result(output, false).unwrap()).unwrap();
+    assert_eq!(result["shipping-address"]["city"], "Oslo");
+    assert_eq!(result["payment"], "cash");
+    assert_eq!(result["items"][0]["quantity"], serde_json::Value::Null);
+
+    let err = from_json_schema(&serde_json::json!({
+        "type": "object",
+        "properties": { "extra": {} },
+    }))
+    .unwrap_err();
+    assert_eq!(
+        format!("{err:#}"),
+        "In property `Root.extra`: The schema accepts any value, which BAML cannot express"
+    );
+}
+
+#[test]
+fn json_schema_roots_without_a_type_import() {
+    let baml = from_json_schema(&serde_json::json!({
+        "properties": { "id": { "type": "string" } },
+        "required": ["id"],
+    }))
+    .unwrap();
+    assert_eq!(baml, "class Root {\n  id string\n}\n");
+
+    let baml = from_json_schema(&serde_json::json!({ "title": "Size", "enum": ["S", "M"] }));
+    assert_eq!(baml.unwrap(), "enum Size {\n  S\n  M\n}\n");
+
+    let baml = from_json_schema(&serde_json::json!({ "items": { "type": "integer" } }));
+    assert_eq!(baml.unwrap(), "type Root = int[]\n");
+
+    // Only definitions, no root type.
+    let baml = from_json_schema(&serde_json::json!({ "$defs": { "Id": { "type": "string" } } }));
+    assert_eq!(baml.unwrap(), "type Id = string\n");
+}
+
+#[test]
+fn json_schema_import_keeps_names_unique() {
+    let baml = from_json_schema(&serde_json::json!({
+        "type": "object",
+        "properties": { "foo-bar": { "type": "string" }, "foo_bar": { "type": "integer" } },
+        "required": ["foo-bar", "foo_bar"],
+    }))
+    .unwrap();
+    assert_eq!(
+        baml,
+        "class Root {\n  foo_bar string @alias(\"foo-bar\")\n  foo_bar_2 int @alias(\"foo_bar\")\n}\n"
+    );
+    let ctx = load_context(&baml, Some("Root"));
+    assert_eq!(
+        ctx.validate_result(r#"{"foo-bar": "a", "foo_bar": 1}"#, false)
+            .unwrap(),
+        r#"{"foo-bar":"a","foo_bar":1}"#
+    );
+
+    let baml = from_json_schema(&serde_json::json!({
+        "title": "Gap",
+        "type": "string",
+        "enum": ["a b", "a-b"],
+    }))
+    .unwrap();
+    assert_eq!(
+        baml,
+        "enum Gap {\n  A_B @alias(\"a b\")\n  A_B_2 @alias(\"a-b\")\n}\n"
+    );
+    load_context(&baml, Some("Gap"));
+
+    // The class for `address` would be named like the `OrderAddress` definition.
+    let baml = from_json_schema(&serde_json::json!({
+        "title": "Order",
+        "type": "object",
+        "properties": {
+            "address": { "type": "object", "properties": { "city": { "type": "string" } } },
+            "billing": { "$ref": "#/$defs/OrderAddress" },
+        },
+        "$defs": {
+            "OrderAddress": { "type": "object", "properties": { "zip": { "type": "string" } } },
+        },
+    }))
+    .unwrap();
+    assert_eq!(
+        baml,
+        r#"class OrderAddress {
+  zip string?
+}
+
+class Order {
+  address OrderAddress2?
+  billing OrderAddress?
+}
+
+class OrderAddress2 {
+  city string?
+}
+"#
+    );
+    load_context(&baml, Some("Order"));
+}
+
+#[test]
+fn anthropic_renderers_share_the_type_graph() {
+    let schema = r#"
+class Person {
+  name string @alias("full_name")
+  age int?
+}
+"#;
+    let ctx = load_context(schema, Some("Person"));
+
+    let tool = ctx.anthropic_tool("record_person", Some("Record a person")).unwrap();
+    assert_eq!(tool["name"], "record_person");
+    assert_eq!(tool["description"], "Record a person");
+    assert_eq!(tool["input_schema"], ctx.json_schema(false).unwrap());
+    assert_eq!(tool["input_schema"]["required"], serde_json::json!(["full_name"]));
+
+    let prompt = ctx.render_xml_prompt(&XmlTags::default()).unwrap();
+    assert_eq!(
+        prompt,
+        "<output_format>\n{\n  full_name: string,\n  age: int or null,\n}\n</output_format>\n\nAnswer inside <answer></answer> tags, following <output_format>."
+    );
+    let output = r#"<answer>{"full_name": "Ada", "age": 36}</answer>"#.to_string();
+    assert_eq!(
+        ctx.validate_result(&output, false).unwrap(),
+        r#"{"full_name":"Ada","age":36}"#
+    );
+
+    let enum_ctx = load_context("enum Mood {\n  HAPPY\n}\n", Some("Mood"));
+    assert!(enum_ctx.anthropic_tool("mood", None).is_err());
+}
+
+#[test]
+fn schemas_span_several_files() {
+    let files = vec![
+        (
+            PathBuf::from("baml_src/order.baml"),
+            "class Order {\n  id string\n  status Status\n}\n".to_string(),
+        ),
+        (
+            PathBuf::from("baml_src/status.baml"),
+            "enum Status {\n  OPEN\n  CLOSED\n}\n".to_string(),
+        ),
+    ];
+    let ctx = BamlContext::try_from_files(&PathBuf::from("baml_src"), files).unwrap();
+    let output = r#"{"id": "a1", "status": "CLOSED"}"#.to_string();
+    assert_eq!(
+        ctx.validate_result(&output, false).unwrap(),
+        r#"{"id":"a1","status":"CLOSED"}"#
+    );
+
+    let broken = vec![
+        (
+            PathBuf::from("baml_src/order.baml"),
+            "class Order {\n  status Missing\n}\n".to_string(),
+        ),
+        (
+            PathBuf::from("baml_src/status.baml"),
+            "enum Status {\n  OPEN\n}\n".to_string(),
+        ),
+    ];
+    let err = BamlContext::try_from_files(&PathBuf::from("baml_src"), broken)
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(err.contains("order.baml"), "{err}");
+    assert!(!err.contains("status.baml"), "{err}");
+}
+
+#[test]
+fn stream_chunks_yield_partial_values() {
+    let ctx = load_context("class Note {\n  title string\n  body string\n}\n", Some("Note"));
+    let mut state = StreamState::default();
+    ctx.validate_stream_chunk(&mut state, "Sure! ");
+
+    let first = ctx
+        .validate_stream_chunk(&mut state, r#"{"title": "Groceries", "#)
+        .unwrap();
+    let BamlValue::Class(_, fields) = &first else {
+        panic!("expected a class, got {first:?}");
+    };
+    assert_eq!(fields["title"], BamlValue::String("Groceries".to_string()));
+    assert_eq!(fields["body"], BamlValue::Null);
+
+    assert_eq!(ctx.validate_stream_chunk(&mut state, "\n  ").as_ref(), Some(&first));
+    ctx.validate_stream_chunk(&mut state, r#""body": "milk"}"#).unwrap();
+    assert_eq!(state.output(), "Sure! {\"title\": \"Groceries\", \n  \"body\": \"milk\"}");
+
+    let BamlValue::Class(_, fields) = ctx.finish_stream(state).unwrap() else {
+        panic!("expected a class");
+    };
+    assert_eq!(fields["body"], BamlValue::String("milk".to_string()));
+}
+
+#[test]
+fn sse_transcripts_validate_from_their_deltas() {
+    let ctx = load_context("class Note {\n  title string\n}\n", Some("Note"));
+    let transcript = concat!(
+        "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n",
+        "data: {\"choices\":[{\"delta\":{\"content\":\"{\\\"title\\\": \"}}]}\n\n",
+        ": keep-alive\n\n",
+        "data: {\"choices\":[{\"delta\":{\"content\":\"\\\"Groceries\\\"}\"}}]}\r\n\r\n",
+        "data: [DONE]\n",
+    );
+    assert_eq!(sse_deltas(transcript).unwrap(), vec!["{\"title\": ", "\"Groceries\"}"]);
+    let BamlValue::Class(_, fields) = ctx.validate_sse_transcript(transcript).unwrap() else {
+        panic!("expected a class");
+    };
+    assert_eq!(fields["title"], BamlValue::String("Groceries".to_string()));
+
+    let anthropic = concat!(
+        "event: content_block_delta\n",
+        "data: {\"type\":\"content_block_delta\",\"delta\":{\"type\":\"text_delta\",\"text\":\"hi\"}}\n",
+    );
+    assert_eq!(sse_deltas(anthropic).unwrap(), vec!["hi"]);
+
+    let err = sse_deltas("data: {\n\n").unwrap_err();
+    assert_eq!(err.to_string(), "Invalid JSON in the event ending on line 2");
+}
+
+#[test]
+fn refusals_fail_before_coercion() {
+    use crate::events::{ValidationEvent, ValidationOutcome};
+    use crate::refusal::{Refusal, RefusalDetector};
+    use std::sync::Mutex;
+
+    let schema = "class Answer {\n  value int\n}\n";
+    let refusal = "  I’m sorry, but I can't help with that.\n";
+    let err = load_context(schema, Some("Answer"))
+        .validate_result(refusal, false)
+        .unwrap_err();
+    assert!(err.downcast_ref::<Refusal>().is_none());
+
+    let events: Arc<Mutex<Vec<ValidationEvent>>> = Arc::default();
+    let recorded = events.clone();
+    let ctx = load_context(schema, Some("Answer"))
+        .with_refusal_detection(RefusalDetector::default())
+        .with_validation_sink(Arc::new(move |event: &ValidationEvent| {
+            recorded.lock().unwrap().push(event.clone())
+        }));
+    let err = ctx.validate_result(refusal, false).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<Refusal>(),
+        Some(&Refusal {
+            text: "I’m sorry, but I can't help with that.".to_string(),
+        })
+    );
+    assert_eq!(
+        events.lock().unwrap()[0].outcome,
+        ValidationOutcome::Refusal {
+            text: "I’m sorry, but I can't help with that.".to_string(),
+        }
+    );
+    assert_eq!(
+        ctx.validate_result("Sorry for the wait! I cannot round: {\"value\": 3}", false)
+            .unwrap(),
+        r#"{"value":3}"#
+    );
+
+    let ctx = load_context(schema, Some("Answer"))
+        .with_refusal_detection(RefusalDetector::new(["je ne peux pas"]));
+    assert!(ctx.validate_result("Je ne peux pas répondre.", false).unwrap_err().is::<Refusal>());
+    assert!(!ctx.validate_result(refusal, false).unwrap_err().is::<Refusal>());
+}
+
+#[test]
+fn reasoning_is_kept_next_to_the_result() {
+    let ctx = load_context("class Answer {\n  value int\n}\n", Some("Answer"));
+
+    let tagged = "<think>\nMaybe {\"value\": 1}? No, 2.\n</think>\n{\"value\": 2}";
+    assert_eq!(
+        ctx.validate_result_with_reasoning(tagged, false).unwrap(),
+        Reasoned {
+            value: r#"{"value":2}"#.to_string(),
+            reasoning: Some("Maybe {\"value\": 1}? No, 2.".to_string()),
+        }
+    );
+
+    let closed_only = "Two it is.</think>{\"value\": 2}";
+    let reasoned = ctx.validate_result_with_reasoning(closed_only, false).unwrap();
+    assert_eq!(reasoned.reasoning.as_deref(), Some("Two it is."));
+
+    let preamble = "One plus one is two.\n```json\n{\"value\": 2}\n```";
+    let reasoned = ctx.validate_result_with_reasoning(preamble, false).unwrap();
+    assert_eq!(reasoned.value, r#"{"value":2}"#);
+    assert_eq!(reasoned.reasoning.as_deref(), Some("One plus one is two."));
+
+    let plain = ctx.validate_result_with_reasoning("{\"value\": 2}", false).unwrap();
+    assert_eq!(plain.reasoning, None);
+}
+
+#[test]
+fn functions_target_their_return_type() {
+    let schema = r##"
+class Item {
+  name string
+}
+
+function ExtractItems(text: string) -> Item[] {
+  client "openai/gpt-4o"
+  prompt #"{{ text }} {{ ctx.output_format }}"#
+}
+"##;
+    let ctx = load_context(schema, Some("ExtractItems"));
+    assert_eq!(
+        ctx.target,
+        FieldType::List(Box::new(FieldType::Class("Item".to_string())))
+    );
+    assert_eq!(
+        ctx.validate_result(r#"[{"name": "pen"}]"#, false).unwrap(),
+        r#"[{"name":"pen"}]"#
+    );
+
+    let err = BamlContext::try_from_schema(schema, Some("Missing".to_string()))
+        .err()
+        .unwrap();
+    assert_eq!(err.to_string(), "No class, enum, type alias or function named `Missing`");
+
+    let versioned = schema.replace("  name string\n", "  name string\n  @@since(\"2\")\n");
+    let err =
+        BamlContext::try_from_schema_versioned(&versioned, Some("ExtractItems".to_string()), "1")
+            .err()
+            .unwrap();
+    assert_eq!(
+        err.to_string(),
+        "Function `ExtractItems` returns `Item`, which is not part of schema version 1"
+    );
+}
+
+#[test]
+fn classify_picks_the_best_fitting_target() {
+    let schema = r#"
+class Weather {
+  city string
+  celsius float
+}
+
+class Clarification {
+  question string
+}
+
+enum Refusal {
+  OFF_TOPIC
+}
+"#;
+    let ctx = load_context(schema, Some("Weather"));
+    let candidates = ["Weather", "Clarification", "Refusal"];
+
+    let classification = ctx
+        .classify(r#"{"question": "Which city?"}"#, &candidates)
+        .unwrap();
+    assert_eq!(classification.target, "Clarification");
+    assert_eq!(classification.value, r#"{"question":"Which city?"}"#);
+    assert_eq!(classification.scores[1], ("Clarification".to_string(), Some(0)));
+    assert_eq!(classification.scores[2], ("Refusal".to_string(), None));
+
+    let classification = ctx.classify("OFF_TOPIC", &candidates).unwrap();
+    assert_eq!(classification.target, "Refusal");
+
+    assert!(ctx
+        .classify(r#"{"celsius": 3}"#, &["Weather", "Refusal"])
+        .is_err());
+}
+
+#[test]
+fn function_prompts_render_with_their_arguments() {
+    use crate::function_prompt::{ChatMessagePart, RenderedPrompt};
+
+    let schema = r##"
+class Item {
+  name string
+}
+
+class Order {
+  id string
+}
+
+function ExtractItem(text: string) -> Item {
+  client "openai/gpt-4o"
+  prompt #"
+    {{ _.role("system") }}
+    Extract the item. {{ ctx.output_format }}
+    {{ _.role("user") }}
+    {{ text }}
+  "#
+}
+"##;
+    let ctx = load_context(schema, Some("Order"));
+    let args = BamlMap::from([("text".to_string(), BamlValue::String("a pen".to_string()))]);
+    let RenderedPrompt::Chat(messages) = ctx.render_function_prompt("ExtractItem", args).unwrap()
+    else {
+        panic!("expected chat messages");
+    };
+    let texts = messages
+        .iter()
+        .map(|message| {
+            let [ChatMessagePart::Text(text)] = message.parts.as_slice() else {
+                panic!("expected one text part, got {:?}", message.parts);
+            };
+            (message.role.as_str(), text.trim())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        texts,
+        vec![
+            (
+                "system",
+                "Extract the item. Answer in JSON using this schema:\n{\n  name: string,\n}"
+            ),
+            ("user", "a pen"),
+        ]
+    );
+
+    assert!(ctx.render_function_prompt("ExtractItem", BamlMap::new()).is_err());
+    assert!(ctx.render_function_prompt("Missing", BamlMap::new()).is_err());
+}
+
+#[test]
+fn prompt_suites_render_named_variants() {
+    use crate::function_prompt::{ChatMessagePart, RenderedPrompt};
+
+    let schema = r##"
+class Item {
+  name string
+}
+
+function ExtractItem(text: string) -> Item {
+  client "openai/gpt-4o"
+  prompt #"Extract the item from {{ text }}. {{ ctx.output_format }}"#
+}
+
+prompt_suite ExtractItemVariants {
+  function ExtractItem
+  variants {
+    terse #"Item in {{ text }}?"#
+    detailed #"
+      Read {{ text }} carefully and extract the item.
+    "#
+  }
+}
+"##;
+    let render = |ctx: &BamlContext, variant: &str| {
+        let args = BamlMap::from([("text".to_string(), BamlValue::String("a pen".to_string()))]);
+        ctx.render_function_prompt_variant("ExtractItem", variant, args)
+            .unwrap()
+    };
+    // Variants without roles render as completions, like function prompts.
+    let ctx = load_context(schema, Some("Item"));
+    let RenderedPrompt::Completion(terse) = render(&ctx, "terse") else {
+        panic!("expected a completion");
+    };
+    assert_eq!(terse.trim(), "Item in a pen?");
+    let RenderedPrompt::Completion(detailed) = render(&ctx, "detailed") else {
+        panic!("expected a completion");
+    };
+    assert_eq!(
+        detailed.trim(),
+        "Read a pen carefully and extract the item."
+    );
+
+    let with_roles = schema.replace("terse #\"Item in", "terse #\"{{ _.role(\"user\") }}Item in");
+    let RenderedPrompt::Chat(messages) = render(&load_context(&with_roles, None), "terse") else {
+        panic!("expected chat messages");
+    };
+    let [message] = messages.as_slice() else {
+        panic!("expected one message, got {messages:?}");
+    };
+    assert_eq!(message.role, "user");
+    let [ChatMessagePart::Text(text)] = message.parts.as_slice() else {
+        panic!("expected one text part, got {:?}", message.parts);
+    };
+    assert_eq!(text.trim(), "Item in a pen?");
+
+    let err = ctx
+        .render_function_prompt_variant("ExtractItem", "tersest", BamlMap::new())
+        .unwrap_err();
+    assert!(err.to_string().contains("Did you mean"), "{err}");
+
+    let duplicate = format!(
+        "{schema}\nprompt_suite More {{\n  function ExtractItem\n  variants {{\n    terse #\"Short\"#\n  }}\n}}\n"
+    );
+    let err = BamlContext::try_from_schema(&duplicate, None).unwrap_err();
+    assert!(
+        format!("{err:#}").contains(
+            "Variant `terse` of function `ExtractItem` is already defined in prompt_suite ExtractItemVariants"
+        ),
+        "{err:#}"
+    );
+    let unknown = schema.replace("  function ExtractItem\n  variants", "  function Extract\n  variants");
+    assert!(BamlContext::try_from_schema(&unknown, None).is_err());
+}
+
+#[test]
+fn constants_resolve_in_attributes_and_constraints() {
+    let schema = r#"
+const MAX_TAGS = 2
+const TAGS_NOTE = "Lowercase keywords"
+
+class Post {
+  tags string[] @description(TAGS_NOTE) @assert(few, {{ this|length <= MAX_TAGS }})
+}
+"#;
+    let ctx = load_context(schema, Some("Post"));
+    let rendered = ctx.render_prompt(None, None).unwrap();
+    assert!(rendered.contains("// Lowercase keywords\n  tags: string[]"), "{rendered}");
+    assert!(ctx
+        .validate_result(r#"{"tags": ["a", "b"]}"#, false)
+        .is_ok());
+    assert!(ctx
+        .validate_result(r#"{"tags": ["a", "b", "c"]}"#, false)
+        .is_err());
+
+    let invalid = "const LIMIT = [1, 2]\nclass Post {\n  tags string[]\n}\n".to_string();
+    let err = BamlContext::try_from_schema(&invalid, None).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("const `LIMIT` must be a number, a quoted string or a boolean"),
+        "{err:#}"
+    );
+}
+
+#[test]
+fn pydantic_models_cover_classes_and_enums() {
+    let schema = r#"
+enum Status {
+  Active @alias("active")
+  Closed @description("No longer used")
+}
+
+class Account {
+  id int
+  owner_name string @alias("ownerName") @description("Full name")
+  status Status
+  tags string[]
+  parent Account?
+  note string | int | null
+  from string
+}
+"#;
+    let ctx = load_context(schema, Some("Account"));
+    let source = ctx.to_pydantic().unwrap();
+    assert!(
+        source.starts_with(
+            "from __future__ import annotations\n\nfrom enum import Enum\nfrom typing import List, Optional, Union\n\nfrom pydantic import BaseModel, ConfigDict, Field\n"
+        ),
+        "{source}"
+    );
+    assert!(
+        source.contains(
+            "class Status(str, Enum):\n    Active = \"active\"\n    # No longer used\n    Closed = \"Closed\"\n"
+        ),
+        "{source}"
+    );
+    assert!(
+        source.contains(concat!(
+            "class Account(BaseModel):\n",
+            "    model_config = ConfigDict(populate_by_name=True)\n\n",
+            "    id: int\n",
+            "    owner_name: str = Field(alias=\"ownerName\", description=\"Full name\")\n",
+            "    status: Status\n",
+            "    tags: List[str]\n",
+            "    parent: Optional[Account] = None\n",
+            "    note: Optional[Union[str, int]] = None\n",
+            "    from_: str = Field(alias=\"from\")\n",
+        )),
+        "{source}"
+    );
+}
+
+#[test]
+fn description_references_render_aliases() {
+    let schema = r#"
+enum Currency {
+  Euro @alias("EUR")
+  Dollar
+}
+
+class Order {
+  total_price int @alias("totalPrice")
+  currency Currency @description("Currency of {{ Order.total_price }}, {{ Currency.Euro }} or {{ Currency.Dollar }}")
+}
+"#;
+    let ctx = load_context(schema, Some("Order"));
+    let prompt = ctx.render_prompt(None, None).unwrap();
+    assert!(prompt.contains("Currency of totalPrice, EUR or Dollar"), "{prompt}");
+
+    let invalid = r#"
+class Order {
+  total_price int
+  currency string @description("Same as {{ Order.price }}")
+}
+"#
+    .to_string();
+    let err = BamlContext::try_from_schema(&invalid, None).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("Unknown reference `Order.price`: `Order` has no field `price`"),
+        "{err:#}"
+    );
+}
+
+#[test]
+fn typescript_declarations_cover_classes_and_enums() {
+    let schema = r#"
+enum Status {
+  Active @alias("active")
+  Closed @description("No longer used")
+}
+
+class Account {
+  id int
+  owner_name string @alias("ownerName") @description("Full name")
+  status Status
+  tags (string | int)[]
+  parent Account?
+  note string | int | null
+}
+"#;
+    let ctx = load_context(schema, Some("Account"));
+    let source = ctx.to_typescript().unwrap();
+    assert!(
+        source.contains(
+            "export enum Status {\n  Active = \"active\",\n  /** No longer used */\n  Closed = \"Closed\",\n}\n"
+        ),
+        "{source}"
+    );
+    assert!(
+        source.contains(concat!(
+            "export interface Account {\n",
+            "  id: number;\n",
+            "  /** Full name */\n",
+            "  ownerName: string;\n",
+            "  status: Status;\n",
+            "  tags: (string | number)[];\n",
+            "  parent?: Account | null;\n",
+            "  note?: string | number | null;\n",
+            "}\n",
+        )),
+        "{source}"
+    );
+}
+
+#[test]
+fn ir_lists_enums_classes_and_template_strings() {
+    let schema = format!(
+        "{FRUIT_SCHEMA}\ntemplate_string Greet(name: string) #\"Hello {{{{ name }}}}\"#\n"
+    );
+    let ctx = load_context(&schema, Some("FruitOrders"));
+    let ir: crate::IntermediateRepr = ctx.ir().unwrap();
+    assert_eq!(
+        ir.walk_enums().map(|e| e.name().to_string()).collect::<Vec<_>>(),
+        ["FruitName"]
+    );
+    assert_eq!(
+        ir.walk_classes().map(|c| c.name().to_string()).collect::<Vec<_>>(),
+        ["Fruit", "FruitOrders"]
+    );
+    assert_eq!(
+        ir.walk_template_strings().map(|t| t.name().to_string()).collect::<Vec<_>>(),
+        ["Greet"]
+    );
+}
+
+#[test]
+fn lazy_ir_builds_only_the_requested_types() {
+    let ctx = load_context(FRUIT_SCHEMA, Some("FruitOrders"));
+    let lazy = ctx.lazy_ir();
+    let fruit = lazy.find_class("Fruit").unwrap();
+    assert_eq!(fruit.elem.name, "Fruit");
+    assert_eq!(lazy.built(), 1);
+    assert!(lazy.find_enum("Fruit").is_err());
+    assert_eq!(lazy.find_enum("FruitName").unwrap().elem.name, "FruitName");
+    assert_eq!(lazy.built(), 2);
+}
+
+#[test]
+fn render_attribute_overrides_class_rendering() {
+    let schema = r#"
+class Address {
+  street string @description("Street line")
+  city string
+
+  @@render({ layout "hoisted", separator ";", descriptions false })
+}
+
+class Person {
+  home Address
+  work Address?
+}
+"#;
+    let ctx = load_context(schema, Some("Person"));
+    let rendered = ctx.render_prompt(None, None).unwrap();
+    assert_eq!(
+        rendered,
+        "Address {\n  street: string;\n  city: string;\n}\n\nAnswer in JSON using this schema:\n{\n  home: Address,\n  work: Address or null,\n}"
+    );
+
+    let invalid = r#"
+class Address {
+  street string
+
+  @@render({ layout "sideways" })
+}
+"#
+    .to_string();
+    let err = BamlContext::try_from_schema(&invalid, None).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("`layout` must be \"inline\" or \"hoisted\"."),
+        "{err:#}"
+    );
+}
+
+#[test]
+fn registered_output_formatter_renders_the_prompt() {
+    use internal_baml_jinja::types::{OutputFormatContent, RenderOptions};
+
+    use crate::{OutputFormatter, STANDARD_FORMATTER};
+
+    struct ClassNames;
+
+    impl OutputFormatter for ClassNames {
+        fn render(
+            &self,
+            content: &OutputFormatContent,
+            _options: RenderOptions,
+        ) -> anyhow::Result<Option<String>> {
+            let names = content.classes.keys().cloned().collect::<Vec<_>>();
+            Ok(Some(format!("Classes: {}", names.join(", "))))
+        }
+    }
+
+    let ctx = load_context(FRUIT_SCHEMA, Some("FruitOrders"))
+        .with_output_formatter("names", Arc::new(ClassNames));
+    assert_eq!(ctx.output_formatters(), ["standard", "names"]);
+    assert_eq!(
+        ctx.render_prompt_with_formatter("names").unwrap(),
+        "Classes: Fruit, FruitOrders"
+    );
+    assert_eq!(
+        ctx.render_prompt_with_formatter(STANDARD_FORMATTER).unwrap(),
+        ctx.render_prompt(None, None).unwrap()
+    );
+    assert!(ctx.render_prompt_with_formatter("toml").is_err());
+
+    let ctx = ctx.with_output_formatter(STANDARD_FORMATTER, Arc::new(ClassNames));
+    assert_eq!(ctx.render_prompt(None, None).unwrap(), "Classes: Fruit, FruitOrders");
+}
+
+/// Redacts email addresses, defaults unparseable ints to 0 and counts the primitives it sees.
+#[derive(Default)]
+struct Sanitizer {
+    primitives: std::sync::atomic::AtomicUsize,
+}
+
+impl CoercerMiddleware for Sanitizer {
+    fn applies_to(&self, kind: TypeKind) -> bool {
+        kind == TypeKind::Primitive
+    }
+
+    fn before(
+        &self,
+        _scope: &[String],
+        _target: &FieldType,
+        value: Option<&serde_json::Value>,
+    ) -> Option<BamlValue> {
+        self.primitives.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        match value {
+            Some(serde_json::Value::String(s)) if s.contains('@') => {
+                Some(BamlValue::String("[redacted]".to_string()))
+            }
+            _ => None,
+        }
+    }
+
+    fn after(
+        &self,
+        _scope: &[String],
+        target: &FieldType,
+        result: Result<&BamlValueWithFlags, &ParsingError>,
+    ) -> Option<BamlValue> {
+        match (target, result) {
+            (FieldType::Primitive(baml_types::TypeValue::Int), Err(_)) => Some(BamlValue::Int(0)),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn coercer_middleware_wraps_every_coercion() {
+    let schema = "class Contact {\n  name string\n  email string\n  age int\n}";
+    let sanitizer = Arc::new(Sanitizer::default());
+    let ctx = load_context(schema, Some("Contact")).with_coercer_middleware(sanitizer.clone());
+    let output = r#"{"name": "Ada", "email": "ada@example.com", "age": "unknown"}"#.to_string();
+    assert_eq!(
+        ctx.validate_result(&output, false).unwrap(),
+        r#"{"name":"Ada","email":"[redacted]","age":0}"#
+    );
+    assert!(sanitizer.primitives.load(std::sync::atomic::Ordering::Relaxed) >= 3);
+}
+
+#[test]
+fn constraint_report_lists_every_assert_and_check() {
+    let schema = r#"
+class Item {
+  name  string
+  price float @check(cheap, {{ this < 10 }}) @assert(positive, {{ this > 0 }})
+}
+
+class Order {
+  items Item[]
+  @@check(not_empty, {{ this.items|length > 0 }})
+}
+"#;
+    let ctx = load_context(schema, Some("Order"));
+    let output = r#"{"items": [{"name": "pen", "price": 2}, {"name": "lamp", "price": 25}]}"#;
+    let report = ctx.validate_with_constraints(output, false).unwrap();
+    assert_eq!(report.value, ctx.validate_result(output, false).unwrap());
+    let outcomes = report
+        .constraints
+        .iter()
+        .map(|c| (c.path.as_str(), c.label.as_deref(), c.passed))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        outcomes,
+        [
+            ("$", Some("not_empty"), true),
+            ("$.items[0].price", Some("cheap"), true),
+            ("$.items[0].price", Some("positive"), true),
+            ("$.items[1].price", Some("cheap"), false),
+            ("$.items[1].price", Some("positive"), true),
+        ]
+    );
+    let failure = report.failures().next().unwrap();
+    assert_eq!(failure.level, baml_types::ConstraintLevel::Check);
+    assert_eq!(failure.expression.trim(), "this < 10");
+
+    let negative = r#"{"items": [{"name": "pen", "price": -2}]}"#;
+    assert!(ctx.validate_with_constraints(negative, false).is_err());
+}
+
+#[test]
+fn message_catalog_translates_errors() {
+    let catalog = TemplateCatalog::new("fr")
+        .with_template(
+            "duplicate_field",
+            "Le champ « {field} » est déjà défini dans {container} « {name} ».",
+        )
+        .with_template("coerce_failed", "Échec de la conversion : {error}");
+    let options = SchemaOptions {
+        message_catalog: Some(Arc::new(catalog)),
+        ..Default::default()
+    };
+
+    let duplicate = "class Person {\n  name string\n  name int\n}".to_string();
+    let err = BamlContext::try_from_schema_with_options(&duplicate, None, &options).unwrap_err();
+    assert!(
+        err.to_string().contains("Le champ « name » est déjà défini dans class « Person »."),
+        "{err}"
+    );
+
+    let schema = "class Person {\n  name string\n  age int\n}".to_string();
+    let ctx = BamlContext::try_from_schema_with_options(&schema, None, &options).unwrap();
+    let err = ctx.validate_result(r#"{"name": "Ada"}"#, false).unwrap_err();
+    assert!(err.to_string().starts_with("Échec de la conversion : "), "{err}");
+    // Messages without a translation stay in English.
+    assert!(err.to_string().contains("Missing required field: age"), "{err}");
+    assert!(err.downcast_ref::<jsonish::CoerceError>().is_some());
+}
+
+#[test]
+fn changelog_groups_changes_and_flags_breaking_ones() {
+    let old = load_context(
+        "class Person {\n  name string\n  age int\n  email string\n}",
+        None,
+    );
+    let new = load_context(
+        "class Person {\n  name string @alias(\"full_name\")\n  email string?\n  nickname string?\n}\n\nenum Status {\n  Active\n}",
+        None,
+    );
+
+    let changes = crate::diff::diff(&old, &new);
+    assert_eq!(changes.iter().filter(|c| c.breaking).count(), 3);
+    assert_eq!(
+        crate::diff::render_changelog(&old, &new),
+        "**3 breaking changes.**\n\
+         \n## Classes\n\
+         \n### `Person`\n\n\
+         - **Breaking:** Field `name` renamed in JSON from `name` to `full_name`.\n\
+         - **Breaking:** Field `email` is now optional.\n\
+         - **Breaking:** Field `age` removed.\n\
+         - Field `nickname` added: `string?`.\n\
+         \n## Enums\n\
+         \n### `Status`\n\n\
+         - Enum added.\n"
+    );
+    assert_eq!(crate::diff::render_changelog(&new, &new), "No changes.\n");
+}
+
+#[test]
+fn union_of_classes_target() {
+    let schema = r#"
+class Invoice {
+  invoice_number string
+  total float
+}
+
+class Receipt {
+  store string
+  total float
+}
+"#
+    .to_string();
+    let ctx = BamlContext::try_from_schema_with_union(&schema, &["Invoice", "Receipt"]).unwrap();
+    assert_eq!(
+        ctx.target,
+        FieldType::Union(vec![
+            FieldType::Class("Invoice".to_string()),
+            FieldType::Class("Receipt".to_string()),
+        ])
+    );
+    let receipt = ctx
+        .validate_result(r#"{"store": "Corner Shop", "total": 4.5}"#, false)
+        .unwrap();
+    assert_eq!(receipt, r#"{"store":"Corner Shop","total":4.5}"#);
+    let invoice = ctx
+        .validate_result(r#"{"invoice_number": "INV-7", "total": 120}"#, false)
+        .unwrap();
+    assert_eq!(invoice, r#"{"invoice_number":"INV-7","total":120.0}"#);
+
+    let listed = BamlContext::try_from_schema(&schema, Some(" Invoice ,Receipt".to_string()));
+    assert_eq!(listed.unwrap().target, ctx.target);
+    let err = BamlContext::try_from_schema(&schema, Some("Invoice,".to_string())).unwrap_err();
+    assert!(err.to_string().contains("comma-separated"), "{err}");
+    let err = BamlContext::try_from_schema_with_union(&schema, &["Invoice", "Bill"]).unwrap_err();
+    assert!(err.to_string().contains("`Bill`"), "{err}");
+}
+
+#[test]
+fn cache_key_normalizes_arguments() {
+    let ctx = load_context(FRUIT_SCHEMA, Some("Fruit")).with_render_profile(
+        "compact",
+        RenderProfile {
+            style: RenderStyle::Compact,
+            ..Default::default()
+        },
+    );
+    let key = ctx.cache_key(r#"{"text": "apples", "n": 2}"#, None).unwrap();
+    assert_eq!(key.len(), 64);
+    assert_eq!(key, ctx.cache_key("{\n  \"n\": 2,\n  \"text\": \"apples\"\n}", None).unwrap());
+    assert_ne!(key, ctx.cache_key(r#"{"text": "pears", "n": 2}"#, None).unwrap());
+    assert_ne!(key, ctx.cache_key(r#"{"text": "apples", "n": 2}"#, Some("compact")).unwrap());
+
+    let described = FRUIT_SCHEMA.replace("Default", "Anything else");
+    let other = load_context(&described, Some("Fruit"));
+    assert_ne!(key, other.cache_key(r#"{"text": "apples", "n": 2}"#, None).unwrap());
+    let saturating = FRUIT_SCHEMA.replace(
+        "@alias(\"fruit_price\")",
+        "@alias(\"fruit_price\") @int_overflow(\"saturate\")",
+    );
+    let other = load_context(&saturating, Some("Fruit"));
+    assert_ne!(key, other.cache_key(r#"{"text": "apples", "n": 2}"#, None).unwrap());
+
+    assert!(ctx.cache_key("{text: apples}", None).is_err());
+    assert!(ctx.cache_key("{}", Some("verbose")).is_err());
+}
+
+#[test]
+fn template_string_parameters_take_defaults() {
+    use crate::function_prompt::RenderedPrompt;
+
+    let schema = r##"
+class Item {
+  name string
+}
+
+template_string Greet(name: string, greeting: string = "Hello", times: int = 1) #"{{ greeting }} {{ name }} x{{ times }}"#
+
+function Welcome(name: string) -> Item {
+  client "openai/gpt-4o"
+  prompt #"{{ Greet(name) }} / {{ Greet(name, times=2) }} / {{ Greet(name, "Hi") }}"#
+}
+"##;
+    let ctx = load_context(schema, Some("Item"));
+    let args = BamlMap::from([("name".to_string(), BamlValue::String("Ada".to_string()))]);
+    assert_eq!(
+        ctx.render_function_prompt("Welcome", args).unwrap(),
+        RenderedPrompt::Completion("Hello Ada x1 / Hello Ada x2 / Hi Ada x1".to_string())
+    );
+    let ir = ctx.ir().unwrap();
+    let greet = ir.walk_template_strings().next().unwrap();
+    assert_eq!(
+        greet.defaults().keys().map(String::as_str).collect::<Vec<_>>(),
+        ["greeting", "times"]
+    );
+
+    let invalid = r##"
+template_string Bad(count: int = "three", label: string) #"{{ count }} {{ label }}"#
+
+class Item {
+  name string
+}
+"##
+    .to_string();
+    let err = BamlContext::try_from_schema(&invalid, None).unwrap_err().to_string();
+    assert!(
+        err.contains("The default value of `count` must be a literal of type `int`."),
+        "{err}"
+    );
+    assert!(err.contains("Parameter `label` needs a default value"), "{err}");
+
+    let bare_word =
+        invalid.replace("count: int = \"three\", label: string", "label: string = hi");
+    let err = BamlContext::try_from_schema(&bare_word, None).unwrap_err().to_string();
+    assert!(
+        err.contains("The default value of `label` must be a literal of type `string`."),
+        "{err}"
+    );
+
+    // A default reading an unset env var only breaks the prompts calling its template string.
+    let unset_env = format!(
+        "{schema}\ntemplate_string Sign(by: string = env.SIGNATURE) #\"{{{{ by }}}}\"#\n\n\
+         function Signed(name: string) -> Item {{\n  client \"openai/gpt-4o\"\n  \
+         prompt #\"{{{{ Greet(name) }}}} {{{{ Sign() }}}}\"#\n}}\n"
+    );
+    let ctx = load_context(&unset_env, Some("Item"));
+    let args = BamlMap::from([("name".to_string(), BamlValue::String("Ada".to_string()))]);
+    assert_eq!(
+        ctx.render_function_prompt("Welcome", args.clone()).unwrap(),
+        RenderedPrompt::Completion("Hello Ada x1 / Hello Ada x2 / Hi Ada x1".to_string())
+    );
+    let err = ctx.render_function_prompt("Signed", args).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("default of `Sign.by`"),
+        "{err:#}"
+    );
+
+    let function_default =
+        schema.replace("Welcome(name: string)", "Welcome(name: string = \"Ada\")");
+    let err = BamlContext::try_from_schema(&function_default, None).unwrap_err().to_string();
+    assert!(err.contains("Only template_string parameters can have default values."), "{err}");
+}
+
+#[test]
+fn input_schema_describes_function_arguments() {
+    let schema = r##"
+class Address {
+  street string
+  city string @description("City name")
+}
+
+function Ship(address: Address, priority: int?) -> Address {
+  client "openai/gpt-4o"
+  prompt #"Ship to {{ address.city }}"#
+}
+"##;
+    let ctx = load_context(schema, Some("Address"));
+    assert_eq!(
+        ctx.render_input_schema("Ship").unwrap(),
+        r#"Call Ship with arguments in JSON using this schema:
+{
+  address: {
+    street: string,
+    // City name
+    city: string,
+  },
+  priority: int or null,
+}"#
+    );
+    assert!(ctx.render_input_schema("Missing").is_err());
+}
+
+#[test]
+fn diagnostics_locate_schema_errors() {
+    let schema = "class Person {\n  name string\n  pet Missing\n}\n".to_string();
+    let options = SchemaOptions {
+        source_name: Some("person.baml".to_string()),
+        ..Default::default()
+    };
+    let (ctx, items) =
+        BamlContext::try_from_schema_with_diagnostics(&schema, Some("Person".into()), &options);
+    assert!(ctx.is_err());
+    let error = items
+        .iter()
+        .find(|item| item.severity == Severity::Error)
+        .expect("an error for the unknown type");
+    assert!(error.message.contains("Missing"), "{}", error.message);
+    assert_eq!(error.path, "person.baml");
+    assert_eq!(&schema[error.start..error.end], "Missing");
+    assert_eq!((error.start_line, error.start_column), (2, 6));
+    assert_eq!((error.end_line, error.end_column), (2, 13));
+
+    let json = serde_json::to_value(error).unwrap();
+    assert_eq!(json["severity"], "error");
+
+    let valid = "class Person {\n  name string\n}\n".to_string();
+    let (ctx, items) =
+        BamlContext::try_from_schema_with_diagnostics(&valid, Some("Person".into()), &options);
+    assert!(ctx.is_ok());
+    assert!(items.iter().all(|item| item.severity == Severity::Warning));
+}
+
+#[test]
+fn recursive_classes_render_with_references_and_coerce_trees() {
+    let schema = r#"
+class Tree {
+  value int
+  children Tree[]
+}
+"#;
+    let ctx = load_context(schema, Some("Tree"));
+    assert_eq!(
+        ctx.render_prompt(None, None).unwrap(),
+        "Tree {\n  value: int,\n  children: Tree[],\n}\n\nAnswer in JSON using this schema: Tree"
+    );
+    let fingerprint = ctx.fingerprint();
+    let ctx = ctx.with_recursion_rendering(RecursionRendering {
+        inline_depth: 1,
+        references: ReferenceStyle::Marker,
+    });
+    assert_ne!(ctx.fingerprint(), fingerprint);
+    assert_eq!(
+        ctx.render_prompt(None, None).unwrap(),
+        r#"Tree {
+  value: int,
+  children: [
+    {
+      value: int,
+      children: #/Tree[],
+    }
+  ],
+}
+
+Answer in JSON using this schema: #/Tree"#
+    );
+
+    let output = r#"{"value": 1, "children": [
+        {"value": 2, "children": [{"value": 3, "children": []}]},
+        {"value": "4", "children": []}
+    ]}"#;
+    assert_eq!(
+        ctx.validate_result(output, false).unwrap(),
+        r#"{"value":1,"children":[{"value":2,"children":[{"value":3,"children":[]}]},{"value":4,"children":[]}]}"#
+    );
+    assert!(ctx.validate_result(r#"{"children": []}"#, false).is_err());
+}
+
+#[test]
+fn max_value_depth_cuts_deep_recursive_outputs() {
+    let schema = r#"
+class Node {
+  value int
+  next Node?
+}
+"#;
+    let output = r#"{"value": 1, "next": {"value": 2, "next": {"value": 3, "next": null}}}"#;
+    let ctx = load_context(schema, Some("Node")).with_max_value_depth(1);
+    assert_eq!(
+        ctx.validate_result(output, false).unwrap(),
+        r#"{"value":1,"next":{"value":2,"next":null}}"#
+    );
+    let flagged = ctx.parse_result_with_flags(output, false).unwrap();
+    let cut = flagged.get("next").unwrap().get("next").unwrap();
+    assert_eq!(cut.flags()[0].kind, "DepthLimitExceeded");
+
+    // A union without an empty value cannot be cut.
+    let schema = "class Node {\n  value int\n  child Node | int\n}";
+    let output = r#"{"value": 1, "child": {"value": 2, "child": {"value": 3}}}"#;
+    let ctx = load_context(schema, Some("Node")).with_max_value_depth(1);
+    assert!(ctx.validate_result(output, false).is_err());
+}
+
+#[test]
+fn union_outputs_render_as_variants() {
+    let schema = r#"
+class Bug {
+  title string
+  labels map<string, int>
+}
+
+type Report = Bug | string
+"#;
+    let ctx = load_context(schema, Some("Report"));
+    let inline = ctx.render_prompt(None, None).unwrap();
+    assert!(inline.starts_with("Answer in JSON using any of these schemas:\n{"), "{inline}");
+
+    let options = PromptOptions {
+        map_style: Some(MapStyle::ObjectLiteral),
+        union_style: UnionStyle::Variants,
+        ..Default::default()
+    };
+    assert_eq!(
+        ctx.render_prompt_with_options(&options).unwrap(),
+        "Answer in JSON using one of the following shapes:\n\nShape 1 (Bug):\n{\n  title: string,\n  labels: {string: int},\n}\n\nShape 2:\nstring"
+    );
+}
+
+#[test]
+fn shared_definitions_hoist_repeated_shapes() {
+    let schema = r#"
+class Address {
+  street string
+  city string
+}
+
+class Location {
+  street string
+  city string
+}
+
+class Order {
+  shipping Address
+  billing Address?
+  stops Location[]
+}
+"#;
+    let ctx = load_context(schema, Some("Order"));
+    let shared = load_context(schema, Some("Order")).with_shared_definitions(true);
+    assert_ne!(ctx.fingerprint(), shared.fingerprint());
+    assert_eq!(
+        shared.render_prompt(None, None).unwrap(),
+        "Address {\n  street: string,\n  city: string,\n}\n\nAnswer in JSON using this schema:\n{\n  shipping: Address,\n  billing: Address or null,\n  stops: Address[],\n}"
+    );
+    let output = r#"{"shipping": {"street": "1 Main St", "city": "Springfield"}, "billing": null, "stops": []}"#;
+    assert_eq!(
+        shared.validate_result(output, false).unwrap(),
+        ctx.validate_result(output, false).unwrap()
+    );
+}
+
+#[test]
+fn markdown_tables_render_classes_and_ab_test_against_blocks() {
+    let schema = r#"
+class Address {
+  city string
+}
+
+class Person {
+  name string @description("Full name")
+  home Address
+}
+"#;
+    let ctx = load_context(schema, Some("Person")).with_render_profile(
+        "markdown",
+        RenderProfile {
+            format: RenderFormat::MarkdownTable,
+            ..Default::default()
+        },
+    );
+    let tables = "Address:\n| Field | Type | Description |\n| --- | --- | --- |\n| city | string |  |\n\nAnswer in JSON using this schema:\n| Field | Type | Description |\n| --- | --- | --- |\n| name | string | Full name |\n| home | Address |  |";
+    assert_eq!(ctx.render_prompt_with_profile("markdown").unwrap().prompt, tables);
+    assert!(ctx.render_prompt(None, None).unwrap().contains("home: {"));
+
+    let markdown = load_context(schema, Some("Person")).with_render_format(RenderFormat::MarkdownTable);
+    assert_eq!(markdown.render_prompt(None, None).unwrap(), tables);
+    assert_ne!(markdown.fingerprint(), ctx.fingerprint());
+    let output = r#"{"name": "Ada", "home": {"city": "London"}}"#;
+    assert_eq!(
+        markdown.validate_result(output, false).unwrap(),
+        ctx.validate_result(output, false).unwrap()
+    );
+}
@@ -0,0 +1,295 @@
+//! The decisions taken while coercing one LLM output, for debugging surprising results.
+
+use std::fmt::Write as _;
+
+use baml_types::BamlValue;
+use jsonish::{deserializer::deserialize_flags::Flag, BamlValueWithFlags};
+use serde::Serialize;
+
+use crate::flagged::FlagInfo;
+
+/// Everything [`BamlContext::trace_result`](crate::BamlContext::trace_result) recorded about
+/// one call: the chosen value with its scores and flags, every union candidate that was
+/// considered and the outcome of each `@check`.
+///
+/// Serializes to JSON; [`to_text`](Self::to_text) and [`to_html`](Self::to_html) render it for
+/// reading.
+#[derive(Debug, Clone, Serialize)]
+pub struct CoercionTrace {
+    /// The target type, e.g. `FruitOrders`.
+    pub target: String,
+    pub allow_partials: bool,
+    /// The coerced value, if coercion succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<TraceNode>,
+    /// Why coercion failed, with the errors of every field and candidate that was tried.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// One value in a [`CoercionTrace`].
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceNode {
+    /// Path of the value, such as `<root>.fruit.0.price`.
+    pub path: String,
+    /// `string`, `int`, `list`, `map`, ..., or the name of the class or enum.
+    pub kind: String,
+    /// The value of scalars; lists, maps and classes have children instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<BamlValue>,
+    /// Penalty of the value and its children: the lowest scoring candidate wins.
+    pub score: i32,
+    /// Flags raised for this value, not including union matches and check results.
+    pub flags: Vec<FlagInfo>,
+    pub constraints: Vec<ConstraintOutcome>,
+    /// The alternatives considered for this value when coercing into a union or picking among
+    /// several readings of the output.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<Candidate>,
+    pub children: Vec<TraceNode>,
+}
+
+/// An alternative considered for a value; see [`TraceNode::candidates`].
+#[derive(Debug, Clone, Serialize)]
+pub struct Candidate {
+    pub index: usize,
+    /// Whether this candidate became the value. Its details are those of the node itself.
+    pub chosen: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<i32>,
+    /// The rejected value, if the candidate could be coerced.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<Box<TraceNode>>,
+    /// Why the candidate could not be coerced.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The outcome of a `@check` on a value.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConstraintOutcome {
+    pub label: String,
+    pub expression: String,
+    pub passed: bool,
+}
+
+impl TraceNode {
+    pub(crate) fn new(value: &BamlValueWithFlags, path: &str) -> Self {
+        let mut flags = Vec::new();
+        let mut constraints = Vec::new();
+        let mut candidates = Vec::new();
+        for flag in value.conditions().flags() {
+            match flag {
+                Flag::UnionMatch(chosen, results) | Flag::FirstMatch(chosen, results) => {
+                    candidates.extend(results.iter().enumerate().map(|(index, result)| {
+                        let chosen = index == *chosen;
+                        Candidate {
+                            index,
+                            chosen,
+                            score: result.as_ref().ok().map(BamlValueWithFlags::score),
+                            node: match result {
+                                Ok(value) if !chosen => {
+                                    Some(Box::new(TraceNode::new(value, path)))
+                                }
+                                _ => None,
+                            },
+                            error: result.as_ref().err().map(|e| e.to_string()),
+                        }
+                    }))
+                }
+                Flag::ConstraintResults(results) => {
+                    constraints.extend(results.iter().filter_map(|(constraint, passed)| {
+                        let (label, expression) = constraint.clone().as_check()?;
+                        Some(ConstraintOutcome {
+                            label,
+                            expression: expression.to_string(),
+                            passed: *passed,
+                        })
+                    }))
+                }
+                flag => flags.push(FlagInfo::new(flag)),
+            }
+        }
+
+        let child = |key: &dyn std::fmt::Display, value: &BamlValueWithFlags| {
+            TraceNode::new(value, &format!("{path}.{key}"))
+        };
+        let (kind, children) = match value {
+            BamlValueWithFlags::List(_, items) => (
+                "list".to_string(),
+                items.iter().enumerate().map(|(i, item)| child(&i, item)).collect(),
+            ),
+            BamlValueWithFlags::Map(_, entries) => (
+                "map".to_string(),
+                entries
+                    .iter()
+                    .map(|(key, (_, entry))| child(key, entry))
+                    .collect(),
+            ),
+            BamlValueWithFlags::Class(name, _, fields) => (
+                name.clone(),
+                fields.iter().map(|(key, field)| child(key, field)).collect(),
+            ),
+            BamlValueWithFlags::Enum(name, _) => (name.clone(), Vec::new()),
+            scalar => {
+                let kind = match scalar {
+                    BamlValueWithFlags::String(_) => "string",
+                    BamlValueWithFlags::Int(_) => "int",
+                    BamlValueWithFlags::Float(_) => "float",
+                    BamlValueWithFlags::Decimal(_) => "decimal",
+                    BamlValueWithFlags::Bool(_) => "bool",
+                    BamlValueWithFlags::Bytes(_) => "bytes",
+                    BamlValueWithFlags::Media(_) => "media",
+                    _ => "null",
+                };
+                (kind.to_string(), Vec::new())
+            }
+        };
+        let score = value.score();
+        let value = (!value.is_composite() || matches!(value, BamlValueWithFlags::Media(_)))
+            .then(|| BamlValue::from(value.clone()));
+
+        TraceNode {
+            path: path.to_string(),
+            kind,
+            value,
+            score,
+            flags,
+            constraints,
+            candidates,
+            children,
+        }
+    }
+
+    fn summary(&self) -> String {
+        let mut summary = format!("{} {} (score {})", self.path, self.kind, self.score);
+        if let Some(value) = &self.value {
+            let value = serde_json::to_string(value).unwrap_or_default();
+            let _ = write!(summary, " = {value}");
+        }
+        summary
+    }
+
+    fn write_text(&self, out: &mut String, depth: usize) {
+        let indent = "  ".repeat(depth);
+        let _ = writeln!(out, "{indent}{}", self.summary());
+        for flag in &self.flags {
+            let _ = writeln!(out, "{indent}  flag {}: {}", flag.kind, flag.message);
+        }
+        for check in &self.constraints {
+            let outcome = if check.passed { "passed" } else { "failed" };
+            let _ = writeln!(
+                out,
+                "{indent}  check {} {outcome}: {}",
+                check.label, check.expression
+            );
+        }
+        for candidate in &self.candidates {
+            let _ = writeln!(out, "{indent}  {}", candidate.summary());
+            if let Some(node) = &candidate.node {
+                node.write_text(out, depth + 2);
+            }
+        }
+        for child in &self.children {
+            child.write_text(out, depth + 1);
+        }
+    }
+
+    fn write_html(&self, out: &mut String) {
+        let _ = write!(out, "<details open><summary>{}</summary><ul>", escape_html(&self.summary()));
+        for flag in &self.flags {
+            let _ = write!(
+                out,
+                "<li class=\"flag\"><b>{}</b>: {}</li>",
+                escape_html(&flag.kind),
+                escape_html(&flag.message)
+            );
+        }
+        for check in &self.constraints {
+            let outcome = if check.passed { "passed" } else { "failed" };
+            let _ = write!(
+                out,
+                "<li class=\"check {outcome}\">check <b>{}</b> {outcome}: <code>{}</code></li>",
+                escape_html(&check.label),
+                escape_html(&check.expression)
+            );
+        }
+        for candidate in &self.candidates {
+            let _ = write!(out, "<li class=\"candidate\">{}", escape_html(&candidate.summary()));
+            if let Some(node) = &candidate.node {
+                node.write_html(out);
+            }
+            out.push_str("</li>");
+        }
+        for child in &self.children {
+            out.push_str("<li>");
+            child.write_html(out);
+            out.push_str("</li>");
+        }
+        out.push_str("</ul></details>");
+    }
+}
+
+impl Candidate {
+    fn summary(&self) -> String {
+        let mut summary = format!("candidate {}", self.index);
+        if self.chosen {
+            summary.push_str(" (chosen)");
+        }
+        if let Some(score) = self.score {
+            let _ = write!(summary, " score {score}");
+        }
+        if let Some(error) = &self.error {
+            let _ = write!(summary, " failed: {}", error.replace('\n', " "));
+        }
+        summary
+    }
+}
+
+impl CoercionTrace {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("traces serialize to JSON")
+    }
+
+    /// An indented outline of the trace, one value, flag, check or candidate per line.
+    pub fn to_text(&self) -> String {
+        let mut out = format!("target {} (allow_partials: {})\n", self.target, self.allow_partials);
+        if let Some(root) = &self.root {
+            root.write_text(&mut out, 0);
+        }
+        if let Some(error) = &self.error {
+            let _ = writeln!(out, "error: {error}");
+        }
+        out
+    }
+
+    /// A standalone HTML page with collapsible values.
+    pub fn to_html(&self) -> String {
+        let mut out = String::from(
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>Coercion trace</title>\
+             <style>.flag{color:#a60}.failed{color:#c00}.candidate{color:#666}</style>\
+             </head><body>",
+        );
+        let _ = write!(
+            out,
+            "<h1>{}</h1><p>allow_partials: {}</p>",
+            escape_html(&self.target),
+            self.allow_partials
+        );
+        if let Some(root) = &self.root {
+            root.write_html(&mut out);
+        }
+        if let Some(error) = &self.error {
+            let _ = write!(out, "<pre class=\"failed\">{}</pre>", escape_html(error));
+        }
+        out.push_str("</body></html>\n");
+        out
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
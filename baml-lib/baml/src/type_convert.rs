@@ -4,12 +4,27 @@ pub use internal_baml_core::{
     internal_baml_parser_database::{ParserDatabase, TypeWalker},
 };
 use internal_baml_core::ast::Identifier;
-use baml_types;
+use internal_baml_core::internal_baml_parser_database::{type_constraints, walkers::TypeAliasWalker};
+use baml_types::{self, BamlMap};
 
 
 // added by LMNR team to convert walker `FieldType`s to actual `baml_types::FieldType`s
 /// Convert ast FieldType to raw FieldType
 pub fn to_raw_field_type(ft: &ast::FieldType, db: &ParserDatabase) -> baml_types::FieldType {
+    // `@check` and `@assert` on the type run when the value is coerced.
+    let base = to_unconstrained_field_type(ft, db);
+    let constraints = type_constraints(ft, db);
+    if constraints.is_empty() {
+        base
+    } else {
+        baml_types::FieldType::Constrained {
+            base: Box::new(base),
+            constraints,
+        }
+    }
+}
+
+fn to_unconstrained_field_type(ft: &ast::FieldType, db: &ParserDatabase) -> baml_types::FieldType {
     match ft {
         ast::FieldType::Symbol(arity, identifier, _) => {
             let inner = match identifier {
@@ -22,17 +37,13 @@ pub fn to_raw_field_type(ft: &ast::FieldType, db: &ParserDatabase) -> baml_types
                     Some(TypeWalker::Enum(_)) => {
                         baml_types::FieldType::Primitive(baml_types::TypeValue::String)
                     }
-                    Some(TypeWalker::TypeAlias(_)) => {
-                        baml_types::FieldType::RecursiveTypeAlias(x.full_name.clone())
-                    }
+                    Some(TypeWalker::TypeAlias(alias)) => alias_field_type(&x.full_name, alias, db),
                 },
                 Identifier::Local(x, _) => match db.find_type(identifier) {
                     None => baml_types::FieldType::Primitive(baml_types::TypeValue::Null),
                     Some(TypeWalker::Class(_c)) => baml_types::FieldType::Class(x.clone()),
                     Some(TypeWalker::Enum(_e)) => baml_types::FieldType::Enum(x.clone()),
-                    Some(TypeWalker::TypeAlias(_t)) => {
-                        baml_types::FieldType::RecursiveTypeAlias(x.clone())
-                    }
+                    Some(TypeWalker::TypeAlias(alias)) => alias_field_type(x, alias, db),
                 },
                 //Identifier::Primitive(idx, _) => baml_types::FieldType::Primitive(idx.clone()),
                 Identifier::String(_, _) => {
@@ -114,3 +125,26 @@ pub fn to_raw_field_type(ft: &ast::FieldType, db: &ParserDatabase) -> baml_types
         }
     }
 }
+
+/// A recursive alias is referenced by name, see [`recursive_aliases`]; any other alias is
+/// replaced by the type it stands for.
+pub fn alias_field_type(
+    name: &str,
+    alias: TypeAliasWalker<'_>,
+    db: &ParserDatabase,
+) -> baml_types::FieldType {
+    if alias.is_recursive() {
+        baml_types::FieldType::RecursiveTypeAlias(name.to_string())
+    } else {
+        to_raw_field_type(alias.resolved(), db)
+    }
+}
+
+/// The recursive type aliases with the types they stand for, which the output format needs to
+/// render and parse them.
+pub fn recursive_aliases(db: &ParserDatabase) -> BamlMap<String, baml_types::FieldType> {
+    db.walk_recursive_alias_cycles()
+        .flatten()
+        .map(|alias| (alias.name().to_string(), to_raw_field_type(alias.target(), db)))
+        .collect()
+}
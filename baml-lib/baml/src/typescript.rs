@@ -0,0 +1,191 @@
+//! TypeScript declarations for the classes and enums of a schema.
+
+use std::collections::{BTreeSet, VecDeque};
+
+use baml_types::{FieldType, LiteralValue, TypeValue};
+use internal_baml_jinja::types::OutputFormatContent;
+
+use crate::BamlContext;
+
+impl BamlContext {
+    /// TypeScript source declaring an `enum` for every enum and an `interface` for every class
+    /// of the schema, typing the JSON returned by [`validate_result`](Self::validate_result).
+    ///
+    /// Interface properties and enum values use the rendered (`@alias`) names, since those are
+    /// the JSON keys and values, while enum members keep their schema names. Optional fields
+    /// are optional properties that may also be `null`, `@description`s become doc comments
+    /// and recursive type aliases become `type`s.
+    pub fn to_typescript(&self) -> anyhow::Result<String> {
+        emit(self)
+    }
+}
+
+fn emit(context: &BamlContext) -> anyhow::Result<String> {
+    let format = &context.format;
+    let mut emitter = Emitter {
+        format,
+        aliases: VecDeque::new(),
+        seen_aliases: BTreeSet::new(),
+    };
+
+    let mut declarations = vec![];
+    for enm in format.enums.values() {
+        let mut declaration = format!("export enum {} {{\n", enm.name.real_name());
+        for (value, description, _) in &enm.values {
+            declaration.push_str(&doc_comment(description.as_deref(), "  "));
+            declaration.push_str(&format!(
+                "  {} = {},\n",
+                context.schema_name(enm.name.real_name(), value.rendered_name()),
+                string_literal(value.rendered_name())
+            ));
+        }
+        declaration.push('}');
+        declarations.push(declaration);
+    }
+
+    for class in format.classes.values() {
+        let mut declaration = format!("export interface {} {{\n", class.name.real_name());
+        for (name, field_type, description, _) in &class.fields {
+            let annotation = emitter.annotation(field_type).map_err(|e| {
+                let field = name.rendered_name();
+                e.context(format!("In field `{}.{field}`", class.name.real_name()))
+            })?;
+            declaration.push_str(&doc_comment(description.as_deref(), "  "));
+            declaration.push_str(&format!(
+                "  {}{}: {annotation};\n",
+                property_name(name.rendered_name()),
+                if field_type.is_optional() { "?" } else { "" }
+            ));
+        }
+        declaration.push('}');
+        declarations.push(declaration);
+    }
+
+    while let Some(name) = emitter.aliases.pop_front() {
+        let target = format.find_recursive_alias_target(&name)?;
+        let annotation = emitter.annotation(target)?;
+        declarations.push(format!("export type {name} = {annotation};"));
+    }
+
+    let mut source = declarations.join("\n\n");
+    source.push('\n');
+    Ok(source)
+}
+
+struct Emitter<'f> {
+    format: &'f OutputFormatContent,
+    /// Recursive aliases still to declare.
+    aliases: VecDeque<String>,
+    seen_aliases: BTreeSet<String>,
+}
+
+impl Emitter<'_> {
+    /// The TypeScript type for `field_type`.
+    fn annotation(&mut self, field_type: &FieldType) -> anyhow::Result<String> {
+        Ok(match field_type {
+            FieldType::Primitive(primitive) => match primitive {
+                // Bytes are base64 encoded in JSON.
+                TypeValue::String | TypeValue::Bytes => "string".to_string(),
+                TypeValue::Int | TypeValue::Float => "number".to_string(),
+                TypeValue::Bool => "boolean".to_string(),
+                TypeValue::Null => "null".to_string(),
+                TypeValue::Media(media) => {
+                    anyhow::bail!("Type `{media}` is not supported in outputs")
+                }
+            },
+            FieldType::Literal(literal) => match literal {
+                LiteralValue::String(s) => string_literal(s),
+                LiteralValue::Int(i) => i.to_string(),
+                LiteralValue::Bool(b) => b.to_string(),
+            },
+            // Open enums also accept values other than their variants.
+            FieldType::Enum(name) if self.format.find_enum(name)?.open => {
+                format!("{name} | string")
+            }
+            FieldType::Enum(name) | FieldType::Class(name) => name.clone(),
+            FieldType::RecursiveTypeAlias(name) => {
+                if self.seen_aliases.insert(name.clone()) {
+                    self.aliases.push_back(name.clone());
+                }
+                name.clone()
+            }
+            FieldType::List(item) => {
+                let item = self.annotation(item)?;
+                if item.contains(' ') {
+                    format!("({item})[]")
+                } else {
+                    format!("{item}[]")
+                }
+            }
+            FieldType::Map(key, value) => {
+                format!("Record<{}, {}>", self.annotation(key)?, self.annotation(value)?)
+            }
+            FieldType::Tuple(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| self.annotation(item))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                format!("[{}]", items.join(", "))
+            }
+            FieldType::Union(items) => {
+                let mut annotations: Vec<String> = vec![];
+                for item in items {
+                    let annotation = self.annotation(item)?;
+                    if !annotations.contains(&annotation) {
+                        annotations.push(annotation);
+                    }
+                }
+                annotations.join(" | ")
+            }
+            FieldType::Optional(inner) => {
+                let inner = self.annotation(inner)?;
+                if inner == "null" || inner.ends_with(" | null") {
+                    inner
+                } else {
+                    format!("{inner} | null")
+                }
+            }
+            FieldType::Constrained { base, .. } => self.annotation(base)?,
+        })
+    }
+}
+
+/// A `/** */` comment for `description`, indented by `indent`.
+fn doc_comment(description: Option<&str>, indent: &str) -> String {
+    let Some(description) = description else {
+        return String::new();
+    };
+    let lines = description.lines().collect::<Vec<_>>();
+    match lines.as_slice() {
+        [] => String::new(),
+        [line] => format!("{indent}/** {} */\n", line.replace("*/", "*\\/")),
+        lines => {
+            let mut comment = format!("{indent}/**\n");
+            for line in lines {
+                comment.push_str(format!("{indent} * {}", line.replace("*/", "*\\/")).trim_end());
+                comment.push('\n');
+            }
+            comment.push_str(&format!("{indent} */\n"));
+            comment
+        }
+    }
+}
+
+/// `name` as an interface property, quoted unless it is a valid identifier.
+fn property_name(name: &str) -> String {
+    let identifier = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if identifier {
+        name.to_string()
+    } else {
+        string_literal(name)
+    }
+}
+
+/// A TypeScript string literal. JSON strings are valid in TypeScript.
+fn string_literal(s: &str) -> String {
+    serde_json::Value::String(s.to_string()).to_string()
+}
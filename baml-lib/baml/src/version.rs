@@ -0,0 +1,77 @@
+//! Schema versions for `@since` / `@until` attributes.
+
+use std::{fmt, str::FromStr};
+
+use baml_types::{EvaluationContext, FieldType};
+use internal_baml_core::internal_baml_parser_database::Attributes;
+
+/// A schema version such as `2` or `1.3`, compared component by component (`1` == `1.0`).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SchemaVersion(Vec<u64>);
+
+impl FromStr for SchemaVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(version: &str) -> anyhow::Result<Self> {
+        let mut parts = version
+            .split('.')
+            .map(|part| {
+                part.parse::<u64>()
+                    .map_err(|_| anyhow::anyhow!("Invalid schema version `{version}`"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        while parts.len() > 1 && parts.last() == Some(&0) {
+            parts.pop();
+        }
+        Ok(Self(parts))
+    }
+}
+
+impl fmt::Display for SchemaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts = self.0.iter().map(u64::to_string).collect::<Vec<_>>();
+        write!(f, "{}", parts.join("."))
+    }
+}
+
+/// Whether a node with `attributes` exists in `version`: `@since` is inclusive, `@until`
+/// exclusive. Every node is available when no version is selected.
+pub(crate) fn is_available(
+    version: Option<&SchemaVersion>,
+    attributes: Option<&Attributes>,
+) -> bool {
+    let (Some(version), Some(attributes)) = (version, attributes) else {
+        return true;
+    };
+    let bound = |value: &Option<_>| -> Option<SchemaVersion> {
+        let value: &baml_types::UnresolvedValue<_> = value.as_ref()?;
+        value
+            .as_str()?
+            .resolve(&EvaluationContext::default())
+            .ok()?
+            .parse()
+            .ok()
+    };
+    bound(attributes.since()).is_none_or(|since| *version >= since)
+        && bound(attributes.until()).is_none_or(|until| *version < until)
+}
+
+/// Names of the classes and enums `field_type` refers to.
+pub(crate) fn referenced_types(field_type: &FieldType) -> Vec<&str> {
+    match field_type {
+        FieldType::Class(name) | FieldType::Enum(name) => vec![name.as_str()],
+        FieldType::List(inner) | FieldType::Optional(inner) => referenced_types(inner),
+        FieldType::Constrained { base, .. } => referenced_types(base),
+        FieldType::Map(key, value) => {
+            let mut names = referenced_types(key);
+            names.extend(referenced_types(value));
+            names
+        }
+        FieldType::Union(items) | FieldType::Tuple(items) => {
+            items.iter().flat_map(referenced_types).collect()
+        }
+        FieldType::Primitive(_) | FieldType::Literal(_) | FieldType::RecursiveTypeAlias(_) => {
+            vec![]
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! JavaScript bindings through `wasm-bindgen`, for browsers and Node: the counterpart of the
+//! Python module for rendering prompts and validating results.
+
+use wasm_bindgen::prelude::*;
+
+use crate::BamlContext;
+
+fn to_js_error(err: anyhow::Error) -> JsError {
+    JsError::new(&format!("{err:#}"))
+}
+
+/// A schema and its target, as `BamlContext` in JavaScript.
+#[wasm_bindgen(js_name = BamlContext)]
+pub struct WasmBamlContext {
+    context: BamlContext,
+}
+
+#[wasm_bindgen(js_class = BamlContext)]
+impl WasmBamlContext {
+    /// `new BamlContext(schema, targetName)`. Throws with the schema diagnostics if the schema
+    /// is invalid.
+    #[wasm_bindgen(constructor)]
+    pub fn new(schema: String, target_name: Option<String>) -> Result<WasmBamlContext, JsError> {
+        let context = BamlContext::try_from_schema(&schema, target_name).map_err(to_js_error)?;
+        Ok(Self { context })
+    }
+
+    /// `renderPrompt(prefix?, alwaysHoistEnums?)`: the output format to add to the prompt.
+    #[wasm_bindgen(js_name = renderPrompt)]
+    pub fn render_prompt(
+        &self,
+        prefix: Option<String>,
+        always_hoist_enums: Option<bool>,
+    ) -> Result<String, JsError> {
+        self.context
+            .render_prompt(prefix, always_hoist_enums)
+            .map_err(to_js_error)
+    }
+
+    /// `validateResult(result, allowPartials?)`: the value parsed from the model's answer, as
+    /// JSON. Throws if it does not match the target.
+    #[wasm_bindgen(js_name = validateResult)]
+    pub fn validate_result(
+        &self,
+        result: &str,
+        allow_partials: Option<bool>,
+    ) -> Result<String, JsError> {
+        self.context
+            .validate_result(result, allow_partials.unwrap_or(false))
+            .map_err(to_js_error)
+    }
+}
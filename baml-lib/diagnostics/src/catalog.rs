@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+/// A message that can be shown in another language: the identifier of its template, such as
+/// `type_not_found`, and the values filled into the template's `{placeholders}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub id: &'static str,
+    pub args: Vec<(&'static str, String)>,
+}
+
+impl Message {
+    pub fn new(id: &'static str) -> Self {
+        Self { id, args: vec![] }
+    }
+
+    pub fn arg(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.args.push((name, value.to_string()));
+        self
+    }
+
+    /// `template` with each `{name}` replaced by the value of the argument `name`.
+    /// Placeholders without an argument are kept as is.
+    pub fn render(&self, template: &str) -> String {
+        let mut rendered = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            rendered.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let value = rest.find('}').and_then(|end| {
+                let name = &rest[1..end];
+                let (_, value) = self.args.iter().find(|(arg, _)| *arg == name)?;
+                Some((end, value))
+            });
+            match value {
+                Some((end, value)) => {
+                    rendered.push_str(value);
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    rendered.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        rendered.push_str(rest);
+        rendered
+    }
+}
+
+/// The translations of error messages for one locale.
+///
+/// Errors with a [`Message`] are shown with the catalog's template for its id; errors without
+/// one, or whose id the catalog has no template for, keep their English text.
+pub trait MessageCatalog: Send + Sync + std::fmt::Debug {
+    /// The locale of the templates, such as `fr` or `pt-BR`.
+    fn locale(&self) -> &str;
+
+    /// The template for the message `id`, with `{name}` placeholders for its arguments.
+    fn template(&self, id: &str) -> Option<&str>;
+
+    /// `message` in this locale, or `None` to keep the English text.
+    fn localize(&self, message: &Message) -> Option<String> {
+        self.template(message.id).map(|template| message.render(template))
+    }
+}
+
+/// A [`MessageCatalog`] from a table of templates, for instance loaded from a translation file.
+#[derive(Debug, Clone, Default)]
+pub struct TemplateCatalog {
+    locale: String,
+    templates: HashMap<String, String>,
+}
+
+impl TemplateCatalog {
+    pub fn new(locale: impl Into<String>) -> Self {
+        Self {
+            locale: locale.into(),
+            templates: HashMap::new(),
+        }
+    }
+
+    /// Use `template` for the messages `id`.
+    pub fn with_template(mut self, id: impl Into<String>, template: impl Into<String>) -> Self {
+        self.templates.insert(id.into(), template.into());
+        self
+    }
+}
+
+impl MessageCatalog for TemplateCatalog {
+    fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    fn template(&self, id: &str) -> Option<&str> {
+        self.templates.get(id).map(String::as_str)
+    }
+}
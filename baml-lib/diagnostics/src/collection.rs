@@ -1,7 +1,7 @@
 use std::{collections::HashMap, path::PathBuf};
 
 use super::DatamodelError;
-use crate::{warning::DatamodelWarning, SourceFile, Span};
+use crate::{warning::DatamodelWarning, MessageCatalog, SourceFile, Span};
 
 /// Represents a list of validation or parser errors and warnings.
 ///
@@ -102,6 +102,19 @@ impl Diagnostics {
         String::from_utf8_lossy(&message).into_owned()
     }
 
+    /// Like [`to_pretty_string`](Self::to_pretty_string), with the messages in the locale of
+    /// `catalog`.
+    pub fn to_pretty_string_localized(&self, catalog: &dyn MessageCatalog) -> String {
+        let mut message: Vec<u8> = Vec::new();
+
+        for err in self.errors() {
+            err.pretty_print_localized(&mut message, catalog)
+                .expect("printing datamodel error");
+        }
+
+        String::from_utf8_lossy(&message).into_owned()
+    }
+
     pub fn warnings_to_pretty_string(&self) -> String {
         let mut message: Vec<u8> = Vec::new();
 
@@ -1,6 +1,7 @@
 use colored::{ColoredString, Colorize};
 
 use crate::{
+    catalog::{Message, MessageCatalog},
     pretty_print::{pretty_print, DiagnosticColorer},
     Span,
 };
@@ -11,6 +12,9 @@ use std::{borrow::Cow, ops::Index};
 pub struct DatamodelError {
     span: Span,
     message: Cow<'static, str>,
+    /// What [`MessageCatalog`]s translate, for errors with a template. Boxed to keep the
+    /// error small.
+    localizable: Option<Box<Message>>,
 }
 
 /// Sorts a collection of strings based on their similarity to a given name.
@@ -66,7 +70,20 @@ where
 impl DatamodelError {
     pub(crate) fn new(message: impl Into<Cow<'static, str>>, span: Span) -> Self {
         let message = message.into();
-        DatamodelError { message, span }
+        DatamodelError {
+            message,
+            span,
+            localizable: None,
+        }
+    }
+
+    /// An error showing `message` with its English `template`.
+    fn new_localizable(message: Message, template: &str, span: Span) -> Self {
+        DatamodelError {
+            message: message.render(template).into(),
+            span,
+            localizable: Some(Box::new(message)),
+        }
     }
 
     pub fn new_anyhow_error(error: anyhow::Error, span: Span) -> Self {
@@ -82,14 +99,21 @@ impl DatamodelError {
         raw_value: &str,
         span: Span,
     ) -> DatamodelError {
-        Self::new(
-            format!("\"{raw_value}\" is not a valid value for {literal_type}."),
+        Self::new_localizable(
+            Message::new("literal_parser_error")
+                .arg("value", raw_value)
+                .arg("type", literal_type),
+            "\"{value}\" is not a valid value for {type}.",
             span,
         )
     }
 
     pub fn new_argument_not_found_error(argument_name: &str, span: Span) -> DatamodelError {
-        Self::new(format!("Argument \"{argument_name}\" is missing."), span)
+        Self::new_localizable(
+            Message::new("argument_not_found").arg("argument", argument_name),
+            "Argument \"{argument}\" is missing.",
+            span,
+        )
     }
 
     pub fn new_argument_count_mismatch_error(
@@ -98,8 +122,14 @@ impl DatamodelError {
         given_count: usize,
         span: Span,
     ) -> DatamodelError {
-        let msg = format!("Function \"{function_name}\" takes {required_count} arguments, but received {given_count}.");
-        Self::new(msg, span)
+        Self::new_localizable(
+            Message::new("argument_count_mismatch")
+                .arg("function", function_name)
+                .arg("required", required_count)
+                .arg("given", given_count),
+            "Function \"{function}\" takes {required} arguments, but received {given}.",
+            span,
+        )
     }
 
     pub fn new_client_error(message: impl Into<Cow<'static, str>>, span: Span) -> DatamodelError {
@@ -111,8 +141,11 @@ impl DatamodelError {
         attribute_name: &str,
         span: Span,
     ) -> DatamodelError {
-        Self::new(
-            format!("Argument \"{argument_name}\" is missing in attribute \"@{attribute_name}\"."),
+        Self::new_localizable(
+            Message::new("attribute_argument_not_found")
+                .arg("argument", argument_name)
+                .arg("attribute", attribute_name),
+            "Argument \"{argument}\" is missing in attribute \"@{attribute}\".",
             span,
         )
     }
@@ -135,15 +168,21 @@ impl DatamodelError {
         attribute_name: &str,
         span: Span,
     ) -> DatamodelError {
-        Self::new(
-            format!("Error parsing attribute \"{attribute_name}\": {message}"),
+        Self::new_localizable(
+            Message::new("attribute_validation_error")
+                .arg("attribute", attribute_name)
+                .arg("message", message),
+            "Error parsing attribute \"{attribute}\": {message}",
             span,
         )
     }
 
     pub fn new_duplicate_attribute_error(attribute_name: &str, span: Span) -> DatamodelError {
-        let msg = format!("Attribute \"@{attribute_name}\" can only be defined once.");
-        Self::new(msg, span)
+        Self::new_localizable(
+            Message::new("duplicate_attribute").arg("attribute", attribute_name),
+            "Attribute \"@{attribute}\" can only be defined once.",
+            span,
+        )
     }
 
     pub fn new_incompatible_native_type(
@@ -185,8 +224,11 @@ impl DatamodelError {
     }
 
     pub fn new_reserved_scalar_type_error(type_name: &str, span: Span) -> DatamodelError {
-        let msg = format!("\"{type_name}\" is a reserved scalar type name and cannot be used.");
-        Self::new(msg, span)
+        Self::new_localizable(
+            Message::new("reserved_scalar_type").arg("type", type_name),
+            "\"{type}\" is a reserved scalar type name and cannot be used.",
+            span,
+        )
     }
 
     pub fn new_duplicate_enum_database_name_error(span: Span) -> DatamodelError {
@@ -228,10 +270,14 @@ impl DatamodelError {
         existing_top_type: &str,
         span: Span,
     ) -> DatamodelError {
-        let msg = format!(
-            "The {top_type} \"{name}\" cannot be defined because a {existing_top_type} with that name already exists.",
-        );
-        Self::new(msg, span)
+        Self::new_localizable(
+            Message::new("duplicate_top")
+                .arg("kind", top_type)
+                .arg("name", name)
+                .arg("existing_kind", existing_top_type),
+            "The {kind} \"{name}\" cannot be defined because a {existing_kind} with that name already exists.",
+            span,
+        )
     }
 
     pub fn new_duplicate_config_key_error(
@@ -244,8 +290,9 @@ impl DatamodelError {
     }
 
     pub fn new_duplicate_argument_error(arg_name: &str, span: Span) -> DatamodelError {
-        Self::new(
-            format!("Argument \"{arg_name}\" is already specified."),
+        Self::new_localizable(
+            Message::new("duplicate_argument").arg("argument", arg_name),
+            "Argument \"{argument}\" is already specified.",
             span,
         )
     }
@@ -276,8 +323,13 @@ impl DatamodelError {
         value_name: &str,
         span: Span,
     ) -> DatamodelError {
-        let msg = format!("Value \"{value_name}\" is already defined on enum \"{enum_name}\".",);
-        Self::new(msg, span)
+        Self::new_localizable(
+            Message::new("duplicate_enum_value")
+                .arg("value", value_name)
+                .arg("enum", enum_name),
+            "Value \"{value}\" is already defined on enum \"{enum}\".",
+            span,
+        )
     }
 
     pub fn new_composite_type_duplicate_field_error(
@@ -298,9 +350,14 @@ impl DatamodelError {
         container: &'static str,
         span: Span,
     ) -> DatamodelError {
-        let msg =
-            format!("Field \"{field_name}\" is already defined on {container} \"{model_name}\".",);
-        Self::new(msg, span)
+        Self::new_localizable(
+            Message::new("duplicate_field")
+                .arg("field", field_name)
+                .arg("container", container)
+                .arg("name", model_name),
+            "Field \"{field}\" is already defined on {container} \"{name}\".",
+            span,
+        )
     }
 
     pub fn new_scalar_list_fields_are_not_supported(
@@ -420,6 +477,7 @@ impl DatamodelError {
                 "int".to_string(),
                 "float".to_string(),
                 "bool".to_string(),
+                "bytes".to_string(),
                 "string".to_string(),
                 "image".to_string(),
                 "audio".to_string(),
@@ -429,23 +487,33 @@ impl DatamodelError {
         }
 
         let close_names = sort_by_match(name, &names, Some(3));
-        let suggestions = if names.is_empty() {
-            "".to_string()
-        } else if close_names.is_empty() {
+        let message = Message::new("not_found")
+            .arg("kind", type_name)
+            .arg("name", name);
+        if close_names.is_empty() {
             // If no names are close enough, suggest nothing or provide a generic message
-            "".to_string()
+            Self::new_localizable(message, "{kind} {name} not found.", span)
         } else if close_names.len() == 1 {
             // If there's only one close name, suggest it
-            format!(" Did you mean `{}`?", close_names[0])
+            Self::new_localizable(
+                Message {
+                    id: "not_found_did_you_mean",
+                    ..message.arg("suggestion", close_names[0])
+                },
+                "{kind} {name} not found. Did you mean `{suggestion}`?",
+                span,
+            )
         } else {
             // If there are multiple close names, suggest them all
-            format!(
-                " Did you mean one of these: `{}`?",
-                close_names.join("`, `")
+            Self::new_localizable(
+                Message {
+                    id: "not_found_did_you_mean_one_of",
+                    ..message.arg("suggestions", close_names.join("`, `"))
+                },
+                "{kind} {name} not found. Did you mean one of these: `{suggestions}`?",
+                span,
             )
-        };
-
-        Self::new(format!("{type_name} {name} not found.{suggestions}"), span)
+        }
     }
 
     pub fn type_not_used_in_prompt_error(
@@ -535,28 +603,39 @@ impl DatamodelError {
     ) -> DatamodelError {
         let close_names = sort_by_match(type_name, &names, Some(10));
 
-        let msg = if close_names.is_empty() {
+        let message = Message::new("type_not_found").arg("type", type_name);
+        if close_names.is_empty() {
             // If no names are close enough, suggest nothing or provide a generic message
-            format!("Type `{type_name}` does not exist.")
+            Self::new_localizable(message, "Type `{type}` does not exist.", span)
         } else if close_names.len() == 1 {
             // If there's only one close name, suggest it
-            format!(
-                "Type `{}` does not exist. Did you mean `{}`?",
-                type_name, close_names[0]
+            Self::new_localizable(
+                Message {
+                    id: "type_not_found_did_you_mean",
+                    ..message.arg("suggestion", close_names[0])
+                },
+                "Type `{type}` does not exist. Did you mean `{suggestion}`?",
+                span,
             )
         } else {
             // If there are multiple close names, suggest them all
-            let suggestions = close_names.join("`, `");
-            format!(
-                "Type `{type_name}` does not exist. Did you mean one of these: `{suggestions}`?"
+            Self::new_localizable(
+                Message {
+                    id: "type_not_found_did_you_mean_one_of",
+                    ..message.arg("suggestions", close_names.join("`, `"))
+                },
+                "Type `{type}` does not exist. Did you mean one of these: `{suggestions}`?",
+                span,
             )
-        };
-
-        Self::new(msg, span)
+        }
     }
 
     pub fn new_attribute_not_known_error(attribute_name: &str, span: Span) -> DatamodelError {
-        Self::new(format!("Attribute not known: \"@{attribute_name}\"."), span)
+        Self::new_localizable(
+            Message::new("attribute_not_known").arg("attribute", attribute_name),
+            "Attribute not known: \"@{attribute}\".",
+            span,
+        )
     }
 
     pub fn new_property_not_known_error<I, T>(
@@ -587,12 +666,21 @@ impl DatamodelError {
     }
 
     pub fn new_argument_not_known_error(property_name: &str, span: Span) -> DatamodelError {
-        Self::new(format!("Argument not known: \"{property_name}\"."), span)
+        Self::new_localizable(
+            Message::new("argument_not_known").arg("argument", property_name),
+            "Argument not known: \"{argument}\".",
+            span,
+        )
     }
 
     pub fn new_value_parser_error(expected_type: &str, raw: &str, span: Span) -> DatamodelError {
-        let msg = format!("Expected {expected_type}, but found {raw}.");
-        Self::new(msg, span)
+        Self::new_localizable(
+            Message::new("value_parser_error")
+                .arg("expected", expected_type)
+                .arg("found", raw),
+            "Expected {expected}, but found {found}.",
+            span,
+        )
     }
 
     pub fn new_type_mismatch_error(
@@ -601,10 +689,14 @@ impl DatamodelError {
         raw: &str,
         span: Span,
     ) -> DatamodelError {
-        let msg = format!(
-            "Expected a {expected_type} value, but received {received_type} value `{raw}`."
-        );
-        Self::new(msg, span)
+        Self::new_localizable(
+            Message::new("type_mismatch")
+                .arg("expected", expected_type)
+                .arg("received", received_type)
+                .arg("value", raw),
+            "Expected a {expected} value, but received {received} value `{value}`.",
+            span,
+        )
     }
 
     pub fn new_missing_required_property_error(
@@ -612,9 +704,13 @@ impl DatamodelError {
         object_name: &str,
         span: Span,
     ) -> DatamodelError {
-        let msg =
-            format!("The required property \"{property_name}\" on \"{object_name}\" is missing.",);
-        Self::new(msg, span)
+        Self::new_localizable(
+            Message::new("missing_required_property")
+                .arg("property", property_name)
+                .arg("object", object_name),
+            "The required property \"{property}\" on \"{object}\" is missing.",
+            span,
+        )
     }
 
     pub fn new_config_property_missing_value_error(
@@ -644,6 +740,19 @@ impl DatamodelError {
         &self.message
     }
 
+    /// The template id and arguments of the message, if it can be translated.
+    pub fn localizable(&self) -> Option<&Message> {
+        self.localizable.as_deref()
+    }
+
+    /// The message in the locale of `catalog`, in English if it has no translation.
+    pub fn localized_message(&self, catalog: &dyn MessageCatalog) -> Cow<'_, str> {
+        match self.localizable().and_then(|m| catalog.localize(m)) {
+            Some(message) => Cow::Owned(message),
+            None => Cow::Borrowed(self.message.as_ref()),
+        }
+    }
+
     pub fn pretty_print(&self, f: &mut dyn std::io::Write) -> std::io::Result<()> {
         pretty_print(
             f,
@@ -652,6 +761,20 @@ impl DatamodelError {
             &DatamodelErrorColorer {},
         )
     }
+
+    /// Like [`pretty_print`](Self::pretty_print), with the message in the locale of `catalog`.
+    pub fn pretty_print_localized(
+        &self,
+        f: &mut dyn std::io::Write,
+        catalog: &dyn MessageCatalog,
+    ) -> std::io::Result<()> {
+        pretty_print(
+            f,
+            self.span(),
+            &self.localized_message(catalog),
+            &DatamodelErrorColorer {},
+        )
+    }
 }
 
 struct DatamodelErrorColorer {}
@@ -1,3 +1,4 @@
+mod catalog;
 mod collection;
 mod error;
 mod pretty_print;
@@ -5,6 +6,7 @@ mod source_file;
 mod span;
 mod warning;
 
+pub use catalog::{Message, MessageCatalog, TemplateCatalog};
 pub use collection::Diagnostics;
 pub use error::DatamodelError;
 pub use source_file::SourceFile;
@@ -20,18 +20,16 @@ pub(crate) fn pretty_print(
 
     let start_line_number = text[..span.start].matches('\n').count();
     let end_line_number = text[..span.end].matches('\n').count();
-    let file_lines = text.split('\n').collect::<Vec<&str>>();
-
-    let chars_in_line_before: usize = file_lines[..start_line_number]
-        .iter()
-        .map(|l| l.len())
-        .sum();
-    // Don't forget to count the all the line breaks.
-    let chars_in_line_before = chars_in_line_before + start_line_number;
+    // Lines without their `\r` (from `\r\n` line endings), which would garble the output.
+    let file_lines = text
+        .split('\n')
+        .map(|l| l.strip_suffix('\r').unwrap_or(l))
+        .collect::<Vec<&str>>();
 
     let line = &file_lines[start_line_number];
 
-    let start_in_line = span.start - chars_in_line_before;
+    let line_start = text[..span.start].rfind('\n').map_or(0, |i| i + 1);
+    let start_in_line = std::cmp::min(span.start - line_start, line.len());
     let end_in_line = std::cmp::min(start_in_line + (span.end - span.start), line.len());
 
     let prefix = &line[..start_in_line];
@@ -66,7 +64,7 @@ pub(crate) fn pretty_print(
         suffix
     )?;
     if offending.len() == 0 {
-        let spacing = " ".repeat(start_in_line);
+        let spacing = " ".repeat(prefix.chars().count());
         writeln!(
             f,
             "{}{}{}",
@@ -9,7 +9,7 @@ pub struct SourceFile {
 
 impl PartialEq for SourceFile {
     fn eq(&self, other: &Self) -> bool {
-        self.path == other.path
+        self.path == other.path && self.is_anonymous() == other.is_anonymous()
     }
 }
 
@@ -30,13 +30,24 @@ impl SourceFile {
         }
     }
 
+    /// A source held in memory rather than read from a file. Diagnostics show `name` where
+    /// they would show the file path, and the source is not required to have a `.baml`
+    /// extension.
+    pub fn anonymous(name: impl Into<String>, contents: impl Into<Arc<str>>) -> Self {
+        Self {
+            path: PathBuf::from(name.into()),
+            contents: Contents::Anonymous(contents.into()),
+        }
+    }
+
     pub fn as_str(&self) -> &str {
         match self.contents {
             Contents::Static(s) => s,
-            Contents::Allocated(ref s) => s,
+            Contents::Allocated(ref s) | Contents::Anonymous(ref s) => s,
         }
     }
 
+    /// The file path, or the label of an anonymous source.
     pub fn path(&self) -> String {
         self.path.to_string_lossy().to_string()
     }
@@ -44,11 +55,28 @@ impl SourceFile {
     pub fn path_buf(&self) -> &PathBuf {
         &self.path
     }
+
+    pub fn is_anonymous(&self) -> bool {
+        matches!(self.contents, Contents::Anonymous(_))
+    }
+
+    /// The line ending to use when inserting lines: `\r\n` if the file has any, else `\n`.
+    pub fn newline(&self) -> &'static str {
+        if self.as_str().contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        }
+    }
 }
 
 impl fmt::Debug for SourceFile {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "SourceFile {{ path: {:?}, contents: ... }}", self.path)?;
+        if self.is_anonymous() {
+            write!(f, "SourceFile {{ name: {:?}, contents: ... }}", self.path)?;
+        } else {
+            write!(f, "SourceFile {{ path: {:?}, contents: ... }}", self.path)?;
+        }
 
         Ok(())
     }
@@ -88,4 +116,7 @@ impl From<(PathBuf, String)> for SourceFile {
 enum Contents {
     Static(&'static str),
     Allocated(Arc<str>),
+    /// The source was not read from a file and `path` is only a label, see
+    /// [`SourceFile::anonymous`].
+    Anonymous(Arc<str>),
 }
@@ -33,6 +33,10 @@ impl Span {
         self.file == other.file && (self.contains(other.start) || self.contains(other.end))
     }
 
+    /// Zero-based line and column (in characters) of the start and end of the span.
+    ///
+    /// `\r\n` ends a line like `\n`, and a byte order mark at the start of the file is not
+    /// counted as a column.
     pub fn line_and_column(&self) -> ((usize, usize), (usize, usize)) {
         let contents = self.file.as_str();
         let mut line = 0;
@@ -41,7 +45,7 @@ impl Span {
         let mut start = None;
         let mut end = None;
 
-        for (idx, c) in contents.chars().enumerate() {
+        for (idx, c) in contents.char_indices() {
             if idx == self.start {
                 start = Some((line, column));
             }
@@ -50,13 +54,19 @@ impl Span {
                 break;
             }
 
-            if c == '\n' {
-                line += 1;
-                column = 0;
-            } else {
-                column += 1;
+            match c {
+                '\n' => {
+                    line += 1;
+                    column = 0;
+                }
+                '\r' if contents[idx + 1..].starts_with('\n') => {}
+                '\u{feff}' if idx == 0 => {}
+                _ => column += 1,
             }
         }
+        if self.start == contents.len() {
+            start = Some((line, column));
+        }
 
         match (start, end) {
             (Some(start), Some(end)) => (start, end),
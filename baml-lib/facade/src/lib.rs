@@ -0,0 +1,155 @@
+#![doc = include_str!("../README.md")]
+#![deny(rust_2018_idioms, unsafe_code, missing_docs)]
+
+pub use baml_lib::internal_baml_diagnostics::{
+    DatamodelError as DiagnosticError, DatamodelWarning as DiagnosticWarning, Diagnostics,
+    SourceFile, Span,
+};
+
+pub use baml_types::{BamlMap, BamlValue, FieldType, LiteralValue, TypeValue};
+
+pub use internal_baml_jinja::types::{MapStyle, OutputFormatContent, RenderOptions, RenderStyle};
+
+/// The error type returned by the fallible [`BamlContext`] APIs.
+pub type Error = anyhow::Error;
+
+/// A `Result` alias using [`Error`].
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Validates `schema` and returns its errors and warnings.
+pub fn validate(schema: &str) -> Diagnostics {
+    baml_lib::validate(schema).diagnostics
+}
+
+/// A schema and its target type: renders the prompt for the target and validates LLM output
+/// against it.
+pub struct BamlContext(baml_lib::BamlContext);
+
+impl BamlContext {
+    /// Loads `schema` with `target_name` as the target, or the schema's only class or enum.
+    pub fn try_from_schema(schema: &str, target_name: Option<String>) -> Result<Self> {
+        baml_lib::BamlContext::try_from_schema(schema, target_name).map(Self)
+    }
+
+    /// Renders classes in `style`.
+    pub fn with_render_style(self, style: RenderStyle) -> Self {
+        Self(self.0.with_render_style(style))
+    }
+
+    /// The type LLM output is validated against.
+    pub fn target(&self) -> &FieldType {
+        &self.0.target
+    }
+
+    /// The output format of the target, to render with other [`RenderOptions`].
+    pub fn output_format(&self) -> &OutputFormatContent {
+        &self.0.format
+    }
+
+    /// The prompt describing the target, after `prefix` instead of the default text such as
+    /// `Answer in JSON using this schema:`.
+    pub fn render_prompt(
+        &self,
+        prefix: Option<String>,
+        always_hoist_enums: Option<bool>,
+    ) -> Result<String> {
+        self.0.render_prompt(prefix, always_hoist_enums)
+    }
+
+    /// Parses `result` into the target type and returns it as JSON. With `allow_partials`,
+    /// missing fields are accepted, as in a response still being streamed.
+    pub fn validate_result(&self, result: &str, allow_partials: bool) -> Result<String> {
+        self.0.validate_result(result, allow_partials)
+    }
+
+    /// Like [`validate_result`](Self::validate_result), returning the value itself.
+    pub fn parse_result(&self, result: &str, allow_partials: bool) -> Result<BamlValue> {
+        self.0.parse_result(result, allow_partials)
+    }
+
+    /// A stable hash of what the rendered prompt means, to key prompt caches.
+    pub fn fingerprint(&self) -> String {
+        self.0.fingerprint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn facade_round_trip() {
+        let schema = r#"
+            enum Color {
+              Red
+              Green
+            }
+
+            class Paint {
+              color Color
+              liters float
+            }
+        "#
+        .to_string();
+
+        assert!(!validate(&schema).has_errors());
+
+        let ctx = BamlContext::try_from_schema(&schema, Some("Paint".to_string())).unwrap();
+        assert_eq!(ctx.target(), &FieldType::class("Paint"));
+
+        let rendered = ctx.render_prompt(None, None).unwrap();
+        assert!(rendered.contains("color: 'Red' or 'Green'"), "{rendered}");
+
+        let parsed = ctx
+            .validate_result(r#"{"color": "green", "liters": "2.5"}"#, false)
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&parsed).unwrap();
+        assert_eq!(value, serde_json::json!({"color": "Green", "liters": 2.5}));
+    }
+
+    /// Every item re-exported here is listed in the README table, and nothing else is.
+    #[test]
+    fn readme_lists_the_exported_items() {
+        let source = include_str!("lib.rs");
+        let source = &source[..source.find("#[cfg(test)]").unwrap()];
+        let mut exported = vec![];
+        for item in source.split(';') {
+            if let Some((_, names)) = item.split_once("pub use ") {
+                let names = names.rsplit("::").next().unwrap();
+                for name in names.trim_matches(['{', '}']).split(',') {
+                    let name = name.split(" as ").last().unwrap().trim();
+                    if !name.is_empty() {
+                        exported.push(name.to_string());
+                    }
+                }
+            } else if let Some((_, alias)) = item.split_once("pub type ") {
+                exported.push(alias.split(['<', ' ']).next().unwrap().to_string());
+            }
+        }
+        // Items defined here, not their methods.
+        for line in source.lines() {
+            for keyword in ["pub struct ", "pub fn "] {
+                if let Some(name) = line.strip_prefix(keyword) {
+                    exported.push(name.split(['(', ';', '<', ' ']).next().unwrap().to_string());
+                }
+            }
+        }
+
+        let mut listed = vec![];
+        for row in include_str!("../README.md")
+            .lines()
+            .filter(|l| l.starts_with("| `"))
+        {
+            let items = row.split('|').nth(1).unwrap();
+            listed.extend(
+                items
+                    .split(',')
+                    .map(|name| name.trim().trim_matches('`').to_string()),
+            );
+        }
+
+        exported.sort();
+        listed.sort();
+        assert_eq!(exported, listed);
+    }
+}
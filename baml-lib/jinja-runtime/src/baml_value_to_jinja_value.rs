@@ -25,7 +25,9 @@ impl IntoMiniJinjaValue for BamlValue {
             BamlValue::String(s) => minijinja::Value::from(s.clone()),
             BamlValue::Int(n) => minijinja::Value::from(*n),
             BamlValue::Float(n) => minijinja::Value::from(*n),
+            BamlValue::Decimal(_) => minijinja::Value::from(self.clone()),
             BamlValue::Bool(b) => minijinja::Value::from(*b),
+            BamlValue::Bytes(b) => minijinja::Value::from(b.as_slice()),
             BamlValue::Map(m) => {
                 let map = m
                     .into_iter()
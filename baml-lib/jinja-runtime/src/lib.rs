@@ -5,7 +5,7 @@ mod chat_message_part;
 mod output_format;
 use internal_baml_core::ir::jinja_helpers::get_env;
 use internal_baml_core::ir::repr::IntermediateRepr;
-pub use output_format::types;
+pub use output_format::{formatter, types};
 mod baml_value_to_jinja_value;
 
 use minijinja::{self, value::Kwargs};
@@ -13,7 +13,7 @@ use minijinja::{context, ErrorKind};
 use output_format::types::OutputFormatContent;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::baml_value_to_jinja_value::IntoMiniJinjaValue;
 pub use crate::chat_message_part::ChatMessagePart;
@@ -38,9 +38,31 @@ pub struct RenderContext {
 pub struct TemplateStringMacro {
     pub name: String,
     pub args: Vec<(String, String)>,
+    /// The default values of args, used when a call leaves them out.
+    pub defaults: HashMap<String, serde_json::Value>,
     pub template: String,
 }
 
+/// `value` as a Jinja literal, for the default values of macro arguments.
+fn jinja_literal(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "none".to_string(),
+        serde_json::Value::Array(items) => {
+            let items = items.iter().map(jinja_literal).collect::<Vec<_>>();
+            format!("[{}]", items.join(", "))
+        }
+        serde_json::Value::Object(entries) => {
+            let entries = entries
+                .iter()
+                .map(|(key, value)| format!("{}: {}", serde_json::json!(key), jinja_literal(value)))
+                .collect::<Vec<_>>();
+            format!("{{{}}}", entries.join(", "))
+        }
+        // JSON strings, numbers and booleans read the same in Jinja.
+        _ => value.to_string(),
+    }
+}
+
 const MAGIC_CHAT_ROLE_DELIMITER: &str = "BAML_CHAT_ROLE_MAGIC_STRING_DELIMITER";
 const MAGIC_MEDIA_DELIMITER: &str = "BAML_MEDIA_MAGIC_STRING_DELIMITER";
 
@@ -82,7 +104,10 @@ fn render_minijinja(
                 template_args = tsm
                     .args
                     .iter()
-                    .map(|(name, _)| name.as_str())
+                    .map(|(name, _)| match tsm.defaults.get(name) {
+                        Some(default) => format!("{name}={}", jinja_literal(default)),
+                        None => name.clone(),
+                    })
                     .collect::<Vec<_>>()
                     .join(", "),
                 template = tsm.template,
@@ -443,6 +468,16 @@ pub fn render_prompt(
     }
 }
 
+/// The names `template` reads without defining them: its arguments, `ctx`, and the template
+/// strings it calls. Empty when the template does not parse, which rendering reports.
+pub fn referenced_names(template: &str) -> HashSet<String> {
+    let env = get_env();
+    match env.template_from_str(template) {
+        Ok(template) => template.undeclared_variables(false),
+        Err(_) => HashSet::new(),
+    }
+}
+
 #[cfg(test)]
 mod render_tests {
 
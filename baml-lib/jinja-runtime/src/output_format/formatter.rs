@@ -0,0 +1,136 @@
+//! Renderers of the output format, registered by name.
+
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+
+use super::types::{OutputFormatContent, RenderOptions};
+
+/// Renders the output format of a prompt: `content` holds the target type and the classes,
+/// enums and recursive aliases it refers to. `None` means nothing to render, as for a plain
+/// `string` target without a prefix.
+///
+/// Implement it to describe the schema another way (a TOML schema, a JSON Schema, ...) and
+/// register it in a [`FormatterRegistry`].
+pub trait OutputFormatter: Send + Sync {
+    fn render(
+        &self,
+        content: &OutputFormatContent,
+        options: RenderOptions,
+    ) -> anyhow::Result<Option<String>>;
+}
+
+/// The built-in renderer, [`OutputFormatContent::render`], registered as
+/// [`STANDARD_FORMATTER`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StandardFormatter;
+
+impl OutputFormatter for StandardFormatter {
+    fn render(
+        &self,
+        content: &OutputFormatContent,
+        options: RenderOptions,
+    ) -> anyhow::Result<Option<String>> {
+        Ok(content.render(options)?)
+    }
+}
+
+/// Name of the [`StandardFormatter`] in every [`FormatterRegistry`].
+pub const STANDARD_FORMATTER: &str = "standard";
+
+/// Output formatters by name, [`StandardFormatter`] included.
+#[derive(Clone)]
+pub struct FormatterRegistry {
+    formatters: IndexMap<String, Arc<dyn OutputFormatter>>,
+}
+
+impl Default for FormatterRegistry {
+    fn default() -> Self {
+        let mut formatters = IndexMap::<String, Arc<dyn OutputFormatter>>::new();
+        formatters.insert(STANDARD_FORMATTER.to_string(), Arc::new(StandardFormatter));
+        Self { formatters }
+    }
+}
+
+impl FormatterRegistry {
+    /// Register `formatter` as `name`, replacing any formatter of that name, the standard one
+    /// included.
+    pub fn register(&mut self, name: impl Into<String>, formatter: Arc<dyn OutputFormatter>) {
+        self.formatters.insert(name.into(), formatter);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arc<dyn OutputFormatter>> {
+        self.formatters.get(name)
+    }
+
+    /// The registered names, in registration order.
+    pub fn names(&self) -> Vec<&str> {
+        self.formatters.keys().map(String::as_str).collect()
+    }
+
+    /// Render `content` with the formatter registered as `name`.
+    pub fn render(
+        &self,
+        name: &str,
+        content: &OutputFormatContent,
+        options: RenderOptions,
+    ) -> anyhow::Result<Option<String>> {
+        let Some(formatter) = self.get(name) else {
+            anyhow::bail!(
+                "Unknown output formatter `{name}`, expected one of: {}",
+                self.names().join(", ")
+            );
+        };
+        formatter.render(content, options)
+    }
+}
+
+impl std::fmt::Debug for FormatterRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.formatters.keys()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct UpperCase;
+
+    impl OutputFormatter for UpperCase {
+        fn render(
+            &self,
+            content: &OutputFormatContent,
+            options: RenderOptions,
+        ) -> anyhow::Result<Option<String>> {
+            Ok(StandardFormatter
+                .render(content, options)?
+                .map(|rendered| rendered.to_uppercase()))
+        }
+    }
+
+    #[test]
+    fn registry_renders_with_named_formatter() {
+        let content = OutputFormatContent::new_array();
+        let mut registry = FormatterRegistry::default();
+        registry.register("upper", Arc::new(UpperCase));
+        assert_eq!(registry.names(), ["standard", "upper"]);
+
+        let standard = registry
+            .render(STANDARD_FORMATTER, &content, RenderOptions::default())
+            .unwrap();
+        assert_eq!(standard, content.render(RenderOptions::default()).unwrap());
+        let upper = registry
+            .render("upper", &content, RenderOptions::default())
+            .unwrap();
+        assert_eq!(upper, standard.map(|s| s.to_uppercase()));
+
+        let err = registry
+            .render("toml", &content, RenderOptions::default())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown output formatter `toml`, expected one of: standard, upper"
+        );
+    }
+}
@@ -1,32 +1,50 @@
+pub mod formatter;
 pub mod types;
 
-use std::str::FromStr;
+use std::{str::FromStr, sync::Arc};
 
 use minijinja::{value::Kwargs, ErrorKind, Value};
 use strum::VariantNames;
 
 use crate::{types::RenderOptions, RenderContext};
 
-use self::types::OutputFormatContent;
+use self::{
+    formatter::{OutputFormatter, StandardFormatter},
+    types::OutputFormatContent,
+};
 
 // TODO: Rename the field to `content`.
-#[derive(Debug)]
 pub struct OutputFormat {
     text: OutputFormatContent,
+    formatter: Arc<dyn OutputFormatter>,
 }
 
 impl OutputFormat {
     pub fn new(ctx: RenderContext) -> Self {
         Self {
             text: ctx.output_format,
+            formatter: Arc::new(StandardFormatter),
         }
     }
+
+    fn render(&self, options: RenderOptions) -> Result<Option<String>, minijinja::Error> {
+        self.formatter
+            .render(&self.text, options)
+            .map_err(|e| minijinja::Error::new(ErrorKind::BadSerialization, format!("{e:#}")))
+    }
+}
+
+impl std::fmt::Debug for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OutputFormat")
+            .field("text", &self.text)
+            .finish_non_exhaustive()
+    }
 }
 
 impl std::fmt::Display for OutputFormat {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let content = self
-            .text
             .render(RenderOptions::default())
             .map_err(|_e| std::fmt::Error {})?;
 
@@ -163,6 +181,99 @@ impl minijinja::value::Object for OutputFormat {
             None
         };
 
+        let field_order = if kwargs.has("field_order") {
+            match kwargs
+                .get::<String>("field_order")
+                .map(|s| types::FieldOrder::from_str(s.as_str()))
+            {
+                Ok(Ok(field_order)) => field_order,
+                Ok(Err(e)) => {
+                    return Err(Error::new(
+                        ErrorKind::SyntaxError,
+                        format!(
+                            "Invalid value for field_order (expected one of {}): {}",
+                            types::FieldOrder::VARIANTS.join(", "),
+                            e
+                        ),
+                    ))
+                }
+                Err(e) => {
+                    return Err(Error::new(
+                        ErrorKind::SyntaxError,
+                        format!(
+                            "Invalid value for field_order (expected one of {}): {}",
+                            types::FieldOrder::VARIANTS.join(", "),
+                            e
+                        ),
+                    ))
+                }
+            }
+        } else {
+            types::FieldOrder::default()
+        };
+
+        let style = if kwargs.has("style") {
+            match kwargs
+                .get::<String>("style")
+                .map(|s| types::RenderStyle::from_str(s.as_str()))
+            {
+                Ok(Ok(style)) => style,
+                Ok(Err(e)) => {
+                    return Err(Error::new(
+                        ErrorKind::SyntaxError,
+                        format!(
+                            "Invalid value for style (expected one of {}): {}",
+                            types::RenderStyle::VARIANTS.join(", "),
+                            e
+                        ),
+                    ))
+                }
+                Err(e) => {
+                    return Err(Error::new(
+                        ErrorKind::SyntaxError,
+                        format!(
+                            "Invalid value for style (expected one of {}): {}",
+                            types::RenderStyle::VARIANTS.join(", "),
+                            e
+                        ),
+                    ))
+                }
+            }
+        } else {
+            types::RenderStyle::default()
+        };
+
+        let render_format = if kwargs.has("format") {
+            match kwargs
+                .get::<String>("format")
+                .map(|s| types::RenderFormat::from_str(s.as_str()))
+            {
+                Ok(Ok(render_format)) => render_format,
+                Ok(Err(e)) => {
+                    return Err(Error::new(
+                        ErrorKind::SyntaxError,
+                        format!(
+                            "Invalid value for format (expected one of {}): {}",
+                            types::RenderFormat::VARIANTS.join(", "),
+                            e
+                        ),
+                    ))
+                }
+                Err(e) => {
+                    return Err(Error::new(
+                        ErrorKind::SyntaxError,
+                        format!(
+                            "Invalid value for format (expected one of {}): {}",
+                            types::RenderFormat::VARIANTS.join(", "),
+                            e
+                        ),
+                    ))
+                }
+            }
+        } else {
+            types::RenderFormat::default()
+        };
+
         let Ok(_) = kwargs.assert_all_used() else {
             return Err(Error::new(
                 ErrorKind::TooManyArguments,
@@ -170,14 +281,19 @@ impl minijinja::value::Object for OutputFormat {
             ));
         };
 
-        let content = self.text.render(RenderOptions::new(
-            prefix,
-            or_splitter,
-            enum_value_prefix,
-            always_hoist_enums,
-            map_style,
-            hoisted_class_prefix,
-        ))?;
+        let content = self.render(
+            RenderOptions::new(
+                prefix,
+                or_splitter,
+                enum_value_prefix,
+                always_hoist_enums,
+                map_style,
+                hoisted_class_prefix,
+            )
+            .with_field_order(field_order)
+            .with_style(style)
+            .with_format(render_format),
+        )?;
 
         match content {
             Some(content) => Ok(Value::from_safe_string(content)),
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use anyhow::Result;
-use baml_types::{Constraint, FieldType, TypeValue};
+use baml_types::{Constraint, FieldType, JinjaExpression, TypeValue};
 use indexmap::{IndexMap, IndexSet};
 
 #[derive(Debug)]
@@ -34,13 +34,43 @@ impl Name {
     }
 }
 
+/// How a class field is parsed and rendered, from its attributes.
+#[derive(Debug, Default)]
+pub struct FieldMetadata {
+    /// Deprecated fields (`@deprecated`) are still parsed but left out of the rendered prompt.
+    pub deprecated: Option<String>,
+    /// Name of the embedder-registered coercer (`@coerce`) that parses the field.
+    pub coercer: Option<String>,
+    /// Condition (`@required_if`) on the enclosing object under which the field must not be null.
+    pub required_if: Option<JinjaExpression>,
+    /// Whether a single value parsed for a `T | T[]` field is wrapped in a list (`@normalize`).
+    pub normalize_to_list: bool,
+    /// What to do with numbers that do not fit the field's `int` (`@int_overflow`), instead of
+    /// the per-call default.
+    pub int_overflow: Option<IntOverflow>,
+    /// Strings (`N/A`, `-`, ...) parsed as null for the field (`@null_values`), instead of the
+    /// per-call null synonyms.
+    pub null_values: Option<Vec<String>>,
+}
+
+/// How an enum value is rendered, from its attributes.
+#[derive(Debug, Default)]
+pub struct EnumValueMetadata {
+    /// Deprecated values (`@deprecated`) are still parsed but left out of the rendered prompt.
+    pub deprecated: Option<String>,
+    /// Heading (`@group`) the value is listed under when rendered.
+    pub group: Option<String>,
+}
+
 // TODO: (Greg) Enum needs to carry its constraints.
 #[derive(Debug)]
 pub struct Enum {
     pub name: Name,
-    // name and description
-    pub values: Vec<(Name, Option<String>)>,
+    // name, description and metadata
+    pub values: Vec<(Name, Option<String>, EnumValueMetadata)>,
     pub constraints: Vec<Constraint>,
+    /// Open enums (`@@open`) also accept values other than the listed ones.
+    pub open: bool,
 }
 
 /// The components of a Class needed to render `OutputFormatContent`.
@@ -48,9 +78,33 @@ pub struct Enum {
 #[derive(Debug)]
 pub struct Class {
     pub name: Name,
-    // fields have name, type and description.
-    pub fields: Vec<(Name, FieldType, Option<String>)>,
+    // fields have name, type, description and metadata.
+    pub fields: Vec<(Name, FieldType, Option<String>, FieldMetadata)>,
     pub constraints: Vec<Constraint>,
+    /// Groups (`@@one_of`) of optional fields, by real name, of which exactly one must be set.
+    pub one_of: Vec<Vec<String>>,
+}
+
+/// How a class is rendered (`@@render`), overriding the [`RenderOptions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassRendering {
+    /// Define the class once above the answer and refer to it by name instead of rendering it
+    /// in place. Recursive classes are always hoisted.
+    pub hoisted: bool,
+    /// Ends each field instead of `,`.
+    pub field_separator: Option<String>,
+    /// Whether field descriptions are rendered.
+    pub descriptions: bool,
+}
+
+impl Default for ClassRendering {
+    fn default() -> Self {
+        Self {
+            hoisted: false,
+            field_separator: None,
+            descriptions: true,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -59,6 +113,8 @@ pub struct OutputFormatContent {
     pub classes: Arc<IndexMap<String, Class>>,
     recursive_classes: Arc<IndexSet<String>>,
     structural_recursive_aliases: Arc<IndexMap<String, FieldType>>,
+    /// Classes rendered differently from the render options, by name.
+    class_rendering: Arc<IndexMap<String, ClassRendering>>,
     pub target: FieldType,
 }
 
@@ -70,6 +126,7 @@ pub struct Builder {
     recursive_classes: IndexSet<String>,
     /// Recursive aliases introduced maps and lists.
     structural_recursive_aliases: IndexMap<String, FieldType>,
+    class_rendering: IndexMap<String, ClassRendering>,
     target: FieldType,
 }
 
@@ -80,6 +137,7 @@ impl Builder {
             classes: vec![],
             recursive_classes: IndexSet::new(),
             structural_recursive_aliases: IndexMap::new(),
+            class_rendering: IndexMap::new(),
             target,
         }
     }
@@ -107,6 +165,11 @@ impl Builder {
         self
     }
 
+    pub fn class_rendering(mut self, class_rendering: IndexMap<String, ClassRendering>) -> Self {
+        self.class_rendering = class_rendering;
+        self
+    }
+
     pub fn target(mut self, target: FieldType) -> Self {
         self.target = target;
         self
@@ -130,6 +193,7 @@ impl Builder {
             structural_recursive_aliases: Arc::new(
                 self.structural_recursive_aliases.into_iter().collect(),
             ),
+            class_rendering: Arc::new(self.class_rendering),
             target: self.target,
         }
     }
@@ -147,7 +211,105 @@ impl<T> Default for RenderSetting<T> {
     }
 }
 
-#[derive(strum::EnumString, strum::VariantNames)]
+/// Order of class fields in the rendered schema. Parsing is not affected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, strum::EnumString, strum::VariantNames)]
+pub enum FieldOrder {
+    #[default]
+    #[strum(serialize = "declaration")]
+    Declaration,
+
+    /// By rendered name.
+    #[strum(serialize = "alphabetical")]
+    Alphabetical,
+
+    /// Required fields first, each group in declaration order.
+    #[strum(serialize = "required_first")]
+    RequiredFirst,
+}
+
+/// How the schema is laid out in the rendered prompt. Parsing is not affected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, strum::EnumString, strum::VariantNames)]
+pub enum RenderStyle {
+    #[default]
+    #[strum(serialize = "standard")]
+    Standard,
+
+    /// Classes and enums on a single line each, short type names (`str` for `string`) and no
+    /// blank lines between definitions. Fewer tokens on large schemas.
+    #[strum(serialize = "compact")]
+    Compact,
+}
+
+/// How classes are written in the rendered schema. Parsing is not affected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, strum::EnumString, strum::VariantNames)]
+pub enum RenderFormat {
+    /// TypeScript-like blocks, as in `{ name: string, }`.
+    #[default]
+    #[strum(serialize = "block")]
+    Block,
+
+    /// A Markdown table per class with a row per field (`| name | string | Full name |`). Tables
+    /// cannot nest, so every class but the output type is defined under its name above the
+    /// answer and referred to by name.
+    #[strum(serialize = "markdown")]
+    MarkdownTable,
+}
+
+/// How the schema refers to recursive classes and type aliases. Parsing is not affected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecursionRendering {
+    /// How many levels of a recursive class to write out where it is used before referring to
+    /// its hoisted definition. `0` refers to it right away.
+    pub inline_depth: usize,
+    pub references: ReferenceStyle,
+}
+
+/// How references to recursive classes and type aliases are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, strum::EnumString, strum::VariantNames)]
+pub enum ReferenceStyle {
+    /// The name, as in `children: Node[]`.
+    #[default]
+    #[strum(serialize = "name")]
+    Name,
+
+    /// The name after a `#/` marker, as in `children: #/Node[]`, so that references stand out
+    /// from types.
+    #[strum(serialize = "marker")]
+    Marker,
+}
+
+/// How a union output type is laid out. Parsing is not affected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, strum::EnumString, strum::VariantNames)]
+pub enum UnionStyle {
+    /// The alternatives on one line, as in `{ ... } or int`.
+    #[default]
+    #[strum(serialize = "inline")]
+    Inline,
+
+    /// Each alternative in its own block under a numbered header, such as `Shape 1 (Person):`,
+    /// after "Answer in JSON using one of the following shapes". Only the output type itself is
+    /// laid out this way: unions in fields stay inline.
+    #[strum(serialize = "variants")]
+    Variants,
+}
+
+/// What to do with a number outside the range of an `int` (`i64`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumString, strum::VariantNames)]
+pub enum IntOverflow {
+    /// Fail to coerce the number.
+    #[strum(serialize = "error")]
+    Error,
+
+    /// Clamp to `i64::MIN` or `i64::MAX`, flagged `IntSaturated`.
+    #[strum(serialize = "saturate")]
+    Saturate,
+
+    /// Return the nearest float instead, flagged `IntToFloat`.
+    #[strum(serialize = "float")]
+    CoerceToFloat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumString, strum::VariantNames)]
 pub enum MapStyle {
     #[strum(serialize = "angle")]
     TypeParameters,
@@ -163,6 +325,12 @@ pub struct RenderOptions {
     hoisted_class_prefix: RenderSetting<String>,
     always_hoist_enums: RenderSetting<bool>,
     map_style: MapStyle,
+    field_order: FieldOrder,
+    style: RenderStyle,
+    format: RenderFormat,
+    recursion: RecursionRendering,
+    union_style: UnionStyle,
+    shared_definitions: bool,
 }
 
 impl Default for RenderOptions {
@@ -174,6 +342,12 @@ impl Default for RenderOptions {
             hoisted_class_prefix: RenderSetting::Auto,
             always_hoist_enums: RenderSetting::Auto,
             map_style: MapStyle::TypeParameters,
+            field_order: FieldOrder::Declaration,
+            style: RenderStyle::Standard,
+            format: RenderFormat::Block,
+            recursion: RecursionRendering::default(),
+            union_style: UnionStyle::Inline,
+            shared_definitions: false,
         }
     }
 }
@@ -204,6 +378,70 @@ impl RenderOptions {
             hoisted_class_prefix: hoisted_class_prefix.map_or(RenderSetting::Auto, |p| {
                 p.map_or(RenderSetting::Never, RenderSetting::Always)
             }),
+            field_order: FieldOrder::Declaration,
+            style: RenderStyle::Standard,
+            format: RenderFormat::Block,
+            recursion: RecursionRendering::default(),
+            union_style: UnionStyle::Inline,
+            shared_definitions: false,
+        }
+    }
+
+    pub fn with_field_order(mut self, field_order: FieldOrder) -> Self {
+        self.field_order = field_order;
+        self
+    }
+
+    pub fn with_style(mut self, style: RenderStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn with_format(mut self, format: RenderFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_recursion(mut self, recursion: RecursionRendering) -> Self {
+        self.recursion = recursion;
+        self
+    }
+
+    pub fn with_union_style(mut self, union_style: UnionStyle) -> Self {
+        self.union_style = union_style;
+        self
+    }
+
+    /// Hoist classes whose schema would be written out more than once into a single definition
+    /// referred to by name, sharing it between classes of the same shape.
+    pub fn with_shared_definitions(mut self, shared_definitions: bool) -> Self {
+        self.shared_definitions = shared_definitions;
+        self
+    }
+
+    fn compact(&self) -> bool {
+        self.style == RenderStyle::Compact
+    }
+
+    fn tables(&self) -> bool {
+        self.format == RenderFormat::MarkdownTable
+    }
+
+    /// How a recursive class or type alias is referred to.
+    fn reference(&self, name: &str) -> String {
+        match self.recursion.references {
+            ReferenceStyle::Name => name.to_owned(),
+            ReferenceStyle::Marker => format!("#/{name}"),
+        }
+    }
+
+    /// The separator between alternatives: `|` in the compact style unless another one was
+    /// asked for.
+    fn or_splitter(&self) -> &str {
+        if self.compact() && self.or_splitter == Self::DEFAULT_OR_SPLITTER {
+            "|"
+        } else {
+            &self.or_splitter
         }
     }
 
@@ -216,6 +454,62 @@ impl RenderOptions {
     }
 }
 
+impl Class {
+    /// All fields, deprecated ones included, in `order`.
+    pub fn fields_in(
+        &self,
+        order: FieldOrder,
+    ) -> Vec<&(Name, FieldType, Option<String>, FieldMetadata)> {
+        let mut fields = self.fields.iter().collect::<Vec<_>>();
+        match order {
+            FieldOrder::Declaration => {}
+            FieldOrder::Alphabetical => fields.sort_by_key(|(name, ..)| name.rendered_name()),
+            FieldOrder::RequiredFirst => {
+                fields.sort_by_key(|(_, field_type, ..)| field_type.is_optional())
+            }
+        }
+        fields
+    }
+}
+
+/// The fields of `class` to render, without deprecated ones, in `order`.
+fn ordered_fields(
+    class: &Class,
+    order: FieldOrder,
+) -> Vec<&(Name, FieldType, Option<String>, FieldMetadata)> {
+    class
+        .fields_in(order)
+        .into_iter()
+        .filter(|(.., metadata)| metadata.deprecated.is_none())
+        .collect()
+}
+
+/// The classes `field_type` refers to, in order, once per reference. Recursive type aliases are
+/// not followed.
+fn class_references<'a>(field_type: &'a FieldType, references: &mut Vec<&'a str>) {
+    match field_type {
+        FieldType::Class(name) => references.push(name),
+        FieldType::List(inner) | FieldType::Optional(inner) => class_references(inner, references),
+        FieldType::Map(key, value) => {
+            class_references(key, references);
+            class_references(value, references);
+        }
+        FieldType::Union(items) | FieldType::Tuple(items) => {
+            for item in items {
+                class_references(item, references);
+            }
+        }
+        FieldType::Constrained { base, .. } => class_references(base, references),
+        FieldType::Primitive(_)
+        | FieldType::Enum(_)
+        | FieldType::Literal(_)
+        | FieldType::RecursiveTypeAlias(_) => {}
+    }
+}
+
+/// Rendered as the last alternative of an open enum.
+const OPEN_ENUM_VALUE: &str = "any other value";
+
 struct Attribute {
     name: String,
     description: Option<String>,
@@ -224,25 +518,87 @@ struct Attribute {
 struct EnumRender {
     name: String,
     delimiter: String,
-    values: Vec<Attribute>,
+    /// Values with their `@group`, if any.
+    values: Vec<(Option<String>, Attribute)>,
+    open: bool,
 }
 
 impl EnumRender {
     fn to_string(&self, options: &RenderOptions) -> String {
+        if options.compact() {
+            return self.to_compact_string(options);
+        }
         let mut result = format!("{}\n{}", self.name, self.delimiter);
-        for value in &self.values {
+        // Ungrouped values first, then each group under its heading in order of appearance.
+        let mut groups = IndexMap::<Option<&str>, Vec<&Attribute>>::new();
+        groups.insert(None, Vec::new());
+        for (group, value) in &self.values {
+            groups.entry(group.as_deref()).or_default().push(value);
+        }
+        for (group, values) in groups {
+            if let Some(group) = group {
+                result.push_str(&format!("\n{group}:"));
+            }
+            for value in values {
+                result.push_str(&format!(
+                    "\n{}{}",
+                    match options.enum_value_prefix {
+                        RenderSetting::Auto => "- ",
+                        RenderSetting::Always(ref prefix) => prefix,
+                        RenderSetting::Never => "",
+                    },
+                    value
+                ));
+            }
+        }
+        if self.open {
             result.push_str(&format!(
-                "\n{}{}",
+                "\n{}or {}",
                 match options.enum_value_prefix {
                     RenderSetting::Auto => "- ",
                     RenderSetting::Always(ref prefix) => prefix,
                     RenderSetting::Never => "",
                 },
-                value
+                OPEN_ENUM_VALUE
             ));
         }
         result
     }
+
+    /// `Name: A|B /* description */|C` with the values of each group together.
+    fn to_compact_string(&self, options: &RenderOptions) -> String {
+        let mut groups = IndexMap::<Option<&str>, Vec<&Attribute>>::new();
+        groups.insert(None, Vec::new());
+        for (group, value) in &self.values {
+            groups.entry(group.as_deref()).or_default().push(value);
+        }
+        let values = groups
+            .into_values()
+            .flatten()
+            .map(|value| match &value.description {
+                Some(description) => format!("{} {}", value.name, compact_comment(description)),
+                None => value.name.clone(),
+            })
+            .chain(self.open.then(|| OPEN_ENUM_VALUE.to_string()))
+            .collect::<Vec<_>>();
+        format!("{}: {}", self.name, values.join(options.or_splitter()))
+    }
+}
+
+/// A description as an inline `/* ... */` comment on a single line.
+fn compact_comment(description: &str) -> String {
+    format!(
+        "/* {} */",
+        description.split_whitespace().collect::<Vec<_>>().join(" ")
+    )
+}
+
+/// `text` on one line with its pipes escaped, to fit in a Markdown table cell.
+fn table_cell(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
 }
 
 impl std::fmt::Display for Attribute {
@@ -259,6 +615,12 @@ struct ClassRender {
     #[allow(dead_code)]
     name: String,
     values: Vec<ClassFieldRender>,
+    /// Rendered field names of each `@@one_of` group.
+    one_of: Vec<Vec<String>>,
+    /// Ends each field.
+    separator: String,
+    compact: bool,
+    table: bool,
 }
 
 struct ClassFieldRender {
@@ -269,6 +631,37 @@ struct ClassFieldRender {
 
 impl std::fmt::Display for ClassRender {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.table {
+            write!(f, "| Field | Type | Description |\n| --- | --- | --- |")?;
+            for value in &self.values {
+                let description = value.description.as_deref().map(table_cell);
+                write!(
+                    f,
+                    "\n| {} | {} | {} |",
+                    table_cell(&value.name),
+                    table_cell(&value.r#type),
+                    description.unwrap_or_default()
+                )?;
+            }
+            for group in &self.one_of {
+                write!(f, "\n\nSet exactly one of: {}", group.join(", "))?;
+            }
+            return Ok(());
+        }
+        if self.compact {
+            let mut parts = self
+                .values
+                .iter()
+                .map(|value| match &value.description {
+                    Some(desc) => format!("{}: {} {}", value.name, value.r#type, compact_comment(desc)),
+                    None => format!("{}: {}", value.name, value.r#type),
+                })
+                .collect::<Vec<_>>();
+            parts.extend(self.one_of.iter().map(|group| {
+                compact_comment(&format!("Set exactly one of: {}", group.join(", ")))
+            }));
+            return write!(f, "{{{}}}", parts.join(&format!("{} ", self.separator)));
+        }
         writeln!(f, "{{")?;
         for value in &self.values {
             if let Some(desc) = &value.description {
@@ -276,11 +669,15 @@ impl std::fmt::Display for ClassRender {
             }
             writeln!(
                 f,
-                "  {}: {},",
+                "  {}: {}{}",
                 value.name,
-                value.r#type.replace('\n', "\n  ")
+                value.r#type.replace('\n', "\n  "),
+                self.separator
             )?;
         }
+        for group in &self.one_of {
+            writeln!(f, "  // Set exactly one of: {}", group.join(", "))?;
+        }
         write!(f, "}}")
     }
 }
@@ -310,8 +707,17 @@ fn indefinite_article_a_or_an(word: &str) -> &str {
     }
 }
 
+#[derive(Default)]
 struct RenderState {
     hoisted_enums: IndexSet<String>,
+    /// Classes with `@@render({ layout "hoisted" })` or shared definitions referred to so far.
+    hoisted_classes: IndexSet<String>,
+    /// How many recursive classes are being written out in place, see
+    /// [`RecursionRendering::inline_depth`].
+    inlined_recursion: usize,
+    /// Classes referred to by the name of a shared definition, see
+    /// [`RenderOptions::with_shared_definitions`].
+    shared_classes: IndexMap<String, String>,
 }
 
 impl OutputFormatContent {
@@ -359,6 +765,9 @@ impl OutputFormatContent {
                 FieldType::List(_) => Some(String::from(
                     "Answer with a JSON Array using this schema:\n",
                 )),
+                FieldType::Union(_) if options.union_style == UnionStyle::Variants => Some(
+                    String::from("Answer in JSON using one of the following shapes:\n\n"),
+                ),
                 FieldType::Union(_) => {
                     Some(String::from("Answer in JSON using any of these schemas:\n"))
                 }
@@ -385,11 +794,18 @@ impl OutputFormatContent {
             values: enm
                 .values
                 .iter()
-                .map(|(name, description)| Attribute {
-                    name: name.rendered_name().to_string(),
-                    description: description.clone(),
+                .filter(|(.., metadata)| metadata.deprecated.is_none())
+                .map(|(name, description, metadata)| {
+                    (
+                        metadata.group.clone(),
+                        Attribute {
+                            name: name.rendered_name().to_string(),
+                            description: description.clone(),
+                        },
+                    )
                 })
                 .collect(),
+            open: enm.open,
         }
         .to_string(options)
     }
@@ -417,6 +833,38 @@ impl OutputFormatContent {
     ) -> Result<String, minijinja::Error> {
         match field_type {
             FieldType::Class(nested_class) if self.recursive_classes.contains(nested_class) => {
+                if render_state.inlined_recursion >= options.recursion.inline_depth
+                    || options.tables()
+                {
+                    return Ok(options.reference(nested_class));
+                }
+                render_state.inlined_recursion += 1;
+                let inlined = self.inner_type_render(
+                    options,
+                    field_type,
+                    render_state,
+                    group_hoisted_literals,
+                );
+                render_state.inlined_recursion -= 1;
+                inlined
+            }
+
+            FieldType::Class(nested_class) if self.rendering(nested_class).hoisted => {
+                render_state.hoisted_classes.insert(nested_class.to_owned());
+                Ok(nested_class.to_owned())
+            }
+
+            FieldType::Class(nested_class)
+                if render_state.shared_classes.contains_key(nested_class) =>
+            {
+                let definition = render_state.shared_classes[nested_class].clone();
+                render_state.hoisted_classes.insert(definition.clone());
+                Ok(definition)
+            }
+
+            // Tables cannot nest, so every class gets its own.
+            FieldType::Class(nested_class) if options.tables() => {
+                render_state.hoisted_classes.insert(nested_class.to_owned());
                 Ok(nested_class.to_owned())
             }
 
@@ -424,6 +872,102 @@ impl OutputFormatContent {
         }
     }
 
+    /// The classes whose schema would be written out more than once for the target, each with
+    /// the class whose hoisted definition is referred to instead: the first class of its shape.
+    ///
+    /// Uses are counted as if the shared classes were hoisted, so a class used twice in a class
+    /// used twice is written out once. The target, recursive classes and classes hoisted with
+    /// `@@render` are hoisted or written out once already.
+    fn shared_definitions(
+        &self,
+        options: &RenderOptions,
+    ) -> Result<IndexMap<String, String>, minijinja::Error> {
+        let field_types = |class_name: &str| {
+            self.classes
+                .get(class_name)
+                .into_iter()
+                .flat_map(|class| ordered_fields(class, options.field_order))
+                .map(|(_, field_type, ..)| field_type)
+        };
+        let mut uses = IndexMap::<&str, usize>::new();
+        let mut walked = IndexSet::new();
+        let mut pending = std::collections::VecDeque::new();
+        match &self.target {
+            FieldType::Class(target) => {
+                walked.insert(target.as_str());
+                pending.extend(field_types(target));
+            }
+            target => pending.push_back(target),
+        }
+        while let Some(field_type) = pending.pop_front() {
+            let mut references = Vec::new();
+            class_references(field_type, &mut references);
+            for class_name in references {
+                *uses.entry(class_name).or_default() += 1;
+                if walked.insert(class_name) {
+                    pending.extend(field_types(class_name));
+                }
+            }
+        }
+
+        // Classes by the schema written out for them.
+        let mut shapes = IndexMap::<String, Vec<&str>>::new();
+        for &class_name in uses.keys() {
+            let target = matches!(&self.target, FieldType::Class(target) if target == class_name);
+            if target
+                || self.recursive_classes.contains(class_name)
+                || self.rendering(class_name).hoisted
+            {
+                continue;
+            }
+            let schema = self.inner_type_render(
+                options,
+                &FieldType::Class(class_name.to_owned()),
+                &mut RenderState::default(),
+                false,
+            )?;
+            shapes.entry(schema).or_default().push(class_name);
+        }
+
+        let mut shared = IndexMap::new();
+        for classes in shapes.values() {
+            let shape_uses: usize = classes.iter().map(|class_name| uses[class_name]).sum();
+            if shape_uses > 1 {
+                for class_name in classes {
+                    shared.insert(class_name.to_string(), classes[0].to_string());
+                }
+            }
+        }
+        Ok(shared)
+    }
+
+    /// The variants of a union output type one after the other, each under a numbered header
+    /// naming the class, enum or type alias it is, for [`UnionStyle::Variants`].
+    fn variants_render(
+        &self,
+        options: &RenderOptions,
+        variants: &[FieldType],
+        render_state: &mut RenderState,
+    ) -> Result<String, minijinja::Error> {
+        let mut shapes = Vec::with_capacity(variants.len());
+        for (i, variant) in variants.iter().enumerate() {
+            let name = match variant {
+                FieldType::Class(cls) => self.classes.get(cls).map(|c| c.name.rendered_name()),
+                FieldType::Enum(e) => self.enums.get(e).map(|e| e.name.rendered_name()),
+                FieldType::RecursiveTypeAlias(name) => Some(name.as_str()),
+                _ => None,
+            };
+            let header = match name {
+                Some(name) => format!("Shape {} ({name}):", i + 1),
+                None => format!("Shape {}:", i + 1),
+            };
+            let schema =
+                self.render_possibly_recursive_type(options, variant, render_state, false)?;
+            shapes.push(format!("{header}\n{schema}"));
+        }
+        Ok(shapes.join(if options.compact() { "\n" } else { "\n\n" }))
+    }
+
     fn inner_type_render(
         &self,
         options: &RenderOptions,
@@ -433,10 +977,13 @@ impl OutputFormatContent {
     ) -> Result<String, minijinja::Error> {
         Ok(match field {
             FieldType::Primitive(t) => match t {
+                TypeValue::String if options.compact() => "str".to_string(),
                 TypeValue::String => "string".to_string(),
                 TypeValue::Int => "int".to_string(),
                 TypeValue::Float => "float".to_string(),
                 TypeValue::Bool => "bool".to_string(),
+                TypeValue::Bytes if options.compact() => "base64".to_string(),
+                TypeValue::Bytes => "string (base64)".to_string(),
                 TypeValue::Null => "null".to_string(),
                 TypeValue::Media(media_type) => {
                     return Err(minijinja::Error::new(
@@ -460,17 +1007,24 @@ impl OutputFormatContent {
                     ));
                 };
 
-                if enm.values.len() <= 6
-                    && enm.values.iter().all(|(_, d)| d.is_none())
+                let values = enm
+                    .values
+                    .iter()
+                    .filter(|(.., metadata)| metadata.deprecated.is_none())
+                    .collect::<Vec<_>>();
+                if values.len() <= 6
+                    && values
+                        .iter()
+                        .all(|(_, d, metadata)| d.is_none() && metadata.group.is_none())
                     && !group_hoisted_literals
                     && !matches!(options.always_hoist_enums, RenderSetting::Always(true))
                 {
-                    let values = enm
-                        .values
+                    let values = values
                         .iter()
-                        .map(|(n, _)| format!("'{}'", n.rendered_name()))
+                        .map(|(n, ..)| format!("'{}'", n.rendered_name()))
+                        .chain(enm.open.then(|| OPEN_ENUM_VALUE.to_string()))
                         .collect::<Vec<_>>()
-                        .join(&options.or_splitter);
+                        .join(options.or_splitter());
 
                     values
                 } else {
@@ -486,15 +1040,17 @@ impl OutputFormatContent {
                     ));
                 };
 
+                let rendering = self.rendering(cls);
                 ClassRender {
                     name: class.name.rendered_name().to_string(),
-                    values: class
-                        .fields
-                        .iter()
-                        .map(|(name, field_type, description)| {
+                    values: ordered_fields(class, options.field_order)
+                        .into_iter()
+                        .map(|(name, field_type, description, _)| {
                             Ok(ClassFieldRender {
                                 name: name.rendered_name().to_string(),
-                                description: description.clone(),
+                                description: description
+                                    .clone()
+                                    .filter(|_| rendering.descriptions),
                                 r#type: self.render_possibly_recursive_type(
                                     options,
                                     field_type,
@@ -504,13 +1060,40 @@ impl OutputFormatContent {
                             })
                         })
                         .collect::<Result<_, minijinja::Error>>()?,
+                    one_of: class
+                        .one_of
+                        .iter()
+                        .map(|group| {
+                            group
+                                .iter()
+                                .map(|field| {
+                                    class
+                                        .fields
+                                        .iter()
+                                        .find(|(name, ..)| name.real_name() == field)
+                                        .map_or(field.as_str(), |(name, ..)| name.rendered_name())
+                                        .to_string()
+                                })
+                                .collect()
+                        })
+                        .collect(),
+                    separator: rendering.field_separator.clone().unwrap_or_else(|| ",".to_string()),
+                    compact: options.compact(),
+                    table: options.tables(),
                 }
                 .to_string()
             }
-            FieldType::RecursiveTypeAlias(name) => name.to_owned(),
+            FieldType::RecursiveTypeAlias(name) => options.reference(name),
             FieldType::List(inner) => {
                 let is_recursive = match inner.as_ref() {
-                    FieldType::Class(nested_class) => self.recursive_classes.contains(nested_class),
+                    // Hoisted classes are referred to by name too.
+                    FieldType::Class(nested_class) => {
+                        (self.recursive_classes.contains(nested_class)
+                            && render_state.inlined_recursion >= options.recursion.inline_depth)
+                            || self.rendering(nested_class).hoisted
+                            || render_state.shared_classes.contains_key(nested_class)
+                            || options.tables()
+                    }
                     FieldType::RecursiveTypeAlias(name) => {
                         self.structural_recursive_aliases.contains_key(name)
                     }
@@ -520,7 +1103,19 @@ impl OutputFormatContent {
                 let inner_str =
                     self.render_possibly_recursive_type(options, inner, render_state, false)?;
 
-                if !is_recursive
+                if options.compact() {
+                    let has_alternatives = match inner.as_ref() {
+                        FieldType::Union(_) | FieldType::Optional(_) => true,
+                        // Small enums are inlined as their values.
+                        FieldType::Enum(_) => inner_str.contains(options.or_splitter()),
+                        _ => false,
+                    };
+                    if has_alternatives {
+                        format!("({inner_str})[]")
+                    } else {
+                        format!("{inner_str}[]")
+                    }
+                } else if !is_recursive
                     && match inner.as_ref() {
                         FieldType::Primitive(_) => false,
                         FieldType::Optional(t) => !t.is_primitive(),
@@ -539,14 +1134,14 @@ impl OutputFormatContent {
                 .iter()
                 .map(|t| self.render_possibly_recursive_type(options, t, render_state, false))
                 .collect::<Result<Vec<_>, minijinja::Error>>()?
-                .join(&options.or_splitter),
+                .join(options.or_splitter()),
             FieldType::Optional(inner) => {
                 let inner_str =
                     self.render_possibly_recursive_type(options, inner, render_state, false)?;
                 if inner.is_optional() {
                     inner_str
                 } else {
-                    format!("{inner_str}{}null", options.or_splitter)
+                    format!("{inner_str}{}null", options.or_splitter())
                 }
             }
             FieldType::Tuple(_) => {
@@ -582,9 +1177,10 @@ impl OutputFormatContent {
     ) -> Result<Option<String>, minijinja::Error> {
         let prefix = self.prefix(&options);
 
-        let mut render_state = RenderState {
-            hoisted_enums: IndexSet::new(),
-        };
+        let mut render_state = RenderState::default();
+        if options.shared_definitions {
+            render_state.shared_classes = self.shared_definitions(&options)?;
+        }
 
         let mut message = match &self.target {
             FieldType::Primitive(TypeValue::String) if prefix.is_none() => None,
@@ -598,6 +1194,9 @@ impl OutputFormatContent {
 
                 Some(self.enum_to_string(enm, &options))
             }
+            FieldType::Union(variants) if options.union_style == UnionStyle::Variants => {
+                Some(self.variants_render(&options, variants, &mut render_state)?)
+            }
             _ => Some(self.inner_type_render(&options, &self.target, &mut render_state, false)?),
         };
 
@@ -605,10 +1204,38 @@ impl OutputFormatContent {
         // entire schema which should already be hoisted.
         if let FieldType::Class(class) = &self.target {
             if self.recursive_classes.contains(class) {
-                message = Some(class.to_owned());
+                message = Some(options.reference(class));
             }
         }
 
+        // Tables start on a line of their own.
+        let name_end = if options.tables() { ":\n" } else { " " };
+        let hoisted_definition = |class_name: &str, schema: String| {
+            match &options.hoisted_class_prefix {
+                RenderSetting::Always(prefix) if !prefix.is_empty() => {
+                    format!("{prefix} {class_name}{name_end}{schema}")
+                }
+                _ => format!("{class_name}{name_end}{schema}"),
+            }
+        };
+
+        // Classes hoisted with `@@render`, including those only referred to by other hoisted
+        // classes, before enums so that their enums are hoisted too.
+        let mut hoisted_class_definitions = Vec::new();
+        while let Some(class_name) = render_state
+            .hoisted_classes
+            .get_index(hoisted_class_definitions.len())
+            .cloned()
+        {
+            let schema = self.inner_type_render(
+                &options,
+                &FieldType::Class(class_name.clone()),
+                &mut render_state,
+                false,
+            )?;
+            hoisted_class_definitions.push(hoisted_definition(&class_name, schema));
+        }
+
         let enum_definitions = Vec::from_iter(render_state.hoisted_enums.iter().map(|e| {
             let enm = self.enums.get(e).expect("Enum not found"); // TODO: Jinja Err
             self.enum_to_string(enm, &options)
@@ -629,13 +1256,9 @@ impl OutputFormatContent {
                 false,
             )?;
 
-            class_definitions.push(match &options.hoisted_class_prefix {
-                RenderSetting::Always(prefix) if !prefix.is_empty() => {
-                    format!("{prefix} {class_name} {schema}")
-                }
-                _ => format!("{class_name} {schema}"),
-            });
+            class_definitions.push(hoisted_definition(class_name, schema));
         }
+        class_definitions.extend(hoisted_class_definitions);
 
         for (alias, target) in self.structural_recursive_aliases.iter() {
             let recursive_pointer =
@@ -650,20 +1273,26 @@ impl OutputFormatContent {
         }
 
         let mut output = String::new();
+        // Markdown tables only end at a blank line.
+        let blank_line = if options.compact() && !options.tables() {
+            "\n"
+        } else {
+            "\n\n"
+        };
 
         if !enum_definitions.is_empty() {
-            output.push_str(&enum_definitions.join("\n\n"));
-            output.push_str("\n\n");
+            output.push_str(&enum_definitions.join(blank_line));
+            output.push_str(blank_line);
         }
 
         if !class_definitions.is_empty() {
-            output.push_str(&class_definitions.join("\n\n"));
-            output.push_str("\n\n");
+            output.push_str(&class_definitions.join(blank_line));
+            output.push_str(blank_line);
         }
 
         if !type_alias_definitions.is_empty() {
             output.push_str(&type_alias_definitions.join("\n"));
-            output.push_str("\n\n");
+            output.push_str(blank_line);
         }
 
         if let Some(p) = prefix {
@@ -711,6 +1340,16 @@ impl OutputFormatContent {
             .ok_or_else(|| anyhow::anyhow!("Class {name} not found"))
     }
 
+    /// The `@@render` overrides of the class `name`, if any.
+    pub fn class_rendering(&self, name: &str) -> Option<&ClassRendering> {
+        self.class_rendering.get(name)
+    }
+
+    /// How the class `name` is rendered: its overrides, or the defaults.
+    fn rendering(&self, name: &str) -> ClassRendering {
+        self.class_rendering(name).cloned().unwrap_or_default()
+    }
+
     pub fn find_recursive_alias_target(&self, name: &str) -> Result<&FieldType> {
         self.structural_recursive_aliases
             .get(name)
@@ -760,11 +1399,24 @@ mod tests {
         let enums = vec![Enum {
             name: Name::new("Color".to_string()),
             values: vec![
-                (Name::new("Red".to_string()), None),
-                (Name::new("Green".to_string()), None),
-                (Name::new("Blue".to_string()), None),
+                (
+                    Name::new("Red".to_string()),
+                    None,
+                    EnumValueMetadata::default(),
+                ),
+                (
+                    Name::new("Green".to_string()),
+                    None,
+                    EnumValueMetadata::default(),
+                ),
+                (
+                    Name::new("Blue".to_string()),
+                    None,
+                    EnumValueMetadata::default(),
+                ),
             ],
             constraints: Vec::new(),
+            open: false,
         }];
 
         let content = OutputFormatContent::target(FieldType::Enum("Color".to_string()))
@@ -779,6 +1431,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn render_enum_with_groups() {
+        let value = |name: &str, group: Option<&str>| {
+            (
+                Name::new(name.to_string()),
+                None,
+                EnumValueMetadata {
+                    group: group.map(str::to_string),
+                    ..Default::default()
+                },
+            )
+        };
+        let enums = vec![Enum {
+            name: Name::new("Food".to_string()),
+            values: vec![
+                value("Apple", Some("Fruit")),
+                value("Carrot", Some("Vegetable")),
+                value("Other", None),
+                value("Banana", Some("Fruit")),
+            ],
+            constraints: Vec::new(),
+            open: false,
+        }];
+
+        let content = OutputFormatContent::target(FieldType::Enum("Food".to_string()))
+            .enums(enums)
+            .build();
+        let rendered = content.render(RenderOptions::default()).unwrap();
+        assert_eq!(
+            rendered,
+            Some(String::from(
+                "Answer with any of the categories:\nFood\n----\n- Other\nFruit:\n- Apple\n- Banana\nVegetable:\n- Carrot"
+            ))
+        );
+    }
+
+    #[test]
+    fn render_open_enum() {
+        let enums = vec![Enum {
+            name: Name::new("Color".to_string()),
+            values: vec![
+                (
+                    Name::new("Red".to_string()),
+                    None,
+                    EnumValueMetadata::default(),
+                ),
+                (
+                    Name::new("Green".to_string()),
+                    None,
+                    EnumValueMetadata::default(),
+                ),
+            ],
+            constraints: Vec::new(),
+            open: true,
+        }];
+
+        let content = OutputFormatContent::target(FieldType::Enum("Color".to_string()))
+            .enums(enums)
+            .build();
+        let rendered = content.render(RenderOptions::default()).unwrap();
+        assert_eq!(
+            rendered,
+            Some(String::from(
+                "Answer with any of the categories:\nColor\n----\n- Red\n- Green\n- or any other value"
+            ))
+        );
+    }
+
     #[test]
     fn render_class() {
         let classes = vec![Class {
@@ -788,24 +1508,441 @@ mod tests {
                     Name::new("name".to_string()),
                     FieldType::string(),
                     Some("The person's name".to_string()),
+                    FieldMetadata::default(),
                 ),
                 (
                     Name::new("age".to_string()),
                     FieldType::int(),
                     Some("The person's age".to_string()),
+                    FieldMetadata::default(),
+                ),
+            ],
+            constraints: Vec::new(),
+            one_of: Vec::new(),
+        }];
+
+        let content = OutputFormatContent::target(FieldType::class("Person"))
+            .classes(classes)
+            .build();
+        let rendered = content.render(RenderOptions::default()).unwrap();
+        assert_eq!(
+            rendered,
+            Some(String::from(
+                "Answer in JSON using this schema:\n{\n  // The person's name\n  name: string,\n  // The person's age\n  age: int,\n}"
+            ))
+        );
+    }
+
+    #[test]
+    fn render_class_with_field_order() {
+        let classes = vec![Class {
+            name: Name::new("Ticket".to_string()),
+            fields: vec![
+                (
+                    Name::new("notes".to_string()),
+                    FieldType::optional(FieldType::string()),
+                    None,
+                    FieldMetadata::default(),
+                ),
+                (
+                    Name::new("title".to_string()),
+                    FieldType::string(),
+                    None,
+                    FieldMetadata::default(),
+                ),
+                (
+                    Name::new("id".to_string()),
+                    FieldType::int(),
+                    None,
+                    FieldMetadata::default(),
+                ),
+            ],
+            constraints: Vec::new(),
+            one_of: Vec::new(),
+        }];
+
+        let content = OutputFormatContent::target(FieldType::class("Ticket"))
+            .classes(classes)
+            .build();
+        let render = |field_order| {
+            content
+                .render(RenderOptions::default().with_field_order(field_order))
+                .unwrap()
+                .unwrap()
+        };
+        assert_eq!(
+            render(FieldOrder::Declaration),
+            "Answer in JSON using this schema:\n{\n  notes: string or null,\n  title: string,\n  id: int,\n}"
+        );
+        assert_eq!(
+            render(FieldOrder::Alphabetical),
+            "Answer in JSON using this schema:\n{\n  id: int,\n  notes: string or null,\n  title: string,\n}"
+        );
+        assert_eq!(
+            render(FieldOrder::RequiredFirst),
+            "Answer in JSON using this schema:\n{\n  title: string,\n  id: int,\n  notes: string or null,\n}"
+        );
+    }
+
+    #[test]
+    fn render_compact_style() {
+        let enums = vec![
+            Enum {
+                name: Name::new("Color".to_string()),
+                values: vec![
+                    (
+                        Name::new("Red".to_string()),
+                        None,
+                        EnumValueMetadata::default(),
+                    ),
+                    (
+                        Name::new("Green".to_string()),
+                        Some("Like\ngrass".to_string()),
+                        EnumValueMetadata::default(),
+                    ),
+                    (
+                        Name::new("Blue".to_string()),
+                        None,
+                        EnumValueMetadata::default(),
+                    ),
+                ],
+                constraints: Vec::new(),
+                open: false,
+            },
+            Enum {
+                name: Name::new("Size".to_string()),
+                values: vec![
+                    (
+                        Name::new("S".to_string()),
+                        None,
+                        EnumValueMetadata::default(),
+                    ),
+                    (
+                        Name::new("M".to_string()),
+                        None,
+                        EnumValueMetadata::default(),
+                    ),
+                ],
+                constraints: Vec::new(),
+                open: false,
+            },
+        ];
+        let classes = vec![Class {
+            name: Name::new("Item".to_string()),
+            fields: vec![
+                (
+                    Name::new("name".to_string()),
+                    FieldType::string(),
+                    Some("The item's name".to_string()),
+                    FieldMetadata::default(),
+                ),
+                (
+                    Name::new("color".to_string()),
+                    FieldType::r#enum("Color"),
+                    None,
+                    FieldMetadata::default(),
+                ),
+                (
+                    Name::new("sizes".to_string()),
+                    FieldType::list(FieldType::r#enum("Size")),
+                    None,
+                    FieldMetadata::default(),
+                ),
+                (
+                    Name::new("note".to_string()),
+                    FieldType::optional(FieldType::string()),
+                    None,
+                    FieldMetadata::default(),
+                ),
+            ],
+            constraints: Vec::new(),
+            one_of: Vec::new(),
+        }];
+
+        let content = OutputFormatContent::target(FieldType::class("Item"))
+            .enums(enums)
+            .classes(classes)
+            .build();
+        let rendered = content
+            .render(RenderOptions::default().with_style(RenderStyle::Compact))
+            .unwrap();
+        assert_eq!(
+            rendered,
+            Some(String::from(
+                "Color: Red|Green /* Like grass */|Blue\nAnswer in JSON using this schema:\n{name: str /* The item's name */, color: Color, sizes: ('S'|'M')[], note: str|null}"
+            ))
+        );
+    }
+
+    #[test]
+    fn render_class_with_one_of() {
+        let classes = vec![Class {
+            name: Name::new("Contact".to_string()),
+            fields: vec![
+                (
+                    Name::new("email".to_string()),
+                    FieldType::optional(FieldType::string()),
+                    None,
+                    FieldMetadata::default(),
+                ),
+                (
+                    Name::new_with_alias("phone".to_string(), Some("phone_number".to_string())),
+                    FieldType::optional(FieldType::string()),
+                    None,
+                    FieldMetadata::default(),
                 ),
             ],
             constraints: Vec::new(),
+            one_of: vec![vec!["email".to_string(), "phone".to_string()]],
         }];
 
+        let content = OutputFormatContent::target(FieldType::class("Contact"))
+            .classes(classes)
+            .build();
+        let rendered = content.render(RenderOptions::default()).unwrap();
+        assert_eq!(
+            rendered,
+            Some("Answer in JSON using this schema:\n{\n  email: string or null,\n  phone_number: string or null,\n  // Set exactly one of: email, phone_number\n}".to_string())
+        );
+    }
+
+    #[test]
+    fn render_class_with_rendering_overrides() {
+        let classes = vec![
+            Class {
+                name: Name::new("Address".to_string()),
+                fields: vec![
+                    (
+                        Name::new("street".to_string()),
+                        FieldType::string(),
+                        Some("Street line".to_string()),
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("city".to_string()),
+                        FieldType::string(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
+                ],
+                constraints: Vec::new(),
+                one_of: Vec::new(),
+            },
+            Class {
+                name: Name::new("Person".to_string()),
+                fields: vec![
+                    (
+                        Name::new("home".to_string()),
+                        FieldType::class("Address"),
+                        None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("past".to_string()),
+                        FieldType::list(FieldType::class("Address")),
+                        None,
+                        FieldMetadata::default(),
+                    ),
+                ],
+                constraints: Vec::new(),
+                one_of: Vec::new(),
+            },
+        ];
+
+        let content = OutputFormatContent::target(FieldType::class("Person"))
+            .classes(classes)
+            .class_rendering(IndexMap::from([(
+                "Address".to_string(),
+                ClassRendering {
+                    hoisted: true,
+                    field_separator: Some(";".to_string()),
+                    descriptions: false,
+                },
+            )]))
+            .build();
+        let rendered = content.render(RenderOptions::default()).unwrap();
+        assert_eq!(
+            rendered,
+            Some("Address {\n  street: string;\n  city: string;\n}\n\nAnswer in JSON using this schema:\n{\n  home: Address,\n  past: Address[],\n}".to_string())
+        );
+    }
+
+    #[test]
+    fn render_repeated_shapes_as_shared_definitions() {
+        let address_fields = || {
+            vec![
+                (
+                    Name::new("street".to_string()),
+                    FieldType::string(),
+                    None,
+                    FieldMetadata::default(),
+                ),
+                (
+                    Name::new("city".to_string()),
+                    FieldType::string(),
+                    None,
+                    FieldMetadata::default(),
+                ),
+            ]
+        };
+        let classes = || {
+            vec![
+                Class {
+                    name: Name::new("Address".to_string()),
+                    fields: address_fields(),
+                    constraints: Vec::new(),
+                    one_of: Vec::new(),
+                },
+                Class {
+                    name: Name::new("Location".to_string()),
+                    fields: address_fields(),
+                    constraints: Vec::new(),
+                    one_of: Vec::new(),
+                },
+                Class {
+                    name: Name::new("Order".to_string()),
+                    fields: vec![
+                        (
+                            Name::new("shipping".to_string()),
+                            FieldType::class("Address"),
+                            None,
+                            FieldMetadata::default(),
+                        ),
+                        (
+                            Name::new("stops".to_string()),
+                            FieldType::list(FieldType::class("Location")),
+                            None,
+                            FieldMetadata::default(),
+                        ),
+                        (
+                            Name::new("note".to_string()),
+                            FieldType::string(),
+                            None,
+                            FieldMetadata::default(),
+                        ),
+                    ],
+                    constraints: Vec::new(),
+                    one_of: Vec::new(),
+                },
+            ]
+        };
+
+        let content = OutputFormatContent::target(FieldType::class("Order"))
+            .classes(classes())
+            .build();
+        let rendered = content
+            .render(RenderOptions::default().with_shared_definitions(true))
+            .unwrap();
+        assert_eq!(
+            rendered,
+            Some("Address {\n  street: string,\n  city: string,\n}\n\nAnswer in JSON using this schema:\n{\n  shipping: Address,\n  stops: Address[],\n  note: string,\n}".to_string())
+        );
+
+        // A class hoisted by `@@render` keeps its own definition.
+        let content = OutputFormatContent::target(FieldType::class("Order"))
+            .classes(classes())
+            .class_rendering(IndexMap::from([(
+                "Address".to_string(),
+                ClassRendering {
+                    hoisted: true,
+                    ..Default::default()
+                },
+            )]))
+            .build();
+        assert_eq!(
+            content
+                .render(RenderOptions::default().with_shared_definitions(true))
+                .unwrap(),
+            Some("Address {\n  street: string,\n  city: string,\n}\n\nAnswer in JSON using this schema:\n{\n  shipping: Address,\n  stops: [\n    {\n      street: string,\n      city: string,\n    }\n  ],\n  note: string,\n}".to_string())
+        );
+
+        // A shape written out once stays in place.
+        let content = OutputFormatContent::target(FieldType::class("Address"))
+            .classes(vec![Class {
+                name: Name::new("Address".to_string()),
+                fields: address_fields(),
+                constraints: Vec::new(),
+                one_of: Vec::new(),
+            }])
+            .build();
+        assert_eq!(
+            content
+                .render(RenderOptions::default().with_shared_definitions(true))
+                .unwrap(),
+            content.render(RenderOptions::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn render_classes_as_markdown_tables() {
+        let classes = vec![
+            Class {
+                name: Name::new("Address".to_string()),
+                fields: vec![
+                    (
+                        Name::new("street".to_string()),
+                        FieldType::string(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("city".to_string()),
+                        FieldType::string(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
+                ],
+                constraints: Vec::new(),
+                one_of: Vec::new(),
+            },
+            Class {
+                name: Name::new("Person".to_string()),
+                fields: vec![
+                    (
+                        Name::new("name".to_string()),
+                        FieldType::string(),
+                        Some("First | last\nname".to_string()),
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("home".to_string()),
+                        FieldType::class("Address"),
+                        None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("past".to_string()),
+                        FieldType::list(FieldType::class("Address")),
+                        None,
+                        FieldMetadata::default(),
+                    ),
+                ],
+                constraints: Vec::new(),
+                one_of: Vec::new(),
+            },
+        ];
+
         let content = OutputFormatContent::target(FieldType::class("Person"))
             .classes(classes)
             .build();
-        let rendered = content.render(RenderOptions::default()).unwrap();
+        let options = RenderOptions::default().with_format(RenderFormat::MarkdownTable);
+        let rendered = content.render(options).unwrap();
+        #[rustfmt::skip]
         assert_eq!(
             rendered,
             Some(String::from(
-                "Answer in JSON using this schema:\n{\n  // The person's name\n  name: string,\n  // The person's age\n  age: int,\n}"
+r#"Address:
+| Field | Type | Description |
+| --- | --- | --- |
+| street | string |  |
+| city | string |  |
+
+Answer in JSON using this schema:
+| Field | Type | Description |
+| --- | --- | --- |
+| name | string | First \| last name |
+| home | Address |  |
+| past | Address[] |  |"#
             ))
         );
     }
@@ -819,15 +1956,23 @@ mod tests {
                     Name::new("school".to_string()),
                     FieldType::optional(FieldType::string()),
                     Some("111\n  ".to_string()),
+                    FieldMetadata::default(),
                 ),
                 (
                     Name::new("degree".to_string()),
                     FieldType::string(),
                     Some("2222222".to_string()),
+                    FieldMetadata::default(),
+                ),
+                (
+                    Name::new("year".to_string()),
+                    FieldType::int(),
+                    None,
+                    FieldMetadata::default(),
                 ),
-                (Name::new("year".to_string()), FieldType::int(), None),
             ],
             constraints: Vec::new(),
+            one_of: Vec::new(),
         }];
 
         let content = OutputFormatContent::target(FieldType::class("Education"))
@@ -852,30 +1997,55 @@ mod tests {
                         Name::new("description".to_string()),
                         FieldType::string(),
                         None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("severity".to_string()),
+                        FieldType::string(),
+                        None,
+                        FieldMetadata::default(),
                     ),
-                    (Name::new("severity".to_string()), FieldType::string(), None),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("Enhancement".to_string()),
                 fields: vec![
-                    (Name::new("title".to_string()), FieldType::string(), None),
+                    (
+                        Name::new("title".to_string()),
+                        FieldType::string(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("description".to_string()),
                         FieldType::string(),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("Documentation".to_string()),
                 fields: vec![
-                    (Name::new("module".to_string()), FieldType::string(), None),
-                    (Name::new("format".to_string()), FieldType::string(), None),
+                    (
+                        Name::new("module".to_string()),
+                        FieldType::string(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("format".to_string()),
+                        FieldType::string(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
@@ -906,6 +2076,54 @@ r#"Answer in JSON using any of these schemas:
         );
     }
 
+    #[test]
+    fn render_top_level_union_as_variants() {
+        let classes = vec![Class {
+            name: Name::new("Bug".to_string()),
+            fields: vec![
+                (
+                    Name::new("title".to_string()),
+                    FieldType::string(),
+                    None,
+                    FieldMetadata::default(),
+                ),
+                (
+                    Name::new("severity".to_string()),
+                    FieldType::int(),
+                    None,
+                    FieldMetadata::default(),
+                ),
+            ],
+            constraints: Vec::new(),
+            one_of: Vec::new(),
+        }];
+
+        let content = OutputFormatContent::target(FieldType::Union(vec![
+            FieldType::class("Bug"),
+            FieldType::List(Box::new(FieldType::string())),
+        ]))
+        .classes(classes)
+        .build();
+        let options = RenderOptions::default().with_union_style(UnionStyle::Variants);
+        let rendered = content.render(options).unwrap();
+        #[rustfmt::skip]
+        assert_eq!(
+            rendered,
+            Some(String::from(
+r#"Answer in JSON using one of the following shapes:
+
+Shape 1 (Bug):
+{
+  title: string,
+  severity: int,
+}
+
+Shape 2:
+string[]"#
+            ))
+        );
+    }
+
     #[test]
     fn render_nested_union() {
         let classes = vec![
@@ -920,10 +2138,17 @@ r#"Answer in JSON using any of these schemas:
                             FieldType::class("Documentation"),
                         ]),
                         None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("date".to_string()),
+                        FieldType::string(),
+                        None,
+                        FieldMetadata::default(),
                     ),
-                    (Name::new("date".to_string()), FieldType::string(), None),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("Bug".to_string()),
@@ -932,30 +2157,55 @@ r#"Answer in JSON using any of these schemas:
                         Name::new("description".to_string()),
                         FieldType::string(),
                         None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("severity".to_string()),
+                        FieldType::string(),
+                        None,
+                        FieldMetadata::default(),
                     ),
-                    (Name::new("severity".to_string()), FieldType::string(), None),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("Enhancement".to_string()),
                 fields: vec![
-                    (Name::new("title".to_string()), FieldType::string(), None),
+                    (
+                        Name::new("title".to_string()),
+                        FieldType::string(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("description".to_string()),
                         FieldType::string(),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("Documentation".to_string()),
                 fields: vec![
-                    (Name::new("module".to_string()), FieldType::string(), None),
-                    (Name::new("format".to_string()), FieldType::string(), None),
+                    (
+                        Name::new("module".to_string()),
+                        FieldType::string(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("format".to_string()),
+                        FieldType::string(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
@@ -990,14 +2240,21 @@ r#"Answer in JSON using this schema:
         let classes = vec![Class {
             name: Name::new("Node".to_string()),
             fields: vec![
-                (Name::new("data".to_string()), FieldType::int(), None),
+                (
+                    Name::new("data".to_string()),
+                    FieldType::int(),
+                    None,
+                    FieldMetadata::default(),
+                ),
                 (
                     Name::new("next".to_string()),
                     FieldType::optional(FieldType::class("Node")),
                     None,
+                    FieldMetadata::default(),
                 ),
             ],
             constraints: Vec::new(),
+            one_of: Vec::new(),
         }];
 
         let content = OutputFormatContent::target(FieldType::class("Node"))
@@ -1025,14 +2282,21 @@ Answer in JSON using this schema: Node"#
             Class {
                 name: Name::new("Node".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("next".to_string()),
                         FieldType::optional(FieldType::class("Node")),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("LinkedList".to_string()),
@@ -1041,10 +2305,17 @@ Answer in JSON using this schema: Node"#
                         Name::new("head".to_string()),
                         FieldType::optional(FieldType::class("Node")),
                         None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("len".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
                     ),
-                    (Name::new("len".to_string()), FieldType::int(), None),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
@@ -1071,6 +2342,58 @@ Answer in JSON using this schema:
         );
     }
 
+    #[test]
+    fn recursive_class_inlined_to_depth_with_markers() {
+        let classes = vec![Class {
+            name: Name::new("Tree".to_string()),
+            fields: vec![
+                (
+                    Name::new("value".to_string()),
+                    FieldType::int(),
+                    None,
+                    FieldMetadata::default(),
+                ),
+                (
+                    Name::new("children".to_string()),
+                    FieldType::list(FieldType::class("Tree")),
+                    None,
+                    FieldMetadata::default(),
+                ),
+            ],
+            constraints: Vec::new(),
+            one_of: Vec::new(),
+        }];
+
+        let content = OutputFormatContent::target(FieldType::class("Tree"))
+            .classes(classes)
+            .recursive_classes(IndexSet::from_iter(["Tree".to_string()]))
+            .build();
+        let recursion = RecursionRendering {
+            inline_depth: 1,
+            references: ReferenceStyle::Marker,
+        };
+        let rendered = content
+            .render(RenderOptions::default().with_recursion(recursion))
+            .unwrap();
+        #[rustfmt::skip]
+        assert_eq!(
+            rendered,
+            Some(String::from(
+r#"Tree {
+  value: int,
+  children: [
+    {
+      value: int,
+      children: #/Tree[],
+    }
+  ],
+}
+
+Answer in JSON using this schema: #/Tree"#
+            ))
+        );
+    }
+
     #[test]
     fn top_level_recursive_cycle() {
         let classes = vec![
@@ -1080,8 +2403,10 @@ Answer in JSON using this schema:
                     Name::new("pointer".to_string()),
                     FieldType::class("B"),
                     None,
+                    FieldMetadata::default(),
                 )],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("B".to_string()),
@@ -1089,8 +2414,10 @@ Answer in JSON using this schema:
                     Name::new("pointer".to_string()),
                     FieldType::class("C"),
                     None,
+                    FieldMetadata::default(),
                 )],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("C".to_string()),
@@ -1098,8 +2425,10 @@ Answer in JSON using this schema:
                     Name::new("pointer".to_string()),
                     FieldType::optional(FieldType::class("A")),
                     None,
+                    FieldMetadata::default(),
                 )],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
@@ -1140,8 +2469,10 @@ Answer in JSON using this schema: A"#
                     Name::new("pointer".to_string()),
                     FieldType::class("B"),
                     None,
+                    FieldMetadata::default(),
                 )],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("B".to_string()),
@@ -1149,8 +2480,10 @@ Answer in JSON using this schema: A"#
                     Name::new("pointer".to_string()),
                     FieldType::class("C"),
                     None,
+                    FieldMetadata::default(),
                 )],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("C".to_string()),
@@ -1158,8 +2491,10 @@ Answer in JSON using this schema: A"#
                     Name::new("pointer".to_string()),
                     FieldType::optional(FieldType::class("A")),
                     None,
+                    FieldMetadata::default(),
                 )],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("NonRecursive".to_string()),
@@ -1168,11 +2503,23 @@ Answer in JSON using this schema: A"#
                         Name::new("pointer".to_string()),
                         FieldType::class("A"),
                         None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("field".to_string()),
+                        FieldType::bool(),
+                        None,
+                        FieldMetadata::default(),
                     ),
-                    (Name::new("data".to_string()), FieldType::int(), None),
-                    (Name::new("field".to_string()), FieldType::bool(), None),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
@@ -1219,14 +2566,17 @@ Answer in JSON using this schema:
                         Name::new("pointer".to_string()),
                         FieldType::class("B"),
                         None,
+                        FieldMetadata::default(),
                     ),
                     (
                         Name::new("nested".to_string()),
                         FieldType::class("Nested"),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("B".to_string()),
@@ -1234,8 +2584,10 @@ Answer in JSON using this schema:
                     Name::new("pointer".to_string()),
                     FieldType::class("C"),
                     None,
+                    FieldMetadata::default(),
                 )],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("C".to_string()),
@@ -1243,8 +2595,10 @@ Answer in JSON using this schema:
                     Name::new("pointer".to_string()),
                     FieldType::optional(FieldType::class("A")),
                     None,
+                    FieldMetadata::default(),
                 )],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("NonRecursive".to_string()),
@@ -1253,19 +2607,42 @@ Answer in JSON using this schema:
                         Name::new("pointer".to_string()),
                         FieldType::class("A"),
                         None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("field".to_string()),
+                        FieldType::bool(),
+                        None,
+                        FieldMetadata::default(),
                     ),
-                    (Name::new("data".to_string()), FieldType::int(), None),
-                    (Name::new("field".to_string()), FieldType::bool(), None),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("Nested".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
-                    (Name::new("field".to_string()), FieldType::bool(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("field".to_string()),
+                        FieldType::bool(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
@@ -1312,14 +2689,21 @@ Answer in JSON using this schema:
             Class {
                 name: Name::new("Tree".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("children".to_string()),
                         FieldType::class("Forest"),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("Forest".to_string()),
@@ -1327,8 +2711,10 @@ Answer in JSON using this schema:
                     Name::new("trees".to_string()),
                     FieldType::list(FieldType::class("Tree")),
                     None,
+                    FieldMetadata::default(),
                 )],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
@@ -1369,8 +2755,10 @@ Answer in JSON using this schema: Tree"#
                     FieldType::optional(FieldType::class("SelfReferential")),
                 ]),
                 None,
+                FieldMetadata::default(),
             )],
             constraints: Vec::new(),
+            one_of: Vec::new(),
         }];
 
         let content = OutputFormatContent::target(FieldType::class("SelfReferential"))
@@ -1399,26 +2787,40 @@ Answer in JSON using this schema: SelfReferential"#
             Class {
                 name: Name::new("Node".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("next".to_string()),
                         FieldType::optional(FieldType::class("Node")),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("Tree".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("children".to_string()),
                         FieldType::list(FieldType::class("Tree")),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
@@ -1462,39 +2864,61 @@ Node or Tree"#
                         Name::new("data_type".to_string()),
                         FieldType::Union(vec![FieldType::class("Node"), FieldType::class("Tree")]),
                         None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("len".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
                     ),
-                    (Name::new("len".to_string()), FieldType::int(), None),
                     (
                         Name::new("description".to_string()),
                         FieldType::string(),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("Node".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("next".to_string()),
                         FieldType::optional(FieldType::class("Node")),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("Tree".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("children".to_string()),
                         FieldType::list(FieldType::class("Tree")),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
@@ -1535,34 +2959,59 @@ Answer in JSON using this schema:
             Class {
                 name: Name::new("Node".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("next".to_string()),
                         FieldType::optional(FieldType::class("Node")),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("Tree".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("children".to_string()),
                         FieldType::list(FieldType::class("Tree")),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("NonRecursive".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
-                    (Name::new("tag".to_string()), FieldType::string(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("tag".to_string()),
+                        FieldType::string(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
@@ -1614,47 +3063,80 @@ Node or Tree or {
                             FieldType::class("NonRecursive"),
                         ]),
                         None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("len".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
                     ),
-                    (Name::new("len".to_string()), FieldType::int(), None),
                     (
                         Name::new("description".to_string()),
                         FieldType::string(),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("Node".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("next".to_string()),
                         FieldType::optional(FieldType::class("Node")),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("Tree".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("children".to_string()),
                         FieldType::list(FieldType::class("Tree")),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("NonRecursive".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
-                    (Name::new("tag".to_string()), FieldType::string(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("tag".to_string()),
+                        FieldType::string(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
@@ -1701,8 +3183,10 @@ Answer in JSON using this schema:
                     Name::new("pointer".to_string()),
                     FieldType::class("B"),
                     None,
+                    FieldMetadata::default(),
                 )],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("B".to_string()),
@@ -1710,8 +3194,10 @@ Answer in JSON using this schema:
                     Name::new("pointer".to_string()),
                     FieldType::class("C"),
                     None,
+                    FieldMetadata::default(),
                 )],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("C".to_string()),
@@ -1719,8 +3205,10 @@ Answer in JSON using this schema:
                     Name::new("pointer".to_string()),
                     FieldType::optional(FieldType::class("A")),
                     None,
+                    FieldMetadata::default(),
                 )],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("NonRecursive".to_string()),
@@ -1729,11 +3217,23 @@ Answer in JSON using this schema:
                         Name::new("pointer".to_string()),
                         FieldType::class("A"),
                         None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("field".to_string()),
+                        FieldType::bool(),
+                        None,
+                        FieldMetadata::default(),
                     ),
-                    (Name::new("data".to_string()), FieldType::int(), None),
-                    (Name::new("field".to_string()), FieldType::bool(), None),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
@@ -1778,26 +3278,40 @@ Answer in JSON using this interface:
             Class {
                 name: Name::new("Node".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("next".to_string()),
                         FieldType::optional(FieldType::class("Node")),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("Tree".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("children".to_string()),
                         FieldType::list(FieldType::class("Tree")),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
@@ -1837,26 +3351,40 @@ Node or int or string or Tree"#
             Class {
                 name: Name::new("Node".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("next".to_string()),
                         FieldType::optional(FieldType::class("Node")),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("Tree".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("children".to_string()),
                         FieldType::list(FieldType::class("Tree")),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("NonRecursive".to_string()),
@@ -1868,11 +3396,23 @@ Node or int or string or Tree"#
                             FieldType::Union(vec![FieldType::string(), FieldType::class("Tree")]),
                         ]),
                         None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("field".to_string()),
+                        FieldType::bool(),
+                        None,
+                        FieldMetadata::default(),
                     ),
-                    (Name::new("data".to_string()), FieldType::int(), None),
-                    (Name::new("field".to_string()), FieldType::bool(), None),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
@@ -1912,14 +3452,21 @@ Answer in JSON using this schema:
         let classes = vec![Class {
             name: Name::new("Node".to_string()),
             fields: vec![
-                (Name::new("data".to_string()), FieldType::int(), None),
+                (
+                    Name::new("data".to_string()),
+                    FieldType::int(),
+                    None,
+                    FieldMetadata::default(),
+                ),
                 (
                     Name::new("next".to_string()),
                     FieldType::optional(FieldType::class("Node")),
                     None,
+                    FieldMetadata::default(),
                 ),
             ],
             constraints: Vec::new(),
+            one_of: Vec::new(),
         }];
 
         let content = OutputFormatContent::target(FieldType::list(FieldType::class("Node")))
@@ -1950,8 +3497,10 @@ Node[]"#
                 Name::new("data".to_string()),
                 FieldType::map(FieldType::string(), FieldType::class("RecursiveMap")),
                 None,
+                FieldMetadata::default(),
             )],
             constraints: Vec::new(),
+            one_of: Vec::new(),
         }];
 
         let content = OutputFormatContent::target(FieldType::class("RecursiveMap"))
@@ -1981,8 +3530,10 @@ Answer in JSON using this schema: RecursiveMap"#
                     Name::new("data".to_string()),
                     FieldType::map(FieldType::string(), FieldType::class("RecursiveMap")),
                     None,
+                    FieldMetadata::default(),
                 )],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("NonRecursive".to_string()),
@@ -1990,8 +3541,10 @@ Answer in JSON using this schema: RecursiveMap"#
                     Name::new("rec_map".to_string()),
                     FieldType::Class("RecursiveMap".to_string()),
                     None,
+                    FieldMetadata::default(),
                 )],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
@@ -2021,14 +3574,21 @@ Answer in JSON using this schema:
         let classes = vec![Class {
             name: Name::new("Node".to_string()),
             fields: vec![
-                (Name::new("data".to_string()), FieldType::int(), None),
+                (
+                    Name::new("data".to_string()),
+                    FieldType::int(),
+                    None,
+                    FieldMetadata::default(),
+                ),
                 (
                     Name::new("next".to_string()),
                     FieldType::optional(FieldType::class("Node")),
                     None,
+                    FieldMetadata::default(),
                 ),
             ],
             constraints: Vec::new(),
+            one_of: Vec::new(),
         }];
 
         let content = OutputFormatContent::target(FieldType::map(
@@ -2063,20 +3623,29 @@ map<string, Node>"#
                     Name::new("data".to_string()),
                     FieldType::map(FieldType::string(), FieldType::class("Node")),
                     None,
+                    FieldMetadata::default(),
                 )],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("Node".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("next".to_string()),
                         FieldType::optional(FieldType::class("Node")),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
@@ -2114,20 +3683,29 @@ Answer in JSON using this schema:
                         FieldType::optional(FieldType::class("Node")),
                     ),
                     None,
+                    FieldMetadata::default(),
                 )],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("Node".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("next".to_string()),
                         FieldType::optional(FieldType::class("Node")),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
@@ -2159,22 +3737,40 @@ Answer in JSON using this schema:
             Class {
                 name: Name::new("Node".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("next".to_string()),
                         FieldType::optional(FieldType::class("Node")),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("NonRecursive".to_string()),
                 fields: vec![
-                    (Name::new("field".to_string()), FieldType::string(), None),
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("field".to_string()),
+                        FieldType::string(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
@@ -2224,28 +3820,48 @@ map<string, Node or int or {
                         ]),
                     ),
                     None,
+                    FieldMetadata::default(),
                 )],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("Node".to_string()),
                 fields: vec![
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                     (
                         Name::new("next".to_string()),
                         FieldType::optional(FieldType::class("Node")),
                         None,
+                        FieldMetadata::default(),
                     ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
             Class {
                 name: Name::new("NonRecursive".to_string()),
                 fields: vec![
-                    (Name::new("field".to_string()), FieldType::string(), None),
-                    (Name::new("data".to_string()), FieldType::int(), None),
+                    (
+                        Name::new("field".to_string()),
+                        FieldType::string(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
+                    (
+                        Name::new("data".to_string()),
+                        FieldType::int(),
+                        None,
+                        FieldMetadata::default(),
+                    ),
                 ],
                 constraints: Vec::new(),
+                one_of: Vec::new(),
             },
         ];
 
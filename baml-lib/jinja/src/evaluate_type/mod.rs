@@ -59,7 +59,8 @@ where
         })
         .collect::<Vec<_>>();
 
-    name_distances.sort_by_key(|k| k.0);
+    // Break ties by name: options often come from hash maps, whose order changes between runs.
+    name_distances.sort_by_key(|&(dist, idx)| (dist, options.index(idx).as_ref()));
 
     // Filter names based on the threshold
     let filtered_names = name_distances
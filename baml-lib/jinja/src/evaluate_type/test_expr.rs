@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use baml_types::LiteralValue;
 use minijinja::machinery::parse_expr;
 
@@ -232,6 +234,34 @@ fn test_call_function() {
     );
 }
 
+#[test]
+fn test_call_function_with_defaults() {
+    let mut types = PredefinedTypes::default(JinjaContext::Prompt);
+    types.add_function_with_defaults(
+        "Greet",
+        Type::String,
+        vec![
+            ("name".into(), Type::String),
+            ("greeting".into(), Type::String),
+        ],
+        HashSet::from(["greeting".to_string()]),
+    );
+
+    assert_eq!(assert_evaluates_to!("Greet('Ada')", &types), Type::String);
+    assert_eq!(
+        assert_evaluates_to!("Greet('Ada', greeting='Hi')", &types),
+        Type::String
+    );
+    assert_eq!(
+        assert_fails_to!("Greet('Ada', none)", &types),
+        vec!["Function 'Greet' expects argument 'greeting' to be of type string, but got none"]
+    );
+    assert_eq!(
+        assert_fails_to!("Greet()", &types),
+        vec!["Function 'Greet' expects 2 arguments, but got 0"]
+    );
+}
+
 #[test]
 fn test_output_format() {
     let types = PredefinedTypes::default(JinjaContext::Prompt);
@@ -239,6 +239,8 @@ enum Scope {
 #[derive(Debug)]
 pub struct PredefinedTypes {
     functions: HashMap<String, (Type, Vec<(String, Type)>)>,
+    /// The args of each function that have a default value, so calls can leave them out.
+    defaulted_args: HashMap<String, HashSet<String>>,
     classes: HashMap<String, HashMap<String, Type>>,
     /// TODO: See the comment for [`Type::AliasRef`].
     ///
@@ -362,6 +364,7 @@ impl PredefinedTypes {
                 JinjaContext::Parsing => Default::default(),
             },
             aliases: HashMap::new(),
+            defaulted_args: HashMap::new(),
             scopes: Vec::new(),
             errors: Vec::new(),
         }
@@ -471,6 +474,19 @@ impl PredefinedTypes {
         self.functions.insert(name.to_string(), (ret, args));
     }
 
+    /// Like [`add_function`](Self::add_function), with `defaulted` the args that have a default
+    /// value: calls can leave them out, but inside the function they keep their type.
+    pub fn add_function_with_defaults(
+        &mut self,
+        name: &str,
+        ret: Type,
+        args: Vec<(String, Type)>,
+        defaulted: HashSet<String>,
+    ) {
+        self.add_function(name, ret, args);
+        self.defaulted_args.insert(name.to_string(), defaulted);
+    }
+
     pub fn add_class(&mut self, name: &str, fields: HashMap<String, Type>) {
         self.classes.insert(name.to_string(), fields);
     }
@@ -545,9 +561,10 @@ impl PredefinedTypes {
         let mut errors = Vec::new();
 
         // Check how many args are required.
+        let defaulted = self.defaulted_args.get(func);
         let mut optional_args = vec![];
         for (name, t) in args.iter().rev() {
-            if !t.is_optional() {
+            if !t.is_optional() && !defaulted.is_some_and(|defaulted| defaulted.contains(name)) {
                 break;
             }
             optional_args.push(name);
@@ -598,16 +615,19 @@ impl PredefinedTypes {
                 }
             }
 
-            kwargs.iter().for_each(|(name, _)| {
-                if !args.iter().any(|(arg_name, _)| arg_name == name) {
-                    errors.push(TypeError::new_unknown_arg(
-                        func,
-                        span,
-                        name,
-                        unused_args.clone(),
-                    ));
-                }
-            });
+            let mut unknown_args = kwargs
+                .keys()
+                .filter(|name| !args.iter().any(|(arg_name, _)| arg_name == *name))
+                .collect::<Vec<_>>();
+            unknown_args.sort();
+            for name in unknown_args {
+                errors.push(TypeError::new_unknown_arg(
+                    func,
+                    span,
+                    name,
+                    unused_args.clone(),
+                ));
+            }
         }
         (ret.clone(), errors)
     }
@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+
+/// Words accepted as `bool` values besides `true` and `false`, e.g. `oui` / `non`.
+///
+/// Words are matched against the whole (trimmed) string, ignoring case. Adding a word to one
+/// side removes it from the other, so later additions win.
+#[derive(Debug, Clone, Default)]
+pub struct BoolVocabulary {
+    truthy: HashSet<String>,
+    falsy: HashSet<String>,
+}
+
+/// `(locale, truthy words, falsy words)`
+const LOCALES: &[(&str, &[&str], &[&str])] = &[
+    ("en", &["yes", "y", "on"], &["no", "n", "off"]),
+    ("fr", &["oui", "vrai"], &["non", "faux"]),
+    ("es", &["sí", "si", "verdadero"], &["no", "falso"]),
+    ("de", &["ja", "wahr"], &["nein", "falsch"]),
+    ("it", &["sì", "si", "vero"], &["no", "falso"]),
+    ("pt", &["sim", "verdadeiro"], &["não", "nao", "falso"]),
+];
+
+impl BoolVocabulary {
+    /// The locales [`with_locale`](Self::with_locale) knows.
+    pub fn locales() -> impl Iterator<Item = &'static str> {
+        LOCALES.iter().map(|(locale, ..)| *locale)
+    }
+
+    pub fn with_truthy<S: AsRef<str>>(mut self, words: impl IntoIterator<Item = S>) -> Self {
+        for word in words {
+            let word = normalize(word.as_ref());
+            self.falsy.remove(&word);
+            self.truthy.insert(word);
+        }
+        self
+    }
+
+    pub fn with_falsy<S: AsRef<str>>(mut self, words: impl IntoIterator<Item = S>) -> Self {
+        for word in words {
+            let word = normalize(word.as_ref());
+            self.truthy.remove(&word);
+            self.falsy.insert(word);
+        }
+        self
+    }
+
+    /// Adds the yes/no words of `locale` (`fr`, `es`, ...), see [`locales`](Self::locales).
+    pub fn with_locale(self, locale: &str) -> anyhow::Result<Self> {
+        let Some((_, truthy, falsy)) = LOCALES.iter().find(|(name, ..)| *name == locale) else {
+            anyhow::bail!(
+                "Unknown bool locale `{locale}`, expected one of {}",
+                Self::locales().collect::<Vec<_>>().join(", ")
+            );
+        };
+        Ok(self.with_truthy(*truthy).with_falsy(*falsy))
+    }
+
+    /// The words read as `true`, sorted.
+    pub fn truthy(&self) -> Vec<&str> {
+        sorted(&self.truthy)
+    }
+
+    /// The words read as `false`, sorted.
+    pub fn falsy(&self) -> Vec<&str> {
+        sorted(&self.falsy)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.truthy.is_empty() && self.falsy.is_empty()
+    }
+
+    /// The bool `word` stands for, if any.
+    pub(super) fn lookup(&self, word: &str) -> Option<bool> {
+        let word = normalize(word);
+        if self.truthy.contains(&word) {
+            Some(true)
+        } else if self.falsy.contains(&word) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+fn sorted(words: &HashSet<String>) -> Vec<&str> {
+    let mut words = words.iter().map(String::as_str).collect::<Vec<_>>();
+    words.sort_unstable();
+    words
+}
+
+fn normalize(word: &str) -> String {
+    word.trim().to_lowercase()
+}
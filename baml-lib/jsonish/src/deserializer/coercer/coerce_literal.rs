@@ -65,9 +65,10 @@ impl TypeCoercer for LiteralValue {
 
         match literal {
             LiteralValue::Int(literal_int) => {
+                // A preserved `Decimal` is out of `i64` range, so it cannot match either.
                 let BamlValueWithFlags::Int(coerced_int) = coerce_int(ctx, target, Some(value))?
                 else {
-                    unreachable!("coerce_int returned a non-integer value");
+                    return Err(ctx.error_unexpected_type(target, value));
                 };
 
                 if coerced_int.value() == literal_int {
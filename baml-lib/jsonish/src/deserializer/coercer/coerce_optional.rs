@@ -29,6 +29,10 @@ pub(super) fn coerce_optional(
     let mut flags = DeserializerConditions::new();
     match value {
         None | Some(crate::jsonish::Value::Null) => Ok(BamlValueWithFlags::Null(flags)),
+        Some(crate::jsonish::Value::String(s)) if ctx.is_null_synonym(s) => {
+            flags.add_flag(Flag::NullSynonym(s.clone()));
+            Ok(BamlValueWithFlags::Null(flags))
+        }
         Some(v) => match inner.coerce(ctx, optional_target, Some(v)) {
             Ok(v) => Ok(v),
             Err(e) => {
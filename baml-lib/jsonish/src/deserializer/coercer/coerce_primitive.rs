@@ -1,6 +1,11 @@
 use anyhow::Result;
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE},
+    Engine as _,
+};
 use baml_types::BamlMediaType;
 use internal_baml_core::ir::{FieldType, TypeValue};
+use internal_baml_jinja::types::IntOverflow;
 
 use crate::deserializer::{
     coercer::TypeCoercer,
@@ -9,7 +14,7 @@ use crate::deserializer::{
 };
 use regex::Regex;
 
-use super::{array_helper::coerce_array_to_singular, ParsingContext, ParsingError};
+use super::{array_helper::coerce_array_to_singular, NumberOverflow, ParsingContext, ParsingError};
 
 impl TypeCoercer for TypeValue {
     fn coerce(
@@ -37,6 +42,7 @@ impl TypeCoercer for TypeValue {
             TypeValue::Int => coerce_int(ctx, target, value),
             TypeValue::Float => coerce_float(ctx, target, value),
             TypeValue::Bool => coerce_bool(ctx, target, value),
+            TypeValue::Bytes => coerce_bytes(ctx, target, value),
             TypeValue::Null => coerce_null(ctx, target, value),
             TypeValue::Media(BamlMediaType::Image) => Err(ctx.error_image_not_supported()),
             TypeValue::Media(BamlMediaType::Audio) => Err(ctx.error_audio_not_supported()),
@@ -90,12 +96,12 @@ pub(super) fn coerce_int(
         crate::jsonish::Value::Number(n) => {
             if let Some(n) = n.as_i64() {
                 Ok(BamlValueWithFlags::Int(n.into()))
+            } else if is_integer_literal(&n.to_string()) {
+                overflowing_int(ctx, target, n, || coerce_lossy_int(ctx, target, value, n))
             } else if let Some(n) = n.as_u64() {
                 Ok(BamlValueWithFlags::Int((n as i64).into()))
             } else if let Some(n) = n.as_f64() {
-                Ok(BamlValueWithFlags::Int(
-                    ((n.round() as i64), Flag::FloatToInt(n)).into(),
-                ))
+                float_to_int(ctx, target, n)
             } else {
                 Err(ctx.error_unexpected_type(target, value))
             }
@@ -106,20 +112,20 @@ pub(super) fn coerce_int(
             let s = s.trim_end_matches(',');
             if let Ok(n) = s.parse::<i64>() {
                 Ok(BamlValueWithFlags::Int(n.into()))
+            } else if let Some(n) = s
+                .parse::<serde_json::Number>()
+                .ok()
+                .filter(|n| is_integer_literal(&n.to_string()))
+            {
+                overflowing_int(ctx, target, &n, || coerce_lossy_int(ctx, target, value, &n))
             } else if let Ok(n) = s.parse::<u64>() {
                 Ok(BamlValueWithFlags::Int((n as i64).into()))
             } else if let Ok(n) = s.parse::<f64>() {
-                Ok(BamlValueWithFlags::Int(
-                    ((n.round() as i64), Flag::FloatToInt(n)).into(),
-                ))
+                float_to_int(ctx, target, n)
             } else if let Some(frac) = float_from_maybe_fraction(s) {
-                Ok(BamlValueWithFlags::Int(
-                    ((frac.round() as i64), Flag::FloatToInt(frac)).into(),
-                ))
+                float_to_int(ctx, target, frac)
             } else if let Some(frac) = float_from_comma_separated(s) {
-                Ok(BamlValueWithFlags::Int(
-                    ((frac.round() as i64), Flag::FloatToInt(frac)).into(),
-                ))
+                float_to_int(ctx, target, frac)
             } else {
                 Err(ctx.error_unexpected_type(target, value))
             }
@@ -133,6 +139,177 @@ pub(super) fn coerce_int(
     }
 }
 
+/// The historical conversion of a number outside `i64` range: wrapping from `u64`, else saturating
+/// from the nearest `f64`.
+fn coerce_lossy_int(
+    ctx: &ParsingContext,
+    target: &FieldType,
+    value: &crate::jsonish::Value,
+    n: &serde_json::Number,
+) -> Result<BamlValueWithFlags, ParsingError> {
+    if let Some(n) = n.as_u64() {
+        Ok(BamlValueWithFlags::Int((n as i64).into()))
+    } else if let Some(n) = n.as_f64() {
+        Ok(BamlValueWithFlags::Int(
+            ((n.round() as i64), Flag::FloatToInt(n)).into(),
+        ))
+    } else {
+        Err(ctx.error_unexpected_type(target, value))
+    }
+}
+
+/// Handles an integer outside `i64` range by the field's or call's [`IntOverflow`] policy, or
+/// else like any number the target cannot hold exactly.
+fn overflowing_int(
+    ctx: &ParsingContext,
+    target: &FieldType,
+    n: &serde_json::Number,
+    lossy: impl FnOnce() -> Result<BamlValueWithFlags, ParsingError>,
+) -> Result<BamlValueWithFlags, ParsingError> {
+    let text = n.to_string();
+    match ctx.int_overflow() {
+        Some(policy) => int_overflow(ctx, target, policy, &text, text.parse().unwrap_or(f64::NAN)),
+        None => overflowing_number(ctx, target, n, lossy),
+    }
+}
+
+/// Rounds `n` to an int, applying the [`IntOverflow`] policy if set and `n` is out of range.
+fn float_to_int(
+    ctx: &ParsingContext,
+    target: &FieldType,
+    n: f64,
+) -> Result<BamlValueWithFlags, ParsingError> {
+    let rounded = n.round();
+    // `i64::MAX as f64` is 2^63, just past the range.
+    let in_range = rounded >= i64::MIN as f64 && rounded < i64::MAX as f64;
+    match ctx.int_overflow() {
+        Some(policy) if !in_range && !n.is_nan() => {
+            int_overflow(ctx, target, policy, &n.to_string(), n)
+        }
+        _ => Ok(BamlValueWithFlags::Int(
+            ((rounded as i64), Flag::FloatToInt(n)).into(),
+        )),
+    }
+}
+
+/// Applies `policy` to the out-of-range number `n`, written `text` in the output.
+fn int_overflow(
+    ctx: &ParsingContext,
+    target: &FieldType,
+    policy: IntOverflow,
+    text: &str,
+    n: f64,
+) -> Result<BamlValueWithFlags, ParsingError> {
+    match policy {
+        IntOverflow::Error => Err(ctx.error_number_overflow(target, text)),
+        IntOverflow::Saturate => {
+            let clamped = if n < 0.0 { i64::MIN } else { i64::MAX };
+            Ok(BamlValueWithFlags::Int(
+                (clamped, Flag::IntSaturated(text.to_string())).into(),
+            ))
+        }
+        IntOverflow::CoerceToFloat => Ok(BamlValueWithFlags::Float(
+            (n, Flag::IntToFloat(text.to_string())).into(),
+        )),
+    }
+}
+
+/// Handles a number the target cannot hold exactly according to the context's [`NumberOverflow`].
+fn overflowing_number(
+    ctx: &ParsingContext,
+    target: &FieldType,
+    n: &serde_json::Number,
+    lossy: impl FnOnce() -> Result<BamlValueWithFlags, ParsingError>,
+) -> Result<BamlValueWithFlags, ParsingError> {
+    match ctx.options.number_overflow {
+        NumberOverflow::Lossy => lossy(),
+        NumberOverflow::Preserve => Ok(BamlValueWithFlags::Decimal(n.to_string().into())),
+        NumberOverflow::Error => Err(ctx.error_number_overflow(target, &n.to_string())),
+    }
+}
+
+fn is_integer_literal(text: &str) -> bool {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Whether the decimal literal `text` is exactly `value`, i.e. survives a round trip through `f64`.
+fn is_exact_f64(text: &str, value: f64) -> bool {
+    let text = decimal_parts(text);
+    text.is_some() && text == decimal_parts(&format!("{value:e}"))
+}
+
+/// `(negative, significant digits, exponent)` of a decimal literal such as `-12.50e3`, so that
+/// equal numbers have equal parts.
+fn decimal_parts(text: &str) -> Option<(bool, String, i64)> {
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let (mantissa, exponent) = match text.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>().ok()?),
+        None => (text, 0),
+    };
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{int_part}{frac_part}");
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let digits = digits.trim_start_matches('0');
+    let significant = digits.trim_end_matches('0');
+    if significant.is_empty() {
+        return Some((false, String::new(), 0));
+    }
+    let exponent = exponent - frac_part.len() as i64 + (digits.len() - significant.len()) as i64;
+    Some((negative, significant.to_string(), exponent))
+}
+
+fn coerce_bytes(
+    ctx: &ParsingContext,
+    target: &FieldType,
+    value: Option<&crate::jsonish::Value>,
+) -> Result<BamlValueWithFlags, ParsingError> {
+    let Some(value) = value else {
+        return Err(ctx.error_unexpected_null(target));
+    };
+
+    match value {
+        crate::jsonish::Value::String(s) => match decode_base64(s) {
+            Ok(bytes) => Ok(BamlValueWithFlags::Bytes(bytes.into())),
+            Err(reason) => Err(ctx.error_invalid_base64(target, &reason)),
+        },
+        crate::jsonish::Value::Array(items) => {
+            coerce_array_to_singular(ctx, target, &items.iter().collect::<Vec<_>>(), &|value| {
+                coerce_bytes(ctx, target, Some(value))
+            })
+        }
+        _ => Err(ctx.error_unexpected_type(target, value)),
+    }
+}
+
+/// Decodes standard or URL-safe base64, optionally wrapped in a `data:...;base64,` URL.
+/// Whitespace (models like to wrap long strings) is ignored, but the padding must be correct.
+fn decode_base64(value: &str) -> Result<Vec<u8>, String> {
+    let value = value.trim();
+    let payload = match value.strip_prefix("data:") {
+        Some(url) => match url.split_once(',') {
+            Some((media_type, data)) if media_type.ends_with(";base64") => data,
+            _ => return Err("data URL is not base64-encoded".to_string()),
+        },
+        None => value,
+    };
+    let payload = payload
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect::<String>();
+    let engine = if payload.contains(['-', '_']) {
+        URL_SAFE
+    } else {
+        STANDARD
+    };
+    engine.decode(payload).map_err(|e| e.to_string())
+}
+
 fn float_from_maybe_fraction(value: &str) -> Option<f64> {
     if let Some((numerator, denominator)) = value.split_once('/') {
         match (
@@ -176,7 +353,9 @@ fn coerce_float(
 
     match value {
         crate::jsonish::Value::Number(n) => {
-            if let Some(n) = n.as_f64() {
+            if let Some(f) = n.as_f64().filter(|f| !is_exact_f64(&n.to_string(), *f)) {
+                overflowing_number(ctx, target, n, || Ok(BamlValueWithFlags::Float(f.into())))
+            } else if let Some(n) = n.as_f64() {
                 Ok(BamlValueWithFlags::Float(n.into()))
             } else if let Some(n) = n.as_i64() {
                 Ok(BamlValueWithFlags::Float((n as f64).into()))
@@ -190,7 +369,14 @@ fn coerce_float(
             let s = s.trim();
             // Trim trailing commas
             let s = s.trim_end_matches(',');
-            if let Ok(n) = s.parse::<f64>() {
+            if let Some((n, f)) = s
+                .parse::<serde_json::Number>()
+                .ok()
+                .and_then(|n| Some((n.clone(), n.as_f64()?)))
+                .filter(|(n, f)| !is_exact_f64(&n.to_string(), *f))
+            {
+                overflowing_number(ctx, target, &n, || Ok(BamlValueWithFlags::Float(f.into())))
+            } else if let Ok(n) = s.parse::<f64>() {
                 Ok(BamlValueWithFlags::Float(n.into()))
             } else if let Ok(n) = s.parse::<i64>() {
                 Ok(BamlValueWithFlags::Float((n as f64).into()))
@@ -238,31 +424,36 @@ pub(super) fn coerce_bool(
             "false" => Ok(BamlValueWithFlags::Bool(
                 (false, Flag::StringToBool(s.clone())).into(),
             )),
-            _ => {
-                match super::match_string::match_string(
-                    ctx,
-                    target,
-                    Some(value),
-                    &[
-                        ("true", vec!["true".into(), "True".into(), "TRUE".into()]),
-                        (
-                            "false",
-                            vec!["false".into(), "False".into(), "FALSE".into()],
-                        ),
-                    ],
-                ) {
-                    Ok(val) => match val.value().as_str() {
-                        "true" => Ok(BamlValueWithFlags::Bool(
-                            (true, Flag::StringToBool(val.value().clone())).into(),
-                        )),
-                        "false" => Ok(BamlValueWithFlags::Bool(
-                            (false, Flag::StringToBool(val.value().clone())).into(),
-                        )),
-                        _ => Err(ctx.error_unexpected_type(target, value)),
-                    },
-                    Err(_) => Err(ctx.error_unexpected_type(target, value)),
+            word => match ctx.options.bool_vocabulary.lookup(word) {
+                Some(b) => Ok(BamlValueWithFlags::Bool(
+                    (b, Flag::StringToBool(s.clone())).into(),
+                )),
+                None => {
+                    match super::match_string::match_string(
+                        ctx,
+                        target,
+                        Some(value),
+                        &[
+                            ("true", vec!["true".into(), "True".into(), "TRUE".into()]),
+                            (
+                                "false",
+                                vec!["false".into(), "False".into(), "FALSE".into()],
+                            ),
+                        ],
+                    ) {
+                        Ok(val) => match val.value().as_str() {
+                            "true" => Ok(BamlValueWithFlags::Bool(
+                                (true, Flag::StringToBool(val.value().clone())).into(),
+                            )),
+                            "false" => Ok(BamlValueWithFlags::Bool(
+                                (false, Flag::StringToBool(val.value().clone())).into(),
+                            )),
+                            _ => Err(ctx.error_unexpected_type(target, value)),
+                        },
+                        Err(_) => Err(ctx.error_unexpected_type(target, value)),
+                    }
                 }
-            }
+            },
         },
         crate::jsonish::Value::Array(items) => {
             coerce_array_to_singular(ctx, target, &items.iter().collect::<Vec<_>>(), &|value| {
@@ -1,7 +1,11 @@
 use anyhow::Result;
 use internal_baml_core::ir::FieldType;
 
-use crate::deserializer::{coercer::array_helper, types::BamlValueWithFlags};
+use crate::deserializer::{
+    coercer::array_helper,
+    deserialize_flags::{DeserializerConditions, Flag},
+    types::BamlValueWithFlags,
+};
 
 use super::{ParsingContext, ParsingError, TypeCoercer};
 
@@ -28,5 +32,33 @@ pub(super) fn coerce_union(
         .map(|option| option.coerce(ctx, option, value))
         .collect::<Vec<_>>();
 
-    array_helper::pick_best(ctx, union_target, &parsed)
+    let best = array_helper::pick_best(ctx, union_target, &parsed);
+    if ctx.options.normalize_lists {
+        best.map(|value| normalize_to_list(union_target, value))
+    } else {
+        best
+    }
+}
+
+/// Wraps a single value coerced into a union such as `string | string[]` in a list, so callers
+/// only handle the list form. Other types, lists and nulls are returned as is.
+pub(super) fn normalize_to_list(
+    field_type: &FieldType,
+    value: BamlValueWithFlags,
+) -> BamlValueWithFlags {
+    let has_list = match field_type {
+        FieldType::Union(options) => options.iter().any(|o| matches!(o, FieldType::List(_))),
+        FieldType::Optional(inner) | FieldType::Constrained { base: inner, .. } => {
+            return normalize_to_list(inner, value)
+        }
+        _ => false,
+    };
+    match value {
+        BamlValueWithFlags::List(..) | BamlValueWithFlags::Null(_) => value,
+        value if has_list => BamlValueWithFlags::List(
+            DeserializerConditions::new().with_flag(Flag::SingleToArray),
+            vec![value],
+        ),
+        value => value,
+    }
 }
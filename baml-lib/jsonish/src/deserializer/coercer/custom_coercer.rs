@@ -0,0 +1,93 @@
+use anyhow::Result;
+use baml_types::BamlValue;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use internal_baml_core::ir::FieldType;
+
+use crate::{deserializer::types::BamlValueWithFlags, jsonish};
+
+use super::{ParsingContext, ParsingError, TypeCoercer};
+
+/// A domain parser for fields marked `@coerce("name")`, such as chemical formulas or citations.
+///
+/// The coercer receives the raw text the model produced for the field and returns its value.
+/// The result is then coerced into the field type like any other value, so structure, aliases
+/// and checks are still handled by the rest of the pipeline.
+pub trait CustomCoercer: Send + Sync {
+    fn coerce(&self, raw: &str, target: &FieldType) -> Result<BamlValue>;
+}
+
+/// Coerces a field value through the coercer registered as `name`.
+///
+/// Missing and null values skip the coercer so optional fields keep their usual defaults.
+pub(super) fn coerce_with(
+    ctx: &ParsingContext,
+    name: &str,
+    target: &FieldType,
+    value: Option<&jsonish::Value>,
+) -> Result<BamlValueWithFlags, ParsingError> {
+    let Some(raw) = value.and_then(raw_text) else {
+        return target.coerce(ctx, target, value);
+    };
+    let Some(coercer) = ctx.options.coercers.get(name) else {
+        return Err(ctx.error_custom_coercer(name, anyhow::anyhow!("not registered")));
+    };
+    let parsed = coercer
+        .coerce(&raw, target)
+        .map_err(|e| ctx.error_custom_coercer(name, e))?;
+    let parsed = to_jsonish(&parsed).map_err(|e| ctx.error_custom_coercer(name, e))?;
+    target.coerce(ctx, target, Some(&parsed))
+}
+
+/// The text the model wrote for a value, or `None` for null.
+fn raw_text(value: &jsonish::Value) -> Option<String> {
+    match value {
+        jsonish::Value::Null => None,
+        jsonish::Value::String(s) | jsonish::Value::AnyOf(_, s) => Some(s.clone()),
+        jsonish::Value::FixedJson(v, _) | jsonish::Value::Markdown(_, v) => raw_text(v),
+        jsonish::Value::Number(n) => Some(n.to_string()),
+        jsonish::Value::Boolean(b) => Some(b.to_string()),
+        jsonish::Value::Object(_) | jsonish::Value::Array(_) => {
+            serde_json::to_string(&to_json(value)).ok()
+        }
+    }
+}
+
+pub(super) fn to_json(value: &jsonish::Value) -> serde_json::Value {
+    match value {
+        jsonish::Value::String(s) | jsonish::Value::AnyOf(_, s) => s.clone().into(),
+        jsonish::Value::Number(n) => serde_json::Value::Number(n.clone()),
+        jsonish::Value::Boolean(b) => (*b).into(),
+        jsonish::Value::Null => serde_json::Value::Null,
+        jsonish::Value::Object(kvs) => kvs
+            .iter()
+            .map(|(k, v)| (k.clone(), to_json(v)))
+            .collect::<serde_json::Map<_, _>>()
+            .into(),
+        jsonish::Value::Array(items) => items.iter().map(to_json).collect(),
+        jsonish::Value::Markdown(_, v) | jsonish::Value::FixedJson(v, _) => to_json(v),
+    }
+}
+
+pub(super) fn to_jsonish(value: &BamlValue) -> Result<jsonish::Value> {
+    Ok(match value {
+        BamlValue::String(s) | BamlValue::Enum(_, s) => jsonish::Value::String(s.clone()),
+        BamlValue::Int(i) => jsonish::Value::Number((*i).into()),
+        BamlValue::Float(f) => match serde_json::Number::from_f64(*f) {
+            Some(n) => jsonish::Value::Number(n),
+            None => jsonish::Value::String(f.to_string()),
+        },
+        BamlValue::Decimal(d) => jsonish::Value::Number(d.parse()?),
+        BamlValue::Bool(b) => jsonish::Value::Boolean(*b),
+        BamlValue::Bytes(b) => jsonish::Value::String(STANDARD.encode(b)),
+        BamlValue::Null => jsonish::Value::Null,
+        BamlValue::List(items) => {
+            jsonish::Value::Array(items.iter().map(to_jsonish).collect::<Result<_>>()?)
+        }
+        BamlValue::Map(kvs) | BamlValue::Class(_, kvs) => jsonish::Value::Object(
+            kvs.iter()
+                .map(|(k, v)| Ok((k.clone(), to_jsonish(v)?)))
+                .collect::<Result<_>>()?,
+        ),
+        BamlValue::Media(_) => anyhow::bail!("coercers may not return media"),
+    })
+}
@@ -1,6 +1,6 @@
 use anyhow::Result;
 use baml_types::{BamlMap, Constraint, ConstraintLevel};
-use internal_baml_core::{ir::FieldType, ir::TypeValue};
+use internal_baml_core::{internal_baml_diagnostics::Message, ir::FieldType, ir::TypeValue};
 
 use crate::deserializer::{
     coercer::{run_user_checks, DefaultValue, TypeCoercer},
@@ -15,6 +15,7 @@ use super::{
     coerce_optional::coerce_optional,
     coerce_union::coerce_union,
     ir_ref::{coerce_alias::coerce_alias, IrRef},
+    middleware::coerce_through_middleware,
     ParsingContext, ParsingError,
 };
 
@@ -78,38 +79,80 @@ impl TypeCoercer for FieldType {
                 v.add_flag(Flag::ObjectFromFixedJson(fixes.to_vec()));
                 Ok(v)
             }
-            _ => match self {
-                FieldType::Primitive(p) => p.coerce(ctx, target, value),
-                FieldType::Enum(e) => IrRef::Enum(e).coerce(ctx, target, value),
-                FieldType::Literal(l) => l.coerce(ctx, target, value),
-                FieldType::Class(c) => IrRef::Class(c).coerce(ctx, target, value),
-                FieldType::RecursiveTypeAlias(name) => coerce_alias(ctx, self, value),
-                FieldType::List(_) => coerce_array(ctx, self, value),
-                FieldType::Union(_) => coerce_union(ctx, self, value),
-                FieldType::Optional(_) => coerce_optional(ctx, self, value),
-                FieldType::Map(_, _) => coerce_map(ctx, self, value),
-                FieldType::Tuple(_) => Err(ctx.error_internal("Tuple not supported")),
-                FieldType::Constrained { base, .. } => {
-                    let mut coerced_value = base.coerce(ctx, base, value)?;
-                    let constraint_results = run_user_checks(&coerced_value.clone().into(), self)
-                        .map_err(|e| ParsingError {
-                        reason: format!("Failed to evaluate constraints: {e:?}"),
-                        scope: ctx.scope.clone(),
-                        causes: Vec::new(),
-                    })?;
-                    validate_asserts(&constraint_results)?;
-                    let check_results = constraint_results
-                        .into_iter()
-                        .filter_map(|(maybe_check, result)| {
-                            maybe_check
-                                .as_check()
-                                .map(|(label, expr)| (label, expr, result))
-                        })
-                        .collect();
-                    coerced_value.add_flag(Flag::ConstraintResults(check_results));
-                    Ok(coerced_value)
+            _ => {
+                if let Some(cut) = cut_at_max_depth(ctx, self, value) {
+                    return cut;
                 }
-            },
+                coerce_through_middleware(ctx, self, value, |value| {
+                    coerce_kind(self, ctx, target, value)
+                })
+            }
+        }
+    }
+}
+
+/// An object or array nested deeper than [`CoerceOptions::max_depth`](super::CoerceOptions),
+/// cut to the empty value of `field_type`, or the error if it has none.
+fn cut_at_max_depth(
+    ctx: &ParsingContext,
+    field_type: &FieldType,
+    value: Option<&crate::jsonish::Value>,
+) -> Option<Result<BamlValueWithFlags, ParsingError>> {
+    let max_depth = ctx.options.max_depth?;
+    if ctx.scope.len() <= max_depth
+        || !matches!(
+            value,
+            Some(crate::jsonish::Value::Object(..) | crate::jsonish::Value::Array(..))
+        )
+    {
+        return None;
+    }
+
+    fn empty(field_type: &FieldType, max_depth: usize) -> Option<BamlValueWithFlags> {
+        let flags = DeserializerConditions::new().with_flag(Flag::DepthLimitExceeded(max_depth));
+        match field_type {
+            FieldType::Primitive(TypeValue::Null) | FieldType::Optional(_) => {
+                Some(BamlValueWithFlags::Null(flags))
+            }
+            FieldType::List(_) => Some(BamlValueWithFlags::List(flags, Vec::new())),
+            FieldType::Map(_, _) => Some(BamlValueWithFlags::Map(flags, BamlMap::new())),
+            FieldType::Union(items) => items.iter().find_map(|item| empty(item, max_depth)),
+            _ => None,
+        }
+    }
+    Some(empty(field_type, max_depth).ok_or_else(|| ctx.error_max_depth(max_depth)))
+}
+
+/// Coerces `value` with the coercer for the kind of `field_type`.
+fn coerce_kind(
+    field_type: &FieldType,
+    ctx: &ParsingContext,
+    target: &FieldType,
+    value: Option<&crate::jsonish::Value>,
+) -> Result<BamlValueWithFlags, ParsingError> {
+    match field_type {
+        FieldType::Primitive(p) => p.coerce(ctx, target, value),
+        FieldType::Enum(e) => IrRef::Enum(e).coerce(ctx, target, value),
+        FieldType::Literal(l) => l.coerce(ctx, target, value),
+        FieldType::Class(c) => IrRef::Class(c).coerce(ctx, target, value),
+        FieldType::RecursiveTypeAlias(name) => coerce_alias(ctx, field_type, value),
+        FieldType::List(_) => coerce_array(ctx, field_type, value),
+        FieldType::Union(_) => coerce_union(ctx, field_type, value),
+        FieldType::Optional(_) => coerce_optional(ctx, field_type, value),
+        FieldType::Map(_, _) => coerce_map(ctx, field_type, value),
+        FieldType::Tuple(_) => Err(ctx.error_internal("Tuple not supported")),
+        FieldType::Constrained { base, .. } => {
+            let mut coerced_value = base.coerce(ctx, base, value)?;
+            let constraint_results = run_user_checks(&coerced_value.clone().into(), field_type)
+                .map_err(|e| ParsingError {
+                reason: format!("Failed to evaluate constraints: {e:?}"),
+                scope: ctx.scope.clone(),
+                causes: Vec::new(),
+                localizable: None,
+            })?;
+            validate_asserts(&constraint_results)?;
+            coerced_value.add_flag(Flag::ConstraintResults(constraint_results));
+            Ok(coerced_value)
         }
     }
 }
@@ -144,13 +187,15 @@ pub fn validate_asserts(constraints: &[(Constraint, bool)]) -> Result<(), Parsin
                 expr.0
             ),
             scope: vec![],
+            localizable: None,
         })
         .collect::<Vec<_>>();
     if !causes.is_empty() {
         Err(ParsingError {
-            causes: vec![],
+            causes,
             reason: "Assertions failed.".to_string(),
             scope: vec![],
+            localizable: Some(Message::new("assertions_failed")),
         })
     } else {
         Ok(())
@@ -39,6 +39,7 @@ pub fn coerce_alias(
             reason: format!("Failed to find recursive alias target: {e}"),
             scope: ctx.scope.clone(),
             causes: Vec::new(),
+            localizable: None,
         })?
         .coerce(ctx, target, value)
 }
@@ -1,19 +1,24 @@
+use std::collections::HashMap;
+
 use anyhow::Result;
-use baml_types::{BamlMap, Constraint};
-use internal_baml_core::ir::FieldType;
-use internal_baml_jinja::types::{Class, Name};
+use baml_types::{BamlMap, BamlValue, Constraint};
+use internal_baml_core::ir::{jinja_helpers::evaluate_predicate, FieldType};
+use internal_baml_jinja::types::{Class, FieldMetadata, Name};
 
 use crate::deserializer::{
     coercer::field_type::validate_asserts,
-    coercer::{array_helper, run_user_checks, DefaultValue, ParsingError, TypeCoercer},
+    coercer::{
+        array_helper, coerce_union, custom_coercer, run_user_checks, DefaultValue, ParsingError,
+        TypeCoercer,
+    },
     deserialize_flags::{DeserializerConditions, Flag},
     types::BamlValueWithFlags,
 };
 
 use super::ParsingContext;
 
-// Name, type, description.
-type FieldValue = (Name, FieldType, Option<String>);
+// Name, type, description, metadata.
+type FieldValue = (Name, FieldType, Option<String>, FieldMetadata);
 
 impl TypeCoercer for Class {
     fn coerce(
@@ -29,6 +34,12 @@ impl TypeCoercer for Class {
             current = value.map(|v| v.r#type()).unwrap_or("<null>".into())
         );
 
+        if let Some(crate::jsonish::Value::Object(obj)) = value {
+            if is_flat(self) && !is_implied_single_field(self, obj) {
+                return coerce_flat(self, ctx, target, value, obj);
+            }
+        }
+
         // If value is not None then we'll update the context to store the
         // current class in the visited set and we'll use that to stop recursion
         // when dealing with recursive classes.
@@ -72,6 +83,8 @@ impl TypeCoercer for Class {
             .map(|(f, ..)| (f.real_name().to_string(), None))
             .collect::<BamlMap<_, _>>();
         let mut flags = DeserializerConditions::new();
+        // Real names of the fields in the order the model wrote them.
+        let mut key_order = Vec::new();
 
         let mut completed_cls = Vec::new();
 
@@ -91,8 +104,13 @@ impl TypeCoercer for Class {
                         .find(|(name, ..)| name.rendered_name().trim() == key)
                     {
                         let scope = ctx.enter_scope(field.0.real_name());
-                        let parsed = field.1.coerce(&scope, &field.1, Some(v));
+                        let parsed = coerce_field(&scope, field, Some(v)).map(|v| {
+                            flag_deprecated(&scope, &field.0, field.3.deprecated.as_deref(), v)
+                        });
                         update_map(&mut required_values, &mut optional_values, field, parsed);
+                        if !key_order.contains(&field.0.real_name()) {
+                            key_order.push(field.0.real_name());
+                        }
                         found_keys = true;
                     } else {
                         extra_keys.push((key, v));
@@ -103,17 +121,15 @@ impl TypeCoercer for Class {
                     // Try to coerce the object into the single field
                     let field = &self.fields[0];
                     let scope = ctx.enter_scope(&format!("<implied:{}>", field.0.real_name()));
-                    let parsed = field
-                        .1
-                        .coerce(
-                            &scope,
-                            &field.1,
-                            Some(&crate::jsonish::Value::Object(obj.clone())),
-                        )
-                        .map(|mut v| {
-                            v.add_flag(Flag::ImpliedKey(field.0.real_name().into()));
-                            v
-                        });
+                    let parsed = coerce_field(
+                        &scope,
+                        field,
+                        Some(&crate::jsonish::Value::Object(obj.clone())),
+                    )
+                    .map(|mut v| {
+                        v.add_flag(Flag::ImpliedKey(field.0.real_name().into()));
+                        v
+                    });
 
                     if let Ok(parsed_value) = parsed {
                         update_map(
@@ -137,7 +153,7 @@ impl TypeCoercer for Class {
                 if self.fields.len() == 1 {
                     let field = &self.fields[0];
                     let scope = ctx.enter_scope(&format!("<implied:{}>", field.0.real_name()));
-                    let parsed = match field.1.coerce(&scope, &field.1, value) {
+                    let parsed = match coerce_field(&scope, field, value) {
                         Ok(mut v) => {
                             v.add_flag(Flag::ImpliedKey(field.0.real_name().into()));
                             Ok(v)
@@ -164,7 +180,7 @@ impl TypeCoercer for Class {
                 if self.fields.len() == 1 {
                     let field = &self.fields[0];
                     let scope = ctx.enter_scope(&format!("<implied:{}>", field.0.real_name()));
-                    let parsed = match field.1.coerce(&scope, &field.1, Some(x)) {
+                    let parsed = match coerce_field(&scope, field, Some(x)) {
                         Ok(mut v) => {
                             v.add_flag(Flag::ImpliedKey(field.0.real_name().into()));
                             flags.add_flag(Flag::InferedObject(x.clone()));
@@ -314,10 +330,9 @@ impl TypeCoercer for Class {
                     }))
                     .collect::<BamlMap<String, _>>();
 
-                // Create a BamlMap ordered according to self.fields
+                // Create a BamlMap in the requested order, or the model's.
                 let mut ordered_valid_fields = BamlMap::new();
-                for field in self.fields.iter() {
-                    let key = field.0.real_name();
+                for key in output_order(ctx, self, &key_order) {
                     if let Some(value) = valid_fields.get(key) {
                         ordered_valid_fields.insert(key.to_string(), value.clone());
                     }
@@ -328,6 +343,8 @@ impl TypeCoercer for Class {
                     flags,
                     ordered_valid_fields.clone(),
                 ))
+                .and_then(|value| check_required_if(ctx, &self.fields, value))
+                .and_then(|value| check_one_of(ctx, &self.one_of, value))
                 .and_then(|value| apply_constraints(target, vec![], value, constraints.clone()));
 
                 completed_cls.insert(0, completed_instance);
@@ -340,6 +357,268 @@ impl TypeCoercer for Class {
     }
 }
 
+/// The real names of the fields of `class` in the order they are returned: the requested order,
+/// or the order the model wrote them in (`key_order`) followed by the others.
+fn output_order<'a>(ctx: &ParsingContext, class: &'a Class, key_order: &[&'a str]) -> Vec<&'a str> {
+    match ctx.options.field_order {
+        Some(order) => class
+            .fields_in(order)
+            .into_iter()
+            .map(|(name, ..)| name.real_name())
+            .collect(),
+        None => key_order
+            .iter()
+            .copied()
+            .chain(
+                class
+                    .fields
+                    .iter()
+                    .map(|(name, ..)| name.real_name())
+                    .filter(|key| !key_order.contains(key)),
+            )
+            .collect(),
+    }
+}
+
+/// Whether objects can be coerced into `class` with [`coerce_flat`]: every field holds a
+/// primitive, enum or literal, possibly optional or in a list. Such fields never recurse into
+/// a class, so there is nothing to track, and are never read as one of several types.
+fn is_flat(class: &Class) -> bool {
+    fn is_flat_field(field_type: &FieldType, in_list: bool) -> bool {
+        match field_type {
+            FieldType::Primitive(_) | FieldType::Enum(_) | FieldType::Literal(_) => true,
+            FieldType::Optional(inner) => is_flat_field(inner, in_list),
+            FieldType::List(inner) if !in_list => is_flat_field(inner, true),
+            _ => false,
+        }
+    }
+    class
+        .fields
+        .iter()
+        .all(|(_, t, ..)| is_flat_field(t, false))
+}
+
+/// Whether `obj` is read as the value of the single field of `class`: none of its keys is the
+/// field's. The generic path handles these, as the object is then coerced into the field.
+fn is_implied_single_field(class: &Class, obj: &[(String, crate::jsonish::Value)]) -> bool {
+    match class.fields.as_slice() {
+        [(name, ..)] => {
+            !obj.is_empty()
+                && obj
+                    .iter()
+                    .all(|(key, _)| name.rendered_name().trim() != key)
+        }
+        _ => false,
+    }
+}
+
+/// Fast path for the hottest case, a JSON object coerced into a [flat](is_flat) class: fields
+/// are looked up by key, and neither visited classes nor alternative readings are tracked.
+///
+/// Returns what the generic path would for the object, errors included.
+fn coerce_flat(
+    class: &Class,
+    ctx: &ParsingContext,
+    target: &FieldType,
+    value: Option<&crate::jsonish::Value>,
+    obj: &[(String, crate::jsonish::Value)],
+) -> Result<BamlValueWithFlags, ParsingError> {
+    let mut index = HashMap::with_capacity(class.fields.len());
+    for (i, (name, ..)) in class.fields.iter().enumerate() {
+        index.entry(name.rendered_name().trim()).or_insert(i);
+    }
+
+    let mut parsed = vec![None; class.fields.len()];
+    let mut key_order = Vec::new();
+    let mut extra_keys = Vec::new();
+    for (key, v) in obj {
+        let Some(&i) = index.get(key.as_str()) else {
+            extra_keys.push((key, v));
+            continue;
+        };
+        // Like the generic path, keep the first value of duplicate keys.
+        if parsed[i].is_none() {
+            let field = &class.fields[i];
+            let scope = ctx.enter_scope(field.0.real_name());
+            parsed[i] = Some(
+                coerce_field(&scope, field, Some(v))
+                    .map(|v| flag_deprecated(&scope, &field.0, field.3.deprecated.as_deref(), v)),
+            );
+            key_order.push(field.0.real_name());
+        }
+    }
+    let mut flags = DeserializerConditions::new();
+    for (key, v) in extra_keys {
+        flags.add_flag(Flag::ExtraKey(key.to_string(), v.clone()));
+    }
+
+    let partial_null = || {
+        ctx.allow_partials.then(|| {
+            BamlValueWithFlags::Null(
+                DeserializerConditions::new().with_flag(Flag::OptionalDefaultFromNoValue),
+            )
+        })
+    };
+    let mut values = BamlMap::new();
+    // Required fields without a value, in declaration order.
+    let mut unparsed = Vec::new();
+    let mut missing = Vec::new();
+    for ((name, t, ..), parsed) in class.fields.iter().zip(parsed) {
+        let field_value = match parsed {
+            Some(Ok(field_value)) => field_value,
+            Some(Err(e)) if t.is_optional() => BamlValueWithFlags::Null(
+                DeserializerConditions::new().with_flag(Flag::DefaultButHadUnparseableValue(e)),
+            ),
+            None if t.is_optional() => BamlValueWithFlags::Null(
+                DeserializerConditions::new().with_flag(Flag::OptionalDefaultFromNoValue),
+            ),
+            Some(Err(e)) => match t.default_value(Some(&e)).or_else(partial_null) {
+                Some(default) => default,
+                None => {
+                    unparsed.push((name.real_name().to_string(), e));
+                    continue;
+                }
+            },
+            None => match t.default_value(None).or_else(partial_null) {
+                Some(default) => default,
+                None => {
+                    missing.push(name.real_name().to_string());
+                    continue;
+                }
+            },
+        };
+        values.insert(name.real_name(), field_value);
+    }
+    if !unparsed.is_empty() || !missing.is_empty() {
+        let unparsed = unparsed.iter().map(|(k, e)| (k.clone(), e)).collect();
+        return Err(ctx.error_missing_required_field(unparsed, missing, value));
+    }
+
+    let mut ordered = BamlMap::new();
+    for key in output_order(ctx, class, &key_order) {
+        if let Some(value) = values.swap_remove(key) {
+            ordered.insert(key.to_string(), value);
+        }
+    }
+    let value = BamlValueWithFlags::Class(class.name.real_name().into(), flags, ordered);
+    check_required_if(ctx, &class.fields, value)
+        .and_then(|value| check_one_of(ctx, &class.one_of, value))
+        .and_then(|value| apply_constraints(target, vec![], value, class.constraints.clone()))
+}
+
+/// Coerce a field value, through its `@coerce` coercer if it has one.
+fn coerce_field(
+    ctx: &ParsingContext,
+    (_, field_type, _, metadata): &FieldValue,
+    value: Option<&crate::jsonish::Value>,
+) -> Result<BamlValueWithFlags, ParsingError> {
+    let field_ctx;
+    let ctx = if metadata.int_overflow.is_some() || metadata.null_values.is_some() {
+        field_ctx = ctx.for_field(metadata);
+        &field_ctx
+    } else {
+        ctx
+    };
+    let value = match metadata.coercer.as_deref() {
+        Some(coercer) => custom_coercer::coerce_with(ctx, coercer, field_type, value),
+        None => field_type.coerce(ctx, field_type, value),
+    };
+    if metadata.normalize_to_list {
+        value.map(|value| coerce_union::normalize_to_list(field_type, value))
+    } else {
+        value
+    }
+}
+
+/// Accept deprecated members, but warn and flag them so callers can track migrations.
+pub(super) fn flag_deprecated(
+    ctx: &ParsingContext,
+    name: &Name,
+    deprecated: Option<&str>,
+    mut value: BamlValueWithFlags,
+) -> BamlValueWithFlags {
+    if let Some(message) = deprecated {
+        log::warn!(
+            "scope: {scope} :: matched deprecated {name}: {message}",
+            scope = ctx.display_scope(),
+            name = name.real_name(),
+        );
+        value.add_flag(Flag::Deprecated(
+            name.real_name().to_string(),
+            message.to_string(),
+        ));
+    }
+    value
+}
+
+/// Reject null fields whose `@required_if` condition holds for the parsed object.
+fn check_required_if(
+    ctx: &ParsingContext,
+    fields: &[FieldValue],
+    value: BamlValueWithFlags,
+) -> Result<BamlValueWithFlags, ParsingError> {
+    // Partial objects are still streaming, so the field may yet arrive.
+    if ctx.allow_partials {
+        return Ok(value);
+    }
+    let BamlValueWithFlags::Class(_, _, values) = &value else {
+        return Ok(value);
+    };
+    let mut this = None;
+    for (name, .., metadata) in fields {
+        let Some(condition) = &metadata.required_if else {
+            continue;
+        };
+        if !matches!(
+            values.get(name.real_name()),
+            None | Some(BamlValueWithFlags::Null(_))
+        ) {
+            continue;
+        }
+        let scope = ctx.enter_scope(name.real_name());
+        let this = this.get_or_insert_with(|| BamlValue::from(&value));
+        match evaluate_predicate(this, condition) {
+            Ok(false) => {}
+            Ok(true) => return Err(scope.error_required_if(condition)),
+            Err(e) => {
+                return Err(scope.error_internal(format!(
+                    "Failed to evaluate @required_if condition {condition}: {e}"
+                )))
+            }
+        }
+    }
+    Ok(value)
+}
+
+/// Reject objects that do not set exactly one field of each `@@one_of` group.
+fn check_one_of(
+    ctx: &ParsingContext,
+    groups: &[Vec<String>],
+    value: BamlValueWithFlags,
+) -> Result<BamlValueWithFlags, ParsingError> {
+    if ctx.allow_partials {
+        return Ok(value);
+    }
+    let BamlValueWithFlags::Class(_, _, values) = &value else {
+        return Ok(value);
+    };
+    for group in groups {
+        let set = group
+            .iter()
+            .filter(|field| {
+                !matches!(
+                    values.get(field.as_str()),
+                    None | Some(BamlValueWithFlags::Null(_))
+                )
+            })
+            .collect::<Vec<_>>();
+        if set.len() != 1 {
+            return Err(ctx.error_one_of(group, &set));
+        }
+    }
+    Ok(value)
+}
+
 pub fn apply_constraints(
     class_type: &FieldType,
     scope: Vec<String>,
@@ -358,17 +637,10 @@ pub fn apply_constraints(
                 reason: format!("Failed to evaluate constraints: {:?}", e),
                 scope,
                 causes: Vec::new(),
+                localizable: None,
             })?;
         validate_asserts(&constraint_results)?;
-        let check_results = constraint_results
-            .into_iter()
-            .filter_map(|(maybe_check, result)| {
-                maybe_check
-                    .as_check()
-                    .map(|(label, expr)| (label, expr, result))
-            })
-            .collect();
-        value.add_flag(Flag::ConstraintResults(check_results));
+        value.add_flag(Flag::ConstraintResults(constraint_results));
         Ok(value)
     }
 }
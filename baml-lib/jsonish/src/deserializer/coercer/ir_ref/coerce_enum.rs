@@ -4,9 +4,11 @@ use internal_baml_jinja::types::Enum;
 
 use crate::deserializer::{
     coercer::{
-        ir_ref::coerce_class::apply_constraints, match_string::match_string, ParsingError,
-        TypeCoercer,
+        ir_ref::coerce_class::{apply_constraints, flag_deprecated},
+        match_string::match_string,
+        ParsingError, TypeCoercer,
     },
+    deserialize_flags::Flag,
     types::BamlValueWithFlags,
 };
 
@@ -15,7 +17,7 @@ use super::ParsingContext;
 fn enum_match_candidates(enm: &Enum) -> Vec<(&str, Vec<String>)> {
     enm.values
         .iter()
-        .map(|(name, desc)| {
+        .map(|(name, desc, _)| {
             (
                 name.real_name(),
                 match desc.as_ref().map(|d| d.trim()) {
@@ -50,13 +52,28 @@ impl TypeCoercer for Enum {
             .find_enum(self.name.real_name())
             .map_or(vec![], |class| class.constraints.clone());
 
-        let variant_match = match_string(ctx, target, value, &enum_match_candidates(self))?;
-        let enum_match = apply_constraints(
-            target,
-            vec![],
-            BamlValueWithFlags::Enum(self.name.real_name().to_string(), variant_match),
-            constraints.clone(),
-        )?;
+        let variant_match = match (
+            match_string(ctx, target, value, &enum_match_candidates(self)),
+            value,
+        ) {
+            (Ok(variant_match), _) => variant_match,
+            // Open enums keep any other string as is.
+            (Err(_), Some(crate::jsonish::Value::String(raw))) if self.open => {
+                let raw = raw.trim().to_string();
+                (raw.clone(), Flag::OpenEnumFallback(raw)).into()
+            }
+            (Err(e), _) => return Err(e),
+        };
+        let variant = self
+            .values
+            .iter()
+            .find(|(name, ..)| name.real_name() == variant_match.value());
+        let mut enum_match =
+            BamlValueWithFlags::Enum(self.name.real_name().to_string(), variant_match);
+        if let Some((name, _, metadata)) = variant {
+            enum_match = flag_deprecated(ctx, name, metadata.deprecated.as_deref(), enum_match);
+        }
+        let enum_match = apply_constraints(target, vec![], enum_match, constraints.clone())?;
 
         Ok(enum_match)
     }
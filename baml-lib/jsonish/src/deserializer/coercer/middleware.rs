@@ -0,0 +1,114 @@
+use baml_types::BamlValue;
+use internal_baml_core::ir::FieldType;
+
+use crate::{deserializer::types::BamlValueWithFlags, jsonish};
+
+use super::{
+    custom_coercer::{to_json, to_jsonish},
+    ParsingContext, ParsingError,
+};
+
+/// The kinds of types a value can be coerced into, to pick which middleware hooks run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TypeKind {
+    Primitive,
+    Enum,
+    Literal,
+    Class,
+    RecursiveTypeAlias,
+    List,
+    Map,
+    Tuple,
+    Union,
+    Optional,
+    Constrained,
+}
+
+impl TypeKind {
+    pub fn of(field_type: &FieldType) -> Self {
+        match field_type {
+            FieldType::Primitive(_) => TypeKind::Primitive,
+            FieldType::Enum(_) => TypeKind::Enum,
+            FieldType::Literal(_) => TypeKind::Literal,
+            FieldType::Class(_) => TypeKind::Class,
+            FieldType::RecursiveTypeAlias(_) => TypeKind::RecursiveTypeAlias,
+            FieldType::List(_) => TypeKind::List,
+            FieldType::Map(_, _) => TypeKind::Map,
+            FieldType::Tuple(_) => TypeKind::Tuple,
+            FieldType::Union(_) => TypeKind::Union,
+            FieldType::Optional(_) => TypeKind::Optional,
+            FieldType::Constrained { .. } => TypeKind::Constrained,
+        }
+    }
+}
+
+/// Hooks run around every coercion into a type of the kinds it [applies to](Self::applies_to),
+/// for behaviors shared by all coercers: logging, metrics, sanitization, fallbacks.
+///
+/// `scope` is the path of the value being coerced, such as `["items", "0", "name"]`. Hooks run
+/// in registration order before coercing and in reverse order after it, like nested wrappers.
+pub trait CoercerMiddleware: Send + Sync {
+    /// Whether the hooks run for targets of `kind`. Every kind by default.
+    fn applies_to(&self, _kind: TypeKind) -> bool {
+        true
+    }
+
+    /// Runs before coercing `value`, `None` when the model left it out. Return a value to
+    /// coerce instead, for instance a sanitized string.
+    fn before(
+        &self,
+        _scope: &[String],
+        _target: &FieldType,
+        _value: Option<&serde_json::Value>,
+    ) -> Option<BamlValue> {
+        None
+    }
+
+    /// Runs with the result of the coercion. Return a value to coerce instead of the result,
+    /// for instance a fallback when it failed.
+    fn after(
+        &self,
+        _scope: &[String],
+        _target: &FieldType,
+        _result: Result<&BamlValueWithFlags, &ParsingError>,
+    ) -> Option<BamlValue> {
+        None
+    }
+}
+
+/// Coerces `value` with `coerce`, through the middleware of the options that apply to the
+/// kind of `field_type`.
+pub(super) fn coerce_through_middleware(
+    ctx: &ParsingContext,
+    field_type: &FieldType,
+    value: Option<&jsonish::Value>,
+    coerce: impl Fn(Option<&jsonish::Value>) -> Result<BamlValueWithFlags, ParsingError>,
+) -> Result<BamlValueWithFlags, ParsingError> {
+    let kind = TypeKind::of(field_type);
+    let middleware = ctx
+        .options
+        .middleware
+        .iter()
+        .filter(|m| m.applies_to(kind))
+        .collect::<Vec<_>>();
+    if middleware.is_empty() {
+        return coerce(value);
+    }
+
+    let mut replaced = None;
+    for m in &middleware {
+        let json = replaced.as_ref().or(value).map(to_json);
+        if let Some(replacement) = m.before(&ctx.scope, field_type, json.as_ref()) {
+            replaced = Some(to_jsonish(&replacement).map_err(|e| ctx.error_middleware(e))?);
+        }
+    }
+
+    let mut result = coerce(replaced.as_ref().or(value));
+    for m in middleware.iter().rev() {
+        if let Some(replacement) = m.after(&ctx.scope, field_type, result.as_ref()) {
+            let replacement = to_jsonish(&replacement).map_err(|e| ctx.error_middleware(e))?;
+            result = coerce(Some(&replacement));
+        }
+    }
+    result
+}
@@ -1,32 +1,123 @@
 mod array_helper;
+mod bool_vocabulary;
 mod coerce_array;
 mod coerce_literal;
 mod coerce_map;
 mod coerce_optional;
 mod coerce_primitive;
 mod coerce_union;
+mod custom_coercer;
 mod field_type;
 mod ir_ref;
 mod match_string;
+mod middleware;
 
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use anyhow::Result;
 
 use baml_types::{BamlValue, Constraint, JinjaExpression};
-use internal_baml_jinja::types::OutputFormatContent;
+use internal_baml_jinja::types::{FieldMetadata, FieldOrder, IntOverflow, OutputFormatContent};
 
-use internal_baml_core::ir::{jinja_helpers::evaluate_predicate, FieldType};
+use internal_baml_core::{
+    internal_baml_diagnostics::{Message, MessageCatalog},
+    ir::{jinja_helpers::evaluate_predicate, FieldType},
+};
 
 use crate::jsonish;
 
 use super::types::BamlValueWithFlags;
 
+/// What to do with a number that an `int` (`i64`) or `float` (`f64`) cannot hold exactly, such
+/// as IDs above 2^63 or amounts with more than ~15 significant digits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NumberOverflow {
+    /// Round to the nearest `f64` (or `i64`).
+    #[default]
+    Lossy,
+    /// Keep every digit as a [`BamlValueWithFlags::Decimal`].
+    Preserve,
+    /// Fail to coerce the number.
+    Error,
+}
+
+impl std::str::FromStr for NumberOverflow {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "lossy" => Ok(NumberOverflow::Lossy),
+            "preserve" => Ok(NumberOverflow::Preserve),
+            "error" => Ok(NumberOverflow::Error),
+            _ => anyhow::bail!("Unknown number overflow `{s}`, expected lossy, preserve or error"),
+        }
+    }
+}
+
+pub use bool_vocabulary::BoolVocabulary;
+pub use custom_coercer::CustomCoercer;
+pub use middleware::{CoercerMiddleware, TypeKind};
+
+/// Options for [`from_str_with_options`](crate::from_str_with_options).
+#[derive(Clone, Default)]
+pub struct CoerceOptions {
+    pub number_overflow: NumberOverflow,
+    /// What to do with numbers outside the range of an `int`, for fields without
+    /// `@int_overflow`. When unset, `number_overflow` applies.
+    pub int_overflow: Option<IntOverflow>,
+    /// Coercers for fields marked `@coerce("name")`, keyed by name.
+    pub coercers: HashMap<String, Arc<dyn CustomCoercer>>,
+    /// Emit class fields in this order. By default they keep the order the model wrote them in,
+    /// followed by the fields it left out in declaration order.
+    pub field_order: Option<FieldOrder>,
+    /// Wrap a single value coerced into a union such as `string | string[]` in a list, as if
+    /// every such field were marked `@normalize("list")`.
+    pub normalize_lists: bool,
+    /// Words accepted as `bool` values besides `true` and `false`.
+    pub bool_vocabulary: BoolVocabulary,
+    /// Strings (`N/A`, `-`, ...) parsed as null for optional values, ignoring case and
+    /// surrounding whitespace, for fields without `@null_values`.
+    pub null_values: Vec<String>,
+    /// Hooks run around the coercion of every value, in order.
+    pub middleware: Vec<Arc<dyn CoercerMiddleware>>,
+    /// How many fields, list items and map values deep objects and arrays may nest, to bound
+    /// the work on adversarially deep outputs of recursive types. Deeper ones are cut to null
+    /// or an empty list or map, flagged `DepthLimitExceeded`, where the type allows it, and
+    /// fail to coerce otherwise. Unlimited if unset.
+    pub max_depth: Option<usize>,
+}
+
+impl std::fmt::Debug for CoerceOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut coercers = self.coercers.keys().collect::<Vec<_>>();
+        coercers.sort();
+        f.debug_struct("CoerceOptions")
+            .field("number_overflow", &self.number_overflow)
+            .field("int_overflow", &self.int_overflow)
+            .field("coercers", &coercers)
+            .field("field_order", &self.field_order)
+            .field("normalize_lists", &self.normalize_lists)
+            .field("bool_vocabulary", &self.bool_vocabulary)
+            .field("null_values", &self.null_values)
+            .field("middleware", &self.middleware.len())
+            .field("max_depth", &self.max_depth)
+            .finish()
+    }
+}
+
 pub struct ParsingContext<'a> {
     pub scope: Vec<String>,
     visited: HashSet<(String, jsonish::Value)>,
     pub of: &'a OutputFormatContent,
     pub allow_partials: bool,
+    pub options: &'a CoerceOptions,
+    /// The `@int_overflow` policy of the field being parsed, overriding the options.
+    int_overflow: Option<IntOverflow>,
+    /// The `@null_values` of the field being parsed, overriding the options.
+    null_values: Option<Vec<String>>,
 }
 
 impl ParsingContext<'_> {
@@ -37,12 +128,19 @@ impl ParsingContext<'_> {
         self.scope.join(".")
     }
 
-    pub(crate) fn new(of: &OutputFormatContent, allow_partials: bool) -> ParsingContext<'_> {
+    pub(crate) fn new<'a>(
+        of: &'a OutputFormatContent,
+        allow_partials: bool,
+        options: &'a CoerceOptions,
+    ) -> ParsingContext<'a> {
         ParsingContext {
             scope: Vec::new(),
             visited: HashSet::new(),
             of,
             allow_partials,
+            options,
+            int_overflow: None,
+            null_values: None,
         }
     }
 
@@ -54,9 +152,40 @@ impl ParsingContext<'_> {
             visited: self.visited.clone(),
             of: self.of,
             allow_partials: self.allow_partials,
+            options: self.options,
+            int_overflow: self.int_overflow,
+            null_values: self.null_values.clone(),
         }
     }
 
+    /// The same context, parsing a field with its `@int_overflow` and `@null_values`.
+    pub(crate) fn for_field(&self, metadata: &FieldMetadata) -> ParsingContext {
+        ParsingContext {
+            scope: self.scope.clone(),
+            visited: self.visited.clone(),
+            of: self.of,
+            allow_partials: self.allow_partials,
+            options: self.options,
+            int_overflow: metadata.int_overflow.or(self.int_overflow),
+            null_values: metadata
+                .null_values
+                .clone()
+                .or_else(|| self.null_values.clone()),
+        }
+    }
+
+    /// Whether `s` stands for null: one of the field's or call's null synonyms.
+    pub(crate) fn is_null_synonym(&self, s: &str) -> bool {
+        let synonyms = self.null_values.as_deref().unwrap_or(&self.options.null_values);
+        let s = s.trim();
+        synonyms.iter().any(|synonym| synonym.trim().eq_ignore_ascii_case(s))
+    }
+
+    /// The policy for numbers outside `int` range: the field's, else the call's.
+    pub(crate) fn int_overflow(&self) -> Option<IntOverflow> {
+        self.int_overflow.or(self.options.int_overflow)
+    }
+
     // TODO: This function and `enter_scope` are clonning both the scope vector
     // and visited hash set each time. Maybe it can be optimized with interior
     // mutability or something.
@@ -71,6 +200,19 @@ impl ParsingContext<'_> {
             visited: new_visited,
             of: self.of,
             allow_partials: self.allow_partials,
+            options: self.options,
+            int_overflow: self.int_overflow,
+            null_values: self.null_values.clone(),
+        }
+    }
+
+    /// An error at this scope showing `message` with its English `template`.
+    fn error(&self, message: Message, template: &str) -> ParsingError {
+        ParsingError {
+            reason: message.render(template),
+            scope: self.scope.clone(),
+            causes: vec![],
+            localizable: Some(message),
         }
     }
 
@@ -92,6 +234,7 @@ impl ParsingContext<'_> {
             ),
             scope: self.scope.clone(),
             causes: vec![],
+            localizable: None,
         }
     }
 
@@ -104,30 +247,100 @@ impl ParsingContext<'_> {
             reason: summary.to_string(),
             scope: self.scope.clone(),
             causes: error.into_iter().cloned().collect(),
+            localizable: None,
         }
     }
 
     pub(crate) fn error_unexpected_empty_array(&self, target: &FieldType) -> ParsingError {
+        self.error(
+            Message::new("unexpected_empty_array").arg("expected", target),
+            "Expected {expected}, got empty array",
+        )
+    }
+
+    pub(crate) fn error_number_overflow(&self, target: &FieldType, number: &str) -> ParsingError {
+        self.error(
+            Message::new("number_overflow")
+                .arg("number", number)
+                .arg("expected", target),
+            "{number} does not fit {expected} without losing precision",
+        )
+    }
+
+    pub(crate) fn error_invalid_base64(&self, target: &FieldType, reason: &str) -> ParsingError {
         ParsingError {
-            reason: format!("Expected {}, got empty array", target),
+            reason: format!("Expected {target} as base64: {reason}"),
             scope: self.scope.clone(),
             causes: vec![],
+            localizable: None,
         }
     }
 
-    pub(crate) fn error_unexpected_null(&self, target: &FieldType) -> ParsingError {
+    pub(crate) fn error_custom_coercer(&self, name: &str, error: anyhow::Error) -> ParsingError {
         ParsingError {
-            reason: format!("Expected {}, got null", target),
+            reason: format!("Coercer `{name}` failed: {error}"),
             scope: self.scope.clone(),
             causes: vec![],
+            localizable: None,
         }
     }
 
+    pub(crate) fn error_middleware(&self, error: anyhow::Error) -> ParsingError {
+        ParsingError {
+            reason: format!("Coercer middleware failed: {error}"),
+            scope: self.scope.clone(),
+            causes: vec![],
+            localizable: None,
+        }
+    }
+
+    pub(crate) fn error_required_if(&self, condition: &JinjaExpression) -> ParsingError {
+        self.error(
+            Message::new("required_if").arg("condition", condition),
+            "Required when `{condition}`, got null",
+        )
+    }
+
+    pub(crate) fn error_one_of(&self, group: &[String], set: &[&String]) -> ParsingError {
+        let got = if set.is_empty() {
+            "none".to_string()
+        } else {
+            set.iter()
+                .map(|f| format!("`{f}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let group = group
+            .iter()
+            .map(|f| format!("`{f}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.error(
+            Message::new("one_of").arg("group", group).arg("got", got),
+            "Expected exactly one of {group}, got {got}",
+        )
+    }
+
+    pub(crate) fn error_max_depth(&self, max_depth: usize) -> ParsingError {
+        self.error(
+            Message::new("max_depth").arg("max_depth", max_depth),
+            "Nested deeper than the maximum depth of {max_depth}",
+        )
+    }
+
+    pub(crate) fn error_unexpected_null(&self, target: &FieldType) -> ParsingError {
+        self.error(
+            Message::new("unexpected_null").arg("expected", target),
+            "Expected {expected}, got null",
+        )
+    }
+
     pub(crate) fn error_image_not_supported(&self) -> ParsingError {
         ParsingError {
             reason: "Image type is not supported here".to_string(),
             scope: self.scope.clone(),
             causes: vec![],
+            localizable: None,
         }
     }
 
@@ -136,6 +349,7 @@ impl ParsingContext<'_> {
             reason: "Audio type is not supported here".to_string(),
             scope: self.scope.clone(),
             causes: vec![],
+            localizable: None,
         }
     }
 
@@ -146,6 +360,7 @@ impl ParsingContext<'_> {
             ),
             scope: self.scope.clone(),
             causes: vec![],
+            localizable: None,
         }
     }
 
@@ -164,17 +379,23 @@ impl ParsingContext<'_> {
             scope: self.scope.clone(),
             causes: missing
                 .into_iter()
-                .map(|k| ParsingError {
-                    scope: self.scope.clone(),
-                    reason: format!("Missing required field: {}", k),
-                    causes: vec![],
+                .map(|k| {
+                    let message = Message::new("missing_required_field").arg("field", &k);
+                    ParsingError {
+                        scope: self.scope.clone(),
+                        reason: message.render("Missing required field: {field}"),
+                        causes: vec![],
+                        localizable: Some(message),
+                    }
                 })
                 .chain(unparsed.into_iter().map(|(k, e)| ParsingError {
                     scope: self.scope.clone(),
                     reason: format!("Failed to parse field {}: {}", k, e),
                     causes: vec![e.clone()],
+                    localizable: None,
                 }))
                 .collect(),
+            localizable: None,
         }
     }
 
@@ -183,19 +404,17 @@ impl ParsingContext<'_> {
         target: &FieldType,
         got: &T,
     ) -> ParsingError {
-        ParsingError {
-            reason: format!(
-                "Expected {}, got {:?}.",
-                match target {
-                    FieldType::Enum(_) => format!("{} enum value", target),
-                    FieldType::Class(_) => format!("{}", target),
-                    _ => format!("{target}"),
-                },
-                got
-            ),
-            scope: self.scope.clone(),
-            causes: vec![],
-        }
+        let expected = match target {
+            FieldType::Enum(_) => format!("{} enum value", target),
+            FieldType::Class(_) => format!("{}", target),
+            _ => format!("{target}"),
+        };
+        self.error(
+            Message::new("unexpected_type")
+                .arg("expected", expected)
+                .arg("got", format!("{got:?}")),
+            "Expected {expected}, got {got}.",
+        )
     }
 
     pub(crate) fn error_internal<T: std::fmt::Display>(&self, error: T) -> ParsingError {
@@ -203,6 +422,7 @@ impl ParsingContext<'_> {
             reason: format!("Internal error: {}", error),
             scope: self.scope.clone(),
             causes: vec![],
+            localizable: None,
         }
     }
 
@@ -215,6 +435,7 @@ impl ParsingContext<'_> {
             reason: format!("Circular reference detected for class-value pair {cls} <-> {value}"),
             scope: self.scope.clone(),
             causes: vec![],
+            localizable: None,
         }
     }
 }
@@ -224,6 +445,8 @@ pub struct ParsingError {
     pub scope: Vec<String>,
     pub reason: String,
     pub causes: Vec<ParsingError>,
+    /// What [`MessageCatalog`]s translate, for reasons with a template.
+    pub localizable: Option<Message>,
 }
 
 impl std::fmt::Display for ParsingError {
@@ -247,6 +470,60 @@ impl std::fmt::Display for ParsingError {
 
 impl std::error::Error for ParsingError {}
 
+impl ParsingError {
+    /// Like the `Display` output, with the reasons in the locale of `catalog` where it has a
+    /// translation.
+    pub fn localized(&self, catalog: &dyn MessageCatalog) -> String {
+        let reason = self
+            .localizable
+            .as_ref()
+            .and_then(|m| catalog.localize(m))
+            .unwrap_or_else(|| self.reason.clone());
+        let mut text = format!(
+            "{}: {reason}",
+            if self.scope.is_empty() {
+                "<root>".to_string()
+            } else {
+                self.scope.join(".")
+            }
+        );
+        for cause in &self.causes {
+            text.push_str(&format!("\n  - {}", cause.localized(catalog).replace('\n', "\n  ")));
+        }
+        text
+    }
+
+    /// The paths (such as `["fruit", "0", "price"]`) of the values the innermost errors that
+    /// caused this one are about, without duplicates. Missing fields are reported in the scope
+    /// of their object, so their path ends with the field.
+    pub fn leaf_paths(&self) -> Vec<Vec<String>> {
+        if self.causes.is_empty() {
+            let mut path = self.scope.clone();
+            if let Some(message) = self
+                .localizable
+                .as_ref()
+                .filter(|m| m.id == "missing_required_field")
+            {
+                path.extend(
+                    message
+                        .args
+                        .iter()
+                        .filter(|(name, _)| *name == "field")
+                        .map(|(_, field)| field.clone()),
+                );
+            }
+            return vec![path];
+        }
+        let mut paths = Vec::new();
+        for path in self.causes.iter().flat_map(ParsingError::leaf_paths) {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+}
+
 pub trait TypeCoercer {
     fn coerce(
         &self,
@@ -271,13 +548,19 @@ pub fn run_user_checks(
     type_: &FieldType,
 ) -> Result<Vec<(Constraint, bool)>> {
     match type_ {
-        FieldType::Constrained { constraints, .. } => constraints
-            .iter()
-            .map(|constraint| {
-                let result = evaluate_predicate(baml_value, &constraint.expression)?;
-                Ok((constraint.clone(), result))
-            })
-            .collect::<Result<Vec<_>>>(),
+        FieldType::Constrained { constraints, .. } => {
+            #[cfg(feature = "tracing")]
+            let _span =
+                tracing::info_span!("jsonish.constraints", constraints = constraints.len())
+                    .entered();
+            constraints
+                .iter()
+                .map(|constraint| {
+                    let result = evaluate_predicate(baml_value, &constraint.expression)?;
+                    Ok((constraint.clone(), result))
+                })
+                .collect::<Result<Vec<_>>>()
+        }
         _ => Ok(vec![]),
     }
 }
@@ -33,6 +33,8 @@ pub enum Flag {
     DefaultFromNoValue,
     DefaultButHadValue(crate::jsonish::Value),
     OptionalDefaultFromNoValue,
+    /// A string such as `N/A` parsed as null (`@null_values`).
+    NullSynonym(String),
 
     // String -> X convertions.
     StringToBool(String),
@@ -42,12 +44,26 @@ pub enum Flag {
 
     // Number -> X convertions.
     FloatToInt(f64),
+    /// A number outside the range of an int, clamped to it (`@int_overflow("saturate")`).
+    IntSaturated(String),
+    /// A number outside the range of an int, returned as a float (`@int_overflow("float")`).
+    IntToFloat(String),
 
     // X -> Object convertions.
     NoFields(Option<crate::jsonish::Value>),
 
     /// Constraint results (only contains checks)
-    ConstraintResults(Vec<(String, JinjaExpression, bool)>),
+    ConstraintResults(Vec<(Constraint, bool)>),
+
+    /// Matched a deprecated field or enum value: `(name, deprecation message)`.
+    Deprecated(String, String),
+
+    /// No variant of an open enum matched, so the raw string was kept.
+    OpenEnumFallback(String),
+
+    /// The value was nested deeper than the maximum depth (the payload), so it was cut to
+    /// null or an empty list or map.
+    DepthLimitExceeded(usize),
 }
 
 #[derive(Clone)]
@@ -89,15 +105,21 @@ impl DeserializerConditions {
                 Flag::DefaultFromNoValue => None,
                 Flag::DefaultButHadValue(_) => None,
                 Flag::OptionalDefaultFromNoValue => None,
+                Flag::NullSynonym(_) => None,
                 Flag::StringToBool(_) => None,
                 Flag::StringToNull(_) => None,
                 Flag::StringToChar(_) => None,
                 Flag::StringToFloat(_) => None,
                 Flag::FloatToInt(_) => None,
+                Flag::IntSaturated(_) => None,
+                Flag::IntToFloat(_) => None,
                 Flag::NoFields(_) => None,
                 Flag::UnionMatch(_idx, _) => None,
                 Flag::DefaultButHadUnparseableValue(e) => Some(e.clone()),
                 Flag::ConstraintResults(_) => None,
+                Flag::Deprecated(_, _) => None,
+                Flag::OpenEnumFallback(_) => None,
+                Flag::DepthLimitExceeded(_) => None,
             })
             .collect::<Vec<_>>()
     }
@@ -106,10 +128,16 @@ impl DeserializerConditions {
         self.flags
             .iter()
             .filter_map(|flag| match flag {
-                Flag::ConstraintResults(cs) => Some(cs.clone()),
+                Flag::ConstraintResults(cs) => Some(cs),
                 _ => None,
             })
             .flatten()
+            .filter_map(|(constraint, result)| {
+                constraint
+                    .clone()
+                    .as_check()
+                    .map(|(label, expr)| (label, expr, *result))
+            })
             .collect()
     }
 }
@@ -144,6 +172,9 @@ impl std::fmt::Display for Flag {
             Flag::OptionalDefaultFromNoValue => {
                 write!(f, "Optional Default value")?;
             }
+            Flag::NullSynonym(value) => {
+                write!(f, "Null synonym: {}", value)?;
+            }
             Flag::DefaultFromNoValue => {
                 write!(f, "Default value")?;
             }
@@ -243,6 +274,12 @@ impl std::fmt::Display for Flag {
             Flag::FloatToInt(value) => {
                 write!(f, "Float to int: {}", value)?;
             }
+            Flag::IntSaturated(value) => {
+                write!(f, "Int saturated: {}", value)?;
+            }
+            Flag::IntToFloat(value) => {
+                write!(f, "Int to float: {}", value)?;
+            }
             Flag::NoFields(value) => {
                 write!(f, "No fields: ")?;
                 if let Some(value) = value {
@@ -252,20 +289,69 @@ impl std::fmt::Display for Flag {
                 }
             }
             Flag::ConstraintResults(cs) => {
-                for (label, _, succeeded) in cs.iter() {
+                for (constraint, succeeded) in cs.iter() {
                     let f_result = if *succeeded { "Succeeded" } else { "Failed" };
-                    writeln!(
-                        f,
-                        "{level:?} {label} {f_result}",
-                        level = ConstraintLevel::Check
-                    )?;
+                    let label = constraint.label.as_deref().unwrap_or("");
+                    writeln!(f, "{:?} {label} {f_result}", constraint.level)?;
                 }
             }
+            Flag::Deprecated(name, message) => {
+                write!(f, "Deprecated {name}: {message}")?;
+            }
+            Flag::OpenEnumFallback(value) => {
+                write!(f, "Open enum fallback: {value}")?;
+            }
+            Flag::DepthLimitExceeded(max_depth) => {
+                write!(f, "Cut at the maximum depth of {max_depth}")?;
+            }
         }
         Ok(())
     }
 }
 
+impl Flag {
+    /// A stable, payload-free name for the flag, suitable for metrics labels.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Flag::ObjectFromMarkdown(_) => "ObjectFromMarkdown",
+            Flag::ObjectFromFixedJson(_) => "ObjectFromFixedJson",
+            Flag::DefaultButHadUnparseableValue(_) => "DefaultButHadUnparseableValue",
+            Flag::ObjectToString(_) => "ObjectToString",
+            Flag::ObjectToPrimitive(_) => "ObjectToPrimitive",
+            Flag::ObjectToMap(_) => "ObjectToMap",
+            Flag::ExtraKey(_, _) => "ExtraKey",
+            Flag::StrippedNonAlphaNumeric(_) => "StrippedNonAlphaNumeric",
+            Flag::SubstringMatch(_) => "SubstringMatch",
+            Flag::SingleToArray => "SingleToArray",
+            Flag::ArrayItemParseError(_, _) => "ArrayItemParseError",
+            Flag::MapKeyParseError(_, _) => "MapKeyParseError",
+            Flag::MapValueParseError(_, _) => "MapValueParseError",
+            Flag::JsonToString(_) => "JsonToString",
+            Flag::ImpliedKey(_) => "ImpliedKey",
+            Flag::InferedObject(_) => "InferedObject",
+            Flag::FirstMatch(_, _) => "FirstMatch",
+            Flag::UnionMatch(_, _) => "UnionMatch",
+            Flag::StrMatchOneFromMany(_) => "StrMatchOneFromMany",
+            Flag::DefaultFromNoValue => "DefaultFromNoValue",
+            Flag::DefaultButHadValue(_) => "DefaultButHadValue",
+            Flag::OptionalDefaultFromNoValue => "OptionalDefaultFromNoValue",
+            Flag::NullSynonym(_) => "NullSynonym",
+            Flag::StringToBool(_) => "StringToBool",
+            Flag::StringToNull(_) => "StringToNull",
+            Flag::StringToChar(_) => "StringToChar",
+            Flag::StringToFloat(_) => "StringToFloat",
+            Flag::FloatToInt(_) => "FloatToInt",
+            Flag::IntSaturated(_) => "IntSaturated",
+            Flag::IntToFloat(_) => "IntToFloat",
+            Flag::NoFields(_) => "NoFields",
+            Flag::ConstraintResults(_) => "ConstraintResults",
+            Flag::Deprecated(_, _) => "Deprecated",
+            Flag::OpenEnumFallback(_) => "OpenEnumFallback",
+            Flag::DepthLimitExceeded(_) => "DepthLimitExceeded",
+        }
+    }
+}
+
 impl DeserializerConditions {
     pub fn add_flag(&mut self, flag: Flag) {
         self.flags.push(flag);
@@ -16,7 +16,9 @@ impl WithScore for BamlValueWithFlags {
             BamlValueWithFlags::String(s) => s.score(),
             BamlValueWithFlags::Int(s) => s.score(),
             BamlValueWithFlags::Float(s) => s.score(),
+            BamlValueWithFlags::Decimal(s) => s.score(),
             BamlValueWithFlags::Bool(s) => s.score(),
+            BamlValueWithFlags::Bytes(s) => s.score(),
             BamlValueWithFlags::List(s, items) => {
                 s.score() + 10 * items.iter().map(WithScore::score).sum::<i32>()
             }
@@ -36,6 +38,7 @@ impl WithScore for Flag {
         match self {
             Flag::InferedObject(_) => 0, // Dont penalize for this but instead handle it at the top level
             Flag::OptionalDefaultFromNoValue => 1,
+            Flag::NullSynonym(_) => 1,
             Flag::DefaultFromNoValue => 100,
             Flag::DefaultButHadValue(_) => 110,
             Flag::ObjectFromFixedJson(_) => 0,
@@ -66,9 +69,17 @@ impl WithScore for Flag {
             Flag::StringToChar(_) => 1,
             Flag::StringToFloat(_) => 1,
             Flag::FloatToInt(_) => 1,
+            Flag::IntSaturated(_) => 1,
+            Flag::IntToFloat(_) => 1,
             Flag::NoFields(_) => 1,
             // No scores for contraints
             Flag::ConstraintResults(_) => 0,
+            // Deprecated members are still valid
+            Flag::Deprecated(_, _) => 0,
+            // Prefer any actual match over keeping the raw value
+            Flag::OpenEnumFallback(_) => 3,
+            // Part of the output was dropped
+            Flag::DepthLimitExceeded(_) => 3,
         }
     }
 }
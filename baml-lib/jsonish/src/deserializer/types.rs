@@ -16,7 +16,12 @@ pub enum BamlValueWithFlags {
     String(ValueWithFlags<String>),
     Int(ValueWithFlags<i64>),
     Float(ValueWithFlags<f64>),
+    /// An int or float kept as its exact decimal text, see [`NumberOverflow::Preserve`].
+    ///
+    /// [`NumberOverflow::Preserve`]: crate::NumberOverflow::Preserve
+    Decimal(ValueWithFlags<String>),
     Bool(ValueWithFlags<bool>),
+    Bytes(ValueWithFlags<Vec<u8>>),
     List(DeserializerConditions, Vec<BamlValueWithFlags>),
     Map(
         DeserializerConditions,
@@ -38,7 +43,9 @@ impl BamlValueWithFlags {
             BamlValueWithFlags::String(_)
             | BamlValueWithFlags::Int(_)
             | BamlValueWithFlags::Float(_)
+            | BamlValueWithFlags::Decimal(_)
             | BamlValueWithFlags::Bool(_)
+            | BamlValueWithFlags::Bytes(_)
             | BamlValueWithFlags::Null(_)
             | BamlValueWithFlags::Enum(_, _) => false,
 
@@ -54,7 +61,9 @@ impl BamlValueWithFlags {
             BamlValueWithFlags::String(f) => f.score(),
             BamlValueWithFlags::Int(f) => f.score(),
             BamlValueWithFlags::Float(f) => f.score(),
+            BamlValueWithFlags::Decimal(f) => f.score(),
             BamlValueWithFlags::Bool(f) => f.score(),
+            BamlValueWithFlags::Bytes(f) => f.score(),
             BamlValueWithFlags::List(f, items) => {
                 f.score() + items.iter().map(|i| i.score()).sum::<i32>()
             }
@@ -78,7 +87,9 @@ impl BamlValueWithFlags {
             BamlValueWithFlags::String(v) => &v.flags,
             BamlValueWithFlags::Int(v) => &v.flags,
             BamlValueWithFlags::Float(v) => &v.flags,
+            BamlValueWithFlags::Decimal(v) => &v.flags,
             BamlValueWithFlags::Bool(v) => &v.flags,
+            BamlValueWithFlags::Bytes(v) => &v.flags,
             BamlValueWithFlags::List(v, _) => v,
             BamlValueWithFlags::Map(v, _) => v,
             BamlValueWithFlags::Enum(_, v) => &v.flags,
@@ -122,6 +133,7 @@ impl BamlValueWithFlags {
                         scope: scope.clone(),
                         reason: "error while parsing string".to_string(),
                         causes,
+                        localizable: None,
                     });
                 }
             }
@@ -132,6 +144,7 @@ impl BamlValueWithFlags {
                         scope: scope.clone(),
                         reason: "error while parsing int".to_string(),
                         causes,
+                        localizable: None,
                     });
                 }
             }
@@ -142,6 +155,29 @@ impl BamlValueWithFlags {
                         scope: scope.clone(),
                         reason: "error while parsing float".to_string(),
                         causes,
+                        localizable: None,
+                    });
+                }
+            }
+            BamlValueWithFlags::Decimal(v) => {
+                let causes = v.flags.explanation();
+                if !causes.is_empty() {
+                    expls.push(ParsingError {
+                        scope: scope.clone(),
+                        reason: "error while parsing decimal".to_string(),
+                        causes,
+                        localizable: None,
+                    });
+                }
+            }
+            BamlValueWithFlags::Bytes(v) => {
+                let causes = v.flags.explanation();
+                if !causes.is_empty() {
+                    expls.push(ParsingError {
+                        scope: scope.clone(),
+                        reason: "error while parsing bytes".to_string(),
+                        causes,
+                        localizable: None,
                     });
                 }
             }
@@ -152,6 +188,7 @@ impl BamlValueWithFlags {
                         scope: scope.clone(),
                         reason: "error while parsing bool".to_string(),
                         causes,
+                        localizable: None,
                     });
                 }
             }
@@ -162,6 +199,7 @@ impl BamlValueWithFlags {
                         scope: scope.clone(),
                         reason: "error while parsing list".to_string(),
                         causes,
+                        localizable: None,
                     });
                 }
                 for (i, value) in values.iter().enumerate() {
@@ -177,6 +215,7 @@ impl BamlValueWithFlags {
                         scope: scope.clone(),
                         reason: "error while parsing map".to_string(),
                         causes,
+                        localizable: None,
                     });
                 }
                 for (k, (v_flags, v)) in kv.iter() {
@@ -186,6 +225,7 @@ impl BamlValueWithFlags {
                             scope: scope.clone(),
                             reason: format!("error while parsing value for map key '{}'", k),
                             causes,
+                            localizable: None,
                         });
                     }
                     let mut scope = scope.clone();
@@ -200,6 +240,7 @@ impl BamlValueWithFlags {
                         scope: scope.clone(),
                         reason: format!("error while parsing {enum_name} enum value"),
                         causes,
+                        localizable: None,
                     });
                 }
             }
@@ -210,6 +251,7 @@ impl BamlValueWithFlags {
                         scope: scope.clone(),
                         reason: format!("error while parsing class {}", class_name),
                         causes,
+                        localizable: None,
                     });
                 }
                 for (k, v) in fields.iter() {
@@ -226,6 +268,7 @@ impl BamlValueWithFlags {
                         scope: scope.clone(),
                         reason: "error while parsing null".to_string(),
                         causes,
+                        localizable: None,
                     });
                 }
             }
@@ -236,6 +279,7 @@ impl BamlValueWithFlags {
                         scope: scope.clone(),
                         reason: "error while parsing media".to_string(),
                         causes,
+                        localizable: None,
                     });
                 }
             }
@@ -296,7 +340,9 @@ impl From<BamlValueWithFlags> for BamlValue {
             BamlValueWithFlags::String(s) => BamlValue::String(s.value),
             BamlValueWithFlags::Int(i) => BamlValue::Int(i.value),
             BamlValueWithFlags::Float(f) => BamlValue::Float(f.value),
+            BamlValueWithFlags::Decimal(d) => BamlValue::Decimal(d.value),
             BamlValueWithFlags::Bool(b) => BamlValue::Bool(b.value),
+            BamlValueWithFlags::Bytes(b) => BamlValue::Bytes(b.value),
             BamlValueWithFlags::List(_, v) => {
                 BamlValue::List(v.into_iter().map(|x| x.into()).collect())
             }
@@ -319,7 +365,9 @@ impl From<&BamlValueWithFlags> for BamlValue {
             BamlValueWithFlags::String(s) => BamlValue::String(s.value.clone()),
             BamlValueWithFlags::Int(i) => BamlValue::Int(i.value),
             BamlValueWithFlags::Float(f) => BamlValue::Float(f.value),
+            BamlValueWithFlags::Decimal(d) => BamlValue::Decimal(d.value.clone()),
             BamlValueWithFlags::Bool(b) => BamlValue::Bool(b.value),
+            BamlValueWithFlags::Bytes(b) => BamlValue::Bytes(b.value.clone()),
             BamlValueWithFlags::List(_, v) => BamlValue::List(v.iter().map(|x| x.into()).collect()),
             BamlValueWithFlags::Map(_, m) => BamlValue::Map(
                 m.into_iter()
@@ -343,7 +391,9 @@ impl BamlValueWithFlags {
             BamlValueWithFlags::String(v) => v.flags.add_flag(flag),
             BamlValueWithFlags::Int(v) => v.flags.add_flag(flag),
             BamlValueWithFlags::Float(v) => v.flags.add_flag(flag),
+            BamlValueWithFlags::Decimal(v) => v.flags.add_flag(flag),
             BamlValueWithFlags::Bool(v) => v.flags.add_flag(flag),
+            BamlValueWithFlags::Bytes(v) => v.flags.add_flag(flag),
             BamlValueWithFlags::List(v, _) => v.add_flag(flag),
             BamlValueWithFlags::Map(v, _) => v.add_flag(flag),
             BamlValueWithFlags::Enum(_, v) => v.flags.add_flag(flag),
@@ -358,7 +408,9 @@ impl BamlValueWithFlags {
             BamlValueWithFlags::String(_) => "String".to_string(),
             BamlValueWithFlags::Int(_) => "Int".to_string(),
             BamlValueWithFlags::Float(_) => "Float".to_string(),
+            BamlValueWithFlags::Decimal(_) => "Decimal".to_string(),
             BamlValueWithFlags::Bool(_) => "Bool".to_string(),
+            BamlValueWithFlags::Bytes(_) => "Bytes".to_string(),
             BamlValueWithFlags::List(_, i) => {
                 let inner = i
                     .iter()
@@ -400,6 +452,18 @@ impl std::fmt::Display for BamlValueWithFlags {
                     write!(f, "\n  {}", v.flags.to_string().replace("\n", "\n  "))?;
                 }
             }
+            BamlValueWithFlags::Decimal(v) => {
+                write!(f, "{}", v.value)?;
+                if !v.flags.flags.is_empty() {
+                    write!(f, "\n  {}", v.flags.to_string().replace("\n", "\n  "))?;
+                }
+            }
+            BamlValueWithFlags::Bytes(v) => {
+                write!(f, "<{} bytes>", v.value.len())?;
+                if !v.flags.flags.is_empty() {
+                    write!(f, "\n  {}", v.flags.to_string().replace("\n", "\n  "))?;
+                }
+            }
             BamlValueWithFlags::Bool(v) => {
                 write!(f, "{}", v.value)?;
                 if !v.flags.flags.is_empty() {
@@ -463,7 +527,9 @@ impl From<BamlValueWithFlags> for BamlValueWithMeta<Vec<(String, JinjaExpression
             String(ValueWithFlags { value, .. }) => BamlValueWithMeta::String(value, c),
             Int(ValueWithFlags { value, .. }) => BamlValueWithMeta::Int(value, c),
             Float(ValueWithFlags { value, .. }) => BamlValueWithMeta::Float(value, c),
+            Decimal(ValueWithFlags { value, .. }) => BamlValueWithMeta::Decimal(value, c),
             Bool(ValueWithFlags { value, .. }) => BamlValueWithMeta::Bool(value, c),
+            Bytes(ValueWithFlags { value, .. }) => BamlValueWithMeta::Bytes(value, c),
             Map(_, values) => BamlValueWithMeta::Map(
                 values.into_iter().map(|(k, v)| (k, v.1.into())).collect(),
                 c,
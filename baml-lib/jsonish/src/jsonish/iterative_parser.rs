@@ -152,6 +152,8 @@ impl From<JsonCollection> for Option<serde_json::Value> {
                     serde_json::Value::Number(n.into())
                 } else if let Ok(n) = s.parse::<u64>() {
                     serde_json::Value::Number(n.into())
+                } else if let Ok(n) = s.parse::<serde_json::Number>() {
+                    serde_json::Value::Number(n)
                 } else if let Ok(n) = s.parse::<f64>() {
                     serde_json::Value::Number(serde_json::Number::from_f64(n).unwrap())
                 } else {
@@ -29,6 +29,10 @@ pub fn parse(str: &str, mut options: ParseOptions) -> Result<Value> {
         }
     };
 
+    // Not valid JSON as-is: extract it from markdown, surrounding text or fix it up.
+    #[cfg(feature = "tracing")]
+    let _extract_span = tracing::info_span!("jsonish.extract", depth = options.depth).entered();
+
     if options.allow_markdown_json {
         match markdown_parser::parse(str, &options) {
             Ok(items) => match items.len() {
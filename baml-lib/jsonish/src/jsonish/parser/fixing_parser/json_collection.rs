@@ -87,6 +87,9 @@ impl From<JsonCollection> for Option<Value> {
                     Value::Number(n.into())
                 } else if let Ok(n) = s.parse::<u64>() {
                     Value::Number(n.into())
+                } else if let Ok(n) = s.parse::<serde_json::Number>() {
+                    // Keeps every digit, see `NumberOverflow`.
+                    Value::Number(n)
                 } else if let Ok(n) = s.parse::<f64>() {
                     match serde_json::Number::from_f64(n) {
                         Some(n) => Value::Number(n),
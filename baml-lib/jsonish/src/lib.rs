@@ -8,25 +8,85 @@ mod jsonish;
 use baml_types::FieldType;
 use deserializer::coercer::{ParsingContext, TypeCoercer};
 
+pub use deserializer::coercer::{
+    BoolVocabulary, CoerceOptions, CoercerMiddleware, CustomCoercer, NumberOverflow, ParsingError,
+    TypeKind,
+};
 pub use deserializer::types::BamlValueWithFlags;
-use internal_baml_core::ir::TypeValue;
+use internal_baml_core::{
+    internal_baml_diagnostics::{Message, MessageCatalog},
+    ir::TypeValue,
+};
 use internal_baml_jinja::types::OutputFormatContent;
 
 use deserializer::deserialize_flags::Flag;
 use jsonish::Value;
 
+/// The output could not be coerced into the target type. `from_str` errors can be downcast to
+/// it to inspect the underlying [`ParsingError`].
+///
+/// [`ParsingError`]: deserializer::coercer::ParsingError
+#[derive(Debug)]
+pub struct CoerceError(pub deserializer::coercer::ParsingError);
+
+impl std::fmt::Display for CoerceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to coerce value: {}", self.0)
+    }
+}
+
+impl std::error::Error for CoerceError {}
+
+impl CoerceError {
+    /// Like the `Display` output, in the locale of `catalog` where it has a translation.
+    pub fn localized(&self, catalog: &dyn MessageCatalog) -> String {
+        let message = Message::new("coerce_failed").arg("error", self.0.localized(catalog));
+        catalog
+            .localize(&message)
+            .unwrap_or_else(|| message.render("Failed to coerce value: {error}"))
+    }
+}
+
 pub fn from_str(
     of: &OutputFormatContent,
     target: &FieldType,
     raw_string: &str,
     allow_partials: bool,
+) -> Result<BamlValueWithFlags> {
+    from_str_with_options(
+        of,
+        target,
+        raw_string,
+        allow_partials,
+        &CoerceOptions::default(),
+    )
+}
+
+pub fn from_str_with_options(
+    of: &OutputFormatContent,
+    target: &FieldType,
+    raw_string: &str,
+    allow_partials: bool,
+    options: &CoerceOptions,
 ) -> Result<BamlValueWithFlags> {
     if matches!(target, FieldType::Primitive(TypeValue::String)) {
         return Ok(BamlValueWithFlags::String(raw_string.to_string().into()));
     }
 
     // When the schema is just a string, i should really just return the raw_string w/o parsing it.
+    #[cfg(feature = "tracing")]
+    let parse_span =
+        tracing::info_span!("jsonish.parse", candidates = tracing::field::Empty).entered();
     let mut value = jsonish::parse(raw_string, jsonish::ParseOptions::default())?;
+    #[cfg(feature = "tracing")]
+    {
+        let candidates = match &value {
+            Value::AnyOf(items, _) => items.len(),
+            _ => 1,
+        };
+        parse_span.record("candidates", candidates);
+        drop(parse_span);
+    }
     // let schema = deserializer::schema::from_jsonish_value(&value, None);
 
     // See Note [Streaming Number Invalidation]
@@ -37,7 +97,7 @@ pub fn from_str(
     // Pick the schema that is the most specific.
     // log::info!("Parsed: {}", schema);
     log::debug!("Parsed JSONish (step 1 of parsing): {:#?}", value);
-    let ctx = ParsingContext::new(of, allow_partials);
+    let ctx = ParsingContext::new(of, allow_partials, options);
     // let res = schema.cast_to(target);
     // log::info!("Casted: {:?}", res);
 
@@ -49,6 +109,13 @@ pub fn from_str(
     // Determine the best way to get the desired schema from the parsed schema.
 
     // Lets try to now coerce the value into the expected schema.
+    #[cfg(feature = "tracing")]
+    let _coerce_span = tracing::info_span!(
+        "jsonish.coerce",
+        target = %target,
+        allow_partials,
+    )
+    .entered();
     match target.coerce(&ctx, target, Some(&value)) {
         Ok(v) => {
             if v.conditions()
@@ -61,7 +128,7 @@ pub fn from_str(
 
             Ok(v)
         }
-        Err(e) => anyhow::bail!("Failed to coerce value: {}", e),
+        Err(e) => Err(CoerceError(e).into()),
     }
 }
 
@@ -1,11 +1,14 @@
 use anyhow::Result;
-use internal_baml_jinja::types::{Class, Enum, Name, OutputFormatContent};
+use internal_baml_jinja::types::{
+    Class, Enum, EnumValueMetadata, FieldMetadata, Name, OutputFormatContent,
+};
 
 #[macro_use]
 pub mod macros;
 
 mod test_aliases;
 mod test_basics;
+mod test_bytes;
 mod test_class;
 mod test_class_2;
 mod test_code;
@@ -14,6 +17,7 @@ mod test_enum;
 mod test_lists;
 mod test_literals;
 mod test_maps;
+mod test_numbers;
 mod test_partials;
 mod test_unions;
 
@@ -73,7 +77,7 @@ fn find_existing_class_field(
     field_name: &str,
     class_walker: &Result<ClassWalker<'_>>,
     env_values: &EvaluationContext<'_>,
-) -> Result<(Name, FieldType, Option<String>)> {
+) -> Result<(Name, FieldType, Option<String>, FieldMetadata)> {
     let Ok(class_walker) = class_walker else {
         anyhow::bail!("Class {} does not exist", class_name);
     };
@@ -85,7 +89,7 @@ fn find_existing_class_field(
     let name = Name::new_with_alias(field_name.to_string(), field_walker.alias(env_values)?);
     let desc = field_walker.description(env_values)?;
     let r#type = field_walker.r#type();
-    Ok((name, r#type.clone(), desc))
+    Ok((name, r#type.clone(), desc, FieldMetadata::default()))
 }
 
 fn find_enum_value(
@@ -93,7 +97,7 @@ fn find_enum_value(
     value_name: &str,
     enum_walker: &Result<EnumWalker<'_>>,
     env_values: &EvaluationContext<'_>,
-) -> Result<Option<(Name, Option<String>)>> {
+) -> Result<Option<(Name, Option<String>, EnumValueMetadata)>> {
     if enum_walker.is_err() {
         anyhow::bail!("Enum {} does not exist", enum_name);
     }
@@ -115,7 +119,7 @@ fn find_enum_value(
     let name = Name::new_with_alias(value_name.to_string(), value_walker.alias(env_values)?);
     let desc = value_walker.description(env_values)?;
 
-    Ok(Some((name, desc)))
+    Ok(Some((name, desc, EnumValueMetadata::default())))
 }
 
 // TODO: This function is "almost" a duplicate of `relevant_data_models` at
@@ -166,6 +170,7 @@ fn relevant_data_models<'a>(
                         name: Name::new_with_alias(enm.to_string(), walker?.alias(env_values)?),
                         values,
                         constraints,
+                        open: false,
                     });
                 }
             }
@@ -210,7 +215,7 @@ fn relevant_data_models<'a>(
 
                     let fields = fields.collect::<Result<Vec<_>>>()?;
 
-                    for (_, t, _) in fields.iter().as_ref() {
+                    for (_, t, ..) in fields.iter().as_ref() {
                         if !checked_types.contains(&t.to_string()) {
                             start.push(t.clone());
                         }
@@ -236,6 +241,7 @@ fn relevant_data_models<'a>(
                         name: Name::new_with_alias(cls.to_string(), walker?.alias(env_values)?),
                         fields,
                         constraints,
+                        one_of: Vec::new(),
                     });
                 }
             }
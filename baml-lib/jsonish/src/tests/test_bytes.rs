@@ -0,0 +1,64 @@
+use super::*;
+
+const ATTACHMENT: &str = r#"
+class Attachment {
+  name string
+  data bytes
+}
+"#;
+
+test_deserializer!(
+    test_bytes_from_base64,
+    ATTACHMENT,
+    r#"{"name": "hello.txt", "data": "aGVsbG8gd29ybGQ="}"#,
+    FieldType::Class("Attachment".to_string()),
+    {"name": "hello.txt", "data": "aGVsbG8gd29ybGQ="}
+);
+
+test_deserializer!(
+    test_bytes_from_data_url,
+    ATTACHMENT,
+    r#"{"name": "hello.txt", "data": "data:text/plain;base64,aGVsbG8g\nd29ybGQ="}"#,
+    FieldType::Class("Attachment".to_string()),
+    {"name": "hello.txt", "data": "aGVsbG8gd29ybGQ="}
+);
+
+test_deserializer!(
+    test_bytes_from_url_safe_base64,
+    ATTACHMENT,
+    r#"{"name": "blob", "data": "-_8="}"#,
+    FieldType::Class("Attachment".to_string()),
+    {"name": "blob", "data": "+/8="}
+);
+
+test_failing_deserializer!(
+    test_bytes_bad_padding,
+    ATTACHMENT,
+    r#"{"name": "hello.txt", "data": "aGVsbG8gd29ybGQ"}"#,
+    FieldType::Class("Attachment".to_string())
+);
+
+test_failing_deserializer!(
+    test_bytes_not_base64,
+    ATTACHMENT,
+    r#"{"name": "hello.txt", "data": "hello world!"}"#,
+    FieldType::Class("Attachment".to_string())
+);
+
+#[test]
+fn test_bytes_are_raw() {
+    let ir = load_test_ir(ATTACHMENT);
+    let target_type = FieldType::Class("Attachment".to_string());
+    let target = render_output_format(&ir, &target_type, &Default::default()).unwrap();
+    let value = from_str(
+        &target,
+        &target_type,
+        r#"{"name": "a", "data": "AAEC"}"#,
+        false,
+    );
+
+    let BamlValue::Class(_, fields) = BamlValue::from(value.unwrap()) else {
+        panic!("expected a class");
+    };
+    assert_eq!(fields["data"], BamlValue::Bytes(vec![0, 1, 2]));
+}
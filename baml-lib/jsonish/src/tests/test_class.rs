@@ -1486,3 +1486,143 @@ test_deserializer!(
     },
   }
 );
+
+const FLAT_FILE: &str = r#"
+enum Color {
+  RED
+  GREEN
+}
+
+class Flat {
+  name string
+  age int?
+  tags string[]
+  color Color
+  note string?
+}
+
+class Nested {
+  inner Flat
+}
+
+// The same fields as `Flat`, but `nested` makes it take the generic path.
+class NotFlat {
+  name string
+  age int?
+  tags string[]
+  color Color
+  note string?
+  nested Nested?
+}
+"#;
+
+#[test_log::test]
+fn test_flat_fast_path_matches_generic_path() {
+    use crate::deserializer::deserialize_flags::Flag;
+    use crate::BamlValueWithFlags;
+
+    let ir = load_test_ir(FLAT_FILE);
+    let outputs = [
+        r#"{"name": "a", "age": "12", "tags": "x", "color": "red", "extra": 1}"#,
+        r#"{"color": "GREEN", "name": "a", "name": "b", "age": "twelve", "tags": []}"#,
+        r#"{"name": "a", "color": "RED"}"#,
+        r#"[{"name": "a", "tags": ["x"], "color": "RED"}]"#,
+    ];
+    for output in outputs {
+        let parse = |class: &str| {
+            let target_type = FieldType::Class(class.to_string());
+            let target = render_output_format(&ir, &target_type, &Default::default()).unwrap();
+            let BamlValueWithFlags::Class(_, flags, mut fields) =
+                from_str(&target, &target_type, output, false).unwrap()
+            else {
+                panic!("expected a class");
+            };
+            fields.shift_remove("nested");
+            // The flag recording the candidates holds the class itself, so it differs.
+            let flags = flags
+                .flags()
+                .iter()
+                .filter(|flag| !matches!(flag, Flag::FirstMatch(..)))
+                .collect::<Vec<_>>();
+            format!("{flags:?} {fields:?}")
+        };
+        assert_eq!(parse("Flat"), parse("NotFlat"), "{output}");
+    }
+
+    // Errors are built the same way too.
+    let error = |class: &str| {
+        let target_type = FieldType::Class(class.to_string());
+        let target = render_output_format(&ir, &target_type, &Default::default()).unwrap();
+        let output = r#"{"age": 1, "color": "BLUE"}"#;
+        from_str(&target, &target_type, output, false)
+            .unwrap_err()
+            .to_string()
+    };
+    assert!(
+        error("Flat").contains("missing=1, unparsed=1"),
+        "{}",
+        error("Flat")
+    );
+    assert_eq!(error("Flat"), error("NotFlat"));
+}
+
+#[test]
+fn test_recursive_class_cut_at_max_depth() {
+    use crate::{deserializer::deserialize_flags::Flag, BamlValueWithFlags};
+
+    let target_type = FieldType::Class("Tree".to_string());
+    let ir = load_test_ir(
+        r#"
+class Tree {
+  value int
+  children Tree[]
+  parent Tree?
+}
+"#,
+    );
+    let target = render_output_format(&ir, &target_type, &Default::default()).unwrap();
+    let options = crate::CoerceOptions {
+        max_depth: Some(2),
+        ..Default::default()
+    };
+    let raw = r#"{"value": 1, "children": [
+        {"value": 2, "children": [{"value": 3, "children": []}], "parent": {"value": 1, "children": []}}
+    ]}"#;
+    let value = crate::from_str_with_options(&target, &target_type, raw, false, &options).unwrap();
+    assert_eq!(
+        serde_json::to_value(BamlValue::from(value.clone())).unwrap(),
+        json!({
+            "value": 1,
+            "children": [{"value": 2, "children": [], "parent": null}],
+            "parent": null,
+        })
+    );
+
+    let BamlValueWithFlags::Class(_, _, fields) = &value else {
+        panic!("expected a class, got {value:?}");
+    };
+    let BamlValueWithFlags::List(_, children) = &fields["children"] else {
+        panic!("expected a list");
+    };
+    let BamlValueWithFlags::Class(_, _, child) = &children[0] else {
+        panic!("expected a class");
+    };
+    for cut in ["children", "parent"] {
+        assert!(
+            child[cut]
+                .conditions()
+                .flags()
+                .iter()
+                .any(|flag| matches!(flag, Flag::DepthLimitExceeded(2))),
+            "{cut}: {:?}",
+            child[cut]
+        );
+    }
+
+    // Without a limit, the whole tree is kept.
+    let value =
+        crate::from_str_with_options(&target, &target_type, raw, false, &Default::default())
+            .unwrap();
+    let value = serde_json::to_value(BamlValue::from(value)).unwrap();
+    assert_eq!(value["children"][0]["children"][0]["value"], 3);
+}
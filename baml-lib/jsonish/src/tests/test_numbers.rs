@@ -0,0 +1,117 @@
+use super::*;
+use crate::{from_str_with_options, BamlValueWithFlags, CoerceOptions, NumberOverflow};
+use internal_baml_jinja::types::IntOverflow;
+
+const INVOICE: &str = r#"
+class Invoice {
+  id int
+  amount float
+}
+"#;
+
+fn parse_invoice(raw: &str, number_overflow: NumberOverflow) -> Result<String> {
+    let ir = load_test_ir(INVOICE);
+    let target_type = FieldType::Class("Invoice".to_string());
+    let target = render_output_format(&ir, &target_type, &Default::default())?;
+    let options = CoerceOptions {
+        number_overflow,
+        ..Default::default()
+    };
+    let value = from_str_with_options(&target, &target_type, raw, false, &options)?;
+    Ok(serde_json::to_string(&BamlValue::from(value))?)
+}
+
+test_deserializer!(
+    test_numbers_are_lossy_by_default,
+    INVOICE,
+    r#"{"id": 12345, "amount": 1234567890123.456789}"#,
+    FieldType::Class("Invoice".to_string()),
+    {"id": 12345, "amount": 1234567890123.4568}
+);
+
+#[test]
+fn test_preserve_keeps_every_digit() {
+    let raw = r#"{"id": 123456789012345678901234567890, "amount": 1234567890123.456789}"#;
+    assert_eq!(
+        parse_invoice(raw, NumberOverflow::Preserve).unwrap(),
+        r#"{"id":123456789012345678901234567890,"amount":1234567890123.456789}"#
+    );
+}
+
+#[test]
+fn test_preserve_quoted_numbers() {
+    let raw = r#"{"id": "18446744073709551616", "amount": "0.30000000000000000001"}"#;
+    assert_eq!(
+        parse_invoice(raw, NumberOverflow::Preserve).unwrap(),
+        r#"{"id":18446744073709551616,"amount":0.30000000000000000001}"#
+    );
+}
+
+#[test]
+fn test_preserve_leaves_exact_numbers_alone() {
+    let raw = r#"{"id": 9007199254740993, "amount": 12.50}"#;
+    assert_eq!(
+        parse_invoice(raw, NumberOverflow::Preserve).unwrap(),
+        r#"{"id":9007199254740993,"amount":12.5}"#
+    );
+}
+
+#[test]
+fn test_overflow_error() {
+    let raw = r#"{"id": 123456789012345678901234567890, "amount": 1.5}"#;
+    assert!(parse_invoice(raw, NumberOverflow::Error).is_err());
+    let raw = r#"{"id": 1, "amount": 1234567890123.456789}"#;
+    assert!(parse_invoice(raw, NumberOverflow::Error).is_err());
+    let raw = r#"{"id": 1, "amount": 1.5}"#;
+    assert!(parse_invoice(raw, NumberOverflow::Error).is_ok());
+}
+
+fn parse_invoice_ints(
+    raw: &str,
+    int_overflow: IntOverflow,
+) -> Result<(serde_json::Value, Vec<&'static str>)> {
+    let ir = load_test_ir(INVOICE);
+    let target_type = FieldType::Class("Invoice".to_string());
+    let target = render_output_format(&ir, &target_type, &Default::default())?;
+    let options = CoerceOptions {
+        int_overflow: Some(int_overflow),
+        ..Default::default()
+    };
+    let value = from_str_with_options(&target, &target_type, raw, false, &options)?;
+    let BamlValueWithFlags::Class(_, _, fields) = &value else {
+        anyhow::bail!("expected a class");
+    };
+    let flags = fields["id"].conditions().flags().iter().map(|f| f.kind()).collect();
+    Ok((serde_json::to_value(BamlValue::from(value))?, flags))
+}
+
+#[test]
+fn test_int_overflow_policies() {
+    let raw = r#"{"id": -123456789012345678901234567890, "amount": 1.5}"#;
+    assert!(parse_invoice_ints(raw, IntOverflow::Error).is_err());
+    assert_eq!(
+        parse_invoice_ints(raw, IntOverflow::Saturate).unwrap(),
+        (
+            serde_json::json!({"id": -9223372036854775808i64, "amount": 1.5}),
+            vec!["IntSaturated"]
+        )
+    );
+    assert_eq!(
+        parse_invoice_ints(raw, IntOverflow::CoerceToFloat).unwrap(),
+        (
+            serde_json::json!({"id": -1.2345678901234568e29, "amount": 1.5}),
+            vec!["IntToFloat"]
+        )
+    );
+
+    let raw = r#"{"id": "1e30", "amount": 1.5}"#;
+    assert_eq!(
+        parse_invoice_ints(raw, IntOverflow::Saturate).unwrap().0,
+        serde_json::json!({"id": 9223372036854775807i64, "amount": 1.5})
+    );
+    let raw = r#"{"id": 42, "amount": 1.5}"#;
+    assert_eq!(
+        parse_invoice_ints(raw, IntOverflow::Error).unwrap(),
+        (serde_json::json!({"id": 42, "amount": 1.5}), vec![])
+    );
+}
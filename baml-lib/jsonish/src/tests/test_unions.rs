@@ -299,3 +299,27 @@ test_deserializer!(
     ]),
     "1 cup unsalted butter, room temperature"
 );
+
+#[test]
+fn test_normalize_lists_wraps_single_values() {
+    let target_type = FieldType::Class("Tagged".to_string());
+    let ir = load_test_ir(
+        r#"
+class Tagged {
+  tags string | string[]
+}
+"#,
+    );
+    let target = render_output_format(&ir, &target_type, &Default::default()).unwrap();
+    let options = crate::CoerceOptions {
+        normalize_lists: true,
+        ..Default::default()
+    };
+    let parse = |raw: &str| {
+        let value =
+            crate::from_str_with_options(&target, &target_type, raw, false, &options).unwrap();
+        serde_json::to_value(BamlValue::from(value)).unwrap()
+    };
+    assert_eq!(parse(r#"{"tags": "a"}"#), json!({"tags": ["a"]}));
+    assert_eq!(parse(r#"{"tags": ["a", "b"]}"#), json!({"tags": ["a", "b"]}));
+}
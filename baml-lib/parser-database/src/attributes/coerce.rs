@@ -0,0 +1,21 @@
+use internal_baml_diagnostics::DatamodelError;
+
+use crate::{context::Context, types::Attributes};
+
+pub(super) fn visit_coerce_attribute(attributes: &mut Attributes, ctx: &mut Context<'_>) {
+    match ctx.visit_default_arg_with_idx("name") {
+        Ok((_, name)) => {
+            if let Some(result) = name.to_unresolved_value(ctx.diagnostics) {
+                if result.as_str().is_some() {
+                    attributes.add_coerce(result);
+                } else {
+                    ctx.push_error(DatamodelError::new_validation_error(
+                        "must be a string.",
+                        result.meta().clone(),
+                    ));
+                }
+            }
+        }
+        Err(err) => ctx.push_error(err),
+    };
+}
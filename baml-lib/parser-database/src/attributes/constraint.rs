@@ -1,8 +1,8 @@
-use baml_types::{Constraint, ConstraintLevel};
+use baml_types::{Constraint, ConstraintLevel, JinjaExpression};
 use internal_baml_diagnostics::{DatamodelError, Span};
-use internal_baml_schema_ast::ast::{Argument, Attribute, Expression};
+use internal_baml_schema_ast::ast::{Argument, Attribute, Expression, FieldType};
 
-use crate::{context::Context, types::Attributes};
+use crate::{context::Context, types::Attributes, ParserDatabase};
 
 /// Interpret an attribute as a constraint, the whole constraint's span,
 /// and the span of the constraint's jinja expression.
@@ -65,6 +65,95 @@ pub fn attribute_as_constraint(
     (Some((constraint, span, expr_span)), datamodel_errors)
 }
 
+/// The `@check` and `@assert` constraints attached directly to a type, e.g.
+/// `int @assert({{ this > 0 }})`, with the constants they use resolved. Invalid constraints
+/// are left out, they are reported when the schema is validated.
+pub fn type_constraints(field_type: &FieldType, db: &ParserDatabase) -> Vec<Constraint> {
+    field_type
+        .attributes()
+        .iter()
+        .filter_map(|attribute| attribute_as_constraint(attribute).0)
+        .map(|(constraint, ..)| Constraint {
+            expression: db.resolve_constants(&constraint.expression),
+            ..constraint
+        })
+        .collect()
+}
+
+/// `expression` with every identifier that `constant` resolves replaced by the Jinja literal
+/// of the constant's value. String literals and attributes such as `this.MAX_ITEMS` are left
+/// alone.
+pub(crate) fn substitute_constants<'a>(
+    expression: &JinjaExpression,
+    constant: impl Fn(&str) -> Option<&'a Expression>,
+) -> JinjaExpression {
+    let source = expression.0.as_str();
+    let mut resolved = String::with_capacity(source.len());
+    let mut chars = source.char_indices().peekable();
+    let mut after_dot = false;
+
+    while let Some((start, c)) = chars.next() {
+        if c == '"' || c == '\'' {
+            let mut end = source.len();
+            let mut escaped = false;
+            for (i, next) in chars.by_ref() {
+                if escaped {
+                    escaped = false;
+                } else if next == '\\' {
+                    escaped = true;
+                } else if next == c {
+                    end = i + next.len_utf8();
+                    break;
+                }
+            }
+            resolved.push_str(&source[start..end]);
+            after_dot = false;
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut end = start + 1;
+            while let Some((i, next)) = chars.peek().copied() {
+                if !(next.is_ascii_alphanumeric() || next == '_') {
+                    break;
+                }
+                end = i + 1;
+                chars.next();
+            }
+            let word = &source[start..end];
+            match constant(word).and_then(jinja_literal) {
+                Some(literal) if !after_dot => resolved.push_str(&literal),
+                _ => resolved.push_str(word),
+            }
+            after_dot = false;
+        } else {
+            resolved.push(c);
+            if !c.is_whitespace() {
+                after_dot = c == '.';
+            }
+        }
+    }
+
+    JinjaExpression(resolved)
+}
+
+/// The Jinja literal for the value of a constant.
+fn jinja_literal(value: &Expression) -> Option<String> {
+    let quote = |s: &str| {
+        format!(
+            "\"{}\"",
+            s.replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n")
+        )
+    };
+    match value {
+        Expression::BoolValue(b, _) => Some(b.to_string()),
+        Expression::NumericValue(n, _) if n.starts_with('-') => Some(format!("({n})")),
+        Expression::NumericValue(n, _) => Some(n.clone()),
+        Expression::StringValue(s, _) => Some(quote(s)),
+        Expression::RawStringValue(s) => Some(quote(s.value())),
+        _ => None,
+    }
+}
+
 pub(super) fn visit_constraint_attributes(
     attribute_name: String,
     span: Span,
@@ -99,10 +188,10 @@ pub(super) fn visit_constraint_attributes(
                     span,
                 ));
             }
-            (None, expression.clone())
+            (None, ctx.resolve_constants(expression))
         }
         [Expression::Identifier(label), Expression::JinjaExpressionValue(expression, _)] => {
-            (Some(label.to_string()), expression.clone())
+            (Some(label.to_string()), ctx.resolve_constants(expression))
         }
         _ => {
             ctx.push_error(
@@ -1,4 +1,8 @@
+use std::ops::Range;
+
+use baml_types::StringOr;
 use internal_baml_diagnostics::DatamodelError;
+use internal_baml_schema_ast::ast::{TopId, WithName};
 
 use crate::{coerce, context::Context, types::Attributes};
 
@@ -9,6 +13,9 @@ pub(super) fn visit_description_attribute(attributes: &mut Attributes, ctx: &mut
                 ctx.push_attribute_validation_error("cannot be specified more than once", false);
             } else if let Some(result) = name.to_unresolved_value(ctx.diagnostics) {
                 if result.as_str().is_some() {
+                    if let Some(StringOr::Value(text)) = result.as_str() {
+                        validate_references(text, ctx);
+                    }
                     attributes.add_description(result);
                 } else {
                     ctx.push_error(DatamodelError::new_validation_error(
@@ -21,3 +28,60 @@ pub(super) fn visit_description_attribute(attributes: &mut Attributes, ctx: &mut
         Err(err) => ctx.push_error(err), // not flattened for error handing legacy reasons
     };
 }
+
+/// The `{{ Type.member }}` references in a description: the byte range of each one, the class
+/// or enum name and the field or value name. They are rendered as the member's rendered name.
+pub fn description_references(text: &str) -> Vec<(Range<usize>, &str, &str)> {
+    let is_identifier = |s: &str| {
+        s.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    let mut references = vec![];
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find("{{").map(|i| offset + i) {
+        let Some(end) = text[start..].find("}}").map(|i| start + i + 2) else {
+            break;
+        };
+        // Only types, which start with an upper case letter, so `{{ env.X }}` stays as is.
+        match text[start + 2..end - 2].trim().split_once('.') {
+            Some((ty, member))
+                if ty.starts_with(|c: char| c.is_ascii_uppercase())
+                    && is_identifier(ty)
+                    && is_identifier(member) =>
+            {
+                references.push((start..end, ty, member))
+            }
+            _ => {}
+        }
+        offset = end;
+    }
+    references
+}
+
+/// Reports references to classes, enums or members that do not exist.
+fn validate_references(text: &str, ctx: &mut Context<'_>) {
+    let span = ctx.current_attribute().span.clone();
+    for (_, ty, member) in description_references(text) {
+        let block = ctx
+            .interner
+            .lookup(ty)
+            .and_then(|id| ctx.names.tops.get(&id))
+            .and_then(|top_id| match top_id {
+                TopId::Class(id) => Some(("field", &ctx.ast[*id])),
+                TopId::Enum(id) => Some(("value", &ctx.ast[*id])),
+                _ => None,
+            });
+        let error = match block {
+            None => format!("Unknown reference `{ty}.{member}`: there is no class or enum `{ty}`"),
+            Some((kind, block)) if !block.fields.iter().any(|f| f.name() == member) => {
+                format!("Unknown reference `{ty}.{member}`: `{ty}` has no {kind} `{member}`")
+            }
+            Some(_) => continue,
+        };
+        ctx.push_error(DatamodelError::new_attribute_validation_error(
+            &error,
+            "@description",
+            span.clone(),
+        ));
+    }
+}
@@ -0,0 +1,21 @@
+use internal_baml_diagnostics::DatamodelError;
+
+use crate::{context::Context, types::Attributes};
+
+/// Accepted `@int_overflow` policies.
+const POLICIES: [&str; 3] = ["error", "saturate", "float"];
+
+pub(super) fn visit_int_overflow_attribute(attributes: &mut Attributes, ctx: &mut Context<'_>) {
+    match ctx.visit_default_arg_with_idx("policy") {
+        Ok((_, policy)) => match policy.as_string_value() {
+            Some((policy, _)) if POLICIES.contains(&policy) => {
+                attributes.set_int_overflow(policy.to_string())
+            }
+            _ => ctx.push_error(DatamodelError::new_validation_error(
+                "must be \"error\", \"saturate\" or \"float\".",
+                policy.span().clone(),
+            )),
+        },
+        Err(err) => ctx.push_error(err),
+    };
+}
@@ -1,16 +1,61 @@
+use std::collections::HashSet;
+
 use internal_baml_diagnostics::{DatamodelError, Span};
 use internal_baml_schema_ast::ast::{
     Assignment, Top, TopId, TypeAliasId, TypeExpId, TypeExpressionBlock,
 };
 
 mod alias;
+mod coerce;
 pub mod constraint;
-mod description;
+mod deprecated;
+mod version;
+pub mod description;
+mod group;
+mod int_overflow;
+mod normalize;
+mod null_values;
+mod one_of;
+mod render;
+mod required_if;
 mod to_string_attribute;
 use crate::interner::StringId;
 use crate::{context::Context, types::ClassAttributes, types::EnumAttributes};
-use baml_types::{Constraint, UnresolvedValue};
-use internal_baml_schema_ast::ast::{Expression, SubType};
+use baml_types::{Constraint, ConstraintLevel, JinjaExpression, TypeValue, UnresolvedValue};
+use internal_baml_schema_ast::ast::{Expression, FieldType, SubType, WithName};
+
+/// The attributes accepted on class fields and enum values, without their leading `@`.
+pub const FIELD_ATTRIBUTES: &[&str] = &[
+    "alias",
+    "description",
+    "deprecated",
+    "coerce",
+    "normalize",
+    "int_overflow",
+    "null_values",
+    "group",
+    "required_if",
+    "since",
+    "until",
+    "skip",
+    "assert",
+    "check",
+];
+
+/// The attributes accepted on classes and enums, without their leading `@@`.
+pub const BLOCK_ATTRIBUTES: &[&str] = &[
+    "alias",
+    "description",
+    "since",
+    "until",
+    "skip",
+    "assert",
+    "check",
+    "open",
+    "one_of",
+    "render",
+    "dynamic",
+];
 
 /// Node attributes.
 #[derive(Debug, Default)]
@@ -29,6 +74,55 @@ pub struct Attributes {
 
     /// @check and @assert attributes attached to the node.
     pub constraints: Vec<Constraint>,
+
+    /// Deprecation message: the node is still accepted when parsing but not rendered.
+    pub deprecated: Option<UnresolvedValue<Span>>,
+
+    /// First schema version the node is part of.
+    pub since: Option<UnresolvedValue<Span>>,
+
+    /// First schema version the node is no longer part of.
+    pub until: Option<UnresolvedValue<Span>>,
+
+    /// Whether the enum also accepts values other than its variants.
+    pub open: Option<bool>,
+
+    /// Name of the embedder-registered coercer that parses the field.
+    pub coerce: Option<UnresolvedValue<Span>>,
+
+    /// Heading the enum value is listed under in the rendered prompt.
+    pub group: Option<UnresolvedValue<Span>>,
+
+    /// Condition on the enclosing object (`this`) under which the optional field must not be null.
+    pub required_if: Option<JinjaExpression>,
+
+    /// Groups of class fields of which exactly one must be set.
+    pub one_of: Vec<Vec<String>>,
+
+    /// Whether a single value parsed for a `T | T[]` field is wrapped in a list.
+    pub normalize_to_list: Option<bool>,
+
+    /// What to do with numbers that do not fit the field's `int`: `error`, `saturate` or `float`.
+    pub int_overflow: Option<String>,
+
+    /// Strings (`N/A`, `-`, ...) parsed as null for the optional field.
+    pub null_values: Option<Vec<String>>,
+
+    /// How the class is rendered, overriding the render options.
+    pub render: Option<RenderOverrides>,
+}
+
+/// Options of `@@render({ layout "hoisted", separator ";", descriptions false })`.
+#[derive(Debug, Clone, Default)]
+pub struct RenderOverrides {
+    /// Whether the class is defined once above the answer and referred to by name.
+    pub hoisted: Option<bool>,
+
+    /// Ends each field instead of `,`.
+    pub separator: Option<String>,
+
+    /// Whether field descriptions are rendered.
+    pub descriptions: Option<bool>,
 }
 
 impl Attributes {
@@ -71,6 +165,153 @@ impl Attributes {
     pub fn set_skip(&mut self) {
         self.skip.replace(true);
     }
+
+    /// Set the deprecation message.
+    pub fn add_deprecated(&mut self, message: UnresolvedValue<Span>) {
+        self.deprecated.replace(message);
+    }
+
+    /// Get the deprecation message.
+    pub fn deprecated(&self) -> &Option<UnresolvedValue<Span>> {
+        &self.deprecated
+    }
+
+    /// Get openness of enum.
+    pub fn open(&self) -> &Option<bool> {
+        &self.open
+    }
+
+    /// Set openness of enum.
+    pub fn set_open(&mut self) {
+        self.open.replace(true);
+    }
+
+    /// Set the version the node was introduced in.
+    pub fn add_since(&mut self, version: UnresolvedValue<Span>) {
+        self.since.replace(version);
+    }
+
+    /// Get the version the node was introduced in.
+    pub fn since(&self) -> &Option<UnresolvedValue<Span>> {
+        &self.since
+    }
+
+    /// Set the version the node was removed in.
+    pub fn add_until(&mut self, version: UnresolvedValue<Span>) {
+        self.until.replace(version);
+    }
+
+    /// Get the version the node was removed in.
+    pub fn until(&self) -> &Option<UnresolvedValue<Span>> {
+        &self.until
+    }
+
+    /// Set the custom coercer name.
+    pub fn add_coerce(&mut self, name: UnresolvedValue<Span>) {
+        self.coerce.replace(name);
+    }
+
+    /// Get the custom coercer name.
+    pub fn coerce(&self) -> &Option<UnresolvedValue<Span>> {
+        &self.coerce
+    }
+
+    /// Set the enum value group.
+    pub fn add_group(&mut self, group: UnresolvedValue<Span>) {
+        self.group.replace(group);
+    }
+
+    /// Get the enum value group.
+    pub fn group(&self) -> &Option<UnresolvedValue<Span>> {
+        &self.group
+    }
+
+    /// Set the condition under which the field is required.
+    pub fn add_required_if(&mut self, condition: JinjaExpression) {
+        self.required_if.replace(condition);
+    }
+
+    /// Get the condition under which the field is required.
+    pub fn required_if(&self) -> &Option<JinjaExpression> {
+        &self.required_if
+    }
+
+    /// Add a group of mutually exclusive fields.
+    pub fn add_one_of(&mut self, fields: Vec<String>) {
+        self.one_of.push(fields);
+    }
+
+    /// Get the groups of mutually exclusive fields.
+    pub fn one_of(&self) -> &[Vec<String>] {
+        &self.one_of
+    }
+
+    /// Get whether single values are wrapped in a list.
+    pub fn normalize_to_list(&self) -> &Option<bool> {
+        &self.normalize_to_list
+    }
+
+    /// Wrap single values in a list.
+    pub fn set_normalize_to_list(&mut self) {
+        self.normalize_to_list.replace(true);
+    }
+
+    /// Get the integer overflow policy.
+    pub fn int_overflow(&self) -> &Option<String> {
+        &self.int_overflow
+    }
+
+    /// Set the integer overflow policy.
+    pub fn set_int_overflow(&mut self, policy: String) {
+        self.int_overflow.replace(policy);
+    }
+
+    /// Get the strings parsed as null.
+    pub fn null_values(&self) -> &Option<Vec<String>> {
+        &self.null_values
+    }
+
+    /// Set the strings parsed as null.
+    pub fn set_null_values(&mut self, values: Vec<String>) {
+        self.null_values.replace(values);
+    }
+
+    /// Get the rendering overrides.
+    pub fn render(&self) -> &Option<RenderOverrides> {
+        &self.render
+    }
+
+    /// Set the rendering overrides.
+    pub fn set_render(&mut self, overrides: RenderOverrides) {
+        self.render.replace(overrides);
+    }
+
+    /// Whether the attribute is set. Attributes are named as in the IR: `@@dynamic` is
+    /// `dynamic_type`, and `@check` / `@assert` are `check` / `assert`.
+    pub fn has(&self, name: &str) -> bool {
+        let level = |level: ConstraintLevel| self.constraints.iter().any(|c| c.level == level);
+        match name {
+            "description" => self.description.is_some(),
+            "alias" => self.alias.is_some(),
+            "dynamic_type" => self.dynamic_type.unwrap_or(false),
+            "skip" => self.skip.unwrap_or(false),
+            "check" => level(ConstraintLevel::Check),
+            "assert" => level(ConstraintLevel::Assert),
+            "deprecated" => self.deprecated.is_some(),
+            "since" => self.since.is_some(),
+            "until" => self.until.is_some(),
+            "open" => self.open.unwrap_or(false),
+            "coerce" => self.coerce.is_some(),
+            "group" => self.group.is_some(),
+            "required_if" => self.required_if.is_some(),
+            "one_of" => !self.one_of.is_empty(),
+            "normalize" => self.normalize_to_list.unwrap_or(false),
+            "int_overflow" => self.int_overflow.is_some(),
+            "null_values" => self.null_values.is_some(),
+            "render" => self.render.is_some(),
+            _ => false,
+        }
+    }
 }
 pub(super) fn resolve_attributes(ctx: &mut Context<'_>) {
     for top in ctx.ast.iter_tops() {
@@ -103,6 +344,36 @@ fn resolve_type_exp_block_attributes<'db>(
             for (value_idx, _value) in ast_typexpr.iter_fields() {
                 ctx.assert_all_attributes_processed((type_id, value_idx).into());
                 if let Some(attrs) = to_string_attribute::visit(ctx, &span, false) {
+                    if let Some(coerce) = attrs.coerce() {
+                        ctx.push_error(DatamodelError::new_validation_error(
+                            "only class fields can use @coerce",
+                            coerce.meta().clone(),
+                        ));
+                    }
+                    if attrs.required_if().is_some() {
+                        ctx.push_error(DatamodelError::new_validation_error(
+                            "only class fields can use @required_if",
+                            span.clone(),
+                        ));
+                    }
+                    if attrs.normalize_to_list().is_some() {
+                        ctx.push_error(DatamodelError::new_validation_error(
+                            "only class fields can use @normalize",
+                            span.clone(),
+                        ));
+                    }
+                    if attrs.int_overflow().is_some() {
+                        ctx.push_error(DatamodelError::new_validation_error(
+                            "only class fields can use @int_overflow",
+                            span.clone(),
+                        ));
+                    }
+                    if attrs.null_values().is_some() {
+                        ctx.push_error(DatamodelError::new_validation_error(
+                            "only class fields can use @null_values",
+                            span.clone(),
+                        ));
+                    }
                     enum_attributes.value_serilizers.insert(value_idx, attrs);
                 }
                 ctx.validate_visited_attributes();
@@ -113,6 +384,28 @@ fn resolve_type_exp_block_attributes<'db>(
             enum_attributes.serilizer = to_string_attribute::visit(ctx, &span, true);
             ctx.validate_visited_attributes();
 
+            if enum_attributes
+                .serilizer
+                .as_ref()
+                .is_some_and(|attrs| !attrs.one_of().is_empty())
+            {
+                ctx.push_error(DatamodelError::new_validation_error(
+                    "only classes can use @@one_of",
+                    span.clone(),
+                ));
+            }
+
+            if enum_attributes
+                .serilizer
+                .as_ref()
+                .is_some_and(|attrs| attrs.render().is_some())
+            {
+                ctx.push_error(DatamodelError::new_validation_error(
+                    "only classes can use @@render",
+                    span.clone(),
+                ));
+            }
+
             ctx.types.enum_attributes.insert(type_id, enum_attributes);
         }
         SubType::Class => {
@@ -121,6 +414,46 @@ fn resolve_type_exp_block_attributes<'db>(
             for (field_idx, field) in ast_typexpr.iter_fields() {
                 ctx.assert_all_attributes_processed((type_id, field_idx).into());
                 if let Some(attrs) = to_string_attribute::visit(ctx, &field.span, false) {
+                    if let Some(group) = attrs.group() {
+                        ctx.push_error(DatamodelError::new_validation_error(
+                            "only enum values can use @group",
+                            group.meta().clone(),
+                        ));
+                    }
+                    // A required field can never be null, so the condition would be pointless.
+                    if attrs.required_if().is_some()
+                        && field.expr.as_ref().is_some_and(|t| !t.is_optional())
+                    {
+                        ctx.push_error(DatamodelError::new_validation_error(
+                            "@required_if only applies to optional fields",
+                            field.span.clone(),
+                        ));
+                    }
+                    // Wrapping only makes sense when the field also accepts a list.
+                    if attrs.normalize_to_list().is_some()
+                        && !field.expr.as_ref().is_some_and(is_union_with_list)
+                    {
+                        ctx.push_error(DatamodelError::new_validation_error(
+                            "@normalize(\"list\") only applies to unions with a list, such as `string | string[]`",
+                            field.span.clone(),
+                        ));
+                    }
+                    if attrs.int_overflow().is_some()
+                        && !field.expr.as_ref().is_some_and(mentions_int)
+                    {
+                        ctx.push_error(DatamodelError::new_validation_error(
+                            "@int_overflow only applies to fields holding an `int`",
+                            field.span.clone(),
+                        ));
+                    }
+                    if attrs.null_values().is_some()
+                        && field.expr.as_ref().is_some_and(|t| !t.is_optional())
+                    {
+                        ctx.push_error(DatamodelError::new_validation_error(
+                            "@null_values only applies to optional fields",
+                            field.span.clone(),
+                        ));
+                    }
                     class_attributes.field_serilizers.insert(field_idx, attrs);
                 }
                 ctx.validate_visited_attributes();
@@ -131,6 +464,21 @@ fn resolve_type_exp_block_attributes<'db>(
             class_attributes.serilizer = to_string_attribute::visit(ctx, &span, true);
             ctx.validate_visited_attributes();
 
+            if class_attributes
+                .serilizer
+                .as_ref()
+                .is_some_and(|attrs| attrs.open().is_some())
+            {
+                ctx.push_error(DatamodelError::new_validation_error(
+                    "only enums can be @@open",
+                    span.clone(),
+                ));
+            }
+
+            if let Some(attrs) = &class_attributes.serilizer {
+                validate_one_of(ctx, ast_typexpr, attrs.one_of(), &span);
+            }
+
             ctx.types.class_attributes.insert(type_id, class_attributes);
         }
 
@@ -138,6 +486,49 @@ fn resolve_type_exp_block_attributes<'db>(
     }
 }
 
+fn is_union_with_list(field_type: &FieldType) -> bool {
+    match field_type {
+        FieldType::Union(_, items, ..) => items.iter().any(|t| matches!(t, FieldType::List(..))),
+        _ => false,
+    }
+}
+
+/// Whether `int` appears in the type, e.g. `int`, `int[]` or `map<string, int | null>`.
+fn mentions_int(field_type: &FieldType) -> bool {
+    match field_type {
+        FieldType::Primitive(_, TypeValue::Int, ..) => true,
+        FieldType::List(_, item, ..) => mentions_int(item),
+        FieldType::Map(_, kv, ..) => mentions_int(&kv.1),
+        FieldType::Tuple(_, items, ..) | FieldType::Union(_, items, ..) => {
+            items.iter().any(mentions_int)
+        }
+        FieldType::Primitive(..) | FieldType::Literal(..) | FieldType::Symbol(..) => false,
+    }
+}
+
+/// Every `@@one_of` field must exist, be optional and belong to a single group.
+fn validate_one_of(
+    ctx: &mut Context<'_>,
+    class: &TypeExpressionBlock,
+    groups: &[Vec<String>],
+    span: &Span,
+) {
+    let mut seen = HashSet::new();
+    for field in groups.iter().flatten() {
+        let error = match class.iter_fields().find(|(_, f)| f.name() == field) {
+            None => format!("@@one_of field `{field}` is not a field of the class"),
+            Some((_, f)) if f.expr.as_ref().is_some_and(|t| !t.is_optional()) => {
+                format!("@@one_of field `{field}` must be optional")
+            }
+            Some(_) if !seen.insert(field) => {
+                format!("@@one_of field `{field}` is listed more than once")
+            }
+            Some(_) => continue,
+        };
+        ctx.push_error(DatamodelError::new_validation_error(&error, span.clone()));
+    }
+}
+
 /// Quick hack to validate type alias attributes.
 ///
 /// Unlike classes and enums, type aliases only support checks and asserts.
@@ -173,6 +564,16 @@ fn resolve_type_alias_attributes<'db>(
                 || attrs.alias().is_some()
                 || attrs.skip().is_some()
                 || attrs.description().is_some()
+                || attrs.deprecated().is_some()
+                || attrs.since().is_some()
+                || attrs.until().is_some()
+                || attrs.open().is_some()
+                || attrs.coerce().is_some()
+                || attrs.group().is_some()
+                || attrs.required_if().is_some()
+                || attrs.normalize_to_list().is_some()
+                || attrs.int_overflow().is_some()
+                || attrs.null_values().is_some()
             {
                 ctx.diagnostics
                     .push_error(DatamodelError::new_validation_error(
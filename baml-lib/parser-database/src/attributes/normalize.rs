@@ -0,0 +1,16 @@
+use internal_baml_diagnostics::DatamodelError;
+
+use crate::{context::Context, types::Attributes};
+
+pub(super) fn visit_normalize_attribute(attributes: &mut Attributes, ctx: &mut Context<'_>) {
+    match ctx.visit_default_arg_with_idx("shape") {
+        Ok((_, shape)) => match shape.as_string_value() {
+            Some(("list", _)) => attributes.set_normalize_to_list(),
+            _ => ctx.push_error(DatamodelError::new_validation_error(
+                "must be \"list\".",
+                shape.span().clone(),
+            )),
+        },
+        Err(err) => ctx.push_error(err),
+    };
+}
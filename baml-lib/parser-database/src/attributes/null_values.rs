@@ -0,0 +1,23 @@
+use internal_baml_diagnostics::DatamodelError;
+
+use crate::{context::Context, types::Attributes};
+
+pub(super) fn visit_null_values_attribute(attributes: &mut Attributes, ctx: &mut Context<'_>) {
+    let values = match ctx.visit_default_arg_with_idx("values") {
+        Ok((_, values)) => values,
+        Err(err) => return ctx.push_error(err),
+    };
+    let strings = values.as_array().and_then(|(items, _)| {
+        items
+            .iter()
+            .map(|item| item.as_string_value().map(|(s, _)| s.to_string()))
+            .collect::<Option<Vec<_>>>()
+    });
+    match strings {
+        Some(strings) => attributes.set_null_values(strings),
+        None => ctx.push_error(DatamodelError::new_validation_error(
+            "must be a list of strings, such as [\"N/A\", \"-\"].",
+            values.span().clone(),
+        )),
+    }
+}
@@ -0,0 +1,28 @@
+use internal_baml_diagnostics::DatamodelError;
+use internal_baml_schema_ast::ast::{Expression, Identifier};
+
+use crate::{context::Context, types::Attributes};
+
+pub(super) fn visit_one_of_attribute(attributes: &mut Attributes, ctx: &mut Context<'_>) {
+    let mut fields = Vec::new();
+    for (_, arg) in ctx.get_all_args() {
+        match arg {
+            Expression::Identifier(Identifier::Local(field, _)) => fields.push(field.clone()),
+            other => {
+                ctx.push_error(DatamodelError::new_validation_error(
+                    "must be field names, such as @@one_of(email, phone).",
+                    other.span().clone(),
+                ));
+                return;
+            }
+        }
+    }
+    if fields.len() < 2 {
+        ctx.push_error(DatamodelError::new_validation_error(
+            "@@one_of needs at least two fields.",
+            ctx.current_attribute().span.clone(),
+        ));
+        return;
+    }
+    attributes.add_one_of(fields);
+}
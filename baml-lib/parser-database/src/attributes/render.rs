@@ -0,0 +1,61 @@
+use internal_baml_diagnostics::DatamodelError;
+use internal_baml_schema_ast::ast::Expression;
+
+use crate::{
+    context::Context,
+    types::{Attributes, RenderOverrides},
+};
+
+pub(super) fn visit_render_attribute(attributes: &mut Attributes, ctx: &mut Context<'_>) {
+    let options = match ctx.visit_default_arg_with_idx("options") {
+        Ok((_, options)) => options,
+        Err(err) => return ctx.push_error(err),
+    };
+    let Some((entries, _)) = options.as_map() else {
+        return ctx.push_error(DatamodelError::new_validation_error(
+            "must be a map of options, such as @@render({ layout \"hoisted\" }).",
+            options.span().clone(),
+        ));
+    };
+    let mut overrides = RenderOverrides::default();
+    for (key, value) in entries {
+        let error = match key.as_string_value().map(|(key, _)| key) {
+            Some("layout") => match value.as_string_value() {
+                Some(("inline", _)) => {
+                    overrides.hoisted = Some(false);
+                    continue;
+                }
+                Some(("hoisted", _)) => {
+                    overrides.hoisted = Some(true);
+                    continue;
+                }
+                _ => "`layout` must be \"inline\" or \"hoisted\".".to_string(),
+            },
+            Some("separator") => match value.as_string_value() {
+                Some((separator, _)) => {
+                    overrides.separator = Some(separator.to_string());
+                    continue;
+                }
+                None => "`separator` must be a string.".to_string(),
+            },
+            Some("descriptions") => match value {
+                Expression::BoolValue(descriptions, _) => {
+                    overrides.descriptions = Some(*descriptions);
+                    continue;
+                }
+                _ => "`descriptions` must be true or false.".to_string(),
+            },
+            _ => {
+                ctx.push_error(DatamodelError::new_validation_error(
+                    &format!(
+                        "Unknown @@render option `{key}`, expected `layout`, `separator` or `descriptions`."
+                    ),
+                    key.span().clone(),
+                ));
+                continue;
+            }
+        };
+        ctx.push_error(DatamodelError::new_validation_error(&error, value.span().clone()));
+    }
+    attributes.set_render(overrides);
+}
@@ -0,0 +1,17 @@
+use internal_baml_diagnostics::DatamodelError;
+use internal_baml_schema_ast::ast::Expression;
+
+use crate::{context::Context, types::Attributes};
+
+pub(super) fn visit_required_if_attribute(attributes: &mut Attributes, ctx: &mut Context<'_>) {
+    match ctx.visit_default_arg_with_idx("condition") {
+        Ok((_, Expression::JinjaExpressionValue(condition, _))) => {
+            attributes.add_required_if(condition.clone());
+        }
+        Ok((_, other)) => ctx.push_error(DatamodelError::new_validation_error(
+            "must be a jinja expression, such as {{ this.kind == \"business\" }}.",
+            other.span().clone(),
+        )),
+        Err(err) => ctx.push_error(err),
+    };
+}
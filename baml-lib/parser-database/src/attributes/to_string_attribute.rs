@@ -5,27 +5,107 @@ use itertools::Itertools;
 
 use crate::{context::Context, types::Attributes};
 
+use super::{BLOCK_ATTRIBUTES, FIELD_ATTRIBUTES};
+
 use super::alias::visit_alias_attribute;
+use super::coerce::visit_coerce_attribute;
 use super::constraint::visit_constraint_attributes;
+use super::deprecated::visit_deprecated_attribute;
 use super::description::visit_description_attribute;
+use super::group::visit_group_attribute;
+use super::int_overflow::visit_int_overflow_attribute;
+use super::normalize::visit_normalize_attribute;
+use super::null_values::visit_null_values_attribute;
+use super::one_of::visit_one_of_attribute;
+use super::render::visit_render_attribute;
+use super::required_if::visit_required_if_attribute;
+use super::version::visit_version_attribute;
+/// Whether `name` is in the attribute table for blocks (`@@name`) or fields (`@name`).
+fn accepts(as_block: bool, name: &str) -> bool {
+    let table = if as_block {
+        BLOCK_ATTRIBUTES
+    } else {
+        FIELD_ATTRIBUTES
+    };
+    table.contains(&name)
+}
+
 pub(super) fn visit(ctx: &mut Context<'_>, span: &Span, as_block: bool) -> Option<Attributes> {
     let mut modified = false;
 
     let mut attributes = Attributes::default();
     // @alias or @@alias
-    if ctx.visit_optional_single_attr("alias") {
+    if accepts(as_block, "alias") && ctx.visit_optional_single_attr("alias") {
         visit_alias_attribute(&mut attributes, ctx);
         modified = true;
         ctx.validate_visited_arguments();
     }
 
-    if ctx.visit_optional_single_attr("description") {
+    if accepts(as_block, "description") && ctx.visit_optional_single_attr("description") {
         visit_description_attribute(&mut attributes, ctx);
         modified = true;
         ctx.validate_visited_arguments();
     }
 
-    if ctx.visit_optional_single_attr("skip") {
+    // @deprecated only applies to class fields and enum values.
+    if accepts(as_block, "deprecated") && ctx.visit_optional_single_attr("deprecated") {
+        visit_deprecated_attribute(&mut attributes, ctx);
+        modified = true;
+        ctx.validate_visited_arguments();
+    }
+
+    // @coerce only applies to class fields.
+    if accepts(as_block, "coerce") && ctx.visit_optional_single_attr("coerce") {
+        visit_coerce_attribute(&mut attributes, ctx);
+        modified = true;
+        ctx.validate_visited_arguments();
+    }
+
+    // @normalize only applies to class fields.
+    if accepts(as_block, "normalize") && ctx.visit_optional_single_attr("normalize") {
+        visit_normalize_attribute(&mut attributes, ctx);
+        modified = true;
+        ctx.validate_visited_arguments();
+    }
+
+    // @int_overflow only applies to class fields.
+    if accepts(as_block, "int_overflow") && ctx.visit_optional_single_attr("int_overflow") {
+        visit_int_overflow_attribute(&mut attributes, ctx);
+        modified = true;
+        ctx.validate_visited_arguments();
+    }
+
+    // @null_values only applies to class fields.
+    if accepts(as_block, "null_values") && ctx.visit_optional_single_attr("null_values") {
+        visit_null_values_attribute(&mut attributes, ctx);
+        modified = true;
+        ctx.validate_visited_arguments();
+    }
+
+    // @group only applies to enum values.
+    if accepts(as_block, "group") && ctx.visit_optional_single_attr("group") {
+        visit_group_attribute(&mut attributes, ctx);
+        modified = true;
+        ctx.validate_visited_arguments();
+    }
+
+    // @required_if only applies to class fields.
+    if accepts(as_block, "required_if") && ctx.visit_optional_single_attr("required_if") {
+        visit_required_if_attribute(&mut attributes, ctx);
+        modified = true;
+        ctx.validate_visited_arguments();
+    }
+
+    // @since / @@since and @until / @@until
+    for name in ["since", "until"] {
+        if accepts(as_block, name) && ctx.visit_optional_single_attr(name) {
+            visit_version_attribute(name, &mut attributes, ctx);
+            modified = true;
+            ctx.validate_visited_arguments();
+        }
+    }
+
+    if accepts(as_block, "skip") && ctx.visit_optional_single_attr("skip") {
         attributes.set_skip();
         modified = true;
         ctx.validate_visited_arguments();
@@ -37,7 +117,28 @@ pub(super) fn visit(ctx: &mut Context<'_>, span: &Span, as_block: bool) -> Optio
         ctx.validate_visited_arguments();
     }
 
-    if as_block && ctx.visit_optional_single_attr("dynamic") {
+    if accepts(as_block, "open") && ctx.visit_optional_single_attr("open") {
+        attributes.set_open();
+        modified = true;
+        ctx.validate_visited_arguments();
+    }
+
+    // @@one_of may be repeated, once per group.
+    if accepts(as_block, "one_of") {
+        while ctx.visit_repeated_attr_from_names(&["one_of"]).is_some() {
+            visit_one_of_attribute(&mut attributes, ctx);
+            modified = true;
+            ctx.validate_visited_arguments();
+        }
+    }
+
+    if accepts(as_block, "render") && ctx.visit_optional_single_attr("render") {
+        visit_render_attribute(&mut attributes, ctx);
+        modified = true;
+        ctx.validate_visited_arguments();
+    }
+
+    if accepts(as_block, "dynamic") && ctx.visit_optional_single_attr("dynamic") {
         attributes.set_dynamic_type();
         modified = true;
         ctx.validate_visited_arguments();
@@ -0,0 +1,35 @@
+use internal_baml_diagnostics::DatamodelError;
+
+use crate::{context::Context, types::Attributes};
+
+/// Versions are dot-separated numbers, e.g. `"2"` or `"1.3"`.
+fn is_version(version: &str) -> bool {
+    version
+        .split('.')
+        .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+}
+
+pub(super) fn visit_version_attribute(
+    attribute_name: &str,
+    attributes: &mut Attributes,
+    ctx: &mut Context<'_>,
+) {
+    match ctx.visit_default_arg_with_idx("version") {
+        Ok((_, version)) => {
+            if let Some(result) = version.to_unresolved_value(ctx.diagnostics) {
+                if result.as_static_str().is_ok_and(is_version) {
+                    match attribute_name {
+                        "since" => attributes.add_since(result),
+                        _ => attributes.add_until(result),
+                    }
+                } else {
+                    ctx.push_error(DatamodelError::new_validation_error(
+                        "must be a version string such as \"2\" or \"1.3\".",
+                        result.meta().clone(),
+                    ));
+                }
+            }
+        }
+        Err(err) => ctx.push_error(err), // not flattened for error handing legacy reasons
+    };
+}
@@ -0,0 +1,281 @@
+//! Typed access to the properties of config blocks such as `generator`, `client<llm>` and
+//! `retry_policy`.
+//!
+//! A block is read through [`ConfigProperties`], which rejects properties the block does not
+//! know, and each property is converted with [`FromBamlExpression`]. Conversion errors carry the
+//! span of the offending value, and are collected so that every problem of a block is reported
+//! at once.
+
+use std::collections::HashMap;
+
+use internal_baml_diagnostics::{DatamodelError, Span};
+use internal_baml_schema_ast::ast::{self, WithIdentifier, WithName, WithSpan};
+
+use crate::coerce_opt;
+
+/// A value that a config block property can be converted to.
+pub trait FromBamlExpression<'a>: Sized {
+    /// The expected type, as shown in type mismatch errors.
+    fn expected_type() -> String;
+
+    /// Converts `expr`, or returns `None` if it does not have the expected type.
+    fn from_expression(expr: &'a ast::Expression) -> Option<Self>;
+}
+
+impl<'a> FromBamlExpression<'a> for &'a ast::Expression {
+    fn expected_type() -> String {
+        "any".to_string()
+    }
+
+    fn from_expression(expr: &'a ast::Expression) -> Option<Self> {
+        Some(expr)
+    }
+}
+
+impl<'a> FromBamlExpression<'a> for &'a str {
+    fn expected_type() -> String {
+        "string".to_string()
+    }
+
+    fn from_expression(expr: &'a ast::Expression) -> Option<Self> {
+        coerce_opt::string(expr)
+    }
+}
+
+impl<'a> FromBamlExpression<'a> for String {
+    fn expected_type() -> String {
+        "string".to_string()
+    }
+
+    fn from_expression(expr: &'a ast::Expression) -> Option<Self> {
+        coerce_opt::string(expr).map(String::from)
+    }
+}
+
+impl<'a> FromBamlExpression<'a> for bool {
+    fn expected_type() -> String {
+        "boolean".to_string()
+    }
+
+    fn from_expression(expr: &'a ast::Expression) -> Option<Self> {
+        coerce_opt::boolean(expr)
+    }
+}
+
+impl<'a> FromBamlExpression<'a> for i64 {
+    fn expected_type() -> String {
+        "numeric".to_string()
+    }
+
+    fn from_expression(expr: &'a ast::Expression) -> Option<Self> {
+        coerce_opt::integer(expr)
+    }
+}
+
+impl<'a> FromBamlExpression<'a> for u32 {
+    fn expected_type() -> String {
+        "non-negative integer".to_string()
+    }
+
+    fn from_expression(expr: &'a ast::Expression) -> Option<Self> {
+        coerce_opt::integer(expr).and_then(|n| u32::try_from(n).ok())
+    }
+}
+
+impl<'a> FromBamlExpression<'a> for f64 {
+    fn expected_type() -> String {
+        "float".to_string()
+    }
+
+    fn from_expression(expr: &'a ast::Expression) -> Option<Self> {
+        coerce_opt::float(expr)
+    }
+}
+
+impl<'a, T: FromBamlExpression<'a>> FromBamlExpression<'a> for Vec<T> {
+    fn expected_type() -> String {
+        format!("{}[]", T::expected_type())
+    }
+
+    fn from_expression(expr: &'a ast::Expression) -> Option<Self> {
+        match expr {
+            ast::Expression::Array(values, _) => values.iter().map(T::from_expression).collect(),
+            _ => None,
+        }
+    }
+}
+
+/// A converted property value together with the span of its expression.
+#[derive(Debug, Clone, Copy)]
+pub struct Spanned<'a, T> {
+    /// The converted value.
+    pub value: T,
+    /// The span of the expression it was converted from.
+    pub span: &'a Span,
+}
+
+impl<'a, T: FromBamlExpression<'a>> FromBamlExpression<'a> for Spanned<'a, T> {
+    fn expected_type() -> String {
+        T::expected_type()
+    }
+
+    fn from_expression(expr: &'a ast::Expression) -> Option<Self> {
+        T::from_expression(expr).map(|value| Spanned {
+            value,
+            span: expr.span(),
+        })
+    }
+}
+
+/// The properties of one config block, read by key.
+///
+/// Properties outside the known keys and properties without a value are reported when the
+/// block is opened. Reading a property records an error if it is missing (for
+/// [`require`](Self::require)) or has the wrong type; the errors are returned by
+/// [`finish`](Self::finish) or [`into_errors`](Self::into_errors).
+pub struct ConfigProperties<'a> {
+    block: &'a ast::ValueExprBlock,
+    properties: HashMap<&'a str, &'a ast::Field<ast::Expression>>,
+    errors: Vec<DatamodelError>,
+}
+
+impl<'a> ConfigProperties<'a> {
+    /// The properties of `block`, recording an error for each one not in `known`.
+    pub fn new(block: &'a ast::ValueExprBlock, known: &[&str]) -> Self {
+        let mut errors = vec![];
+        let mut properties = HashMap::new();
+        for field in block.fields() {
+            if !known.contains(&field.name()) {
+                errors.push(DatamodelError::new_property_not_known_error(
+                    field.name(),
+                    field.identifier().span().clone(),
+                    known.to_vec(),
+                ));
+            } else if field.expr.is_none() {
+                errors.push(DatamodelError::new_config_property_missing_value_error(
+                    field.name(),
+                    block.name(),
+                    block.get_type(),
+                    field.identifier().span().clone(),
+                ));
+            } else {
+                properties.insert(field.name(), field);
+            }
+        }
+        ConfigProperties {
+            block,
+            properties,
+            errors,
+        }
+    }
+
+    /// The block the properties belong to.
+    pub fn block(&self) -> &'a ast::ValueExprBlock {
+        self.block
+    }
+
+    /// Whether the block sets `key`.
+    pub fn contains(&self, key: &str) -> bool {
+        self.properties.contains_key(key)
+    }
+
+    /// The span of the whole `key` property, or of the block name if it is not set.
+    pub fn span_of(&self, key: &str) -> &'a Span {
+        self.properties
+            .get(key)
+            .map(|field| field.span())
+            .unwrap_or_else(|| self.block.identifier().span())
+    }
+
+    /// Reads an optional property. Returns `None` if it is not set, or if it has the wrong type,
+    /// in which case an error is recorded.
+    pub fn get<T: FromBamlExpression<'a>>(&mut self, key: &str) -> Option<T> {
+        let expr = self.properties.get(key)?.expr.as_ref()?;
+        match T::from_expression(expr) {
+            Some(value) => Some(value),
+            None => {
+                self.errors.push(DatamodelError::new_type_mismatch_error(
+                    &T::expected_type(),
+                    expr.describe_value_type(),
+                    &expr.to_string(),
+                    expr.span().clone(),
+                ));
+                None
+            }
+        }
+    }
+
+    /// Reads a property the block must set, recording an error if it does not.
+    pub fn require<T: FromBamlExpression<'a>>(&mut self, key: &str) -> Option<T> {
+        if !self.contains(key) {
+            self.errors
+                .push(DatamodelError::new_missing_required_property_error(
+                    key,
+                    self.block.name(),
+                    self.block.identifier().span().clone(),
+                ));
+            return None;
+        }
+        self.get(key)
+    }
+
+    /// Records an error found while interpreting a property.
+    pub fn push_error(&mut self, error: DatamodelError) {
+        self.errors.push(error);
+    }
+
+    /// Every error recorded while reading the properties.
+    pub fn into_errors(self) -> Vec<DatamodelError> {
+        self.errors
+    }
+
+    /// Fails with every recorded error, if any.
+    pub fn finish(self) -> Result<(), Vec<DatamodelError>> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
+}
+
+/// A config block that converts to a typed value.
+///
+/// ```ignore
+/// struct Cache<'a> {
+///     dir: &'a str,
+///     ttl_seconds: u32,
+/// }
+///
+/// impl<'a> FromConfigBlock<'a> for Cache<'a> {
+///     const PROPERTIES: &'static [&'static str] = &["dir", "ttl_seconds"];
+///
+///     fn from_properties(properties: &mut ConfigProperties<'a>) -> Option<Self> {
+///         let dir = properties.require("dir");
+///         let ttl_seconds = properties.get("ttl_seconds").unwrap_or(3600);
+///         Some(Cache { dir: dir?, ttl_seconds })
+///     }
+/// }
+/// ```
+pub trait FromConfigBlock<'a>: Sized {
+    /// The keys the block accepts.
+    const PROPERTIES: &'static [&'static str];
+
+    /// Reads the value from the properties of the block. Returning `None` should come with an
+    /// error recorded on `properties`.
+    fn from_properties(properties: &mut ConfigProperties<'a>) -> Option<Self>;
+
+    /// Reads `block`, or returns every error found in its properties.
+    fn from_config_block(block: &'a ast::ValueExprBlock) -> Result<Self, Vec<DatamodelError>> {
+        let mut properties = ConfigProperties::new(block, Self::PROPERTIES);
+        let value = Self::from_properties(&mut properties);
+        match value {
+            Some(value) if properties.errors.is_empty() => Ok(value),
+            _ if !properties.errors.is_empty() => Err(properties.errors),
+            _ => Err(vec![DatamodelError::new_validation_error(
+                &format!("Invalid {} `{}`", block.get_type(), block.name()),
+                block.identifier().span().clone(),
+            )]),
+        }
+    }
+}
@@ -1,9 +1,15 @@
+use baml_types::JinjaExpression;
 use internal_baml_diagnostics::{DatamodelWarning, Span};
 use internal_baml_schema_ast::ast::{Argument, ArgumentId, Attribute};
 
 use crate::{
-    ast, ast::WithName, interner::StringInterner, names::Names, types::Types, DatamodelError,
-    Diagnostics, StringId,
+    ast,
+    ast::WithName,
+    attributes::constraint::substitute_constants,
+    interner::StringInterner,
+    names::{Names, ReservedNamePolicy},
+    types::Types,
+    DatamodelError, Diagnostics, StringId,
 };
 
 use self::attributes::AttributesValidationState;
@@ -29,6 +35,7 @@ pub(crate) struct Context<'db> {
     pub(crate) names: &'db mut Names,
     pub(crate) types: &'db mut Types,
     pub(crate) diagnostics: &'db mut Diagnostics,
+    pub(crate) reserved_names: &'db ReservedNamePolicy,
     attributes: AttributesValidationState, // state machine for attribute validation
 }
 
@@ -39,6 +46,7 @@ impl<'db> Context<'db> {
         names: &'db mut Names,
         types: &'db mut Types,
         diagnostics: &'db mut Diagnostics,
+        reserved_names: &'db ReservedNamePolicy,
     ) -> Self {
         Context {
             ast,
@@ -46,6 +54,7 @@ impl<'db> Context<'db> {
             names,
             types,
             diagnostics,
+            reserved_names,
             attributes: Default::default(),
         }
     }
@@ -68,6 +77,20 @@ impl<'db> Context<'db> {
         self.push_error(err);
     }
 
+    /// The value of the constant `name`, if there is one.
+    pub(crate) fn constant_value(&self, name: &str) -> Option<&'db ast::Expression> {
+        self.interner
+            .lookup(name)
+            .and_then(|name_id| self.names.tops.get(&name_id))
+            .and_then(|top_id| top_id.as_constant_id())
+            .map(|constant_id| self.ast[constant_id].value())
+    }
+
+    /// `expression` with the constants it uses replaced by their values.
+    pub(crate) fn resolve_constants(&self, expression: &JinjaExpression) -> JinjaExpression {
+        substitute_constants(expression, |name| self.constant_value(name))
+    }
+
     pub(super) fn push_error(&mut self, error: DatamodelError) {
         self.diagnostics.push_error(error)
     }
@@ -171,9 +194,15 @@ impl<'db> Context<'db> {
         name: &str,
     ) -> Result<(ArgumentId, &'db ast::Expression), DatamodelError> {
         match self.attributes.args.pop_front() {
+            // An identifier naming a constant stands for the constant's value.
             Some(arg_idx) => {
-                let arg = self.arg_at(arg_idx);
-                Ok((arg_idx, &arg.value))
+                let value = &self.arg_at(arg_idx).value;
+                match value {
+                    ast::Expression::Identifier(ast::Identifier::Local(name, _)) => {
+                        Ok((arg_idx, self.constant_value(name).unwrap_or(value)))
+                    }
+                    _ => Ok((arg_idx, value)),
+                }
             }
             None => Err(DatamodelError::new_argument_not_found_error(
                 name,
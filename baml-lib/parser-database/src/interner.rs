@@ -2,13 +2,22 @@
 //! allocation pressure of ParserDatabase.
 //!
 //! The StringIds returned by `intern` are only valid for this specific instance of the interner
-//! they were interned with.
+//! they were interned with. Within that instance they never change: strings are only ever added,
+//! and each one keeps the index it was first interned at.
 
 use indexmap::IndexSet;
 
+/// A string interned by a [`ParserDatabase`](crate::ParserDatabase).
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
 pub struct StringId(usize);
 
+impl StringId {
+    /// The position of the string in the interner, usable as a compact key in side tables.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
 #[derive(Default)]
 pub(crate) struct StringInterner {
     map: IndexSet<String>,
@@ -24,6 +33,17 @@ impl StringInterner {
         self.map.get_index_of(s).map(StringId)
     }
 
+    pub(crate) fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (StringId, &str)> {
+        self.map
+            .iter()
+            .enumerate()
+            .map(|(idx, s)| (StringId(idx), s.as_str()))
+    }
+
     pub(crate) fn intern(&mut self, s: &str) -> StringId {
         if let Some(id) = self.lookup(s) {
             id
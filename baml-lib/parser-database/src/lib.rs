@@ -26,6 +26,7 @@
 //!   defined with a `map:` argument or be a default, generated name if the `map:` argument is not
 //!   provided. These usually require a datamodel connector to be defined.
 
+pub mod config_block;
 pub mod walkers;
 
 mod attributes;
@@ -33,22 +34,31 @@ mod coerce_expression;
 mod context;
 mod interner;
 mod names;
+mod references;
 mod tarjan;
+mod type_depth;
 mod types;
 
 use std::collections::{HashMap, HashSet, VecDeque};
 
+pub use attributes::constraint::type_constraints;
+pub use attributes::{BLOCK_ATTRIBUTES, FIELD_ATTRIBUTES};
+pub use attributes::description::description_references;
 pub use coerce_expression::{coerce, coerce_array, coerce_opt};
+pub use interner::StringId;
 pub use internal_baml_schema_ast::ast;
-use internal_baml_schema_ast::ast::{FieldType, SchemaAst, WithName};
+pub use names::ReservedNamePolicy;
+use internal_baml_schema_ast::ast::{FieldType, SchemaAst, WithName, WithSpan};
 pub use tarjan::Tarjan;
+pub use type_depth::DEFAULT_MAX_TYPE_DEPTH;
 pub use types::{
     Attributes, ClientProperties, ContantDelayStrategy, ExponentialBackoffStrategy, PrinterType,
-    PromptAst, PromptVariable, RetryPolicy, RetryPolicyStrategy, StaticType,
+    PromptAst, PromptSuite, PromptVariable, RenderOverrides, RetryPolicy, RetryPolicyStrategy,
+    StaticType,
 };
 pub use walkers::TypeWalker;
 
-use self::{context::Context, interner::StringId, types::Types};
+use self::{context::Context, types::Types};
 use internal_baml_diagnostics::{DatamodelError, Diagnostics};
 use names::Names;
 
@@ -76,6 +86,8 @@ pub struct ParserDatabase {
     interner: interner::StringInterner,
     names: Names,
     types: Types,
+    reserved_names: ReservedNamePolicy,
+    max_type_depth: usize,
 }
 
 impl Default for ParserDatabase {
@@ -88,26 +100,46 @@ impl ParserDatabase {
     /// Create a new, empty ParserDatabase.
     pub fn new() -> Self {
         ParserDatabase {
-            ast: ast::SchemaAst { tops: vec![] },
+            ast: ast::SchemaAst::new(),
             interner: Default::default(),
             names: Default::default(),
             types: Default::default(),
+            reserved_names: Default::default(),
+            max_type_depth: DEFAULT_MAX_TYPE_DEPTH,
         }
     }
 
     /// See the docs on [ParserDatabase](/struct.ParserDatabase.html).
     pub fn add_ast(&mut self, ast: SchemaAst) {
         self.ast.tops.extend(ast.tops);
+        self.ast.trivia.extend(ast.trivia);
+    }
+
+    /// Use `policy` instead of the default reserved names when validating.
+    pub fn set_reserved_names(&mut self, policy: ReservedNamePolicy) {
+        self.reserved_names = policy;
+    }
+
+    /// Reject types nested deeper than `max_depth` instead of [`DEFAULT_MAX_TYPE_DEPTH`].
+    pub fn set_max_type_depth(&mut self, max_depth: usize) {
+        self.max_type_depth = max_depth;
     }
 
     /// See the docs on [ParserDatabase](/struct.ParserDatabase.html).
     pub fn validate(&mut self, diag: &mut Diagnostics) -> Result<(), Diagnostics> {
+        // Every later pass recurses into types, so too deep ones must not reach them.
+        if !type_depth::validate_type_depth(&self.ast, self.max_type_depth, diag) {
+            return Err(std::mem::take(diag));
+        }
+
+        let max_type_depth = self.max_type_depth;
         let mut ctx = Context::new(
             &self.ast,
             &mut self.interner,
             &mut self.names,
             &mut self.types,
             diag,
+            &self.reserved_names,
         );
 
         // First pass: resolve names.
@@ -119,6 +151,7 @@ impl ParserDatabase {
         // Resolve type aliases now because Jinja template validation needs this
         // information.
         types::resolve_type_aliases(&mut ctx);
+        type_depth::validate_resolved_alias_depth(&mut ctx, max_type_depth);
 
         // Return early on type resolution errors.
         ctx.diagnostics.to_result()?;
@@ -165,22 +198,32 @@ impl ParserDatabase {
                         resolved_deps.insert(cls.id);
                     }
                     Some(TypeWalker::Enum(_)) => {}
-                    // Gotta resolve type aliases.
+                    // Gotta resolve type aliases. Aliases that failed to resolve were already
+                    // reported, e.g. for nesting too deeply.
                     Some(TypeWalker::TypeAlias(alias)) => {
-                        resolved_deps.extend(alias.resolved().flat_idns().iter().map(|ident| {
+                        let Some(resolved) = self.types.resolved_type_aliases.get(&alias.id) else {
+                            continue;
+                        };
+                        for ident in resolved.flat_idns() {
                             match self.find_type_by_str(ident.name()) {
-                                Some(TypeWalker::Class(cls)) => cls.id,
-                                Some(TypeWalker::Enum(_)) => {
-                                    panic!("Enums are not allowed in type aliases")
+                                Some(TypeWalker::Class(cls)) => {
+                                    resolved_deps.insert(cls.id);
                                 }
-                                Some(TypeWalker::TypeAlias(alias)) => {
-                                    panic!("Alias should be resolved at this point")
-                                }
-                                None => panic!("Unknown class `{dep}`"),
+                                // Only recursive aliases are left unresolved, and the classes
+                                // they refer to are already part of the resolved type.
+                                Some(TypeWalker::Enum(_)) | Some(TypeWalker::TypeAlias(_)) => {}
+                                None => diag.push_error(DatamodelError::new_type_not_found_error(
+                                    ident.name(),
+                                    vec![],
+                                    ident.span().clone(),
+                                )),
                             }
-                        }))
+                        }
                     }
-                    None => panic!("Unknown class `{dep}`"),
+                    None => diag.push_error(DatamodelError::new_validation_error(
+                        &format!("Unknown class `{dep}`"),
+                        self.ast[*id].span.clone(),
+                    )),
                 }
             }
 
@@ -268,6 +311,35 @@ impl ParserDatabase {
         &self.ast
     }
 
+    /// The parsed AST, taken out of the database: the tops and trivia of every
+    /// [`add_ast`](Self::add_ast), one after the other.
+    pub fn into_ast(self) -> ast::SchemaAst {
+        self.ast
+    }
+
+    /// The id of `s` if it was interned while validating, e.g. as the name of a type, field,
+    /// function or client.
+    ///
+    /// Ids are stable for the lifetime of this database but mean nothing in any other one.
+    pub fn lookup_string(&self, s: &str) -> Option<StringId> {
+        self.interner.lookup(s)
+    }
+
+    /// The string behind `id`, or `None` if `id` comes from another database.
+    pub fn resolve_string(&self, id: StringId) -> Option<&str> {
+        self.interner.get(id)
+    }
+
+    /// Every interned string with its id, in id order.
+    pub fn interned_strings(&self) -> impl Iterator<Item = (StringId, &str)> {
+        self.interner.iter()
+    }
+
+    /// The number of interned strings. Ids are below this.
+    pub fn interned_strings_count(&self) -> usize {
+        self.interner.len()
+    }
+
     /// Returns the graph of type aliases.
     ///
     /// Each vertex is a type alias and each edge is a reference to another type
@@ -693,4 +765,108 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn query_by_attribute() -> Result<(), Diagnostics> {
+        #[rustfmt::skip]
+        let db = parse(r#"
+            class Plain {
+                a int
+            }
+
+            class Dynamic {
+                b int @skip
+                c string @alias("see")
+                @@dynamic
+            }
+
+            enum Color {
+                Red
+                Green @skip
+                @@dynamic
+            }
+        "#)?;
+
+        let types = db
+            .types_with_attribute("dynamic_type")
+            .map(|t| match t {
+                TypeWalker::Class(c) => c.name().to_string(),
+                TypeWalker::Enum(e) => e.name().to_string(),
+                TypeWalker::TypeAlias(a) => a.name().to_string(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(types, ["Dynamic", "Color"]);
+
+        let skipped = db
+            .fields_with_attribute("skip")
+            .map(|f| f.name().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(skipped, ["b"]);
+
+        let skipped_values = db
+            .enum_values_with_attribute("skip")
+            .map(|v| v.name().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(skipped_values, ["Green"]);
+
+        assert_eq!(db.fields_with_attribute("unknown").count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn interned_names_resolve_by_id() -> Result<(), Diagnostics> {
+        let db = parse(
+            r#"
+            class Foo {
+                bar int
+            }
+        "#,
+        )?;
+
+        let foo = db.lookup_string("Foo").expect("class names are interned");
+        assert_eq!(db.resolve_string(foo), Some("Foo"));
+        assert_eq!(&db[foo], "Foo");
+        assert!(db.lookup_string("Missing").is_none());
+        assert!(matches!(db.find_type_by_id(foo), Some(TypeWalker::Class(_))));
+
+        let bar = db.lookup_string("bar").expect("field names are interned");
+        assert!(db.find_type_by_id(bar).is_none());
+
+        let all = db.interned_strings().collect::<Vec<_>>();
+        assert_eq!(all.len(), db.interned_strings_count());
+        assert!(all.iter().enumerate().all(|(idx, (id, _))| id.index() == idx));
+        assert!(all.contains(&(foo, "Foo")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn config_blocks_report_every_property_error() {
+        let Err(diagnostics) = parse(
+            r#"
+            retry_policy Retry {
+                max_retries 1.5
+                stratgy {
+                    type constant_delay
+                }
+            }
+        "#,
+        ) else {
+            panic!("the retry policy is invalid");
+        };
+
+        let messages = diagnostics
+            .errors()
+            .iter()
+            .map(|e| e.message().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            [
+                "Property not known: \"stratgy\". Did you mean one of these: \"strategy\", \"options\", \"max_retries\"?",
+                "Expected a non-negative integer value, but received numeric value `1.5`.",
+            ]
+        );
+    }
 }
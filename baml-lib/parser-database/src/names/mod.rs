@@ -1,5 +1,7 @@
 mod validate_reserved_names;
 
+pub use validate_reserved_names::ReservedNamePolicy;
+
 use crate::{
     ast::{self, TopId, WithAttributes, WithName, WithSpan},
     coerce, coerce_array, Context, DatamodelError, StaticType, StringId,
@@ -42,11 +44,11 @@ pub(super) fn resolve_names(ctx: &mut Context<'_>) {
         let namespace = match (top_id, top) {
             (_, ast::Top::Enum(ast_enum)) => {
                 tmp_names.clear();
-                validate_enum_name(ast_enum, ctx.diagnostics);
+                validate_enum_name(ast_enum, ctx);
                 validate_attribute_identifiers(ast_enum, ctx);
 
                 for value in &ast_enum.fields {
-                    validate_enum_value_name(value, ctx.diagnostics);
+                    validate_enum_value_name(value, ctx);
 
                     validate_attribute_identifiers(value, ctx);
 
@@ -63,11 +65,11 @@ pub(super) fn resolve_names(ctx: &mut Context<'_>) {
             }
 
             (ast::TopId::Class(model_id), ast::Top::Class(ast_class)) => {
-                validate_class_name(ast_class, ctx.diagnostics);
+                validate_class_name(ast_class, ctx);
                 validate_attribute_identifiers(ast_class, ctx);
 
                 for (field_id, field) in ast_class.iter_fields() {
-                    validate_class_field_name(field, ctx.diagnostics);
+                    validate_class_field_name(field, ctx);
                     validate_attribute_identifiers(field, ctx);
 
                     let field_name_id = ctx.interner.intern(field.name());
@@ -92,7 +94,7 @@ pub(super) fn resolve_names(ctx: &mut Context<'_>) {
             }
 
             (ast::TopId::TypeAlias(_), ast::Top::TypeAlias(type_alias)) => {
-                validate_type_alias_name(type_alias, ctx.diagnostics);
+                validate_type_alias_name(type_alias, ctx);
 
                 ctx.interner.intern(type_alias.name());
 
@@ -104,7 +106,7 @@ pub(super) fn resolve_names(ctx: &mut Context<'_>) {
             }
 
             (ast::TopId::TemplateString(_), ast::Top::TemplateString(template_string)) => {
-                validate_template_string_name(template_string, ctx.diagnostics);
+                validate_template_string_name(template_string, ctx);
                 validate_attribute_identifiers(template_string, ctx);
 
                 Some(either::Left(&mut names.tops))
@@ -114,7 +116,7 @@ pub(super) fn resolve_names(ctx: &mut Context<'_>) {
             }
 
             (ast::TopId::Function(_function_id), ast::Top::Function(ast_function)) => {
-                validate_function_name(ast_function, ctx.diagnostics);
+                validate_function_name(ast_function, ctx);
                 validate_attribute_identifiers(ast_function, ctx);
 
                 Some(either::Left(&mut names.tops))
@@ -124,7 +126,7 @@ pub(super) fn resolve_names(ctx: &mut Context<'_>) {
             }
 
             (ast::TopId::Client(_), ast::Top::Client(ast_client)) => {
-                validate_client_name(ast_client, ctx.diagnostics);
+                validate_client_name(ast_client, ctx);
                 validate_attribute_identifiers(ast_client, ctx);
 
                 ctx.interner.intern(ast_client.identifier().name());
@@ -136,7 +138,7 @@ pub(super) fn resolve_names(ctx: &mut Context<'_>) {
             }
 
             (ast::TopId::RetryPolicy(_), ast::Top::RetryPolicy(ast_retry_policy)) => {
-                validate_retry(ast_retry_policy, ctx.diagnostics);
+                validate_retry(ast_retry_policy, ctx);
                 validate_attribute_identifiers(ast_retry_policy, ctx);
 
                 ctx.interner.intern(ast_retry_policy.identifier().name());
@@ -147,14 +149,41 @@ pub(super) fn resolve_names(ctx: &mut Context<'_>) {
                 unreachable!("Encountered impossible retry_policy declaration during parsing")
             }
 
+            (ast::TopId::PromptSuite(_), ast::Top::PromptSuite(ast_prompt_suite)) => {
+                validate_prompt_suite_name(ast_prompt_suite, ctx);
+                validate_attribute_identifiers(ast_prompt_suite, ctx);
+                check_for_duplicate_properties(
+                    top,
+                    ast_prompt_suite.fields(),
+                    &mut tmp_names,
+                    ctx,
+                );
+
+                Some(either::Left(&mut names.tops))
+            }
+            (_, ast::Top::PromptSuite(_)) => {
+                unreachable!("Encountered impossible prompt_suite declaration during parsing")
+            }
+
+            (ast::TopId::Constant(_), ast::Top::Constant(constant)) => {
+                validate_constant_name(constant, ctx);
+
+                ctx.interner.intern(constant.name());
+
+                Some(either::Left(&mut names.tops))
+            }
+            (_, ast::Top::Constant(_)) => {
+                unreachable!("Encountered impossible const declaration during parsing")
+            }
+
             (_, ast::Top::Generator(generator)) => {
-                validate_generator_name(generator, ctx.diagnostics);
+                validate_generator_name(generator, ctx);
                 check_for_duplicate_properties(top, generator.fields(), &mut tmp_names, ctx);
                 Some(either::Left(&mut names.generators))
             }
 
             (ast::TopId::TestCase(testcase_id), ast::Top::TestCase(testcase)) => {
-                validate_test(testcase, ctx.diagnostics);
+                validate_test(testcase, ctx);
                 check_for_duplicate_properties(top, testcase.fields(), &mut tmp_names, ctx);
 
                 // TODO: I think we should do this later after all parsing, as duplication
@@ -232,7 +261,7 @@ fn duplicate_top_error(existing: &ast::Top, duplicate: &ast::Top) -> DatamodelEr
 }
 
 fn assert_is_not_a_reserved_scalar_type(ident: &ast::Identifier, ctx: &mut Context<'_>) {
-    if StaticType::try_from_str(ident.name()).is_some() {
+    if !ctx.reserved_names.allows_scalar_type(ident.name()) {
         ctx.push_error(DatamodelError::new_reserved_scalar_type_error(
             ident.name(),
             ident.span().clone(),
@@ -260,6 +289,6 @@ fn check_for_duplicate_properties<'a>(
 
 fn validate_attribute_identifiers(with_attrs: &dyn WithAttributes, ctx: &mut Context<'_>) {
     for attribute in with_attrs.attributes() {
-        validate_attribute_name(attribute, ctx.diagnostics);
+        validate_attribute_name(attribute, ctx);
     }
 }
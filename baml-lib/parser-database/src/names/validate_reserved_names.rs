@@ -2,104 +2,128 @@ use internal_baml_schema_ast::ast::{FieldType, ValueExprBlockType};
 
 use crate::{
     ast::{self, WithIdentifier, WithName},
-    DatamodelError, Diagnostics,
+    Context, DatamodelError, StaticType,
 };
 
-pub(crate) fn validate_attribute_name(ast_attr: &ast::Attribute, diagnostics: &mut Diagnostics) {
-    validate_name("attribute", ast_attr.identifier(), diagnostics, false);
+/// Which names a schema may not give its classes, enums, fields, functions and so on.
+///
+/// By default that is `BamlClient`, and the scalar type names (`String`, `Int`, ...) for
+/// classes, enums and other top-level declarations. Set on the database with
+/// [`ParserDatabase::set_reserved_names`](crate::ParserDatabase::set_reserved_names).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReservedNamePolicy {
+    /// Names to reserve as well, e.g. types that generated code defines itself.
+    pub reserve: Vec<String>,
+    /// Names reserved by default to allow anyway, e.g. `String` when no generated client
+    /// would clash with it.
+    pub allow: Vec<String>,
 }
 
-pub(crate) fn validate_generator_name(
-    ast_gen: &ast::ValueExprBlock,
-    diagnostics: &mut Diagnostics,
-) {
-    validate_name("generator", ast_gen.identifier(), diagnostics, false);
+impl ReservedNamePolicy {
+    /// Why `name` is reserved for any declaration, if it is.
+    pub(crate) fn reserved(&self, name: &str) -> Option<&'static str> {
+        if self.reserve.iter().any(|n| n == name) {
+            Some("This name is reserved by the active reserved-name policy.")
+        } else if RESERVED_NAMES.contains(&name) && !self.allows(name) {
+            Some("This name is reserved. The reserved-name policy can allow it.")
+        } else {
+            None
+        }
+    }
+
+    /// Whether `name` may be used for a top-level declaration although it is a scalar type.
+    pub(crate) fn allows_scalar_type(&self, name: &str) -> bool {
+        StaticType::try_from_str(name).is_none() || self.allows(name)
+    }
+
+    fn allows(&self, name: &str) -> bool {
+        self.allow.iter().any(|n| n == name)
+    }
 }
 
-pub(crate) fn validate_client_name(
-    ast_client: &ast::ValueExprBlock,
-    diagnostics: &mut Diagnostics,
-) {
-    validate_name("client", ast_client.identifier(), diagnostics, true);
+pub(crate) fn validate_attribute_name(ast_attr: &ast::Attribute, ctx: &mut Context<'_>) {
+    validate_name("attribute", ast_attr.identifier(), ctx, false);
+}
+
+pub(crate) fn validate_generator_name(ast_gen: &ast::ValueExprBlock, ctx: &mut Context<'_>) {
+    validate_name("generator", ast_gen.identifier(), ctx, false);
 }
 
-pub(crate) fn validate_test(ast_config: &ast::ValueExprBlock, diagnostics: &mut Diagnostics) {
-    validate_name("test", ast_config.identifier(), diagnostics, false);
+pub(crate) fn validate_client_name(ast_client: &ast::ValueExprBlock, ctx: &mut Context<'_>) {
+    validate_name("client", ast_client.identifier(), ctx, true);
 }
 
-pub(crate) fn validate_retry(ast_config: &ast::ValueExprBlock, diagnostics: &mut Diagnostics) {
+pub(crate) fn validate_test(ast_config: &ast::ValueExprBlock, ctx: &mut Context<'_>) {
+    validate_name("test", ast_config.identifier(), ctx, false);
+}
+
+pub(crate) fn validate_retry(ast_config: &ast::ValueExprBlock, ctx: &mut Context<'_>) {
     validate_name(
         "retry",
         ast_config.identifier(),
-        diagnostics,
+        ctx,
         // Test cases don't need to be upper case.
         true,
     )
 }
 
-pub(crate) fn validate_class_name(
-    ast_class: &ast::TypeExpressionBlock,
-    diagnostics: &mut Diagnostics,
+pub(crate) fn validate_prompt_suite_name(
+    ast_config: &ast::ValueExprBlock,
+    ctx: &mut Context<'_>,
 ) {
-    validate_name("class", ast_class.identifier(), diagnostics, true);
+    validate_name("prompt_suite", ast_config.identifier(), ctx, true);
 }
 
-pub(crate) fn validate_type_alias_name(ast_class: &ast::Assignment, diagnostics: &mut Diagnostics) {
-    validate_name("type alias", ast_class.identifier(), diagnostics, true);
+pub(crate) fn validate_constant_name(ast_constant: &ast::Constant, ctx: &mut Context<'_>) {
+    validate_name("const", ast_constant.identifier(), ctx, true);
 }
 
-pub(crate) fn validate_class_field_name<T>(
-    ast_class_field: &ast::Field<T>,
-    diagnostics: &mut Diagnostics,
-) {
-    validate_name(
-        "class field",
-        ast_class_field.identifier(),
-        diagnostics,
-        false,
-    );
+pub(crate) fn validate_class_name(ast_class: &ast::TypeExpressionBlock, ctx: &mut Context<'_>) {
+    validate_name("class", ast_class.identifier(), ctx, true);
+}
+
+pub(crate) fn validate_type_alias_name(ast_class: &ast::Assignment, ctx: &mut Context<'_>) {
+    validate_name("type alias", ast_class.identifier(), ctx, true);
+}
+
+pub(crate) fn validate_class_field_name<T>(ast_class_field: &ast::Field<T>, ctx: &mut Context<'_>) {
+    validate_name("class field", ast_class_field.identifier(), ctx, false);
 }
 
 pub(crate) fn validate_template_string_name(
     ast_template_string: &ast::TemplateString,
-    diagnostics: &mut Diagnostics,
+    ctx: &mut Context<'_>,
 ) {
     validate_name(
         "template_string",
         ast_template_string.identifier(),
-        diagnostics,
+        ctx,
         true,
     );
 }
 
-pub(crate) fn validate_function_name(
-    ast_func: &ast::ValueExprBlock,
-    diagnostics: &mut Diagnostics,
-) {
-    validate_name("function", ast_func.identifier(), diagnostics, true);
+pub(crate) fn validate_function_name(ast_func: &ast::ValueExprBlock, ctx: &mut Context<'_>) {
+    validate_name("function", ast_func.identifier(), ctx, true);
 }
 
-pub(crate) fn validate_enum_name(
-    ast_enum: &ast::TypeExpressionBlock,
-    diagnostics: &mut Diagnostics,
-) {
-    validate_name("enum", ast_enum.identifier(), diagnostics, true);
+pub(crate) fn validate_enum_name(ast_enum: &ast::TypeExpressionBlock, ctx: &mut Context<'_>) {
+    validate_name("enum", ast_enum.identifier(), ctx, true);
     ast_enum.iter_fields().for_each(|(_, val)| {
-        validate_name("enum value", val.identifier(), diagnostics, true);
+        validate_name("enum value", val.identifier(), ctx, true);
     })
 }
 
 pub(crate) fn validate_enum_value_name(
     ast_enum_value: &ast::Field<FieldType>,
-    diagnostics: &mut Diagnostics,
+    ctx: &mut Context<'_>,
 ) {
-    validate_name("enum value", ast_enum_value.identifier(), diagnostics, true);
+    validate_name("enum value", ast_enum_value.identifier(), ctx, true);
 }
 
 fn validate_name(
     _type: &str,
     idn: &ast::Identifier,
-    diagnostics: &mut Diagnostics,
+    ctx: &mut Context<'_>,
     require_upper_case: bool,
 ) {
     let res = match idn {
@@ -127,10 +151,10 @@ fn validate_name(
                 "Must start with an uppercase letter.",
                 span.clone(),
             ))
-        } else if RESERVED_NAMES.contains(&val.as_str()) {
+        } else if let Some(reason) = ctx.reserved_names.reserved(val) {
             Err(DatamodelError::new_name_error(
                 _type,
-                "This name is reserved.",
+                reason,
                 span.clone(),
             ))
         } else {
@@ -140,7 +164,7 @@ fn validate_name(
 
     match res {
         Ok(_) => {}
-        Err(e) => diagnostics.push_error(e),
+        Err(e) => ctx.push_error(e),
     }
 }
 
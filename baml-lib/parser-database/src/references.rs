@@ -0,0 +1,140 @@
+//! Where types and class fields are referred to, e.g. to rename them.
+
+use internal_baml_diagnostics::Span;
+use internal_baml_schema_ast::ast::{
+    Attribute, Expression, FieldType, Identifier, Top, TypeExpressionBlock, WithIdentifier,
+    WithName, WithSpan,
+};
+
+use crate::ParserDatabase;
+
+impl ParserDatabase {
+    /// Spans of every occurrence of the class, enum or type alias `name`: its declaration and
+    /// its uses in field types, type aliases, and function and template string signatures.
+    pub fn type_references(&self, name: &str) -> Vec<Span> {
+        let mut spans = Vec::new();
+        for (_, top) in self.ast.iter_tops() {
+            match top {
+                Top::Class(block) | Top::Enum(block) => {
+                    push_if_named(block.identifier(), name, &mut spans);
+                    for field in &block.fields {
+                        if let Some(field_type) = &field.expr {
+                            symbol_spans(field_type, name, &mut spans);
+                        }
+                    }
+                }
+                Top::TypeAlias(assignment) => {
+                    push_if_named(&assignment.identifier, name, &mut spans);
+                    symbol_spans(&assignment.value, name, &mut spans);
+                }
+                Top::Function(block) => {
+                    for (_, arg) in block.input().iter().flat_map(|input| &input.args) {
+                        symbol_spans(&arg.field_type, name, &mut spans);
+                    }
+                    if let Some(output) = block.output() {
+                        symbol_spans(&output.field_type, name, &mut spans);
+                    }
+                }
+                Top::TemplateString(template) => {
+                    for (_, arg) in template.input().iter().flat_map(|input| &input.args) {
+                        symbol_spans(&arg.field_type, name, &mut spans);
+                    }
+                }
+                _ => {}
+            }
+        }
+        spans
+    }
+
+    /// Spans of every occurrence of field `field` of class `class`: its declaration, the
+    /// class's `@@one_of` arguments and `this.field` in the class's `@@assert` and `@@check`
+    /// expressions and its fields' `@required_if` conditions.
+    pub fn field_references(&self, class: &str, field: &str) -> Vec<Span> {
+        let mut spans = Vec::new();
+        let Some(block) = self.ast.iter_tops().find_map(|(_, top)| match top {
+            Top::Class(block) if block.name() == class => Some(block),
+            _ => None,
+        }) else {
+            return spans;
+        };
+        for f in &block.fields {
+            push_if_named(f.identifier(), field, &mut spans);
+        }
+        for attribute in &block.attributes {
+            for (_, argument) in attribute.arguments.iter() {
+                match &argument.value {
+                    Expression::Identifier(identifier) if attribute.name() == "one_of" => {
+                        push_if_named(identifier, field, &mut spans)
+                    }
+                    Expression::JinjaExpressionValue(_, span) => {
+                        this_field_spans(span, field, &mut spans)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        for attribute in field_attributes(block).filter(|a| a.name() == "required_if") {
+            for (_, argument) in attribute.arguments.iter() {
+                if let Expression::JinjaExpressionValue(_, span) = &argument.value {
+                    this_field_spans(span, field, &mut spans);
+                }
+            }
+        }
+        spans.sort_by_key(|span| span.start);
+        spans
+    }
+}
+
+fn field_attributes(block: &TypeExpressionBlock) -> impl Iterator<Item = &Attribute> {
+    block.fields.iter().flat_map(|field| {
+        field
+            .attributes
+            .iter()
+            .chain(field.expr.iter().flat_map(|t| t.attributes()))
+    })
+}
+
+fn push_if_named(identifier: &Identifier, name: &str, spans: &mut Vec<Span>) {
+    if matches!(identifier, Identifier::Local(..)) && identifier.name() == name {
+        spans.push(identifier.span().clone());
+    }
+}
+
+fn symbol_spans(field_type: &FieldType, name: &str, spans: &mut Vec<Span>) {
+    match field_type {
+        FieldType::Symbol(_, identifier, _) => push_if_named(identifier, name, spans),
+        FieldType::List(_, inner, ..) => symbol_spans(inner, name, spans),
+        FieldType::Map(_, kv, ..) => {
+            symbol_spans(&kv.0, name, spans);
+            symbol_spans(&kv.1, name, spans);
+        }
+        FieldType::Tuple(_, items, ..) | FieldType::Union(_, items, ..) => {
+            for item in items {
+                symbol_spans(item, name, spans);
+            }
+        }
+        FieldType::Primitive(..) | FieldType::Literal(..) => {}
+    }
+}
+
+/// Spans of `field` in each `this.field` within the jinja expression at `span`.
+fn this_field_spans(span: &Span, field: &str, spans: &mut Vec<Span>) {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let text = &span.file.as_str()[span.start..span.end];
+    let pattern = format!("this.{field}");
+    for (idx, _) in text.match_indices(&pattern) {
+        let end = idx + pattern.len();
+        let starts_word = text[..idx]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !is_ident(c) && c != '.');
+        let ends_word = text[end..].chars().next().is_none_or(|c| !is_ident(c));
+        if starts_word && ends_word {
+            spans.push(Span::new(
+                span.file.clone(),
+                span.start + end - field.len(),
+                span.start + end,
+            ));
+        }
+    }
+}
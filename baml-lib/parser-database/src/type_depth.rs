@@ -0,0 +1,159 @@
+//! Limits on how deeply types may nest.
+//!
+//! Converting types between the AST, the database and the IR recurses once per level of
+//! nesting, so a hostile schema with a type nested thousands of levels deep would overflow the
+//! stack. These checks run before any of that and only use explicit stacks themselves.
+
+use std::collections::{HashMap, HashSet};
+
+use internal_baml_diagnostics::{DatamodelError, Diagnostics};
+use internal_baml_schema_ast::ast::{self, FieldType, WithName};
+
+use crate::context::Context;
+
+/// How deeply types may nest unless
+/// [`ParserDatabase::set_max_type_depth`](crate::ParserDatabase::set_max_type_depth) says
+/// otherwise. See [`FieldType::depth`].
+pub const DEFAULT_MAX_TYPE_DEPTH: usize = 128;
+
+/// Reports every class field, function argument, function return type and type alias written
+/// with a type nested deeper than `max_depth`. Returns whether there were none.
+pub(crate) fn validate_type_depth(
+    ast: &ast::SchemaAst,
+    max_depth: usize,
+    diag: &mut Diagnostics,
+) -> bool {
+    let mut within_limit = true;
+    for (_, top) in ast.iter_tops() {
+        match top {
+            ast::Top::Class(class) => {
+                for field in &class.fields {
+                    if let Some(field_type) = &field.expr {
+                        within_limit &= check(field_type, max_depth, diag);
+                    }
+                }
+            }
+            ast::Top::Function(function) => {
+                for (_, arg) in function.input().into_iter().flat_map(|input| &input.args) {
+                    within_limit &= check(&arg.field_type, max_depth, diag);
+                }
+                if let Some(output) = function.output() {
+                    within_limit &= check(&output.field_type, max_depth, diag);
+                }
+            }
+            ast::Top::TypeAlias(alias) => within_limit &= check(&alias.value, max_depth, diag),
+            _ => {}
+        }
+    }
+    within_limit
+}
+
+/// Reports type aliases that only nest too deeply once the aliases they use are substituted,
+/// e.g. a long chain of `type A2 = A1[]`. Aliases that are part of a cycle count as a single
+/// level where they are used.
+pub(crate) fn validate_resolved_alias_depth(ctx: &mut Context<'_>, max_depth: usize) {
+    let cyclic = ctx
+        .types
+        .recursive_alias_cycles
+        .iter()
+        .flatten()
+        .copied()
+        .collect::<HashSet<_>>();
+
+    let mut aliases = ctx
+        .types
+        .type_alias_dependencies
+        .keys()
+        .copied()
+        .collect::<Vec<_>>();
+    aliases.sort();
+
+    // Post-order walk of the alias graph so the depths of the aliases an alias uses are known
+    // before its own.
+    let mut depths = HashMap::new();
+    for root in &aliases {
+        let mut stack = vec![(*root, false)];
+        while let Some((id, deps_visited)) = stack.pop() {
+            if depths.contains_key(&id) {
+                continue;
+            }
+            if deps_visited {
+                let depth = expanded_depth(&ctx.ast[id].value, ctx, &depths);
+                depths.insert(id, depth);
+                continue;
+            }
+            stack.push((id, true));
+            stack.extend(
+                ctx.types.type_alias_dependencies[&id]
+                    .iter()
+                    .filter(|dep| !cyclic.contains(*dep) && !depths.contains_key(*dep))
+                    .map(|dep| (*dep, false)),
+            );
+        }
+    }
+
+    for id in aliases {
+        if depths[&id] <= max_depth {
+            continue;
+        }
+        let alias = &ctx.ast[id];
+        ctx.push_error(DatamodelError::new_validation_error(
+            &format!(
+                "Type alias `{}` nests deeper than the limit of {max_depth} levels once resolved.",
+                alias.name()
+            ),
+            alias.value.span().clone(),
+        ));
+    }
+}
+
+/// Like [`FieldType::depth`], continuing into the aliases in `depths` instead of counting them
+/// as a single level.
+fn expanded_depth(
+    field_type: &FieldType,
+    ctx: &Context<'_>,
+    depths: &HashMap<ast::TypeAliasId, usize>,
+) -> usize {
+    let mut deepest = 0;
+    let mut stack = vec![(field_type, 1)];
+    while let Some((field_type, depth)) = stack.pop() {
+        deepest = deepest.max(depth);
+        match field_type {
+            FieldType::Union(_, f, ..) | FieldType::Tuple(_, f, ..) => {
+                stack.extend(f.iter().map(|t| (t, depth + 1)))
+            }
+            FieldType::Map(_, kv, ..) => {
+                stack.push((&kv.0, depth + 1));
+                stack.push((&kv.1, depth + 1));
+            }
+            FieldType::List(_, t, dims, ..) => stack.push((t, depth + *dims as usize)),
+            FieldType::Symbol(_, ident, _) => {
+                let alias_depth = ctx
+                    .interner
+                    .lookup(ident.name())
+                    .and_then(|name| ctx.names.tops.get(&name))
+                    .and_then(|top| match top {
+                        ast::TopId::TypeAlias(alias_id) => depths.get(alias_id),
+                        _ => None,
+                    });
+                if let Some(alias_depth) = alias_depth {
+                    deepest = deepest.max(depth - 1 + alias_depth);
+                }
+            }
+            FieldType::Primitive(..) | FieldType::Literal(..) => {}
+        }
+    }
+    deepest
+}
+
+fn check(field_type: &FieldType, max_depth: usize, diag: &mut Diagnostics) -> bool {
+    let depth = field_type.depth();
+    if depth <= max_depth {
+        return true;
+    }
+    diag.push_error(DatamodelError::new_validation_error(
+        &format!("This type nests {depth} levels deep, over the limit of {max_depth}."),
+        field_type.span().clone(),
+    ));
+    false
+}
@@ -1,17 +1,20 @@
 use baml_types::Constraint;
 use baml_types::UnresolvedValue;
+use indexmap::IndexMap;
 use internal_baml_diagnostics::{DatamodelError, DatamodelWarning, Span};
 use internal_baml_schema_ast::ast::{
-    Attribute, ValExpId, ValueExprBlock, WithIdentifier, WithName, WithSpan,
+    Attribute, Expression, ValExpId, ValueExprBlock, WithIdentifier, WithName, WithSpan,
 };
 use regex::Regex;
 use std::{collections::HashSet, ops::Deref};
 
 use crate::attributes::constraint::attribute_as_constraint;
+use crate::config_block::{ConfigProperties, Spanned};
 use crate::{coerce, coerce_array, coerce_expression::coerce_map, context::Context};
 
 use super::{
-    Attributes, ContantDelayStrategy, ExponentialBackoffStrategy, RetryPolicy, RetryPolicyStrategy,
+    Attributes, ContantDelayStrategy, ExponentialBackoffStrategy, PromptSuite, RetryPolicy,
+    RetryPolicyStrategy,
 };
 
 fn dedent(s: &str) -> String {
@@ -41,74 +44,99 @@ pub(crate) fn visit_retry_policy<'db>(
     config: &'db ValueExprBlock,
     ctx: &mut Context<'db>,
 ) {
-    let mut max_reties = None;
+    let mut properties = ConfigProperties::new(config, &["max_retries", "strategy", "options"]);
 
-    let mut strategy = Some(RetryPolicyStrategy::ConstantDelay(
-        super::ContantDelayStrategy { delay_ms: 200 },
-    ));
-    let mut options = None;
+    let max_retries = properties.require::<u32>("max_retries");
+    let strategy = properties
+        .get::<&Expression>("strategy")
+        .and_then(|val| {
+            let val = coerce_map(val, &coerce::string_with_span, ctx.diagnostics)?;
+            visit_strategy(properties.span_of("strategy"), val, ctx.diagnostics)
+        })
+        .unwrap_or(RetryPolicyStrategy::ConstantDelay(ContantDelayStrategy {
+            delay_ms: 200,
+        }));
+    let options = match properties
+        .get::<&Expression>("options")
+        .and_then(|val| val.to_unresolved_value(ctx.diagnostics))
+    {
+        Some(UnresolvedValue::<Span>::Map(kv, _)) => Some(kv),
+        Some(other) => {
+            properties.push_error(DatamodelError::new_validation_error(
+                "`options` must be a map",
+                other.meta().clone(),
+            ));
+            None
+        }
+        None => None,
+    };
 
-    config
-        .iter_fields()
-        .for_each(|(_idx, f)| match (f.name(), &f.expr) {
-            (name, None) => {
-                ctx.push_error(DatamodelError::new_config_property_missing_value_error(
+    for error in properties.into_errors() {
+        ctx.push_error(error);
+    }
+
+    if let Some(max_retries) = max_retries {
+        ctx.types.retry_policies.insert(
+            idx,
+            RetryPolicy {
+                max_retries,
+                strategy,
+                options,
+            },
+        );
+    }
+}
+
+pub(crate) fn visit_prompt_suite<'db>(
+    idx: ValExpId,
+    config: &'db ValueExprBlock,
+    ctx: &mut Context<'db>,
+) {
+    let mut properties = ConfigProperties::new(config, &["function", "variants"]);
+
+    let function = properties.require::<Spanned<'_, &str>>("function");
+    let mut variants = IndexMap::new();
+    let entries = properties
+        .require::<&Expression>("variants")
+        .and_then(|val| coerce_map(val, &coerce::string_with_span, ctx.diagnostics));
+    if let Some(entries) = entries {
+        if entries.is_empty() {
+            properties.push_error(DatamodelError::new_validation_error(
+                "A prompt suite needs at least one variant, e.g. `variants { terse #\"...\"# }`",
+                properties.span_of("variants").clone(),
+            ));
+        }
+        for ((name, name_span), template) in entries {
+            let Some(template) = coerce::template_string(template, ctx.diagnostics) else {
+                continue;
+            };
+            if variants.contains_key(name) {
+                properties.push_error(DatamodelError::new_duplicate_config_key_error(
+                    &format!("prompt_suite {}", config.name()),
                     name,
-                    config.name(),
-                    "retry_policy",
-                    f.identifier().span().clone(),
-                ))
+                    name_span.clone(),
+                ));
+                continue;
             }
-            ("max_retries", Some(val)) => {
-                if let Some(val) = coerce::integer(val, ctx.diagnostics) {
-                    max_reties = Some(val as u32)
-                }
-            }
-            ("strategy", Some(val)) => {
-                if let Some(val) = coerce_map(val, &coerce::string_with_span, ctx.diagnostics) {
-                    if let Some(val) = visit_strategy(f.span(), val, ctx.diagnostics) {
-                        strategy = Some(val)
-                    }
-                }
-            }
-            ("options", Some(val)) => match val.to_unresolved_value(ctx.diagnostics) {
-                Some(UnresolvedValue::<Span>::Map(kv, _)) => options = Some(kv),
-                Some(other) => {
-                    ctx.push_error(DatamodelError::new_validation_error(
-                        "`options` must be a map",
-                        other.meta().clone(),
-                    ));
-                }
-                None => {}
-            },
-            (name, Some(_)) => ctx.push_error(DatamodelError::new_property_not_known_error(
-                name,
-                f.identifier().span().clone(),
-                ["max_retries", "strategy", "options"].to_vec(),
-            )),
-        });
-    match (max_reties, strategy) {
-        (Some(max_retries), Some(strategy)) => {
-            ctx.types.retry_policies.insert(
-                idx,
-                RetryPolicy {
-                    max_retries,
-                    strategy,
-                    options,
-                },
+            variants.insert(
+                name.to_string(),
+                (template.value().to_string(), template.span().clone()),
             );
         }
-        (Some(_), None) => {
-            unreachable!("max_retries is set but strategy is not");
-        }
-        (None, Some(_)) => ctx.push_error(DatamodelError::new_validation_error(
-            "Missing `max_reties` property",
-            config.identifier().span().clone(),
-        )),
-        (None, None) => ctx.push_error(DatamodelError::new_validation_error(
-            "Missing `strategy` property",
-            config.identifier().span().clone(),
-        )),
+    }
+
+    for error in properties.into_errors() {
+        ctx.push_error(error);
+    }
+
+    if let Some(function) = function {
+        ctx.types.prompt_suites.insert(
+            idx,
+            PromptSuite {
+                function: (function.value.to_string(), function.span.clone()),
+                variants,
+            },
+        );
     }
 }
 
@@ -268,7 +296,17 @@ pub(crate) fn visit_test_case<'db>(
             for error in errors {
                 ctx.push_error(error);
             }
-            maybe_constraint
+            maybe_constraint.map(|(constraint, span, expr_span)| {
+                let expression = ctx.resolve_constants(&constraint.expression);
+                (
+                    Constraint {
+                        expression,
+                        ..constraint
+                    },
+                    span,
+                    expr_span,
+                )
+            })
         })
         .collect();
 
@@ -3,6 +3,7 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use std::hash::Hash;
 use std::ops::Deref;
 
+use crate::config_block::{ConfigProperties, Spanned};
 use crate::types::configurations::visit_test_case;
 use crate::{coerce, ParserDatabase, Tarjan};
 use crate::{context::Context, DatamodelError};
@@ -22,11 +23,11 @@ mod configurations;
 mod prompt;
 mod types;
 
-pub use crate::attributes::Attributes;
+pub use crate::attributes::{Attributes, RenderOverrides};
 pub(crate) use types::EnumAttributes;
 pub(crate) use types::*;
 
-use self::configurations::visit_retry_policy;
+use self::configurations::{visit_prompt_suite, visit_retry_policy};
 
 pub(super) fn resolve_types(ctx: &mut Context<'_>) {
     for (top_id, top) in ctx.ast.iter_tops() {
@@ -66,6 +67,14 @@ pub(super) fn resolve_types(ctx: &mut Context<'_>) {
                 visit_test_case(idx, config, ctx);
             }
             (_, ast::Top::TestCase(_)) => unreachable!("TestCase misconfigured"),
+            (ast::TopId::PromptSuite(idx), ast::Top::PromptSuite(config)) => {
+                visit_prompt_suite(idx, config, ctx);
+            }
+            (_, ast::Top::PromptSuite(_)) => unreachable!("PromptSuite misconfigured"),
+            (ast::TopId::Constant(_), ast::Top::Constant(constant)) => {
+                visit_constant(constant, ctx);
+            }
+            (_, ast::Top::Constant(_)) => unreachable!("Constant misconfigured"),
 
             _ => {}
         }
@@ -178,6 +187,15 @@ pub struct TestCase {
     pub constraints: Vec<(Constraint, Span, Span)>,
 }
 
+/// Named prompt templates for one function, from a `prompt_suite` block.
+#[derive(Debug)]
+pub struct PromptSuite {
+    /// The function the variants are prompts for.
+    pub function: (String, Span),
+    /// The template of each variant, in declaration order.
+    pub variants: IndexMap<String, (String, Span)>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Printer {
     pub template: (String, Span),
@@ -254,6 +272,8 @@ pub struct TemplateStringProperties {
     pub type_dependencies: HashSet<String>,
     /// This is dedented and trimmed.
     pub template: String,
+    /// The default values of parameters, by parameter name.
+    pub defaults: IndexMap<String, UnresolvedValue<Span>>,
 }
 
 #[derive(Default)]
@@ -326,6 +346,7 @@ pub(super) struct Types {
     pub(super) client_properties: HashMap<ast::ValExpId, ClientProperties>,
     pub(super) retry_policies: HashMap<ast::ValExpId, RetryPolicy>,
     pub(super) test_cases: HashMap<ast::ValExpId, TestCase>,
+    pub(super) prompt_suites: HashMap<ast::ValExpId, PromptSuite>,
     pub(super) template_strings:
         HashMap<either::Either<ast::TemplateStringId, ast::ValExpId>, TemplateStringProperties>,
 }
@@ -351,6 +372,20 @@ fn visit_template_string<'db>(
                 .as_raw_string_value()
                 .map(|v| v.value().to_string())
                 .unwrap(),
+            defaults: template_string
+                .input()
+                .map(|input| {
+                    input
+                        .args
+                        .iter()
+                        .filter_map(|(name, arg)| {
+                            let default = arg.default.as_ref()?;
+                            let value = default.to_unresolved_value(ctx.diagnostics)?;
+                            Some((name.name().to_string(), value))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
         },
     );
 }
@@ -591,6 +626,24 @@ fn visit_type_alias<'db>(
     }
 }
 
+/// Constants are substituted into attributes and constraints, so they can only stand for a
+/// number, a string or a boolean.
+fn visit_constant(constant: &ast::Constant, ctx: &mut Context<'_>) {
+    match &constant.value {
+        ast::Expression::NumericValue(..)
+        | ast::Expression::BoolValue(..)
+        | ast::Expression::StringValue(..)
+        | ast::Expression::RawStringValue(_) => {}
+        other => ctx.push_error(DatamodelError::new_validation_error(
+            &format!(
+                "const `{}` must be a number, a quoted string or a boolean",
+                constant.name()
+            ),
+            other.span().clone(),
+        )),
+    }
+}
+
 fn visit_function<'db>(idx: ValExpId, function: &'db ast::ValueExprBlock, ctx: &mut Context<'db>) {
     let input_deps = function
         .input()
@@ -657,6 +710,7 @@ fn visit_function<'db>(idx: ValExpId, function: &'db ast::ValueExprBlock, ctx: &
                     name: None,
                     type_dependencies: input_deps,
                     template: prompt.value().to_string(),
+                    defaults: IndexMap::new(),
                 },
             );
         }
@@ -682,80 +736,61 @@ fn visit_function<'db>(idx: ValExpId, function: &'db ast::ValueExprBlock, ctx: &
 }
 
 fn visit_client<'db>(idx: ValExpId, client: &'db ast::ValueExprBlock, ctx: &mut Context<'db>) {
-    let mut provider = None;
-    let mut retry_policy = None;
-    let mut options = None;
-    client
-        .iter_fields()
-        .for_each(|(_idx, field)| match field.name() {
-            "provider" => {
-                match field
-                    .expr
-                    .as_ref()
-                    .and_then(|e| e.to_unresolved_value(ctx.diagnostics))
-                {
-                    Some(e) => match e.as_static_str() {
-                        Ok(s) => match s.parse::<ClientProvider>() {
-                            Ok(p) => provider = Some((p, e.meta().clone())),
-                            Err(err) => {
-                                ctx.push_error(DatamodelError::not_found_error(
-                                    "client provider",
-                                    s,
-                                    e.meta().clone(),
-                                    ClientProvider::allowed_providers()
-                                        .iter()
-                                        .map(|v| v.to_string())
-                                        .collect(),
-                                    false,
-                                ));
-                            }
-                        },
-                        Err(err) => ctx.push_error(DatamodelError::new_validation_error(
-                            &format!("`provider` value error: {err}"),
-                            e.meta().clone(),
-                        )),
-                    },
-                    None => ctx.push_error(DatamodelError::new_validation_error(
-                        "Missing `provider` field in client. e.g. `provider \"openai\"`",
-                        field.span().clone(),
-                    )),
-                }
-            }
-            "retry_policy" => retry_policy = field.expr.as_ref(),
-            "options" => {
-                match field
-                    .expr
-                    .as_ref()
-                    .and_then(|e| e.to_unresolved_value(ctx.diagnostics))
-                {
-                    Some(UnresolvedValue::Map(kv, _)) => {
-                        options = Some((kv, field.identifier().span().clone()));
-                    }
-                    Some(v) => {
-                        ctx.push_error(DatamodelError::new_validation_error(
-                            &format!("Expected a key-value pair, but got a: {}", v.r#type()),
-                            v.meta().clone(),
-                        ));
-                    }
-                    None => {}
+    let mut properties = ConfigProperties::new(client, &["provider", "retry_policy", "options"]);
+
+    let provider = match properties
+        .get::<&ast::Expression>("provider")
+        .and_then(|e| e.to_unresolved_value(ctx.diagnostics))
+    {
+        Some(e) => match e.as_static_str() {
+            Ok(s) => match s.parse::<ClientProvider>() {
+                Ok(p) => Some((p, e.meta().clone())),
+                Err(_) => {
+                    properties.push_error(DatamodelError::not_found_error(
+                        "client provider",
+                        s,
+                        e.meta().clone(),
+                        ClientProvider::allowed_providers()
+                            .iter()
+                            .map(|v| v.to_string())
+                            .collect(),
+                        false,
+                    ));
+                    None
                 }
-            }
-            config => ctx.push_error(DatamodelError::new_validation_error(
-                &format!("Unknown field `{}` in client", config),
-                field.span().clone(),
-            )),
-        });
-
-    let retry_policy = match retry_policy {
-        Some(retry_policy) => match coerce::string_with_span(retry_policy, ctx.diagnostics) {
-            Some((retry_policy, span)) => Some((retry_policy.to_string(), span.clone())),
-            _ => {
-                // Errors are handled by coerce.
+            },
+            Err(err) => {
+                properties.push_error(DatamodelError::new_validation_error(
+                    &format!("`provider` value error: {err}"),
+                    e.meta().clone(),
+                ));
                 None
             }
         },
         None => None,
     };
+    let retry_policy = properties
+        .get::<Spanned<'_, &str>>("retry_policy")
+        .map(|retry_policy| (retry_policy.value.to_string(), retry_policy.span.clone()));
+    let options = match properties
+        .get::<&ast::Expression>("options")
+        .and_then(|e| e.to_unresolved_value(ctx.diagnostics))
+    {
+        Some(UnresolvedValue::Map(kv, _)) => Some((kv, properties.span_of("options").clone())),
+        Some(v) => {
+            properties.push_error(DatamodelError::new_validation_error(
+                &format!("Expected a key-value pair, but got a: {}", v.r#type()),
+                v.meta().clone(),
+            ));
+            None
+        }
+        None => None,
+    };
+
+    let has_provider = properties.contains("provider");
+    for error in properties.into_errors() {
+        ctx.push_error(error);
+    }
 
     match provider {
         Some(provider) => {
@@ -784,10 +819,11 @@ fn visit_client<'db>(idx: ValExpId, client: &'db ast::ValueExprBlock, ctx: &mut
                 }
             }
         }
-        None => ctx.push_error(DatamodelError::new_validation_error(
+        None if !has_provider => ctx.push_error(DatamodelError::new_validation_error(
             "Missing `provider` field in client. e.g. `provider openai`",
             client.span().clone(),
         )),
+        None => {}
     }
 }
 
@@ -31,6 +31,11 @@ impl<'db> TypeAliasWalker<'db> {
         &self.db.types.resolved_type_aliases[&self.id]
     }
 
+    /// Whether the alias is part of a cycle, like `type Json = int | Json[]`.
+    pub fn is_recursive(&self) -> bool {
+        self.db.is_recursive_type_alias(&self.id)
+    }
+
     /// Add to Jinja types.
     pub fn add_to_types(self, types: &mut internal_baml_jinja_types::PredefinedTypes) {
         types.add_alias(self.name(), self.db.to_jinja_type(&self.target()))
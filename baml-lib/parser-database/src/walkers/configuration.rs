@@ -1,13 +1,13 @@
 use internal_baml_schema_ast::ast::{self, WithIdentifier, WithSpan};
 
-use crate::types::{RetryPolicy, TestCase};
+use crate::types::{PromptSuite, RetryPolicy, TestCase};
 
 /// A `class` declaration in the Prisma schema.
 pub type ConfigurationWalker<'db> = super::Walker<'db, (ast::ValExpId, &'static str)>;
 
-impl ConfigurationWalker<'_> {
+impl<'db> ConfigurationWalker<'db> {
     /// Get the AST node for this class.
-    pub fn ast_node(&self) -> &ast::ValueExprBlock {
+    pub fn ast_node(&self) -> &'db ast::ValueExprBlock {
         &self.db.ast[self.id.0]
     }
 
@@ -22,6 +22,12 @@ impl ConfigurationWalker<'_> {
         assert!(self.id.1 == "test");
         &self.db.types.test_cases[&self.id.0]
     }
+
+    /// Get as a prompt suite.
+    pub fn prompt_suite(&self) -> &'db PromptSuite {
+        assert!(self.id.1 == "prompt_suite");
+        &self.db.types.prompt_suites[&self.id.0]
+    }
 }
 
 impl WithIdentifier for ConfigurationWalker<'_> {
@@ -0,0 +1,40 @@
+use internal_baml_jinja_types::Type;
+use internal_baml_schema_ast::ast::{self, Expression, Span, WithIdentifier, WithName, WithSpan};
+
+use super::Walker;
+
+/// A `const` declaration in the schema.
+pub type ConstantWalker<'db> = Walker<'db, ast::ConstantId>;
+
+impl<'db> ConstantWalker<'db> {
+    /// The AST node.
+    pub fn ast_node(self) -> &'db ast::Constant {
+        &self.db.ast()[self.id]
+    }
+
+    /// Name of the constant.
+    pub fn name(self) -> &'db str {
+        self.ast_node().name()
+    }
+
+    /// Identifier span.
+    pub fn span(self) -> &'db Span {
+        self.ast_node().identifier().span()
+    }
+
+    /// The value the constant stands for.
+    pub fn value(self) -> &'db Expression {
+        self.ast_node().value()
+    }
+
+    /// The Jinja type of the value, for type checking the expressions that use it.
+    pub fn jinja_type(self) -> Type {
+        match self.value() {
+            Expression::BoolValue(..) => Type::Bool,
+            Expression::NumericValue(n, _) if n.parse::<i64>().is_ok() => Type::Int,
+            Expression::NumericValue(..) => Type::Float,
+            Expression::StringValue(..) | Expression::RawStringValue(_) => Type::String,
+            _ => Type::Unknown,
+        }
+    }
+}
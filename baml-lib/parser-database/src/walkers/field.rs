@@ -1,4 +1,5 @@
-use crate::types::Attributes;
+use crate::{attributes::constraint::type_constraints, types::Attributes};
+use baml_types::Constraint;
 
 use super::{ClassWalker, Walker};
 
@@ -40,6 +41,14 @@ impl<'db> FieldWalker<'db> {
         result
     }
 
+    /// The `@check` and `@assert` constraints on the field.
+    pub fn get_constraints(&self) -> Vec<Constraint> {
+        self.r#type()
+            .as_ref()
+            .map(|field_type| type_constraints(field_type, self.db))
+            .unwrap_or_default()
+    }
+
     /// The field's docstring.
     pub fn get_documentation(&self) -> Option<String> {
         self.ast_field()
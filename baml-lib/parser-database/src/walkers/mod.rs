@@ -10,25 +10,29 @@ mod alias;
 mod r#class;
 mod client;
 mod configuration;
+mod constant;
 mod r#enum;
 mod field;
 mod function;
 mod template_string;
 
 pub use alias::TypeAliasWalker;
-use baml_types::TypeValue;
+use baml_types::{JinjaExpression, TypeValue};
 pub use client::*;
 pub use configuration::*;
+pub use constant::ConstantWalker;
 use either::Either;
 pub use field::*;
 pub use function::FunctionWalker;
 use internal_baml_schema_ast::ast::{
-    FieldType, Identifier, TopId, TypeAliasId, TypeExpId, WithName,
+    FieldType, Identifier, SubType, TopId, TypeAliasId, TypeExpId, WithName,
 };
 pub use r#class::*;
 pub use r#enum::*;
 pub use template_string::TemplateStringWalker;
 
+use crate::attributes::constraint::substitute_constants;
+
 /// A generic walker. Only walkers intantiated with a concrete ID type (`I`) are useful.
 #[derive(Clone, Copy)]
 pub struct Walker<'db, I> {
@@ -91,6 +95,12 @@ impl<'db> crate::ParserDatabase {
         })
     }
 
+    /// Find a type by the id of its interned name, see
+    /// [`lookup_string`](crate::ParserDatabase::lookup_string).
+    pub fn find_type_by_id(&'db self, name: crate::StringId) -> Option<TypeWalker<'db>> {
+        self.find_type_by_str(self.resolve_string(name)?)
+    }
+
     /// Find a type by name.
     pub fn find_type(&'db self, idn: &Identifier) -> Option<TypeWalker<'db>> {
         match idn {
@@ -139,6 +149,29 @@ impl<'db> crate::ParserDatabase {
             .map(|model_id| self.walk((model_id, "retry_policy")))
     }
 
+    /// Find a prompt suite by name.
+    pub fn find_prompt_suite(&'db self, name: &str) -> Option<ConfigurationWalker<'db>> {
+        self.interner
+            .lookup(name)
+            .and_then(|name_id| self.names.tops.get(&name_id))
+            .and_then(|top_id| top_id.as_prompt_suite_id())
+            .map(|model_id| self.walk((model_id, "prompt_suite")))
+    }
+
+    /// Find a constant by name.
+    pub fn find_constant(&'db self, name: &str) -> Option<ConstantWalker<'db>> {
+        self.find_top_by_str(name)
+            .and_then(|top_id| top_id.as_constant_id())
+            .map(|constant_id| self.walk(constant_id))
+    }
+
+    /// `expression` with the constants it uses replaced by their values.
+    pub fn resolve_constants(&'db self, expression: &JinjaExpression) -> JinjaExpression {
+        substitute_constants(expression, |name| {
+            self.find_constant(name).map(|constant| constant.value())
+        })
+    }
+
     /// Returns a set of all classes that are part of some recursive definition.
     pub fn finite_recursive_cycles(&self) -> &[Vec<TypeExpId>] {
         &self.types.finite_recursive_cycles
@@ -177,7 +210,7 @@ impl<'db> crate::ParserDatabase {
         names.extend(self.walk_enums().map(|e| e.name().to_string()));
         // Add primitive types
         names.extend(
-            vec!["string", "int", "float", "bool", "true", "false"]
+            vec!["string", "int", "float", "bool", "bytes", "true", "false"]
                 .into_iter()
                 .map(String::from),
         );
@@ -236,6 +269,14 @@ impl<'db> crate::ParserDatabase {
             })
     }
 
+    /// Walk the recursive type aliases, one group per cycle of aliases that refer to each
+    /// other (a strongly connected component).
+    pub fn walk_recursive_alias_cycles(&self) -> impl Iterator<Item = Vec<TypeAliasWalker<'_>>> {
+        self.recursive_alias_cycles()
+            .iter()
+            .map(move |cycle| cycle.iter().map(|id| self.walk(*id)).collect())
+    }
+
     /// Walk all template strings in the schema.
     pub fn walk_templates(&self) -> impl Iterator<Item = TemplateStringWalker<'_>> {
         self.ast()
@@ -291,6 +332,71 @@ impl<'db> crate::ParserDatabase {
             })
     }
 
+    /// Walk all prompt suites in the schema.
+    pub fn walk_prompt_suites(&self) -> impl Iterator<Item = ConfigurationWalker<'_>> {
+        self.ast()
+            .iter_tops()
+            .filter_map(|(top_id, _)| top_id.as_prompt_suite_id())
+            .map(move |top_id| Walker {
+                db: self,
+                id: (top_id, "prompt_suite"),
+            })
+    }
+
+    /// Walk all constants in the schema.
+    pub fn walk_constants(&self) -> impl Iterator<Item = ConstantWalker<'_>> {
+        self.ast()
+            .iter_tops()
+            .filter_map(|(top_id, _)| top_id.as_constant_id())
+            .map(move |constant_id| self.walk(constant_id))
+    }
+
+    /// Walk the classes and enums that carry the block attribute `name`, see
+    /// [`Attributes::has`](crate::types::Attributes::has) for attribute names.
+    pub fn types_with_attribute<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = TypeWalker<'a>> {
+        self.ast()
+            .iter_tops()
+            .filter_map(move |(top_id, _)| match top_id {
+                TopId::Class(id) => {
+                    let class = self.walk(id);
+                    class
+                        .get_default_attributes(SubType::Class)
+                        .is_some_and(|a| a.has(name))
+                        .then_some(TypeWalker::Class(class))
+                }
+                TopId::Enum(id) => {
+                    let enm = self.walk(id);
+                    enm.get_default_attributes(SubType::Enum)
+                        .is_some_and(|a| a.has(name))
+                        .then_some(TypeWalker::Enum(enm))
+                }
+                _ => None,
+            })
+    }
+
+    /// Walk the class fields that carry the attribute `name`.
+    pub fn fields_with_attribute<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = FieldWalker<'a>> {
+        self.walk_classes()
+            .flat_map(|class| class.static_fields())
+            .filter(move |field| field.get_default_attributes().is_some_and(|a| a.has(name)))
+    }
+
+    /// Walk the enum values that carry the attribute `name`.
+    pub fn enum_values_with_attribute<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = EnumValueWalker<'a>> {
+        self.walk_enums()
+            .flat_map(|enm| enm.values())
+            .filter(move |value| value.get_default_attributes().is_some_and(|a| a.has(name)))
+    }
+
     /// Convert a field type to a `Type`.
     pub fn to_jinja_type(&self, ft: &FieldType) -> internal_baml_jinja_types::Type {
         use internal_baml_jinja_types::Type;
@@ -359,7 +465,7 @@ impl<'db> crate::ParserDatabase {
                     TypeValue::Float => Type::Float,
                     TypeValue::Bool => Type::Bool,
                     TypeValue::Null => Type::None,
-                    TypeValue::Media(_) => Type::Unknown,
+                    TypeValue::Bytes | TypeValue::Media(_) => Type::Unknown,
                 };
                 if arity.is_optional() || matches!(t, Type::None) {
                     t = Type::None | t;
@@ -1,4 +1,8 @@
+use std::collections::HashSet;
+
+use baml_types::UnresolvedValue;
 use either::Either;
+use indexmap::IndexMap;
 use internal_baml_jinja_types::{PredefinedTypes, Type};
 use internal_baml_schema_ast::ast::{
     self, ArgumentId, BlockArgs, Span, WithIdentifier, WithName, WithSpan,
@@ -31,6 +35,11 @@ impl<'db> TemplateStringWalker<'db> {
         &self.metadata().template
     }
 
+    /// The default values of the parameters that have one, by parameter name.
+    pub fn defaults(self) -> &'db IndexMap<String, UnresolvedValue<Span>> {
+        &self.metadata().defaults
+    }
+
     /// Walk the input arguments of the template string.
     pub fn walk_input_args(self) -> impl ExactSizeIterator<Item = ArgWalker<'db>> {
         match self.ast_node().input() {
@@ -53,9 +62,13 @@ impl<'db> TemplateStringWalker<'db> {
         let name = self.name();
         let ret_type = Type::String;
         let mut params = vec![];
+        let mut defaulted = HashSet::new();
 
         if let Some(p) = self.ast_node().input() {
             p.args.iter().for_each(|(name, t)| {
+                if t.default.is_some() {
+                    defaulted.insert(name.name().to_string());
+                }
                 params.push((
                     name.name().to_string(),
                     self.db.to_jinja_type(&t.field_type),
@@ -63,7 +76,7 @@ impl<'db> TemplateStringWalker<'db> {
             });
         }
 
-        types.add_function(name, ret_type, params);
+        types.add_function_with_defaults(name, ret_type, params, defaulted);
     }
 }
 
@@ -108,4 +121,9 @@ impl<'db> ArgWalker<'db> {
     pub fn is_optional(self) -> bool {
         self.field_type().is_optional()
     }
+
+    /// The default value of the argument, as in `n: int = 3`.
+    pub fn default(self) -> Option<&'db ast::Expression> {
+        self.ast_arg().1.default.as_ref()
+    }
 }
@@ -4,6 +4,7 @@ mod attribute;
 
 mod comment;
 mod config;
+mod constant;
 
 mod expression;
 mod field;
@@ -15,6 +16,7 @@ mod newline_type;
 mod template_string;
 mod top;
 mod traits;
+mod trivia;
 mod type_expression_block;
 mod value_expression_block;
 pub(crate) use self::comment::Comment;
@@ -23,6 +25,7 @@ pub use argument::{Argument, ArgumentId, ArgumentsList};
 pub use assignment::Assignment;
 pub use attribute::{Attribute, AttributeContainer, AttributeId};
 pub use config::ConfigBlockProperty;
+pub use constant::Constant;
 pub use expression::{Expression, RawString};
 pub use field::{Field, FieldArity, FieldType};
 pub use identifier::{Identifier, RefIdentifier};
@@ -32,6 +35,7 @@ pub use newline_type::NewlineType;
 pub use template_string::TemplateString;
 pub use top::Top;
 pub use traits::{WithAttributes, WithDocumentation, WithIdentifier, WithName, WithSpan};
+pub use trivia::{NodeTrivia, Trivia, TriviaKind};
 pub use type_expression_block::{FieldId, SubType, TypeExpressionBlock};
 pub use value_expression_block::{BlockArg, BlockArgs, ValueExprBlock, ValueExprBlockType};
 
@@ -45,10 +49,12 @@ pub use value_expression_block::{BlockArg, BlockArgs, ValueExprBlock, ValueExprB
 /// node is annotated with its location in the text representation.
 /// Basically, the AST is an object oriented representation of the datamodel's
 /// text. Schema = Datamodel + Generators + Datasources
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SchemaAst {
     /// All models, enums, composite types, datasources, generators and type aliases.
     pub tops: Vec<Top>,
+    /// All comments and blank lines, in source order within each file.
+    pub trivia: Vec<Trivia>,
 }
 
 impl Default for SchemaAst {
@@ -59,7 +65,10 @@ impl Default for SchemaAst {
 
 impl SchemaAst {
     pub fn new() -> Self {
-        SchemaAst { tops: Vec::new() }
+        SchemaAst {
+            tops: Vec::new(),
+            trivia: Vec::new(),
+        }
     }
 
     /// Iterate over all the top-level items in the schema.
@@ -143,6 +152,17 @@ impl std::ops::Index<TemplateStringId> for SchemaAst {
     }
 }
 
+/// An opaque identifier for a constant in a schema AST.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ConstantId(u32);
+impl std::ops::Index<ConstantId> for SchemaAst {
+    type Output = Constant;
+
+    fn index(&self, index: ConstantId) -> &Self::Output {
+        self.tops[index.0 as usize].as_constant().unwrap()
+    }
+}
+
 /// An identifier for a top-level item in a schema AST. Use the `schema[top_id]`
 /// syntax to resolve the id to an `ast::Top`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -172,6 +192,12 @@ pub enum TopId {
     TestCase(ValExpId),
 
     RetryPolicy(ValExpId),
+
+    /// A prompt suite.
+    PromptSuite(ValExpId),
+
+    /// A named constant.
+    Constant(ConstantId),
 }
 
 impl TopId {
@@ -234,6 +260,21 @@ impl TopId {
             _ => None,
         }
     }
+
+    pub fn as_prompt_suite_id(self) -> Option<ValExpId> {
+        match self {
+            TopId::PromptSuite(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Try to interpret the top as a constant.
+    pub fn as_constant_id(self) -> Option<ConstantId> {
+        match self {
+            TopId::Constant(id) => Some(id),
+            _ => None,
+        }
+    }
 }
 
 impl std::ops::Index<TopId> for SchemaAst {
@@ -250,6 +291,8 @@ impl std::ops::Index<TopId> for SchemaAst {
             TopId::Generator(ValExpId(idx)) => idx,
             TopId::TestCase(ValExpId(idx)) => idx,
             TopId::RetryPolicy(ValExpId(idx)) => idx,
+            TopId::PromptSuite(ValExpId(idx)) => idx,
+            TopId::Constant(ConstantId(idx)) => idx,
         };
 
         &self.tops[idx as usize]
@@ -267,5 +310,7 @@ fn top_idx_to_top_id(top_idx: usize, top: &Top) -> TopId {
         Top::Generator(_) => TopId::Generator(ValExpId(top_idx as u32)),
         Top::TestCase(_) => TopId::TestCase(ValExpId(top_idx as u32)),
         Top::RetryPolicy(_) => TopId::RetryPolicy(ValExpId(top_idx as u32)),
+        Top::PromptSuite(_) => TopId::PromptSuite(ValExpId(top_idx as u32)),
+        Top::Constant(_) => TopId::Constant(ConstantId(top_idx as u32)),
     }
 }
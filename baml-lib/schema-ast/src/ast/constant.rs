@@ -0,0 +1,42 @@
+use super::{Expression, Identifier, Span, WithIdentifier, WithSpan};
+
+/// A named constant. `const MAX_ITEMS = 10`.
+#[derive(Debug, Clone)]
+pub struct Constant {
+    /// The name of the constant.
+    ///
+    /// ```ignore
+    /// const MAX_ITEMS = 10
+    ///       ^^^^^^^^^
+    /// ```
+    pub identifier: Identifier,
+
+    /// The value the constant stands for.
+    ///
+    /// ```ignore
+    /// const MAX_ITEMS = 10
+    ///                   ^^
+    /// ```
+    pub value: Expression,
+
+    /// Span of the entire declaration.
+    pub span: Span,
+}
+
+impl Constant {
+    pub fn value(&self) -> &Expression {
+        &self.value
+    }
+}
+
+impl WithSpan for Constant {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+}
+
+impl WithIdentifier for Constant {
+    fn identifier(&self) -> &Identifier {
+        &self.identifier
+    }
+}
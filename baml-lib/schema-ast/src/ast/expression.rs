@@ -56,32 +56,52 @@ impl RawString {
         &self.raw_value
     }
 
+    /// The span in the source file of `span`, a span within [`value`](Self::value).
     pub fn to_raw_span(&self, span: pest::Span<'_>) -> Span {
-        let start_idx = span.start();
-        let end_idx = span.end();
-        // Count number of \n in the raw string before the start of the span.
-        let start_line_count = self.value()[..start_idx]
-            .lines()
-            .filter(|line| !line.trim().is_empty())
-            .count();
-        let end_line_count = self.value()[..end_idx]
-            .lines()
-            .filter(|line| !line.trim().is_empty())
-            .count();
-
         Span {
             file: self.raw_span.file.clone(),
-            start: self.raw_span.start
-                + self.inner_span_start
-                + self.indent * start_line_count
-                + span.start(),
-            end: self.raw_span.start
-                + self.inner_span_start
-                + self.indent * end_line_count
-                + span.end(),
+            start: self.to_raw_offset(span.start()),
+            end: self.to_raw_offset(span.end()),
         }
     }
 
+    /// The offset in the source file of the byte at `offset` in [`value`](Self::value).
+    ///
+    /// Offsets that are not on a character boundary are moved back to the start of their
+    /// character, so the result is always a character boundary in the source file.
+    pub fn to_raw_offset(&self, offset: usize) -> usize {
+        let mut offset = offset.min(self.inner_value.len());
+        while !self.inner_value.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &self.inner_value[..offset];
+        let line = before.matches('\n').count();
+        let column = offset - before.rfind('\n').map_or(0, |i| i + 1);
+
+        // `value()` has the raw lines, without the leading blank ones and with the common
+        // indentation removed, so its lines are the raw lines from the first non-blank one.
+        let raw = &self.raw_value[self.inner_span_start..];
+        let mut line_start = 0;
+        let mut lines = raw
+            .split('\n')
+            .map(|text| {
+                let start = line_start;
+                line_start += text.len() + 1;
+                (start, text.trim_end_matches('\r'))
+            })
+            .skip_while(|(_, text)| text.trim().is_empty());
+        let Some((start, text)) = lines.nth(line) else {
+            return self.raw_span.end;
+        };
+        // Blank lines are empty in `value()`, so all of their whitespace is skipped.
+        let prefix = if text.trim().is_empty() {
+            text.len()
+        } else {
+            self.indent.min(text.len())
+        };
+        self.raw_span.start + self.inner_span_start + start + prefix + column
+    }
+
     pub fn assert_eq_up_to_span(&self, other: &RawString) {
         assert_eq!(self.inner_value, other.inner_value);
         assert_eq!(self.raw_value, other.raw_value);
@@ -403,3 +423,73 @@ impl Expression {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use internal_baml_diagnostics::SourceFile;
+
+    use super::*;
+
+    fn raw_string(source: &'static str) -> RawString {
+        let start = source.find("#\"").unwrap() + 2;
+        let end = source.rfind("\"#").unwrap();
+        let file = SourceFile::new_static("test.baml".into(), source);
+        RawString::new(
+            source[start..end].to_string(),
+            Span::new(file, start, end),
+            None,
+        )
+    }
+
+    #[track_caller]
+    fn assert_offsets_map_to_same_chars(source: &str, raw: &RawString) {
+        for (offset, ch) in raw.value().char_indices() {
+            let raw_offset = raw.to_raw_offset(offset);
+            assert!(
+                source[raw_offset..].starts_with(ch),
+                "{ch:?} at {offset} maps to {:?}",
+                &source[raw_offset..]
+            );
+        }
+    }
+
+    #[test]
+    fn raw_spans_with_multibyte_text() {
+        let source = "function Greet() -> string {\n  prompt #\"\n    你好, {{ name }} 👋\n      \n    Ünïcödé 🎉🎉\n      indented ✓\n  \"#\n}\n";
+        let raw = raw_string(source);
+        assert_eq!(
+            raw.value(),
+            "你好, {{ name }} 👋\n\nÜnïcödé 🎉🎉\n  indented ✓"
+        );
+        assert_offsets_map_to_same_chars(source, &raw);
+
+        for needle in ["{{ name }}", "👋", "Ünïcödé 🎉🎉", "indented ✓"] {
+            let start = raw.value().find(needle).unwrap();
+            let span = pest::Span::new(raw.value(), start, start + needle.len()).unwrap();
+            let span = raw.to_raw_span(span);
+            assert_eq!(&source[span.start..span.end], needle);
+        }
+    }
+
+    #[test]
+    fn raw_offsets_snap_to_char_boundaries() {
+        let source = "template_string T #\"\n  日本語\n\"#\n";
+        let raw = raw_string(source);
+        assert_offsets_map_to_same_chars(source, &raw);
+        // The middle of `本` maps to its start.
+        assert_eq!(raw.to_raw_offset(4), raw.to_raw_offset(3));
+        assert_eq!(&source[raw.to_raw_offset(4)..][..3], "本");
+        assert_eq!(
+            raw.to_raw_offset(raw.value().len()),
+            source.find("語").unwrap() + 3
+        );
+    }
+
+    #[test]
+    fn raw_offsets_on_single_line() {
+        let source = "class A {\n  a string @description(#\"héllo 🌍\"#)\n}\n";
+        let raw = raw_string(source);
+        assert_eq!(raw.value(), "héllo 🌍");
+        assert_offsets_map_to_same_chars(source, &raw);
+    }
+}
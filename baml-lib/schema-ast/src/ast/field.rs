@@ -188,22 +188,48 @@ impl FieldType {
 
     // All the identifiers used in this type.
     pub fn flat_idns(&self) -> Vec<&Identifier> {
-        match self {
-            FieldType::Symbol(_, idn, ..) => {
-                vec![&idn]
+        // Walked with an explicit stack so that deeply nested types cannot overflow the call
+        // stack. Children are pushed in reverse to keep the recursive order.
+        let mut idns = vec![];
+        let mut stack = vec![self];
+        while let Some(field_type) = stack.pop() {
+            match field_type {
+                FieldType::Symbol(_, idn, ..) => idns.push(idn),
+                FieldType::Union(_, f, _, _) | FieldType::Tuple(_, f, ..) => {
+                    stack.extend(f.iter().rev())
+                }
+                FieldType::Map(_, kv, ..) => {
+                    stack.push(&kv.0);
+                    stack.push(&kv.1);
+                }
+                FieldType::List(_, t, ..) => stack.push(t),
+                FieldType::Primitive(..) => {}
+                FieldType::Literal(..) => {}
             }
+        }
+        idns
+    }
 
-            FieldType::Union(_, f, _, _) => f.iter().flat_map(|t| t.flat_idns()).collect(),
-            FieldType::Tuple(_, f, ..) => f.iter().flat_map(|t| t.flat_idns()).collect(),
-            FieldType::Map(_, kv, ..) => {
-                let mut idns = kv.1.flat_idns();
-                idns.extend(kv.0.flat_idns());
-                idns
+    /// How deeply the type nests: 1 for a primitive, symbol or literal, plus one per list
+    /// dimension, map, tuple or union around it. Computed without recursion.
+    pub fn depth(&self) -> usize {
+        let mut deepest = 0;
+        let mut stack = vec![(self, 1)];
+        while let Some((field_type, depth)) = stack.pop() {
+            deepest = deepest.max(depth);
+            match field_type {
+                FieldType::Union(_, f, ..) | FieldType::Tuple(_, f, ..) => {
+                    stack.extend(f.iter().map(|t| (t, depth + 1)))
+                }
+                FieldType::Map(_, kv, ..) => {
+                    stack.push((&kv.0, depth + 1));
+                    stack.push((&kv.1, depth + 1));
+                }
+                FieldType::List(_, t, dims, ..) => stack.push((t, depth + *dims as usize)),
+                FieldType::Symbol(..) | FieldType::Primitive(..) | FieldType::Literal(..) => {}
             }
-            FieldType::List(_, t, ..) => t.flat_idns(),
-            FieldType::Primitive(..) => vec![],
-            FieldType::Literal(..) => vec![],
         }
+        deepest
     }
 
     pub fn attributes(&self) -> &[Attribute] {
@@ -1,5 +1,5 @@
 use super::{
-    assignment::Assignment, traits::WithSpan, Identifier, Span, TemplateString,
+    assignment::Assignment, traits::WithSpan, Constant, Identifier, Span, TemplateString,
     TypeExpressionBlock, ValueExprBlock, WithIdentifier,
 };
 
@@ -26,6 +26,12 @@ pub enum Top {
     TestCase(ValueExprBlock),
 
     RetryPolicy(ValueExprBlock),
+
+    /// Named prompt variants of a function.
+    PromptSuite(ValueExprBlock),
+
+    /// A named constant.
+    Constant(Constant),
 }
 
 impl Top {
@@ -42,6 +48,8 @@ impl Top {
             Top::Generator(_) => "generator",
             Top::TestCase(_) => "test_case",
             Top::RetryPolicy(_) => "retry_policy",
+            Top::PromptSuite(_) => "prompt_suite",
+            Top::Constant(_) => "const",
         }
     }
 
@@ -61,6 +69,7 @@ impl Top {
             Top::Generator(gen) => Some(gen),
             Top::TestCase(test) => Some(test),
             Top::RetryPolicy(retry) => Some(retry),
+            Top::PromptSuite(suite) => Some(suite),
             _ => None,
         }
     }
@@ -78,6 +87,13 @@ impl Top {
             _ => None,
         }
     }
+
+    pub fn as_constant(&self) -> Option<&Constant> {
+        match self {
+            Top::Constant(constant) => Some(constant),
+            _ => None,
+        }
+    }
 }
 
 impl WithIdentifier for Top {
@@ -94,6 +110,8 @@ impl WithIdentifier for Top {
             Top::Generator(x) => x.identifier(),
             Top::TestCase(x) => x.identifier(),
             Top::RetryPolicy(x) => x.identifier(),
+            Top::PromptSuite(x) => x.identifier(),
+            Top::Constant(x) => x.identifier(),
         }
     }
 }
@@ -110,6 +128,8 @@ impl WithSpan for Top {
             Top::Generator(gen) => gen.span(),
             Top::TestCase(test) => test.span(),
             Top::RetryPolicy(retry) => retry.span(),
+            Top::PromptSuite(suite) => suite.span(),
+            Top::Constant(constant) => constant.span(),
         }
     }
 }
@@ -0,0 +1,107 @@
+use super::{SchemaAst, Span};
+
+/// Source text that does not change what the schema means: comments and blank lines.
+///
+/// Every comment in the schema is kept as trivia, not just doc comments, so tools that edit
+/// or reprint the source can put them back where they were.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    /// For comments, from the `//` (or `{//`) to the end of the comment, without the newline.
+    /// For blank lines, the whole lines, including their newlines.
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriviaKind {
+    /// `// ...`
+    Comment,
+    /// `/// ...`
+    DocComment,
+    /// `{// ... //}`
+    BlockComment,
+    /// This many consecutive lines with nothing but whitespace.
+    BlankLines(usize),
+}
+
+impl Trivia {
+    /// The trivia's source text.
+    pub fn text(&self) -> &str {
+        &self.span.file.as_str()[self.span.start..self.span.end]
+    }
+
+    pub fn is_comment(&self) -> bool {
+        !matches!(self.kind, TriviaKind::BlankLines(_))
+    }
+
+    /// Whether nothing but whitespace precedes the trivia on its line.
+    fn starts_line(&self) -> bool {
+        let source = self.span.file.as_str();
+        let line_start = source[..self.span.start].rfind('\n').map_or(0, |i| i + 1);
+        source[line_start..self.span.start].trim().is_empty()
+    }
+}
+
+/// The trivia attached to a node, see [`SchemaAst::trivia_of`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct NodeTrivia<'a> {
+    /// The comments and blank lines directly above the node, in source order. The comments
+    /// that belong to the node are the ones after the last blank lines.
+    pub leading: Vec<&'a Trivia>,
+    /// A comment after the node, on its last line.
+    pub trailing: Option<&'a Trivia>,
+}
+
+impl NodeTrivia<'_> {
+    /// The leading comments not separated from the node by a blank line.
+    pub fn attached_comments(&self) -> &[&Trivia] {
+        let first = self
+            .leading
+            .iter()
+            .rposition(|t| !t.is_comment())
+            .map_or(0, |i| i + 1);
+        &self.leading[first..]
+    }
+}
+
+impl SchemaAst {
+    /// The trivia around the node at `span`.
+    ///
+    /// Leading trivia are the comments on their own lines and the blank lines right above the
+    /// node, up to the previous code. The trailing comment is one on the node's last line.
+    pub fn trivia_of(&self, span: &Span) -> NodeTrivia<'_> {
+        let in_file = |t: &&Trivia| t.span.file == span.file;
+        let source = span.file.as_str();
+
+        let mut leading = Vec::new();
+        let mut position = span.start;
+        for trivia in self
+            .trivia
+            .iter()
+            .filter(in_file)
+            .rev()
+            .skip_while(|t| t.span.end > span.start)
+        {
+            let gap = &source[trivia.span.end..position];
+            let adjacent = gap.trim().is_empty() && gap.matches('\n').count() <= 1;
+            if !adjacent || !trivia.starts_line() {
+                break;
+            }
+            leading.push(trivia);
+            position = trivia.span.start;
+        }
+        leading.reverse();
+
+        let end = span.start + source[span.start..span.end].trim_end().len();
+        let last_line = source[..end].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[end..].find('\n').map_or(source.len(), |i| end + i);
+        let trailing = self.trivia.iter().filter(in_file).find(|t| {
+            t.is_comment()
+                && t.span.start >= span.start.max(last_line)
+                && t.span.start < line_end
+                && !t.starts_line()
+        });
+
+        NodeTrivia { leading, trailing }
+    }
+}
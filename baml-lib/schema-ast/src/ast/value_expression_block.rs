@@ -48,6 +48,9 @@ pub struct BlockArg {
     /// The field's type.
     pub field_type: FieldType,
 
+    /// The default value, as in `n: int = 3`.
+    pub default: Option<Expression>,
+
     /// The location of this field in the text representation.
     pub(crate) span: Span,
 }
@@ -71,6 +74,7 @@ pub enum ValueExprBlockType {
     Generator,
     RetryPolicy,
     Test,
+    PromptSuite,
 }
 
 impl Display for ValueExprBlockType {
@@ -81,6 +85,7 @@ impl Display for ValueExprBlockType {
             ValueExprBlockType::Generator => write!(f, "generator"),
             ValueExprBlockType::RetryPolicy => write!(f, "retry_policy"),
             ValueExprBlockType::Test => write!(f, "test"),
+            ValueExprBlockType::PromptSuite => write!(f, "prompt_suite"),
         }
     }
 }
@@ -104,7 +109,7 @@ impl BlockArgs {
 }
 
 /// A block declaration.
-/// A complete Function, Client, Generator, Test, or RetryPolicy.
+/// A complete Function, Client, Generator, Test, RetryPolicy or PromptSuite.
 #[derive(Debug, Clone)]
 pub struct ValueExprBlock {
     /// The name of the block.
@@ -192,6 +197,7 @@ impl ValueExprBlock {
             ValueExprBlockType::Client => "client",
             ValueExprBlockType::Generator => "generator",
             ValueExprBlockType::Test => "test",
+            ValueExprBlockType::PromptSuite => "prompt_suite",
         }
     }
 }
@@ -0,0 +1,602 @@
+//! Building BAML schemas in code instead of with string templates.
+//!
+//! ```
+//! # use internal_baml_schema_ast::builder::{Arg, AstBuilder, TypeExpr};
+//! let source = AstBuilder::new()
+//!     .declare(
+//!         AstBuilder::class("Person")
+//!             .field("name", TypeExpr::string())
+//!             .attribute("description", [Arg::string("Full \"legal\" name")])
+//!             .field("age", TypeExpr::int().optional()),
+//!     )
+//!     .to_source()
+//!     .unwrap();
+//! assert!(source.contains("name string @description(#\"Full \"legal\" name\"#)"));
+//! ```
+
+use std::{fmt::Write, path::PathBuf};
+
+use anyhow::{bail, Result};
+use internal_baml_diagnostics::SourceFile;
+
+use crate::{ast::SchemaAst, format_schema, parse_schema, FormatOptions};
+
+/// A type as written in a field, type alias or signature.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeExpr {
+    /// A primitive such as `string`, or a class, enum or type alias name.
+    Named(String),
+    StringLiteral(String),
+    IntLiteral(i64),
+    BoolLiteral(bool),
+    List(Box<TypeExpr>),
+    Map(Box<TypeExpr>, Box<TypeExpr>),
+    Union(Vec<TypeExpr>),
+    Optional(Box<TypeExpr>),
+}
+
+impl TypeExpr {
+    pub fn string() -> Self {
+        Self::named("string")
+    }
+
+    pub fn int() -> Self {
+        Self::named("int")
+    }
+
+    pub fn float() -> Self {
+        Self::named("float")
+    }
+
+    pub fn bool() -> Self {
+        Self::named("bool")
+    }
+
+    /// A class, enum or type alias (or any other type by name).
+    pub fn named(name: impl Into<String>) -> Self {
+        Self::Named(name.into())
+    }
+
+    pub fn map(key: TypeExpr, value: TypeExpr) -> Self {
+        Self::Map(Box::new(key), Box::new(value))
+    }
+
+    pub fn union(items: impl IntoIterator<Item = TypeExpr>) -> Self {
+        Self::Union(items.into_iter().collect())
+    }
+
+    /// A list of this type.
+    pub fn list(self) -> Self {
+        Self::List(Box::new(self))
+    }
+
+    /// This type or null.
+    pub fn optional(self) -> Self {
+        match self {
+            Self::Optional(_) => self,
+            other => Self::Optional(Box::new(other)),
+        }
+    }
+
+    fn render(&self, out: &mut String, errors: &mut Vec<String>) {
+        match self {
+            Self::Named(name) => {
+                check_identifier(name, errors);
+                out.push_str(name);
+            }
+            Self::StringLiteral(value) => {
+                if value.contains(['"', '\n', '\\']) {
+                    errors.push(format!(
+                        "String literal types cannot contain quotes, backslashes or newlines: {value:?}"
+                    ));
+                }
+                let _ = write!(out, "\"{value}\"");
+            }
+            Self::IntLiteral(value) => {
+                let _ = write!(out, "{value}");
+            }
+            Self::BoolLiteral(value) => {
+                let _ = write!(out, "{value}");
+            }
+            Self::List(inner) => {
+                inner.render_operand(out, errors);
+                out.push_str("[]");
+            }
+            Self::Map(key, value) => {
+                out.push_str("map<");
+                key.render(out, errors);
+                out.push_str(", ");
+                value.render(out, errors);
+                out.push('>');
+            }
+            Self::Union(items) => {
+                if items.is_empty() {
+                    errors.push("Unions need at least one type".to_string());
+                }
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(" | ");
+                    }
+                    item.render(out, errors);
+                }
+            }
+            Self::Optional(inner) => {
+                inner.render_operand(out, errors);
+                out.push('?');
+            }
+        }
+    }
+
+    /// Renders the type in parentheses if it is a union, so `[]` or `?` applies to all of it.
+    fn render_operand(&self, out: &mut String, errors: &mut Vec<String>) {
+        if matches!(self, Self::Union(items) if items.len() > 1) {
+            out.push('(');
+            self.render(out, errors);
+            out.push(')');
+        } else {
+            self.render(out, errors);
+        }
+    }
+}
+
+/// An attribute argument.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Arg {
+    /// A string, quoted or as a raw string as needed.
+    String(String),
+    /// A bare name, e.g. a check label or a field in `@@one_of`.
+    Identifier(String),
+    /// A jinja expression, rendered between `{{ }}`.
+    Jinja(String),
+    Number(f64),
+    Bool(bool),
+}
+
+impl Arg {
+    pub fn string(value: impl Into<String>) -> Self {
+        Self::String(value.into())
+    }
+
+    pub fn ident(name: impl Into<String>) -> Self {
+        Self::Identifier(name.into())
+    }
+
+    pub fn jinja(expression: impl Into<String>) -> Self {
+        Self::Jinja(expression.into())
+    }
+
+    fn render(&self, out: &mut String, errors: &mut Vec<String>) {
+        match self {
+            Self::String(value) => render_string(value, out, errors),
+            Self::Identifier(name) => {
+                check_identifier(name, errors);
+                out.push_str(name);
+            }
+            Self::Jinja(expression) => {
+                if expression.contains("}}") {
+                    errors.push(format!(
+                        "Jinja expressions cannot contain `}}}}`: {expression}"
+                    ));
+                }
+                let _ = write!(out, "{{{{ {} }}}}", expression.trim());
+            }
+            Self::Number(value) => {
+                let _ = write!(out, "{value}");
+            }
+            Self::Bool(value) => {
+                let _ = write!(out, "{value}");
+            }
+        }
+    }
+}
+
+impl From<&str> for Arg {
+    fn from(value: &str) -> Self {
+        Self::string(value)
+    }
+}
+
+impl From<String> for Arg {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Member {
+    name: String,
+    r#type: Option<TypeExpr>,
+    doc: Option<String>,
+    attributes: Vec<(String, Vec<Arg>)>,
+}
+
+impl Member {
+    fn new(name: String, r#type: Option<TypeExpr>) -> Self {
+        Self {
+            name,
+            r#type,
+            ..Default::default()
+        }
+    }
+
+    /// Renders the member's doc comment, then `name type @attr(...)`, at `indent`.
+    fn render(&self, indent: &str, prefix: &str, out: &mut String, errors: &mut Vec<String>) {
+        render_doc(self.doc.as_deref(), indent, out);
+        out.push_str(indent);
+        check_identifier(&self.name, errors);
+        out.push_str(&self.name);
+        if let Some(r#type) = &self.r#type {
+            out.push(' ');
+            r#type.render(out, errors);
+        }
+        for attribute in &self.attributes {
+            out.push(' ');
+            render_attribute(prefix, attribute, out, errors);
+        }
+        out.push('\n');
+    }
+}
+
+/// A class or enum: the block itself plus its fields or values.
+#[derive(Debug, Clone)]
+struct Block {
+    keyword: &'static str,
+    header: Member,
+    members: Vec<Member>,
+}
+
+impl Block {
+    fn new(keyword: &'static str, name: String) -> Self {
+        Self {
+            keyword,
+            header: Member::new(name, None),
+            members: Vec::new(),
+        }
+    }
+
+    /// The member added last, or the block itself before any member is added.
+    fn current(&mut self) -> &mut Member {
+        self.members.last_mut().unwrap_or(&mut self.header)
+    }
+
+    fn render(&self, out: &mut String, errors: &mut Vec<String>) {
+        render_doc(self.header.doc.as_deref(), "", out);
+        check_identifier(&self.header.name, errors);
+        let _ = writeln!(out, "{} {} {{", self.keyword, self.header.name);
+        for member in &self.members {
+            member.render("  ", "@", out, errors);
+        }
+        if !self.header.attributes.is_empty() {
+            out.push('\n');
+        }
+        for attribute in &self.header.attributes {
+            out.push_str("  ");
+            render_attribute("@@", attribute, out, errors);
+            out.push('\n');
+        }
+        out.push_str("}\n");
+    }
+}
+
+/// Builds a class. [`doc`](Self::doc) and [`attribute`](Self::attribute) apply to the field
+/// added last, or to the class itself (as `@@attribute`) before any field is added.
+#[derive(Debug, Clone)]
+pub struct ClassBuilder(Block);
+
+impl ClassBuilder {
+    pub fn field(mut self, name: impl Into<String>, r#type: TypeExpr) -> Self {
+        self.0.members.push(Member::new(name.into(), Some(r#type)));
+        self
+    }
+
+    /// A `///` doc comment, one line per line of `doc`.
+    pub fn doc(mut self, doc: impl Into<String>) -> Self {
+        self.0.current().doc = Some(doc.into());
+        self
+    }
+
+    pub fn attribute(
+        mut self,
+        name: impl Into<String>,
+        args: impl IntoIterator<Item = Arg>,
+    ) -> Self {
+        self.0
+            .current()
+            .attributes
+            .push((name.into(), args.into_iter().collect()));
+        self
+    }
+}
+
+/// Builds an enum. [`doc`](Self::doc) and [`attribute`](Self::attribute) apply to the value
+/// added last, or to the enum itself (as `@@attribute`) before any value is added.
+#[derive(Debug, Clone)]
+pub struct EnumBuilder(Block);
+
+impl EnumBuilder {
+    pub fn value(mut self, name: impl Into<String>) -> Self {
+        self.0.members.push(Member::new(name.into(), None));
+        self
+    }
+
+    /// A `///` doc comment, one line per line of `doc`.
+    pub fn doc(mut self, doc: impl Into<String>) -> Self {
+        self.0.current().doc = Some(doc.into());
+        self
+    }
+
+    pub fn attribute(
+        mut self,
+        name: impl Into<String>,
+        args: impl IntoIterator<Item = Arg>,
+    ) -> Self {
+        self.0
+            .current()
+            .attributes
+            .push((name.into(), args.into_iter().collect()));
+        self
+    }
+}
+
+/// A class, enum or type alias in an [`AstBuilder`].
+#[derive(Debug, Clone)]
+pub struct Declaration(DeclarationKind);
+
+#[derive(Debug, Clone)]
+enum DeclarationKind {
+    Block(Block),
+    TypeAlias(String, TypeExpr),
+}
+
+impl From<ClassBuilder> for Declaration {
+    fn from(class: ClassBuilder) -> Self {
+        Self(DeclarationKind::Block(class.0))
+    }
+}
+
+impl From<EnumBuilder> for Declaration {
+    fn from(enm: EnumBuilder) -> Self {
+        Self(DeclarationKind::Block(enm.0))
+    }
+}
+
+/// A schema built from classes, enums and type aliases, in the order they are added.
+#[derive(Debug, Clone, Default)]
+pub struct AstBuilder {
+    declarations: Vec<Declaration>,
+}
+
+impl AstBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn class(name: impl Into<String>) -> ClassBuilder {
+        ClassBuilder(Block::new("class", name.into()))
+    }
+
+    pub fn enumeration(name: impl Into<String>) -> EnumBuilder {
+        EnumBuilder(Block::new("enum", name.into()))
+    }
+
+    /// Adds a class or enum.
+    pub fn declare(mut self, declaration: impl Into<Declaration>) -> Self {
+        self.declarations.push(declaration.into());
+        self
+    }
+
+    pub fn type_alias(mut self, name: impl Into<String>, r#type: TypeExpr) -> Self {
+        self.declarations
+            .push(Declaration(DeclarationKind::TypeAlias(name.into(), r#type)));
+        self
+    }
+
+    /// The schema as formatted BAML source.
+    ///
+    /// Fails on names that are not identifiers, strings that cannot be written in BAML, or
+    /// output that does not parse. Whether types exist is not checked here.
+    pub fn to_source(&self) -> Result<String> {
+        let mut out = String::new();
+        let mut errors = Vec::new();
+        for (i, declaration) in self.declarations.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            match &declaration.0 {
+                DeclarationKind::Block(block) => block.render(&mut out, &mut errors),
+                DeclarationKind::TypeAlias(name, r#type) => {
+                    check_identifier(name, &mut errors);
+                    let _ = write!(out, "type {name} = ");
+                    r#type.render(&mut out, &mut errors);
+                    out.push('\n');
+                }
+            }
+        }
+        if !errors.is_empty() {
+            bail!("{}", errors.join("\n"));
+        }
+        let source = format_schema(
+            &out,
+            FormatOptions {
+                indent_width: 2,
+                fail_on_unhandled_rule: false,
+            },
+        )?;
+        parse(&source)?;
+        Ok(source)
+    }
+
+    /// The schema's AST, parsed from [`to_source`](Self::to_source) so that spans point into
+    /// that text.
+    pub fn build(&self) -> Result<SchemaAst> {
+        parse(&self.to_source()?)
+    }
+}
+
+fn parse(source: &str) -> Result<SchemaAst> {
+    let file = SourceFile::from((PathBuf::from("generated.baml"), source));
+    match parse_schema(&PathBuf::from("."), &file) {
+        Ok((ast, diagnostics)) if !diagnostics.has_errors() => Ok(ast),
+        Ok((_, diagnostics)) | Err(diagnostics) => {
+            bail!(
+                "Generated invalid BAML:\n{}",
+                diagnostics.to_pretty_string()
+            )
+        }
+    }
+}
+
+fn check_identifier(name: &str, errors: &mut Vec<String>) {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        errors.push(format!("`{name}` is not a valid name"));
+    }
+}
+
+fn render_doc(doc: Option<&str>, indent: &str, out: &mut String) {
+    for line in doc.into_iter().flat_map(str::lines) {
+        let _ = writeln!(out, "{indent}/// {line}");
+    }
+}
+
+fn render_attribute(
+    prefix: &str,
+    (name, args): &(String, Vec<Arg>),
+    out: &mut String,
+    errors: &mut Vec<String>,
+) {
+    check_identifier(name, errors);
+    let _ = write!(out, "{prefix}{name}");
+    if args.is_empty() {
+        return;
+    }
+    out.push('(');
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        arg.render(out, errors);
+    }
+    out.push(')');
+}
+
+/// Writes `value` quoted, or as a raw string (`#"..."#`) if it has quotes, backslashes or
+/// newlines, with as many `#` as it takes for the content not to end the string.
+fn render_string(value: &str, out: &mut String, errors: &mut Vec<String>) {
+    if !value.contains(['"', '\n', '\\']) {
+        let _ = write!(out, "\"{value}\"");
+        return;
+    }
+    // The grammar accepts raw strings with up to five `#`.
+    let Some(hashes) = (1..=5)
+        .map(|n| "#".repeat(n))
+        .find(|hashes| !value.contains(&format!("\"{hashes}")) && !value.ends_with('"'))
+    else {
+        errors.push(format!("Cannot write {value:?} as a BAML string"));
+        return;
+    };
+    let _ = write!(out, "{hashes}\"{value}\"{hashes}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{Top, WithName};
+
+    #[test]
+    fn builds_formatted_parseable_schemas() {
+        let builder = AstBuilder::new()
+            .declare(
+                AstBuilder::enumeration("Role")
+                    .doc("Who the person is.")
+                    .value("Admin")
+                    .attribute("description", [Arg::string("Can do\nanything")])
+                    .value("Guest"),
+            )
+            .declare(
+                AstBuilder::class("Person")
+                    .field("name", TypeExpr::string())
+                    .attribute("alias", [Arg::string("full_name")])
+                    .doc("Legal name.")
+                    .field(
+                        "roles",
+                        TypeExpr::union([TypeExpr::named("Role"), TypeExpr::string()]).list(),
+                    )
+                    .field("email", TypeExpr::string().optional())
+                    .field("phone", TypeExpr::string().optional())
+                    .attribute(
+                        "assert",
+                        [
+                            Arg::ident("has_contact"),
+                            Arg::jinja("this.email or this.phone"),
+                        ],
+                    )
+                    .field(
+                        "tags",
+                        TypeExpr::map(TypeExpr::string(), TypeExpr::int()).optional(),
+                    ),
+            )
+            .type_alias(
+                "People",
+                TypeExpr::union([TypeExpr::named("Person"), TypeExpr::named("Person").list()]),
+            );
+
+        assert_eq!(
+            builder.to_source().unwrap(),
+            r##"/// Who the person is.
+enum Role {
+  Admin @description(#"Can do
+anything"#)
+  Guest
+}
+
+class Person {
+  /// Legal name.
+  name string @alias("full_name")
+  roles (Role | string)[]
+  email string?
+  phone string? @assert(has_contact, {{ this.email or this.phone }})
+  tags map<string, int>?
+}
+
+type People = Person | Person[]
+"##
+        );
+
+        let ast = builder.build().unwrap();
+        let names = ast
+            .iter_tops()
+            .map(|(_, top)| top.name().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Role", "Person", "People"]);
+        let Some((_, Top::Class(person))) = ast.iter_tops().nth(1) else {
+            panic!("expected a class");
+        };
+        let source = builder.to_source().unwrap();
+        let span = &person.fields[0].span;
+        assert!(source[span.start..span.end].starts_with("name string"));
+    }
+
+    #[test]
+    fn rejects_what_cannot_be_written() {
+        let bad_name = AstBuilder::new().declare(AstBuilder::class("Foo Bar"));
+        assert!(bad_name.to_source().is_err());
+
+        let bad_literal = AstBuilder::new().declare(
+            AstBuilder::class("Foo").field("kind", TypeExpr::StringLiteral("a\"b".to_string())),
+        );
+        assert!(bad_literal.to_source().is_err());
+
+        let tricky = AstBuilder::new().declare(
+            AstBuilder::class("Foo")
+                .field("x", TypeExpr::int())
+                .attribute("description", [Arg::string("ends with \"# inside")]),
+        );
+        let source = tricky.to_source().unwrap();
+        assert!(source.contains("##\"ends with \"# inside\"##"), "{source}");
+    }
+}
@@ -43,7 +43,23 @@ pub fn format_schema(source: &str, format_options: FormatOptions) -> Result<Stri
     let mut w = Vec::new();
     doc.render(10, &mut w)
         .map_err(|_| anyhow!("Failed to render doc"))?;
-    Ok(String::from_utf8(w).map_err(|_| anyhow!("Failed to convert to string"))?)
+    let formatted = String::from_utf8(w).map_err(|_| anyhow!("Failed to convert to string"))?;
+
+    // The grammar skips a byte order mark and the doc uses `\n` line breaks, so put back the
+    // ones the file was saved with.
+    let bom = if source.starts_with('\u{feff}') {
+        "\u{feff}"
+    } else {
+        ""
+    };
+    if source.contains("\r\n") {
+        Ok(format!(
+            "{bom}{}",
+            formatted.replace("\r\n", "\n").replace('\n', "\r\n")
+        ))
+    } else {
+        Ok(format!("{bom}{formatted}"))
+    }
 }
 
 macro_rules! next_pair {
@@ -155,7 +171,7 @@ impl Formatter {
                 Rule::EOI => {
                     // skip
                 }
-                Rule::value_expression_block | Rule::empty_lines => {
+                Rule::value_expression_block | Rule::comment_block | Rule::empty_lines => {
                     doc = doc.append(pair.to_doc());
                 }
                 _ => {
@@ -220,3 +220,62 @@ fn newlines_with_only_spaces_are_stripped() -> anyhow::Result<()> {
 
     assert_format_eq(&actual, &expected)
 }
+
+#[test]
+fn formatting_keeps_every_comment() -> anyhow::Result<()> {
+    let schema = r#"
+        // Header
+
+        /// A person.
+        class Person {
+          // Shown to the model.
+          name    string // trailing
+          {// old field //}
+
+          /// Years.
+          age int|float   @description("age") // in years
+        }
+
+        enum Role {
+          // The default.
+          Guest
+          Admin // can do anything
+        }
+        // Footer
+        "#
+    .unindent();
+
+    let comments = |source: &str| -> anyhow::Result<Vec<String>> {
+        let file = internal_baml_diagnostics::SourceFile::from((
+            std::path::PathBuf::from("test.baml"),
+            source,
+        ));
+        let (ast, _) = crate::parse_schema(std::path::Path::new("."), &file)
+            .map_err(|e| anyhow!(e.to_pretty_string()))?;
+        Ok(ast
+            .trivia
+            .iter()
+            .filter(|t| t.is_comment())
+            .map(|t| t.text().to_string())
+            .collect())
+    };
+
+    let formatted = format_schema(
+        &schema,
+        FormatOptions {
+            indent_width: 2,
+            fail_on_unhandled_rule: true,
+        },
+    )?;
+    assert_eq!(comments(&formatted)?, comments(&schema)?);
+    Ok(())
+}
+
+#[test]
+fn keeps_byte_order_mark_and_line_endings() -> anyhow::Result<()> {
+    let schema = "\u{feff}// Saved on Windows\r\nclass Foo {\r\n    field1   string|int\r\n}\r\n";
+    assert_format_eq(
+        schema,
+        "\u{feff}// Saved on Windows\r\nclass Foo {\r\n  field1 string | int\r\n}\r\n",
+    )
+}
@@ -9,9 +9,12 @@ pub use self::parser::parse_schema;
 /// source span information.
 pub mod ast;
 
+/// Typed construction of BAML source, for code generators.
+pub mod builder;
 mod formatter;
 mod parser;
 
+pub use builder::AstBuilder;
 pub use formatter::{format_schema, FormatOptions};
 
 /// Transform the input string into a valid (quoted and escaped) PSL string literal.
@@ -1,5 +1,6 @@
 use super::{
     helpers::{parsing_catch_all, Pair},
+    parse_expression::parse_expression,
     parse_identifier::parse_identifier,
     parse_named_args_list::parse_named_argument_list,
     Rule,
@@ -72,6 +73,33 @@ pub(crate) fn parse_assignment(pair: Pair<'_>, diagnostics: &mut Diagnostics) ->
     }
 }
 
+/// Parses a constant declaration in the form of `const identifier = expression`.
+///
+/// Returns `None` if the value is not a valid expression, which has already been reported.
+pub(crate) fn parse_constant(pair: Pair<'_>, diagnostics: &mut Diagnostics) -> Option<Constant> {
+    assert_correct_parser!(pair, Rule::const_declaration);
+
+    let span = pair.as_span();
+
+    let mut identifier: Option<Identifier> = None;
+    let mut value: Option<Expression> = None;
+
+    for current in pair.into_inner() {
+        match current.as_rule() {
+            Rule::CONST_KEYWORD | Rule::assignment => {}
+            Rule::identifier => identifier = Some(parse_identifier(current, diagnostics)),
+            Rule::expression => value = parse_expression(current, diagnostics),
+            _ => parsing_catch_all(current, "const"),
+        }
+    }
+
+    Some(Constant {
+        identifier: identifier?,
+        value: value?,
+        span: diagnostics.span(span),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -133,6 +161,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_numeric_constant() {
+        let input = "const MAX_ITEMS = 10";
+        let path = "test.baml";
+        let source = SourceFile::new_static(path.into(), input);
+
+        let mut diagnostics = Diagnostics::new(path.into());
+        diagnostics.set_source(&source);
+
+        let pair = BAMLParser::parse(Rule::const_declaration, input)
+            .unwrap()
+            .next()
+            .unwrap();
+        let constant = super::parse_constant(pair, &mut diagnostics).unwrap();
+
+        assert_eq!(constant.identifier.to_string(), "MAX_ITEMS");
+        assert_eq!(constant.value.as_numeric_value().map(|(n, _)| n), Some("10"));
+    }
+
     #[test]
     fn parse_union_type_alias() {
         let assignment = parse_type_alias("type Test = int | string");
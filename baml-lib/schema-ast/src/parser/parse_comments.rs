@@ -2,7 +2,8 @@ use super::{
     helpers::{parsing_catch_all, Pair},
     Rule,
 };
-use crate::ast::Comment;
+use crate::ast::{Comment, Trivia, TriviaKind};
+use internal_baml_diagnostics::Diagnostics;
 
 pub(crate) fn parse_comment_block(token: Pair<'_>) -> Option<Comment> {
     debug_assert!(token.as_rule() == Rule::comment_block);
@@ -56,3 +57,40 @@ pub(crate) fn parse_doc_comment(token: Pair<'_>) -> &str {
         ),
     }
 }
+
+/// Pushes every comment and run of blank lines under `pair` to `trivia`, in source order.
+pub(crate) fn collect_trivia(pair: Pair<'_>, diagnostics: &Diagnostics, trivia: &mut Vec<Trivia>) {
+    let kind = match pair.as_rule() {
+        Rule::comment => TriviaKind::Comment,
+        Rule::doc_comment => TriviaKind::DocComment,
+        Rule::block_comment => TriviaKind::BlockComment,
+        Rule::empty_lines => {
+            let mut span = diagnostics.span(pair.as_span());
+            let source = span.file.as_str();
+            // The first newline may end a line with code on it.
+            if span.start > 0 && !source[..span.start].ends_with('\n') {
+                let Some(newline) = pair.as_str().find('\n') else {
+                    return;
+                };
+                span.start += newline + 1;
+            }
+            let lines = source[span.start..span.end].matches('\n').count();
+            if lines > 0 {
+                trivia.push(Trivia {
+                    kind: TriviaKind::BlankLines(lines),
+                    span,
+                });
+            }
+            return;
+        }
+        _ => {
+            for inner in pair.into_inner() {
+                collect_trivia(inner, diagnostics, trivia);
+            }
+            return;
+        }
+    };
+    let mut span = diagnostics.span(pair.as_span());
+    span.start += pair.as_str().len() - pair.as_str().trim_start().len();
+    trivia.push(Trivia { kind, span });
+}
@@ -1,8 +1,8 @@
 use internal_baml_diagnostics::Diagnostics;
 
 use super::{
-    helpers::parsing_catch_all, parse_field::parse_field_type_chain,
-    parse_identifier::parse_identifier,
+    helpers::parsing_catch_all, parse_expression::parse_expression,
+    parse_field::parse_field_type_chain, parse_identifier::parse_identifier,
 };
 use crate::{
     assert_correct_parser,
@@ -41,6 +41,7 @@ pub(crate) fn parse_named_argument_list(
 
         let mut name = None;
         let mut r#type = None;
+        let mut default = None;
         for arg in named_arg.into_inner() {
             match arg.as_rule() {
                 Rule::identifier => {
@@ -50,12 +51,13 @@ pub(crate) fn parse_named_argument_list(
                 Rule::field_type | Rule::field_type_chain => {
                     r#type = Some(parse_function_arg(arg, diagnostics)?);
                 }
+                Rule::expression => default = parse_expression(arg, diagnostics),
                 _ => parsing_catch_all(arg, "named_argument_list"),
             }
         }
 
         match (name, r#type) {
-            (Some(name), Some(r#type)) => args.push((name, r#type)),
+            (Some(name), Some(r#type)) => args.push((name, BlockArg { default, ..r#type })),
             (Some(name), None) => diagnostics.push_error(DatamodelError::new_validation_error(
                 &format!(
                     "No type specified for argument: {name}. Expected: `{name}: type`",
@@ -91,6 +93,7 @@ pub fn parse_function_arg(
         Some(ftype) => Ok(BlockArg {
             span,
             field_type: ftype,
+            default: None,
         }),
         None => Err(DatamodelError::new_validation_error(
             "Failed to find type",
@@ -1,7 +1,9 @@
 use std::path::{Path, PathBuf};
 
 use super::{
-    parse_assignment::parse_assignment, parse_template_string::parse_template_string,
+    parse_assignment::{parse_assignment, parse_constant},
+    parse_comments::collect_trivia,
+    parse_template_string::parse_template_string,
     parse_type_expression_block::parse_type_expression_block,
     parse_value_expression_block::parse_value_expression_block, BAMLParser, Rule,
 };
@@ -32,10 +34,14 @@ pub fn parse_schema(
     let mut diagnostics = Diagnostics::new(root_path.to_path_buf());
     diagnostics.set_source(source);
 
-    if !source.path().ends_with(".baml") {
+    // `.baml.jinja` files hold template strings shared between schemas. Anonymous sources have
+    // a label instead of a path and are read as `.baml` files.
+    let path = source.path().to_lowercase();
+    let is_jinja_include = !source.is_anonymous() && path.ends_with(".baml.jinja");
+    if !source.is_anonymous() && !path.ends_with(".baml") && !is_jinja_include {
         diagnostics.push_error(DatamodelError::new_validation_error(
             &format!(
-                "A BAML file must have the file extension `.baml`, but found: {}",
+                "A BAML file must have the file extension `.baml` (or `.baml.jinja` for shared template strings), but found: {}",
                 source.path().to_string()
             ),
             Span::empty(source.clone()),
@@ -54,6 +60,8 @@ pub fn parse_schema(
             pretty_print(datamodel.clone(), 0);
 
             let mut top_level_definitions = Vec::new();
+            let mut trivia = Vec::new();
+            collect_trivia(datamodel.clone(), &diagnostics, &mut trivia);
 
             let mut pending_block_comment = None;
             let mut pairs = datamodel.into_inner().peekable();
@@ -86,6 +94,7 @@ pub fn parse_schema(
                                 ValueExprBlockType::Client => Top::Client(val),
                                 ValueExprBlockType::RetryPolicy => Top::RetryPolicy(val),
                                 ValueExprBlockType::Generator => Top::Generator(val),
+                                ValueExprBlockType::PromptSuite => Top::PromptSuite(val),
                             }),
                             Err(e) => diagnostics.push_error(e),
                         }
@@ -94,6 +103,11 @@ pub fn parse_schema(
                         let assignment = parse_assignment(current, &mut diagnostics);
                         top_level_definitions.push(Top::TypeAlias(assignment));
                     }
+                    Rule::const_declaration => {
+                        if let Some(constant) = parse_constant(current, &mut diagnostics) {
+                            top_level_definitions.push(Top::Constant(constant));
+                        }
+                    }
 
                     Rule::template_declaration => {
                         match parse_template_string(
@@ -136,9 +150,27 @@ pub fn parse_schema(
                 }
             }
 
+            if is_jinja_include {
+                top_level_definitions.retain(|top| {
+                    let is_template_string = matches!(top, Top::TemplateString(_));
+                    if !is_template_string {
+                        diagnostics.push_error(DatamodelError::new_validation_error(
+                            &format!(
+                                "A `.baml.jinja` file can only declare template_string blocks, but `{}` is a {}.",
+                                top.name(),
+                                top.get_type()
+                            ),
+                            top.identifier().span().clone(),
+                        ));
+                    }
+                    is_template_string
+                });
+            }
+
             Ok((
                 SchemaAst {
                     tops: top_level_definitions,
+                    trivia,
                 },
                 diagnostics,
             ))
@@ -397,4 +429,140 @@ mod tests {
 
         assert_eq!(alias.to_string(), "One");
     }
+
+    #[test]
+    fn test_trivia() {
+        let input = r#"// Schema header
+
+/// A person.
+class Person {
+  // Shown to the model.
+  name string // trailing
+
+  // Not attached to `age`.
+
+  /// Years.
+  age int
+}
+
+// Loose comment
+"#;
+        let source = SourceFile::new_static("test_file.baml".into(), input);
+        let (schema_ast, _) = parse_schema(Path::new("."), &source).unwrap();
+
+        let kinds = schema_ast
+            .trivia
+            .iter()
+            .map(|t| (t.kind, t.text()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                (TriviaKind::Comment, "// Schema header"),
+                (TriviaKind::BlankLines(1), "\n"),
+                (TriviaKind::DocComment, "/// A person."),
+                (TriviaKind::Comment, "// Shown to the model."),
+                (TriviaKind::Comment, "// trailing"),
+                (TriviaKind::BlankLines(1), "\n"),
+                (TriviaKind::Comment, "// Not attached to `age`."),
+                (TriviaKind::BlankLines(1), "\n"),
+                (TriviaKind::DocComment, "/// Years."),
+                (TriviaKind::BlankLines(1), "\n"),
+                (TriviaKind::Comment, "// Loose comment"),
+            ]
+        );
+
+        let Top::Class(person) = &schema_ast.tops[0] else {
+            panic!("Expected a class");
+        };
+        let texts = |trivia: &[&Trivia]| {
+            trivia
+                .iter()
+                .map(|t| t.text().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let class = schema_ast.trivia_of(&person.span);
+        assert_eq!(
+            texts(&class.leading),
+            ["// Schema header", "\n", "/// A person."]
+        );
+        assert_eq!(texts(class.attached_comments()), ["/// A person."]);
+
+        let name = schema_ast.trivia_of(&person.fields[0].span);
+        assert_eq!(texts(&name.leading), ["// Shown to the model."]);
+        assert_eq!(name.trailing.map(|t| t.text()), Some("// trailing"));
+
+        let age = schema_ast.trivia_of(&person.fields[1].span);
+        assert_eq!(
+            texts(&age.leading),
+            ["\n", "// Not attached to `age`.", "\n", "/// Years."]
+        );
+        assert_eq!(texts(age.attached_comments()), ["/// Years."]);
+        assert_eq!(age.trailing, None);
+    }
+
+    #[test]
+    fn test_crlf_and_bom() {
+        let input = "\u{feff}/// A person.\r\nclass Person {\r\n  name string // trailing\r\n  bio string @description(#\"\r\n    Short.\r\n\r\n      Indented.\r\n  \"#)\r\n}\r\n";
+        let source = SourceFile::new_static("test_file.baml".into(), input);
+        let (schema_ast, diagnostics) = parse_schema(Path::new("."), &source).unwrap();
+        assert!(
+            !diagnostics.has_errors(),
+            "{}",
+            diagnostics.to_pretty_string()
+        );
+
+        let Top::Class(person) = &schema_ast.tops[0] else {
+            panic!("Expected a class");
+        };
+        assert_eq!(person.documentation(), Some("A person."));
+        let name = &person.fields[0].name;
+        assert_eq!(&input[name.span().start..name.span().end], "name");
+
+        let Some(Expression::RawStringValue(description)) = person.fields[1].attributes[0]
+            .arguments
+            .arguments
+            .first()
+            .map(|arg| &arg.value)
+        else {
+            panic!("Expected a raw string");
+        };
+        assert_eq!(description.value(), "Short.\n\n  Indented.");
+        let indented = description.value().find("Indented").unwrap();
+        assert!(input[description.to_raw_offset(indented)..].starts_with("Indented.\r\n"));
+
+        let comments = schema_ast
+            .trivia
+            .iter()
+            .map(|t| t.text())
+            .collect::<Vec<_>>();
+        assert_eq!(comments, ["/// A person.", "// trailing"]);
+    }
+
+    #[test]
+    fn test_file_extensions() {
+        let parse = |path: &str, input: &'static str| {
+            let source = SourceFile::new_static(path.into(), input);
+            parse_schema(Path::new("."), &source)
+        };
+
+        let (ast, diagnostics) = parse("Schema.BAML", "class A {\n  a string\n}\n").unwrap();
+        assert!(!diagnostics.has_errors());
+        assert_eq!(ast.tops.len(), 1);
+
+        assert!(parse("schema.txt", "class A {\n  a string\n}\n").is_err());
+
+        let partials = "template_string Greet(name: string) #\"\n  Hello {{ name }}\n\"#\n";
+        let (ast, diagnostics) = parse("partials.Baml.Jinja", partials).unwrap();
+        assert!(!diagnostics.has_errors());
+        assert!(matches!(ast.tops.as_slice(), [Top::TemplateString(_)]));
+
+        let mixed = "template_string Greet(name: string) #\"\n  Hello {{ name }}\n\"#\n\nclass A {\n  a string\n}\n";
+        let (ast, diagnostics) = parse("partials.baml.jinja", mixed).unwrap();
+        assert!(matches!(ast.tops.as_slice(), [Top::TemplateString(_)]));
+        assert!(diagnostics.to_pretty_string().contains(
+            "A `.baml.jinja` file can only declare template_string blocks, but `A` is a class."
+        ));
+    }
 }
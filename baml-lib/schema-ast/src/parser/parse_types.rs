@@ -129,7 +129,7 @@ fn parse_base_type(pair: Pair<'_>, diagnostics: &mut Diagnostics) -> Option<Fiel
             Rule::identifier => {
                 let identifier = parse_identifier(current.clone(), diagnostics);
                 let field_type = match current.as_str() {
-                    "string" | "int" | "float" | "bool" | "image" | "audio" => {
+                    "string" | "int" | "float" | "bool" | "bytes" | "image" | "audio" => {
                         FieldType::Primitive(
                             FieldArity::Required,
                             TypeValue::from_str(identifier.name()).expect("Invalid type value"),
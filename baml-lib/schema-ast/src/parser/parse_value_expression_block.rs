@@ -33,6 +33,7 @@ pub(crate) fn parse_value_expression_block(
                 "client" | "client<llm>" => sub_type = Some(ValueExprBlockType::Client),
                 "retry_policy" => sub_type = Some(ValueExprBlockType::RetryPolicy),
                 "generator" => sub_type = Some(ValueExprBlockType::Generator),
+                "prompt_suite" => sub_type = Some(ValueExprBlockType::PromptSuite),
                 _ => panic!("Unexpected value expression keyword: {}", current.as_str()),
             },
             Rule::ARROW => {
@@ -67,6 +68,7 @@ pub(crate) fn parse_value_expression_block(
                                         ValueExprBlockType::Client => "Client",
                                         ValueExprBlockType::RetryPolicy => "RetryPolicy",
                                         ValueExprBlockType::Generator => "Generator",
+                                        ValueExprBlockType::PromptSuite => "PromptSuite",
                                     })
                                     .unwrap_or("Other"),
                                 item,